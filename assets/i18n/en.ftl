@@ -0,0 +1,114 @@
+# english strings for client-owned text. keys are shared by all translation packs, and any key
+# missing from a pack falls back to the text here.
+
+## buttons
+button-ok = Ok
+button-close = Close
+button-cancel = Cancel
+button-best-realm = Best Realm
+button-favorite = Favorite
+button-unfavorite = Unfavorite
+button-view-entity = View entity
+button-jump-in = Jump In
+button-events = Events
+button-notify-me = Notify me
+button-dont-notify = Don't notify
+button-clear = Clear
+button-import = Import
+button-export = Export
+button-edit-hud = Edit HUD
+
+## toasts
+toast-memory-low-1 = Memory is low: trimming caches
+toast-memory-low-2 = Memory is low: unloading distant scenes
+toast-memory-low-3 = Memory is low: downscaling textures and reducing load distance
+toast-memory-low-4 = Memory is low: reducing load distance further
+toast-inspector = Please open chrome and navigate to "chrome://inspect" to attach a debugger
+toast-message-copied = Message copied to clipboard
+toast-message-copy-failed = Failed to copy message
+toast-login-failed = Login failed: { $error }
+toast-guest-warning = Warning: Guest profile will not persist beyond the current session
+toast-language-installed = Installed translations for { $language }
+toast-language-failed = Failed to load translations for { $language }
+toast-language-no-content-server = No content server to download translations from
+toast-purchase-complete = Purchased { $name }
+toast-purchase-failed = Purchase failed: { $error }
+toast-session-renewed = Login renewed
+toast-session-renew-failed = Failed to renew login: { $error }
+toast-now-playing = Now playing: { $title }
+toast-now-playing-station = Now playing on { $station }: { $title }
+
+## hotbar
+hotbar-action = Action { $n }
+hotbar-map = Map
+hotbar-mic = Mic
+
+## photo mode
+photo-saved = Saved { $path }
+photo-folder-failed = Failed to create gallery folder: { $error }
+photo-no-window = No window to capture
+photo-capture-failed = Failed to capture: { $error }
+
+## gallery
+gallery-empty = No photos yet, take some in photo mode
+gallery-showing-latest = Showing the latest { $count } of { $total } photos
+gallery-uploaded = Uploaded
+gallery-upload = Upload
+gallery-uploading = Uploading photo...
+gallery-upload-complete = Photo uploaded to your camera reel
+gallery-upload-failed = Photo upload failed: { $error }
+gallery-view-online = View Online
+gallery-delete = Delete
+gallery-no-details = No details were saved with this photo
+gallery-details =
+    Taken { $date } in { $realm } at { $x },{ $y }
+    People: { $people }
+
+## crash reports
+crash-report-title = Unexpected Exit
+crash-report-body = The explorer closed unexpectedly last time. A crash report with the log, your location, the loaded scenes, your graphics card and settings was saved to { $path }. Send it to help fix the problem?
+crash-report-body-local = The explorer closed unexpectedly last time. A crash report with the log, your location, the loaded scenes, your graphics card and settings was saved to { $path }. Crash reports can only be sent with telemetry turned on in the settings.
+crash-report-send = Send Report
+crash-report-dont-send = Don't Send
+crash-report-sent = Crash report sent, thank you
+
+## menus and tooltips
+menu-copy = Copy
+menu-report = Report
+menu-select = Select
+menu-teleport = Jump here
+menu-pin = Pin
+menu-unpin = Unpin
+menu-copy-coords = Copy coordinates
+menu-preview-parcel = Go to { $x },{ $y }
+menu-parcel-info = Parcel info
+toast-message-reported = Message reported, saved to { $path }
+toast-message-report-failed = Failed to save report
+toast-coords-copied = Coordinates copied to clipboard
+wearable-info = { $rarity } { $category }
+wearable-incompatible = Not available for this body shape
+map-parcel = Parcel { $x },{ $y }
+map-pinned = Pinned
+
+## narration
+narration-chat = { $name } says: { $message }
+chat-history-search = history search `{ $query }`: { $entry }
+chat-history-search-failed = history search `{ $query }`: no match
+
+## console replies
+console-no-window = no window
+console-hud-saved = hud layout saved
+console-hud-editing = editing hud layout
+console-bind-invalid = invalid command
+console-bind-slash = commands must start with `/`
+console-unbind-missing = no command bound to { $binding }
+console-unbound = unbound { $binding }
+console-binds-count = { $count } command bindings
+console-hotbar-range = slot must be 1-{ $max }
+console-hotbar-unknown-action = unknown action `{ $action }`
+console-hotbar-usage = usage: /hotbar <slot> <emote|action|map|mic|command|clear> [args]
+console-hotbar-updated = hotbar slot { $slot } updated
+console-language-current = language: { $language }
+console-language-set = language set to { $language }
+console-language-unknown = no translations for `{ $language }`, give a content hash or url to download them
+console-language-downloading = downloading translations for `{ $language }`
@@ -0,0 +1,113 @@
+# traducción al español
+
+## buttons
+button-ok = Aceptar
+button-close = Cerrar
+button-cancel = Cancelar
+button-best-realm = Mejor reino
+button-favorite = Favorito
+button-unfavorite = Quitar favorito
+button-view-entity = Ver entidad
+button-jump-in = Entrar
+button-events = Eventos
+button-notify-me = Avisarme
+button-dont-notify = No avisar
+button-clear = Borrar
+button-import = Importar
+button-export = Exportar
+button-edit-hud = Editar HUD
+
+## toasts
+toast-memory-low-1 = Memoria baja: vaciando cachés
+toast-memory-low-2 = Memoria baja: descargando escenas lejanas
+toast-memory-low-3 = Memoria baja: reduciendo texturas y distancia de carga
+toast-memory-low-4 = Memoria baja: reduciendo aún más la distancia de carga
+toast-inspector = Abre chrome y ve a "chrome://inspect" para conectar un depurador
+toast-message-copied = Mensaje copiado al portapapeles
+toast-message-copy-failed = No se pudo copiar el mensaje
+toast-login-failed = Error al iniciar sesión: { $error }
+toast-guest-warning = Aviso: el perfil de invitado no se guardará al terminar la sesión
+toast-language-installed = Traducción instalada: { $language }
+toast-language-failed = No se pudo cargar la traducción: { $language }
+toast-language-no-content-server = No hay servidor de contenido para descargar traducciones
+toast-purchase-complete = Has comprado { $name }
+toast-purchase-failed = Error en la compra: { $error }
+toast-session-renewed = Sesión renovada
+toast-session-renew-failed = No se pudo renovar la sesión: { $error }
+toast-now-playing = Reproduciendo: { $title }
+toast-now-playing-station = Reproduciendo en { $station }: { $title }
+
+## hotbar
+hotbar-action = Acción { $n }
+hotbar-map = Mapa
+hotbar-mic = Micro
+
+## photo mode
+photo-saved = Guardada { $path }
+photo-folder-failed = No se pudo crear la carpeta de la galería: { $error }
+photo-no-window = No hay ventana que capturar
+photo-capture-failed = No se pudo capturar: { $error }
+
+## gallery
+gallery-empty = Aún no hay fotos, haz algunas en el modo foto
+gallery-showing-latest = Mostrando las últimas { $count } de { $total } fotos
+gallery-uploaded = Subida
+gallery-upload = Subir
+gallery-uploading = Subiendo foto...
+gallery-upload-complete = Foto subida a tu carrete
+gallery-upload-failed = No se pudo subir la foto: { $error }
+gallery-view-online = Ver en línea
+gallery-delete = Eliminar
+gallery-no-details = No se guardaron detalles con esta foto
+gallery-details =
+    Tomada el { $date } en { $realm } en { $x },{ $y }
+    Personas: { $people }
+
+## crash reports
+crash-report-title = Cierre inesperado
+crash-report-body = El explorador se cerró inesperadamente la última vez. Se guardó un informe con el registro, tu ubicación, las escenas cargadas, tu tarjeta gráfica y tu configuración en { $path }. ¿Quieres enviarlo para ayudar a solucionar el problema?
+crash-report-body-local = El explorador se cerró inesperadamente la última vez. Se guardó un informe con el registro, tu ubicación, las escenas cargadas, tu tarjeta gráfica y tu configuración en { $path }. Los informes solo se pueden enviar con la telemetría activada en la configuración.
+crash-report-send = Enviar informe
+crash-report-dont-send = No enviar
+crash-report-sent = Informe enviado, gracias
+
+## menus and tooltips
+menu-copy = Copiar
+menu-report = Denunciar
+menu-select = Seleccionar
+menu-teleport = Saltar aquí
+menu-pin = Fijar
+menu-unpin = Quitar
+menu-copy-coords = Copiar coordenadas
+menu-preview-parcel = Ir a { $x },{ $y }
+menu-parcel-info = Info de parcela
+toast-message-reported = Mensaje denunciado, guardado en { $path }
+toast-message-report-failed = No se pudo guardar la denuncia
+toast-coords-copied = Coordenadas copiadas al portapapeles
+wearable-info = { $rarity } { $category }
+wearable-incompatible = No disponible para este tipo de cuerpo
+map-parcel = Parcela { $x },{ $y }
+map-pinned = Fijada
+
+## narration
+narration-chat = { $name } dice: { $message }
+chat-history-search = buscar en historial `{ $query }`: { $entry }
+chat-history-search-failed = buscar en historial `{ $query }`: sin resultados
+
+## console replies
+console-no-window = no hay ventana
+console-hud-saved = diseño del hud guardado
+console-hud-editing = editando el diseño del hud
+console-bind-invalid = comando no válido
+console-bind-slash = los comandos deben empezar por `/`
+console-unbind-missing = no hay ningún comando asignado a { $binding }
+console-unbound = { $binding } desasignado
+console-binds-count = { $count } comandos asignados
+console-hotbar-range = la casilla debe estar entre 1 y { $max }
+console-hotbar-unknown-action = acción desconocida `{ $action }`
+console-hotbar-usage = uso: /hotbar <casilla> <emote|action|map|mic|command|clear> [args]
+console-hotbar-updated = casilla { $slot } actualizada
+console-language-current = idioma: { $language }
+console-language-set = idioma cambiado a { $language }
+console-language-unknown = no hay traducción para `{ $language }`, indica un hash de contenido o una url para descargarla
+console-language-downloading = descargando la traducción para `{ $language }`
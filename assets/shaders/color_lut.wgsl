@@ -0,0 +1,38 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct ColorLutSettings {
+    strength: f32,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var screen_sampler: sampler;
+@group(0) @binding(2) var lut_texture: texture_3d<f32>;
+@group(0) @binding(3) var lut_sampler: sampler;
+@group(0) @binding(4) var<uniform> settings: ColorLutSettings;
+
+fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+    let lo = c * 12.92;
+    let hi = 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055;
+    return select(hi, lo, c <= vec3(0.0031308));
+}
+
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+    let lo = c / 12.92;
+    let hi = pow((c + 0.055) / 1.055, vec3(2.4));
+    return select(hi, lo, c <= vec3(0.04045));
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(screen_texture, screen_sampler, in.uv);
+
+    // luts are authored against display (srgb) values
+    let srgb = linear_to_srgb(saturate(color.rgb));
+
+    // remap so that 0 and 1 land on the centers of the edge texels
+    let size = f32(textureDimensions(lut_texture).x);
+    let coords = srgb * ((size - 1.0) / size) + 0.5 / size;
+    let graded = textureSample(lut_texture, lut_sampler, coords).rgb;
+
+    return vec4(srgb_to_linear(mix(srgb, graded, settings.strength)), color.a);
+}
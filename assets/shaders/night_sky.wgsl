@@ -0,0 +1,70 @@
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::view,
+}
+
+struct NightSkySettings {
+    moon_direction: vec3<f32>,
+    night: f32,
+    sun_direction: vec3<f32>,
+    moon_phase: f32,
+    time: f32,
+}
+
+@group(2) @binding(0) var<uniform> settings: NightSkySettings;
+
+const STAR_GRID: f32 = 180.0;
+const STAR_DENSITY: f32 = 0.996;
+const MOON_RADIUS: f32 = 0.03;
+
+fn hash3(p: vec3<f32>) -> f32 {
+    let q = fract(p * vec3(0.1031, 0.1030, 0.0973));
+    let r = q + dot(q, q.yxz + 33.33);
+    return fract((r.x + r.y) * r.z);
+}
+
+fn stars(dir: vec3<f32>) -> vec3<f32> {
+    let p = dir * STAR_GRID;
+    let cell = floor(p);
+    let h = hash3(cell);
+    if h < STAR_DENSITY {
+        return vec3(0.0);
+    }
+
+    let center = cell + vec3(hash3(cell + 17.0), hash3(cell + 31.0), hash3(cell + 47.0));
+    let d = length(p - center);
+    let size = 0.08 + 0.12 * hash3(cell + 5.0);
+    let twinkle = 0.75 + 0.25 * sin(settings.time * (1.0 + 3.0 * hash3(cell + 11.0)) + h * 100.0);
+    let brightness = smoothstep(size, 0.0, d) * twinkle * (h - STAR_DENSITY) / (1.0 - STAR_DENSITY);
+    // slight color variation from blue-white to yellow-white
+    let tint = mix(vec3(0.8, 0.9, 1.0), vec3(1.0, 0.9, 0.75), hash3(cell + 23.0));
+    return tint * brightness * 4.0;
+}
+
+fn moon(dir: vec3<f32>) -> vec3<f32> {
+    let m = normalize(settings.moon_direction);
+    let offset = (dir - m * dot(dir, m)) / MOON_RADIUS;
+    let r2 = dot(offset, offset);
+    if dot(dir, m) < 0.0 || r2 > 1.0 {
+        // faint halo
+        let halo = max(dot(dir, m), 0.0);
+        return vec3(0.6, 0.7, 0.9) * pow(halo, 2000.0) * 0.5;
+    }
+
+    // normal of the visible moon hemisphere, lit from the sun direction
+    let normal = offset - m * sqrt(1.0 - r2);
+    let lit = smoothstep(-0.05, 0.05, dot(normal, normalize(settings.sun_direction)));
+    // a little earthshine on the dark side
+    return vec3(1.0, 0.97, 0.9) * mix(0.02, 1.5, lit);
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let dir = normalize(in.world_position.xyz - view.world_position);
+
+    // fade towards the horizon, where the atmosphere is thickest
+    let horizon = smoothstep(-0.05, 0.15, dir.y);
+    let color = (stars(dir) + moon(dir)) * horizon * settings.night;
+
+    return vec4(color * view.exposure * 1000.0, 1.0);
+}
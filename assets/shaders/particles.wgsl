@@ -0,0 +1,121 @@
+#import bevy_pbr::{
+    mesh_functions,
+    mesh_view_bindings::{globals, view},
+    view_transformations::position_world_to_clip,
+}
+
+struct ParticleSettings {
+    velocity: vec3<f32>,
+    rate: f32,
+    acceleration: vec3<f32>,
+    lifetime: f32,
+    color_start: vec4<f32>,
+    color_end: vec4<f32>,
+    size_start: f32,
+    size_end: f32,
+    velocity_spread: f32,
+    velocity_end_scale: f32,
+    emitter_radius: f32,
+    count: u32,
+    sheet_columns: u32,
+    sheet_rows: u32,
+    sheet_fps: f32,
+    start_time: f32,
+    stop_time: f32,
+    additive: u32,
+}
+
+@group(2) @binding(0) var<uniform> settings: ParticleSettings;
+@group(2) @binding(1) var particle_texture: texture_2d<f32>;
+@group(2) @binding(2) var particle_sampler: sampler;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    // xy: quad corner, z: particle index
+    @location(0) position: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+fn hash(n: f32) -> f32 {
+    return fract(sin(n) * 43758.5453);
+}
+
+fn random_direction(seed: f32) -> vec3<f32> {
+    let z = hash(seed) * 2.0 - 1.0;
+    let a = hash(seed + 17.0) * 6.2831853;
+    let r = sqrt(max(0.0, 1.0 - z * z));
+    return vec3(r * cos(a), r * sin(a), z);
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    let index = vertex.position.z;
+    let t = globals.time;
+
+    // each particle respawns once per period, staggered by its index
+    let period = f32(settings.count) / settings.rate;
+    let offset = index / settings.rate;
+    let cycle = floor((t - offset) / period);
+    let spawn = cycle * period + offset;
+    let age = t - spawn;
+
+    if age < 0.0 || age >= settings.lifetime || spawn < settings.start_time || spawn > settings.stop_time {
+        // outside the clip volume
+        out.position = vec4(2.0, 2.0, 2.0, 1.0);
+        return out;
+    }
+
+    let life = age / settings.lifetime;
+    let seed = index * 12.9898 + cycle * 78.233;
+
+    // local space emission, scaled and rotated with the entity
+    let model = mesh_functions::get_world_from_local(vertex.instance_index);
+    let start = random_direction(seed) * settings.emitter_radius * pow(hash(seed + 3.0), 1.0 / 3.0);
+    let spread = random_direction(seed + 5.0) * settings.velocity_spread;
+    let v0 = settings.velocity + spread;
+    // velocity eases linearly towards v0 * velocity_end_scale over the lifetime
+    let travel = v0 * (age + (settings.velocity_end_scale - 1.0) * age * age / (2.0 * settings.lifetime));
+    let local_position = start + travel;
+    var world_position = mesh_functions::mesh_position_local_to_world(model, vec4(local_position, 1.0)).xyz;
+    // acceleration is in world space (e.g. gravity)
+    world_position += 0.5 * settings.acceleration * age * age;
+
+    // camera facing quad
+    let size = mix(settings.size_start, settings.size_end, life);
+    let right = view.world_from_view[0].xyz;
+    let up = view.world_from_view[1].xyz;
+    world_position += (right * vertex.position.x + up * vertex.position.y) * size;
+
+    out.position = position_world_to_clip(world_position);
+    out.color = mix(settings.color_start, settings.color_end, life);
+
+    // sprite sheet frame, by fps or spread over the lifetime
+    let frames = settings.sheet_columns * settings.sheet_rows;
+    var frame: u32;
+    if settings.sheet_fps > 0.0 {
+        frame = u32(age * settings.sheet_fps) % frames;
+    } else {
+        frame = min(u32(life * f32(frames)), frames - 1u);
+    }
+    let cell = vec2(f32(frame % settings.sheet_columns), f32(frame / settings.sheet_columns));
+    out.uv = (cell + vertex.uv) / vec2(f32(settings.sheet_columns), f32(settings.sheet_rows));
+
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(particle_texture, particle_sampler, in.uv) * in.color;
+    if settings.additive != 0u {
+        color = vec4(color.rgb * color.a, color.a);
+    }
+    return color;
+}
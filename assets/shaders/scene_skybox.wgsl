@@ -0,0 +1,34 @@
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::view,
+}
+
+struct SceneSkyboxSettings {
+    brightness: f32,
+    alpha: f32,
+    equirect: u32,
+}
+
+@group(2) @binding(0) var<uniform> settings: SceneSkyboxSettings;
+@group(2) @binding(1) var cubemap_texture: texture_cube<f32>;
+@group(2) @binding(2) var cubemap_sampler: sampler;
+@group(2) @binding(3) var equirect_texture: texture_2d<f32>;
+@group(2) @binding(4) var equirect_sampler: sampler;
+
+const PI: f32 = 3.14159265358979323846;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let dir = normalize(in.world_position.xyz - view.world_position);
+
+    var color: vec3<f32>;
+    if settings.equirect == 1u {
+        let uv = vec2(atan2(dir.z, dir.x) / (2.0 * PI) + 0.5, acos(clamp(dir.y, -1.0, 1.0)) / PI);
+        // avoid the mip seam where u wraps
+        color = textureSampleLevel(equirect_texture, equirect_sampler, uv, 0.0).rgb;
+    } else {
+        color = textureSample(cubemap_texture, cubemap_sampler, dir).rgb;
+    }
+
+    return vec4(color * settings.brightness * view.exposure, settings.alpha);
+}
@@ -4,7 +4,7 @@ use serde::Serialize;
 pub struct SegmentMetricEventBody {
     #[serde(rename = "type")]
     r#type: String,
-    event: String,
+    pub(crate) event: String,
     #[serde(rename = "userId")]
     user_id: String,
     properties: serde_json::Value,
@@ -51,6 +51,8 @@ pub enum SegmentEvent {
     ExplorerSceneLoadTimes(SegmentEventExplorerSceneLoadTimes),
     ExplorerMoveToParcel(String, SegmentEventExplorerMoveToParcel),
     SystemInfoReport(SegmentEventSystemInfoReport),
+    ExplorerRealmChange(SegmentEventExplorerRealmChange),
+    ExplorerSessionStart(SegmentEventExplorerSessionStart),
 }
 
 #[derive(Serialize)]
@@ -112,11 +114,11 @@ pub struct SegmentEventExplorerError {
 #[derive(Serialize)]
 pub struct SegmentEventExplorerSceneLoadTimes {
     // Unique hash for the scene.
-    scene_hash: String,
+    pub scene_hash: String,
     // Time to load in seconds.
-    elapsed: f32,
+    pub elapsed: f32,
     // Boolean flag indicating wether the scene loaded without errors.
-    success: bool,
+    pub success: bool,
 }
 
 // TODO: maybe important what realm?
@@ -126,6 +128,20 @@ pub struct SegmentEventExplorerMoveToParcel {
     pub old_parcel: String,
 }
 
+#[derive(Serialize)]
+pub struct SegmentEventExplorerRealmChange {
+    // Realm the user is leaving, empty for the first realm of the session.
+    pub previous_realm: String,
+    // Realm the user is joining.
+    pub new_realm: String,
+}
+
+#[derive(Serialize)]
+pub struct SegmentEventExplorerSessionStart {
+    // If the previous session ended without a clean exit.
+    pub previous_session_crashed: bool,
+}
+
 #[derive(Serialize)]
 pub struct SegmentEventSystemInfoReport {
     // Processor used by the user.
@@ -171,6 +187,16 @@ pub fn build_segment_event_batch_item(
             serde_json::to_value(event).unwrap(),
             None,
         ),
+        SegmentEvent::ExplorerRealmChange(event) => (
+            "Explorer Realm Change".to_string(),
+            serde_json::to_value(event).unwrap(),
+            None,
+        ),
+        SegmentEvent::ExplorerSessionStart(event) => (
+            "Explorer Session Start".to_string(),
+            serde_json::to_value(event).unwrap(),
+            None,
+        ),
     };
 
     let mut properties = serde_json::to_value(common).unwrap();
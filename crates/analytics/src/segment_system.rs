@@ -2,9 +2,9 @@ use bevy::{
     prelude::*,
     tasks::{IoTaskPool, Task},
 };
-use common::util::TaskExt;
+use common::{structs::AppConfig, util::TaskExt};
 use isahc::RequestExt;
-use std::time::Duration;
+use std::{collections::VecDeque, time::Duration};
 
 use crate::data_definition::{
     build_segment_event_batch_item, SegmentEvent, SegmentEventCommonExplorerFields,
@@ -21,6 +21,7 @@ impl Plugin for SegmentMetricPlugin {
         .insert_resource(SegmentMetricsEvents {
             events: Vec::new(),
             serialized_events: Vec::new(),
+            history: VecDeque::new(),
         })
         .add_systems(Update, send_segment_metric_event);
     }
@@ -36,19 +37,41 @@ pub struct SegmentConfig {
 #[derive(Resource)]
 pub struct SegmentMetricTimer(Timer);
 
+// an event as it was (or would have been) sent
+pub struct TelemetryRecord {
+    pub event: String,
+    pub json: String,
+    pub sent: bool,
+}
+
 #[derive(Resource)]
 pub struct SegmentMetricsEvents {
     events: Vec<SegmentEvent>,
     serialized_events: Vec<String>,
+    history: VecDeque<TelemetryRecord>,
 }
 
 const SEGMENT_EVENT_SIZE_LIMIT_BYTES: usize = 32000;
 const SEGMENT_BATCH_SIZE_LIMIT_BYTES: usize = 500000;
+// events kept for the telemetry viewer
+const HISTORY_LENGTH: usize = 100;
 
 impl SegmentMetricsEvents {
     pub fn add_event(&mut self, event: SegmentEvent) {
         self.events.push(event);
     }
+
+    // most recent first
+    pub fn history(&self) -> impl Iterator<Item = &TelemetryRecord> {
+        self.history.iter().rev()
+    }
+
+    fn record(&mut self, event: String, json: String, sent: bool) {
+        self.history.push_back(TelemetryRecord { event, json, sent });
+        if self.history.len() > HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+    }
 }
 
 impl SegmentConfig {
@@ -79,9 +102,23 @@ fn send_segment_metric_event(
     mut timer: ResMut<SegmentMetricTimer>,
     mut metrics: ResMut<SegmentMetricsEvents>,
     config: Res<SegmentConfig>,
+    app_config: Res<AppConfig>,
     mut send_task: Local<Option<Task<Result<(), anyhow::Error>>>>,
 ) {
     if timer.0.tick(time.delta()).just_finished() {
+        if !app_config.telemetry {
+            // keep what would have been sent for the viewer, but send nothing
+            metrics.serialized_events.clear();
+            while let Some(event) = metrics.events.pop() {
+                let raw_event =
+                    build_segment_event_batch_item(config.user_id.clone(), &config.common, event);
+                let json_body =
+                    serde_json::to_string(&raw_event).expect("Failed to serialize event body");
+                metrics.record(raw_event.event, json_body, false);
+            }
+            return;
+        }
+
         if let Some(mut t) = send_task.take() {
             match t.complete() {
                 Some(Ok(_)) => {}
@@ -110,6 +147,7 @@ fn send_segment_metric_event(
                     error!("Event too large: {}", json_body.len());
                     continue;
                 }
+                metrics.record(raw_event.event, json_body.clone(), true);
 
                 if accumulated_length + json_body.len() > SEGMENT_BATCH_SIZE_LIMIT_BYTES {
                     let write_key = config.write_key.clone();
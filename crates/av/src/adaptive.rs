@@ -0,0 +1,265 @@
+// hls and dash playlists. ffmpeg's demuxers handle the playlists and segments (dash only if ffmpeg
+// was built with libxml2), and only download the variants whose streams aren't discarded. we pick
+// a starting variant, then step down a variant when playback stalls and back up after a while
+// without stalls. the contexts keep decoding the stream they started with: packets from the
+// active variant are relabelled to the original stream indices, so variants must share codecs.
+
+use std::time::{Duration, Instant};
+
+use bevy::log::{debug, info};
+use ffmpeg_next::{ffi::AVDiscard, format::context::Input, media::Type, Dictionary, Packet};
+
+// variants taller than this aren't used, to keep decoding and texture upload costs down
+const MAX_VARIANT_HEIGHT: u32 = 1080;
+// seconds without a stall before trying a higher bitrate
+const UPSWITCH_DELAY: Duration = Duration::from_secs(30);
+// stalls right after a switch are still loading the new variant, so aren't counted
+const SWITCH_GRACE: Duration = Duration::from_secs(5);
+
+pub fn is_adaptive(path: &str) -> bool {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    path.ends_with(".m3u8") || path.ends_with(".mpd")
+}
+
+// demuxer options for playlist sources
+pub fn add_adaptive_options(options: &mut Dictionary) {
+    // start live streams a few segments behind the live edge, so there is something to buffer
+    options.set("live_start_index", "-3");
+    // keep connections open between segments, and fetch the next segment while reading the current
+    options.set("http_persistent", "1");
+    options.set("http_multiple", "1");
+    options.set("seg_max_retry", "3");
+    options.set("max_reload", "10");
+}
+
+struct Variant {
+    bitrate: u64,
+    video: usize,
+    // audio muxed with the video, if any
+    audio: Option<usize>,
+}
+
+pub struct Variants {
+    // by ascending bitrate
+    variants: Vec<Variant>,
+    // audio for variants without their own
+    shared_audio: Option<usize>,
+    current: usize,
+    // switching to this variant once it reaches a keyframe
+    pending: Option<usize>,
+    // the stream indices the contexts were created with
+    video_index: usize,
+    audio_index: Option<usize>,
+    last_switch: Instant,
+    last_stall: Instant,
+}
+
+impl Variants {
+    // returns none for sources without a choice of compatible variants
+    pub fn new(input: &mut Input) -> Option<Self> {
+        let streams = input.streams().collect::<Vec<_>>();
+        let bitrate = |index: usize| {
+            streams[index]
+                .metadata()
+                .get("variant_bitrate")
+                .and_then(|rate| rate.parse::<u64>().ok())
+        };
+
+        let programs = programs(input);
+        let mut variants = Vec::default();
+        for stream in streams.iter() {
+            let params = stream.parameters();
+            if params.medium() != Type::Video {
+                continue;
+            }
+            let Some(rate) = bitrate(stream.index()) else {
+                continue;
+            };
+            let height = unsafe { (*params.as_ptr()).height } as u32;
+            if height > MAX_VARIANT_HEIGHT {
+                continue;
+            }
+            let audio = programs
+                .iter()
+                .find(|program| program.contains(&stream.index()))
+                .and_then(|program| {
+                    program
+                        .iter()
+                        .copied()
+                        .find(|ix| streams[*ix].parameters().medium() == Type::Audio)
+                });
+            variants.push(Variant {
+                bitrate: rate,
+                video: stream.index(),
+                audio,
+            });
+        }
+
+        // only variants matching the first one's codecs can be swapped in
+        let first = variants.first()?;
+        let codec = |index: usize| streams[index].parameters().id();
+        let sample_rate =
+            |index: usize| unsafe { (*streams[index].parameters().as_ptr()).sample_rate };
+        let (video_codec, audio_format) = (
+            codec(first.video),
+            first.audio.map(|ix| (codec(ix), sample_rate(ix))),
+        );
+        variants.retain(|variant| {
+            codec(variant.video) == video_codec
+                && variant.audio.map(|ix| (codec(ix), sample_rate(ix))) == audio_format
+        });
+        if variants.len() < 2 {
+            return None;
+        }
+        variants.sort_by_key(|variant| variant.bitrate);
+
+        let shared_audio = variants
+            .iter()
+            .all(|variant| variant.audio.is_none())
+            .then(|| input.streams().best(Type::Audio).map(|s| s.index()))
+            .flatten();
+
+        // start in the middle, and adjust as we see how the connection copes
+        let current = variants.len() / 2;
+        let video_index = variants[current].video;
+        let audio_index = variants[current].audio.or(shared_audio);
+
+        let now = Instant::now();
+        let variants = Self {
+            variants,
+            shared_audio,
+            current,
+            pending: None,
+            video_index,
+            audio_index,
+            last_switch: now,
+            last_stall: now,
+        };
+        info!(
+            "adaptive stream with {} variants, starting at {}bps",
+            variants.variants.len(),
+            variants.variants[current].bitrate
+        );
+        variants.apply(input);
+        Some(variants)
+    }
+
+    // set which streams are downloaded. also needed on a reopened input
+    pub fn apply(&self, input: &mut Input) {
+        let mut active = vec![self.variants[self.current].video];
+        active.extend(self.variants[self.current].audio.or(self.shared_audio));
+        if let Some(pending) = self.pending {
+            active.push(self.variants[pending].video);
+            active.extend(self.variants[pending].audio);
+        }
+
+        unsafe {
+            let context = input.as_mut_ptr();
+            for ix in 0..(*context).nb_streams as usize {
+                let stream = *(*context).streams.add(ix);
+                (*stream).discard = if active.contains(&ix) {
+                    AVDiscard::AVDISCARD_DEFAULT
+                } else {
+                    AVDiscard::AVDISCARD_ALL
+                };
+            }
+        }
+    }
+
+    // the stream index to hand the packet to the contexts as, or none to drop it
+    pub fn map_packet(&mut self, input: &mut Input, packet: &Packet) -> Option<usize> {
+        let index = packet.stream();
+
+        if let Some(pending) = self.pending {
+            // cut over at the first keyframe from the new variant
+            if index == self.variants[pending].video && packet.is_key() {
+                debug!(
+                    "switched to variant at {}bps",
+                    self.variants[pending].bitrate
+                );
+                self.current = pending;
+                self.pending = None;
+                self.last_switch = Instant::now();
+                self.apply(input);
+            }
+        }
+
+        let variant = &self.variants[self.current];
+        if index == variant.video {
+            Some(self.video_index)
+        } else if variant.audio.is_some_and(|audio| audio == index) {
+            self.audio_index
+        } else if variant.audio.is_none() && Some(index) == self.shared_audio {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    // playback ran out of data
+    pub fn on_stall(&mut self, input: &mut Input) {
+        let now = Instant::now();
+        if now.duration_since(self.last_switch) < SWITCH_GRACE {
+            return;
+        }
+        self.last_stall = now;
+        let target = self.pending.unwrap_or(self.current);
+        if target > 0 {
+            self.switch_to(input, target - 1);
+        }
+    }
+
+    // playback is running from the buffer
+    pub fn on_playing(&mut self, input: &mut Input) {
+        let now = Instant::now();
+        if self.pending.is_some()
+            || self.current + 1 == self.variants.len()
+            || now.duration_since(self.last_stall) < UPSWITCH_DELAY
+            || now.duration_since(self.last_switch) < UPSWITCH_DELAY
+        {
+            return;
+        }
+        self.switch_to(input, self.current + 1);
+    }
+
+    fn switch_to(&mut self, input: &mut Input, target: usize) {
+        debug!(
+            "switching from {}bps to {}bps",
+            self.variants[self.current].bitrate, self.variants[target].bitrate
+        );
+        self.pending = (target != self.current).then_some(target);
+        self.last_switch = Instant::now();
+        self.apply(input);
+    }
+}
+
+// the stream indices in each program. hls puts each variant in its own program
+fn programs(input: &Input) -> Vec<Vec<usize>> {
+    unsafe {
+        let context = input.as_ptr();
+        (0..(*context).nb_programs as usize)
+            .map(|ix| {
+                let program = *(*context).programs.add(ix);
+                (0..(*program).nb_stream_indexes as usize)
+                    .map(|s| *(*program).stream_index.add(s) as usize)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// the best stream of a type that hasn't been discarded by variant selection
+pub fn best_stream(input: &Input, kind: Type) -> Option<ffmpeg_next::format::stream::Stream> {
+    let not_discarded = |stream: &ffmpeg_next::format::stream::Stream| unsafe {
+        (*stream.as_ptr()).discard != AVDiscard::AVDISCARD_ALL
+    };
+    input
+        .streams()
+        .best(kind)
+        .filter(not_discarded)
+        .or_else(|| {
+            input
+                .streams()
+                .find(|stream| stream.parameters().medium() == kind && not_discarded(stream))
+        })
+}
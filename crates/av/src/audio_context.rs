@@ -9,7 +9,7 @@ use kira::sound::streaming::StreamingSoundData;
 use thiserror::Error;
 use tokio::sync::mpsc::error::TryRecvError;
 
-use crate::stream_processor::FfmpegContext;
+use crate::{adaptive::best_stream, stream_processor::FfmpegContext, video_context::VideoData};
 
 trait SampleFormatHelper {
     fn is_planar(&self) -> bool;
@@ -217,20 +217,28 @@ pub struct AudioContext {
 
     current_frame: usize,
     start_frame: usize,
+    // media time of `time_frame`, for reporting position
+    start_time: f64,
+    time_frame: usize,
+    // seconds per timestamp unit
+    time_base: f64,
+    // after a seek, frames before this time are dropped
+    skip_until: Option<f64>,
     dead: bool,
+    // playback state goes here when there's no video context to report it
+    state_sink: Option<tokio::sync::mpsc::Sender<VideoData>>,
 }
 
 impl AudioContext {
     pub fn init(
         input_context: &Input,
         channel: tokio::sync::mpsc::Sender<StreamingSoundData<AudioDecoderError>>,
+        state_sink: Option<tokio::sync::mpsc::Sender<VideoData>>,
     ) -> Result<Self, AudioError> {
-        let input_stream = input_context
-            .streams()
-            .best(Type::Audio)
-            .ok_or(AudioError::NoStream)?;
+        let input_stream = best_stream(input_context, Type::Audio).ok_or(AudioError::NoStream)?;
 
         let stream_index = input_stream.index();
+        let time_base = f64::from(input_stream.time_base());
 
         let context_decoder =
             ffmpeg_next::codec::context::Context::from_parameters(input_stream.parameters())
@@ -290,8 +298,13 @@ impl AudioContext {
             sink: sx,
             current_frame: 0,
             start_frame: 0,
+            start_time: 0.0,
+            time_frame: 0,
+            time_base,
+            skip_until: None,
             rate: frame_rate,
             dead: false,
+            state_sink,
         })
     }
 }
@@ -309,6 +322,13 @@ impl FfmpegContext for AudioContext {
         self.decoder.send_packet(&packet).unwrap();
         let mut decoded = frame::Audio::empty();
         if let Ok(()) = self.decoder.receive_frame(&mut decoded) {
+            if let Some(target) = self.skip_until {
+                let time = decoded.timestamp().map(|ts| ts as f64 * self.time_base);
+                if time.is_some_and(|time| time + 1.0 / self.rate < target) {
+                    return Ok(());
+                }
+                self.skip_until = None;
+            }
             self.buffer.push_back(decoded);
         }
         Ok(())
@@ -343,19 +363,33 @@ impl FfmpegContext for AudioContext {
         self.start_frame = self.current_frame;
     }
 
-    fn reset_start_frame(&mut self) {}
+    fn reset_start_frame(&mut self) {
+        // looped back to the start, which is sent after what's already buffered
+        self.start_time = 0.0;
+        self.time_frame = self.current_frame + self.buffer.len();
+    }
 
     fn seconds_till_next_frame(&self) -> f64 {
         (self.current_frame - self.start_frame + 1) as f64 / self.rate
     }
 
-    fn update_state(&self, _state: VideoState) {
-        // disregard
+    fn current_time(&self) -> f64 {
+        self.start_time + (self.current_frame as f64 - self.time_frame as f64) / self.rate
+    }
+
+    fn update_state(&self, state: VideoState) {
+        if let Some(sink) = self.state_sink.as_ref() {
+            let _ = sink.blocking_send(VideoData::State(state));
+        }
     }
 
-    fn clear(&mut self) {
+    fn clear(&mut self, time: f64) {
+        self.decoder.flush();
         self.buffer.clear();
         self.current_frame = 0;
         self.start_frame = 0;
+        self.start_time = time;
+        self.time_frame = 0;
+        self.skip_until = Some(time);
     }
 }
@@ -0,0 +1,133 @@
+// output device selection. kira can't move a running manager to another device, so when the chosen
+// device changes (or is plugged in or removed) the manager is rebuilt on the new device and
+// anything long-running is restarted on it. clips, scene streams and the reverb track are
+// restarted here and by the video player, voice streams are resent by the transport when their
+// sound is dropped.
+
+use bevy::prelude::*;
+use bevy_kira_audio::{audio_output::AudioOutput, prelude::AudioEmitter};
+use common::structs::AudioSettings;
+use cpal::traits::{DeviceTrait, HostTrait};
+use kira::manager::{
+    backend::{cpal::CpalBackendSettings, DefaultBackend},
+    AudioManager, AudioManagerSettings,
+};
+
+use crate::{audio_source::AudioSource, reverb_zone::ReverbTrack};
+
+// seconds between checks for plugged or unplugged devices
+const DEVICE_CHECK_INTERVAL: f32 = 1.0;
+
+pub struct AudioDevicePlugin;
+
+impl Plugin for AudioDevicePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AudioOutputChanged>();
+        app.add_systems(Update, (update_output_device, restart_scene_audio).chain());
+    }
+}
+
+// sent when the manager is replaced, all existing sounds are dead
+#[derive(Event)]
+pub struct AudioOutputChanged;
+
+// the named input device if it's plugged in, else the system default
+pub fn find_input_device(name: Option<&str>) -> Option<cpal::Device> {
+    let host = cpal::default_host();
+    name.and_then(|name| {
+        host.input_devices()
+            .ok()?
+            .find(|d| d.name().is_ok_and(|n| n == name))
+    })
+    .or_else(|| host.default_input_device())
+}
+
+// the named output device if it's plugged in, else the system default
+pub fn find_output_device(name: Option<&str>) -> Option<cpal::Device> {
+    let host = cpal::default_host();
+    name.and_then(|name| {
+        host.output_devices()
+            .ok()?
+            .find(|d| d.name().is_ok_and(|n| n == name))
+    })
+    .or_else(|| host.default_output_device())
+}
+
+fn update_output_device(
+    mut audio_output: NonSendMut<AudioOutput<DefaultBackend>>,
+    settings: Res<AudioSettings>,
+    time: Res<Time>,
+    mut active: Local<Option<Option<String>>>,
+    mut last_check: Local<f32>,
+    mut changed: EventWriter<AudioOutputChanged>,
+) {
+    if !settings.is_changed() && time.elapsed_seconds() - *last_check < DEVICE_CHECK_INTERVAL {
+        return;
+    }
+    *last_check = time.elapsed_seconds();
+
+    let device = find_output_device(settings.output_device.as_deref());
+    let name = device.as_ref().and_then(|d| d.name().ok());
+
+    let Some(current) = active.as_mut() else {
+        // bevy_kira_audio starts on the default device
+        *active = Some(
+            cpal::default_host()
+                .default_output_device()
+                .and_then(|d| d.name().ok()),
+        );
+        return;
+    };
+
+    if name == *current {
+        return;
+    }
+
+    // drop the old manager first, some backends only allow one stream per device
+    audio_output.manager = None;
+    *current = None;
+    changed.send(AudioOutputChanged);
+
+    let Some(device) = device else {
+        warn!("no audio output device");
+        return;
+    };
+
+    let manager_settings = AudioManagerSettings {
+        backend: CpalBackendSettings {
+            device: Some(device),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    match AudioManager::<DefaultBackend>::new(manager_settings) {
+        Ok(manager) => {
+            info!("audio output set to {}", name.as_deref().unwrap_or("?"));
+            audio_output.manager = Some(manager);
+            *current = name;
+        }
+        // retried on the next check
+        Err(e) => warn!("failed to open audio output: {e}"),
+    }
+}
+
+fn restart_scene_audio(
+    mut commands: Commands,
+    mut changed: EventReader<AudioOutputChanged>,
+    mut sources: Query<(&mut AudioSource, &mut AudioEmitter)>,
+) {
+    if changed.read().last().is_none() {
+        return;
+    }
+
+    // the reverb track is recreated on the new manager
+    commands.add(|world: &mut World| {
+        world.remove_non_send_resource::<ReverbTrack>();
+    });
+
+    // clips are replayed from their start position
+    for (mut source, mut emitter) in sources.iter_mut() {
+        emitter.instances.clear();
+        source.set_changed();
+    }
+}
@@ -0,0 +1,82 @@
+// lower scene sounds and media streams while someone is talking, so voice chat can be heard over
+// music and ambience without adjusting the volume settings. the amount comes from
+// `AudioSettings::ducking`.
+
+use bevy::prelude::*;
+use common::{structs::AudioSettings, util::AudioReceiver};
+use comms::global_crdt::ForeignVoiceActive;
+
+use crate::microphone::MicState;
+
+// voice from further away than this isn't played
+const DUCKING_DISTANCE: f32 = 25.0;
+// seconds to fade fully down when voice starts
+const DUCKING_ATTACK: f32 = 0.15;
+// seconds to fade fully back up once voice stops
+const DUCKING_RELEASE: f32 = 1.0;
+// stay ducked for this long after voice was last detected, to ride over gaps between words
+const DUCKING_HOLD: f32 = 0.5;
+
+pub struct AudioDuckingPlugin;
+
+impl Plugin for AudioDuckingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AudioDucking>();
+        app.add_systems(Update, update_ducking);
+    }
+}
+
+#[derive(Resource)]
+pub struct AudioDucking {
+    current: f32,
+    last_voice: Option<f32>,
+}
+
+impl Default for AudioDucking {
+    fn default() -> Self {
+        Self {
+            current: 1.0,
+            last_voice: None,
+        }
+    }
+}
+
+impl AudioDucking {
+    // volume multiplier for ducked buses
+    pub fn volume(&self) -> f32 {
+        self.current
+    }
+}
+
+fn update_ducking(
+    mut ducking: ResMut<AudioDucking>,
+    settings: Res<AudioSettings>,
+    mic: Res<MicState>,
+    speakers: Query<&GlobalTransform, With<ForeignVoiceActive>>,
+    receiver: Query<&GlobalTransform, With<AudioReceiver>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let nearby_voice = receiver.get_single().is_ok_and(|receiver| {
+        speakers.iter().any(|speaker| {
+            speaker.translation().distance(receiver.translation()) < DUCKING_DISTANCE
+        })
+    });
+    if mic.speaking || nearby_voice {
+        ducking.last_voice = Some(now);
+    }
+
+    let active = ducking
+        .last_voice
+        .is_some_and(|last| now - last < DUCKING_HOLD);
+    let target = if active { settings.ducked() } else { 1.0 };
+    if ducking.current == target {
+        return;
+    }
+
+    ducking.current = if target < ducking.current {
+        (ducking.current - time.delta_seconds() / DUCKING_ATTACK).max(target)
+    } else {
+        (ducking.current + time.delta_seconds() / DUCKING_RELEASE).min(target)
+    };
+}
@@ -0,0 +1,119 @@
+// cap the number of clips each scene can play at once. when a scene is over the limit the
+// quietest sources (after distance and occlusion) are stopped, and looping ones are restarted
+// when they become loud enough to win a slot back.
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_kira_audio::{prelude::AudioEmitter, AudioInstance, AudioTween, PlaybackState};
+use common::util::VolumePanning;
+use dcl::{SceneLogLevel, SceneLogMessage};
+use scene_runner::{renderer_context::RendererSceneContext, SceneEntity};
+
+use crate::{audio_occlusion::AudioOcclusion, audio_source::AudioSource};
+
+// clips a single scene can play at once
+pub const MAX_SCENE_SOURCES: usize = 32;
+// seconds between priority checks
+const LIMIT_INTERVAL: f32 = 0.5;
+
+// the source was stopped to keep its scene under the limit
+#[derive(Component)]
+pub struct AudioSourceStolen;
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn limit_scene_sources(
+    mut commands: Commands,
+    mut sources: Query<(
+        Entity,
+        &SceneEntity,
+        &mut AudioSource,
+        &GlobalTransform,
+        Option<&AudioEmitter>,
+        Option<&AudioOcclusion>,
+        Has<AudioSourceStolen>,
+    )>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut scenes: Query<&mut RendererSceneContext>,
+    pan: VolumePanning,
+    time: Res<Time>,
+    mut last_check: Local<f32>,
+    mut warned: Local<HashSet<Entity>>,
+) {
+    if time.elapsed_seconds() - *last_check < LIMIT_INTERVAL {
+        return;
+    }
+    *last_check = time.elapsed_seconds();
+
+    // (priority, entity) of the active sources in each scene
+    let mut active: HashMap<Entity, Vec<(f32, Entity)>> = HashMap::default();
+    for (ent, scene_ent, source, transform, maybe_emitter, maybe_occlusion, stolen) in
+        sources.iter()
+    {
+        let playing = maybe_emitter
+            .and_then(|emitter| emitter.instances.first())
+            .and_then(|h_instance| audio_instances.get(h_instance))
+            .is_some_and(|instance| matches!(instance.state(), PlaybackState::Playing { .. }));
+        // stolen loops still want to play
+        if !(playing || (stolen && source.0.playing() && source.0.r#loop())) {
+            continue;
+        }
+
+        let attenuation = if source.0.global() {
+            1.0
+        } else {
+            pan.volume_and_panning(transform.translation()).0
+                * maybe_occlusion.map_or(1.0, AudioOcclusion::volume)
+        };
+        let priority = source.0.volume.unwrap_or(1.0) * attenuation;
+        active
+            .entry(scene_ent.root)
+            .or_default()
+            .push((priority, ent));
+    }
+
+    warned.retain(|root| scenes.contains(*root));
+
+    for (root, mut scene_sources) in active {
+        // closest and loudest first
+        scene_sources.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        if scene_sources.len() > MAX_SCENE_SOURCES && warned.insert(root) {
+            if let Ok(mut context) = scenes.get_mut(root) {
+                let timestamp = context.total_runtime as f64;
+                context.log(SceneLogMessage {
+                    timestamp,
+                    level: SceneLogLevel::SceneError,
+                    message: format!(
+                        "{} audio sources are playing, only the {MAX_SCENE_SOURCES} closest and loudest will be heard",
+                        scene_sources.len()
+                    ),
+                });
+            }
+        }
+
+        for (ix, (_, ent)) in scene_sources.into_iter().enumerate() {
+            let Ok((_, _, mut source, _, maybe_emitter, _, stolen)) = sources.get_mut(ent) else {
+                continue;
+            };
+
+            if ix < MAX_SCENE_SOURCES {
+                if stolen {
+                    commands.entity(ent).remove::<AudioSourceStolen>();
+                    // restarted by `update_audio`
+                    if source.0.r#loop() {
+                        source.set_changed();
+                    }
+                }
+            } else if !stolen {
+                for h_instance in maybe_emitter.iter().flat_map(|emitter| &emitter.instances) {
+                    if let Some(instance) = audio_instances.get_mut(h_instance) {
+                        instance.stop(AudioTween::default());
+                    }
+                }
+                commands.entity(ent).try_insert(AudioSourceStolen);
+            }
+        }
+    }
+}
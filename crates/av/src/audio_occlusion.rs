@@ -0,0 +1,127 @@
+// muffle scene audio that is behind walls. a few rays are cast from the receiver to each emitter a
+// couple of times a second, and the emitter volume is scaled by how many of them are blocked by the
+// scene's physics colliders.
+// TODO: also low-pass occluded sources, bevy_kira_audio instances don't expose per-sound filters
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::AudioEmitter;
+use common::util::AudioReceiver;
+use dcl_component::proto_components::sdk::components::ColliderLayer;
+use scene_runner::{
+    renderer_context::RendererSceneContext, update_world::mesh_collider::SceneColliderData,
+    SceneEntity,
+};
+
+use crate::audio_source::AudioSource;
+
+// seconds between checks for each emitter
+const OCCLUSION_INTERVAL: f32 = 0.5;
+// volume when every ray is blocked
+const OCCLUDED_VOLUME: f32 = 0.25;
+// fraction of the remaining difference closed per second
+const OCCLUSION_SMOOTHING: f32 = 4.0;
+// rays stop short of the emitter so the source's own collider doesn't count
+const EMITTER_CLEARANCE: f32 = 0.5;
+// the rays are spread around the direct path, so a thin pillar doesn't silence a source
+const RAY_OFFSETS: [Vec3; 3] = [
+    Vec3::ZERO,
+    Vec3::new(0.0, 0.75, 0.0),
+    Vec3::new(0.0, -0.5, 0.0),
+];
+
+#[derive(Component)]
+pub struct AudioOcclusion {
+    target: f32,
+    current: f32,
+    next_check: f32,
+}
+
+impl AudioOcclusion {
+    // volume multiplier for the source
+    pub fn volume(&self) -> f32 {
+        self.current
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_occlusion(
+    mut commands: Commands,
+    mut emitters: Query<
+        (
+            Entity,
+            &SceneEntity,
+            &GlobalTransform,
+            Option<&AudioSource>,
+            Option<&mut AudioOcclusion>,
+        ),
+        With<AudioEmitter>,
+    >,
+    mut scenes: Query<(&RendererSceneContext, &mut SceneColliderData)>,
+    receiver: Query<&GlobalTransform, With<AudioReceiver>>,
+    time: Res<Time>,
+) {
+    let Ok(receiver) = receiver.get_single() else {
+        return;
+    };
+    let now = time.elapsed_seconds();
+
+    for (ent, scene_ent, transform, maybe_source, maybe_occlusion) in emitters.iter_mut() {
+        // global sources have no position to be occluded from
+        if maybe_source.is_some_and(|source| source.0.global()) {
+            if maybe_occlusion.is_some() {
+                commands.entity(ent).remove::<AudioOcclusion>();
+            }
+            continue;
+        }
+
+        let Some(mut occlusion) = maybe_occlusion else {
+            // spread the first checks out so emitters don't all cast on the same frame
+            commands.entity(ent).try_insert(AudioOcclusion {
+                target: 1.0,
+                current: 1.0,
+                next_check: now + OCCLUSION_INTERVAL * (ent.index() % 16) as f32 / 16.0,
+            });
+            continue;
+        };
+
+        if now >= occlusion.next_check {
+            occlusion.next_check = now + OCCLUSION_INTERVAL;
+
+            if let Ok((context, mut colliders)) = scenes.get_mut(scene_ent.root) {
+                let origin = receiver.translation();
+                let blocked = RAY_OFFSETS
+                    .iter()
+                    .filter(|offset| {
+                        let path = transform.translation() + **offset - origin;
+                        let distance = path.length() - EMITTER_CLEARANCE;
+                        distance > 0.0
+                            && colliders
+                                .cast_ray_nearest(
+                                    context.last_update_frame,
+                                    origin,
+                                    path.normalize(),
+                                    distance,
+                                    ColliderLayer::ClPhysics as u32,
+                                    true,
+                                )
+                                .is_some()
+                    })
+                    .count();
+                let blocked = blocked as f32 / RAY_OFFSETS.len() as f32;
+                occlusion.target = 1.0 - blocked * (1.0 - OCCLUDED_VOLUME);
+            } else {
+                occlusion.target = 1.0;
+            }
+        }
+
+        if occlusion.current != occlusion.target {
+            let step = (OCCLUSION_SMOOTHING * time.delta_seconds()).min(1.0);
+            let current = occlusion.current + (occlusion.target - occlusion.current) * step;
+            occlusion.current = if (current - occlusion.target).abs() < 0.01 {
+                occlusion.target
+            } else {
+                current
+            };
+        }
+    }
+}
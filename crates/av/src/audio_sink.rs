@@ -1,11 +1,27 @@
-use bevy::prelude::*;
-use common::structs::{AudioDecoderError, AudioSettings, PrimaryCamera, PrimaryUser};
+use bevy::{prelude::*, utils::HashMap};
+use common::{
+    structs::{AudioDecoderError, AudioSettings, PrimaryCamera, PrimaryUser},
+    util::VolumePanning,
+};
 use comms::global_crdt::ForeignAudioSource;
-use kira::{manager::backend::DefaultBackend, sound::streaming::StreamingSoundData, tween::Tween};
+use kira::{
+    manager::backend::DefaultBackend,
+    sound::streaming::StreamingSoundData,
+    track::{TrackBuilder, TrackHandle, TrackRoutes},
+    tween::Tween,
+};
 use scene_runner::{ContainingScene, SceneEntity};
 use tokio::sync::mpsc::error::TryRecvError;
 
-use crate::stream_processor::AVCommand;
+use crate::{
+    audio_ducking::AudioDucking,
+    binaural::{BinauralBuilder, BinauralHandle},
+    reverb_zone::ReverbTrack,
+    stereo_width::{StereoWidthBuilder, StereoWidthHandle},
+    stream_processor::AVCommand,
+    video_player::AVPlayer,
+    video_stream::VideoSink,
+};
 
 #[derive(Component)]
 pub struct AudioSink {
@@ -43,23 +59,43 @@ impl Drop for AudioSpawned {
     }
 }
 
+// stereo sources are full width up to this distance
+const FULL_WIDTH_DISTANCE: f32 = 5.0;
+// and mono from here
+const MONO_DISTANCE: f32 = 30.0;
+
+// a video's own track, for narrowing its stereo image with distance
+pub struct MediaTrack {
+    _track: TrackHandle,
+    width: StereoWidthHandle,
+}
+
 // TODO integrate better with bevy_kira_audio to avoid logic on a main-thread system (NonSendMut forces this system to the main thread)
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn spawn_audio_streams(
     mut commands: Commands,
     mut streams: Query<(
         Entity,
         &SceneEntity,
+        &GlobalTransform,
         &mut AudioSink,
         Option<&mut AudioSpawned>,
+        Option<&AVPlayer>,
+        Option<&VideoSink>,
     )>,
     mut audio_manager: NonSendMut<bevy_kira_audio::audio_output::AudioOutput<DefaultBackend>>,
     containing_scene: ContainingScene,
     player: Query<Entity, With<PrimaryUser>>,
-    settings: Res<AudioSettings>,
+    (settings, ducking): (Res<AudioSettings>, Res<AudioDucking>),
+    reverb: Option<NonSend<ReverbTrack>>,
+    pan: VolumePanning,
+    mut media_tracks: Local<HashMap<Entity, MediaTrack>>,
 ) {
-    if audio_manager.manager.is_none() {
+    let Some(manager) = audio_manager.manager.as_mut() else {
         return;
-    }
+    };
+
+    media_tracks.retain(|ent, _| streams.contains(*ent));
 
     let containing_scenes = player
         .get_single()
@@ -67,17 +103,43 @@ pub fn spawn_audio_streams(
         .map(|player| containing_scene.get(player))
         .unwrap_or_default();
 
-    for (ent, scene, mut stream, mut maybe_spawned) in streams.iter_mut() {
+    for (ent, scene, transform, mut stream, mut maybe_spawned, maybe_player, maybe_video) in
+        streams.iter_mut()
+    {
+        let spatial = maybe_player.is_some_and(|player| player.spatial);
         if maybe_spawned.is_none() || stream.is_changed() {
             match stream.sound_data.try_recv() {
                 Ok(sound_data) => {
                     info!("{ent:?} received sound data!");
-                    let handle = audio_manager
-                        .manager
-                        .as_mut()
-                        .unwrap()
-                        .play(sound_data)
-                        .unwrap();
+                    media_tracks.remove(&ent);
+                    let mut track = None;
+                    if spatial {
+                        let mut builder = TrackBuilder::new();
+                        if let Some(reverb) = reverb.as_ref() {
+                            builder = builder.routes(TrackRoutes::parent(reverb.track.id()));
+                        }
+                        let width = builder.add_effect(StereoWidthBuilder);
+                        match manager.add_sub_track(builder) {
+                            Ok(handle) => track = Some((handle, width)),
+                            Err(e) => warn!("failed to create media track: {e}"),
+                        }
+                    }
+                    let sound_data = match (track, reverb.as_ref()) {
+                        (Some((track, width)), _) => {
+                            let sound_data = sound_data.output_destination(&track);
+                            media_tracks.insert(
+                                ent,
+                                MediaTrack {
+                                    _track: track,
+                                    width,
+                                },
+                            );
+                            sound_data
+                        }
+                        (None, Some(reverb)) => sound_data.output_destination(&reverb.track),
+                        (None, None) => sound_data,
+                    };
+                    let handle = manager.play(sound_data).unwrap();
                     commands.entity(ent).try_insert(AudioSpawned(Some(handle)));
                 }
                 Err(TryRecvError::Disconnected) => {
@@ -90,19 +152,44 @@ pub fn spawn_audio_streams(
             }
         }
 
-        let volume = stream.volume * settings.scene();
         if let Some(handle) = maybe_spawned.as_mut().and_then(|a| a.0.as_mut()) {
-            if containing_scenes.contains(&scene.root) {
-                let _ = handle.set_volume(volume as f64, Tween::default());
+            let (volume, panning) = if !containing_scenes.contains(&scene.root) {
+                (0.0, 0.5)
+            } else if spatial {
+                let (attenuation, panning) = pan.volume_and_panning(transform.translation());
+                if let Some(track) = media_tracks.get(&ent) {
+                    let distance = pan.distance(transform.translation());
+                    track.width.set_width(
+                        1.0 - (distance - FULL_WIDTH_DISTANCE)
+                            / (MONO_DISTANCE - FULL_WIDTH_DISTANCE),
+                    );
+                }
+                (
+                    stream.volume * settings.scene() * ducking.volume() * attenuation,
+                    panning,
+                )
             } else {
-                let _ = handle.set_volume(0.0, Tween::default());
-            }
+                (stream.volume * settings.media() * ducking.volume(), 0.5)
+            };
+            let _ = handle.set_volume(volume as f64, Tween::default());
+            let _ = handle.set_panning(panning as f64, Tween::default());
+            // the av thread sends audio at the player's rate
+            let rate = maybe_player.map_or(1.0, AVPlayer::playback_rate)
+                * maybe_video.map_or(1.0, |video| video.rate_nudge);
+            let _ = handle.set_playback_rate(rate, Tween::default());
         }
     }
 }
 
 const MAX_CHAT_DISTANCE: f32 = 25.0;
 
+// a voice stream's own track, for binaural spatialization
+pub struct VoiceTrack {
+    _track: TrackHandle,
+    binaural: BinauralHandle,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_and_locate_foreign_streams(
     mut commands: Commands,
     mut streams: Query<(
@@ -114,25 +201,50 @@ pub fn spawn_and_locate_foreign_streams(
     mut audio_manager: NonSendMut<bevy_kira_audio::audio_output::AudioOutput<DefaultBackend>>,
     receiver: Query<&GlobalTransform, With<PrimaryCamera>>,
     settings: Res<AudioSettings>,
+    reverb: Option<NonSend<ReverbTrack>>,
+    mut voice_tracks: Local<HashMap<Entity, VoiceTrack>>,
 ) {
-    if audio_manager.manager.is_none() {
+    let Some(manager) = audio_manager.manager.as_mut() else {
         return;
-    }
+    };
 
     let Ok(receiver_transform) = receiver.get_single() else {
         return;
     };
 
+    voice_tracks.retain(|ent, _| streams.contains(*ent));
+
     for (ent, emitter_transform, mut stream, mut maybe_spawned) in streams.iter_mut() {
         match stream.0.try_recv() {
             Ok(sound_data) => {
                 info!("{ent:?} received foreign sound data!");
-                let handle = audio_manager
-                    .manager
-                    .as_mut()
-                    .unwrap()
-                    .play(sound_data)
-                    .unwrap();
+                let mut builder = TrackBuilder::new();
+                if let Some(reverb) = reverb.as_ref() {
+                    builder = builder.routes(TrackRoutes::parent(reverb.track.id()));
+                }
+                let binaural = builder.add_effect(BinauralBuilder::new(settings.headphones));
+                let sound_data = match manager.add_sub_track(builder) {
+                    Ok(track) => {
+                        let sound_data = sound_data.output_destination(&track);
+                        voice_tracks.insert(
+                            ent,
+                            VoiceTrack {
+                                _track: track,
+                                binaural,
+                            },
+                        );
+                        sound_data
+                    }
+                    Err(e) => {
+                        warn!("failed to create voice track: {e}");
+                        voice_tracks.remove(&ent);
+                        match reverb.as_ref() {
+                            Some(reverb) => sound_data.output_destination(&reverb.track),
+                            None => sound_data,
+                        }
+                    }
+                };
+                let handle = manager.play(sound_data).unwrap();
                 commands.entity(ent).try_insert(AudioSpawned(Some(handle)));
             }
             Err(TryRecvError::Disconnected) => (),
@@ -145,12 +257,25 @@ pub fn spawn_and_locate_foreign_streams(
                 .clamp(0., 1.)
                 .powi(2);
 
-            let panning = if sound_path.length() > f32::EPSILON {
+            let binaural = voice_tracks
+                .get(&ent)
+                .map(|track| &track.binaural)
+                .filter(|_| settings.headphones);
+            let panning = if sound_path.length() <= f32::EPSILON {
+                0.5
+            } else if let Some(binaural) = binaural {
+                // the effect positions the sound, so it isn't panned as well
+                let right = receiver_transform.right().dot(sound_path);
+                let forward = receiver_transform.forward().dot(sound_path);
+                binaural.set_azimuth(right.atan2(forward));
+                0.5
+            } else {
                 let right_ear_angle = receiver_transform.right().angle_between(sound_path);
                 (right_ear_angle.cos() + 1.) / 2.
-            } else {
-                0.5
             };
+            if let Some(track) = voice_tracks.get(&ent) {
+                track.binaural.set_enabled(settings.headphones);
+            }
 
             let volume = volume * settings.voice();
 
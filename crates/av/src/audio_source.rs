@@ -8,7 +8,7 @@ use bevy_kira_audio::{prelude::AudioEmitter, AudioControl, AudioInstance, AudioT
 use common::{
     sets::SetupSets,
     structs::{AudioSettings, PrimaryCameraRes, PrimaryUser, SystemAudio},
-    util::{AudioReceiver, VolumePanning},
+    util::{AudioReceiver, EmitterVolume, VolumePanning},
 };
 use dcl::interface::ComponentPosition;
 use dcl_component::{proto_components::sdk::components::PbAudioSource, SceneComponentId};
@@ -18,8 +18,14 @@ use scene_runner::{
     SceneEntity,
 };
 
+use crate::{
+    audio_ducking::AudioDucking,
+    audio_limit::limit_scene_sources,
+    audio_occlusion::{update_occlusion, AudioOcclusion},
+};
+
 #[derive(Component, Debug)]
-pub struct AudioSource(PbAudioSource);
+pub struct AudioSource(pub(crate) PbAudioSource);
 
 impl From<PbAudioSource> for AudioSource {
     fn from(value: PbAudioSource) -> Self {
@@ -38,7 +44,16 @@ impl Plugin for AudioSourcePlugin {
         );
         app.add_systems(
             PostUpdate,
-            (update_audio, update_source_volume, play_system_audio)
+            (
+                update_audio,
+                update_occlusion.before(update_source_volume),
+                limit_scene_sources
+                    .after(update_audio)
+                    .after(update_occlusion)
+                    .before(update_source_volume),
+                update_source_volume,
+                play_system_audio,
+            )
                 .after(TransformSystem::TransformPropagate),
         );
         app.add_systems(Startup, setup_audio.in_set(SetupSets::Main));
@@ -77,6 +92,7 @@ fn update_audio(
     player: Query<Entity, With<PrimaryUser>>,
     cam: Query<&GlobalTransform, With<AudioReceiver>>,
     settings: Res<AudioSettings>,
+    ducking: Res<AudioDucking>,
 ) {
     let current_scenes = player
         .get_single()
@@ -133,7 +149,7 @@ fn update_audio(
             );
 
             let volume = if current_scenes.contains(&scene_ent.root) {
-                audio_source.0.volume.unwrap_or(1.0) * settings.scene()
+                audio_source.0.volume.unwrap_or(1.0) * settings.scene() * ducking.volume()
             } else {
                 0.0
             };
@@ -253,6 +269,8 @@ fn update_source_volume(
         Option<&AudioSource>,
         &mut AudioEmitter,
         &GlobalTransform,
+        Option<&AudioOcclusion>,
+        Option<&EmitterVolume>,
     )>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
     containing_scene: ContainingScene,
@@ -260,6 +278,7 @@ fn update_source_volume(
     mut prev_scenes: Local<HashSet<Entity>>,
     pan: VolumePanning,
     settings: Res<AudioSettings>,
+    ducking: Res<AudioDucking>,
     mut all_instances: Local<HashMap<Entity, Vec<Handle<AudioInstance>>>>,
 ) {
     let current_scenes = player
@@ -270,25 +289,36 @@ fn update_source_volume(
 
     let mut prev_instances = std::mem::take(&mut *all_instances);
 
-    for (ent, maybe_scene, maybe_source, mut emitter, transform) in query.iter_mut() {
+    for (
+        ent,
+        maybe_scene,
+        maybe_source,
+        mut emitter,
+        transform,
+        maybe_occlusion,
+        maybe_emitter_volume,
+    ) in query.iter_mut()
+    {
         if maybe_scene.map_or(true, |scene| current_scenes.contains(&scene.root)) {
             let (volume, panning) = if maybe_source.is_some_and(|source| source.0.global()) {
                 (
                     maybe_source
                         .and_then(|source| source.0.volume)
-                        .unwrap_or(1.0),
+                        .unwrap_or(1.0)
+                        * ducking.volume(),
                     0.5,
                 )
             } else {
                 let volume_adjust = if maybe_scene.is_some() {
-                    settings.scene()
+                    settings.scene() * ducking.volume()
                 } else {
-                    settings.avatar()
+                    settings.avatar() * maybe_emitter_volume.map_or(1.0, |v| v.0)
                 };
 
                 let (volume, panning) = pan.volume_and_panning(transform.translation());
+                let occlusion = maybe_occlusion.map_or(1.0, AudioOcclusion::volume);
 
-                (volume * volume_adjust, panning)
+                (volume * volume_adjust * occlusion, panning)
             };
 
             emitter.instances.retain_mut(|h_instance| {
@@ -0,0 +1,226 @@
+// a cheap binaural spatializer for headphones, using a spherical head model rather than measured
+// hrtfs: the far ear hears the sound slightly later (interaural time difference), quieter and
+// duller (head shadow), and sounds behind the listener are a little muffled.
+// the effect runs on a per-source kira track, when disabled it passes audio through unchanged and
+// the sound is panned as normal.
+// it applies to voice chat only. scene `PbAudioSource` clips are played by bevy_kira_audio, which
+// can't route sounds to a track, and stay stereo panned with the volume, occlusion and limit
+// handling in `audio_source`.
+
+use std::sync::{
+    atomic::{AtomicBool, AtomicU32, Ordering},
+    Arc,
+};
+
+use kira::{
+    clock::clock_info::ClockInfoProvider,
+    dsp::Frame,
+    modulator::value_provider::ModulatorValueProvider,
+    track::effect::{Effect, EffectBuilder},
+};
+
+// meters
+const HEAD_RADIUS: f32 = 0.0875;
+// meters per second
+const SPEED_OF_SOUND: f32 = 343.0;
+// low-pass cutoff for the far ear of a sound directly to one side
+const SHADOW_CUTOFF: f32 = 1500.0;
+// volume for the far ear of a sound directly to one side
+const SHADOW_VOLUME: f32 = 0.6;
+// low-pass cutoff for a sound directly behind
+const REAR_CUTOFF: f32 = 6000.0;
+const OPEN_CUTOFF: f32 = 20000.0;
+// fraction of the remaining difference closed per sample, so moving sources don't click
+const SMOOTHING: f32 = 0.002;
+
+pub struct BinauralBuilder {
+    params: Arc<BinauralParams>,
+}
+
+#[derive(Default)]
+struct BinauralParams {
+    enabled: AtomicBool,
+    // radians clockwise from straight ahead, as f32 bits
+    azimuth: AtomicU32,
+}
+
+#[derive(Clone)]
+pub struct BinauralHandle {
+    params: Arc<BinauralParams>,
+}
+
+impl BinauralHandle {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.params.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn set_azimuth(&self, azimuth: f32) {
+        self.params
+            .azimuth
+            .store(azimuth.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl BinauralBuilder {
+    pub fn new(enabled: bool) -> Self {
+        let params = BinauralParams::default();
+        params.enabled.store(enabled, Ordering::Relaxed);
+        Self {
+            params: Arc::new(params),
+        }
+    }
+}
+
+impl EffectBuilder for BinauralBuilder {
+    type Handle = BinauralHandle;
+
+    fn build(self) -> (Box<dyn Effect>, Self::Handle) {
+        (
+            Box::new(Binaural {
+                params: self.params.clone(),
+                sample_rate: 48000.0,
+                buffer: Vec::new(),
+                write: 0,
+                azimuth_bits: None,
+                target: [EarParams::OPEN; 2],
+                current: [EarParams::OPEN; 2],
+                lowpass: [0.0; 2],
+            }),
+            BinauralHandle {
+                params: self.params,
+            },
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+struct EarParams {
+    // samples
+    delay: f32,
+    volume: f32,
+    // one-pole low-pass coefficient
+    filter: f32,
+}
+
+impl EarParams {
+    const OPEN: Self = Self {
+        delay: 0.0,
+        volume: 1.0,
+        filter: 1.0,
+    };
+
+    fn approach(&mut self, target: &Self) {
+        self.delay += (target.delay - self.delay) * SMOOTHING;
+        self.volume += (target.volume - self.volume) * SMOOTHING;
+        self.filter += (target.filter - self.filter) * SMOOTHING;
+    }
+}
+
+struct Binaural {
+    params: Arc<BinauralParams>,
+    sample_rate: f32,
+    // mono input history for the delays
+    buffer: Vec<f32>,
+    write: usize,
+    azimuth_bits: Option<u32>,
+    target: [EarParams; 2],
+    current: [EarParams; 2],
+    lowpass: [f32; 2],
+}
+
+impl Binaural {
+    fn filter_coefficient(&self, cutoff: f32) -> f32 {
+        1.0 - (-std::f32::consts::TAU * cutoff / self.sample_rate).exp()
+    }
+
+    fn update_targets(&mut self, azimuth: f32) {
+        // -1 for hard left to 1 for hard right
+        let lateral = azimuth.sin();
+        let rear = (-azimuth.cos()).max(0.0);
+
+        // woodworth's formula for the extra path around the head to the far ear
+        let angle = lateral.abs().asin();
+        let delay = HEAD_RADIUS / SPEED_OF_SOUND * (angle + angle.sin()) * self.sample_rate;
+
+        let rear_cutoff = OPEN_CUTOFF + (REAR_CUTOFF - OPEN_CUTOFF) * rear;
+        let near = EarParams {
+            delay: 0.0,
+            volume: 1.0,
+            filter: self.filter_coefficient(rear_cutoff),
+        };
+        let far_cutoff = rear_cutoff + (SHADOW_CUTOFF - rear_cutoff) * lateral.abs();
+        let far = EarParams {
+            delay,
+            volume: 1.0 + (SHADOW_VOLUME - 1.0) * lateral.abs(),
+            filter: self.filter_coefficient(far_cutoff),
+        };
+
+        self.target = if lateral >= 0.0 {
+            [far, near]
+        } else {
+            [near, far]
+        };
+    }
+
+    // output for ear 0 (left) or 1 (right) from the current input history
+    fn process_ear(&mut self, ear: usize) -> f32 {
+        let target = self.target[ear];
+        self.current[ear].approach(&target);
+        let params = self.current[ear];
+        let sample = self.read_delayed(params.delay);
+        self.lowpass[ear] += (sample - self.lowpass[ear]) * params.filter;
+        self.lowpass[ear] * params.volume
+    }
+
+    fn read_delayed(&self, delay: f32) -> f32 {
+        let len = self.buffer.len();
+        let whole = delay.floor() as usize;
+        let fraction = delay - whole as f32;
+        let a = self.buffer[(self.write + len - whole) % len];
+        let b = self.buffer[(self.write + len - whole - 1) % len];
+        a + (b - a) * fraction
+    }
+}
+
+impl Effect for Binaural {
+    fn init(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate as f32;
+        // room for the largest delay plus interpolation
+        let max_delay = HEAD_RADIUS / SPEED_OF_SOUND * (std::f32::consts::FRAC_PI_2 + 1.0);
+        self.buffer = vec![0.0; (max_delay * self.sample_rate).ceil() as usize + 2];
+        self.azimuth_bits = None;
+    }
+
+    fn on_change_sample_rate(&mut self, sample_rate: u32) {
+        self.init(sample_rate);
+    }
+
+    fn process(
+        &mut self,
+        input: Frame,
+        _dt: f64,
+        _clock_info_provider: &ClockInfoProvider,
+        _modulator_value_provider: &ModulatorValueProvider,
+    ) -> Frame {
+        if !self.params.enabled.load(Ordering::Relaxed) || self.buffer.is_empty() {
+            return input;
+        }
+
+        let azimuth_bits = self.params.azimuth.load(Ordering::Relaxed);
+        if self.azimuth_bits != Some(azimuth_bits) {
+            self.update_targets(f32::from_bits(azimuth_bits));
+            if self.azimuth_bits.is_none() {
+                self.current = self.target;
+            }
+            self.azimuth_bits = Some(azimuth_bits);
+        }
+
+        self.write = (self.write + 1) % self.buffer.len();
+        self.buffer[self.write] = (input.left + input.right) * 0.5;
+
+        Frame {
+            left: self.process_ear(0),
+            right: self.process_ear(1),
+        }
+    }
+}
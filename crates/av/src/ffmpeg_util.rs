@@ -1,13 +1,46 @@
-use ffmpeg_next::{format::context::Input, Packet};
+use std::time::Duration;
+
+use bevy::log::{debug, warn};
+use ffmpeg_next::{format::context::Input, Dictionary, Packet};
+
+use crate::{
+    adaptive::{add_adaptive_options, is_adaptive, Variants},
+    video_context::{StreamMetadata, VideoData},
+};
 
 pub const BUFFER_TIME: f64 = 10.0;
 
+// reconnection attempts for a dropped live stream before giving up, and the delay limit between them
+const RECONNECT_ATTEMPTS: u32 = 8;
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+// open a file or url. http sources ask ffmpeg to reconnect transparently on short network errors,
+// and to send shoutcast metadata. hls and dash playlists get extra demuxer options
+pub fn open_input(path: &str) -> Result<Input, ffmpeg_next::Error> {
+    if !path.starts_with("http") {
+        return ffmpeg_next::format::input(&path);
+    }
+
+    let mut options = Dictionary::new();
+    options.set("reconnect", "1");
+    options.set("reconnect_streamed", "1");
+    options.set("reconnect_on_network_error", "1");
+    options.set("reconnect_delay_max", "5");
+    options.set("icy", "1");
+    if is_adaptive(path) {
+        add_adaptive_options(&mut options);
+    }
+    ffmpeg_next::format::input_with_dictionary(&path, options)
+}
+
 pub trait PacketIter {
     fn is_eof(&self) -> bool;
     fn try_next(&mut self) -> Option<(usize, Packet)>;
     fn blocking_next(&mut self) -> Option<(usize, Packet)>;
     fn reset(&mut self);
     fn seek_to(&mut self, time: f64);
+    // the source dropped and couldn't be reconnected
+    fn is_failed(&self) -> bool;
 }
 
 // input stream wrapper allows reloading
@@ -16,20 +49,110 @@ pub struct InputWrapper {
     pending_input: Option<tokio::sync::oneshot::Receiver<Input>>,
     path: String,
     is_eof: bool,
+    // live streams have no duration, and reaching the end means the connection dropped
+    is_live: bool,
+    is_failed: bool,
+    metadata: StreamMetadata,
+    events: tokio::sync::mpsc::Sender<VideoData>,
+    // hls/dash variant selection
+    variants: Option<Variants>,
+    // reads are happening while playing from the buffer, so a blocking read is a stall
+    playing: bool,
 }
 
 impl InputWrapper {
-    pub fn new(input: Input, path: String) -> Self {
-        Self {
+    pub fn new(
+        input: Input,
+        path: String,
+        events: tokio::sync::mpsc::Sender<VideoData>,
+        variants: Option<Variants>,
+    ) -> Self {
+        let is_live = input.duration() <= 0;
+        let mut wrapper = Self {
             input: Some(input),
             pending_input: None,
             path,
             is_eof: false,
+            is_live,
+            is_failed: false,
+            metadata: StreamMetadata::default(),
+            events,
+            variants,
+            playing: false,
+        };
+        wrapper.update_metadata();
+        wrapper
+    }
+
+    // icy metadata arrives between packets, ffmpeg copies it onto the input as it is read
+    fn update_metadata(&mut self) {
+        let Some(input) = self.input.as_ref() else {
+            return;
+        };
+        let metadata = input.metadata();
+        let get = |key: &str| metadata.get(key).map(str::trim).filter(|v| !v.is_empty());
+        let (station, title) = (get("icy-name"), get("StreamTitle"));
+        if station == self.metadata.station.as_deref() && title == self.metadata.title.as_deref() {
+            return;
         }
+
+        self.metadata = StreamMetadata {
+            station: station.map(ToOwned::to_owned),
+            title: title.map(ToOwned::to_owned),
+        };
+        debug!("stream metadata: {:?}", self.metadata);
+        let _ = self
+            .events
+            .try_send(VideoData::Metadata(self.metadata.clone()));
+    }
+
+    // reopen a dropped live stream, backing off between attempts
+    fn reconnect(&mut self) -> bool {
+        let mut delay = Duration::from_secs(1);
+        for attempt in 1..=RECONNECT_ATTEMPTS {
+            if self.events.is_closed() {
+                break;
+            }
+            warn!(
+                "stream {} dropped, reconnecting in {}s ({attempt}/{RECONNECT_ATTEMPTS})",
+                self.path,
+                delay.as_secs()
+            );
+            std::thread::sleep(delay);
+            delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+
+            if let Ok(input) = open_input(&self.path) {
+                self.set_input(input);
+                self.pending_input = None;
+                self.is_eof = false;
+                return true;
+            }
+        }
+
+        warn!("stream {} lost", self.path);
+        self.input = None;
+        self.is_eof = true;
+        self.is_failed = true;
+        false
     }
 }
 
 impl InputWrapper {
+    fn set_input(&mut self, mut input: Input) {
+        if let Some(variants) = self.variants.as_ref() {
+            variants.apply(&mut input);
+        }
+        self.input = Some(input);
+    }
+
+    // the stream index to report a packet as, none if it's from an inactive variant
+    fn packet_stream(&mut self, packet: &Packet) -> Option<usize> {
+        match (self.variants.as_mut(), self.input.as_mut()) {
+            (Some(variants), Some(input)) => variants.map_packet(input, packet),
+            _ => Some(packet.stream()),
+        }
+    }
+
     fn get_input(&mut self, blocking: bool) -> Option<&mut Input> {
         if self.input.is_some() {
             return Some(self.input.as_mut().unwrap());
@@ -39,7 +162,7 @@ impl InputWrapper {
             if let Some(pending_input) = self.pending_input.take() {
                 match pending_input.blocking_recv() {
                     Ok(input) => {
-                        self.input = Some(input);
+                        self.set_input(input);
                         return Some(self.input.as_mut().unwrap());
                     }
                     Err(_) => {
@@ -51,7 +174,7 @@ impl InputWrapper {
         } else if let Some(pending_input) = self.pending_input.as_mut() {
             match pending_input.try_recv() {
                 Ok(input) => {
-                    self.input = Some(input);
+                    self.set_input(input);
                     return Some(self.input.as_mut().unwrap());
                 }
                 Err(tokio::sync::oneshot::error::TryRecvError::Empty) => return None,
@@ -72,36 +195,80 @@ impl PacketIter for InputWrapper {
     }
 
     fn try_next(&mut self) -> Option<(usize, Packet)> {
+        self.playing = true;
         let input = self.get_input(false)?;
         let mut packet = Packet::empty();
 
         match packet.read(input) {
-            Ok(..) => Some((packet.stream(), packet)),
-            Err(ffmpeg_next::util::error::Error::Eof) => {
+            Ok(..) => {
+                self.update_metadata();
+                if let (Some(variants), Some(input)) = (self.variants.as_mut(), self.input.as_mut())
+                {
+                    variants.on_playing(input);
+                }
+                Some((self.packet_stream(&packet)?, packet))
+            }
+            Err(ffmpeg_next::util::error::Error::Eof) if !self.is_live => {
                 self.is_eof = true;
                 None
             }
+            // a live stream ending is picked up by the next blocking read, once the buffer drains
             _ => None,
         }
     }
 
     fn blocking_next(&mut self) -> Option<(usize, Packet)> {
-        let input = self.get_input(true)?;
+        if std::mem::take(&mut self.playing) {
+            if let (Some(variants), Some(input)) = (self.variants.as_mut(), self.input.as_mut()) {
+                variants.on_stall(input);
+            }
+        }
+
         let mut packet = Packet::empty();
+        let mut errors = 0;
 
         loop {
+            let input = self.get_input(true)?;
             match packet.read(input) {
-                Ok(..) => return Some((packet.stream(), packet)),
-                Err(ffmpeg_next::util::error::Error::Eof) => {
+                Ok(..) => {
+                    self.update_metadata();
+                    match self.packet_stream(&packet) {
+                        Some(stream) => return Some((stream, packet)),
+                        None => continue,
+                    }
+                }
+                Err(ffmpeg_next::util::error::Error::Eof) if !self.is_live => {
                     self.is_eof = true;
                     return None;
                 }
-                Err(..) => (),
+                Err(ffmpeg_next::util::error::Error::Other { errno })
+                    if errno == ffmpeg_next::util::error::EAGAIN =>
+                {
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => {
+                    // odd corrupt packets are skipped, a live stream ending or repeated errors
+                    // mean the connection is gone
+                    errors += 1;
+                    if errors < 10 && e != ffmpeg_next::util::error::Error::Eof {
+                        continue;
+                    }
+                    if !self.is_live {
+                        warn!("giving up on {} after read errors: {e}", self.path);
+                        self.is_eof = true;
+                        return None;
+                    }
+                    if !self.reconnect() {
+                        return None;
+                    }
+                    errors = 0;
+                }
             }
         }
     }
 
     fn reset(&mut self) {
+        self.playing = false;
         let Some(input) = self.get_input(false) else {
             return;
         };
@@ -111,7 +278,7 @@ impl PacketIter for InputWrapper {
             let (sx, rx) = tokio::sync::oneshot::channel();
             let path = self.path.clone();
             std::thread::spawn(move || {
-                if let Ok(input) = ffmpeg_next::format::input(&path) {
+                if let Ok(input) = open_input(&path) {
                     let _ = sx.send(input);
                 }
             });
@@ -123,6 +290,7 @@ impl PacketIter for InputWrapper {
     }
 
     fn seek_to(&mut self, time: f64) {
+        self.playing = false;
         let Some(input) = self.get_input(false) else {
             return;
         };
@@ -132,7 +300,7 @@ impl PacketIter for InputWrapper {
             let (sx, rx) = tokio::sync::oneshot::channel();
             let path = self.path.clone();
             std::thread::spawn(move || {
-                if let Ok(mut input) = ffmpeg_next::format::input(&path) {
+                if let Ok(mut input) = open_input(&path) {
                     let _ = input.seek((time * 1000000.0) as i64, ..);
                     let _ = sx.send(input);
                 }
@@ -143,4 +311,8 @@ impl PacketIter for InputWrapper {
 
         self.is_eof = false;
     }
+
+    fn is_failed(&self) -> bool {
+        self.is_failed
+    }
 }
@@ -0,0 +1,162 @@
+// hardware video decoding. decoders use the platform's decode api (dxva/d3d11, videotoolbox or
+// vaapi) through one shared ffmpeg device, and decoded frames are copied back to memory for the
+// scaler. when there's no device, the codec isn't supported by it, or decoding fails, the video
+// context falls back to a software decoder.
+
+use std::{
+    ffi::{c_void, CStr},
+    sync::OnceLock,
+};
+
+use bevy::log::{debug, info, warn};
+use ffmpeg_next::{
+    codec, decoder,
+    ffi::{self, AVHWDeviceType, AVPixelFormat},
+    frame,
+};
+
+// device types to try, in order
+#[cfg(target_os = "windows")]
+const DEVICE_TYPES: &[AVHWDeviceType] = &[
+    AVHWDeviceType::AV_HWDEVICE_TYPE_D3D11VA,
+    AVHWDeviceType::AV_HWDEVICE_TYPE_DXVA2,
+];
+#[cfg(target_os = "macos")]
+const DEVICE_TYPES: &[AVHWDeviceType] = &[AVHWDeviceType::AV_HWDEVICE_TYPE_VIDEOTOOLBOX];
+#[cfg(target_os = "linux")]
+const DEVICE_TYPES: &[AVHWDeviceType] = &[
+    AVHWDeviceType::AV_HWDEVICE_TYPE_VAAPI,
+    AVHWDeviceType::AV_HWDEVICE_TYPE_CUDA,
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+const DEVICE_TYPES: &[AVHWDeviceType] = &[];
+
+struct HwDevice {
+    kind: AVHWDeviceType,
+    context: *mut ffi::AVBufferRef,
+}
+
+// the device context is reference counted, and each decoder takes its own reference
+unsafe impl Send for HwDevice {}
+unsafe impl Sync for HwDevice {}
+
+// created on first use and kept for the life of the process
+fn device() -> Option<&'static HwDevice> {
+    static DEVICE: OnceLock<Option<HwDevice>> = OnceLock::new();
+    DEVICE
+        .get_or_init(|| {
+            let device = DEVICE_TYPES.iter().find_map(|kind| unsafe {
+                let mut context = std::ptr::null_mut();
+                if ffi::av_hwdevice_ctx_create(
+                    &mut context,
+                    *kind,
+                    std::ptr::null(),
+                    std::ptr::null_mut(),
+                    0,
+                ) < 0
+                {
+                    debug!("no {} device", type_name(*kind));
+                    return None;
+                }
+                Some(HwDevice {
+                    kind: *kind,
+                    context,
+                })
+            });
+            match device.as_ref() {
+                Some(device) => info!("hardware video decoding with {}", type_name(device.kind)),
+                None => info!("no hardware video decoder, using software"),
+            }
+            device
+        })
+        .as_ref()
+}
+
+fn type_name(kind: AVHWDeviceType) -> &'static str {
+    unsafe {
+        let name = ffi::av_hwdevice_get_type_name(kind);
+        if name.is_null() {
+            return "hardware";
+        }
+        CStr::from_ptr(name).to_str().unwrap_or("hardware")
+    }
+}
+
+// the pixel format the codec decodes to on the device, if the device supports the codec
+fn hw_format(codec: &codec::Codec, kind: AVHWDeviceType) -> Option<AVPixelFormat> {
+    (0..)
+        .map_while(|ix| unsafe { ffi::avcodec_get_hw_config(codec.as_ptr(), ix).as_ref() })
+        .find(|config| {
+            config.methods & ffi::AV_CODEC_HW_CONFIG_METHOD_HW_DEVICE_CTX as i32 != 0
+                && config.device_type == kind
+        })
+        .map(|config| config.pix_fmt)
+}
+
+// picks the device format from the decoder's options. the format is stashed in `opaque`
+unsafe extern "C" fn get_hw_format(
+    context: *mut ffi::AVCodecContext,
+    formats: *const AVPixelFormat,
+) -> AVPixelFormat {
+    let wanted = (*context).opaque as usize as i32;
+    let mut format = formats;
+    while *format != AVPixelFormat::AV_PIX_FMT_NONE {
+        if *format as i32 == wanted {
+            return *format;
+        }
+        format = format.add(1);
+    }
+    // the device can't decode this stream, so decoding fails and we fall back to software
+    AVPixelFormat::AV_PIX_FMT_NONE
+}
+
+pub struct HwDecoder {
+    pub decoder: decoder::Video,
+    // format of frames that need copying back from the device
+    pub format: AVPixelFormat,
+    pub device: &'static str,
+}
+
+pub fn open_hw_decoder(parameters: codec::Parameters) -> Option<HwDecoder> {
+    let device = device()?;
+    let codec = decoder::find(parameters.id())?;
+    let format = hw_format(&codec, device.kind)?;
+    let mut context = codec::context::Context::from_parameters(parameters).ok()?;
+    unsafe {
+        let raw = context.as_mut_ptr();
+        // released with the codec context
+        (*raw).hw_device_ctx = ffi::av_buffer_ref(device.context);
+        (*raw).opaque = format as i32 as usize as *mut c_void;
+        (*raw).get_format = Some(get_hw_format);
+    }
+
+    match context
+        .decoder()
+        .open_as(codec)
+        .and_then(|opened| opened.video())
+    {
+        Ok(decoder) => Some(HwDecoder {
+            decoder,
+            format,
+            device: type_name(device.kind),
+        }),
+        Err(e) => {
+            warn!("failed to open {} decoder: {e}", type_name(device.kind));
+            None
+        }
+    }
+}
+
+// copy a decoded frame from the device into memory
+pub fn transfer_frame(frame: &frame::Video) -> Result<frame::Video, ffmpeg_next::Error> {
+    let mut output = frame::Video::empty();
+    unsafe {
+        let res = ffi::av_hwframe_transfer_data(output.as_mut_ptr(), frame.as_ptr(), 0);
+        if res < 0 {
+            return Err(ffmpeg_next::Error::from(res));
+        }
+        // keep the timestamps
+        ffi::av_frame_copy_props(output.as_mut_ptr(), frame.as_ptr());
+    }
+    Ok(output)
+}
@@ -1,11 +1,24 @@
 #[cfg(feature = "ffmpeg")]
+pub mod adaptive;
+#[cfg(feature = "ffmpeg")]
 pub mod audio_context;
+pub mod audio_device;
+pub mod audio_ducking;
+pub mod audio_limit;
+pub mod audio_occlusion;
 #[cfg(feature = "ffmpeg")]
 pub mod audio_sink;
 pub mod audio_source;
+pub mod binaural;
 #[cfg(feature = "ffmpeg")]
 pub mod ffmpeg_util;
+#[cfg(feature = "ffmpeg")]
+pub mod hw_decode;
+#[cfg(feature = "ffmpeg")]
+pub mod livekit_video;
 pub mod microphone;
+pub mod reverb_zone;
+pub mod stereo_width;
 #[cfg(feature = "ffmpeg")]
 pub mod stream_processor;
 #[cfg(test)]
@@ -13,15 +26,22 @@ pub mod test;
 #[cfg(feature = "ffmpeg")]
 pub mod video_context;
 #[cfg(feature = "ffmpeg")]
+pub mod video_placeholder;
+#[cfg(feature = "ffmpeg")]
 pub mod video_player;
 #[cfg(feature = "ffmpeg")]
 pub mod video_stream;
+#[cfg(feature = "ffmpeg")]
+pub mod video_sync;
 
+use audio_device::AudioDevicePlugin;
+use audio_ducking::AudioDuckingPlugin;
 #[cfg(feature = "ffmpeg")]
 use audio_sink::{spawn_and_locate_foreign_streams, spawn_audio_streams};
 use audio_source::AudioSourcePlugin;
 use bevy::prelude::*;
 use microphone::MicPlugin;
+use reverb_zone::ReverbZonePlugin;
 #[cfg(feature = "ffmpeg")]
 use video_player::VideoPlayerPlugin;
 
@@ -34,6 +54,9 @@ impl Plugin for AudioPlugin {
         app.add_plugins(VideoPlayerPlugin);
         app.add_plugins(MicPlugin);
         app.add_plugins(AudioSourcePlugin);
+        app.add_plugins(ReverbZonePlugin);
+        app.add_plugins(AudioDuckingPlugin);
+        app.add_plugins(AudioDevicePlugin);
         #[cfg(feature = "ffmpeg")]
         app.add_systems(
             PostUpdate,
@@ -0,0 +1,217 @@
+// video shared by other players over livekit (screen shares and cameras), shown on scene video
+// players with a `livekit-video://` source. `livekit-video://current-stream` shows the most
+// recently started share, and `livekit-video://<address>` a specific player's. scenes need the
+// show streams permission, since the content comes from other players rather than the scene.
+
+use bevy::{
+    prelude::*,
+    render::render_resource::Extent3d,
+    utils::{HashMap, HashSet},
+};
+use common::structs::PermissionType;
+use comms::global_crdt::{ForeignPlayer, ForeignVideoFrame, ForeignVideoSource};
+use dcl_component::proto_components::sdk::components::VideoState;
+use scene_runner::{permissions::Permission, ContainerEntity};
+use tokio::sync::mpsc::error::TryRecvError;
+
+use crate::{
+    stream_processor::AVCommand,
+    video_context::{StreamMetadata, VideoData},
+    video_player::AVPlayer,
+    video_stream::VideoSink,
+};
+
+pub const LIVEKIT_VIDEO_SCHEME: &str = "livekit-video://";
+const CURRENT_STREAM: &str = "current-stream";
+
+pub fn is_livekit_video(source: &str) -> bool {
+    source.starts_with(LIVEKIT_VIDEO_SCHEME)
+}
+
+// feeds state changes to the sink, which reports them to the scene
+#[derive(Component)]
+pub struct LivekitVideoFeed {
+    sender: tokio::sync::mpsc::Sender<VideoData>,
+    state: VideoState,
+}
+
+impl LivekitVideoFeed {
+    fn set_state(&mut self, state: VideoState) {
+        if state != self.state {
+            self.state = state;
+            let _ = self.sender.try_send(VideoData::State(state));
+        }
+    }
+}
+
+pub fn livekit_video_sink(source: String, image: Handle<Image>) -> (VideoSink, LivekitVideoFeed) {
+    // nothing reads the commands, the feed follows the player's settings directly
+    let (command_sender, _) = tokio::sync::mpsc::channel::<AVCommand>(1);
+    let (video_sender, video_receiver) = tokio::sync::mpsc::channel(10);
+
+    (
+        VideoSink {
+            source,
+            command_sender,
+            video_receiver,
+            image,
+            current_time: -1.0,
+            last_reported_time: -1.0,
+            length: None,
+            rate: None,
+            metadata: StreamMetadata::default(),
+            decode_path: Some("livekit".to_owned()),
+            state: VideoState::VsNone,
+            state_since: 0.0,
+            rate_nudge: 1.0,
+        },
+        LivekitVideoFeed {
+            sender: video_sender,
+            state: VideoState::VsNone,
+        },
+    )
+}
+
+struct SharedVideo {
+    address: String,
+    frame: Option<ForeignVideoFrame>,
+    // bumped for each new frame
+    serial: usize,
+    started: f32,
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn feed_livekit_videos(
+    mut images: ResMut<Assets<Image>>,
+    mut sources: Query<(Entity, &ForeignPlayer, &mut ForeignVideoSource)>,
+    mut sinks: Query<(
+        Entity,
+        &AVPlayer,
+        &ContainerEntity,
+        &mut VideoSink,
+        &mut LivekitVideoFeed,
+    )>,
+    mut perms: Permission<Entity>,
+    time: Res<Time>,
+    mut shared: Local<HashMap<Entity, SharedVideo>>,
+    // sink -> (permission granted, shared video and serial last shown)
+    mut shown: Local<HashMap<Entity, (Option<bool>, Option<(Entity, usize)>)>>,
+) {
+    let now = time.elapsed_seconds();
+
+    // keep the latest frame of each share, so any number of players can show it
+    let mut live = HashSet::new();
+    for (ent, player, mut source) in sources.iter_mut() {
+        let video = shared.entry(ent).or_insert_with(|| SharedVideo {
+            address: format!("{:#x}", player.address),
+            frame: None,
+            serial: 0,
+            started: now,
+        });
+        loop {
+            match source.0.try_recv() {
+                Ok(frame) => {
+                    video.frame = Some(frame);
+                    video.serial += 1;
+                }
+                Err(TryRecvError::Empty) => {
+                    live.insert(ent);
+                    break;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    debug!("video share from {} ended", video.address);
+                    break;
+                }
+            }
+        }
+    }
+    shared.retain(|ent, _| live.contains(ent));
+
+    // request permission for new sinks
+    shown.retain(|ent, _| sinks.contains(*ent));
+    for (ent, _, container, sink, _) in sinks.iter() {
+        if is_livekit_video(&sink.source) && !shown.contains_key(&ent) {
+            perms.check(PermissionType::ShowStreams, container.root, ent, None, true);
+            shown.insert(ent, (None, None));
+        }
+    }
+    for ent in perms.drain_success(PermissionType::ShowStreams) {
+        if let Some((granted, _)) = shown.get_mut(&ent) {
+            *granted = Some(true);
+        }
+    }
+    for ent in perms.drain_fail(PermissionType::ShowStreams) {
+        if let Some((granted, _)) = shown.get_mut(&ent) {
+            *granted = Some(false);
+        }
+    }
+
+    for (ent, player, _, mut sink, mut feed) in sinks.iter_mut() {
+        let Some((granted, last_shown)) = shown.get_mut(&ent) else {
+            continue;
+        };
+
+        match granted {
+            None => {
+                feed.set_state(VideoState::VsLoading);
+                continue;
+            }
+            Some(false) => {
+                if feed.state != VideoState::VsError {
+                    feed.state = VideoState::VsError;
+                    let _ = feed
+                        .sender
+                        .try_send(VideoData::Error("permission denied".to_owned()));
+                }
+                continue;
+            }
+            Some(true) => (),
+        }
+
+        let target = sink
+            .source
+            .strip_prefix(LIVEKIT_VIDEO_SCHEME)
+            .unwrap_or_default()
+            .to_lowercase();
+        let video = if target == CURRENT_STREAM {
+            shared
+                .iter()
+                .max_by(|(_, a), (_, b)| a.started.total_cmp(&b.started))
+        } else {
+            shared.iter().find(|(_, video)| video.address == target)
+        };
+
+        let Some((video_ent, video)) = video else {
+            // nobody is sharing (yet), or the share ended
+            feed.set_state(match last_shown {
+                Some(_) => VideoState::VsBuffering,
+                None => VideoState::VsLoading,
+            });
+            continue;
+        };
+
+        feed.set_state(VideoState::VsPlaying);
+        sink.current_time = (now - video.started) as f64;
+
+        if !player.source.playing.unwrap_or(true) || *last_shown == Some((*video_ent, video.serial))
+        {
+            continue;
+        }
+        let Some(frame) = video.frame.as_ref().filter(|f| f.width > 0 && f.height > 0) else {
+            continue;
+        };
+        *last_shown = Some((*video_ent, video.serial));
+
+        let Some(image) = images.get_mut(&sink.image) else {
+            continue;
+        };
+        if image.width() != frame.width || image.height() != frame.height {
+            image.resize(Extent3d {
+                width: frame.width,
+                height: frame.height,
+                depth_or_array_layers: 1,
+            });
+        }
+        image.data.copy_from_slice(&frame.data);
+    }
+}
@@ -1,6 +1,14 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
 use bevy::prelude::*;
+use common::structs::AudioSettings;
 use comms::global_crdt::{LocalAudioFrame, LocalAudioSource};
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::traits::{DeviceTrait, StreamTrait};
+
+use crate::audio_device::find_input_device;
 
 pub struct MicPlugin;
 
@@ -8,8 +16,15 @@ pub struct MicPlugin;
 pub struct MicState {
     pub available: bool,
     pub enabled: bool,
+    // the mic is on and picking up more than background noise
+    pub speaking: bool,
 }
 
+// peak sample level counted as speech
+const SPEAKING_LEVEL: f32 = 0.05;
+// seconds between checks for plugged or unplugged devices
+const DEVICE_CHECK_INTERVAL: f32 = 1.0;
+
 impl Plugin for MicPlugin {
     fn build(&self, app: &mut App) {
         app.init_non_send_resource::<MicStream>();
@@ -19,17 +34,35 @@ impl Plugin for MicPlugin {
 }
 
 #[derive(Default)]
-pub struct MicStream(Option<cpal::Stream>);
+pub struct MicStream {
+    stream: Option<cpal::Stream>,
+    // peak level of the latest captured frame, as f32 bits
+    level: Arc<AtomicU32>,
+}
 
 pub fn update_mic(
     mic: Res<LocalAudioSource>,
     mut last_name: Local<String>,
     mut stream: NonSendMut<MicStream>,
     mut mic_state: ResMut<MicState>,
+    settings: Res<AudioSettings>,
+    time: Res<Time>,
+    mut last_check: Local<f32>,
 ) {
-    let default_host = cpal::default_host();
-    let default_input = default_host.default_input_device();
-    if let Some(input) = default_input {
+    // enumerating devices is slow, so only look for plugged or unplugged devices occasionally
+    let check_devices = mic_state.is_changed()
+        || settings.is_changed()
+        || time.elapsed_seconds() - *last_check >= DEVICE_CHECK_INTERVAL;
+
+    let level = f32::from_bits(stream.level.load(Ordering::Relaxed));
+    mic_state.speaking = stream.stream.is_some() && mic_state.enabled && level > SPEAKING_LEVEL;
+
+    if !check_devices {
+        return;
+    }
+    *last_check = time.elapsed_seconds();
+
+    if let Some(input) = find_input_device(settings.input_device.as_deref()) {
         if let Ok(name) = input.name() {
             mic_state.available = true;
 
@@ -38,7 +71,7 @@ pub fn update_mic(
             }
 
             // drop old stream
-            stream.0 = None;
+            stream.stream = None;
 
             if !mic_state.enabled {
                 "disabled".clone_into(&mut last_name);
@@ -47,12 +80,18 @@ pub fn update_mic(
 
             let config = input.default_input_config().unwrap();
             let sender = mic.sender.clone();
+            let level = stream.level.clone();
+            level.store(0f32.to_bits(), Ordering::Relaxed);
             let num_channels = config.channels() as u32;
             let sample_rate = config.sample_rate().0;
             let new_stream = input
                 .build_input_stream(
                     &config.into(),
                     move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                        let peak = data
+                            .iter()
+                            .fold(0f32, |peak, sample| peak.max(sample.abs()));
+                        level.store(peak.to_bits(), Ordering::Relaxed);
                         if sender
                             .send(LocalAudioFrame {
                                 data: data.to_owned(),
@@ -73,7 +112,7 @@ pub fn update_mic(
                 .unwrap();
             match new_stream.play() {
                 Ok(()) => {
-                    stream.0 = Some(new_stream);
+                    stream.stream = Some(new_stream);
                     info!("set mic to {name}");
                     *last_name = name;
                 }
@@ -87,7 +126,7 @@ pub fn update_mic(
     }
 
     // faild to find input - drop old stream
-    stream.0 = None;
+    stream.stream = None;
     "no device".clone_into(&mut last_name);
     mic_state.available = false;
 }
@@ -0,0 +1,154 @@
+// scene-defined reverb. streamed scene audio and voice chat are played through a kira track with a
+// reverb effect, which is dry until the listener enters a `PbReverbZone`.
+// TODO: clips from `PbAudioSource` are played by bevy_kira_audio, which can't route sounds to a track
+
+use bevy::prelude::*;
+use bevy_kira_audio::audio_output::AudioOutput;
+use common::{structs::PrimaryUser, util::AudioReceiver};
+use dcl::interface::ComponentPosition;
+use dcl_component::{proto_components::sdk::components::PbReverbZone, SceneComponentId};
+use kira::{
+    effect::reverb::{ReverbBuilder, ReverbHandle},
+    manager::backend::DefaultBackend,
+    track::{TrackBuilder, TrackHandle},
+    tween::Tween,
+};
+use scene_runner::{update_world::AddCrdtInterfaceExt, ContainingScene, SceneEntity};
+
+// seconds to fade between zones
+const REVERB_FADE: f32 = 0.5;
+
+pub struct ReverbZonePlugin;
+
+impl Plugin for ReverbZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_crdt_lww_component::<PbReverbZone, ReverbZone>(
+            SceneComponentId::REVERB_ZONE,
+            ComponentPosition::EntityOnly,
+        );
+        app.init_resource::<ActiveReverb>();
+        app.add_systems(
+            PostUpdate,
+            (update_active_reverb, apply_reverb)
+                .chain()
+                .after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct ReverbZone(pub PbReverbZone);
+
+impl From<PbReverbZone> for ReverbZone {
+    fn from(value: PbReverbZone) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReverbParams {
+    pub room_size: f32,
+    pub damping: f32,
+    pub mix: f32,
+}
+
+impl From<&PbReverbZone> for ReverbParams {
+    fn from(value: &PbReverbZone) -> Self {
+        Self {
+            room_size: value.room_size.unwrap_or(0.5).clamp(0.0, 1.0),
+            damping: value.damping.unwrap_or(0.5).clamp(0.0, 1.0),
+            mix: value.mix.unwrap_or(0.3).clamp(0.0, 1.0),
+        }
+    }
+}
+
+// the reverb for the zone the listener is in, if any
+#[derive(Resource, Default, PartialEq, Debug)]
+pub struct ActiveReverb(pub Option<ReverbParams>);
+
+// the track streams are played through. a non-send resource, as the kira handles aren't sync
+pub struct ReverbTrack {
+    pub track: TrackHandle,
+    reverb: ReverbHandle,
+}
+
+fn update_active_reverb(
+    zones: Query<(&SceneEntity, &ReverbZone, &GlobalTransform)>,
+    receiver: Query<&GlobalTransform, With<AudioReceiver>>,
+    player: Query<Entity, With<PrimaryUser>>,
+    containing_scene: ContainingScene,
+    mut active: ResMut<ActiveReverb>,
+) {
+    let (Ok(receiver), Ok(player)) = (receiver.get_single(), player.get_single()) else {
+        return;
+    };
+    let scenes = containing_scene.get(player);
+    let listener = receiver.translation();
+
+    // the smallest zone containing the listener
+    let zone = zones
+        .iter()
+        .filter(|(scene_ent, ..)| scenes.contains(&scene_ent.root))
+        .filter_map(|(_, zone, transform)| {
+            let (_, rotation, translation) = transform.to_scale_rotation_translation();
+            let relative = rotation.inverse() * (listener - translation);
+            let extent = zone.0.area.unwrap_or_default().abs_vec_to_vec3() * 0.5;
+            (relative.clamp(-extent, extent) == relative)
+                .then_some((extent.x * extent.y * extent.z, zone))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, zone)| ReverbParams::from(&zone.0));
+
+    active.set_if_neq(ActiveReverb(zone));
+}
+
+fn apply_reverb(
+    mut commands: Commands,
+    mut audio_output: NonSendMut<AudioOutput<DefaultBackend>>,
+    track: Option<NonSendMut<ReverbTrack>>,
+    active: Res<ActiveReverb>,
+    mut creating: Local<bool>,
+) {
+    let Some(mut track) = track else {
+        if *creating {
+            return;
+        }
+        let Some(manager) = audio_output.manager.as_mut() else {
+            return;
+        };
+
+        let mut builder = TrackBuilder::new();
+        let reverb = builder.add_effect(ReverbBuilder::new().mix(0.0));
+        match manager.add_sub_track(builder) {
+            Ok(track) => {
+                *creating = true;
+                commands.add(move |world: &mut World| {
+                    world.insert_non_send_resource(ReverbTrack { track, reverb });
+                });
+            }
+            Err(e) => warn!("failed to create reverb track: {e}"),
+        }
+        return;
+    };
+    // recreated if removed, when the output device changes
+    *creating = false;
+
+    if !active.is_changed() && !track.is_added() {
+        return;
+    }
+
+    let tween = Tween {
+        duration: std::time::Duration::from_secs_f32(REVERB_FADE),
+        ..Default::default()
+    };
+    let params = active.0.unwrap_or(ReverbParams {
+        room_size: 0.5,
+        damping: 0.5,
+        mix: 0.0,
+    });
+    let _ = track
+        .reverb
+        .set_feedback(params.room_size as f64 * 0.95, tween);
+    let _ = track.reverb.set_damping(params.damping as f64, tween);
+    let _ = track.reverb.set_mix(params.mix as f64, tween);
+}
@@ -0,0 +1,72 @@
+// narrows a stereo sound towards mono. a distant source is effectively a point, so its stereo
+// image collapses as it moves away and it's panned as a whole instead.
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use kira::{
+    clock::clock_info::ClockInfoProvider,
+    dsp::Frame,
+    modulator::value_provider::ModulatorValueProvider,
+    track::effect::{Effect, EffectBuilder},
+};
+
+// fraction of the remaining difference closed per sample, so moving sources don't click
+const SMOOTHING: f32 = 0.001;
+
+pub struct StereoWidthBuilder;
+
+#[derive(Clone)]
+pub struct StereoWidthHandle {
+    // 0 for mono to 1 for unchanged, as f32 bits
+    width: Arc<AtomicU32>,
+}
+
+impl StereoWidthHandle {
+    pub fn set_width(&self, width: f32) {
+        self.width
+            .store(width.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl EffectBuilder for StereoWidthBuilder {
+    type Handle = StereoWidthHandle;
+
+    fn build(self) -> (Box<dyn Effect>, Self::Handle) {
+        let width = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+        (
+            Box::new(StereoWidth {
+                target: width.clone(),
+                current: 1.0,
+            }),
+            StereoWidthHandle { width },
+        )
+    }
+}
+
+struct StereoWidth {
+    target: Arc<AtomicU32>,
+    current: f32,
+}
+
+impl Effect for StereoWidth {
+    fn process(
+        &mut self,
+        input: Frame,
+        _dt: f64,
+        _clock_info_provider: &ClockInfoProvider,
+        _modulator_value_provider: &ModulatorValueProvider,
+    ) -> Frame {
+        let target = f32::from_bits(self.target.load(Ordering::Relaxed));
+        self.current += (target - self.current) * SMOOTHING;
+
+        let mid = (input.left + input.right) * 0.5;
+        let side = (input.left - input.right) * 0.5 * self.current;
+        Frame {
+            left: mid + side,
+            right: mid - side,
+        }
+    }
+}
@@ -12,7 +12,10 @@ pub enum AVCommand {
     Play,
     Pause,
     Repeat(bool),
+    // loop start and optional end, in seconds
+    LoopRange(f64, Option<f64>),
     Seek(f64),
+    Rate(f64),
     Dispose,
 }
 
@@ -26,8 +29,18 @@ pub trait FfmpegContext {
     fn set_start_frame(&mut self);
     fn reset_start_frame(&mut self);
     fn seconds_till_next_frame(&self) -> f64;
+    // media time of the last frame sent
+    fn current_time(&self) -> f64;
     fn update_state(&self, state: VideoState);
-    fn clear(&mut self);
+    // empty the buffers before a seek to `time`
+    fn clear(&mut self, time: f64);
+}
+
+fn seek(input_context: &mut impl PacketIter, streams: &mut [&mut dyn FfmpegContext], time: f64) {
+    for stream in streams.iter_mut() {
+        stream.clear(time);
+    }
+    input_context.seek_to(time);
 }
 
 // pumps packets through stream contexts keeping them in sync
@@ -38,6 +51,11 @@ pub fn process_streams(
 ) -> Result<(), anyhow::Error> {
     let mut start_instant: Option<Instant> = None;
     let mut repeat = false;
+    let mut loop_start = 0.0;
+    let mut loop_end = None;
+    let mut rate = 1.0;
+    // restart the clock once the buffers are refilled
+    let mut resync = false;
     let mut init = false;
     let mut last_state = VideoState::VsNone;
 
@@ -72,6 +90,20 @@ pub fn process_streams(
             }
         }
 
+        if input_context.is_failed() {
+            bail!("stream lost");
+        }
+
+        if resync {
+            resync = false;
+            if start_instant.is_some() {
+                start_instant = Some(Instant::now());
+                for stream in streams.iter_mut() {
+                    stream.set_start_frame();
+                }
+            }
+        }
+
         // state ready if required
         if !init {
             update_state(VideoState::VsReady, streams);
@@ -80,13 +112,19 @@ pub fn process_streams(
 
         if input_context.is_eof() {
             // eof
-            if repeat {
+            if repeat && loop_start == 0.0 {
                 input_context.reset();
                 for stream in streams.iter_mut() {
                     stream.reset_start_frame();
                 }
                 continue;
             } else if streams.iter().all(|ctx| ctx.buffered_time() == 0.0) {
+                if repeat {
+                    // seeking drops the buffers, so wait for them to play out
+                    seek(&mut input_context, streams, loop_start);
+                    resync = true;
+                    continue;
+                }
                 info!("eof");
                 for stream in streams.iter() {
                     info!("stream: {}", stream.buffered_time());
@@ -114,14 +152,26 @@ pub fn process_streams(
                 start_instant = None;
             }
             Ok(AVCommand::Repeat(r)) => repeat = r,
+            Ok(AVCommand::LoopRange(start, end)) => {
+                loop_start = start;
+                loop_end = end;
+            }
             Ok(AVCommand::Seek(time)) => {
-                for stream in streams.iter_mut() {
-                    stream.clear();
-                }
-                input_context.seek_to(time);
+                seek(&mut input_context, streams, time);
                 update_state(VideoState::VsSeeking, streams);
+                resync = true;
                 continue;
             }
+            Ok(AVCommand::Rate(r)) => {
+                rate = r;
+                // frame times are scaled from the start instant, so restart it from here
+                if start_instant.is_some() {
+                    start_instant = Some(Instant::now());
+                    for stream in streams.iter_mut() {
+                        stream.set_start_frame();
+                    }
+                }
+            }
             Err(TryRecvError::Empty) => (),
             Err(TryRecvError::Disconnected) | Ok(AVCommand::Dispose) => return Ok(()),
         }
@@ -145,7 +195,7 @@ pub fn process_streams(
                 },
             );
             let now = Instant::now();
-            let next_frame_time = play_instant + Duration::from_secs_f64(next_frame_time);
+            let next_frame_time = play_instant + Duration::from_secs_f64(next_frame_time / rate);
 
             if tick % 25 == 0 {
                 debug!(
@@ -186,6 +236,17 @@ pub fn process_streams(
                 let context = streams.get_mut(index).unwrap();
                 context.send_frame();
             }
+
+            if let Some(end) = loop_end.filter(|_| repeat) {
+                let time = streams
+                    .iter()
+                    .map(|ctx| ctx.current_time())
+                    .fold(f64::MAX, f64::min);
+                if time >= end {
+                    seek(&mut input_context, streams, loop_start);
+                    resync = true;
+                }
+            }
         }
     }
 }
@@ -5,10 +5,14 @@ use dcl_component::proto_components::sdk::components::VideoState;
 use ffmpeg_next::ffi::AVPixelFormat;
 use ffmpeg_next::format::Pixel;
 use ffmpeg_next::software::scaling::{context::Context, flag::Flags};
-use ffmpeg_next::{decoder, format::context::Input, media::Type, util::frame, Packet};
+use ffmpeg_next::{codec, decoder, format::context::Input, media::Type, util::frame, Packet};
 use thiserror::Error;
 
-use crate::stream_processor::FfmpegContext;
+use crate::{
+    adaptive::best_stream,
+    hw_decode::{open_hw_decoder, transfer_frame},
+    stream_processor::FfmpegContext,
+};
 
 pub struct VideoInfo {
     pub width: u32,
@@ -17,15 +21,31 @@ pub struct VideoInfo {
     pub length: f64,
 }
 
+// shoutcast/icecast now playing info
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct StreamMetadata {
+    pub station: Option<String>,
+    pub title: Option<String>,
+}
+
 pub enum VideoData {
     Info(VideoInfo),
     Frame(frame::Video, f64),
     State(VideoState),
+    Metadata(StreamMetadata),
+    // the codec and whether it's decoded in hardware or software
+    DecodePath(String),
+    // playback failed, with the reason
+    Error(String),
 }
 
 pub struct VideoContext {
     stream_index: usize,
     decoder: decoder::Video,
+    // kept to reopen in software if the hardware decoder fails
+    parameters: codec::Parameters,
+    // format of hardware frames, while decoding in hardware
+    hw_format: Option<AVPixelFormat>,
     scaler_context: Context,
     rate: f64,
     buffer: VecDeque<(usize, frame::video::Video)>,
@@ -33,6 +53,11 @@ pub struct VideoContext {
     current_frame: usize,
     start_frame: usize,
     next_store_frame: usize,
+    // seconds per timestamp unit
+    time_base: f64,
+    last_sent_time: f64,
+    // after a seek, frames before this time are decoded but not shown
+    skip_until: Option<f64>,
 }
 
 #[derive(Debug, Error)]
@@ -52,10 +77,7 @@ impl VideoContext {
         input_context: &Input,
         sink: tokio::sync::mpsc::Sender<VideoData>,
     ) -> Result<Self, VideoError> {
-        let input_stream = input_context
-            .streams()
-            .best(Type::Video)
-            .ok_or(VideoError::NoStream)?;
+        let input_stream = best_stream(input_context, Type::Video).ok_or(VideoError::NoStream)?;
 
         let pixel_format: AVPixelFormat =
             unsafe { std::mem::transmute((*input_stream.parameters().as_ptr()).format) };
@@ -66,14 +88,19 @@ impl VideoContext {
 
         let stream_index = input_stream.index();
 
-        let context_decoder =
-            ffmpeg_next::codec::context::Context::from_parameters(input_stream.parameters())
-                .map_err(VideoError::Failed)?;
-
-        let decoder = context_decoder
-            .decoder()
-            .video()
-            .map_err(VideoError::Failed)?;
+        // an owned copy, the stream's parameters keep the whole input alive
+        let parameters = input_stream.parameters().clone();
+        let (decoder, hw_format, decode_path) = match open_hw_decoder(parameters.clone()) {
+            Some(hw) => {
+                let path = format!("{} ({})", parameters.id().name(), hw.device);
+                (hw.decoder, Some(hw.format), path)
+            }
+            None => {
+                let decoder = open_decoder(parameters.clone()).map_err(VideoError::Failed)?;
+                let path = software_path(&decoder);
+                (decoder, None, path)
+            }
+        };
 
         let roundup = |x: u32| {
             (x.saturating_sub(1) / 8 + 1) * 8
@@ -95,6 +122,7 @@ impl VideoContext {
         .map_err(VideoError::Failed)?;
 
         let rate = f64::from(input_stream.rate());
+        let time_base = f64::from(input_stream.time_base());
         let length = (input_stream.frames() as f64) / rate;
         debug!(
             "frames: {}, length: {}, rate: {}",
@@ -115,21 +143,84 @@ impl VideoContext {
             // channel closed
             return Err(VideoError::ChannelClosed);
         }
+        let _ = sink.blocking_send(VideoData::DecodePath(decode_path));
 
         Ok(VideoContext {
             stream_index,
             rate,
             decoder,
+            parameters,
+            hw_format,
             scaler_context,
             buffer: Default::default(),
             sink,
             current_frame: 0,
             start_frame: 0,
             next_store_frame: 0,
+            time_base,
+            last_sent_time: 0.0,
+            skip_until: None,
         })
     }
 }
 
+// decoders to try before ffmpeg's default for the codec. the native av1 decoder only works with
+// hardware acceleration, so av1 needs one of the software decoders
+fn preferred_decoders(id: codec::Id) -> &'static [&'static str] {
+    match id {
+        codec::Id::AV1 => &["libdav1d", "libaom-av1"],
+        _ => &[],
+    }
+}
+
+pub fn has_software_av1() -> bool {
+    preferred_decoders(codec::Id::AV1)
+        .iter()
+        .any(|name| decoder::find_by_name(name).is_some())
+}
+
+fn software_path(decoder: &decoder::Video) -> String {
+    let name = decoder
+        .codec()
+        .map(|codec| codec.name().to_owned())
+        .unwrap_or_default();
+    format!("{name} (software)")
+}
+
+impl VideoContext {
+    // the hardware decoder failed, carry on in software
+    fn use_software(&mut self, error: ffmpeg_next::Error) -> Result<(), anyhow::Error> {
+        warn!("hardware video decoding failed ({error}), falling back to software");
+        self.decoder = open_decoder(self.parameters.clone())?;
+        self.hw_format = None;
+        let _ = self
+            .sink
+            .blocking_send(VideoData::DecodePath(software_path(&self.decoder)));
+        Ok(())
+    }
+}
+
+fn open_decoder(parameters: codec::Parameters) -> Result<decoder::Video, ffmpeg_next::Error> {
+    for name in preferred_decoders(parameters.id()) {
+        let Some(preferred) = decoder::find_by_name(name) else {
+            continue;
+        };
+        let context = codec::context::Context::from_parameters(parameters.clone())?;
+        match context
+            .decoder()
+            .open_as(preferred)
+            .and_then(|opened| opened.video())
+        {
+            Ok(decoder) => return Ok(decoder),
+            Err(e) => warn!("failed to open {name} decoder: {e}"),
+        }
+    }
+
+    codec::context::Context::from_parameters(parameters)?
+        .decoder()
+        .video()
+}
+
 impl FfmpegContext for VideoContext {
     fn is_live(&self) -> bool {
         !self.sink.is_closed()
@@ -140,9 +231,49 @@ impl FfmpegContext for VideoContext {
     }
 
     fn receive_packet(&mut self, packet: Packet) -> Result<(), anyhow::Error> {
-        self.decoder.send_packet(&packet).unwrap();
+        let sent = self.decoder.send_packet(&packet);
+        if let (Err(e), Some(_)) = (sent, self.hw_format) {
+            // the packet is lost, the software decoder picks up from the next keyframe
+            return self.use_software(e);
+        }
+        sent.unwrap();
         let mut decoded = frame::Video::empty();
         if let Ok(()) = self.decoder.receive_frame(&mut decoded) {
+            if let Some(target) = self.skip_until {
+                // seeking lands on the keyframe before the target
+                let time = decoded.timestamp().map(|ts| ts as f64 * self.time_base);
+                if time.is_some_and(|time| time < target - 0.5 / self.rate) {
+                    return Ok(());
+                }
+                self.skip_until = None;
+            }
+
+            if self
+                .hw_format
+                .is_some_and(|format| unsafe { (*decoded.as_ptr()).format } == format as i32)
+            {
+                match transfer_frame(&decoded) {
+                    Ok(frame) => decoded = frame,
+                    Err(e) => return self.use_software(e),
+                }
+            }
+
+            let input = self.scaler_context.input();
+            if (input.format, input.width, input.height)
+                != (decoded.format(), decoded.width(), decoded.height())
+            {
+                // adaptive streams change resolution between variants, keep the original output size
+                let output = *self.scaler_context.output();
+                self.scaler_context = Context::get(
+                    decoded.format(),
+                    decoded.width(),
+                    decoded.height(),
+                    output.format,
+                    output.width,
+                    output.height,
+                    Flags::BILINEAR,
+                )?;
+            }
             let mut rgb_frame = frame::Video::empty();
             // run frame through scaler for color space conversion
             self.scaler_context.run(&decoded, &mut rgb_frame)?;
@@ -167,9 +298,10 @@ impl FfmpegContext for VideoContext {
             self.buffer.len()
         );
         if let Some((index, frame)) = self.buffer.pop_front() {
+            self.last_sent_time = index as f64 / self.rate;
             if let Err(e) = self
                 .sink
-                .blocking_send(VideoData::Frame(frame, index as f64 / self.rate))
+                .blocking_send(VideoData::Frame(frame, self.last_sent_time))
             {
                 error!("failed to send video frame: {e}");
             }
@@ -190,14 +322,21 @@ impl FfmpegContext for VideoContext {
         (self.current_frame - self.start_frame + 1) as f64 / self.rate
     }
 
+    fn current_time(&self) -> f64 {
+        self.last_sent_time
+    }
+
     fn update_state(&self, state: VideoState) {
         let _ = self.sink.blocking_send(VideoData::State(state));
     }
 
-    fn clear(&mut self) {
+    fn clear(&mut self, time: f64) {
+        self.decoder.flush();
         self.buffer.clear();
         self.current_frame = 0;
         self.start_frame = 0;
-        self.next_store_frame = 0;
+        self.next_store_frame = (time * self.rate).round() as usize;
+        self.last_sent_time = time;
+        self.skip_until = Some(time);
     }
 }
@@ -0,0 +1,148 @@
+// placeholders drawn into video textures while a stream is loading or buffering (a spinner) and
+// after it fails (an offline icon), so surfaces don't sit frozen on the last frame. the next
+// decoded frame overwrites them.
+
+use bevy::{prelude::*, render::render_resource::Extent3d, utils::HashSet};
+use dcl_component::proto_components::sdk::components::VideoState;
+
+use crate::video_stream::VideoSink;
+
+// seconds of buffering before the spinner shows, so short stalls don't flash
+const SPINNER_DELAY: f32 = 0.5;
+// every redraw reuploads the whole texture, so the spinner runs slowly
+const SPINNER_FPS: f32 = 8.0;
+const SPINNER_DOTS: usize = 8;
+// size for surfaces that haven't received any video info yet
+const DEFAULT_SIZE: (u32, u32) = (256, 144);
+const BACKGROUND: [u8; 4] = [24, 24, 24, 255];
+
+enum Placeholder {
+    Spinner(usize),
+    Offline,
+}
+
+pub fn draw_video_placeholders(
+    mut images: ResMut<Assets<Image>>,
+    sinks: Query<(Entity, &VideoSink)>,
+    time: Res<Time>,
+    mut last_step: Local<usize>,
+    mut offline_drawn: Local<HashSet<Entity>>,
+) {
+    let now = time.elapsed_seconds();
+    let step = (now * SPINNER_FPS) as usize;
+    let spinner_due = step != *last_step;
+    *last_step = step;
+
+    offline_drawn.retain(|ent| {
+        sinks
+            .get(*ent)
+            .is_ok_and(|(_, sink)| sink.state == VideoState::VsError)
+    });
+
+    for (ent, sink) in sinks.iter() {
+        let placeholder = match sink.state {
+            VideoState::VsLoading | VideoState::VsBuffering | VideoState::VsSeeking
+                if spinner_due && now - sink.state_since > SPINNER_DELAY =>
+            {
+                Placeholder::Spinner(step % SPINNER_DOTS)
+            }
+            VideoState::VsError if offline_drawn.insert(ent) => Placeholder::Offline,
+            _ => continue,
+        };
+
+        let Some(image) = images.get_mut(&sink.image) else {
+            continue;
+        };
+        // frames are copied in at the size from the video info, so only resize before it arrives
+        if sink.rate.is_none() && image.width() < DEFAULT_SIZE.0 {
+            image.resize(Extent3d {
+                width: DEFAULT_SIZE.0,
+                height: DEFAULT_SIZE.1,
+                depth_or_array_layers: 1,
+            });
+        }
+
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let mut canvas = Canvas {
+            data: &mut image.data,
+            width,
+            height,
+        };
+        canvas.fill(BACKGROUND);
+        match placeholder {
+            Placeholder::Spinner(lead) => canvas.spinner(lead),
+            Placeholder::Offline => canvas.offline(),
+        }
+    }
+}
+
+struct Canvas<'a> {
+    data: &'a mut [u8],
+    width: usize,
+    height: usize,
+}
+
+impl Canvas<'_> {
+    fn fill(&mut self, color: [u8; 4]) {
+        for pixel in self.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+
+    fn center_and_scale(&self) -> (Vec2, f32) {
+        let center = Vec2::new(self.width as f32, self.height as f32) * 0.5;
+        (center, self.width.min(self.height) as f32)
+    }
+
+    // colour each pixel within `radius` of `center` for which `shade` returns some alpha
+    fn draw(&mut self, center: Vec2, radius: f32, color: [u8; 3], shade: impl Fn(Vec2) -> f32) {
+        let min = (center - radius).max(Vec2::ZERO).as_uvec2();
+        let max = (center + radius)
+            .min(Vec2::new(self.width as f32, self.height as f32))
+            .as_uvec2();
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let alpha = shade(Vec2::new(x as f32 + 0.5, y as f32 + 0.5)).clamp(0.0, 1.0);
+                if alpha <= 0.0 {
+                    continue;
+                }
+                let ix = (y as usize * self.width + x as usize) * 4;
+                for (channel, value) in color.iter().enumerate() {
+                    let dst = &mut self.data[ix + channel];
+                    *dst = (*dst as f32 + (*value as f32 - *dst as f32) * alpha) as u8;
+                }
+            }
+        }
+    }
+
+    // a ring of dots, brightest at `lead` and fading behind it
+    fn spinner(&mut self, lead: usize) {
+        let (center, scale) = self.center_and_scale();
+        let ring = scale * 0.12;
+        let dot = scale * 0.03;
+        for ix in 0..SPINNER_DOTS {
+            let angle = ix as f32 / SPINNER_DOTS as f32 * std::f32::consts::TAU;
+            let dot_center = center + Vec2::new(angle.sin(), -angle.cos()) * ring;
+            let age = (lead + SPINNER_DOTS - ix) % SPINNER_DOTS;
+            let brightness = 1.0 - age as f32 / SPINNER_DOTS as f32 * 0.8;
+            self.draw(dot_center, dot + 1.0, [230; 3], |p| {
+                (dot - p.distance(dot_center) + 0.5) * brightness
+            });
+        }
+    }
+
+    // a circle with a slash through it
+    fn offline(&mut self) {
+        let (center, scale) = self.center_and_scale();
+        let radius = scale * 0.15;
+        let stroke = (scale * 0.02).max(1.0);
+        let slash = Vec2::new(1.0, 1.0).normalize();
+        self.draw(center, radius + stroke, [200, 80, 80], |p| {
+            let offset = p - center;
+            let ring = stroke * 0.5 - (offset.length() - radius).abs();
+            let bar = stroke * 0.5 - offset.perp_dot(slash).abs();
+            let bar = if offset.length() < radius { bar } else { -1.0 };
+            ring.max(bar) + 0.5
+        });
+    }
+}
@@ -1,7 +1,12 @@
 use crate::{
+    audio_device::AudioOutputChanged,
+    audio_sink::{AudioSink, AudioSpawned},
+    livekit_video::{feed_livekit_videos, is_livekit_video, livekit_video_sink, LivekitVideoFeed},
     stream_processor::AVCommand,
-    video_context::{VideoData, VideoInfo},
+    video_context::{has_software_av1, VideoData, VideoInfo},
+    video_placeholder::draw_video_placeholders,
     video_stream::{av_sinks, VideoSink},
+    video_sync::sync_videos,
 };
 use bevy::{
     color::palettes::basic,
@@ -17,17 +22,20 @@ use bevy::{
 use common::{
     sets::SceneSets,
     structs::{AppConfig, PrimaryUser},
+    tr,
 };
 use dcl::interface::{ComponentPosition, CrdtType};
 use dcl_component::{
-    proto_components::sdk::components::{PbAudioStream, PbVideoEvent, PbVideoPlayer, VideoState},
+    proto_components::sdk::components::{
+        PbAudioStream, PbAudioStreamMetadata, PbVideoEvent, PbVideoPlayer, VideoState,
+    },
     SceneComponentId,
 };
 use ipfs::IpfsResource;
 use scene_runner::{
     renderer_context::RendererSceneContext,
     update_world::{material::VideoTextureOutput, AddCrdtInterfaceExt},
-    ContainerEntity, ContainingScene,
+    ContainerEntity, ContainingScene, DebugInfo, Toaster,
 };
 
 pub struct VideoPlayerPlugin;
@@ -43,7 +51,19 @@ impl Plugin for VideoPlayerPlugin {
             ComponentPosition::EntityOnly,
         );
         app.add_systems(Startup, init_ffmpeg);
-        app.add_systems(Update, play_videos);
+        app.add_systems(
+            Update,
+            (
+                feed_livekit_videos,
+                play_videos,
+                sync_videos,
+                draw_video_placeholders,
+                show_now_playing,
+            )
+                .chain(),
+        );
+        app.add_systems(Update, restart_on_output_change);
+        app.add_systems(Update, video_debug_info);
         app.add_systems(Update, update_video_players.in_set(SceneSets::PostLoop));
     }
 }
@@ -52,11 +72,44 @@ impl Plugin for VideoPlayerPlugin {
 pub struct AVPlayer {
     // note we reuse PbVideoPlayer for audio as well
     pub source: PbVideoPlayer,
+    // video audio comes from the video entity, audio streams play everywhere in the scene
+    pub spatial: bool,
+}
+
+// kira resamples to change speed, so the pitch changes with the rate
+const MIN_PLAYBACK_RATE: f32 = 0.25;
+const MAX_PLAYBACK_RATE: f32 = 4.0;
+
+impl AVPlayer {
+    pub fn playback_rate(&self) -> f64 {
+        self.source
+            .playback_rate
+            .unwrap_or(1.0)
+            .clamp(MIN_PLAYBACK_RATE, MAX_PLAYBACK_RATE) as f64
+    }
+
+    // loop start and optional end in seconds
+    pub fn loop_range(&self) -> (f64, Option<f64>) {
+        let start = self.source.loop_start.unwrap_or(0.0).max(0.0);
+        let end = self.source.loop_end.filter(|end| *end > start);
+        (start as f64, end.map(f64::from))
+    }
+
+    // send the scene's rate and loop settings to the av thread
+    fn send_settings(&self, sender: &tokio::sync::mpsc::Sender<AVCommand>, rate_nudge: f64) {
+        let (start, end) = self.loop_range();
+        let _ = sender.try_send(AVCommand::Repeat(self.source.r#loop.unwrap_or(false)));
+        let _ = sender.try_send(AVCommand::LoopRange(start, end));
+        let _ = sender.try_send(AVCommand::Rate(self.playback_rate() * rate_nudge));
+    }
 }
 
 impl From<PbVideoPlayer> for AVPlayer {
     fn from(value: PbVideoPlayer) -> Self {
-        Self { source: value }
+        Self {
+            source: value,
+            spatial: true,
+        }
     }
 }
 
@@ -69,6 +122,7 @@ impl From<PbAudioStream> for AVPlayer {
                 volume: value.volume,
                 ..Default::default()
             },
+            spatial: false,
         }
     }
 }
@@ -76,6 +130,9 @@ impl From<PbAudioStream> for AVPlayer {
 fn init_ffmpeg() {
     ffmpeg_next::init().unwrap();
     ffmpeg_next::log::set_level(ffmpeg_next::log::Level::Error);
+    if !has_software_av1() {
+        warn!("ffmpeg was built without dav1d or libaom, av1 videos won't play");
+    }
 }
 
 fn play_videos(
@@ -83,10 +140,12 @@ fn play_videos(
     mut q: Query<(&mut VideoSink, &ContainerEntity)>,
     mut scenes: Query<&mut RendererSceneContext>,
     frame: Res<FrameCount>,
+    time: Res<Time>,
 ) {
     for (mut sink, container) in q.iter_mut() {
         let mut last_frame_received = None;
         let mut new_state = None;
+        let mut error_detail = None;
         loop {
             match sink.video_receiver.try_recv() {
                 Ok(VideoData::Info(VideoInfo {
@@ -108,7 +167,33 @@ fn play_videos(
                     last_frame_received = Some(frame);
                     sink.current_time = time;
                 }
-                Ok(VideoData::State(state)) => new_state = Some(state),
+                Ok(VideoData::State(state)) => {
+                    sink.set_state(state, time.elapsed_seconds());
+                    new_state = Some(state);
+                }
+                Ok(VideoData::Error(detail)) => {
+                    sink.set_state(VideoState::VsError, time.elapsed_seconds());
+                    new_state = Some(VideoState::VsError);
+                    error_detail = Some(detail);
+                }
+                Ok(VideoData::Metadata(metadata)) => {
+                    if let Ok(mut context) = scenes.get_mut(container.root) {
+                        context.update_crdt(
+                            SceneComponentId::AUDIO_STREAM_METADATA,
+                            CrdtType::LWW_ENT,
+                            container.container_id,
+                            &PbAudioStreamMetadata {
+                                station: metadata.station.clone(),
+                                title: metadata.title.clone(),
+                            },
+                        );
+                    }
+                    sink.metadata = metadata;
+                }
+                Ok(VideoData::DecodePath(path)) => {
+                    debug!("decoding {} with {path}", sink.source);
+                    sink.decode_path = Some(path);
+                }
                 Err(_) => break,
             }
         }
@@ -139,6 +224,7 @@ fn play_videos(
                     current_offset: sink.current_time as f32,
                     video_length: sink.length.unwrap_or(-1.0) as f32,
                     state: state.into(),
+                    error_detail: error_detail.take(),
                 };
                 context.update_crdt(
                     SceneComponentId::VIDEO_EVENT,
@@ -152,6 +238,88 @@ fn play_videos(
     }
 }
 
+// toast track changes from streams playing in the player's scene
+fn show_now_playing(
+    sinks: Query<(Entity, &VideoSink, &ContainerEntity, &AVPlayer)>,
+    containing_scene: ContainingScene,
+    user: Query<Entity, With<PrimaryUser>>,
+    config: Res<AppConfig>,
+    mut toaster: Toaster,
+    mut shown: Local<HashMap<Entity, Option<String>>>,
+) {
+    let scenes = user
+        .get_single()
+        .map(|user| containing_scene.get(user))
+        .unwrap_or_default();
+
+    let mut prev_shown = std::mem::take(&mut *shown);
+    for (ent, sink, container, player) in sinks.iter() {
+        let title = sink.metadata.title.clone();
+        let prev = prev_shown.remove(&ent).flatten();
+        shown.insert(ent, title.clone());
+
+        let Some(title) = title else {
+            continue;
+        };
+        if Some(&title) == prev.as_ref()
+            || !config.show_now_playing
+            || !player.source.playing.unwrap_or(true)
+            || !scenes.contains(&container.root)
+        {
+            continue;
+        }
+
+        let message = match sink.metadata.station.clone() {
+            Some(station) => tr!(
+                "toast-now-playing-station",
+                station = station,
+                title = title
+            ),
+            None => tr!("toast-now-playing", title = title),
+        };
+        toaster.add_toast("now playing", message);
+    }
+}
+
+// how the active videos are being decoded, e.g. "2x h264 (vaapi), 1x vp9 (software)"
+fn video_debug_info(sinks: Query<&VideoSink>, mut debug_info: ResMut<DebugInfo>) {
+    let mut paths = HashMap::<&str, usize>::default();
+    for path in sinks.iter().filter_map(|sink| sink.decode_path.as_deref()) {
+        *paths.entry(path).or_default() += 1;
+    }
+
+    if paths.is_empty() {
+        debug_info.info.remove("Video");
+        return;
+    }
+
+    let mut paths = paths.into_iter().collect::<Vec<_>>();
+    paths.sort();
+    let info = paths
+        .into_iter()
+        .map(|(path, count)| format!("{count}x {path}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    debug_info.info.insert("Video", info);
+}
+
+// streams are consumed by the old audio manager, so restart them from scratch on a new device
+fn restart_on_output_change(
+    mut commands: Commands,
+    mut changed: EventReader<AudioOutputChanged>,
+    players: Query<Entity, With<VideoSink>>,
+) {
+    if changed.read().last().is_none() {
+        return;
+    }
+
+    for ent in players.iter() {
+        commands
+            .entity(ent)
+            .remove::<(VideoSink, AudioSink, AudioSpawned)>();
+    }
+}
+
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn update_video_players(
     mut commands: Commands,
@@ -170,8 +338,10 @@ pub fn update_video_players(
     mut system_paused: Local<HashMap<Entity, Option<tokio::sync::mpsc::Sender<AVCommand>>>>,
     containing_scene: ContainingScene,
     user: Query<&GlobalTransform, With<PrimaryUser>>,
+    mut positions: Local<HashMap<Entity, f32>>,
 ) {
     let mut previously_stopped = std::mem::take(&mut *system_paused);
+    positions.retain(|ent, _| video_players.contains(*ent));
 
     for (ent, container, player, maybe_sink, maybe_texture, _) in video_players.iter() {
         if maybe_sink.map(|sink| &sink.source) != Some(&player.source.src) {
@@ -195,6 +365,18 @@ pub fn update_video_players(
                 Some(texture) => texture.0.clone(),
             };
 
+            if is_livekit_video(&player.source.src) {
+                let (video_sink, feed) =
+                    livekit_video_sink(player.source.src.clone(), image_handle);
+                let video_output = VideoTextureOutput(video_sink.image.clone());
+                commands
+                    .entity(ent)
+                    .remove::<(AudioSink, AudioSpawned)>()
+                    .try_insert((video_sink, video_output, feed));
+                debug!("{ent:?} has {}", player.source.src);
+                continue;
+            }
+
             let Ok(context) = scenes.get(container.root) else {
                 continue;
             };
@@ -214,9 +396,18 @@ pub fn update_video_players(
                 player.source.playing.unwrap_or(true)
             );
             previously_stopped.insert(ent, Some(video_sink.command_sender.clone()));
+            player.send_settings(&video_sink.command_sender, video_sink.rate_nudge);
+            let position = player.source.position.unwrap_or(0.0);
+            if position > 0.0 {
+                let _ = video_sink
+                    .command_sender
+                    .try_send(AVCommand::Seek(position as f64));
+            }
+            positions.insert(ent, position);
             let video_output = VideoTextureOutput(video_sink.image.clone());
             commands
                 .entity(ent)
+                .remove::<LivekitVideoFeed>()
                 .try_insert((video_sink, video_output, audio_sink));
             debug!("{ent:?} has {}", player.source.src);
         } else if player.is_changed() {
@@ -228,9 +419,14 @@ pub fn update_video_players(
                 debug!("scene stopping {ent:?}");
                 let _ = sink.command_sender.try_send(AVCommand::Pause);
             }
-            let _ = sink
-                .command_sender
-                .try_send(AVCommand::Repeat(player.source.r#loop.unwrap_or(false)));
+            player.send_settings(&sink.command_sender, sink.rate_nudge);
+            // scenes seek by changing the position
+            let position = player.source.position.unwrap_or(0.0);
+            if positions.insert(ent, position) != Some(position) {
+                let _ = sink
+                    .command_sender
+                    .try_send(AVCommand::Seek(position as f64));
+            }
         }
     }
 
@@ -3,17 +3,17 @@ use std::path::Path;
 use bevy::{prelude::*, utils::tracing};
 use common::structs::AudioDecoderError;
 use dcl_component::proto_components::sdk::components::VideoState;
-use ffmpeg_next::format::input;
 use ipfs::{IpfsIo, IpfsResource};
 use isahc::ReadResponseExt;
 use kira::sound::streaming::StreamingSoundData;
 
 use crate::{
+    adaptive::Variants,
     audio_context::{AudioContext, AudioError},
     audio_sink::AudioSink,
-    ffmpeg_util::InputWrapper,
+    ffmpeg_util::{open_input, InputWrapper},
     stream_processor::{process_streams, AVCommand},
-    video_context::{VideoContext, VideoData, VideoError},
+    video_context::{StreamMetadata, VideoContext, VideoData, VideoError},
 };
 
 #[derive(Component)]
@@ -26,6 +26,22 @@ pub struct VideoSink {
     pub last_reported_time: f64,
     pub length: Option<f64>,
     pub rate: Option<f64>,
+    pub metadata: StreamMetadata,
+    pub decode_path: Option<String>,
+    // latest state from the av thread, and when it was entered
+    pub state: VideoState,
+    pub state_since: f32,
+    // playback rate adjustment to keep synchronized videos on the shared clock
+    pub rate_nudge: f64,
+}
+
+impl VideoSink {
+    pub fn set_state(&mut self, state: VideoState, now: f32) {
+        if state != self.state {
+            self.state = state;
+            self.state_since = now;
+        }
+    }
 }
 
 pub fn av_sinks(
@@ -67,6 +83,11 @@ pub fn av_sinks(
             last_reported_time: -1.0,
             length: None,
             rate: None,
+            metadata: StreamMetadata::default(),
+            decode_path: None,
+            state: VideoState::VsNone,
+            state_since: 0.0,
+            rate_nudge: 1.0,
         },
         AudioSink::new(volume, command_sender, audio_receiver),
     )
@@ -97,7 +118,7 @@ fn av_thread(
     );
     let _span = tracing::info_span!("av-thread").entered();
     if let Err(e) = av_thread_inner(&ipfs, commands, frames.clone(), audio, path, hash) {
-        let _ = frames.blocking_send(VideoData::State(VideoState::VsError));
+        let _ = frames.blocking_send(VideoData::Error(e.to_string()));
         warn!("av error: {e}");
     } else {
         debug!("av closed");
@@ -141,7 +162,9 @@ pub fn av_thread_inner(
         }
     };
 
-    let mut input_context = input(&path)?;
+    let mut input_context = open_input(&path)?;
+    // hls/dash variant selection, before the contexts pick their streams
+    let mut variants = Variants::new(&mut input_context);
 
     // try and get a video context
     let video_context: Option<VideoContext> = {
@@ -151,8 +174,12 @@ pub fn av_thread_inner(
                 // try to workaround ffmpeg remote streaming issue by downloading the file
                 debug!("failed to determine pixel format - downloading ...");
                 let path = download(&path)?;
-                input_context = input(&path)?;
-                Some(VideoContext::init(&input_context, video).map_err(|e| anyhow::anyhow!(e))?)
+                input_context = open_input(&path)?;
+                variants = Variants::new(&mut input_context);
+                Some(
+                    VideoContext::init(&input_context, video.clone())
+                        .map_err(|e| anyhow::anyhow!(e))?,
+                )
             }
             Err(VideoError::NoStream) => None,
             Err(VideoError::Failed(ffmpeg_err)) => Err(ffmpeg_err)?,
@@ -160,21 +187,19 @@ pub fn av_thread_inner(
         }
     };
 
-    // try and get an audio context
-    let audio_context: Option<AudioContext> = match AudioContext::init(&input_context, audio) {
-        Ok(ac) => Some(ac),
-        Err(AudioError::NoStream) => None,
-        Err(AudioError::Failed(ffmpeg_err)) => Err(ffmpeg_err)?,
-    };
-
-    if video_context.is_none() && audio_context.is_none() {
-        // no data
-    }
+    // try and get an audio context. without video, it reports the playback state instead
+    let state_sender = video_context.is_none().then(|| video.clone());
+    let audio_context: Option<AudioContext> =
+        match AudioContext::init(&input_context, audio, state_sender) {
+            Ok(ac) => Some(ac),
+            Err(AudioError::NoStream) => None,
+            Err(AudioError::Failed(ffmpeg_err)) => Err(ffmpeg_err)?,
+        };
 
-    let input_context = InputWrapper::new(input_context, path);
+    let input_context = InputWrapper::new(input_context, path, video, variants);
 
     match (video_context, audio_context) {
-        (None, None) => Ok(()),
+        (None, None) => Err(anyhow::anyhow!("no audio or video streams")),
         (None, Some(mut ac)) => process_streams(input_context, &mut [&mut ac], commands),
         (Some(mut vc), None) => process_streams(input_context, &mut [&mut vc], commands),
         (Some(mut vc), Some(mut ac)) => {
@@ -0,0 +1,92 @@
+// synchronized videos follow the realm clock, so everyone in a venue sees the same frame. small
+// drift is corrected by nudging the playback rate (which shifts the audio pitch slightly), large
+// differences from joining late or stalling are corrected by seeking.
+
+use bevy::{prelude::*, utils::HashMap};
+use dcl_component::proto_components::sdk::components::VideoState;
+use ipfs::CurrentRealm;
+
+use crate::{stream_processor::AVCommand, video_player::AVPlayer, video_stream::VideoSink};
+
+// seconds of drift allowed before nudging
+const SYNC_TOLERANCE: f64 = 0.15;
+// fraction of drift corrected per second of playback
+const NUDGE_GAIN: f64 = 0.1;
+const MAX_NUDGE: f64 = 0.05;
+// seconds of drift corrected by seeking instead
+const SEEK_THRESHOLD: f64 = 3.0;
+// seek ahead to cover the time spent buffering
+const SEEK_LEAD: f64 = 0.5;
+// seconds to let a seek settle before checking again
+const SEEK_COOLDOWN: f32 = 3.0;
+
+// where a synchronized video should be now, and the loop length for wrapping the difference
+fn sync_target(player: &AVPlayer, length: f64, shared_time: f64) -> Option<(f64, Option<f64>)> {
+    // not started yet
+    let elapsed = shared_time - player.source.sync_start_time.unwrap_or(0.0);
+    if elapsed < 0.0 || length <= 0.0 {
+        return None;
+    }
+
+    if player.source.r#loop.unwrap_or(false) {
+        let (start, end) = player.loop_range();
+        let span = end.unwrap_or(length) - start;
+        (span > 0.0).then(|| (start + elapsed % span, Some(span)))
+    } else {
+        // finished
+        (elapsed < length).then_some((elapsed, None))
+    }
+}
+
+pub fn sync_videos(
+    mut players: Query<(Entity, &AVPlayer, &mut VideoSink)>,
+    realm: Res<CurrentRealm>,
+    time: Res<Time>,
+    mut last_seek: Local<HashMap<Entity, f32>>,
+) {
+    let now = time.elapsed_seconds();
+    let shared_time = realm.shared_time();
+    last_seek.retain(|ent, _| players.contains(*ent));
+
+    for (ent, player, mut sink) in players.iter_mut() {
+        let target =
+            if player.source.synchronized.unwrap_or(false) && sink.state == VideoState::VsPlaying {
+                sink.length
+                    .and_then(|length| sync_target(player, length, shared_time))
+            } else {
+                None
+            };
+
+        let mut nudge = 1.0;
+        if let Some((target, wrap)) = target {
+            let mut drift = target - sink.current_time;
+            if let Some(wrap) = wrap {
+                drift = (drift + wrap * 0.5).rem_euclid(wrap) - wrap * 0.5;
+            }
+
+            if drift.abs() > SEEK_THRESHOLD {
+                if !last_seek
+                    .get(&ent)
+                    .is_some_and(|t| now - t <= SEEK_COOLDOWN)
+                {
+                    debug!("seeking {} to {target} for sync", sink.source);
+                    let _ = sink
+                        .command_sender
+                        .try_send(AVCommand::Seek(target + SEEK_LEAD));
+                    last_seek.insert(ent, now);
+                }
+            } else if drift.abs() > SYNC_TOLERANCE {
+                // in steps of 1%, so we don't restart the clock every frame
+                nudge = 1.0
+                    + ((drift * NUDGE_GAIN).clamp(-MAX_NUDGE, MAX_NUDGE) * 100.0).round() / 100.0;
+            }
+        }
+
+        if nudge != sink.rate_nudge {
+            sink.rate_nudge = nudge;
+            let _ = sink
+                .command_sender
+                .try_send(AVCommand::Rate(player.playback_rate() * nudge));
+        }
+    }
+}
@@ -18,8 +18,8 @@ use common::{
     dynamics::PLAYER_COLLIDER_RADIUS,
     rpc::{RpcCall, RpcEventSender},
     sets::SceneSets,
-    structs::{AppConfig, PrimaryUser},
-    util::{TryPushChildrenEx, VolumePanning},
+    structs::{AudioSettings, EffectRng, PrimaryUser},
+    util::{EmitterVolume, TryPushChildrenEx, VolumePanning},
 };
 use comms::{
     chat_marker_things,
@@ -49,7 +49,10 @@ use scene_runner::{
     ContainerEntity, ContainingScene,
 };
 
-use crate::{process_avatar, AvatarDefinition};
+use crate::{
+    footsteps::{footstep_speed_volume, FootstepSurface},
+    process_avatar, AvatarDefinition,
+};
 
 use super::AvatarDynamicState;
 
@@ -104,6 +107,7 @@ impl Plugin for AvatarAnimationPlugin {
             )
                 .in_set(SceneSets::PostLoop),
         );
+        app.init_resource::<EffectRng>();
         app.add_console_command::<EmoteConsoleCommand, _>(emote_console_command);
     }
 }
@@ -165,6 +169,7 @@ fn read_player_emotes(
 fn broadcast_emote(
     q: Query<&EmoteList, With<PrimaryUser>>,
     transports: Query<&Transport>,
+    new_transports: Query<(), Added<Transport>>,
     mut last: Local<Option<String>>,
     mut count: Local<usize>,
     time: Res<Time>,
@@ -179,6 +184,11 @@ fn broadcast_emote(
         senders.push(sender.clone());
     }
 
+    // resend an ongoing emote to peers after a realm change
+    if !new_transports.is_empty() {
+        *last = None;
+    }
+
     for list in q.iter() {
         if let Some(EmoteCommand {
             emote: PbAvatarEmoteCommand { emote_urn, .. },
@@ -510,6 +520,7 @@ fn play_current_emote(
         &AvatarAnimPlayer,
         &Children,
         &GlobalTransform,
+        Option<&FootstepSurface>,
     )>,
     definitions: Query<&AvatarDefinition>,
     mut emote_loader: CollectibleManager<Emote>,
@@ -526,12 +537,13 @@ fn play_current_emote(
     mut cached_gltf_handles: Local<HashSet<Handle<Gltf>>>,
     mut spawned_extras: Local<HashMap<Entity, SpawnedExtras>>,
     mut scene_spawner: ResMut<SceneSpawner>,
-    (audio, sounds, anim_clips, config, pan): (
+    (audio, sounds, anim_clips, mixer, pan, mut rng): (
         Res<bevy_kira_audio::Audio>,
         Res<Assets<bevy_kira_audio::AudioSource>>,
         Res<Assets<AnimationClip>>,
-        Res<AppConfig>,
+        Res<AudioSettings>,
         VolumePanning,
+        ResMut<EffectRng>,
     ),
     mut emitters: Query<&mut bevy_kira_audio::prelude::AudioEmitter>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
@@ -540,7 +552,7 @@ fn play_current_emote(
     let prior_playing = std::mem::take(&mut *playing);
     let mut prev_spawned_extras = std::mem::take(&mut *spawned_extras);
 
-    for (entity, mut active_emote, target_entity, children, transform) in q.iter_mut() {
+    for (entity, mut active_emote, target_entity, children, transform, surface) in q.iter_mut() {
         debug!("emote {}", active_emote.urn);
         let Some(definition) = children
             .iter()
@@ -756,6 +768,7 @@ fn play_current_emote(
             } else {
                 last_audio_mark
             },
+            &mut rng.0,
         ) {
             Ok(Some((t, s))) => {
                 if last_audio_mark.is_finite() {
@@ -772,7 +785,7 @@ fn play_current_emote(
             sound.as_ref().map(|(t, _)| t)
         );
         let sound = if sound.is_none() && active_emote.repeat {
-            match emote.audio(&sounds, f32::NEG_INFINITY) {
+            match emote.audio(&sounds, f32::NEG_INFINITY, &mut rng.0) {
                 Ok(None) => None,
                 Ok(Some((play_time, s))) => {
                     Some((play_time + clip_duration * (completions + 1.0), s))
@@ -882,6 +895,22 @@ fn play_current_emote(
                 debug!("duration {}", clip_duration);
                 debug!("play {:?} @ {}>{}", sound.path(), elapsed, play_time);
                 let (volume, panning) = pan.volume_and_panning(transform.translation());
+                let footstep = ["walk", "run", "jump"]
+                    .iter()
+                    .any(|urn| active_emote.urn == EmoteUrn::new(urn).unwrap());
+                // disabled footsteps still advance the mark so the cycle stays in sync
+                let (step_volume, rate) = match (footstep, mixer.footsteps) {
+                    (false, _) => (1.0, 1.0),
+                    (true, false) => (0.0, 1.0),
+                    (true, true) => {
+                        let (surface_volume, rate) = surface.copied().unwrap_or_default().sound();
+                        (
+                            surface_volume * footstep_speed_volume(active_emote.speed),
+                            rate,
+                        )
+                    }
+                };
+                let volume = volume * step_volume;
                 let existing = spawned_extras
                     .get_mut(&entity)
                     .and_then(|extras| extras.audio.as_mut());
@@ -897,16 +926,22 @@ fn play_current_emote(
                     existing_emitter.instances.push(
                         audio
                             .play(sound)
-                            .with_volume((volume * config.audio.avatar()) as f64)
+                            .with_volume((volume * mixer.avatar()) as f64)
                             .with_panning(panning as f64)
+                            .with_playback_rate(rate as f64)
                             .handle(),
                     );
-                    existing.unwrap().1 = elapsed;
+                    let (audio_entity, mark) = existing.unwrap();
+                    *mark = elapsed;
+                    if let Some(mut commands) = commands.get_entity(*audio_entity) {
+                        commands.try_insert(EmitterVolume(step_volume));
+                    }
                 } else {
                     let handle = audio
                         .play(sound)
-                        .with_volume((volume * config.audio.avatar()) as f64)
+                        .with_volume((volume * mixer.avatar()) as f64)
                         .with_panning(panning as f64)
+                        .with_playback_rate(rate as f64)
                         .handle();
 
                     let audio_entity = commands
@@ -915,6 +950,7 @@ fn play_current_emote(
                             bevy_kira_audio::prelude::AudioEmitter {
                                 instances: vec![handle],
                             },
+                            EmitterVolume(step_volume),
                         ))
                         .id();
 
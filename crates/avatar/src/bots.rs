@@ -0,0 +1,314 @@
+// `/spawn_bots <n>` load testing. spawns synthetic foreign players with random base wearables and
+// movement patterns around the player. they are fed through the normal comms player pipeline from a
+// local transport, without any network, so avatar loading, lod and visibility see them as real
+// players. `/spawn_bots 0` stops them, and they time out like departed peers.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_console::ConsoleCommand;
+use collectibles::base_wearables;
+use common::{
+    profile::{AvatarColor, SerializedProfile},
+    structs::{EffectRng, PrimaryUser},
+    util::AsH160,
+};
+use comms::{
+    global_crdt::{GlobalCrdtState, PlayerMessage, PlayerUpdate},
+    NetworkMessage, Transport, TransportType,
+};
+use console::DoAddConsoleCommand;
+use dcl_component::{
+    proto_components::{common::Color3, kernel::comms::rfc4},
+    transform_and_parent::{DclQuat, DclTranslation},
+};
+use ethers_core::types::Address;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Bots>();
+        app.add_systems(Update, update_bots);
+        app.add_console_command::<SpawnBotsCommand, _>(spawn_bots_command);
+    }
+}
+
+// position updates per bot per second, similar to real peers
+const BOT_UPDATE_RATE: f32 = 10.0;
+// keeps idle bots from timing out
+const BOT_IDLE_UPDATE: f32 = 1.0;
+// bounds the messages queued per frame so large counts don't overflow the player channel
+const MAX_ANNOUNCE_PER_FRAME: usize = 100;
+const BOT_WEARABLE_COUNT: usize = 8;
+const BOT_AREA_RADIUS: f32 = 24.0;
+
+#[derive(Clone, Copy, Debug)]
+enum BotMovement {
+    Idle,
+    Circle { radius: f32, speed: f32 },
+    Wander { target: Vec3, speed: f32 },
+    Patrol { offset: Vec3, speed: f32 },
+}
+
+struct Bot {
+    address: Address,
+    profile: Option<SerializedProfile>,
+    origin: Vec3,
+    position: Vec3,
+    facing: Vec3,
+    movement: BotMovement,
+    phase: f32,
+    next_update: f32,
+}
+
+struct BotTransport {
+    entity: Entity,
+    // profile requests and our own broadcasts, discarded
+    receiver: Receiver<NetworkMessage>,
+    player_sender: Sender<PlayerUpdate>,
+}
+
+#[derive(Resource, Default)]
+pub struct Bots {
+    transport: Option<BotTransport>,
+    bots: Vec<Bot>,
+    next_id: u64,
+    position_index: u32,
+}
+
+/// spawn n simulated avatars around the player for load testing, or remove them with 0
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/spawn_bots")]
+struct SpawnBotsCommand {
+    count: usize,
+}
+
+fn spawn_bots_command(
+    mut input: ConsoleCommand<SpawnBotsCommand>,
+    mut commands: Commands,
+    mut bots: ResMut<Bots>,
+    mut rng: ResMut<EffectRng>,
+    global_crdt: Res<GlobalCrdtState>,
+    player: Query<&GlobalTransform, With<PrimaryUser>>,
+) {
+    let Some(Ok(command)) = input.take() else {
+        return;
+    };
+
+    if command.count == 0 {
+        let count = bots.bots.len();
+        bots.bots.clear();
+        input.reply_ok(format!("removed {count} bots"));
+        return;
+    }
+
+    let Ok(origin) = player.get_single().map(GlobalTransform::translation) else {
+        input.reply_failed("no player");
+        return;
+    };
+
+    let bots = &mut *bots;
+    if bots.transport.is_none() {
+        let (sender, receiver) = tokio::sync::mpsc::channel(1000);
+        let entity = commands
+            .spawn(Transport {
+                transport_type: TransportType::Bots,
+                sender,
+                foreign_aliases: Default::default(),
+            })
+            .id();
+        bots.transport = Some(BotTransport {
+            entity,
+            receiver,
+            player_sender: global_crdt.get_sender(),
+        });
+    }
+
+    let rng = &mut rng.0;
+    for _ in 0..command.count {
+        bots.next_id += 1;
+        let address = format!("0x{:040x}", 0xb07_0000_0000u64 + bots.next_id)
+            .as_h160()
+            .unwrap();
+        let position = origin + random_offset(rng, BOT_AREA_RADIUS);
+        let speed = 1.0 + rng.f32() * 4.0;
+        let movement = match rng.u32(0..4) {
+            0 => BotMovement::Idle,
+            1 => BotMovement::Circle {
+                radius: 2.0 + rng.f32() * 8.0,
+                speed,
+            },
+            2 => BotMovement::Wander {
+                target: position,
+                speed,
+            },
+            _ => BotMovement::Patrol {
+                offset: random_offset(rng, 10.0),
+                speed,
+            },
+        };
+        bots.bots.push(Bot {
+            address,
+            profile: Some(random_profile(rng, address, bots.next_id)),
+            origin: position,
+            position,
+            facing: Vec3::Z,
+            movement,
+            phase: rng.f32() * TAU,
+            next_update: 0.0,
+        });
+    }
+
+    input.reply_ok(format!(
+        "spawned {} bots ({} total)",
+        command.count,
+        bots.bots.len()
+    ));
+}
+
+fn random_offset(rng: &mut fastrand::Rng, radius: f32) -> Vec3 {
+    let angle = rng.f32() * TAU;
+    let distance = rng.f32().sqrt() * radius;
+    Vec3::new(angle.cos() * distance, 0.0, angle.sin() * distance)
+}
+
+fn random_color(rng: &mut fastrand::Rng) -> Option<AvatarColor> {
+    Some(AvatarColor::new(Color3 {
+        r: rng.f32(),
+        g: rng.f32(),
+        b: rng.f32(),
+    }))
+}
+
+fn random_profile(rng: &mut fastrand::Rng, address: Address, id: u64) -> SerializedProfile {
+    let mut profile = SerializedProfile {
+        name: format!("Bot{id}"),
+        eth_address: format!("{address:#x}"),
+        user_id: Some(format!("{address:#x}")),
+        ..Default::default()
+    };
+
+    let body_shape = if rng.bool() {
+        "urn:decentraland:off-chain:base-avatars:BaseMale"
+    } else {
+        "urn:decentraland:off-chain:base-avatars:BaseFemale"
+    };
+    let candidates = base_wearables::base_wearable_urns()
+        .into_iter()
+        .map(|urn| urn.to_string())
+        .filter(|urn| !urn.ends_with(":BaseMale") && !urn.ends_with(":BaseFemale"))
+        .collect::<Vec<_>>();
+    // the avatar keeps the first wearable of each category and fills the rest from defaults
+    let wearables = (0..BOT_WEARABLE_COUNT)
+        .filter_map(|_| rng.choice(candidates.iter()).cloned())
+        .collect();
+
+    let avatar = &mut profile.avatar;
+    avatar.name = Some(profile.name.clone());
+    avatar.body_shape = Some(body_shape.to_owned());
+    avatar.wearables = wearables;
+    avatar.eyes = random_color(rng);
+    avatar.hair = random_color(rng);
+    avatar.skin = random_color(rng);
+    profile
+}
+
+fn update_bots(mut bots: ResMut<Bots>, mut rng: ResMut<EffectRng>, time: Res<Time>) {
+    let bots = &mut *bots;
+    let Some(transport) = bots.transport.as_mut() else {
+        return;
+    };
+    while transport.receiver.try_recv().is_ok() {}
+
+    let send = |address: Address, message: rfc4::packet::Message| {
+        // dropped if the channel is full, positions are resent anyway
+        let _ = transport.player_sender.try_send(PlayerUpdate {
+            transport_id: transport.entity,
+            message: PlayerMessage::PlayerData(message),
+            address,
+        });
+    };
+
+    // announce new bots, a few at a time
+    let mut announced = 0;
+    for bot in bots.bots.iter_mut().filter(|bot| bot.profile.is_some()) {
+        if announced == MAX_ANNOUNCE_PER_FRAME {
+            break;
+        }
+        let profile = bot.profile.take().unwrap();
+        send(
+            bot.address,
+            rfc4::packet::Message::ProfileVersion(rfc4::AnnounceProfileVersion {
+                profile_version: profile.version as u32,
+            }),
+        );
+        send(
+            bot.address,
+            rfc4::packet::Message::ProfileResponse(rfc4::ProfileResponse {
+                serialized_profile: serde_json::to_string(&profile).unwrap(),
+                base_url: String::default(),
+            }),
+        );
+        announced += 1;
+    }
+
+    let dt = time.delta_seconds();
+    let now = time.elapsed_seconds();
+    let rng = &mut rng.0;
+    for bot in bots.bots.iter_mut().filter(|bot| bot.profile.is_none()) {
+        let previous = bot.position;
+        match &mut bot.movement {
+            BotMovement::Idle => (),
+            BotMovement::Circle { radius, speed } => {
+                bot.phase += dt * *speed / *radius;
+                bot.position =
+                    bot.origin + Vec3::new(bot.phase.cos(), 0.0, bot.phase.sin()) * *radius;
+            }
+            BotMovement::Wander { target, speed } => {
+                let to_target = *target - bot.position;
+                if to_target.length() < 0.5 {
+                    *target = bot.origin + random_offset(rng, 8.0);
+                } else {
+                    bot.position += to_target.normalize() * (*speed * dt).min(to_target.length());
+                }
+            }
+            BotMovement::Patrol { offset, speed } => {
+                bot.phase += dt * *speed / offset.length().max(1.0);
+                bot.position = bot.origin + *offset * bot.phase.sin();
+            }
+        }
+        let velocity = bot.position - previous;
+        if velocity.length_squared() > 1e-6 {
+            bot.facing = velocity.normalize();
+        }
+
+        if now < bot.next_update {
+            continue;
+        }
+        bot.next_update = now
+            + match bot.movement {
+                BotMovement::Idle => BOT_IDLE_UPDATE,
+                _ => 1.0 / BOT_UPDATE_RATE,
+            };
+
+        bots.position_index += 1;
+        let transform = Transform::from_translation(bot.position).looking_to(bot.facing, Vec3::Y);
+        let translation = DclTranslation::from_bevy_translation(transform.translation);
+        let rotation = DclQuat::from_bevy_quat(transform.rotation);
+        send(
+            bot.address,
+            rfc4::packet::Message::Position(rfc4::Position {
+                index: bots.position_index,
+                position_x: translation.0[0],
+                position_y: translation.0[1],
+                position_z: translation.0[2],
+                rotation_x: rotation.0[0],
+                rotation_y: rotation.0[1],
+                rotation_z: rotation.0[2],
+                rotation_w: rotation.0[3],
+            }),
+        );
+    }
+}
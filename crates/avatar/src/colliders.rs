@@ -9,7 +9,7 @@ use common::{
     rpc::{RpcCall, RpcEventSender},
     sets::SceneSets,
     structs::{PrimaryCamera, ShowProfileEvent, ToolTips, TooltipSource},
-    util::{AsH160, FireEventEx},
+    util::{window_to_world_ray, AsH160, FireEventEx},
 };
 use comms::{global_crdt::ForeignPlayer, profile::UserProfile};
 use input_manager::AcceptInput;
@@ -161,7 +161,7 @@ fn update_avatar_collider_actions(
         cursor_position
     };
 
-    let Some(ray) = camera.viewport_to_world(camera_position, cursor_position) else {
+    let Some(ray) = window_to_world_ray(camera, camera_position, window, cursor_position) else {
         error!("no ray, not sure why that would happen");
         return;
     };
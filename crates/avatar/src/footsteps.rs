@@ -0,0 +1,151 @@
+// footstep sounds vary with the surface under the avatar. the step timing comes from the walk, run
+// and jump animations (see `play_current_emote`), this tracks what each nearby avatar is standing
+// on and how the step should sound.
+//
+// scene colliders pick a surface from a tag in their gltf node name, e.g. `floor_wood_collider`.
+// everything else sounds like the default ground.
+
+use bevy::prelude::*;
+use common::util::AudioReceiver;
+use scene_runner::{
+    renderer_context::RendererSceneContext, update_world::mesh_collider::SceneColliderData,
+    ContainingScene,
+};
+
+use crate::AvatarDynamicState;
+
+// avatars further than this from the listener are inaudible, so aren't checked
+const FOOTSTEP_DISTANCE: f32 = 25.0;
+// seconds between surface checks for each avatar
+const SURFACE_INTERVAL: f32 = 0.25;
+// height above a surface that still counts as standing on it
+const GROUNDED_HEIGHT: f32 = 0.2;
+
+pub struct FootstepPlugin;
+
+impl Plugin for FootstepPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_footstep_surface);
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FootstepSurface {
+    // the ground plane, and colliders without a recognised tag
+    #[default]
+    Ground,
+    Grass,
+    Sand,
+    Stone,
+    Wood,
+    Metal,
+    Water,
+}
+
+impl FootstepSurface {
+    const TAGS: [(&'static str, FootstepSurface); 12] = [
+        ("grass", Self::Grass),
+        ("dirt", Self::Grass),
+        ("sand", Self::Sand),
+        ("snow", Self::Sand),
+        ("stone", Self::Stone),
+        ("rock", Self::Stone),
+        ("concrete", Self::Stone),
+        ("wood", Self::Wood),
+        ("metal", Self::Metal),
+        ("steel", Self::Metal),
+        ("water", Self::Water),
+        ("puddle", Self::Water),
+    ];
+
+    pub fn from_collider_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        Self::TAGS
+            .iter()
+            .find(|(tag, _)| name.contains(tag))
+            .map(|(_, surface)| *surface)
+            .unwrap_or_default()
+    }
+
+    // (volume, playback rate) for steps on the surface.
+    // TODO: these only shape the shipped footstep clips, use recorded sets per surface when we have
+    // them
+    pub fn sound(&self) -> (f32, f32) {
+        match self {
+            FootstepSurface::Ground => (1.0, 1.0),
+            FootstepSurface::Grass => (0.7, 0.9),
+            FootstepSurface::Sand => (0.6, 0.8),
+            FootstepSurface::Stone => (1.0, 1.1),
+            FootstepSurface::Wood => (1.0, 0.95),
+            FootstepSurface::Metal => (1.1, 1.3),
+            FootstepSurface::Water => (0.9, 0.7),
+        }
+    }
+}
+
+// volume multiplier for a step at the given animation speed, so slow shuffles are quieter
+pub fn footstep_speed_volume(speed: f32) -> f32 {
+    speed.abs().clamp(0.3, 1.0)
+}
+
+fn update_footstep_surface(
+    mut commands: Commands,
+    mut avatars: Query<(
+        Entity,
+        &GlobalTransform,
+        &AvatarDynamicState,
+        Option<&mut FootstepSurface>,
+    )>,
+    receiver: Query<&GlobalTransform, With<AudioReceiver>>,
+    containing_scene: ContainingScene,
+    mut scenes: Query<(&RendererSceneContext, &mut SceneColliderData)>,
+    time: Res<Time>,
+    mut last_check: Local<f32>,
+) {
+    if time.elapsed_seconds() - *last_check < SURFACE_INTERVAL {
+        return;
+    }
+    *last_check = time.elapsed_seconds();
+
+    let Ok(receiver) = receiver.get_single() else {
+        return;
+    };
+
+    for (ent, transform, dynamic_state, maybe_surface) in avatars.iter_mut() {
+        let position = transform.translation();
+        // airborne avatars keep their last surface for landing
+        if position.distance(receiver.translation()) > FOOTSTEP_DISTANCE
+            || dynamic_state.ground_height > GROUNDED_HEIGHT
+        {
+            continue;
+        }
+
+        let mut surface = FootstepSurface::Ground;
+        for scene in containing_scene.get(ent) {
+            let Ok((context, mut colliders)) = scenes.get_mut(scene) else {
+                continue;
+            };
+            if let Some((height, id)) =
+                colliders.get_groundheight(context.last_update_frame, position)
+            {
+                if height <= GROUNDED_HEIGHT {
+                    surface = id
+                        .name
+                        .as_deref()
+                        .map(FootstepSurface::from_collider_name)
+                        .unwrap_or_default();
+                    break;
+                }
+            }
+        }
+
+        match maybe_surface {
+            Some(mut current) => {
+                current.set_if_neq(surface);
+            }
+            None => {
+                commands.entity(ent).try_insert(surface);
+            }
+        }
+    }
+}
@@ -17,6 +17,7 @@ use bevy::{
 };
 use bevy_console::ConsoleCommand;
 use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use bots::BotPlugin;
 use collectibles::{
     base_wearables,
     wearables::{UsedWearables, Wearable, WearableCategory, WearableUrn},
@@ -24,13 +25,16 @@ use collectibles::{
 };
 use colliders::AvatarColliderPlugin;
 use console::DoAddConsoleCommand;
+use footsteps::FootstepPlugin;
 use npc_dynamics::NpcMovementPlugin;
 use scene_material::{BoundRegion, SceneBound, SceneMaterial};
 
 pub mod animate;
 pub mod attach;
 pub mod avatar_texture;
+pub mod bots;
 pub mod colliders;
+pub mod footsteps;
 pub mod foreign_dynamics;
 pub mod mask_material;
 pub mod npc_dynamics;
@@ -83,6 +87,8 @@ impl Plugin for AvatarPlugin {
         app.add_plugins(AttachPlugin);
         app.add_plugins(AvatarColliderPlugin);
         app.add_plugins(AvatarTexturePlugin);
+        app.add_plugins(BotPlugin);
+        app.add_plugins(FootstepPlugin);
         app.add_systems(
             Update,
             (
@@ -483,6 +483,7 @@ impl Emote {
         &self,
         audio: &Assets<bevy_kira_audio::AudioSource>,
         after: f32,
+        rng: &mut fastrand::Rng,
     ) -> Result<Option<(f32, Handle<bevy_kira_audio::AudioSource>)>, CollectibleError> {
         self.sound
             .iter()
@@ -491,7 +492,7 @@ impl Emote {
                 if clips.is_empty() {
                     return Ok(None);
                 }
-                let clip = &clips[fastrand::usize(0..clips.len())];
+                let clip = &clips[rng.usize(0..clips.len())];
                 if audio.get(clip.id()).is_some() {
                     Ok(Some((*t, clip.clone())))
                 } else {
@@ -92,15 +92,32 @@ impl<T: CollectibleType> Clone for CollectibleData<T> {
     }
 }
 
+// an item bought in this session. the ownership lambdas take a while to index new tokens, so these
+// are merged into the owned lists until they show up there
+#[derive(Clone, Debug)]
+pub struct PurchasedCollectible {
+    pub urn: String,
+    pub token_id: String,
+    pub name: String,
+    pub category: String,
+    pub rarity: String,
+    pub purchased_at: String,
+}
+
 #[derive(Resource)]
 pub struct Collectibles<T: CollectibleType> {
     pointers: HashMap<CollectibleUrn<T>, PointerResult<T>>,
     pointer_request: HashSet<CollectibleUrn<T>>,
     cache: HashMap<CollectibleUrn<T>, (u32, Handle<Collectible<T>>)>,
     data_cache: HashMap<CollectibleUrn<T>, (u32, Handle<CollectibleData<T>>)>,
+    purchased: Vec<PurchasedCollectible>,
 }
 
 impl<T: CollectibleType> Collectibles<T> {
+    pub fn purchased(&self) -> &[PurchasedCollectible] {
+        &self.purchased
+    }
+
     pub fn retain(&mut self, frame: u32, f: impl Fn(&CollectibleUrn<T>) -> bool) {
         let count = self.cache.len();
         self.cache
@@ -123,6 +140,7 @@ impl<T: CollectibleType> Default for Collectibles<T> {
             pointer_request: Default::default(),
             cache: Default::default(),
             data_cache: Default::default(),
+            purchased: Default::default(),
         }
     }
 }
@@ -270,6 +288,17 @@ impl<T: CollectibleType> CollectibleManager<'_, '_, T> {
         }
     }
 
+    // record a purchase, and re-resolve the item in case it was missing before it was published
+    pub fn add_purchased(&mut self, purchase: PurchasedCollectible) {
+        if let Ok(urn) = CollectibleUrn::<T>::new(&purchase.urn) {
+            if let Some(PointerResult::Missing) = self.collectibles.pointers.get(&urn) {
+                self.collectibles.pointers.remove(&urn);
+            }
+            self.collectibles.pointer_request.insert(urn);
+        }
+        self.collectibles.purchased.push(purchase);
+    }
+
     pub fn add_builtin(&mut self, urn: CollectibleUrn<T>, value: Collectible<T>) {
         if let Some(PointerResult::Builtin(h)) = self.collectibles.pointers.get(&urn) {
             let asset = self.assets.get_mut(h).unwrap();
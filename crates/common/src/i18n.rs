@@ -0,0 +1,212 @@
+// translations for client-owned strings. packs use a small subset of the fluent (`.ftl`) format:
+// `key = value` lines, `#` comments, indented continuation lines, and `{ $name }` placeables.
+// english is built in and used for any key missing from the active language.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        OnceLock, RwLock,
+    },
+};
+
+use bevy::prelude::Component;
+
+use crate::util::project_directories;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// languages shipped in `assets/i18n`, with their own names
+pub const BUNDLED_LANGUAGES: [(&str, &str); 2] = [("en", "English"), ("es", "Español")];
+
+const ENGLISH: &str = include_str!("../../../assets/i18n/en.ftl");
+
+struct Catalog {
+    language: String,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+fn catalog() -> &'static RwLock<Catalog> {
+    static CATALOG: OnceLock<RwLock<Catalog>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        RwLock::new(Catalog {
+            language: DEFAULT_LANGUAGE.to_owned(),
+            strings: Default::default(),
+            fallback: parse_ftl(ENGLISH).expect("bad built-in english strings"),
+        })
+    })
+}
+
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+// user and downloaded packs are stored as `<config dir>/i18n/<language>.ftl`
+pub fn translation_dir() -> PathBuf {
+    project_directories().config_dir().join("i18n")
+}
+
+// languages with a downloaded or user-provided pack
+pub fn user_languages() -> Vec<String> {
+    let Ok(dir) = std::fs::read_dir(translation_dir()) else {
+        return Vec::default();
+    };
+
+    let mut languages = dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ftl"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .filter(|language| !BUNDLED_LANGUAGES.iter().any(|(code, _)| code == language))
+        .collect::<Vec<_>>();
+    languages.sort();
+    languages
+}
+
+pub fn language_name(language: &str) -> String {
+    BUNDLED_LANGUAGES
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map(|(_, name)| (*name).to_owned())
+        .unwrap_or_else(|| language.to_owned())
+}
+
+pub fn parse_ftl(source: &str) -> Result<HashMap<String, String>, String> {
+    let mut strings = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+
+    for (line_no, line) in source.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with([' ', '\t']) {
+            let Some((_, value)) = current.as_mut() else {
+                return Err(format!("line {}: continuation without a key", line_no + 1));
+            };
+            if !value.is_empty() {
+                value.push('\n');
+            }
+            value.push_str(line.trim());
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected `key = value`", line_no + 1));
+        };
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("line {}: invalid key `{key}`", line_no + 1));
+        }
+        strings.extend(current.replace((key.to_owned(), value.trim().to_owned())));
+    }
+    strings.extend(current);
+
+    Ok(strings)
+}
+
+// replace `{ $name }` placeables. unknown names are left as they are
+fn fill_placeables(template: &str, args: &[(&str, String)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let inner = rest[start + 1..start + end].trim();
+        match inner
+            .strip_prefix('$')
+            .and_then(|name| args.iter().find(|(arg, _)| *arg == name))
+        {
+            Some((_, value)) => result.push_str(value),
+            None => result.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+pub fn set_language(language: &str, strings: HashMap<String, String>) {
+    let mut catalog = catalog().write().unwrap();
+    catalog.language = language.to_owned();
+    catalog.strings = strings;
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn current_language() -> String {
+    catalog().read().unwrap().language.clone()
+}
+
+// incremented whenever the language changes, so displayed text can be refreshed
+pub fn generation() -> usize {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+pub fn tr_args(key: &str, args: &[(&str, String)]) -> String {
+    let catalog = catalog().read().unwrap();
+    match catalog
+        .strings
+        .get(key)
+        .or_else(|| catalog.fallback.get(key))
+    {
+        Some(template) => fill_placeables(template, args),
+        None => key.to_owned(),
+    }
+}
+
+pub fn tr(key: &str) -> String {
+    tr_args(key, &[])
+}
+
+// `tr!("key")` or `tr!("key", name = value, ...)`
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::tr($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::tr_args($key, &[$((stringify!($name), ($value).to_string())),+])
+    };
+}
+
+// text whose first section is kept in the current language
+#[derive(Component, Clone)]
+pub struct Localized(pub String);
+
+#[cfg(test)]
+mod test {
+    use super::{fill_placeables, parse_ftl, ENGLISH};
+
+    #[test]
+    fn parse_pack() {
+        let strings = parse_ftl(
+            "# comment\nhello = Hello, { $name }!\n\nmulti =\n    first\n    second\nempty =\n",
+        )
+        .unwrap();
+        assert_eq!(strings["hello"], "Hello, { $name }!");
+        assert_eq!(strings["multi"], "first\nsecond");
+        assert_eq!(strings["empty"], "");
+
+        assert!(parse_ftl("  orphan\n").is_err());
+        assert!(parse_ftl("no value\n").is_err());
+        assert!(parse_ftl("bad key = x\n").is_err());
+    }
+
+    #[test]
+    fn format_placeables() {
+        let args = [("name", "bob".to_owned()), ("n", "3".to_owned())];
+        assert_eq!(fill_placeables("hi {$name} x{ $n }", &args), "hi bob x3");
+        assert_eq!(fill_placeables("{ $missing } {", &args), "{ $missing } {");
+    }
+
+    #[test]
+    fn english_parses() {
+        assert!(parse_ftl(ENGLISH).is_ok());
+    }
+}
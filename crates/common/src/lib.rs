@@ -1,6 +1,9 @@
 pub mod dynamics;
+pub mod i18n;
 pub mod profile;
 pub mod rpc;
 pub mod sets;
 pub mod structs;
+pub mod trace;
+pub mod traffic;
 pub mod util;
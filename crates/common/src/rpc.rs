@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
     any::Any,
+    collections::HashMap,
     sync::{Arc, RwLock},
 };
 
@@ -116,6 +117,11 @@ pub enum RpcCall {
         scene: Entity,
         to: Vec3,
     },
+    // let the user pick one of the scene's spawn points, responds with the chosen name
+    ChooseSpawnPoint {
+        scene: Entity,
+        response: RpcResultSender<Result<String, String>>,
+    },
     SpawnPortable {
         location: PortableLocation,
         spawner: Entity,
@@ -197,8 +203,17 @@ pub enum RpcCall {
         name: String,
         success: bool,
         error: Option<String>,
+        stack: Option<String>,
+        frames: i32,
+        seconds: f32,
     },
     TestSnapshot(CompareSnapshot),
+    // scene relative, in dcl coordinates
+    TestCameraTransform {
+        scene: Entity,
+        position: [f32; 3],
+        rotation: [f32; 4],
+    },
     SendAsync {
         body: RPCSendableMessage,
         scene: Entity,
@@ -215,4 +230,27 @@ pub enum RpcCall {
         message: Option<String>,
         response: RpcResultSender<bool>,
     },
+    // add, move (`Some((parcel, label))`) or remove (`None`) a scene's map pin
+    SetMapMarker {
+        scene: Entity,
+        id: String,
+        marker: Option<(IVec2, String)>,
+    },
+    StartQuest {
+        scene: Entity,
+        quest_id: String,
+        response: RpcResultSender<Result<(), String>>,
+    },
+    AbortQuest {
+        scene: Entity,
+        instance_id: String,
+        response: RpcResultSender<Result<(), String>>,
+    },
+    // advance quests by sending an action, responds with the accepted event id
+    SendQuestEvent {
+        scene: Entity,
+        action_type: String,
+        parameters: HashMap<String, String>,
+        response: RpcResultSender<Result<String, String>>,
+    },
 }
@@ -1,8 +1,12 @@
-use std::{f32::consts::PI, num::ParseIntError, ops::Range, str::FromStr, sync::Arc};
+use std::{
+    collections::BTreeMap, f32::consts::PI, num::ParseIntError, ops::Range, path::PathBuf,
+    str::FromStr, sync::Arc,
+};
 
 use bevy::{
+    pbr::{CascadeShadowConfig, CascadeShadowConfigBuilder},
     prelude::*,
-    render::view::RenderLayers,
+    render::{renderer::RenderAdapterInfo, settings::Backends, view::RenderLayers},
     utils::{HashMap, HashSet},
 };
 use ethers_core::abi::Address;
@@ -89,7 +93,12 @@ pub struct PrimaryCamera {
     pub key_roll_left: KeyCode,
     pub key_roll_right: KeyCode,
     pub distance: f32,
-    pub sensitivity: f32,
+    // radians
+    pub fov_first_person: f32,
+    pub fov_third_person: f32,
+    // 0-1
+    pub head_bob: f32,
+    pub camera_shake: f32,
     // impl details (todo: move to separate private struct)
     pub initialized: bool,
     pub yaw: f32,
@@ -123,11 +132,103 @@ pub enum CameraOverride {
     Cinematic(CinematicSettings),
 }
 
+// depth of field for the primary camera
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DofConfig {
+    // meters
+    pub focal_distance: f32,
+    pub aperture_f_stops: f32,
+}
+
+impl Default for DofConfig {
+    fn default() -> Self {
+        Self {
+            focal_distance: 5.0,
+            aperture_f_stops: 2.0,
+        }
+    }
+}
+
+// fixed step clock and seeded randomness so tests and replays are bit-stable across runs, set with
+// `--fixed_dt <seconds>` and `--seed <n>`
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct Determinism {
+    // seconds per frame and per scene tick
+    pub fixed_dt: Option<f32>,
+    // 0 when unseeded
+    pub seed: u64,
+}
+
+// randomness for effects and placement (emote sounds, bots, teleport spawn positions), seeded from
+// `Determinism::seed` when one is set. particles need none, they are hashed from the shader time
+#[derive(Resource)]
+pub struct EffectRng(pub fastrand::Rng);
+
+impl Default for EffectRng {
+    fn default() -> Self {
+        Self(fastrand::Rng::new())
+    }
+}
+
+impl EffectRng {
+    pub fn with_seed(seed: u64) -> Self {
+        Self(fastrand::Rng::with_seed(seed))
+    }
+
+    // uniform in `min..=max`
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + self.0.f32() * (max - min)
+    }
+}
+
+// camera placement requested by scene test code. while set it replaces the normal camera
+#[derive(Resource, Default, Clone, Debug)]
+pub struct TestCamera(pub Option<Transform>);
+
+// photo mode state. while active the camera is detached from the player and flown freely, the hud
+// is hidden, and the fields below override the normal view
+#[derive(Resource, Default, Clone, Debug)]
+pub struct PhotoMode {
+    pub active: bool,
+    // radians
+    pub fov: f32,
+    pub roll: f32,
+    pub dof: Option<DofConfig>,
+    // hours, overrides the sun position
+    pub time_of_day: Option<f32>,
+    // color lut path
+    pub filter: Option<String>,
+    // render resolution multiplier
+    pub supersample: u32,
+}
+
+// saves the offscreen render target (see `visuals::render_scale`) at its full resolution, so
+// supersampled photos are not reduced to the window size
+#[derive(Resource, Default)]
+pub struct RenderTargetCapture {
+    pub target: Option<Handle<Image>>,
+    pub requests: Vec<PathBuf>,
+}
+
+impl RenderTargetCapture {
+    // false if the camera is rendering directly to the window
+    pub fn save_to_disk(&mut self, path: impl Into<PathBuf>) -> bool {
+        if self.target.is_none() {
+            return false;
+        }
+        self.requests.push(path.into());
+        true
+    }
+}
+
 impl Default for PrimaryCamera {
     fn default() -> Self {
         Self {
             mouse_key_enable_mouse: MouseButton::Right,
-            sensitivity: 5.0,
+            fov_first_person: DEFAULT_FOV.to_radians(),
+            fov_third_person: DEFAULT_FOV.to_radians(),
+            head_bob: 0.0,
+            camera_shake: 0.5,
             initialized: Default::default(),
             yaw: Default::default(),
             pitch: Default::default(),
@@ -140,6 +241,136 @@ impl Default for PrimaryCamera {
     }
 }
 
+// degrees
+pub const DEFAULT_FOV: f32 = 56.25;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CameraSettings {
+    // degrees
+    pub fov_first_person: f32,
+    pub fov_third_person: f32,
+    // 0-1
+    pub head_bob: f32,
+    pub camera_shake: f32,
+}
+
+// response of an analog input. input inside the dead zone is ignored, the rest is rescaled to 0-1,
+// raised to the power of the curve and multiplied by the sensitivity
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct AxisResponse {
+    pub sensitivity: f32,
+    // 0-1
+    pub dead_zone: f32,
+    // 1 is linear, higher values give finer control near the center
+    pub curve: f32,
+}
+
+impl Default for AxisResponse {
+    fn default() -> Self {
+        Self {
+            sensitivity: 1.0,
+            dead_zone: 0.0,
+            curve: 1.0,
+        }
+    }
+}
+
+impl AxisResponse {
+    pub fn apply(&self, value: f32) -> f32 {
+        let magnitude =
+            ((value.abs() - self.dead_zone) / (1.0 - self.dead_zone).max(0.01)).clamp(0.0, 1.0);
+        value.signum() * magnitude.powf(self.curve) * self.sensitivity
+    }
+
+    // applies to the length of a stick's deflection, so the dead zone is round
+    pub fn apply_2d(&self, value: Vec2) -> Vec2 {
+        let length = value.length();
+        if length == 0.0 {
+            return Vec2::ZERO;
+        }
+        value / length * self.apply(length)
+    }
+
+    // the input value at which the response reaches `output`
+    pub fn threshold(&self, output: f32) -> f32 {
+        let magnitude = (output / self.sensitivity)
+            .clamp(0.0, 1.0)
+            .powf(1.0 / self.curve);
+        self.dead_zone + magnitude * (1.0 - self.dead_zone)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct InputAxisSettings {
+    // mouse motion is relative, so only the sensitivity is used
+    pub mouse: AxisResponse,
+    pub move_stick: AxisResponse,
+    pub look_stick: AxisResponse,
+    // triggers act as buttons, pressed when the response reaches half way
+    pub triggers: AxisResponse,
+}
+
+impl Default for InputAxisSettings {
+    fn default() -> Self {
+        Self {
+            mouse: AxisResponse::default(),
+            move_stick: AxisResponse {
+                dead_zone: 0.15,
+                ..Default::default()
+            },
+            look_stick: AxisResponse {
+                dead_zone: 0.15,
+                curve: 2.0,
+                ..Default::default()
+            },
+            triggers: AxisResponse {
+                dead_zone: 0.1,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct InputAccessibility {
+    // sdk input action names (e.g. "IA_WALK") that toggle on each press instead of acting while
+    // held
+    pub toggle_actions: HashSet<String>,
+    pub toggle_push_to_talk: bool,
+    // multiplier for the tap, double-tap and hold timings
+    pub timing_scale: f32,
+    // seconds before held ui navigation repeats, and between repeats
+    pub repeat_delay: f32,
+    pub repeat_interval: f32,
+}
+
+impl Default for InputAccessibility {
+    fn default() -> Self {
+        Self {
+            toggle_actions: Default::default(),
+            toggle_push_to_talk: false,
+            timing_scale: 1.0,
+            repeat_delay: 0.4,
+            repeat_interval: 0.15,
+        }
+    }
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            fov_first_person: DEFAULT_FOV,
+            fov_third_person: DEFAULT_FOV,
+            head_bob: 0.0,
+            camera_shake: 0.5,
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct PrimaryPlayerRes(pub Entity);
 
@@ -168,19 +399,235 @@ pub struct ChainLink {
     pub signature: String,
 }
 
-// ephemeral identity info
+// ephemeral identity info. stored encrypted by `wallet::session_store`, older configs may still
+// hold one in `AppConfig::previous_login`
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PreviousLogin {
     pub root_address: Address,
     pub ephemeral_key: Vec<u8>,
     pub auth: Vec<ChainLink>,
+    // set when the login was signed by a walletconnect wallet, which then also handles later
+    // requests
+    #[serde(default)]
+    pub wallet_connect: Option<WalletConnectSession>,
+    // set when the login was signed by a ledger, which must then be connected for later requests
+    #[serde(default)]
+    pub ledger: Option<LedgerAccount>,
+}
+
+// an account on a ledger device
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LedgerAccount {
+    // bip32 derivation path, e.g. `m/44'/60'/0'/0/0`
+    pub path: String,
+    pub address: Address,
+}
+
+// a walletconnect session with a wallet app, enough to reconnect to it through the relay
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WalletConnectSession {
+    pub project_id: String,
+    pub topic: String,
+    // hex encoded
+    pub sym_key: String,
+    pub address: Address,
+    pub peer_name: String,
+    // unix seconds
+    pub expiry: u64,
+}
+
+impl WalletConnectSession {
+    pub fn is_expired(&self) -> bool {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now >= self.expiry
+    }
+}
+
+// kinds of event recorded in the notification center
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NotificationCategory {
+    FriendRequest,
+    Mention,
+    Download,
+    Permission,
+    Scene,
+    Quest,
+    Event,
+}
+
+impl NotificationCategory {
+    pub const ALL: [NotificationCategory; 7] = [
+        NotificationCategory::FriendRequest,
+        NotificationCategory::Mention,
+        NotificationCategory::Download,
+        NotificationCategory::Permission,
+        NotificationCategory::Scene,
+        NotificationCategory::Quest,
+        NotificationCategory::Event,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationCategory::FriendRequest => "Friend Requests",
+            NotificationCategory::Mention => "Mentions",
+            NotificationCategory::Download => "Downloads",
+            NotificationCategory::Permission => "Permissions",
+            NotificationCategory::Scene => "Scene Events",
+            NotificationCategory::Quest => "Quests",
+            NotificationCategory::Event => "Events",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HudElement {
+    Minimap,
+    Chat,
+    Toasts,
+    Hotbar,
+}
+
+impl HudElement {
+    pub const ALL: [HudElement; 4] = [
+        HudElement::Minimap,
+        HudElement::Chat,
+        HudElement::Toasts,
+        HudElement::Hotbar,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HudElement::Minimap => "Minimap",
+            HudElement::Chat => "Chat",
+            HudElement::Toasts => "Toasts",
+            HudElement::Hotbar => "Hotbar",
+        }
+    }
+}
+
+pub const HOTBAR_SLOTS: usize = 9;
+
+// an action that can be placed on the hotbar
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum HotbarAction {
+    // emote urn
+    Emote(String),
+    // a scene input action by name (e.g. `IaAction3`), as used by smart wearables
+    SceneAction(String),
+    OpenMap,
+    ToggleMic,
+    // console command line
+    Command(String),
+}
+
+impl HotbarAction {
+    // the scene actions on their usual keys, then a few emotes and shortcuts
+    pub fn default_slots() -> Vec<Option<HotbarAction>> {
+        vec![
+            Some(HotbarAction::SceneAction("IaAction3".to_owned())),
+            Some(HotbarAction::SceneAction("IaAction4".to_owned())),
+            Some(HotbarAction::SceneAction("IaAction5".to_owned())),
+            Some(HotbarAction::SceneAction("IaAction6".to_owned())),
+            Some(HotbarAction::Emote("wave".to_owned())),
+            Some(HotbarAction::Emote("fistpump".to_owned())),
+            Some(HotbarAction::Emote("raiseHand".to_owned())),
+            Some(HotbarAction::OpenMap),
+            Some(HotbarAction::ToggleMic),
+        ]
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct HudElementLayout {
+    // offset from the default position, in vmin
+    pub offset: Vec2,
+    pub scale: f32,
+    pub hidden: bool,
+}
+
+impl Default for HudElementLayout {
+    fn default() -> Self {
+        Self {
+            offset: Vec2::ZERO,
+            scale: 1.0,
+            hidden: false,
+        }
+    }
+}
+
+// screen reader narration, from least to most verbose
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum NarrationLevel {
+    #[default]
+    Off,
+    // labels of the focused ui element
+    Focus,
+    // focus, and toasts
+    Alerts,
+    // focus, toasts and chat
+    All,
+}
+
+// palette adjustment for color vision deficiencies, see `ui_core::theme`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorblindMode {
+    #[default]
+    Off,
+    // red-green, reduced green sensitivity
+    Deuteranopia,
+    // red-green, reduced red sensitivity
+    Protanopia,
+    // blue-yellow
+    Tritanopia,
+}
+
+// an erc20 token whose balance is shown alongside eth and mana
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TrackedToken {
+    pub symbol: String,
+    pub address: Address,
+    pub decimals: u32,
+    // on polygon rather than mainnet
+    #[serde(default)]
+    pub polygon: bool,
+}
+
+// a realm or world in the recent or favorite lists
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SavedRealm {
+    pub name: String,
+    pub url: String,
+    // unix time in seconds
+    pub last_visit: u64,
+    // navmap thumbnail url of the scene arrived at
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+// an upcoming event the user asked to be notified about
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct EventReminder {
+    pub id: String,
+    pub name: String,
+    pub parcel: IVec2,
+    // realm url to join for the event
+    pub realm: String,
+    // unix time in seconds
+    pub start: u64,
 }
 
 // app configuration
 #[derive(Serialize, Deserialize, Resource, Clone)]
+#[serde(default)]
 pub struct AppConfig {
     pub server: String,
     pub location: IVec2,
+    // parcels pinned on the map
+    pub map_pins: Vec<IVec2>,
     pub previous_login: Option<PreviousLogin>,
     pub graphics: GraphicsSettings,
     pub audio: AudioSettings,
@@ -190,11 +637,26 @@ pub struct AppConfig {
     pub scene_imposter_distances: Vec<f32>,
     pub scene_imposter_multisample: bool,
     pub scene_imposter_bake: SceneImposterBake,
+    // base url for downloading pre-baked imposters, mirroring the local cache layout
+    pub scene_imposter_service: Option<String>,
     pub sysinfo_visible: bool,
     pub scene_log_to_console: bool,
     pub max_avatars: usize,
+    pub max_scene_particles: usize,
+    pub power_save: PowerSaveSetting,
+    pub power_save_fps: usize,
+    // 0 for no limit
+    pub memory_limit_mb: u32,
+    pub texture_memory_limit_mb: u32,
     pub constrain_scene_ui: bool,
+    // multiplier for system ui size
+    pub ui_scale: f32,
+    pub gamepad_rumble: bool,
+    pub input_bindings: InputBindings,
+    pub input_axes: InputAxisSettings,
+    pub input_accessibility: InputAccessibility,
     pub player_settings: PrimaryUser,
+    pub camera_settings: CameraSettings,
     pub max_videos: usize,
     pub max_concurrent_remotes: usize,
     pub despawn_workaround: bool,
@@ -202,6 +664,38 @@ pub struct AppConfig {
     pub default_permissions: HashMap<PermissionType, PermissionValue>,
     pub realm_permissions: HashMap<String, HashMap<PermissionType, PermissionValue>>,
     pub scene_permissions: HashMap<String, HashMap<PermissionType, PermissionValue>>,
+    // notification categories that are neither recorded nor toasted
+    pub muted_notifications: Vec<NotificationCategory>,
+    // hud element layouts, keyed by window resolution ("1920x1080")
+    pub hud_layouts: HashMap<String, HashMap<HudElement, HudElementLayout>>,
+    // hotbar slots, triggered by the number keys 1-9
+    pub hotbar: Vec<Option<HotbarAction>>,
+    // interface language code, see `i18n`
+    pub language: String,
+    pub narration: NarrationLevel,
+    pub colorblind_mode: ColorblindMode,
+    // multipliers for interface text, and additionally for chat text
+    pub text_scale: f32,
+    pub chat_text_scale: f32,
+    // additional font files, used as fallbacks for glyphs the built in fonts lack. fonts in the
+    // data directory's `fonts` folder are also loaded.
+    pub extra_fonts: Vec<String>,
+    // walletconnect cloud project id, required to pair with wallet apps over walletconnect
+    pub wallet_connect_project_id: Option<String>,
+    // keep the login (encrypted) between launches
+    pub remember_login: bool,
+    // extra tokens to show wallet balances for
+    pub tracked_tokens: Vec<TrackedToken>,
+    // toast the track name when a scene's radio stream changes song
+    pub show_now_playing: bool,
+    // share usage metrics and crash reports. nothing is sent while off
+    pub telemetry: bool,
+    // console aliases, from name (without the leading `/`) to commands separated by `;`
+    pub console_aliases: BTreeMap<String, String>,
+    // most recent first
+    pub recent_realms: Vec<SavedRealm>,
+    pub favorite_realms: Vec<SavedRealm>,
+    pub event_reminders: Vec<EventReminder>,
 }
 
 impl Default for AppConfig {
@@ -210,6 +704,7 @@ impl Default for AppConfig {
             server: "https://sdk-team-cdn.decentraland.org/ipfs/goerli-plaza-main-latest"
                 .to_owned(),
             location: IVec2::new(78, -7),
+            map_pins: Default::default(),
             previous_login: None,
             graphics: Default::default(),
             audio: Default::default(),
@@ -219,11 +714,23 @@ impl Default for AppConfig {
             scene_imposter_distances: vec![150.0, 300.0, 600.0, 1200.0, 2400.0, 4800.0],
             scene_imposter_multisample: true,
             scene_imposter_bake: SceneImposterBake::Off,
+            scene_imposter_service: None,
             sysinfo_visible: true,
             scene_log_to_console: false,
             max_avatars: 100,
+            max_scene_particles: 10_000,
+            power_save: PowerSaveSetting::UnfocusedOrBattery,
+            power_save_fps: 30,
+            memory_limit_mb: 8192,
+            texture_memory_limit_mb: 2048,
             constrain_scene_ui: false,
+            ui_scale: 1.0,
+            gamepad_rumble: true,
+            input_bindings: Default::default(),
+            input_axes: Default::default(),
+            input_accessibility: Default::default(),
             player_settings: Default::default(),
+            camera_settings: Default::default(),
             max_videos: 1,
             max_concurrent_remotes: 32,
             #[cfg(target_os = "linux")]
@@ -234,11 +741,52 @@ impl Default for AppConfig {
             default_permissions: Default::default(),
             realm_permissions: Default::default(),
             scene_permissions: Default::default(),
+            muted_notifications: Default::default(),
+            hud_layouts: Default::default(),
+            hotbar: HotbarAction::default_slots(),
+            language: crate::i18n::DEFAULT_LANGUAGE.to_owned(),
+            narration: NarrationLevel::Off,
+            colorblind_mode: ColorblindMode::Off,
+            text_scale: 1.0,
+            chat_text_scale: 1.0,
+            extra_fonts: Vec::default(),
+            wallet_connect_project_id: None,
+            remember_login: true,
+            tracked_tokens: Vec::default(),
+            show_now_playing: true,
+            telemetry: false,
+            console_aliases: Default::default(),
+            recent_realms: Vec::default(),
+            favorite_realms: Vec::default(),
+            event_reminders: Vec::default(),
         }
     }
 }
 
 impl AppConfig {
+    // defaults for a first run on a steam deck: fullscreen, larger ui for the 800p screen, and
+    // stick response tuned for the deck's controls. graphics presets are still detected from the
+    // hardware on startup.
+    pub fn steam_deck() -> Self {
+        let mut config = Self {
+            ui_scale: 1.25,
+            ..Default::default()
+        };
+        config.graphics.window = WindowSetting::Fullscreen;
+        config.input_axes.move_stick.dead_zone = 0.1;
+        config.input_axes.look_stick.dead_zone = 0.1;
+        config.input_axes.look_stick.sensitivity = 1.2;
+        config
+    }
+
+    // a favorite or recently visited realm by name, favorites first
+    pub fn saved_realm(&self, name: &str) -> Option<&SavedRealm> {
+        self.favorite_realms
+            .iter()
+            .chain(self.recent_realms.iter())
+            .find(|realm| realm.name.eq_ignore_ascii_case(name))
+    }
+
     pub fn get_permission(
         &self,
         ty: PermissionType,
@@ -277,6 +825,7 @@ impl AppConfig {
 }
 
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct GraphicsSettings {
     pub vsync: bool,
     pub log_fps: bool,
@@ -285,14 +834,37 @@ pub struct GraphicsSettings {
     pub shadow_distance: f32,
     pub shadow_settings: ShadowSetting,
     pub shadow_caster_count: usize,
+    // 0 -> auto
+    pub shadow_cascades: usize,
+    // 0 -> auto
+    pub shadow_map_size: usize,
     pub window: WindowSetting,
     // removed until bevy window resizing bugs are fixed
     // pub fullscreen_res: FullscreenResSetting,
     pub fog: FogSetting,
     pub bloom: BloomSetting,
+    pub bloom_intensity: i32,
+    pub bloom_threshold: i32,
+    pub tonemapper: TonemapperSetting,
+    pub render_scale: RenderScaleSetting,
+    // dynamic render scale bounds, in percent of the window resolution
+    pub dynamic_scale_min: i32,
+    pub dynamic_scale_max: i32,
+    pub color_lut: Option<String>,
+    pub scene_color_luts: bool,
     pub ssao: SsaoSetting,
     pub oob: f32,
     pub ambient_brightness: i32,
+    // largest dimension for scene textures, larger textures are downscaled on load. 0 -> unlimited
+    pub max_texture_size: u32,
+    // set once a preset has been picked for the detected hardware. existing configs predate
+    // auto-detection, so keep their settings
+    #[serde(default = "default_true")]
+    pub preset_detected: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Default for GraphicsSettings {
@@ -305,24 +877,290 @@ impl Default for GraphicsSettings {
             shadow_distance: 200.0,
             shadow_settings: ShadowSetting::High,
             shadow_caster_count: 8,
+            shadow_cascades: 0,
+            shadow_map_size: 0,
             window: WindowSetting::Windowed,
             // fullscreen_res: FullscreenResSetting(UVec2::new(1280,720)),
             fog: FogSetting::Atmospheric,
             bloom: BloomSetting::Low,
+            bloom_intensity: 10,
+            bloom_threshold: 0,
+            tonemapper: TonemapperSetting::TonyMcMapface,
+            render_scale: RenderScaleSetting::Native,
+            dynamic_scale_min: 50,
+            dynamic_scale_max: 100,
+            color_lut: None,
+            scene_color_luts: true,
             ssao: SsaoSetting::Off,
             oob: 2.0,
             ambient_brightness: 50,
+            max_texture_size: 0,
+            preset_detected: false,
+        }
+    }
+}
+
+impl GraphicsSettings {
+    // bloom intensity is stored in hundredths. never negative, even from a hand-edited config, as
+    // it bounds the scene's intensity
+    pub fn bloom_intensity(&self) -> f32 {
+        self.bloom_intensity.max(0) as f32 / 100.0
+    }
+
+    // bloom threshold is stored in tenths of hdr luminance
+    pub fn bloom_threshold(&self) -> f32 {
+        self.bloom_threshold as f32 / 10.0
+    }
+
+    // bounds for dynamic render scale as fractions of the window resolution
+    pub fn dynamic_scale_range(&self) -> (f32, f32) {
+        let max = self.dynamic_scale_max.clamp(25, 100) as f32 / 100.0;
+        let min = self.dynamic_scale_min.clamp(25, 100) as f32 / 100.0;
+        (min.min(max), max)
+    }
+
+    pub fn shadow_cascades(&self, tier: GpuTier) -> usize {
+        match self.shadow_settings {
+            ShadowSetting::Off | ShadowSetting::Low => 1,
+            ShadowSetting::High => match (self.shadow_cascades, tier) {
+                (0, GpuTier::Low) => 2,
+                (0, GpuTier::Medium) => 3,
+                (0, GpuTier::High) => 4,
+                (count, _) => count.clamp(1, 4),
+            },
+        }
+    }
+
+    pub fn shadow_map_size(&self, tier: GpuTier) -> usize {
+        match (self.shadow_map_size, tier) {
+            (0, GpuTier::Low) => 1024,
+            (0, GpuTier::Medium) => 2048,
+            (0, GpuTier::High) => 4096,
+            (size, _) => size,
+        }
+    }
+
+    pub fn cascade_shadow_config(&self, tier: GpuTier) -> CascadeShadowConfig {
+        let num_cascades = self.shadow_cascades(tier);
+        // keep the near cascade roughly the same size regardless of the count
+        let first_cascade_far_bound = self.shadow_distance
+            / match num_cascades {
+                1 => 1.0,
+                2 => 4.0,
+                3 => 8.0,
+                _ => 15.0,
+            };
+
+        CascadeShadowConfigBuilder {
+            num_cascades,
+            minimum_distance: 0.1,
+            maximum_distance: self.shadow_distance,
+            first_cascade_far_bound,
+            overlap_proportion: 0.2,
+        }
+        .build()
+    }
+}
+
+// rough classification of the gpu, used to pick values for `auto` settings
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GpuTier {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl GpuTier {
+    pub fn from_adapter(info: Option<&RenderAdapterInfo>) -> Self {
+        let Some(info) = info else {
+            return Self::default();
+        };
+
+        if Backends::from(info.backend) == Backends::GL {
+            return Self::Low;
+        }
+
+        // the device type enum isn't re-exported by bevy, so match on its name
+        match format!("{:?}", info.device_type).as_str() {
+            "DiscreteGpu" => Self::High,
+            "IntegratedGpu" => Self::Medium,
+            _ => Self::Low,
         }
     }
 }
 
+// groups of graphics and performance settings. presets are not stored, a config matching a
+// preset's values reports that preset and anything else is custom
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GraphicsPreset {
+    Custom,
+    Low,
+    Medium,
+    High,
+    Ultra,
+}
+
+impl GraphicsPreset {
+    pub fn apply_to(&self, config: &mut AppConfig) {
+        let graphics = &mut config.graphics;
+        let (shadows, fog, ssao, bloom, max_avatars, load_distance, max_texture_size) = match self {
+            GraphicsPreset::Custom => return,
+            GraphicsPreset::Low => (
+                ShadowSetting::Off,
+                FogSetting::Basic,
+                SsaoSetting::Off,
+                BloomSetting::Off,
+                20,
+                20.0,
+                512,
+            ),
+            GraphicsPreset::Medium => (
+                ShadowSetting::Low,
+                FogSetting::Atmospheric,
+                SsaoSetting::Off,
+                BloomSetting::Low,
+                50,
+                50.0,
+                1024,
+            ),
+            GraphicsPreset::High => (
+                ShadowSetting::High,
+                FogSetting::Atmospheric,
+                SsaoSetting::Low,
+                BloomSetting::Low,
+                100,
+                75.0,
+                2048,
+            ),
+            GraphicsPreset::Ultra => (
+                ShadowSetting::High,
+                FogSetting::Volumetric,
+                SsaoSetting::High,
+                BloomSetting::High,
+                100,
+                100.0,
+                0,
+            ),
+        };
+
+        graphics.shadow_settings = shadows;
+        graphics.fog = fog;
+        graphics.ssao = ssao;
+        graphics.bloom = bloom;
+        graphics.max_texture_size = max_texture_size;
+        config.max_avatars = max_avatars;
+        config.scene_load_distance = load_distance;
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        [Self::Low, Self::Medium, Self::High, Self::Ultra]
+            .into_iter()
+            .find(|preset| {
+                let mut preset_config = config.clone();
+                preset.apply_to(&mut preset_config);
+                preset_config.graphics.shadow_settings == config.graphics.shadow_settings
+                    && preset_config.graphics.fog == config.graphics.fog
+                    && preset_config.graphics.ssao == config.graphics.ssao
+                    && preset_config.graphics.bloom == config.graphics.bloom
+                    && preset_config.graphics.max_texture_size == config.graphics.max_texture_size
+                    && preset_config.max_avatars == config.max_avatars
+                    && preset_config.scene_load_distance == config.scene_load_distance
+            })
+            .unwrap_or(Self::Custom)
+    }
+
+    // pick a starting preset from the adapter. wgpu doesn't expose vram, so the device name is
+    // used to spot recent high-end cards
+    pub fn detect(info: Option<&RenderAdapterInfo>) -> Self {
+        const ULTRA_MARKERS: [&str; 6] =
+            ["RTX 40", "RTX 50", "RTX 3080", "RTX 3090", "RX 7", "RX 9"];
+
+        match GpuTier::from_adapter(info) {
+            GpuTier::Low => Self::Low,
+            GpuTier::Medium => Self::Medium,
+            GpuTier::High => {
+                let name = info
+                    .map(|info| info.name.to_uppercase())
+                    .unwrap_or_default();
+                if ULTRA_MARKERS.iter().any(|marker| name.contains(marker)) {
+                    Self::Ultra
+                } else {
+                    Self::High
+                }
+            }
+        }
+    }
+}
+
+// the mixer buses. every sound is played on one of these, and its volume is scaled by the bus
+// and the master volume
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioBus {
+    Master,
+    // audio sources and video players from scenes
+    Scene,
+    // music and radio streams from scenes
+    Media,
+    Voice,
+    // ui sounds
+    System,
+    // emotes and footsteps
+    Avatar,
+}
+
+impl AudioBus {
+    pub const ALL: [AudioBus; 6] = [
+        AudioBus::Master,
+        AudioBus::Scene,
+        AudioBus::Media,
+        AudioBus::Voice,
+        AudioBus::System,
+        AudioBus::Avatar,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AudioBus::Master => "master",
+            AudioBus::Scene => "scene",
+            AudioBus::Media => "media",
+            AudioBus::Voice => "voice",
+            AudioBus::System => "system",
+            AudioBus::Avatar => "avatar",
+        }
+    }
+}
+
+impl FromStr for AudioBus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|bus| bus.name() == s.to_lowercase())
+            .ok_or_else(|| format!("unknown audio bus `{s}`"))
+    }
+}
+
 #[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
 pub struct AudioSettings {
     pub master: i32, // 0-100
     pub voice: i32,
     pub scene: i32,
+    pub media: i32,
     pub system: i32,
     pub avatar: i32,
+    // 0-100, how far scene and media audio drop while someone nearby is talking
+    pub ducking: i32,
+    // play avatar footstep sounds
+    pub footsteps: bool,
+    // binaural spatialization of voice chat for headphones instead of stereo panning
+    pub headphones: bool,
+    // device names, or the system default when unset or unplugged
+    pub output_device: Option<String>,
+    pub input_device: Option<String>,
 }
 
 impl Default for AudioSettings {
@@ -331,24 +1169,69 @@ impl Default for AudioSettings {
             master: 100,
             voice: 100,
             scene: 100,
+            media: 100,
             system: 100,
             avatar: 100,
+            ducking: 50,
+            footsteps: true,
+            headphones: false,
+            output_device: None,
+            input_device: None,
         }
     }
 }
 
 impl AudioSettings {
+    pub fn get(&self, bus: AudioBus) -> i32 {
+        match bus {
+            AudioBus::Master => self.master,
+            AudioBus::Scene => self.scene,
+            AudioBus::Media => self.media,
+            AudioBus::Voice => self.voice,
+            AudioBus::System => self.system,
+            AudioBus::Avatar => self.avatar,
+        }
+    }
+
+    pub fn set(&mut self, bus: AudioBus, value: i32) {
+        let value = value.clamp(0, 100);
+        match bus {
+            AudioBus::Master => self.master = value,
+            AudioBus::Scene => self.scene = value,
+            AudioBus::Media => self.media = value,
+            AudioBus::Voice => self.voice = value,
+            AudioBus::System => self.system = value,
+            AudioBus::Avatar => self.avatar = value,
+        }
+    }
+
+    // the bus volume including master, as an amplitude
+    pub fn volume(&self, bus: AudioBus) -> f32 {
+        match bus {
+            AudioBus::Master => self.master as f32 / 100.0,
+            _ => (self.get(bus) * self.master) as f32 / 10_000.0,
+        }
+    }
+
     pub fn voice(&self) -> f32 {
-        (self.voice * self.master) as f32 / 10_000.0
+        self.volume(AudioBus::Voice)
     }
     pub fn scene(&self) -> f32 {
-        (self.scene * self.master) as f32 / 10_000.0
+        self.volume(AudioBus::Scene)
+    }
+    pub fn media(&self) -> f32 {
+        self.volume(AudioBus::Media)
     }
     pub fn system(&self) -> f32 {
-        (self.system * self.master) as f32 / 10_000.0
+        self.volume(AudioBus::System)
     }
     pub fn avatar(&self) -> f32 {
-        (self.avatar * self.master) as f32 / 10_000.0
+        self.volume(AudioBus::Avatar)
+    }
+
+    // multiplier for ducked buses while voice is active
+    pub fn ducked(&self) -> f32 {
+        1.0 - self.ducking.clamp(0, 100) as f32 / 100.0
     }
 }
 
@@ -384,6 +1267,7 @@ pub enum FogSetting {
     Off,
     Basic,
     Atmospheric,
+    Volumetric,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
@@ -393,6 +1277,77 @@ pub enum BloomSetting {
     High,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TonemapperSetting {
+    TonyMcMapface,
+    AgX,
+    Reinhard,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RenderScaleSetting {
+    Native,
+    Quality,
+    Balanced,
+    Performance,
+    Dynamic,
+}
+
+impl RenderScaleSetting {
+    // fraction of the window resolution the 3d view is rendered at
+    pub fn scale(&self) -> f32 {
+        match self {
+            RenderScaleSetting::Native => 1.0,
+            RenderScaleSetting::Quality => 1.0 / 1.5,
+            RenderScaleSetting::Balanced => 1.0 / 1.7,
+            RenderScaleSetting::Performance => 0.5,
+            // see DynamicRenderScale
+            RenderScaleSetting::Dynamic => 1.0,
+        }
+    }
+}
+
+// render scale chosen by dynamic resolution to hold the fps target
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct DynamicRenderScale {
+    pub scale: f32,
+    pub active: bool,
+}
+
+impl Default for DynamicRenderScale {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            active: false,
+        }
+    }
+}
+
+// smoothed frame timings from the scene loop
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct FrameLoad {
+    // seconds per frame spent outside of scene script execution
+    pub non_scene_secs: f32,
+    // seconds per frame spent running the scene loop, excluding the sleep to the frame target
+    pub scene_secs: f32,
+    // the frame duration the scene loop aims for
+    pub target_secs: f32,
+}
+
+// when to throttle frame rate and effects to save power
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerSaveSetting {
+    Off,
+    Unfocused,
+    Battery,
+    UnfocusedOrBattery,
+    Always,
+}
+
+// whether power saving is currently in effect
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerSaving(pub bool);
+
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum SsaoSetting {
     Off,
@@ -406,6 +1361,44 @@ pub enum AudioDecoderError {
     Other(String),
 }
 
+// estimated memory use and how aggressively we are currently trying to reduce it
+#[derive(Resource, Default, Debug, Clone)]
+pub struct MemoryPressure {
+    pub rss_bytes: Option<u64>,
+    pub texture_bytes: u64,
+    // 0 when within limits, increases while limits are exceeded
+    pub level: u32,
+}
+
+impl MemoryPressure {
+    pub const MAX_LEVEL: u32 = 4;
+
+    // scale applied to the scene load distance
+    pub fn load_distance_scale(&self) -> f32 {
+        match self.level {
+            0..=2 => 1.0,
+            3 => 0.75,
+            _ => 0.5,
+        }
+    }
+
+    // whether scenes beyond the load distance should be kept
+    pub fn keep_unload_margin(&self) -> bool {
+        self.level < 2
+    }
+
+    // max size for content textures, halving the configured size (or 2048 if unlimited)
+    pub fn max_texture_size(&self, configured: u32) -> u32 {
+        if self.level < 3 {
+            return configured;
+        }
+        match configured {
+            0 => 2048,
+            size => (size / 2).max(256),
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct SceneLoadDistance {
     pub load: f32,
@@ -489,11 +1482,22 @@ impl SpawnPosition {
     }
 }
 
+// where the camera should look after spawning, relative to the scene base
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct SpawnCameraTarget {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct SpawnPoint {
     pub name: Option<String>,
+    #[serde(default)]
     pub default: bool,
     pub position: SpawnPosition,
+    #[serde(rename = "cameraTarget")]
+    pub camera_target: Option<SpawnCameraTarget>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -505,6 +1509,21 @@ pub struct SceneMetaScene {
 #[derive(Deserialize, Debug)]
 pub struct SceneDisplay {
     pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "navmapThumbnail")]
+    pub navmap_thumbnail: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SceneContact {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenePolicy {
+    pub content_rating: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -515,6 +1534,9 @@ pub struct SceneMeta {
     pub scene: SceneMetaScene,
     pub runtime_version: Option<String>,
     pub spawn_points: Option<Vec<SpawnPoint>>,
+    pub contact: Option<SceneContact>,
+    pub required_permissions: Option<Vec<String>>,
+    pub policy: Option<ScenePolicy>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -540,6 +1562,7 @@ pub enum PermissionType {
     Fetch,
     Websocket,
     OpenUrl,
+    ShowStreams,
 }
 
 #[derive(Resource)]
@@ -588,6 +1611,7 @@ pub enum SettingsTab {
     Discover,
     Settings,
     Permissions,
+    Gallery,
 }
 
 #[derive(Event, Clone)]
@@ -596,6 +1620,10 @@ pub struct ShowSettingsEvent(pub SettingsTab);
 #[derive(Event, Clone)]
 pub struct ShowProfileEvent(pub Address);
 
+// open the marketplace purchase dialog for a wearable or emote item urn
+#[derive(Event, Clone)]
+pub struct ShowPurchaseEvent(pub String);
+
 #[derive(Event, Clone)]
 pub struct SystemAudio(pub String);
 
@@ -617,6 +1645,295 @@ impl From<&String> for SystemAudio {
     }
 }
 
+// gamepad force feedback. motor intensities are 0-1, ramping up over `attack` seconds, holding for
+// `sustain` and fading out over `release`. overlapping requests combine by taking the strongest
+#[derive(Event, Clone, Copy, Debug)]
+pub struct RumbleRequest {
+    pub strong: f32,
+    pub weak: f32,
+    pub attack: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl RumbleRequest {
+    pub fn pulse(strong: f32, weak: f32, duration: f32) -> Self {
+        Self {
+            strong,
+            weak,
+            attack: 0.0,
+            sustain: duration,
+            release: 0.0,
+        }
+    }
+
+    pub fn with_envelope(self, attack: f32, release: f32) -> Self {
+        Self {
+            attack,
+            release,
+            ..self
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.attack + self.sustain + self.release
+    }
+
+    // envelope multiplier `elapsed` seconds after the request started
+    pub fn envelope(&self, elapsed: f32) -> f32 {
+        if elapsed < self.attack {
+            elapsed / self.attack
+        } else if elapsed < self.attack + self.sustain {
+            1.0
+        } else if elapsed < self.duration() {
+            (self.duration() - elapsed) / self.release
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
+pub enum InputItem {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+    Any,
+}
+
+impl std::fmt::Display for InputItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputItem::Key(k) => f.write_str(key_to_str(k).as_str()),
+            InputItem::Mouse(m) => f.write_fmt(format_args!("{:?}", m)),
+            InputItem::Gamepad(b) => f.write_str(gamepad_button_to_str(b).as_str()),
+            InputItem::Any => f.write_str("(Any)"),
+        }
+    }
+}
+
+fn key_to_str(key: &KeyCode) -> String {
+    use KeyCode::*;
+    let str = match key {
+        Digit0 => "0",
+        Digit1 => "1",
+        Digit2 => "2",
+        Digit3 => "3",
+        Digit4 => "4",
+        Digit5 => "5",
+        Digit6 => "6",
+        Digit7 => "7",
+        Digit8 => "8",
+        Digit9 => "9",
+        Space => "Space",
+        ShiftLeft => "Left Shift",
+        ShiftRight => "Right Shift",
+        ControlLeft => "Left Ctrl",
+        ControlRight => "Right Ctrl",
+        AltLeft => "Left Alt",
+        AltRight => "Right Alt",
+        ArrowUp => "Up",
+        ArrowDown => "Down",
+        ArrowLeft => "Left",
+        ArrowRight => "Right",
+        _ => {
+            // strip the `Key` prefix from letters
+            let name = format!("{:?}", key);
+            return name.strip_prefix("Key").unwrap_or(&name).to_owned();
+        }
+    };
+    str.to_owned()
+}
+
+fn gamepad_button_to_str(button: &GamepadButtonType) -> String {
+    use GamepadButtonType::*;
+    let str = match button {
+        South => "A",
+        East => "B",
+        West => "X",
+        North => "Y",
+        LeftTrigger => "LB",
+        RightTrigger => "RB",
+        LeftTrigger2 => "LT",
+        RightTrigger2 => "RT",
+        LeftThumb => "L3",
+        RightThumb => "R3",
+        DPadUp => "D-Pad Up",
+        DPadDown => "D-Pad Down",
+        DPadLeft => "D-Pad Left",
+        DPadRight => "D-Pad Right",
+        _ => return format!("{:?}", button),
+    };
+    str.to_owned()
+}
+
+// keyboard modifier held for a chord, either side of the keyboard
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
+pub enum InputModifier {
+    Ctrl,
+    Shift,
+    Alt,
+}
+
+impl InputModifier {
+    pub const ALL: [InputModifier; 3] = [Self::Ctrl, Self::Shift, Self::Alt];
+
+    pub fn keys(&self) -> [KeyCode; 2] {
+        match self {
+            InputModifier::Ctrl => [KeyCode::ControlLeft, KeyCode::ControlRight],
+            InputModifier::Shift => [KeyCode::ShiftLeft, KeyCode::ShiftRight],
+            InputModifier::Alt => [KeyCode::AltLeft, KeyCode::AltRight],
+        }
+    }
+
+    pub fn from_key(key: KeyCode) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.keys().contains(&key))
+    }
+}
+
+// how an input must be pressed to trigger a binding
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Default, Serialize, Deserialize)]
+pub enum InputGesture {
+    // down while held
+    #[default]
+    Press,
+    // a short press, triggered on release
+    Tap,
+    // a second press shortly after a tap, down while the second press is held
+    DoubleTap,
+    // triggered after holding for a while, down until released
+    Hold,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
+pub struct InputBinding {
+    pub item: InputItem,
+    #[serde(default)]
+    pub modifier: Option<InputModifier>,
+    #[serde(default)]
+    pub gesture: InputGesture,
+}
+
+impl From<InputItem> for InputBinding {
+    fn from(item: InputItem) -> Self {
+        Self {
+            item,
+            modifier: None,
+            gesture: InputGesture::Press,
+        }
+    }
+}
+
+impl std::fmt::Display for InputBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.gesture {
+            InputGesture::Press => (),
+            InputGesture::Tap => f.write_str("Tap ")?,
+            InputGesture::DoubleTap => f.write_str("Double ")?,
+            InputGesture::Hold => f.write_str("Hold ")?,
+        }
+        if let Some(modifier) = self.modifier {
+            f.write_fmt(format_args!("{:?}+", modifier))?;
+        }
+        self.item.fmt(f)
+    }
+}
+
+// parses bindings written as e.g. "k", "ctrl+k", "double+w" or "hold+shift+mouseright"
+impl FromStr for InputBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s
+            .split('+')
+            .map(|part| part.trim().to_lowercase())
+            .collect::<Vec<_>>();
+        let item = parts.pop().unwrap_or_default();
+        let item = str_to_item(&item).ok_or_else(|| format!("unknown key `{item}`"))?;
+
+        let mut binding = InputBinding::from(item);
+        for part in parts {
+            match part.as_str() {
+                "tap" => binding.gesture = InputGesture::Tap,
+                "double" => binding.gesture = InputGesture::DoubleTap,
+                "hold" => binding.gesture = InputGesture::Hold,
+                "ctrl" => binding.modifier = Some(InputModifier::Ctrl),
+                "shift" => binding.modifier = Some(InputModifier::Shift),
+                "alt" => binding.modifier = Some(InputModifier::Alt),
+                other => return Err(format!("unknown modifier `{other}`")),
+            }
+        }
+        Ok(binding)
+    }
+}
+
+fn str_to_item(name: &str) -> Option<InputItem> {
+    use KeyCode::*;
+    const LETTERS: [KeyCode; 26] = [
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM, KeyN, KeyO,
+        KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+    ];
+    const DIGITS: [KeyCode; 10] = [
+        Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+    ];
+    const FUNCTION: [KeyCode; 12] = [F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12];
+
+    let key = match name {
+        "space" => Space,
+        "enter" => Enter,
+        "tab" => Tab,
+        "backspace" => Backspace,
+        "escape" | "esc" => Escape,
+        "up" => ArrowUp,
+        "down" => ArrowDown,
+        "left" => ArrowLeft,
+        "right" => ArrowRight,
+        "home" => Home,
+        "end" => End,
+        "pageup" => PageUp,
+        "pagedown" => PageDown,
+        "insert" => Insert,
+        "delete" => Delete,
+        "`" | "backquote" => Backquote,
+        "mouseleft" => return Some(InputItem::Mouse(MouseButton::Left)),
+        "mouseright" => return Some(InputItem::Mouse(MouseButton::Right)),
+        "mousemiddle" => return Some(InputItem::Mouse(MouseButton::Middle)),
+        "mouse4" => return Some(InputItem::Mouse(MouseButton::Back)),
+        "mouse5" => return Some(InputItem::Mouse(MouseButton::Forward)),
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c @ 'a'..='z'), None) => LETTERS[(c as u8 - b'a') as usize],
+                (Some(c @ '0'..='9'), None) => DIGITS[(c as u8 - b'0') as usize],
+                (Some('f'), Some(_)) => *name[1..]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| FUNCTION.get(n.checked_sub(1)?))?,
+                _ => return None,
+            }
+        }
+    };
+    Some(InputItem::Key(key))
+}
+
+// a console command run when the binding is pressed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CommandBinding {
+    pub binding: InputBinding,
+    // e.g. "/changerealm main"
+    pub command: String,
+}
+
+// user input bindings per profile, keyed by sdk input action name (e.g. "IA_JUMP"). actions
+// that are not present use the default bindings
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct InputBindings {
+    pub keyboard: HashMap<String, InputBinding>,
+    pub gamepad: HashMap<String, InputBinding>,
+    pub commands: Vec<CommandBinding>,
+}
+
 #[derive(Resource, Default)]
 pub struct PermissionTarget {
     pub scene: Option<Entity>,
@@ -635,6 +1952,8 @@ pub const PRIMARY_AVATAR_LIGHT_LAYER: RenderLayers =
 pub const PROFILE_UI_RENDERLAYER: RenderLayers = RenderLayers::layer(3);
 // layer for ground
 pub const GROUND_RENDERLAYER: RenderLayers = RenderLayers::layer(4);
+// layer for the upscaled 3d view when rendering below native resolution
+pub const UPSCALE_RENDERLAYER: RenderLayers = RenderLayers::layer(6);
 
 #[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum SceneImposterBake {
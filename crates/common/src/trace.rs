@@ -0,0 +1,162 @@
+// performance trace capture for `/trace`. spans from the main world schedules, the scene loop and
+// individual scenes are collected while a capture is running and written as chrome trace event
+// json, which loads in chrome://tracing, perfetto or tracy (via `tracy-import-chrome`).
+
+use std::{
+    borrow::Cow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, OnceLock,
+    },
+    time::Instant,
+};
+
+use serde_json::json;
+
+// the main thread, shared by the main world schedules and the scene loop
+pub const MAIN_TRACK: &str = "main world";
+
+// bounds memory if a capture is left running
+const MAX_SPANS: usize = 2_000_000;
+
+static TRACING: AtomicBool = AtomicBool::new(false);
+
+struct TraceSpan {
+    track: usize,
+    category: &'static str,
+    name: Cow<'static, str>,
+    start: Instant,
+    end: Instant,
+}
+
+#[derive(Default)]
+struct Capture {
+    started: Option<Instant>,
+    tracks: Vec<String>,
+    spans: Vec<TraceSpan>,
+    dropped: usize,
+}
+
+fn capture() -> &'static Mutex<Capture> {
+    static CAPTURE: OnceLock<Mutex<Capture>> = OnceLock::new();
+    CAPTURE.get_or_init(Default::default)
+}
+
+pub fn trace_active() -> bool {
+    TRACING.load(Ordering::Relaxed)
+}
+
+// discards any previous capture
+pub fn start_trace() {
+    *capture().lock().unwrap() = Capture {
+        started: Some(Instant::now()),
+        ..Default::default()
+    };
+    TRACING.store(true, Ordering::Relaxed);
+}
+
+// returns the capture as chrome trace json, and the number of spans recorded
+pub fn stop_trace() -> Option<(String, usize)> {
+    if !TRACING.swap(false, Ordering::Relaxed) {
+        return None;
+    }
+    let capture = std::mem::take(&mut *capture().lock().unwrap());
+    let started = capture.started?;
+
+    let micros = |instant: Instant| {
+        instant
+            .checked_duration_since(started)
+            .unwrap_or_default()
+            .as_secs_f64()
+            * 1e6
+    };
+    let tracks = capture.tracks.iter().enumerate().map(|(tid, name)| {
+        json!({
+            "name": "thread_name",
+            "ph": "M",
+            "pid": 1,
+            "tid": tid,
+            "args": { "name": name },
+        })
+    });
+    let spans = capture.spans.iter().map(|span| {
+        json!({
+            "name": span.name,
+            "cat": span.category,
+            "ph": "X",
+            "pid": 1,
+            "tid": span.track,
+            "ts": micros(span.start),
+            "dur": (micros(span.end) - micros(span.start)).max(0.0),
+        })
+    });
+
+    let json = json!({
+        "traceEvents": tracks.chain(spans).collect::<Vec<_>>(),
+        "displayTimeUnit": "ms",
+        "otherData": { "droppedSpans": capture.dropped },
+    });
+    Some((json.to_string(), capture.spans.len()))
+}
+
+pub fn record_span(
+    track: &str,
+    category: &'static str,
+    name: impl Into<Cow<'static, str>>,
+    start: Instant,
+    end: Instant,
+) {
+    if !trace_active() {
+        return;
+    }
+
+    let mut capture = capture().lock().unwrap();
+    if capture.spans.len() >= MAX_SPANS {
+        capture.dropped += 1;
+        return;
+    }
+    let track = match capture.tracks.iter().position(|t| t == track) {
+        Some(ix) => ix,
+        None => {
+            capture.tracks.push(track.to_owned());
+            capture.tracks.len() - 1
+        }
+    };
+    capture.spans.push(TraceSpan {
+        track,
+        category,
+        name: name.into(),
+        start,
+        end,
+    });
+}
+
+// records a span from creation until dropped
+pub struct SpanGuard {
+    track: &'static str,
+    category: &'static str,
+    name: Option<Cow<'static, str>>,
+    start: Instant,
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        if let Some(name) = self.name.take() {
+            record_span(self.track, self.category, name, self.start, Instant::now());
+        }
+    }
+}
+
+pub fn trace_span(
+    track: &'static str,
+    category: &'static str,
+    name: impl Into<Cow<'static, str>>,
+) -> SpanGuard {
+    SpanGuard {
+        track,
+        category,
+        // skip the allocation when not capturing
+        name: trace_active().then(|| name.into()),
+        start: Instant::now(),
+    }
+}
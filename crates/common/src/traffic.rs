@@ -0,0 +1,83 @@
+// network traffic counters for the traffic debug panel, by transport, peer message type and scene.
+// they're recorded from transport tasks and scene threads, so they live in a static rather than a
+// resource.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, OnceLock,
+    },
+};
+
+// detailed counters take a lock per message (and transports decode outbound packets to find the
+// type), so they're only recorded while the panel is open
+static TRAFFIC_DETAIL: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum TrafficGroup {
+    Transport,
+    Message,
+    // scene network apis (fetch, signed fetch, websocket), keyed by scene hash
+    Scene(&'static str),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    In,
+    Out,
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TrafficCount {
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    pub messages_in: u64,
+    pub messages_out: u64,
+}
+
+type Counters = Mutex<HashMap<(TrafficGroup, String), TrafficCount>>;
+
+fn counters() -> &'static Counters {
+    static COUNTERS: OnceLock<Counters> = OnceLock::new();
+    COUNTERS.get_or_init(Default::default)
+}
+
+pub fn set_traffic_detail(enabled: bool) {
+    TRAFFIC_DETAIL.store(enabled, Ordering::Relaxed);
+}
+
+pub fn traffic_detail() -> bool {
+    TRAFFIC_DETAIL.load(Ordering::Relaxed)
+}
+
+pub fn record_traffic(group: TrafficGroup, name: &str, direction: Direction, bytes: usize) {
+    if !traffic_detail() {
+        return;
+    }
+
+    let mut counters = counters().lock().unwrap();
+    let count = counters.entry((group, name.to_owned())).or_default();
+    match direction {
+        Direction::In => {
+            count.bytes_in += bytes as u64;
+            count.messages_in += 1;
+        }
+        Direction::Out => {
+            count.bytes_out += bytes as u64;
+            count.messages_out += 1;
+        }
+    }
+}
+
+// running totals since detail was first enabled
+pub fn traffic_totals() -> Vec<(TrafficGroup, String, TrafficCount)> {
+    let mut totals = counters()
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|((group, name), count)| (*group, name.clone(), *count))
+        .collect::<Vec<_>>();
+    totals.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+    totals
+}
@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, marker::PhantomData, path::PathBuf};
+use std::{collections::VecDeque, marker::PhantomData, path::PathBuf, sync::OnceLock};
 
 use bevy::{
     app::Update,
@@ -9,13 +9,14 @@ use bevy::{
         world::Command,
     },
     hierarchy::DespawnRecursiveExt,
-    math::Vec3,
+    math::{Ray3d, Vec2, Vec3},
     prelude::{
-        despawn_with_children_recursive, BuildWorldChildren, Bundle, Entity, GlobalTransform,
-        IntoSystemConfigs, Plugin, With, World,
+        despawn_with_children_recursive, BuildWorldChildren, Bundle, Camera, Entity,
+        GlobalTransform, IntoSystemConfigs, Plugin, With, World,
     },
     render::view::{Layer, RenderLayers},
     tasks::Task,
+    window::Window,
 };
 use ethers_core::types::H160;
 use futures_lite::future;
@@ -33,6 +34,16 @@ pub fn config_file() -> PathBuf {
     project_directories().config_dir().join("config.json")
 }
 
+// user color luts, served as the `luts://` asset source
+pub fn user_lut_dir() -> PathBuf {
+    project_directories().config_dir().join("luts")
+}
+
+// photo mode screenshots
+pub fn gallery_dir() -> PathBuf {
+    project_directories().data_dir().join("gallery")
+}
+
 // get results from a task
 pub trait TaskExt {
     type Output;
@@ -314,6 +325,32 @@ pub fn project_directories() -> directories::ProjectDirs {
     directories::ProjectDirs::from("org", "decentraland", "BevyExplorer").unwrap()
 }
 
+// steam sets `SteamDeck=1` for games launched in gaming mode. in desktop mode we check the board.
+pub fn is_steam_deck() -> bool {
+    static IS_STEAM_DECK: OnceLock<bool> = OnceLock::new();
+
+    *IS_STEAM_DECK.get_or_init(|| {
+        if std::env::var("SteamDeck").is_ok_and(|v| v == "1") {
+            return true;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let read = |name: &str| {
+                std::fs::read_to_string(format!("/sys/devices/virtual/dmi/id/{name}"))
+                    .map(|s| s.trim().to_owned())
+                    .unwrap_or_default()
+            };
+            // jupiter is the lcd model, galileo the oled
+            read("board_vendor") == "Valve"
+                && matches!(read("product_name").as_str(), "Jupiter" | "Galileo")
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        false
+    })
+}
+
 // commands to modify components
 
 pub struct ModifyComponent<C: Component, F: FnOnce(&mut C) + Send + Sync + 'static> {
@@ -419,6 +456,10 @@ macro_rules! anim_last_system {
 #[derive(Component)]
 pub struct AudioReceiver;
 
+// extra volume multiplier for a non-scene audio emitter, e.g. footsteps on soft surfaces
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct EmitterVolume(pub f32);
+
 #[derive(SystemParam)]
 pub struct VolumePanning<'w, 's> {
     receiver: Query<'w, 's, &'static GlobalTransform, With<AudioReceiver>>,
@@ -440,6 +481,12 @@ impl VolumePanning<'_, '_> {
 
         (volume, panning)
     }
+
+    pub fn distance(&self, translation: Vec3) -> f32 {
+        self.receiver
+            .get_single()
+            .map_or(0.0, |receiver| receiver.translation().distance(translation))
+    }
 }
 
 pub fn camera_to_render_layers<'a>(
@@ -454,3 +501,15 @@ pub fn camera_to_render_layers<'a>(
         }
     })
 }
+
+// like `Camera::viewport_to_world`, but takes a position in window coordinates, which differ
+// from the camera's viewport coordinates when the camera renders at a reduced resolution
+pub fn window_to_world_ray(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    window: &Window,
+    position: Vec2,
+) -> Option<Ray3d> {
+    let scale = camera.logical_viewport_size()? / window.size();
+    camera.viewport_to_world(camera_transform, position * scale)
+}
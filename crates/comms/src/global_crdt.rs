@@ -65,6 +65,10 @@ impl Plugin for GlobalCrdtPlugin {
 pub enum PlayerMessage {
     PlayerData(rfc4::packet::Message),
     AudioStream(Box<StreamingSoundData<AudioDecoderError>>),
+    // the player started sharing video (a screen share or camera)
+    VideoStream(mpsc::Receiver<ForeignVideoFrame>),
+    // the transport's voice activity detection started or stopped hearing the player
+    VoiceActivity(bool),
 }
 
 impl std::fmt::Debug for PlayerMessage {
@@ -72,6 +76,8 @@ impl std::fmt::Debug for PlayerMessage {
         match self {
             Self::PlayerData(arg0) => f.debug_tuple("PlayerData").field(arg0).finish(),
             Self::AudioStream(_) => f.debug_tuple("AudioStream").finish(),
+            Self::VideoStream(_) => f.debug_tuple("VideoStream").finish(),
+            Self::VoiceActivity(arg0) => f.debug_tuple("VoiceActivity").field(arg0).finish(),
         }
     }
 }
@@ -148,9 +154,24 @@ pub struct ForeignPlayer {
     audio_sender: mpsc::Sender<StreamingSoundData<AudioDecoderError>>,
 }
 
+// present while the player is talking
+#[derive(Component)]
+pub struct ForeignVoiceActive;
+
 #[derive(Component)]
 pub struct ForeignAudioSource(pub mpsc::Receiver<StreamingSoundData<AudioDecoderError>>);
 
+// an rgba frame of video shared by a foreign player
+pub struct ForeignVideoFrame {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+// present while the player is sharing video. the channel closes when the track ends
+#[derive(Component)]
+pub struct ForeignVideoSource(pub mpsc::Receiver<ForeignVideoFrame>);
+
 // TODO: I should avoid the clone on recv somehow
 #[derive(Clone)]
 pub struct LocalAudioFrame {
@@ -310,6 +331,19 @@ pub fn process_transport_updates(
                 // pass through
                 let _ = audio_channel.blocking_send(*audio);
             }
+            PlayerMessage::VideoStream(receiver) => {
+                // replaces any previous track
+                commands
+                    .entity(entity)
+                    .try_insert(ForeignVideoSource(receiver));
+            }
+            PlayerMessage::VoiceActivity(active) => {
+                if active {
+                    commands.entity(entity).try_insert(ForeignVoiceActive);
+                } else {
+                    commands.entity(entity).remove::<ForeignVoiceActive>();
+                }
+            }
             PlayerMessage::PlayerData(Message::Position(pos)) => {
                 let dcl_transform = DclTransformAndParent {
                     translation: DclTranslation([pos.position_x, pos.position_y, pos.position_z]),
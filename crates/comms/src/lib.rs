@@ -5,6 +5,9 @@ pub mod global_crdt;
 #[cfg(feature = "livekit")]
 pub mod livekit_room;
 
+#[cfg(feature = "mock_transport")]
+pub mod mock_room;
+
 pub mod preview;
 pub mod profile;
 pub mod signed_login;
@@ -12,17 +15,22 @@ pub mod signed_login;
 mod test;
 pub mod websocket_room;
 
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bimap::BiMap;
+use common::traffic::{record_traffic, traffic_detail, Direction, TrafficGroup};
 use ethers_core::types::Address;
 use preview::PreviewPlugin;
 use signed_login::{SignedLoginPlugin, StartSignedLogin};
 use tokio::sync::mpsc::Sender;
 
-use dcl_component::{DclWriter, ToDclWriter};
+use dcl_component::{proto_components::kernel::comms::rfc4, DclWriter, ToDclWriter};
 use ipfs::CurrentRealm;
+use prost::Message as _;
 use wallet::Wallet;
 
 use self::{
@@ -59,6 +67,9 @@ impl Plugin for CommsPlugin {
         #[cfg(feature = "livekit")]
         app.add_plugins(LivekitPlugin);
 
+        #[cfg(feature = "mock_transport")]
+        app.add_plugins(mock_room::MockTransportPlugin);
+
         app.add_systems(Update, process_realm_change);
     }
 }
@@ -74,6 +85,48 @@ pub enum TransportType {
     Livekit,
     Archipelago,
     Island(String),
+    // local simulated players from `/spawn_bots`
+    Bots,
+    #[cfg(feature = "mock_transport")]
+    Mock,
+}
+
+// total bytes of peer messages sent and received over all transports, for diagnostics
+pub static NETWORK_BYTES_SENT: AtomicU64 = AtomicU64::new(0);
+pub static NETWORK_BYTES_RECEIVED: AtomicU64 = AtomicU64::new(0);
+
+pub fn record_bytes_sent(bytes: usize) {
+    NETWORK_BYTES_SENT.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+pub fn record_bytes_received(bytes: usize) {
+    NETWORK_BYTES_RECEIVED.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+fn message_type(message: &rfc4::packet::Message) -> &'static str {
+    use rfc4::packet::Message::*;
+    match message {
+        Position(_) | Movement(_) => "position",
+        ProfileVersion(_) | ProfileRequest(_) | ProfileResponse(_) => "profile",
+        Chat(_) => "chat",
+        Scene(_) => "scene bus",
+        Voice(_) => "voice",
+        PlayerEmote(_) | SceneEmote(_) => "emote",
+    }
+}
+
+// per transport and message type traffic for the debug panel. `data` is an encoded rfc4 packet
+pub(crate) fn record_packet_traffic(transport: &str, direction: Direction, data: &[u8]) {
+    if !traffic_detail() {
+        return;
+    }
+
+    record_traffic(TrafficGroup::Transport, transport, direction, data.len());
+    let message_type = rfc4::Packet::decode(data)
+        .ok()
+        .and_then(|packet| packet.message)
+        .map_or("unknown", |message| message_type(&message));
+    record_traffic(TrafficGroup::Message, message_type, direction, data.len());
 }
 
 pub struct NetworkMessage {
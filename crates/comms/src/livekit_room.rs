@@ -3,7 +3,10 @@
 use std::sync::Arc;
 
 use async_tungstenite::tungstenite::http::Uri;
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use futures_lite::StreamExt;
 use livekit::{
     id::TrackSid,
@@ -11,7 +14,7 @@ use livekit::{
     track::{LocalAudioTrack, LocalTrack, TrackSource},
     webrtc::{
         audio_source::native::NativeAudioSource,
-        prelude::{AudioFrame, AudioSourceOptions, RtcAudioSource},
+        prelude::{AudioFrame, AudioSourceOptions, RtcAudioSource, VideoFormatType},
     },
     RoomOptions,
 };
@@ -21,13 +24,13 @@ use tokio::sync::{
     Mutex,
 };
 
-use common::{structs::AudioDecoderError, util::AsH160};
+use common::{structs::AudioDecoderError, traffic::Direction, util::AsH160};
 use dcl_component::proto_components::kernel::comms::rfc4;
 
 use crate::{
-    global_crdt::{LocalAudioFrame, LocalAudioSource, PlayerMessage},
+    global_crdt::{ForeignVideoFrame, LocalAudioFrame, LocalAudioSource, PlayerMessage},
     profile::CurrentUserProfile,
-    Transport, TransportType,
+    record_bytes_received, record_bytes_sent, record_packet_traffic, Transport, TransportType,
 };
 
 use super::{
@@ -226,6 +229,8 @@ fn livekit_handler_inner(
         });
 
         let mut app_rx = app_rx.lock().await;
+        // remote participants the server currently reports as talking
+        let mut speaking = HashSet::new();
         'stream: loop {
             tokio::select!(
                 incoming = network_rx.recv() => {
@@ -237,6 +242,8 @@ fn livekit_handler_inner(
 
                     match incoming {
                         livekit::RoomEvent::DataReceived { payload, participant, .. } => {
+                            record_bytes_received(payload.len());
+                            record_packet_traffic("livekit", Direction::In, &payload);
                             if let Some(address) = participant.and_then(|p| p.identity().0.as_str().as_h160()) {
                                 let packet = match rfc4::Packet::decode(payload.as_slice()) {
                                     Ok(packet) => packet,
@@ -274,43 +281,106 @@ fn livekit_handler_inner(
                                                 return;
                                             };
 
-                                            let (frame_sender, frame_receiver) = tokio::sync::mpsc::channel(10);
+                                            // the app drops the sound when its audio output changes, so hand it a new one
+                                            'sound: loop {
+                                                let (frame_sender, frame_receiver) = tokio::sync::mpsc::channel(10);
+
+                                                let bridge = LivekitKiraBridge {
+                                                    sample_rate: frame.sample_rate,
+                                                    receiver: frame_receiver,
+                                                };
+
+                                                println!("recced with {} / {}", frame.sample_rate, frame.num_channels);
+
+                                                let sound_data = kira::sound::streaming::StreamingSoundData::from_decoder(
+                                                    bridge,
+                                                    kira::sound::streaming::StreamingSoundSettings::new(),
+                                                );
+
+                                                if sender.send(PlayerUpdate {
+                                                    transport_id,
+                                                    message: PlayerMessage::AudioStream(Box::new(sound_data)),
+                                                    address,
+                                                }).await.is_err() {
+                                                    warn!("app pipe broken, exiting task");
+                                                    return;
+                                                }
 
-                                            let bridge = LivekitKiraBridge {
-                                                sample_rate: frame.sample_rate,
-                                                receiver: frame_receiver,
-                                            };
+                                                while let Some(frame) = x.next().await {
+                                                    match frame_sender.try_send(frame) {
+                                                        Ok(()) => (),
+                                                        Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
+                                                            warn!("livekit audio receiver buffer full, dropping frame");
+                                                        },
+                                                        Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
+                                                            debug!("livekit audio receiver dropped, restarting sound");
+                                                            continue 'sound;
+                                                        },
+                                                    }
+                                                }
 
-                                            println!("recced with {} / {}", frame.sample_rate, frame.num_channels);
+                                                break;
+                                            }
 
-                                            let sound_data = kira::sound::streaming::StreamingSoundData::from_decoder(
-                                                bridge,
-                                                kira::sound::streaming::StreamingSoundSettings::new(),
-                                            );
+                                            warn!("track ended, exiting task");
+                                        });
+                                    },
+                                    livekit::track::RemoteTrack::Video(video) => {
+                                        let sender = sender.clone();
+                                        rt2.spawn(async move {
+                                            let mut x = livekit::webrtc::video_stream::native::NativeVideoStream::new(video.rtc_track());
+                                            // a couple of frames is plenty, we only show the latest
+                                            let (frame_sender, frame_receiver) = tokio::sync::mpsc::channel(2);
 
-                                            let _ = sender.send(PlayerUpdate {
+                                            if sender.send(PlayerUpdate {
                                                 transport_id,
-                                                message: PlayerMessage::AudioStream(Box::new(sound_data)),
+                                                message: PlayerMessage::VideoStream(frame_receiver),
                                                 address,
-                                            }).await;
+                                            }).await.is_err() {
+                                                warn!("app pipe broken, exiting task");
+                                                return;
+                                            }
 
                                             while let Some(frame) = x.next().await {
-                                                match frame_sender.try_send(frame) {
+                                                let (width, height) = (frame.buffer.width(), frame.buffer.height());
+                                                let mut data = vec![0; width as usize * height as usize * 4];
+                                                // libyuv names formats by word order, so abgr is rgba in memory
+                                                frame.buffer.to_argb(VideoFormatType::ABGR, &mut data, width * 4, width as i32, height as i32);
+                                                match frame_sender.try_send(ForeignVideoFrame { width, height, data }) {
                                                     Ok(()) => (),
                                                     Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
-                                                        warn!("livekit audio receiver buffer full, dropping frame");
+                                                        debug!("livekit video receiver behind, dropping frame");
                                                     },
                                                     Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
-                                                        warn!("livekit audio receiver dropped, exiting task");
+                                                        debug!("livekit video receiver dropped, exiting task");
                                                         return;
                                                     },
                                                 }
                                             }
 
-                                            warn!("track ended, exiting task");
+                                            debug!("video track ended, exiting task");
                                         });
                                     },
-                                    _ => warn!("not processing video tracks"),
+                                }
+                            }
+                        }
+                        livekit::RoomEvent::ActiveSpeakersChanged { speakers } => {
+                            let now_speaking: HashSet<_> = speakers.iter().filter_map(|participant| match participant {
+                                livekit::participant::Participant::Remote(remote) => remote.identity().0.as_str().as_h160(),
+                                livekit::participant::Participant::Local(_) => None,
+                            }).collect();
+                            let changes = now_speaking.difference(&speaking).map(|address| (*address, true))
+                                .chain(speaking.difference(&now_speaking).map(|address| (*address, false)))
+                                .collect::<Vec<_>>();
+                            speaking = now_speaking;
+                            for (address, active) in changes {
+                                if let Err(e) = sender.send(PlayerUpdate {
+                                    transport_id,
+                                    message: PlayerMessage::VoiceActivity(active),
+                                    address,
+                                }).await {
+                                    warn!("app pipe broken ({e}), existing loop");
+                                    break 'stream;
                                 }
                             }
                         }
@@ -323,6 +393,8 @@ fn livekit_handler_inner(
                         break 'stream;
                     };
 
+                    record_bytes_sent(outgoing.data.len());
+                    record_packet_traffic("livekit", Direction::Out, &outgoing.data);
                     let packet = livekit::DataPacket { payload: outgoing.data, topic: None, reliable: !outgoing.unreliable, destination_identities: Default::default() };
                     if let Err(_e) = room.local_participant().publish_data(packet).await {
                         // debug!("outgoing failed: {_e}; not exiting loop though since it often fails at least once or twice at the start...");
@@ -0,0 +1,155 @@
+// scripted transport for integration tests, enabled with the `mock_transport` feature. foreign
+// players, their movement, chat and profile announcements are injected as if they arrived from a
+// room, profile requests for scripted players are answered, and everything the client sends is
+// kept for inspection. no server is involved.
+
+use bevy::{prelude::*, utils::HashMap};
+use common::{profile::SerializedProfile, util::AsH160};
+use dcl_component::{
+    proto_components::kernel::comms::rfc4,
+    transform_and_parent::{DclQuat, DclTranslation},
+};
+use ethers_core::types::Address;
+use prost::Message as _;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use crate::{
+    global_crdt::{process_transport_updates, GlobalCrdtState, PlayerMessage, PlayerUpdate},
+    NetworkMessage, Transport, TransportType,
+};
+
+pub struct MockTransportPlugin;
+
+impl Plugin for MockTransportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            process_mock_transport.before(process_transport_updates),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct MockTransport {
+    entity: Entity,
+    receiver: Receiver<NetworkMessage>,
+    player_sender: Sender<PlayerUpdate>,
+    profiles: HashMap<Address, SerializedProfile>,
+    position_index: u32,
+    // packets sent by the client, oldest first
+    pub sent: Vec<rfc4::Packet>,
+}
+
+impl MockTransport {
+    // spawns the mock alongside the `Transport` that the comms systems send through
+    pub fn spawn(world: &mut World) -> Entity {
+        let (sender, receiver) = tokio::sync::mpsc::channel(1000);
+        let player_sender = world.resource::<GlobalCrdtState>().get_sender();
+        let entity = world
+            .spawn(Transport {
+                transport_type: TransportType::Mock,
+                sender,
+                foreign_aliases: Default::default(),
+            })
+            .id();
+        world.entity_mut(entity).insert(MockTransport {
+            entity,
+            receiver,
+            player_sender,
+            profiles: Default::default(),
+            position_index: 0,
+            sent: Default::default(),
+        });
+        entity
+    }
+
+    // a packet from `address`, creating the foreign player if it's new
+    pub fn inject(&self, address: Address, message: rfc4::packet::Message) {
+        let _ = self.player_sender.try_send(PlayerUpdate {
+            transport_id: self.entity,
+            message: PlayerMessage::PlayerData(message),
+            address,
+        });
+    }
+
+    // a player that announces `profile` and answers requests for it
+    pub fn add_player(&mut self, address: Address, profile: SerializedProfile) {
+        let profile_version = profile.version as u32;
+        self.profiles.insert(address, profile);
+        self.inject(
+            address,
+            rfc4::packet::Message::ProfileVersion(rfc4::AnnounceProfileVersion { profile_version }),
+        );
+    }
+
+    // world space bevy transform
+    pub fn move_player(&mut self, address: Address, transform: Transform) {
+        self.position_index += 1;
+        let translation = DclTranslation::from_bevy_translation(transform.translation);
+        let rotation = DclQuat::from_bevy_quat(transform.rotation);
+        self.inject(
+            address,
+            rfc4::packet::Message::Position(rfc4::Position {
+                index: self.position_index,
+                position_x: translation.0[0],
+                position_y: translation.0[1],
+                position_z: translation.0[2],
+                rotation_x: rotation.0[0],
+                rotation_y: rotation.0[1],
+                rotation_z: rotation.0[2],
+                rotation_w: rotation.0[3],
+            }),
+        );
+    }
+
+    pub fn chat(&self, address: Address, message: impl Into<String>, timestamp: f64) {
+        self.inject(
+            address,
+            rfc4::packet::Message::Chat(rfc4::Chat {
+                message: message.into(),
+                timestamp,
+            }),
+        );
+    }
+
+    // sent chat messages, oldest first
+    pub fn sent_chat(&self) -> impl Iterator<Item = &rfc4::Chat> {
+        self.sent.iter().filter_map(|packet| match &packet.message {
+            Some(rfc4::packet::Message::Chat(chat)) => Some(chat),
+            _ => None,
+        })
+    }
+}
+
+fn process_mock_transport(mut transports: Query<&mut MockTransport>) {
+    for mut transport in transports.iter_mut() {
+        let transport = &mut *transport;
+        while let Ok(message) = transport.receiver.try_recv() {
+            let packet = match rfc4::Packet::decode(message.data.as_slice()) {
+                Ok(packet) => packet,
+                Err(e) => {
+                    warn!("mock transport received an invalid packet: {e}");
+                    continue;
+                }
+            };
+
+            if let Some(rfc4::packet::Message::ProfileRequest(request)) = packet.message.as_ref() {
+                if let Some((address, profile)) = request
+                    .address
+                    .as_h160()
+                    .and_then(|address| transport.profiles.get_key_value(&address))
+                {
+                    transport.inject(
+                        *address,
+                        rfc4::packet::Message::ProfileResponse(rfc4::ProfileResponse {
+                            serialized_profile: serde_json::to_string(profile).unwrap(),
+                            base_url: String::default(),
+                        }),
+                    );
+                }
+            }
+
+            transport.sent.push(packet);
+        }
+    }
+}
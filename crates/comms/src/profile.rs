@@ -61,6 +61,16 @@ enum ProfileDisplayState {
 #[derive(Resource, Default)]
 pub struct ProfileCache(HashMap<Address, ProfileDisplayState>);
 
+impl ProfileCache {
+    // drop loaded and failed profiles, they will be re-requested when next needed
+    pub fn trim(&mut self) -> usize {
+        let before = self.0.len();
+        self.0
+            .retain(|_, state| matches!(state, ProfileDisplayState::Loading(_)));
+        before - self.0.len()
+    }
+}
+
 #[derive(SystemParam)]
 pub struct ProfileManager<'w, 's> {
     cache: ResMut<'w, ProfileCache>,
@@ -10,7 +10,10 @@ use futures_util::{pin_mut, select, stream::StreamExt, FutureExt, SinkExt};
 use prost::Message;
 use tokio::sync::mpsc::{Receiver, Sender};
 
-use common::util::{dcl_assert, AsH160};
+use common::{
+    traffic::Direction,
+    util::{dcl_assert, AsH160},
+};
 use dcl_component::proto_components::kernel::comms::{
     rfc4,
     rfc5::{
@@ -19,7 +22,10 @@ use dcl_component::proto_components::kernel::comms::{
 };
 use wallet::Wallet;
 
-use crate::{global_crdt::PlayerMessage, profile::CurrentUserProfile, Transport, TransportType};
+use crate::{
+    global_crdt::PlayerMessage, profile::CurrentUserProfile, record_bytes_received,
+    record_bytes_sent, record_packet_traffic, Transport, TransportType,
+};
 
 use super::{
     global_crdt::{GlobalCrdtState, PlayerUpdate},
@@ -278,6 +284,10 @@ async fn websocket_room_handler_inner(
             };
             let mut buf = Vec::default();
             packet.encode(&mut buf)?;
+            record_bytes_sent(buf.len());
+            if let Some(ws_packet::Message::PeerUpdateMessage(update)) = packet.message.as_ref() {
+                record_packet_traffic("websocket room", Direction::Out, &update.body);
+            }
             write.send(buf.into()).await?;
         }
 
@@ -288,8 +298,9 @@ async fn websocket_room_handler_inner(
     // unwrap and forward inbound messages
     let f_read = async move {
         while let Some(next) = read.next().await {
-            let next = next?;
-            let next = WsPacket::decode(next.into_data().as_slice())?;
+            let next = next?.into_data();
+            record_bytes_received(next.len());
+            let next = WsPacket::decode(next.as_slice())?;
             let Some(message) = next.message else {
                 bail!("received empty packet")
             };
@@ -319,6 +330,7 @@ async fn websocket_room_handler_inner(
                     foreign_aliases.remove_by_left(&peer.alias);
                 }
                 ws_packet::Message::PeerUpdateMessage(update) => {
+                    record_packet_traffic("websocket room", Direction::In, &update.body);
                     let packet = match rfc4::Packet::decode(update.body.as_slice()) {
                         Ok(packet) => packet,
                         Err(e) => {
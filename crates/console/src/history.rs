@@ -0,0 +1,65 @@
+// command lines entered in chat, kept across sessions. repeated commands move to the end rather
+// than being stored twice.
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use common::util::config_file;
+
+const MAX_HISTORY: usize = 200;
+
+fn history_file() -> PathBuf {
+    config_file().with_file_name("command_history.txt")
+}
+
+#[derive(Resource)]
+pub struct CommandHistory {
+    // oldest first
+    entries: Vec<String>,
+}
+
+impl Default for CommandHistory {
+    fn default() -> Self {
+        let entries = std::fs::read_to_string(history_file())
+            .map(|history| {
+                history
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { entries }
+    }
+}
+
+impl CommandHistory {
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn push(&mut self, line: &str) {
+        if line.is_empty() || self.entries.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.entries.retain(|entry| entry != line);
+        self.entries.push(line.to_owned());
+        let excess = self.entries.len().saturating_sub(MAX_HISTORY);
+        self.entries.drain(..excess);
+
+        let path = history_file();
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::write(&path, self.entries.join("\n")) {
+            warn!("failed to save command history: {e}");
+        }
+    }
+
+    // the most recent entry before index `before` containing `query`
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+}
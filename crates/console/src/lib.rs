@@ -1,3 +1,6 @@
+pub mod history;
+pub mod slash_commands;
+
 use bevy::{prelude::*, scene::scene_spawner_system};
 use bevy_console::{
     Command, ConsoleCommand, ConsoleCommandEntered, ConsoleConfiguration, ConsoleSet,
@@ -6,6 +9,8 @@ use bevy_console::{
 use clap::Parser;
 
 use common::sets::SceneSets;
+use history::CommandHistory;
+use slash_commands::{ArgHints, CommandRegistry, DynamicCommandEntered, SlashCommands};
 
 pub trait DoAddConsoleCommand {
     fn add_console_command<T: Command, U>(
@@ -72,6 +77,10 @@ impl Plugin for ConsolePlugin {
         .add_console_command::<HelpCommand, _>(help_command)
         .add_console_command::<ExitCommand, _>(exit_command)
         .init_resource::<PendingCommands>()
+        .init_resource::<SlashCommands>()
+        .init_resource::<ArgHints>()
+        .init_resource::<CommandHistory>()
+        .add_event::<DynamicCommandEntered>()
         .add_systems(Update, send_pending);
 
         app.configure_sets(
@@ -87,11 +96,13 @@ impl Plugin for ConsolePlugin {
 }
 
 fn remove_default_commands(mut config: ResMut<ConsoleConfiguration>) {
-    for command_name in ["/help", "/clear", "/exit"] {
+    for command_name in ["/clear", "/exit"] {
         if let Some(res) = config.commands.remove(&command_name[1..]) {
             config.commands.insert(command_name, res);
         }
     }
+    // replaced by our own `/help`, which also lists dynamic commands
+    config.commands.remove("help");
 }
 
 #[derive(Resource, Default)]
@@ -111,17 +122,36 @@ pub(crate) fn clear_command(
     }
 }
 
-//re-add default commands, unfortunately have to copy/paste
+/// list commands, or show help for one command
 #[derive(Parser, ConsoleCommand)]
 #[command(name = "/help")]
-pub(crate) struct HelpCommand;
+pub(crate) struct HelpCommand {
+    command: Option<String>,
+}
 
-pub(crate) fn help_command(
-    mut cmd: ConsoleCommand<HelpCommand>,
-    mut pending: ResMut<PendingCommands>,
-) {
-    if let Some(Ok(_)) = cmd.take() {
-        pending.0.push("help".to_owned());
+pub(crate) fn help_command(mut cmd: ConsoleCommand<HelpCommand>, registry: CommandRegistry) {
+    if let Some(Ok(HelpCommand { command })) = cmd.take() {
+        match command {
+            Some(name) => {
+                let name = if name.starts_with('/') {
+                    name
+                } else {
+                    format!("/{name}")
+                };
+                match registry.help(&name) {
+                    Some(help) => cmd.reply_ok(help),
+                    None => cmd.reply_failed(format!("unknown command `{name}`")),
+                }
+            }
+            None => {
+                for (name, about) in registry.list() {
+                    cmd.reply(format!("{name} - {about}"));
+                }
+                cmd.reply_ok(
+                    "use `/help <command>` for details, tab to complete and ctrl+r to search history in chat",
+                );
+            }
+        }
     }
 }
 
@@ -0,0 +1,342 @@
+// registry of `/commands` usable from chat, key bindings and the hotbar. clap commands added with
+// `add_console_command` are listed from the console configuration; other commands (e.g. from
+// scenes) are registered at runtime with `SlashCommands::register` and arrive as
+// `DynamicCommandEntered` events for their owner to handle.
+
+use std::collections::BTreeMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_console::{ConsoleCommandEntered, ConsoleConfiguration};
+use shlex::Shlex;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CommandOwner {
+    Client,
+    Scene(Entity),
+}
+
+#[derive(Clone, Debug)]
+pub struct CommandArg {
+    pub name: String,
+    pub help: String,
+    pub required: bool,
+    // suggested values for autocompletion
+    pub values: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DynamicCommand {
+    // including the leading `/`
+    pub name: String,
+    pub about: String,
+    pub args: Vec<CommandArg>,
+    pub owner: CommandOwner,
+}
+
+#[derive(Resource, Default)]
+pub struct SlashCommands {
+    dynamic: BTreeMap<String, DynamicCommand>,
+}
+
+impl SlashCommands {
+    // fails if the name is taken by a console command or another owner's command
+    pub fn register(
+        &mut self,
+        console: &ConsoleConfiguration,
+        command: DynamicCommand,
+    ) -> Result<(), String> {
+        if !command.name.starts_with('/') || command.name.contains(char::is_whitespace) {
+            return Err(format!("invalid command name `{}`", command.name));
+        }
+        if console.commands.contains_key(command.name.as_str())
+            || self
+                .dynamic
+                .get(&command.name)
+                .is_some_and(|existing| existing.owner != command.owner)
+        {
+            return Err(format!("`{}` is already registered", command.name));
+        }
+        self.dynamic.insert(command.name.clone(), command);
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, name: &str, owner: CommandOwner) {
+        if self.dynamic.get(name).is_some_and(|c| c.owner == owner) {
+            self.dynamic.remove(name);
+        }
+    }
+
+    pub fn unregister_owner(&mut self, owner: CommandOwner) {
+        self.dynamic.retain(|_, command| command.owner != owner);
+    }
+}
+
+// a registered dynamic command was entered
+#[derive(Event, Clone, Debug)]
+pub struct DynamicCommandEntered {
+    pub name: String,
+    pub args: Vec<String>,
+    pub owner: CommandOwner,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Completion {
+    // the full input line with this completion applied
+    pub line: String,
+    // what to show in the suggestion list
+    pub label: String,
+}
+
+// suggested values for clap arguments by argument id, for values only known at runtime (realms,
+// parcels) that clap can't list as possible values
+#[derive(Resource, Default)]
+pub struct ArgHints(HashMap<String, Vec<String>>);
+
+impl ArgHints {
+    pub fn set(&mut self, arg: &str, values: Vec<String>) {
+        self.0.insert(arg.to_owned(), values);
+    }
+}
+
+#[derive(SystemParam)]
+pub struct CommandRegistry<'w> {
+    console: Res<'w, ConsoleConfiguration>,
+    slash: Res<'w, SlashCommands>,
+    hints: Res<'w, ArgHints>,
+}
+
+impl CommandRegistry<'_> {
+    pub fn contains(&self, name: &str) -> bool {
+        self.console.commands.contains_key(name) || self.slash.dynamic.contains_key(name)
+    }
+
+    // (name, about) for every command, sorted by name
+    pub fn list(&self) -> Vec<(String, String)> {
+        let mut commands = self
+            .console
+            .commands
+            .iter()
+            .map(|(name, command)| {
+                (
+                    name.to_string(),
+                    command
+                        .get_about()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                )
+            })
+            .chain(
+                self.slash
+                    .dynamic
+                    .values()
+                    .map(|command| (command.name.clone(), command.about.clone())),
+            )
+            .collect::<Vec<_>>();
+        commands.sort();
+        commands
+    }
+
+    pub fn help(&self, name: &str) -> Option<String> {
+        if let Some(command) = self.console.commands.get(name) {
+            return Some(command.clone().render_long_help().to_string());
+        }
+
+        let command = self.slash.dynamic.get(name)?;
+        let mut help = command.about.clone();
+        help.push_str(&format!("\n\nUsage: {}", usage(command)));
+        for arg in &command.args {
+            help.push_str(&format!("\n  <{}>  {}", arg.name, arg.help));
+        }
+        Some(help)
+    }
+
+    // argument names for the command, and the suggested values for each
+    fn args(&self, name: &str) -> Option<Vec<(String, Vec<String>)>> {
+        if let Some(command) = self.console.commands.get(name) {
+            return Some(
+                command
+                    .get_arguments()
+                    .filter(|arg| arg.is_positional())
+                    .map(|arg| {
+                        let id = arg.get_id().to_string();
+                        let mut values = arg
+                            .get_possible_values()
+                            .iter()
+                            .map(|value| value.get_name().to_owned())
+                            .collect::<Vec<_>>();
+                        if values.is_empty() {
+                            values = self.hints.0.get(&id).cloned().unwrap_or_default();
+                        }
+                        (id, values)
+                    })
+                    .collect(),
+            );
+        }
+
+        self.slash.dynamic.get(name).map(|command| {
+            command
+                .args
+                .iter()
+                .map(|arg| (arg.name.clone(), arg.values.clone()))
+                .collect()
+        })
+    }
+
+    // completions for a partial input line: command names while typing the first word, then the
+    // suggested values for the current argument
+    pub fn complete(&self, line: &str) -> Vec<Completion> {
+        if !line.starts_with('/') {
+            return Vec::default();
+        }
+
+        let mut words = line.split(' ').collect::<Vec<_>>();
+        let current = words.pop().unwrap_or_default();
+        if words.is_empty() {
+            return self
+                .list()
+                .into_iter()
+                .filter(|(name, _)| name.starts_with(current))
+                .map(|(name, about)| Completion {
+                    line: format!("{name} "),
+                    label: if about.is_empty() {
+                        name
+                    } else {
+                        format!("{name} - {about}")
+                    },
+                })
+                .collect();
+        }
+
+        let Some(args) = self.args(words[0]) else {
+            return Vec::default();
+        };
+        let Some((arg_name, values)) = args.get(words.len() - 1) else {
+            return Vec::default();
+        };
+        let prefix = words.join(" ");
+        let mut completions = values
+            .iter()
+            .filter(|value| value.starts_with(current))
+            .map(|value| Completion {
+                line: format!("{prefix} {value} "),
+                label: value.clone(),
+            })
+            .collect::<Vec<_>>();
+        if completions.is_empty() && current.is_empty() {
+            // nothing to suggest, but show which argument is expected
+            completions.push(Completion {
+                line: line.to_owned(),
+                label: format!("<{arg_name}>"),
+            });
+        }
+        completions
+    }
+}
+
+fn usage(command: &DynamicCommand) -> String {
+    std::iter::once(command.name.clone())
+        .chain(command.args.iter().map(|arg| {
+            if arg.required {
+                format!("<{}>", arg.name)
+            } else {
+                format!("[{}]", arg.name)
+            }
+        }))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// runs command lines from any source
+#[derive(SystemParam)]
+pub struct CommandDispatcher<'w> {
+    registry: CommandRegistry<'w>,
+    console_entered: EventWriter<'w, ConsoleCommandEntered>,
+    dynamic_entered: EventWriter<'w, DynamicCommandEntered>,
+}
+
+impl CommandDispatcher<'_> {
+    pub fn run(&mut self, line: &str) -> Result<(), String> {
+        let mut args = Shlex::new(line).collect::<Vec<_>>();
+        if args.is_empty() {
+            return Err("empty command".to_owned());
+        }
+        let command_name = args.remove(0);
+        debug!("command: `{command_name}`, with args: `{args:?}`");
+
+        if self
+            .registry
+            .console
+            .commands
+            .contains_key(command_name.as_str())
+        {
+            self.console_entered
+                .send(ConsoleCommandEntered { command_name, args });
+            Ok(())
+        } else if let Some(command) = self.registry.slash.dynamic.get(&command_name) {
+            self.dynamic_entered.send(DynamicCommandEntered {
+                name: command_name,
+                args,
+                owner: command.owner,
+            });
+            Ok(())
+        } else {
+            Err(format!("unknown command `{command_name}`, try /help"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{ecs::system::SystemState, prelude::*};
+    use bevy_console::ConsoleConfiguration;
+
+    use super::{
+        ArgHints, CommandArg, CommandOwner, CommandRegistry, DynamicCommand, SlashCommands,
+    };
+
+    #[test]
+    fn register_and_complete() {
+        let mut world = World::new();
+        let console = ConsoleConfiguration::default();
+        let mut slash = SlashCommands::default();
+        let command = DynamicCommand {
+            name: "/dance".to_owned(),
+            about: "dance".to_owned(),
+            args: vec![CommandArg {
+                name: "style".to_owned(),
+                help: "how to dance".to_owned(),
+                required: true,
+                values: vec!["disco".to_owned(), "robot".to_owned()],
+            }],
+            owner: CommandOwner::Scene(Entity::from_raw(1)),
+        };
+        slash.register(&console, command.clone()).unwrap();
+        assert!(slash
+            .register(
+                &console,
+                DynamicCommand {
+                    owner: CommandOwner::Client,
+                    ..command
+                }
+            )
+            .is_err());
+        world.insert_resource(console);
+        world.insert_resource(slash);
+        world.init_resource::<ArgHints>();
+
+        let mut state = SystemState::<CommandRegistry>::new(&mut world);
+        let registry = state.get(&world);
+        assert_eq!(registry.complete("/da")[0].line, "/dance ");
+        let values = registry.complete("/dance ");
+        assert_eq!(values.len(), 2);
+        assert_eq!(registry.complete("/dance r")[0].line, "/dance robot ");
+        assert!(registry.complete("/dance robot x").is_empty());
+        assert!(registry.help("/dance").unwrap().contains("/dance <style>"));
+
+        world
+            .resource_mut::<SlashCommands>()
+            .unregister_owner(CommandOwner::Scene(Entity::from_raw(1)));
+        let registry = state.get(&world);
+        assert!(!registry.contains("/dance"));
+    }
+}
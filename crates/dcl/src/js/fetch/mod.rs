@@ -3,7 +3,11 @@ use std::{cell::RefCell, rc::Rc};
 mod fetch_response_body_resource;
 
 use bevy::prelude::debug;
-use common::{rpc::RpcCall, structs::SceneMeta};
+use common::{
+    rpc::RpcCall,
+    structs::SceneMeta,
+    traffic::{record_traffic, Direction, TrafficGroup},
+};
 use deno_core::{
     anyhow::{self, anyhow},
     error::{type_error, AnyError},
@@ -246,6 +250,13 @@ pub async fn op_fetch_send(
     }
 
     let ipfs = state.borrow_mut().borrow_mut::<IpfsResource>().clone();
+    let hash = state.borrow().borrow::<CrdtContext>().hash.clone();
+    record_traffic(
+        TrafficGroup::Scene("fetch"),
+        &hash,
+        Direction::Out,
+        body_bytes.as_ref().map_or(0, Vec::len),
+    );
 
     let async_req = if let Some(body_id) = request_body_rid {
         let body = state.borrow_mut().resource_table.take_any(body_id)?;
@@ -274,6 +285,12 @@ pub async fn op_fetch_send(
 
     let content_length = res.body().len();
     let chunk = bytes::Bytes::from(res.bytes().await?);
+    record_traffic(
+        TrafficGroup::Scene("fetch"),
+        &hash,
+        Direction::In,
+        chunk.len(),
+    );
 
     let response_rid = state
         .borrow_mut()
@@ -397,6 +414,7 @@ pub async fn op_signed_fetch_headers(
     let realm_info = realm_information(state.clone()).await?;
     let wallet = state.borrow().borrow::<Wallet>().clone();
     let urn = state.borrow().borrow::<CrdtContext>().hash.clone();
+    record_traffic(TrafficGroup::Scene("signed fetch"), &urn, Direction::Out, 0);
     let ipfs = state.borrow().borrow::<IpfsResource>().clone();
     let scene_meta = ipfs
         .entity_definition(&urn)
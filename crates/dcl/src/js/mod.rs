@@ -52,6 +52,7 @@ pub mod events;
 #[cfg(feature = "inspect")]
 pub mod inspector;
 pub mod player;
+pub mod quests;
 pub mod system_api;
 pub mod testing;
 pub mod websocket;
@@ -61,6 +62,13 @@ pub struct ShuttingDown;
 
 pub struct RendererStore(pub CrdtStore);
 
+// makes `Math.random` repeat across runs. must be called before the first scene starts
+pub fn set_random_seed(seed: u64) {
+    // v8 treats 0 as unseeded
+    let seed = (seed % i32::MAX as u64).max(1);
+    deno_core::v8_set_flags(vec![String::default(), format!("--random-seed={seed}")]);
+}
+
 pub fn create_runtime(
     init: bool,
     inspect: bool,
@@ -84,7 +92,7 @@ pub fn create_runtime(
 
     let mut ops = vec![op_require(), op_log(), op_error()];
 
-    let op_sets: [Vec<deno_core::OpDecl>; 13] = [
+    let op_sets: [Vec<deno_core::OpDecl>; 14] = [
         engine::ops(),
         restricted_actions::ops(),
         runtime::ops(),
@@ -92,6 +100,7 @@ pub fn create_runtime(
         portables::ops(),
         user_identity::ops(),
         player::ops(),
+        quests::ops(),
         events::ops(),
         comms::ops(),
         testing::ops(),
@@ -207,6 +216,8 @@ pub(crate) fn scene_thread(
     inspect: bool,
     testing: bool,
     preview: bool,
+    // seconds per tick, in place of the wall clock
+    fixed_dt: Option<f32>,
     super_user: Option<tokio::sync::mpsc::UnboundedSender<SystemApi>>,
 ) {
     let scene_context = CrdtContext::new(scene_id, scene_hash, testing, preview);
@@ -328,13 +339,22 @@ pub(crate) fn scene_thread(
 
     let start_time = std::time::Instant::now();
     let mut prev_time = start_time;
-    let mut elapsed;
+    let mut ticks = 0;
     let mut reported_errors = 0;
     loop {
-        let now = std::time::Instant::now();
-        let dt = now.saturating_duration_since(prev_time);
-        elapsed = now.saturating_duration_since(start_time);
-        prev_time = now;
+        let (dt, elapsed) = match fixed_dt {
+            Some(step) => {
+                ticks += 1;
+                let step = std::time::Duration::from_secs_f32(step);
+                (step, step * ticks)
+            }
+            None => {
+                let now = std::time::Instant::now();
+                let dt = now.saturating_duration_since(prev_time);
+                prev_time = now;
+                (dt, now.saturating_duration_since(start_time))
+            }
+        };
 
         state
             .borrow_mut()
@@ -474,6 +494,7 @@ fn op_require(
         "~system/PortableExperiences" => {
             Ok(include_str!("modules/PortableExperiences.js").to_owned())
         }
+        "~system/Quests" => Ok(include_str!("modules/Quests.js").to_owned()),
         "~system/RestrictedActions" => Ok(include_str!("modules/RestrictedActions.js").to_owned()),
         "~system/Runtime" => Ok(include_str!("modules/Runtime.js").to_owned()),
         "~system/Scene" => Ok(include_str!("modules/Scene.js").to_owned()),
@@ -0,0 +1,17 @@
+module.exports.startQuest = async function (body) { 
+    await Deno.core.ops.op_quest_start(String(body.questId));
+    return {} 
+}
+
+module.exports.abortQuest = async function (body) { 
+    await Deno.core.ops.op_quest_abort(String(body.questInstanceId));
+    return {} 
+}
+
+module.exports.sendEvent = async function (body) { 
+    const parameters = Object.fromEntries(
+        Object.entries(body.action.parameters ?? {}).map(([key, value]) => [key, String(value)])
+    );
+    const eventId = await Deno.core.ops.op_quest_send_event(String(body.action.type), parameters);
+    return { eventId } 
+}
@@ -12,6 +12,12 @@ module.exports.teleportTo = async function (body) {
     return {} 
 }
 
+// not part of the sdk api: lets the user pick one of the scene's spawn points and moves them there
+module.exports.chooseSpawnPoint = async function (body) { 
+    const name = await Deno.core.ops.op_choose_spawn_point();
+    return { success: name !== null, name: name ?? undefined } 
+}
+
 module.exports.triggerEmote = async function (body) { 
     // if only there was a way to run an ecs system here
     Deno.core.ops.op_emote(body.predefinedEmote)
@@ -35,6 +41,16 @@ module.exports.openExternalUrl = async function (body) {
 module.exports.openNftDialog = async function (body) { 
     return await Deno.core.ops.op_open_nft_dialog(body.urn) 
 }
+module.exports.setMapMarker = async function (body) { 
+    Deno.core.ops.op_set_map_marker(String(body.id), Number(body.worldCoordinates.x), Number(body.worldCoordinates.y), body.label ?? "");
+    return {} 
+}
+
+module.exports.removeMapMarker = async function (body) { 
+    Deno.core.ops.op_remove_map_marker(String(body.id));
+    return {} 
+}
+
 module.exports.setCommunicationsAdapter = async function (body) { 
     console.error("RestrictedActions::setCommunicationsAdapter not implemented");
     return {} 
@@ -35,7 +35,14 @@ function testingModule() {
             Deno.core.ops.op_log_test_plan(body);
             return {}
         },
-        setCameraTransform: async function (body) { return {} },
+        setCameraTransform: async function (body) {
+            const { position, rotation } = body
+            Deno.core.ops.op_set_camera_transform(
+                [position.x, position.y, position.z],
+                [rotation.x, rotation.y, rotation.z, rotation.w]
+            );
+            return {}
+        },
         takeAndCompareScreenshot
     }
 }
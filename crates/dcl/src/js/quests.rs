@@ -0,0 +1,75 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use bevy::log::debug;
+use common::rpc::RpcCall;
+use deno_core::{anyhow::anyhow, error::AnyError, op2, OpDecl, OpState};
+
+use crate::{interface::crdt_context::CrdtContext, RpcCalls};
+
+// list of op declarations
+pub fn ops() -> Vec<OpDecl> {
+    vec![op_quest_start(), op_quest_abort(), op_quest_send_event()]
+}
+
+#[op2(async)]
+async fn op_quest_start(
+    state: Rc<RefCell<OpState>>,
+    #[string] quest_id: String,
+) -> Result<(), AnyError> {
+    debug!("op_quest_start");
+    let (sx, rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
+    let scene = state.borrow().borrow::<CrdtContext>().scene_id.0;
+    state
+        .borrow_mut()
+        .borrow_mut::<RpcCalls>()
+        .push(RpcCall::StartQuest {
+            scene,
+            quest_id,
+            response: sx.into(),
+        });
+
+    rx.await.map_err(|e| anyhow!(e))?.map_err(|e| anyhow!(e))
+}
+
+#[op2(async)]
+async fn op_quest_abort(
+    state: Rc<RefCell<OpState>>,
+    #[string] instance_id: String,
+) -> Result<(), AnyError> {
+    debug!("op_quest_abort");
+    let (sx, rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
+    let scene = state.borrow().borrow::<CrdtContext>().scene_id.0;
+    state
+        .borrow_mut()
+        .borrow_mut::<RpcCalls>()
+        .push(RpcCall::AbortQuest {
+            scene,
+            instance_id,
+            response: sx.into(),
+        });
+
+    rx.await.map_err(|e| anyhow!(e))?.map_err(|e| anyhow!(e))
+}
+
+#[op2(async)]
+#[string]
+async fn op_quest_send_event(
+    state: Rc<RefCell<OpState>>,
+    #[string] action_type: String,
+    #[serde] parameters: HashMap<String, String>,
+) -> Result<String, AnyError> {
+    debug!("op_quest_send_event");
+    let (sx, rx) = tokio::sync::oneshot::channel::<Result<String, String>>();
+    let scene = state.borrow().borrow::<CrdtContext>().scene_id.0;
+    state
+        .borrow_mut()
+        .borrow_mut::<RpcCalls>()
+        .push(RpcCall::SendQuestEvent {
+            scene,
+            action_type,
+            parameters,
+            response: sx.into(),
+        });
+
+    rx.await.map_err(|e| anyhow!(e))?.map_err(|e| anyhow!(e))
+}
@@ -25,11 +25,14 @@ pub fn ops() -> Vec<OpDecl> {
     vec![
         op_move_player_to(),
         op_teleport_to(),
+        op_choose_spawn_point(),
         op_change_realm(),
         op_external_url(),
         op_emote(),
         op_scene_emote(),
         op_open_nft_dialog(),
+        op_set_map_marker(),
+        op_remove_map_marker(),
     ]
 }
 
@@ -122,6 +125,23 @@ async fn op_teleport_to(state: Rc<RefCell<OpState>>, position_x: i32, position_y
     matches!(rx.await, Ok(Ok(_)))
 }
 
+#[op2(async)]
+#[string]
+async fn op_choose_spawn_point(state: Rc<RefCell<OpState>>) -> Option<String> {
+    debug!("op_choose_spawn_point");
+    let (sx, rx) = tokio::sync::oneshot::channel::<Result<String, String>>();
+    let scene = state.borrow().borrow::<CrdtContext>().scene_id.0;
+    state
+        .borrow_mut()
+        .borrow_mut::<RpcCalls>()
+        .push(RpcCall::ChooseSpawnPoint {
+            scene,
+            response: sx.into(),
+        });
+
+    rx.await.ok().and_then(Result::ok)
+}
+
 #[op2(async)]
 async fn op_change_realm(
     state: Rc<RefCell<OpState>>,
@@ -248,3 +268,35 @@ async fn op_open_nft_dialog(
 
     rx.await.map_err(|e| anyhow!(e))?.map_err(|e| anyhow!(e))
 }
+
+#[op2]
+fn op_set_map_marker(
+    op_state: &mut OpState,
+    #[string] id: String,
+    position_x: i32,
+    position_y: i32,
+    #[string] label: String,
+) {
+    debug!("op_set_map_marker");
+    let scene = op_state.borrow::<CrdtContext>().scene_id.0;
+    op_state
+        .borrow_mut::<RpcCalls>()
+        .push(RpcCall::SetMapMarker {
+            scene,
+            id,
+            marker: Some((IVec2::new(position_x, position_y), label)),
+        });
+}
+
+#[op2]
+fn op_remove_map_marker(op_state: &mut OpState, #[string] id: String) {
+    debug!("op_remove_map_marker");
+    let scene = op_state.borrow::<CrdtContext>().scene_id.0;
+    op_state
+        .borrow_mut::<RpcCalls>()
+        .push(RpcCall::SetMapMarker {
+            scene,
+            id,
+            marker: None,
+        });
+}
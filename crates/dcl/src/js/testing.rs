@@ -20,6 +20,7 @@ pub fn ops() -> Vec<OpDecl> {
         op_take_and_compare_snapshot(),
         op_log_test_result(),
         op_log_test_plan(),
+        op_set_camera_transform(),
     ]
 }
 
@@ -73,9 +74,30 @@ fn op_log_test_result(state: &mut OpState, #[serde] body: SceneTestResult) {
         name: body.name,
         success: body.ok,
         error: body.error,
+        stack: body.stack,
+        frames: body.total_frames,
+        seconds: body.total_time,
     });
 }
 
+#[op2]
+fn op_set_camera_transform(
+    state: &mut OpState,
+    #[serde] position: (f32, f32, f32),
+    #[serde] rotation: (f32, f32, f32, f32),
+) {
+    debug!("op_set_camera_transform");
+    let scene = state.borrow::<CrdtContext>().scene_id.0;
+
+    state
+        .borrow_mut::<RpcCalls>()
+        .push(RpcCall::TestCameraTransform {
+            scene,
+            position: [position.0, position.1, position.2],
+            rotation: [rotation.0, rotation.1, rotation.2, rotation.3],
+        });
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GreyPixelDiffResult {
     pub similarity: f64,
@@ -1,6 +1,9 @@
 use std::{cell::RefCell, rc::Rc};
 
-use common::rpc::RpcCall;
+use common::{
+    rpc::RpcCall,
+    traffic::{record_traffic, Direction, TrafficGroup},
+};
 use deno_core::{anyhow, error::AnyError, op2, ByteString, OpDecl, OpState, ResourceId};
 use deno_websocket::{CreateResponse, WebSocketPermissions};
 use tokio::sync::oneshot::channel;
@@ -63,6 +66,10 @@ where
         anyhow::bail!("User denied fetch request");
     }
 
+    // connections only, messages go through deno's own ops
+    let hash = state.borrow().borrow::<CrdtContext>().hash.clone();
+    record_traffic(TrafficGroup::Scene("websocket"), &hash, Direction::Out, 0);
+
     // set default headers
     let mut headers = headers.unwrap_or_default();
     if !headers
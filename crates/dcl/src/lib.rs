@@ -100,6 +100,7 @@ pub fn spawn_scene(
     inspect: bool,
     testing: bool,
     preview: bool,
+    fixed_dt: Option<f32>,
     super_user: Option<tokio::sync::mpsc::UnboundedSender<SystemApi>>,
 ) -> Sender<RendererResponse> {
     let (main_sx, thread_rx) = tokio::sync::mpsc::channel::<RendererResponse>(1);
@@ -122,6 +123,7 @@ pub fn spawn_scene(
                     inspect,
                     testing,
                     preview,
+                    fixed_dt,
                     super_user,
                 )
             }));
@@ -51,6 +51,11 @@ fn gen_sdk_components() -> Result<()> {
         "camera_layer",
         "camera_layers",
         "primary_pointer_info",
+        "post_processing",
+        "skybox",
+        "particle_system",
+        "reverb_zone",
+        "audio_stream_metadata",
     ];
 
     let mut sources = components
@@ -90,8 +95,21 @@ fn gen_social_service() -> Result<()> {
     Ok(())
 }
 
+fn gen_quests_service() -> Result<()> {
+    let mut conf = prost_build::Config::new();
+    conf.service_generator(Box::new(dcl_rpc::codegen::RPCServiceGenerator::new()));
+    conf.type_attribute("*", "#[derive(Debug)]");
+    conf.compile_protos(
+        &["src/proto/decentraland/quests/definitions.proto"],
+        &["src/proto"],
+    )?;
+    println!("cargo:rerun-if-changed=src/proto/decentraland/quests/definitions.proto");
+    Ok(())
+}
+
 fn main() -> Result<()> {
     gen_sdk_components()?;
     gen_social_service()?;
+    gen_quests_service()?;
     Ok(())
 }
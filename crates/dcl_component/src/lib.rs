@@ -124,6 +124,11 @@ impl SceneComponentId {
     pub const CAMERA_LAYERS: SceneComponentId = SceneComponentId(1208);
     pub const PRIMARY_POINTER_INFO: SceneComponentId = SceneComponentId(1209);
     pub const CAMERA_LAYER: SceneComponentId = SceneComponentId(1210);
+    pub const POST_PROCESSING: SceneComponentId = SceneComponentId(1211);
+    pub const SKYBOX: SceneComponentId = SceneComponentId(1212);
+    pub const PARTICLE_SYSTEM: SceneComponentId = SceneComponentId(1213);
+    pub const REVERB_ZONE: SceneComponentId = SceneComponentId(1214);
+    pub const AUDIO_STREAM_METADATA: SceneComponentId = SceneComponentId(1215);
 }
 
 #[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone, Copy, Default)]
@@ -47,6 +47,10 @@ pub mod social {
     ));
 }
 
+pub mod quests {
+    include!(concat!(env!("OUT_DIR"), "/decentraland.quests.rs"));
+}
+
 trait DclProtoComponent: prost::Message + Default {}
 
 impl<T: DclProtoComponent + Sync + Send + 'static> FromDclReader for T {
@@ -114,6 +118,11 @@ impl DclProtoComponent for sdk::components::PbTextureCamera {}
 impl DclProtoComponent for sdk::components::PbCameraLayers {}
 impl DclProtoComponent for sdk::components::PbPrimaryPointerInfo {}
 impl DclProtoComponent for sdk::components::PbCameraLayer {}
+impl DclProtoComponent for sdk::components::PbPostProcessing {}
+impl DclProtoComponent for sdk::components::PbSkybox {}
+impl DclProtoComponent for sdk::components::PbParticleSystem {}
+impl DclProtoComponent for sdk::components::PbReverbZone {}
+impl DclProtoComponent for sdk::components::PbAudioStreamMetadata {}
 
 // VECTOR2 conversions
 impl Copy for common::Vector2 {}
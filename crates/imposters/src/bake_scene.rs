@@ -3,6 +3,7 @@
 
 use bevy::{
     core::FrameCount,
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
     math::FloatOrd,
     prelude::*,
     render::{primitives::Aabb, view::RenderLayers},
@@ -14,7 +15,7 @@ use boimp::{
 };
 use common::{
     sets::SceneSets,
-    structs::{AppConfig, PrimaryUser, SceneImposterBake},
+    structs::{AppConfig, PowerSaving, PrimaryUser, SceneImposterBake},
 };
 use crc::CRC_32_CKSUM;
 use ipfs::{CurrentRealm, IpfsAssetServer};
@@ -22,7 +23,7 @@ use scene_material::{BoundRegion, SceneBound, SceneMaterial};
 
 use scene_runner::{
     initialize_scene::{
-        CurrentImposterScene, LiveScenes, PointerResult, ScenePointers, PARCEL_SIZE,
+        CurrentImposterScene, LiveScenes, PointerResult, SceneLoading, ScenePointers, PARCEL_SIZE,
     },
     renderer_context::RendererSceneContext,
     DebugInfo,
@@ -47,14 +48,17 @@ impl Plugin for DclImposterBakeScenePlugin {
         app.add_plugins(ImposterBakePlugin)
             .init_resource::<ImposterBakeList>()
             .init_resource::<CurrentImposterImposter>()
+            .init_resource::<ImposterBakeProgress>()
             .add_systems(
                 Update,
                 (
+                    check_bake_headroom,
                     make_scene_oven,
                     bake_scene_imposters,
                     bake_imposter_imposter,
                     check_bake_state,
                     pick_imposter_to_bake,
+                    update_bake_progress,
                 )
                     .chain()
                     .before(crate::render::spawn_imposters)
@@ -67,6 +71,7 @@ impl Plugin for DclImposterBakeScenePlugin {
 pub struct ImposterOven {
     start_tick: u32,
     hash: String,
+    total_regions: usize,
     unbaked_parcels: Vec<BoundRegion>,
     all_parcels: HashSet<IVec2>,
     baked_scene: BakedScene,
@@ -114,10 +119,12 @@ fn make_scene_oven(
                 commands.spawn(ImposterOven {
                     start_tick: tick.0,
                     hash: hash.clone(),
+                    total_regions: 1,
                     unbaked_parcels: vec![BoundRegion::new(*parcel, *parcel, 1)],
                     all_parcels: HashSet::from_iter([*parcel]),
                     baked_scene: BakedScene {
                         crc: crc::Crc::<u32>::new(&CRC_32_CKSUM).checksum(hash.as_bytes()),
+                        parcels: vec![*parcel],
                         ..Default::default()
                     },
                 });
@@ -194,10 +201,12 @@ fn make_scene_oven(
         commands.spawn(ImposterOven {
             start_tick: tick.0,
             hash: hash.clone(),
+            total_regions: unbaked_parcels.len(),
             unbaked_parcels,
             all_parcels: context.parcels.clone(),
             baked_scene: BakedScene {
                 crc: crc::Crc::<u32>::new(&CRC_32_CKSUM).checksum(hash.as_bytes()),
+                parcels: context.parcels.iter().copied().collect(),
                 ..Default::default()
             },
         });
@@ -218,6 +227,7 @@ fn bake_scene_imposters(
     mut materials: ResMut<Assets<SceneMaterial>>,
     lookup: Res<ImposterEntities>,
     config: Res<AppConfig>,
+    progress: Res<ImposterBakeProgress>,
 ) {
     if let Ok((baking_ent, mut oven)) = baking.get_single_mut() {
         let current_scene_ent = {
@@ -342,9 +352,7 @@ fn bake_scene_imposters(
                     .clamp(2.0, TILE_SIZE as f32 * 2.0) as u32;
                 warn!("tile size: {tile_size}");
 
-                let max_tiles_per_frame = ((GRID_SIZE * GRID_SIZE) as f32
-                    * config.scene_imposter_bake.as_mult())
-                .ceil() as usize;
+                let max_tiles_per_frame = max_tiles_per_frame(&config, &progress);
 
                 let mut camera = ImposterBakeCamera {
                     radius,
@@ -441,6 +449,7 @@ fn bake_imposter_imposter(
     mut layers: Query<&mut RenderLayers>,
     tick: Res<FrameCount>,
     config: Res<AppConfig>,
+    progress: Res<ImposterBakeProgress>,
 ) {
     if baking.is_some() {
         let all_cams_finished = all_baking_cams
@@ -542,9 +551,7 @@ fn bake_imposter_imposter(
                 .clamp(2.0, 256.0) as u32;
             warn!("tile size: {tile_size}");
 
-            let max_tiles_per_frame = ((GRID_SIZE * GRID_SIZE) as f32
-                * config.scene_imposter_bake.as_mult())
-            .ceil() as usize;
+            let max_tiles_per_frame = max_tiles_per_frame(&config, &progress);
 
             let mut camera = ImposterBakeCamera {
                 radius,
@@ -633,6 +640,8 @@ fn pick_imposter_to_bake(
     mut baking: ResMut<ImposterBakeList>,
     current_realm: Res<CurrentRealm>,
     config: Res<AppConfig>,
+    loading_scenes: Query<(), With<SceneLoading>>,
+    mut progress: ResMut<ImposterBakeProgress>,
 ) {
     if config.scene_imposter_bake == SceneImposterBake::Off {
         return;
@@ -651,6 +660,12 @@ fn pick_imposter_to_bake(
         return;
     }
 
+    // only start new bakes when scenes have finished loading and frames are within budget
+    if progress.paused || !loading_scenes.is_empty() {
+        progress.paused = true;
+        return;
+    }
+
     let focus = focus
         .get_single()
         .map(|gt| gt.translation())
@@ -728,25 +743,7 @@ fn check_bake_state(
     mut ingredients: ResMut<BakingIngredients>,
     lookup: ImposterLookup,
     mut scene_pointers: ResMut<ScenePointers>,
-    mut debug_info: ResMut<DebugInfo>,
 ) {
-    if !baking.0.is_empty() {
-        debug_info.info.insert(
-            "Imposter Generation",
-            format!(
-                "{:?}",
-                baking
-                    .0
-                    .iter()
-                    .map(|p| format!("{p:?}"))
-                    .collect::<Vec<_>>()
-                    .join(" ")
-            ),
-        );
-    } else {
-        debug_info.info.remove("Imposter Generation");
-    }
-
     ingredients.0.clear();
 
     if let Some(imposter) = baking.0.last() {
@@ -855,3 +852,91 @@ fn check_bake_state(
         }
     }
 }
+
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ImposterBakeProgress {
+    // the imposter currently being baked
+    pub current: Option<String>,
+    // regions of the current scene baked so far, and in total
+    pub regions: Option<(usize, usize)>,
+    // imposters waiting on the current bake
+    pub queued: usize,
+    // baking is throttled to leave room for the main view, or waiting for scenes to load
+    pub paused: bool,
+}
+
+fn max_tiles_per_frame(config: &AppConfig, progress: &ImposterBakeProgress) -> usize {
+    if progress.paused {
+        return 1;
+    }
+
+    ((GRID_SIZE * GRID_SIZE) as f32 * config.scene_imposter_bake.as_mult()).ceil() as usize
+}
+
+// throttle baking when the frame time is over the target
+fn check_bake_headroom(
+    config: Res<AppConfig>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut progress: ResMut<ImposterBakeProgress>,
+    mut cams: Query<&mut ImposterBakeCamera>,
+    power_saving: Option<Res<PowerSaving>>,
+    time: Res<Time>,
+) {
+    if config.scene_imposter_bake == SceneImposterBake::Off {
+        return;
+    }
+
+    let budget_ms = 1000.0 / config.graphics.fps_target.max(1) as f64;
+    // the diagnostic is only registered with `log_fps`, otherwise use the last frame's time
+    let frame_ms = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|frame_time| frame_time.smoothed())
+        .unwrap_or_else(|| time.delta_seconds_f64() * 1000.0);
+    progress.paused = frame_ms > budget_ms * 1.15 || power_saving.is_some_and(|ps| ps.0);
+
+    let max_tiles = max_tiles_per_frame(&config, &progress);
+    for mut cam in cams.iter_mut() {
+        // top-down floor bakes are a single tile
+        if cam.grid_size > 1 && cam.max_tiles_per_frame != max_tiles {
+            cam.max_tiles_per_frame = max_tiles;
+        }
+    }
+}
+
+fn update_bake_progress(
+    baking: Res<ImposterBakeList>,
+    ovens: Query<&ImposterOven>,
+    mut progress: ResMut<ImposterBakeProgress>,
+    mut debug_info: ResMut<DebugInfo>,
+) {
+    progress.current = baking.0.last().map(|imposter| match imposter {
+        ImposterToBake::Scene(parcel, _) => format!("scene @ {parcel}"),
+        ImposterToBake::Mip(parcel, level) => format!("level {level} @ {parcel}"),
+    });
+    progress.regions = ovens.get_single().ok().map(|oven| {
+        (
+            oven.total_regions - oven.unbaked_parcels.len(),
+            oven.total_regions,
+        )
+    });
+    progress.queued = baking.0.len().saturating_sub(1);
+
+    let status = match (&progress.current, progress.regions) {
+        (None, _) if progress.paused => "paused".to_owned(),
+        (None, _) => {
+            debug_info.info.remove("Imposter Generation");
+            return;
+        }
+        (Some(current), Some((done, total))) => format!("{current} (region {done}/{total})"),
+        (Some(current), None) => current.clone(),
+    };
+    let throttled = if progress.paused && progress.current.is_some() {
+        ", throttled"
+    } else {
+        ""
+    };
+    debug_info.info.insert(
+        "Imposter Generation",
+        format!("{status}, {} queued{throttled}", progress.queued),
+    );
+}
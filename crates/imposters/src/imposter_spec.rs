@@ -1,8 +1,14 @@
-use std::{path::PathBuf, str::FromStr, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
+use anyhow::{anyhow, bail};
 use bevy::{asset::AsyncReadExt, prelude::*, utils::HashMap};
 use common::structs::IVec2Arg;
 use ipfs::{IpfsAssetServer, IpfsIo};
+use isahc::AsyncReadResponseExt;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Debug, Serialize, Deserialize, Component, Clone)]
@@ -18,6 +24,9 @@ pub struct BakedScene {
     #[serde(deserialize_with = "imposter_deserialize")]
     pub imposters: HashMap<IVec2, ImposterSpec>,
     pub crc: u32,
+    // all parcels with a floor texture, including those with no imposter
+    #[serde(default)]
+    pub parcels: Vec<IVec2>,
 }
 
 fn imposter_serialize<S>(val: &HashMap<IVec2, ImposterSpec>, s: S) -> Result<S::Ok, S::Error>
@@ -104,6 +113,7 @@ pub async fn load_imposter(
     parcel: IVec2,
     level: usize,
     required_crc: Option<u32>,
+    remote: Option<String>,
 ) -> Option<BakedScene> {
     // try locally
     let path = spec_path(&ipfs, &id, parcel, level);
@@ -126,7 +136,80 @@ pub async fn load_imposter(
         };
     }
 
-    // TODO try remote
+    // try remote
+    if let Some(remote) = remote {
+        match load_remote_imposter(&ipfs, &remote, &id, parcel, level, required_crc).await {
+            Ok(baked_scene) => return Some(baked_scene),
+            Err(e) => debug!("no remote imposter for {id} {parcel} {level}: {e}"),
+        }
+    }
 
     None
 }
+
+// the remote service mirrors the local cache layout under `imposters/`
+async fn fetch_remote(ipfs: &IpfsIo, remote: &str, path: &Path) -> Result<Vec<u8>, anyhow::Error> {
+    let relative = path.strip_prefix(ipfs.cache_path())?;
+    let relative = relative
+        .iter()
+        .map(|component| urlencoding::encode(&component.to_string_lossy()).into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    let url = format!("{}/{relative}", remote.trim_end_matches('/'));
+
+    let request = isahc::Request::get(&url).body(())?;
+    let mut response = ipfs.async_request(request, None).await?;
+    if !response.status().is_success() {
+        bail!("{url}: status {}", response.status());
+    }
+    Ok(response.bytes().await?)
+}
+
+async fn write_cache(path: &Path, bytes: &[u8]) -> Result<(), anyhow::Error> {
+    async_fs::create_dir_all(path.parent().ok_or(anyhow!("no parent"))?).await?;
+    async_fs::write(path, bytes).await?;
+    Ok(())
+}
+
+async fn load_remote_imposter(
+    ipfs: &IpfsIo,
+    remote: &str,
+    id: &str,
+    parcel: IVec2,
+    level: usize,
+    required_crc: Option<u32>,
+) -> Result<BakedScene, anyhow::Error> {
+    let spec_path = spec_path(ipfs, id, parcel, level);
+    let spec_bytes = fetch_remote(ipfs, remote, &spec_path).await?;
+    let baked_scene = serde_json::from_slice::<BakedScene>(&spec_bytes)?;
+    if required_crc.is_some_and(|crc| crc != baked_scene.crc) {
+        bail!("mismatched crc");
+    }
+
+    let parcels = if level == 0 {
+        baked_scene.imposters.keys().copied().collect::<Vec<_>>()
+    } else {
+        vec![parcel]
+    };
+    for parcel in parcels.iter() {
+        let path = texture_path(ipfs, id, *parcel, level);
+        write_cache(&path, &fetch_remote(ipfs, remote, &path).await?).await?;
+    }
+
+    // floors are only baked when the crc is non-zero
+    if baked_scene.crc != 0 {
+        let floor_parcels = if level == 0 && !baked_scene.parcels.is_empty() {
+            baked_scene.parcels.clone()
+        } else {
+            parcels
+        };
+        for parcel in floor_parcels {
+            let path = floor_path(ipfs, id, parcel, level);
+            write_cache(&path, &fetch_remote(ipfs, remote, &path).await?).await?;
+        }
+    }
+
+    // write the spec last so an interrupted download is retried
+    write_cache(&spec_path, &spec_bytes).await?;
+    Ok(baked_scene)
+}
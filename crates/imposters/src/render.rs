@@ -229,13 +229,14 @@ pub struct SceneImposter {
 pub struct ImposterLoadTask(Task<Option<BakedScene>>);
 
 impl ImposterLoadTask {
-    pub fn new_scene(ipfas: &IpfsAssetServer, scene_hash: &str) -> Self {
+    pub fn new_scene(ipfas: &IpfsAssetServer, scene_hash: &str, remote: Option<String>) -> Self {
         Self(IoTaskPool::get().spawn(load_imposter(
             ipfas.ipfs().clone(),
             scene_hash.to_string(),
             IVec2::MAX,
             0,
             None, // don't need to check since we load by id
+            remote,
         )))
     }
 
@@ -245,6 +246,7 @@ impl ImposterLoadTask {
         parcel: IVec2,
         level: usize,
         crc: u32,
+        remote: Option<String>,
     ) -> Self {
         Self(IoTaskPool::get().spawn(load_imposter(
             ipfas.ipfs().clone(),
@@ -252,6 +254,7 @@ impl ImposterLoadTask {
             parcel,
             level,
             Some(crc),
+            remote,
         )))
     }
 }
@@ -442,6 +445,7 @@ fn load_imposters(
     mut scene_pointers: ResMut<ScenePointers>,
     ipfas: IpfsAssetServer,
     current_realm: Res<CurrentRealm>,
+    config: Res<AppConfig>,
 ) {
     // create any new load tasks
     for (ent, imposter) in new_imposters.iter() {
@@ -451,7 +455,14 @@ fn load_imposters(
                     loading_scenes
                         .entry(hash.clone())
                         .or_insert_with(|| {
-                            (ImposterLoadTask::new_scene(&ipfas, hash), Vec::default())
+                            (
+                                ImposterLoadTask::new_scene(
+                                    &ipfas,
+                                    hash,
+                                    config.scene_imposter_service.clone(),
+                                ),
+                                Vec::default(),
+                            )
                         })
                         .1
                         .push((ent, imposter.parcel));
@@ -480,6 +491,7 @@ fn load_imposters(
                     imposter.parcel,
                     imposter.level,
                     crc,
+                    config.scene_imposter_service.clone(),
                 ));
         } else {
             commands.entity(ent).try_insert(RetryImposter);
@@ -0,0 +1,82 @@
+// analog input from the mouse and gamepads, shaped by the user's `InputAxisSettings`
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::gamepad::{ButtonSettings, GamepadAxisType, GamepadSettings, Gamepads},
+    prelude::*,
+};
+use common::structs::{AppConfig, AxisResponse};
+
+use crate::AcceptInput;
+
+pub struct AxesPlugin;
+
+impl Plugin for AxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_trigger_thresholds.run_if(resource_changed::<AppConfig>),
+        );
+    }
+}
+
+// trigger buttons press at the configured response rather than bevy's fixed threshold
+fn update_trigger_thresholds(config: Res<AppConfig>, mut settings: ResMut<GamepadSettings>) {
+    let triggers = &config.input_axes.triggers;
+    let press = triggers.threshold(0.5).clamp(0.02, 1.0);
+    let release = press * 0.9;
+    match ButtonSettings::new(press, release) {
+        Ok(button_settings) => settings.default_button_settings = button_settings,
+        Err(e) => warn!("invalid trigger threshold {press}: {e}"),
+    }
+}
+
+#[derive(SystemParam)]
+pub struct AnalogInput<'w> {
+    config: Res<'w, AppConfig>,
+    gamepads: Res<'w, Gamepads>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+    should_accept: Res<'w, AcceptInput>,
+}
+
+impl AnalogInput<'_> {
+    pub fn mouse(&self, delta: Vec2) -> Vec2 {
+        delta * self.config.input_axes.mouse.sensitivity
+    }
+
+    // strongest deflection over all connected gamepads, y up
+    fn stick(&self, x: GamepadAxisType, y: GamepadAxisType, response: &AxisResponse) -> Vec2 {
+        if !self.should_accept.gamepad {
+            return Vec2::ZERO;
+        }
+
+        self.gamepads
+            .iter()
+            .map(|gamepad| {
+                let axis = |axis_type| {
+                    self.axes
+                        .get(GamepadAxis::new(gamepad, axis_type))
+                        .unwrap_or(0.0)
+                };
+                response.apply_2d(Vec2::new(axis(x), axis(y)))
+            })
+            .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+            .unwrap_or(Vec2::ZERO)
+    }
+
+    pub fn move_stick(&self) -> Vec2 {
+        self.stick(
+            GamepadAxisType::LeftStickX,
+            GamepadAxisType::LeftStickY,
+            &self.config.input_axes.move_stick,
+        )
+    }
+
+    pub fn look_stick(&self) -> Vec2 {
+        self.stick(
+            GamepadAxisType::RightStickX,
+            GamepadAxisType::RightStickY,
+            &self.config.input_axes.look_stick,
+        )
+    }
+}
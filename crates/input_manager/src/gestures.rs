@@ -0,0 +1,163 @@
+// timing for tap, double-tap and hold bindings. each frame the raw state of every input bound
+// with a gesture is tracked, and the gestures that are down / just down / just up are recorded
+// for the `InputManager` to match against. timings are scaled by the accessibility timing scale.
+
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use common::structs::{AppConfig, InputGesture, InputItem};
+
+use crate::InputMap;
+
+// longest press that counts as a tap
+pub const TAP_SECS: f32 = 0.25;
+// longest gap between a tap and the second press of a double tap
+pub const DOUBLE_TAP_SECS: f32 = 0.3;
+// how long a press must be held to count as a hold
+pub const HOLD_SECS: f32 = 0.5;
+
+pub struct GesturePlugin;
+
+impl Plugin for GesturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputGestures>();
+        app.add_systems(PreUpdate, update_gestures.after(InputSystem));
+    }
+}
+
+#[derive(Default)]
+struct ItemState {
+    pressed_at: Option<f32>,
+    // end of the last press, if it was a tap
+    tapped_at: Option<f32>,
+    double: bool,
+    held: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct InputGestures {
+    states: HashMap<InputItem, ItemState>,
+    down: HashSet<(InputItem, InputGesture)>,
+    just_down: HashSet<(InputItem, InputGesture)>,
+    just_up: HashSet<(InputItem, InputGesture)>,
+}
+
+impl InputGestures {
+    pub fn down(&self, item: InputItem, gesture: InputGesture) -> bool {
+        self.down.contains(&(item, gesture))
+    }
+
+    pub fn just_down(&self, item: InputItem, gesture: InputGesture) -> bool {
+        self.just_down.contains(&(item, gesture))
+    }
+
+    pub fn just_up(&self, item: InputItem, gesture: InputGesture) -> bool {
+        self.just_up.contains(&(item, gesture))
+    }
+}
+
+pub(crate) fn update_gestures(
+    map: Res<InputMap>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad: Res<ButtonInput<GamepadButton>>,
+    time: Res<Time<Real>>,
+    config: Res<AppConfig>,
+    mut gestures: ResMut<InputGestures>,
+) {
+    let scale = config.input_accessibility.timing_scale;
+    let (tap_secs, double_tap_secs, hold_secs) =
+        (TAP_SECS * scale, DOUBLE_TAP_SECS * scale, HOLD_SECS * scale);
+
+    let gestures = &mut *gestures;
+    gestures.down.clear();
+    gestures.just_down.clear();
+    gestures.just_up.clear();
+
+    let items = map
+        .bindings()
+        .filter(|binding| binding.gesture != InputGesture::Press && binding.item != InputItem::Any)
+        .map(|binding| binding.item)
+        .collect::<HashSet<_>>();
+    gestures.states.retain(|item, _| items.contains(item));
+
+    let now = time.elapsed_seconds();
+    for item in items {
+        let (just_pressed, pressed, just_released) = match item {
+            InputItem::Key(k) => (keys.just_pressed(k), keys.pressed(k), keys.just_released(k)),
+            InputItem::Mouse(mb) => (
+                mouse.just_pressed(mb),
+                mouse.pressed(mb),
+                mouse.just_released(mb),
+            ),
+            InputItem::Gamepad(b) => (
+                gamepad.get_just_pressed().any(|g| g.button_type == b),
+                gamepad.get_pressed().any(|g| g.button_type == b),
+                gamepad.get_just_released().any(|g| g.button_type == b),
+            ),
+            InputItem::Any => continue,
+        };
+
+        let state = gestures.states.entry(item).or_default();
+        let mut emit = |gesture, just_down: bool, down: bool, just_up: bool| {
+            if just_down {
+                gestures.just_down.insert((item, gesture));
+            }
+            if down {
+                gestures.down.insert((item, gesture));
+            }
+            if just_up {
+                gestures.just_up.insert((item, gesture));
+            }
+        };
+
+        if just_pressed {
+            state.double = state
+                .tapped_at
+                .is_some_and(|tapped| now - tapped <= double_tap_secs);
+            state.tapped_at = None;
+            state.pressed_at = Some(now);
+            state.held = false;
+            if state.double {
+                emit(InputGesture::DoubleTap, true, false, false);
+            }
+        }
+
+        if let Some(pressed_at) = state.pressed_at {
+            if pressed || just_released {
+                if state.double {
+                    emit(InputGesture::DoubleTap, false, true, false);
+                }
+                if !state.held && now - pressed_at >= hold_secs {
+                    state.held = true;
+                    emit(InputGesture::Hold, true, false, false);
+                }
+                if state.held {
+                    emit(InputGesture::Hold, false, true, false);
+                }
+            }
+
+            if just_released || !pressed {
+                if now - pressed_at <= tap_secs && !state.held {
+                    // taps are down for a single frame
+                    emit(InputGesture::Tap, true, true, true);
+                    // the second press of a double tap doesn't start another
+                    if !state.double {
+                        state.tapped_at = Some(now);
+                    }
+                }
+                if state.held {
+                    emit(InputGesture::Hold, false, false, true);
+                }
+                if state.double {
+                    emit(InputGesture::DoubleTap, false, false, true);
+                }
+                state.pressed_at = None;
+                state.double = false;
+                state.held = false;
+            }
+        }
+    }
+}
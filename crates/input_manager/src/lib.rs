@@ -1,16 +1,36 @@
 // input settings
 
+pub mod axes;
+pub mod gestures;
+pub mod playback;
+pub mod rumble;
+pub mod toggles;
+pub mod touch;
+pub mod virtual_actions;
+
 use bimap::BiMap;
 
 use bevy::{ecs::system::SystemParam, prelude::*, ui::UiSystem, window::PrimaryWindow};
 use bevy_console::ConsoleOpen;
 use bevy_egui::EguiContext;
 
+use axes::AxesPlugin;
+use common::structs::{AppConfig, CommandBinding, InputBindings, InputGesture, InputModifier};
 use dcl_component::proto_components::sdk::components::common::InputAction;
+use gestures::{GesturePlugin, InputGestures};
+use playback::InputPlaybackPlugin;
+use rumble::RumblePlugin;
+use toggles::{TogglePlugin, ToggledActions};
+use touch::{TouchInput, TouchPlugin, TouchPointer};
 use ui_core::{
     focus::{BlockKeyboard, Focus},
+    gamepad_nav::{update_gamepad_nav, GamepadFocus},
+    ime::ImeComposition,
     ui_actions::UiActionSet,
 };
+use virtual_actions::{VirtualActionPlugin, VirtualActions};
+
+pub use common::structs::{InputBinding, InputItem};
 
 pub struct InputManagerPlugin;
 
@@ -18,12 +38,26 @@ impl Plugin for InputManagerPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<InputMap>();
         app.init_resource::<AcceptInput>();
+        app.add_plugins((
+            TouchPlugin,
+            RumblePlugin,
+            GesturePlugin,
+            AxesPlugin,
+            TogglePlugin,
+            VirtualActionPlugin,
+            InputPlaybackPlugin,
+        ));
         app.add_systems(
             PreUpdate,
             check_accept_input
                 .after(UiSystem::Focus)
+                .after(update_gamepad_nav)
                 .before(UiActionSet),
         );
+        app.add_systems(
+            Update,
+            update_input_map.run_if(resource_changed::<AppConfig>),
+        );
     }
 }
 
@@ -31,38 +65,183 @@ impl Plugin for InputManagerPlugin {
 #[derive(Component)]
 pub struct MouseInteractionComponent;
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BindingProfile {
+    Keyboard,
+    Gamepad,
+}
+
+impl BindingProfile {
+    pub fn accepts(&self, binding: &InputBinding) -> bool {
+        match self {
+            BindingProfile::Keyboard => {
+                matches!(binding.item, InputItem::Key(_) | InputItem::Mouse(_))
+            }
+            // chords use keyboard modifiers
+            BindingProfile::Gamepad => {
+                matches!(binding.item, InputItem::Gamepad(_)) && binding.modifier.is_none()
+            }
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct InputMap {
-    inputs: BiMap<InputAction, InputItem>,
+    inputs: BiMap<InputAction, InputBinding>,
+    gamepad: BiMap<InputAction, InputBinding>,
+    commands: Vec<CommandBinding>,
 }
 
 impl Default for InputMap {
     fn default() -> Self {
         Self {
-            inputs: BiMap::from_iter([
-                (InputAction::IaAny, InputItem::Any),
-                (InputAction::IaPointer, InputItem::Mouse(MouseButton::Left)),
-                (InputAction::IaPrimary, InputItem::Key(KeyCode::KeyE)),
-                (InputAction::IaSecondary, InputItem::Key(KeyCode::KeyF)),
-                (InputAction::IaForward, InputItem::Key(KeyCode::KeyW)),
-                (InputAction::IaBackward, InputItem::Key(KeyCode::KeyS)),
-                (InputAction::IaRight, InputItem::Key(KeyCode::KeyD)),
-                (InputAction::IaLeft, InputItem::Key(KeyCode::KeyA)),
-                (InputAction::IaJump, InputItem::Key(KeyCode::Space)),
-                (InputAction::IaWalk, InputItem::Key(KeyCode::ShiftLeft)),
-                (InputAction::IaAction3, InputItem::Key(KeyCode::Digit1)),
-                (InputAction::IaAction4, InputItem::Key(KeyCode::Digit2)),
-                (InputAction::IaAction5, InputItem::Key(KeyCode::Digit3)),
-                (InputAction::IaAction6, InputItem::Key(KeyCode::Digit4)),
-            ]),
+            inputs: BiMap::from_iter(
+                [
+                    (InputAction::IaAny, InputItem::Any),
+                    (InputAction::IaPointer, InputItem::Mouse(MouseButton::Left)),
+                    (InputAction::IaPrimary, InputItem::Key(KeyCode::KeyE)),
+                    (InputAction::IaSecondary, InputItem::Key(KeyCode::KeyF)),
+                    (InputAction::IaForward, InputItem::Key(KeyCode::KeyW)),
+                    (InputAction::IaBackward, InputItem::Key(KeyCode::KeyS)),
+                    (InputAction::IaRight, InputItem::Key(KeyCode::KeyD)),
+                    (InputAction::IaLeft, InputItem::Key(KeyCode::KeyA)),
+                    (InputAction::IaJump, InputItem::Key(KeyCode::Space)),
+                    (InputAction::IaWalk, InputItem::Key(KeyCode::ShiftLeft)),
+                    (InputAction::IaAction3, InputItem::Key(KeyCode::Digit1)),
+                    (InputAction::IaAction4, InputItem::Key(KeyCode::Digit2)),
+                    (InputAction::IaAction5, InputItem::Key(KeyCode::Digit3)),
+                    (InputAction::IaAction6, InputItem::Key(KeyCode::Digit4)),
+                ]
+                .map(|(action, item)| (action, item.into())),
+            ),
+            // the d-pad is left free for ui navigation
+            gamepad: BiMap::from_iter(
+                [
+                    (InputAction::IaPointer, GamepadButtonType::RightTrigger2),
+                    (InputAction::IaPrimary, GamepadButtonType::West),
+                    (InputAction::IaSecondary, GamepadButtonType::North),
+                    (InputAction::IaJump, GamepadButtonType::South),
+                    (InputAction::IaWalk, GamepadButtonType::LeftThumb),
+                    (InputAction::IaAction3, GamepadButtonType::LeftTrigger),
+                    (InputAction::IaAction4, GamepadButtonType::RightTrigger),
+                    (InputAction::IaAction5, GamepadButtonType::LeftTrigger2),
+                    (InputAction::IaAction6, GamepadButtonType::RightThumb),
+                ]
+                .map(|(action, button)| (action, InputItem::Gamepad(button).into())),
+            ),
+            commands: Vec::default(),
         }
     }
 }
 
 impl InputMap {
-    pub fn get_input(&self, action: InputAction) -> InputItem {
+    // actions that can be rebound, grouped by category
+    pub const CATEGORIES: [(&'static str, &'static [InputAction]); 3] = [
+        (
+            "Movement",
+            &[
+                InputAction::IaForward,
+                InputAction::IaBackward,
+                InputAction::IaLeft,
+                InputAction::IaRight,
+                InputAction::IaJump,
+                InputAction::IaWalk,
+            ],
+        ),
+        (
+            "Interaction",
+            &[
+                InputAction::IaPointer,
+                InputAction::IaPrimary,
+                InputAction::IaSecondary,
+            ],
+        ),
+        (
+            "Scene Actions",
+            &[
+                InputAction::IaAction3,
+                InputAction::IaAction4,
+                InputAction::IaAction5,
+                InputAction::IaAction6,
+            ],
+        ),
+    ];
+
+    pub fn from_bindings(bindings: &InputBindings) -> Self {
+        let mut map = Self::default();
+        for (profile, overrides) in [
+            (BindingProfile::Keyboard, &bindings.keyboard),
+            (BindingProfile::Gamepad, &bindings.gamepad),
+        ] {
+            for (name, binding) in overrides {
+                let Some(action) = InputAction::from_str_name(name) else {
+                    warn!("unknown input action in bindings: {name}");
+                    continue;
+                };
+                if action == InputAction::IaAny || !profile.accepts(binding) {
+                    warn!("invalid binding for {name}: {binding:?}");
+                    continue;
+                }
+                // inserting replaces any other action bound to the same binding
+                map.profile_mut(profile).insert(action, *binding);
+            }
+        }
+        map.commands = bindings.commands.clone();
+        map
+    }
+
+    fn profile(&self, profile: BindingProfile) -> &BiMap<InputAction, InputBinding> {
+        match profile {
+            BindingProfile::Keyboard => &self.inputs,
+            BindingProfile::Gamepad => &self.gamepad,
+        }
+    }
+
+    fn profile_mut(&mut self, profile: BindingProfile) -> &mut BiMap<InputAction, InputBinding> {
+        match profile {
+            BindingProfile::Keyboard => &mut self.inputs,
+            BindingProfile::Gamepad => &mut self.gamepad,
+        }
+    }
+
+    pub fn get_input(&self, action: InputAction) -> InputBinding {
         *self.inputs.get_by_left(&action).unwrap()
     }
+
+    pub fn get_binding(
+        &self,
+        profile: BindingProfile,
+        action: InputAction,
+    ) -> Option<InputBinding> {
+        self.profile(profile).get_by_left(&action).copied()
+    }
+
+    pub fn get_action(
+        &self,
+        profile: BindingProfile,
+        binding: InputBinding,
+    ) -> Option<InputAction> {
+        self.profile(profile).get_by_right(&binding).copied()
+    }
+
+    // all keyboard and gamepad bindings
+    pub fn bindings(&self) -> impl Iterator<Item = &InputBinding> {
+        self.inputs
+            .right_values()
+            .chain(self.gamepad.right_values())
+            .chain(self.commands.iter().map(|command| &command.binding))
+    }
+}
+
+fn update_input_map(config: Res<AppConfig>, mut map: ResMut<InputMap>) {
+    *map = InputMap::from_bindings(&config.input_bindings);
+}
+
+#[derive(Clone, Copy)]
+enum ItemCheck {
+    JustDownRaw,
+    JustUp,
+    Down,
 }
 
 #[derive(SystemParam)]
@@ -70,7 +249,12 @@ pub struct InputManager<'w> {
     map: Res<'w, InputMap>,
     mouse_input: Res<'w, ButtonInput<MouseButton>>,
     key_input: Res<'w, ButtonInput<KeyCode>>,
+    gamepad_input: Res<'w, ButtonInput<GamepadButton>>,
     should_accept: Res<'w, AcceptInput>,
+    touch: Res<'w, TouchInput>,
+    gestures: Res<'w, InputGestures>,
+    toggles: Res<'w, ToggledActions>,
+    virtual_actions: Res<'w, VirtualActions>,
 }
 
 impl InputManager<'_> {
@@ -79,136 +263,288 @@ impl InputManager<'_> {
             || self.mouse_input.get_just_released().len() != 0
             || self.key_input.get_just_pressed().len() != 0
             || self.key_input.get_just_released().len() != 0
+            || self.gamepad_input.get_just_pressed().len() != 0
+            || self.gamepad_input.get_just_released().len() != 0
+            || self.touch.pointer != TouchPointer::None
     }
 
-    pub fn just_down(&self, action: InputAction) -> bool {
-        self.map
-            .inputs
-            .get_by_left(&action)
-            .is_some_and(|item| match item {
-                InputItem::Key(k) => self.should_accept.key && self.key_input.just_pressed(*k),
-                InputItem::Mouse(mb) => {
-                    self.should_accept.mouse && self.mouse_input.just_pressed(*mb)
+    // touch taps act as the left mouse button. they only start over the world, so they bypass
+    // the mouse acceptance check
+    fn touch_pointer(&self, mb: MouseButton, state: TouchPointer) -> bool {
+        mb == MouseButton::Left && self.touch.pointer == state
+    }
+
+    fn mouse_just_pressed(&self, mb: MouseButton) -> bool {
+        (self.should_accept.mouse && self.mouse_input.just_pressed(mb))
+            || self.touch_pointer(mb, TouchPointer::JustPressed)
+    }
+
+    fn mouse_just_released(&self, mb: MouseButton) -> bool {
+        self.mouse_input.just_released(mb) || self.touch_pointer(mb, TouchPointer::JustReleased)
+    }
+
+    fn mouse_pressed(&self, mb: MouseButton) -> bool {
+        (self.should_accept.mouse && self.mouse_input.pressed(mb))
+            || self.touch_pointer(mb, TouchPointer::JustPressed)
+    }
+
+    // input from any connected gamepad
+    fn gamepad_just_pressed(&self, button: GamepadButtonType) -> bool {
+        self.should_accept.gamepad
+            && self
+                .gamepad_input
+                .get_just_pressed()
+                .any(|b| b.button_type == button)
+    }
+
+    fn gamepad_just_released(&self, button: GamepadButtonType) -> bool {
+        self.gamepad_input
+            .get_just_released()
+            .any(|b| b.button_type == button)
+    }
+
+    fn gamepad_pressed(&self, button: GamepadButtonType) -> bool {
+        self.should_accept.gamepad
+            && self
+                .gamepad_input
+                .get_pressed()
+                .any(|b| b.button_type == button)
+    }
+
+    fn item_just_down(&self, item: &InputItem) -> bool {
+        match item {
+            InputItem::Key(k) => self.should_accept.key && self.key_input.just_pressed(*k),
+            InputItem::Mouse(mb) => self.mouse_just_pressed(*mb),
+            InputItem::Gamepad(b) => self.gamepad_just_pressed(*b),
+            InputItem::Any => self.iter_just_down().next().is_some(),
+        }
+    }
+
+    // as item_just_down, but mouse buttons are not checked against ui interaction
+    fn item_just_down_raw(&self, item: &InputItem) -> bool {
+        match item {
+            InputItem::Mouse(m) => {
+                self.mouse_input.just_pressed(*m)
+                    || self.touch_pointer(*m, TouchPointer::JustPressed)
+            }
+            InputItem::Any => false,
+            item => self.item_just_down(item),
+        }
+    }
+
+    fn item_just_up(&self, item: &InputItem) -> bool {
+        match item {
+            InputItem::Key(k) => self.key_input.just_released(*k),
+            InputItem::Mouse(mb) => self.mouse_just_released(*mb),
+            InputItem::Gamepad(b) => self.gamepad_just_released(*b),
+            InputItem::Any => self.iter_just_up().next().is_some(),
+        }
+    }
+
+    fn item_down(&self, item: &InputItem) -> bool {
+        match item {
+            InputItem::Key(k) => self.should_accept.key && self.key_input.pressed(*k),
+            InputItem::Mouse(mb) => self.mouse_pressed(*mb),
+            InputItem::Gamepad(b) => self.gamepad_pressed(*b),
+            InputItem::Any => self.iter_down().next().is_some(),
+        }
+    }
+
+    fn accepts(&self, item: &InputItem) -> bool {
+        match item {
+            InputItem::Key(_) => self.should_accept.key,
+            InputItem::Mouse(_) => self.should_accept.mouse,
+            InputItem::Gamepad(_) => self.should_accept.gamepad,
+            InputItem::Any => true,
+        }
+    }
+
+    fn modifier_down(&self, modifier: InputModifier) -> bool {
+        modifier.keys().iter().any(|k| self.key_input.pressed(*k))
+    }
+
+    // a chord requires its modifier. a plain binding gives way to a chord on the same input
+    // while that chord's modifier is held, so ctrl+k doesn't also trigger k
+    fn modifier_matches(&self, binding: &InputBinding) -> bool {
+        match binding.modifier {
+            Some(modifier) => self.modifier_down(modifier),
+            None => !self.map.bindings().any(|other| {
+                other.item == binding.item
+                    && other.gesture == binding.gesture
+                    && other.modifier.is_some_and(|m| self.modifier_down(m))
+            }),
+        }
+    }
+
+    fn binding_just_down(&self, binding: &InputBinding, raw: bool) -> bool {
+        self.modifier_matches(binding)
+            && match binding.gesture {
+                InputGesture::Press if raw => self.item_just_down_raw(&binding.item),
+                InputGesture::Press => self.item_just_down(&binding.item),
+                gesture => {
+                    (self.accepts(&binding.item)
+                        || (raw && matches!(binding.item, InputItem::Mouse(_))))
+                        && self.gestures.just_down(binding.item, gesture)
                 }
-                InputItem::Any => self.iter_just_down().next().is_some(),
-            })
+            }
     }
 
-    pub fn just_up(&self, action: InputAction) -> bool {
-        self.map
-            .inputs
-            .get_by_left(&action)
-            .is_some_and(|item| match item {
-                InputItem::Key(k) => self.key_input.just_released(*k),
-                InputItem::Mouse(mb) => self.mouse_input.just_released(*mb),
-                InputItem::Any => self.iter_just_up().next().is_some(),
-            })
+    // releases are reported even if the chord's modifier was let go first
+    fn binding_just_up(&self, binding: &InputBinding) -> bool {
+        (binding.modifier.is_some() || self.modifier_matches(binding))
+            && match binding.gesture {
+                InputGesture::Press => self.item_just_up(&binding.item),
+                gesture => self.gestures.just_up(binding.item, gesture),
+            }
     }
 
-    pub fn is_down(&self, action: InputAction) -> bool {
+    fn binding_down(&self, binding: &InputBinding) -> bool {
+        self.modifier_matches(binding)
+            && match binding.gesture {
+                InputGesture::Press => self.item_down(&binding.item),
+                gesture => self.accepts(&binding.item) && self.gestures.down(binding.item, gesture),
+            }
+    }
+
+    // keyboard and gamepad bindings for the action
+    fn bindings(&self, action: InputAction) -> impl Iterator<Item = &InputBinding> {
         self.map
             .inputs
             .get_by_left(&action)
-            .is_some_and(|item| match item {
-                InputItem::Key(k) => self.should_accept.key && self.key_input.pressed(*k),
-                InputItem::Mouse(mb) => self.should_accept.mouse && self.mouse_input.pressed(*mb),
-                InputItem::Any => self.iter_down().next().is_some(),
-            })
+            .into_iter()
+            .chain(self.map.gamepad.get_by_left(&action))
     }
 
-    pub fn iter_just_down(&self) -> impl Iterator<Item = &InputAction> {
-        self.map
-            .inputs
-            .iter()
-            .filter(|(_, button)| match button {
-                InputItem::Key(k) => self.should_accept.key && self.key_input.just_pressed(*k),
-                InputItem::Mouse(m) => self.mouse_input.just_pressed(*m),
-                InputItem::Any => false,
-            })
-            .map(|(action, _)| action)
+    fn check(&self, check: ItemCheck, binding: &InputBinding) -> bool {
+        match check {
+            ItemCheck::JustDownRaw => self.binding_just_down(binding, true),
+            ItemCheck::JustUp => self.binding_just_up(binding),
+            ItemCheck::Down => self.binding_down(binding),
+        }
     }
 
-    pub fn iter_just_up(&self) -> impl Iterator<Item = &InputAction> {
-        self.map
+    // actions with a binding matching the check. `Any` bindings and toggled actions are excluded
+    fn iter_matching(&self, check: ItemCheck) -> impl Iterator<Item = &InputAction> {
+        let is_match = move |binding: &InputBinding| {
+            binding.item != InputItem::Any && self.check(check, binding)
+        };
+        let keyboard = self
+            .map
             .inputs
             .iter()
-            .filter(|(_, button)| match button {
-                InputItem::Key(k) => self.key_input.just_released(*k),
-                InputItem::Mouse(m) => self.mouse_input.just_released(*m),
-                InputItem::Any => false,
+            .filter(move |(action, binding)| {
+                !self.toggles.actions.contains(action) && is_match(binding)
             })
-            .map(|(action, _)| action)
-    }
-
-    pub fn iter_down(&self) -> impl Iterator<Item = &InputAction> {
-        self.map
-            .inputs
+            .map(|(action, _)| action);
+        // skip actions already matched by their keyboard binding
+        let gamepad = self
+            .map
+            .gamepad
             .iter()
-            .filter(|(_, button)| match button {
-                InputItem::Key(k) => self.should_accept.key && self.key_input.pressed(*k),
-                InputItem::Mouse(m) => self.should_accept.mouse && self.mouse_input.pressed(*m),
-                InputItem::Any => false,
+            .filter(move |(action, binding)| {
+                !self.toggles.actions.contains(action)
+                    && is_match(binding)
+                    && !self
+                        .map
+                        .inputs
+                        .get_by_left(action)
+                        .is_some_and(|binding| is_match(binding))
             })
-            .map(|(action, _)| action)
+            .map(|(action, _)| action);
+        keyboard.chain(gamepad)
     }
 
-    pub fn iter_up(&self) -> impl Iterator<Item = &InputAction> {
+    // the bindings' state, ignoring toggles
+    pub(crate) fn bound_just_down(&self, action: InputAction) -> bool {
+        self.bindings(action)
+            .any(|binding| self.binding_just_down(binding, false))
+    }
+
+    pub fn just_down(&self, action: InputAction) -> bool {
+        if self.virtual_actions.just_down.contains(&action) {
+            return true;
+        }
+        if self.toggles.actions.contains(&action) {
+            return self.toggles.just_on.contains(&action);
+        }
+        self.bound_just_down(action)
+    }
+
+    pub fn just_up(&self, action: InputAction) -> bool {
+        if self.virtual_actions.just_up.contains(&action) {
+            return true;
+        }
+        if self.toggles.actions.contains(&action) {
+            return self.toggles.just_off.contains(&action);
+        }
+        self.bindings(action)
+            .any(|binding| self.binding_just_up(binding))
+    }
+
+    pub fn is_down(&self, action: InputAction) -> bool {
+        if self.virtual_actions.down.contains(&action) {
+            return true;
+        }
+        if self.toggles.actions.contains(&action) {
+            return self.toggles.active.contains(&action);
+        }
+        self.bindings(action)
+            .any(|binding| self.binding_down(binding))
+    }
+
+    // shortcuts give way to actions and commands: true if the binding was just pressed and
+    // nothing else is bound to it
+    pub fn unbound_just_down(&self, binding: &InputBinding) -> bool {
+        !self.map.bindings().any(|other| other == binding) && self.binding_just_down(binding, false)
+    }
+
+    // console commands whose binding was just pressed
+    pub fn iter_commands_just_down(&self) -> impl Iterator<Item = &str> {
         self.map
-            .inputs
+            .commands
             .iter()
-            .filter(|(_, button)| match button {
-                InputItem::Key(k) => self.key_input.just_released(*k),
-                InputItem::Mouse(m) => self.mouse_input.just_released(*m),
-                InputItem::Any => false,
-            })
-            .map(|(action, _)| action)
+            .filter(|command| self.binding_just_down(&command.binding, false))
+            .map(|command| command.command.as_str())
     }
-}
 
-#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
-pub enum InputItem {
-    Key(KeyCode),
-    Mouse(MouseButton),
-    Any,
-}
+    pub fn iter_just_down(&self) -> impl Iterator<Item = &InputAction> {
+        self.iter_matching(ItemCheck::JustDownRaw)
+            .chain(self.toggles.just_on.iter())
+            .chain(self.virtual_actions.just_down.iter())
+    }
 
-impl std::fmt::Display for InputItem {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            InputItem::Key(k) => f.write_str(key_to_str(k).as_str()),
-            InputItem::Mouse(m) => f.write_fmt(format_args!("{:?}", m)),
-            InputItem::Any => f.write_str("(Any)"),
-        }
+    pub fn iter_just_up(&self) -> impl Iterator<Item = &InputAction> {
+        self.iter_matching(ItemCheck::JustUp)
+            .chain(self.toggles.just_off.iter())
+            .chain(self.virtual_actions.just_up.iter())
     }
-}
 
-// todo extend this when we make rebindable keys
-fn key_to_str(key: &KeyCode) -> String {
-    use KeyCode::*;
-    let str = match key {
-        Digit1 => "1",
-        Digit2 => "2",
-        Digit3 => "3",
-        Digit4 => "4",
-        Space => "Space",
-        ShiftLeft => "Left Shift",
-        _ => return format!("{:?}", key),
-    };
-    str.to_owned()
+    pub fn iter_down(&self) -> impl Iterator<Item = &InputAction> {
+        self.iter_matching(ItemCheck::Down)
+            .chain(self.toggles.active.iter())
+            .chain(self.virtual_actions.down.iter())
+    }
+
+    pub fn iter_up(&self) -> impl Iterator<Item = &InputAction> {
+        self.iter_just_up()
+    }
 }
 
 #[derive(Resource, Default)]
 pub struct AcceptInput {
     pub mouse: bool,
     pub key: bool,
+    pub gamepad: bool,
 }
 
-fn check_accept_input(
+pub(crate) fn check_accept_input(
     ui_roots: Query<&Interaction, With<MouseInteractionComponent>>,
     console: Res<ConsoleOpen>,
     mut ctx: Query<&mut EguiContext, With<PrimaryWindow>>,
     mut should_accept: ResMut<AcceptInput>,
     key_blocked: Query<Entity, (With<Focus>, With<BlockKeyboard>)>,
+    gamepad_focus: Res<GamepadFocus>,
+    composition: Res<ImeComposition>,
 ) {
     let Ok(mut ctx) = ctx.get_single_mut() else {
         return;
@@ -217,8 +553,13 @@ fn check_accept_input(
     should_accept.mouse = ui_roots
         .iter()
         .any(|root| !matches!(root, Interaction::None));
-    should_accept.key =
-        !console.open && !ctx.get_mut().wants_keyboard_input() && key_blocked.is_empty();
+    // keys used for an ime composition never reach the game
+    should_accept.key = !console.open
+        && !ctx.get_mut().wants_keyboard_input()
+        && key_blocked.is_empty()
+        && !composition.composing();
+    // gamepad buttons go to the ui while it has gamepad focus
+    should_accept.gamepad = !console.open && gamepad_focus.entity.is_none();
 }
 
 pub fn should_accept_key(should_accept: Res<AcceptInput>) -> bool {
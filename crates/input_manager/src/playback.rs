@@ -0,0 +1,242 @@
+// input recording and playback for end to end tests. `/input record <file>` captures the actions
+// going down and up each frame, and `/input play <file>` replays them through `VirtualActions` on
+// the same frame offsets, so a flow plays back the same way regardless of wall clock timing.
+
+use std::collections::VecDeque;
+
+use bevy::{core::FrameCount, prelude::*, utils::HashSet};
+use bevy_console::{ConsoleCommand, PrintConsoleLine};
+use console::DoAddConsoleCommand;
+use dcl_component::proto_components::sdk::components::common::InputAction;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    virtual_actions::{update_virtual_actions, VirtualActions},
+    InputManager,
+};
+
+pub struct InputPlaybackPlugin;
+
+impl Plugin for InputPlaybackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputPlayback>();
+        app.add_systems(PreUpdate, play_input.before(update_virtual_actions));
+        app.add_systems(PostUpdate, record_input);
+        app.add_console_command::<InputCommand, _>(input_command);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordedInput {
+    // frames and seconds since the recording started
+    pub frame: u32,
+    pub time: f32,
+    pub action: String,
+    pub down: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct InputRecording {
+    pub events: Vec<RecordedInput>,
+}
+
+struct Recording {
+    path: String,
+    start_frame: u32,
+    start_time: f32,
+    recording: InputRecording,
+}
+
+struct Playing {
+    start_frame: u32,
+    events: VecDeque<RecordedInput>,
+    held: HashSet<InputAction>,
+}
+
+#[derive(Resource, Default)]
+pub struct InputPlayback {
+    recording: Option<Recording>,
+    playing: Option<Playing>,
+}
+
+impl InputPlayback {
+    pub fn is_playing(&self) -> bool {
+        self.playing.is_some()
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum InputCommandAction {
+    Record,
+    Play,
+    Stop,
+}
+
+/// record input actions to a file, play them back, or stop either
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/input")]
+struct InputCommand {
+    action: InputCommandAction,
+    file: Option<String>,
+}
+
+fn stop_recording(playback: &mut InputPlayback) -> Option<Result<String, String>> {
+    let recording = playback.recording.take()?;
+    let count = recording.recording.events.len();
+    Some(
+        serde_json::to_string_pretty(&recording.recording)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(&recording.path, json).map_err(|e| e.to_string()))
+            .map(|_| format!("{count} input events written to {}", recording.path))
+            .map_err(|e| format!("failed to write {}: {e}", recording.path)),
+    )
+}
+
+fn stop_playing(playback: &mut InputPlayback, virtual_actions: &mut VirtualActions) -> bool {
+    let Some(playing) = playback.playing.take() else {
+        return false;
+    };
+    for action in playing.held {
+        virtual_actions.release(action);
+    }
+    true
+}
+
+fn input_command(
+    mut input: ConsoleCommand<InputCommand>,
+    mut playback: ResMut<InputPlayback>,
+    mut virtual_actions: ResMut<VirtualActions>,
+    frame: Res<FrameCount>,
+    time: Res<Time<Real>>,
+) {
+    let Some(Ok(command)) = input.take() else {
+        return;
+    };
+
+    match (command.action, command.file) {
+        (InputCommandAction::Record, Some(path)) => {
+            let _ = stop_recording(&mut playback);
+            playback.recording = Some(Recording {
+                path,
+                start_frame: frame.0,
+                start_time: time.elapsed_seconds(),
+                recording: Default::default(),
+            });
+            input.reply_ok("recording input");
+        }
+        (InputCommandAction::Play, Some(path)) => {
+            let recording = std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| {
+                    serde_json::from_slice::<InputRecording>(&bytes).map_err(|e| e.to_string())
+                });
+            match recording {
+                Ok(recording) => {
+                    stop_playing(&mut playback, &mut virtual_actions);
+                    let count = recording.events.len();
+                    playback.playing = Some(Playing {
+                        // starts next frame
+                        start_frame: frame.0.wrapping_add(1),
+                        events: recording.events.into(),
+                        held: Default::default(),
+                    });
+                    input.reply_ok(format!("playing {count} input events"));
+                }
+                Err(e) => input.reply_failed(format!("failed to read {path}: {e}")),
+            }
+        }
+        (InputCommandAction::Stop, _) => {
+            let stopped_playing = stop_playing(&mut playback, &mut virtual_actions);
+            match stop_recording(&mut playback) {
+                Some(Ok(message)) => input.reply_ok(message),
+                Some(Err(e)) => input.reply_failed(e),
+                None if stopped_playing => input.reply_ok("playback stopped"),
+                None => input.reply_failed("not recording or playing"),
+            }
+        }
+        (_, None) => input.reply_failed("a file is required"),
+    }
+}
+
+fn play_input(
+    mut playback: ResMut<InputPlayback>,
+    mut virtual_actions: ResMut<VirtualActions>,
+    frame: Res<FrameCount>,
+    mut console: EventWriter<PrintConsoleLine>,
+) {
+    let Some(playing) = playback.playing.as_mut() else {
+        return;
+    };
+
+    let offset = frame.0.wrapping_sub(playing.start_frame);
+    let mut held_this_frame = HashSet::default();
+    while playing
+        .events
+        .front()
+        .is_some_and(|event| event.frame <= offset)
+    {
+        let event = playing.events.pop_front().unwrap();
+        let Some(action) = InputAction::from_str_name(&event.action) else {
+            warn!("unknown action `{}` in input playback", event.action);
+            continue;
+        };
+        if event.down {
+            virtual_actions.hold(action);
+            playing.held.insert(action);
+            held_this_frame.insert(action);
+        } else {
+            virtual_actions.release(action);
+            playing.held.remove(&action);
+            if held_this_frame.contains(&action) {
+                // a tap, down for one frame
+                virtual_actions.press(action);
+            }
+        }
+    }
+
+    if playing.events.is_empty() {
+        stop_playing(&mut playback, &mut virtual_actions);
+        console.send(PrintConsoleLine::new("input playback finished".into()));
+    }
+}
+
+fn record_input(
+    mut playback: ResMut<InputPlayback>,
+    input_manager: InputManager,
+    frame: Res<FrameCount>,
+    time: Res<Time<Real>>,
+) {
+    let Some(recording) = playback.recording.as_mut() else {
+        return;
+    };
+
+    let frame = frame.0.wrapping_sub(recording.start_frame);
+    let time = time.elapsed_seconds() - recording.start_time;
+    let mut record = |action: &InputAction, down: bool| {
+        recording.recording.events.push(RecordedInput {
+            frame,
+            time,
+            action: action.as_str_name().to_owned(),
+            down,
+        });
+    };
+    // bindings and virtual presses may both report an action
+    let just_down = input_manager.iter_just_down().collect::<HashSet<_>>();
+    let just_up = input_manager.iter_just_up().collect::<HashSet<_>>();
+    for action in just_up.difference(&just_down) {
+        record(*action, false);
+    }
+    for action in just_down.iter() {
+        if !just_up.contains(action) {
+            record(*action, true);
+        } else if input_manager.is_down(**action) {
+            // released and pressed again
+            record(*action, false);
+            record(*action, true);
+        } else {
+            // tapped within the frame
+            record(*action, true);
+            record(*action, false);
+        }
+    }
+}
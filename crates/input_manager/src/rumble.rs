@@ -0,0 +1,90 @@
+// gamepad force feedback. systems send `RumbleRequest`s, which are combined and forwarded to all
+// connected gamepads. envelopes are approximated by re-sending the motor intensities when they
+// change noticeably.
+
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest, Gamepads},
+    prelude::*,
+};
+use common::structs::{AppConfig, RumbleRequest};
+
+// minimum intensity change before updating the motors
+const RUMBLE_STEP: f32 = 0.05;
+
+pub struct RumblePlugin;
+
+impl Plugin for RumblePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RumbleRequest>();
+        app.add_systems(Update, update_rumble);
+    }
+}
+
+fn update_rumble(
+    mut requests: EventReader<RumbleRequest>,
+    config: Res<AppConfig>,
+    gamepads: Res<Gamepads>,
+    time: Res<Time<Real>>,
+    mut rumble: EventWriter<GamepadRumbleRequest>,
+    mut active: Local<Vec<(f32, RumbleRequest)>>,
+    mut current: Local<Option<GamepadRumbleIntensity>>,
+) {
+    if !config.gamepad_rumble {
+        requests.clear();
+        active.clear();
+        if current.take().is_some() {
+            rumble.send_batch(
+                gamepads
+                    .iter()
+                    .map(|gamepad| GamepadRumbleRequest::Stop { gamepad }),
+            );
+        }
+        return;
+    }
+
+    let now = time.elapsed_seconds();
+    active.extend(requests.read().map(|request| (now, *request)));
+    active.retain(|(start, request)| now - start < request.duration());
+
+    if active.is_empty() {
+        // the last request to the motors expires by itself
+        *current = None;
+        return;
+    }
+
+    let mut intensity = GamepadRumbleIntensity {
+        strong_motor: 0.0,
+        weak_motor: 0.0,
+    };
+    let mut remaining = 0f32;
+    for (start, request) in active.iter() {
+        let elapsed = now - start;
+        let scale = request.envelope(elapsed);
+        intensity.strong_motor = intensity.strong_motor.max(request.strong * scale);
+        intensity.weak_motor = intensity.weak_motor.max(request.weak * scale);
+        remaining = remaining.max(request.duration() - elapsed);
+    }
+    intensity.strong_motor = intensity.strong_motor.clamp(0.0, 1.0);
+    intensity.weak_motor = intensity.weak_motor.clamp(0.0, 1.0);
+
+    let changed = current.map_or(true, |prev| {
+        (prev.strong_motor - intensity.strong_motor).abs() >= RUMBLE_STEP
+            || (prev.weak_motor - intensity.weak_motor).abs() >= RUMBLE_STEP
+    });
+    if !changed {
+        return;
+    }
+
+    // stop first, as added effects play concurrently rather than replacing each other
+    for gamepad in gamepads.iter() {
+        rumble.send(GamepadRumbleRequest::Stop { gamepad });
+        rumble.send(GamepadRumbleRequest::Add {
+            gamepad,
+            intensity,
+            duration: Duration::from_secs_f32(remaining),
+        });
+    }
+    *current = Some(intensity);
+}
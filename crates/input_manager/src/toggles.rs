@@ -0,0 +1,78 @@
+// accessibility option for actions that normally act while held (e.g. walk): each press of the
+// binding toggles the action on or off instead
+
+use bevy::{prelude::*, utils::HashSet};
+use common::structs::AppConfig;
+use dcl_component::proto_components::sdk::components::common::InputAction;
+
+use crate::{check_accept_input, gestures::update_gestures, InputManager};
+
+pub struct TogglePlugin;
+
+impl Plugin for TogglePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ToggledActions>();
+        app.add_systems(
+            PreUpdate,
+            update_toggles
+                .after(check_accept_input)
+                .after(update_gestures),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ToggledActions {
+    pub(crate) actions: HashSet<InputAction>,
+    pub(crate) active: HashSet<InputAction>,
+    pub(crate) just_on: HashSet<InputAction>,
+    pub(crate) just_off: HashSet<InputAction>,
+}
+
+fn update_toggles(
+    config: Res<AppConfig>,
+    mut params: ParamSet<(InputManager, ResMut<ToggledActions>)>,
+) {
+    let actions = config
+        .input_accessibility
+        .toggle_actions
+        .iter()
+        .filter_map(|name| InputAction::from_str_name(name))
+        .filter(|action| *action != InputAction::IaAny)
+        .collect::<HashSet<_>>();
+
+    let pressed = {
+        let input = params.p0();
+        actions
+            .iter()
+            .copied()
+            .filter(|action| input.bound_just_down(*action))
+            .collect::<Vec<_>>()
+    };
+
+    let toggles = &mut *params.p1();
+    toggles.just_on.clear();
+    toggles.just_off.clear();
+
+    // release actions that are no longer toggles
+    let removed = toggles
+        .active
+        .difference(&actions)
+        .copied()
+        .collect::<Vec<_>>();
+    for action in removed {
+        toggles.active.remove(&action);
+        toggles.just_off.insert(action);
+    }
+
+    for action in pressed {
+        if toggles.active.remove(&action) {
+            toggles.just_off.insert(action);
+        } else {
+            toggles.active.insert(action);
+            toggles.just_on.insert(action);
+        }
+    }
+
+    toggles.actions = actions;
+}
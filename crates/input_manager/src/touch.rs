@@ -0,0 +1,238 @@
+// touch controls: a virtual joystick on the left of the screen for movement, drags elsewhere to
+// turn the camera, and taps mapped to the pointer action. touches that start over ui are left to
+// the ui.
+
+use bevy::{input::touch::Touch, prelude::*, ui::UiScale, utils::HashMap, window::PrimaryWindow};
+
+use common::structs::AppConfig;
+
+use crate::{check_accept_input, AcceptInput};
+
+// fraction of the window width (from the left) where a touch starts the joystick
+const JOYSTICK_AREA: f32 = 0.4;
+// joystick deflection for full speed, in logical pixels
+const JOYSTICK_RADIUS: f32 = 60.0;
+// max duration and travel for a touch to count as a tap
+const TAP_SECS: f32 = 0.3;
+const TAP_DISTANCE: f32 = 15.0;
+// ui scale multiplier while using touch
+const TOUCH_UI_SCALE: f32 = 1.5;
+
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchInput>();
+        app.add_systems(Startup, setup_joystick);
+        app.add_systems(
+            PreUpdate,
+            (update_touch_input, update_touch_ui_scale)
+                .chain()
+                .after(check_accept_input),
+        );
+        app.add_systems(Update, update_joystick_display);
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TouchPointer {
+    #[default]
+    None,
+    JustPressed,
+    JustReleased,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct TouchInput {
+    // true after a touch, until the mouse or keyboard is used
+    pub active: bool,
+    // joystick deflection, y is forward. length <= 1
+    pub movement: Vec2,
+    // camera drag this frame in logical pixels
+    pub look_delta: Vec2,
+    // taps generate a pointer press on release, and a pointer release the following frame
+    pub pointer: TouchPointer,
+    pub pointer_position: Option<Vec2>,
+    joystick: Option<(u64, Vec2)>,
+}
+
+#[derive(Clone, Copy)]
+struct TouchStart {
+    time: f32,
+    over_world: bool,
+}
+
+fn update_touch_input(
+    touches: Res<Touches>,
+    mut touch_input: ResMut<TouchInput>,
+    accept_input: Res<AcceptInput>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    time: Res<Time>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut starts: Local<HashMap<u64, TouchStart>>,
+) {
+    let now = time.elapsed_seconds();
+    let width = window.get_single().map(Window::width).unwrap_or(1.0);
+
+    if touches.iter_just_pressed().next().is_some() {
+        touch_input.active = true;
+    } else if mouse.get_just_pressed().len() != 0 || keys.get_just_pressed().len() != 0 {
+        touch_input.active = false;
+    }
+
+    // complete any tap from last frame
+    touch_input.pointer = match touch_input.pointer {
+        TouchPointer::JustPressed => TouchPointer::JustReleased,
+        _ => {
+            touch_input.pointer_position = None;
+            TouchPointer::None
+        }
+    };
+
+    for touch in touches.iter_just_pressed() {
+        // ui focus has already run, so accept_input tells us if this touch reached the world
+        let over_world = accept_input.mouse;
+        starts.insert(
+            touch.id(),
+            TouchStart {
+                time: now,
+                over_world,
+            },
+        );
+
+        if over_world
+            && touch_input.joystick.is_none()
+            && touch.position().x < width * JOYSTICK_AREA
+        {
+            touch_input.joystick = Some((touch.id(), touch.position()));
+        }
+    }
+
+    touch_input.movement = Vec2::ZERO;
+    touch_input.look_delta = Vec2::ZERO;
+    for touch in touches.iter() {
+        match touch_input.joystick {
+            Some((id, origin)) if id == touch.id() => {
+                let offset = (touch.position() - origin) / JOYSTICK_RADIUS;
+                touch_input.movement = Vec2::new(offset.x, -offset.y).clamp_length_max(1.0);
+            }
+            _ => {
+                if starts
+                    .get(&touch.id())
+                    .is_some_and(|start| start.over_world)
+                {
+                    touch_input.look_delta += touch.delta();
+                }
+            }
+        }
+    }
+
+    let released = touches
+        .iter_just_released()
+        .chain(touches.iter_just_canceled());
+    for touch in released {
+        let start = starts.remove(&touch.id());
+        if touch_input.joystick.is_some_and(|(id, _)| id == touch.id()) {
+            touch_input.joystick = None;
+            continue;
+        }
+
+        if start.is_some_and(|start| start.over_world && is_tap(touch, start.time, now))
+            && touch_input.pointer == TouchPointer::None
+        {
+            touch_input.pointer = TouchPointer::JustPressed;
+            touch_input.pointer_position = Some(touch.position());
+        }
+    }
+}
+
+fn is_tap(touch: &Touch, start_time: f32, now: f32) -> bool {
+    now - start_time < TAP_SECS && touch.distance().length() < TAP_DISTANCE
+}
+
+fn update_touch_ui_scale(
+    touch_input: Res<TouchInput>,
+    config: Res<AppConfig>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let scale = if touch_input.active {
+        config.ui_scale * TOUCH_UI_SCALE
+    } else {
+        config.ui_scale
+    };
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
+
+#[derive(Component)]
+struct JoystickBase;
+
+#[derive(Component)]
+struct JoystickKnob;
+
+fn setup_joystick(mut commands: Commands) {
+    let size = JOYSTICK_RADIUS * 2.0;
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(size),
+                    height: Val::Px(size),
+                    display: Display::None,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: Color::srgba(1.0, 1.0, 1.0, 0.15).into(),
+                border_radius: BorderRadius::all(Val::Percent(50.0)),
+                z_index: ZIndex::Global(i16::MAX as i32 + 4),
+                ..Default::default()
+            },
+            JoystickBase,
+        ))
+        .with_children(|c| {
+            c.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Px(size * 0.4),
+                        height: Val::Px(size * 0.4),
+                        ..Default::default()
+                    },
+                    background_color: Color::srgba(1.0, 1.0, 1.0, 0.5).into(),
+                    border_radius: BorderRadius::all(Val::Percent(50.0)),
+                    ..Default::default()
+                },
+                JoystickKnob,
+            ));
+        });
+}
+
+fn update_joystick_display(
+    touch_input: Res<TouchInput>,
+    ui_scale: Res<UiScale>,
+    mut base: Query<&mut Style, (With<JoystickBase>, Without<JoystickKnob>)>,
+    mut knob: Query<&mut Style, With<JoystickKnob>>,
+) {
+    let (Ok(mut base), Ok(mut knob)) = (base.get_single_mut(), knob.get_single_mut()) else {
+        return;
+    };
+
+    let Some((_, origin)) = touch_input.joystick else {
+        if base.display != Display::None {
+            base.display = Display::None;
+        }
+        return;
+    };
+
+    // positions are in logical pixels, styles are scaled by the ui scale
+    let origin = origin / ui_scale.0;
+    base.display = Display::Flex;
+    base.left = Val::Px(origin.x - JOYSTICK_RADIUS);
+    base.top = Val::Px(origin.y - JOYSTICK_RADIUS);
+    let offset = Vec2::new(touch_input.movement.x, -touch_input.movement.y) * JOYSTICK_RADIUS;
+    knob.left = Val::Px(offset.x);
+    knob.top = Val::Px(offset.y);
+}
@@ -0,0 +1,53 @@
+// actions pressed from the ui (e.g. the hotbar) rather than a binding, or held by input playback.
+// a press is reported as just down for one frame and released on the next
+
+use bevy::{prelude::*, utils::HashSet};
+use dcl_component::proto_components::sdk::components::common::InputAction;
+
+use crate::check_accept_input;
+
+pub struct VirtualActionPlugin;
+
+impl Plugin for VirtualActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VirtualActions>();
+        app.add_systems(PreUpdate, update_virtual_actions.after(check_accept_input));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct VirtualActions {
+    queued: HashSet<InputAction>,
+    held: HashSet<InputAction>,
+    pub(crate) down: HashSet<InputAction>,
+    pub(crate) just_down: HashSet<InputAction>,
+    pub(crate) just_up: HashSet<InputAction>,
+}
+
+impl VirtualActions {
+    pub fn press(&mut self, action: InputAction) {
+        self.queued.insert(action);
+    }
+
+    // down until released
+    pub fn hold(&mut self, action: InputAction) {
+        self.held.insert(action);
+    }
+
+    pub fn release(&mut self, action: InputAction) {
+        self.held.remove(&action);
+    }
+}
+
+pub(crate) fn update_virtual_actions(mut actions: ResMut<VirtualActions>) {
+    let actions = &mut *actions;
+    let pressed = std::mem::take(&mut actions.queued);
+    let previous = std::mem::take(&mut actions.down);
+    actions.down = pressed.union(&actions.held).copied().collect();
+    actions.just_down = pressed
+        .iter()
+        .chain(actions.held.difference(&previous))
+        .copied()
+        .collect();
+    actions.just_up = previous.difference(&actions.down).copied().collect();
+}
@@ -8,7 +8,7 @@ use std::{
         atomic::{self, AtomicU16},
         Arc,
     },
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::anyhow;
@@ -51,6 +51,9 @@ pub struct EntityDefinitionJson {
     pub pointers: Vec<String>,
     pub content: Vec<TypedIpfsRef>,
     pub metadata: Option<serde_json::Value>,
+    // deployment time, unix ms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
 }
 
 #[derive(Asset, Debug, Default, TypePath)]
@@ -59,6 +62,7 @@ pub struct EntityDefinition {
     pub pointers: Vec<String>,
     pub content: ContentMap,
     pub metadata: Option<serde_json::Value>,
+    pub timestamp: Option<u64>,
 }
 
 impl IpfsAsset for EntityDefinition {
@@ -117,6 +121,7 @@ impl EntityDefinitionLoader {
                 pointers: definition_json.pointers,
                 content,
                 metadata: definition_json.metadata,
+                timestamp: definition_json.timestamp,
             };
             Ok(definition)
         })
@@ -378,6 +383,9 @@ pub struct ServerAbout {
     pub comms: Option<CommsConfig>,
     pub configurations: Option<ServerConfiguration>,
     pub lambdas: Option<EndpointConfig>,
+    // seconds the realm's clock is ahead of ours, from the about response's date header
+    #[serde(skip)]
+    pub clock_offset: Option<f64>,
 }
 
 impl ServerAbout {
@@ -398,6 +406,7 @@ impl Default for ServerAbout {
             }),
             configurations: Default::default(),
             lambdas: Default::default(),
+            clock_offset: None,
         }
     }
 }
@@ -510,6 +519,22 @@ pub struct CurrentRealm {
     pub config: ServerConfiguration,
     pub comms: Option<CommsConfig>,
     pub public_url: String,
+    pub clock_offset: f64,
+}
+
+// the date header only has second precision, so smaller differences are left to the local clock
+// (usually ntp synced), and only a clock that's clearly wrong is corrected
+const MIN_CLOCK_CORRECTION: f64 = 2.0;
+
+impl CurrentRealm {
+    // unix time in seconds on the realm's clock, for things all clients should agree on
+    pub fn shared_time(&self) -> f64 {
+        let local = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        local + self.clock_offset
+    }
 }
 
 #[allow(clippy::type_complexity)]
@@ -534,6 +559,10 @@ pub fn change_realm(
                             .as_ref()
                             .map(|c| c.public_url.clone())
                             .unwrap_or_default(),
+                        clock_offset: about
+                            .clock_offset
+                            .filter(|offset| offset.abs() > MIN_CLOCK_CORRECTION)
+                            .unwrap_or(0.0),
                     };
 
                     match about.configurations {
@@ -678,6 +707,7 @@ impl IpfsIo {
         write.about = None;
         drop(write);
 
+        let sent = SystemTime::now();
         let mut about = isahc::get_async(format!("{new_realm}/about"))
             .await
             .map_err(|e| anyhow!(e))?;
@@ -685,7 +715,27 @@ impl IpfsIo {
             return Err(anyhow!("status: {}", about.status()));
         }
 
-        let about = about.json::<ServerAbout>().await.map_err(|e| anyhow!(e))?;
+        // compare the server's date to the middle of the request
+        let received = SystemTime::now();
+        let local_time = sent
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64()
+            + received
+                .duration_since(sent)
+                .unwrap_or_default()
+                .as_secs_f64()
+                * 0.5;
+        let clock_offset = about
+            .headers()
+            .get(isahc::http::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+            // the date is truncated to the second
+            .map(|date| date.timestamp() as f64 + 0.5 - local_time);
+
+        let mut about = about.json::<ServerAbout>().await.map_err(|e| anyhow!(e))?;
+        about.clock_offset = clock_offset;
 
         let mut write = self.context.write().await;
         write.base_url.clone_from(&new_realm);
@@ -791,6 +841,7 @@ impl IpfsIo {
                             id: entity.id.unwrap(),
                             pointers: entity.pointers,
                             metadata: entity.metadata,
+                            timestamp: entity.timestamp,
                             content: ContentMap(HashMap::from_iter(
                                 entity.content.into_iter().map(|ipfs| {
                                     (normalize_path(&ipfs.file).to_lowercase(), ipfs.hash)
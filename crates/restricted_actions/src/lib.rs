@@ -22,7 +22,7 @@ use common::{
         SpawnResponse,
     },
     sets::SceneSets,
-    structs::{PermissionType, PrimaryCamera, PrimaryUser},
+    structs::{EffectRng, PermissionType, PrimaryCamera, PrimaryUser, ShowPurchaseEvent},
     util::{AsH160, FireEventEx, TaskExt},
 };
 use comms::{
@@ -48,9 +48,15 @@ use scene_runner::{
     ContainingScene, SceneEntity,
 };
 use serde_json::{json, Value};
-use teleport::{handle_out_of_world, teleport_player};
+use teleport::{
+    choose_spawn_point, goto_command, handle_out_of_world, teleport_player, GotoCommand,
+};
 use ui_core::button::DuiButton;
-use wallet::{browser_auth::remote_send_async, Wallet};
+use wallet::{
+    balances::RefreshBalances,
+    tx_review::{self, RequestReview},
+    Wallet,
+};
 
 pub struct RestrictedActionsPlugin;
 
@@ -86,11 +92,14 @@ impl Plugin for RestrictedActionsPlugin {
                     handle_texture_size,
                     handle_generic_perm,
                     handle_spawned_command,
+                    choose_spawn_point,
                 ),
             )
                 .in_set(SceneSets::RestrictedActions),
         );
         app.init_resource::<PendingPortableCommands>();
+        app.init_resource::<EffectRng>();
+        app.add_console_command::<GotoCommand, _>(goto_command);
         app.add_console_command::<SpawnPortableCommand, _>(spawn_portable_command);
         app.add_console_command::<KillPortableCommand, _>(kill_portable_command);
     }
@@ -978,6 +987,7 @@ fn open_nft_dialog(
     containing_scene: ContainingScene,
     primary_user: Query<Entity, With<PrimaryUser>>,
     asset_server: Res<AssetServer>,
+    mut purchase: EventWriter<ShowPurchaseEvent>,
 ) {
     for (scene, urn, response) in events.read().filter_map(|c| match c {
         RpcCall::OpenNftDialog {
@@ -997,6 +1007,14 @@ fn open_nft_dialog(
             return;
         }
 
+        // wearable and emote items open the marketplace purchase flow
+        let lower_urn = urn.to_lowercase();
+        if lower_urn.starts_with("urn:decentraland:") && lower_urn.contains(":collections-v2:") {
+            purchase.send(ShowPurchaseEvent(urn.clone()));
+            response.send(Ok(()));
+            continue;
+        }
+
         let h_nft = asset_server.load(format!("nft://{}.nft", urlencoding::encode(urn)));
 
         commands.spawn(NftDialogSpawn {
@@ -1081,7 +1099,7 @@ fn show_nft_dialog(
     }
 }
 
-#[allow(clippy::type_complexity)]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn handle_eth_async(
     mut events: EventReader<RpcCall>,
     scenes: Query<&RendererSceneContext>,
@@ -1089,11 +1107,21 @@ pub fn handle_eth_async(
     time: Res<Time>,
     mut tasks: Local<
         Vec<(
+            bool,
             RpcResultSender<Result<serde_json::Value, String>>,
             Task<Result<serde_json::Value, anyhow::Error>>,
         )>,
     >,
+    mut reviews: Local<
+        Vec<(
+            Entity,
+            RPCSendableMessage,
+            RpcResultSender<Result<serde_json::Value, String>>,
+            Task<RequestReview>,
+        )>,
+    >,
     mut perms: Permission<(RPCSendableMessage, RpcResultSender<Result<Value, String>>)>,
+    mut refresh_balances: EventWriter<RefreshBalances>,
 ) {
     for (body, scene, response) in events.read().filter_map(|ev| match ev {
         RpcCall::SendAsync {
@@ -1118,14 +1146,29 @@ pub fn handle_eth_async(
         }
 
         debug!("[{:?}] handle_eth_async {:?}", scene, body);
-        perms.check(
+        // decode the request before asking, so the user can see what they are agreeing to
+        reviews.push((
+            *scene,
+            body.clone(),
+            response.clone(),
+            IoTaskPool::get().spawn(tx_review::review(body.clone(), wallet.address())),
+        ));
+    }
+
+    reviews.retain_mut(|(scene, body, response, task)| {
+        let Some(review) = task.complete() else {
+            return true;
+        };
+        perms.check_with_details(
             PermissionType::Web3,
             *scene,
             (body.clone(), response.clone()),
-            None,
+            Some(review.summary),
+            Some(review.raw),
             false,
         );
-    }
+        false
+    });
 
     for (body, response) in perms.drain_success(PermissionType::Web3) {
         if wallet.is_guest() || wallet.address().is_none() {
@@ -1134,8 +1177,9 @@ pub fn handle_eth_async(
         }
 
         tasks.push((
+            body.method == "eth_sendTransaction",
             response.clone(),
-            IoTaskPool::get().spawn(remote_send_async(body.clone(), wallet.auth_chain().ok())),
+            IoTaskPool::get().spawn(wallet.send_async(body.clone())),
         ));
     }
 
@@ -1143,8 +1187,11 @@ pub fn handle_eth_async(
         response.send(Err("permission denied".to_owned()));
     }
 
-    tasks.retain_mut(|(response, task)| {
+    tasks.retain_mut(|(is_transaction, response, task)| {
         if let Some(result) = task.complete() {
+            if *is_transaction && result.is_ok() {
+                refresh_balances.send(RefreshBalances::AfterTransaction);
+            }
             response.send(result.map_err(|e| e.to_string()));
             false
         } else {
@@ -1,11 +1,17 @@
+use std::str::FromStr;
+
 use avatar::AvatarDynamicState;
-use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy::{ecs::system::SystemParam, math::Vec3Swizzles, prelude::*};
+use bevy_console::ConsoleCommand;
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
 use common::{
     rpc::{RpcCall, RpcResultSender},
-    structs::{PermissionType, PrimaryUser},
+    structs::{
+        AppConfig, EffectRng, IVec2Arg, PermissionType, PrimaryCamera, PrimaryUser, SpawnPoint,
+    },
 };
 use comms::global_crdt::ForeignPlayer;
-use ethers_core::rand::{seq::SliceRandom, thread_rng, Rng};
+use ipfs::{ChangeRealmEvent, CurrentRealm};
 use scene_runner::{
     initialize_scene::{
         LiveScenes, PointerResult, SceneHash, SceneLoading, ScenePointers, PARCEL_SIZE,
@@ -13,16 +19,65 @@ use scene_runner::{
     permissions::Permission,
     renderer_context::RendererSceneContext,
     update_world::mesh_collider::SceneColliderData,
-    OutOfWorld,
+    ContainingScene, OutOfWorld, RealmTransition,
 };
+use ui_core::button::DuiButton;
 use wallet::Wallet;
 
+/// teleport to a parcel in the current realm, e.g. `/goto -9,-9`, or to a favorite or recently
+/// visited realm by name
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/goto")]
+pub struct GotoCommand {
+    location: String,
+}
+
+pub fn goto_command(
+    mut input: ConsoleCommand<GotoCommand>,
+    mut rpc: EventWriter<RpcCall>,
+    mut change_realm: EventWriter<ChangeRealmEvent>,
+    config: Res<AppConfig>,
+) {
+    if let Some(Ok(command)) = input.take() {
+        if let Ok(IVec2Arg(to)) = IVec2Arg::from_str(&command.location) {
+            rpc.send(RpcCall::TeleportPlayer {
+                scene: None,
+                to,
+                response: RpcResultSender::default(),
+            });
+            input.ok();
+        } else if let Some(realm) = config.saved_realm(&command.location) {
+            change_realm.send(ChangeRealmEvent {
+                new_realm: realm.url.clone(),
+            });
+            input.ok();
+        } else {
+            input.reply_failed(format!("invalid location `{}`", command.location));
+        }
+    }
+}
+
 pub fn teleport_player(
     mut commands: Commands,
     mut events: EventReader<RpcCall>,
     mut player: Query<(Entity, &mut Transform, &mut AvatarDynamicState), With<PrimaryUser>>,
     mut perms: Permission<(IVec2, RpcResultSender<Result<(), String>>)>,
+    mut realm_changes: EventReader<ChangeRealmEvent>,
+    realm: Res<CurrentRealm>,
+    mut transition: ResMut<RealmTransition>,
 ) {
+    // hold the player out of world from the start of a realm change, so they aren't placed by the
+    // old realm's scenes
+    if let Some(ev) = realm_changes.read().last() {
+        *transition = RealmTransition {
+            realm: Some(ev.new_realm.clone()),
+            target: None,
+        };
+        if let Ok((ent, ..)) = player.get_single() {
+            commands.entity(ent).try_insert(OutOfWorld);
+        }
+    }
+
     let mut do_teleport = |to: IVec2, response: RpcResultSender<Result<(), String>>| {
         let Ok((ent, mut transform, mut dynamic_state)) = player.get_single_mut() else {
             warn!("player doesn't exist?!");
@@ -49,7 +104,11 @@ pub fn teleport_player(
         } => Some((*scene, *to, response.clone())),
         _ => None,
     }) {
-        if let Some(scene) = scene {
+        if scene.is_none() && transition.realm.is_some() {
+            // user initiated along with the realm change, applied on arrival
+            transition.target = Some(to);
+            response.send(Ok(()));
+        } else if let Some(scene) = scene {
             perms.check(
                 PermissionType::Teleport,
                 scene,
@@ -69,6 +128,15 @@ pub fn teleport_player(
     for (_, response) in perms.drain_fail(PermissionType::Teleport) {
         response.send(Err("User declined".to_owned()))
     }
+
+    if realm.is_changed() && transition.realm.as_ref() == Some(&realm.address) {
+        info!("arrived in realm {}", realm.address);
+        let target = transition.target.take();
+        transition.realm = None;
+        if let Some(to) = target {
+            do_teleport(to, RpcResultSender::default());
+        }
+    }
 }
 
 #[allow(clippy::type_complexity)]
@@ -87,11 +155,19 @@ pub fn handle_out_of_world(
     live_scenes: Res<LiveScenes>,
     foreign_players: Query<&GlobalTransform, With<ForeignPlayer>>,
     wallet: Res<Wallet>,
+    transition: Res<RealmTransition>,
+    mut camera: Query<&mut PrimaryCamera>,
+    mut rng: ResMut<EffectRng>,
 ) {
     let Ok((player, mut t)) = player.get_single_mut() else {
         return;
     };
 
+    if transition.realm.is_some() {
+        debug!("waiting for realm change");
+        return;
+    }
+
     debug!("out of world!");
 
     if wallet.address().is_none() {
@@ -138,50 +214,35 @@ pub fn handle_out_of_world(
                 .iter()
                 .map(|gt| gt.translation())
                 .collect::<Vec<_>>();
-            let base_position =
-                Vec3::new(context.base.x as f32, 0.0, -context.base.y as f32) * PARCEL_SIZE;
-
-            let rng = &mut thread_rng();
-            let mut best_distance = 0.0;
-            let mut best_position = Vec3::new(
-                rng.gen_range(0.0..PARCEL_SIZE),
-                1000.0,
-                -rng.gen_range(0.0..PARCEL_SIZE),
-            ) + base_position;
-            best_position.y = 1000.0
-                - maybe_collider_data
-                    .and_then(|mut cd| cd.get_groundheight(context.tick_number, best_position))
-                    .map(|(h, _)| h)
-                    .unwrap_or(1000.0);
-            let mut count = 100;
-
-            if !context.spawn_points.is_empty() {
-                while best_distance < 0.75 && count > 0 {
-                    let spawn_point = context.spawn_points.choose(rng).unwrap();
-                    if !spawn_point.default && count > 50 {
-                        continue;
-                    }
-                    let aabb = spawn_point.position.bounding_box();
-                    let position = base_position
-                        + Vec3::new(
-                            rng.gen_range(aabb.0.x..=aabb.1.x),
-                            rng.gen_range(aabb.0.y..=aabb.1.y),
-                            -rng.gen_range(aabb.0.z..=aabb.1.z),
-                        );
-                    let distance = other_positions
-                        .iter()
-                        .fold(0.75, |d, other| f32::min(d, (position - *other).length()));
-                    if distance > best_distance {
-                        best_distance = distance;
-                        best_position = position;
-                    }
-
-                    count -= 1;
-                }
-            }
+            let base_position = base_position(context.base);
 
-            debug!("chose {best_position}");
-            t.translation = best_position;
+            // the teleport target within the scene
+            let requested = t.translation - base_position;
+            if let Some(spawn_point) = nearest_spawn_point(&context.spawn_points, requested) {
+                let position =
+                    spawn_position(spawn_point, base_position, &other_positions, &mut rng);
+                debug!("chose {position} at spawn point {:?}", spawn_point.name);
+                place_at_spawn_point(
+                    spawn_point,
+                    base_position,
+                    position,
+                    &mut t,
+                    camera.get_single_mut().ok().as_deref_mut(),
+                );
+            } else {
+                let mut position = Vec3::new(
+                    rng.range(0.0, PARCEL_SIZE),
+                    1000.0,
+                    -rng.range(0.0, PARCEL_SIZE),
+                ) + base_position;
+                position.y = 1000.0
+                    - maybe_collider_data
+                        .and_then(|mut cd| cd.get_groundheight(context.tick_number, position))
+                        .map(|(h, _)| h)
+                        .unwrap_or(1000.0);
+                debug!("chose {position}");
+                t.translation = position;
+            }
             commands.entity(player).remove::<OutOfWorld>();
         }
         return;
@@ -200,3 +261,207 @@ pub fn handle_out_of_world(
         }
     }
 }
+
+// scene-relative center of a spawn point's area, in bevy coordinates
+fn spawn_center(spawn_point: &SpawnPoint) -> Vec3 {
+    let (min, max) = spawn_point.position.bounding_box();
+    (min + max) * Vec3::new(0.5, 0.5, -0.5)
+}
+
+// the spawn point closest to the scene-relative requested position. default spawn points are
+// preferred, the rest are only used when the scene has no defaults
+pub fn nearest_spawn_point(spawn_points: &[SpawnPoint], requested: Vec3) -> Option<&SpawnPoint> {
+    let any_default = spawn_points.iter().any(|spawn_point| spawn_point.default);
+    let distance =
+        |spawn_point: &SpawnPoint| spawn_center(spawn_point).xz().distance(requested.xz());
+    spawn_points
+        .iter()
+        .filter(|spawn_point| spawn_point.default || !any_default)
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
+
+// a random position within the spawn point's area, keeping away from other players if possible
+fn spawn_position(
+    spawn_point: &SpawnPoint,
+    base_position: Vec3,
+    other_positions: &[Vec3],
+    rng: &mut EffectRng,
+) -> Vec3 {
+    let aabb = spawn_point.position.bounding_box();
+    let mut best_distance = -1.0;
+    let mut best_position = base_position;
+    let mut count = 50;
+
+    while best_distance < 0.75 && count > 0 {
+        let position = base_position
+            + Vec3::new(
+                rng.range(aabb.0.x, aabb.1.x),
+                rng.range(aabb.0.y, aabb.1.y),
+                -rng.range(aabb.0.z, aabb.1.z),
+            );
+        let distance = other_positions
+            .iter()
+            .fold(0.75, |d, other| f32::min(d, (position - *other).length()));
+        if distance > best_distance {
+            best_distance = distance;
+            best_position = position;
+        }
+
+        count -= 1;
+    }
+
+    best_position
+}
+
+// move the player to a position chosen from the spawn point, facing its camera target
+fn place_at_spawn_point(
+    spawn_point: &SpawnPoint,
+    base_position: Vec3,
+    position: Vec3,
+    transform: &mut Transform,
+    camera: Option<&mut PrimaryCamera>,
+) {
+    transform.translation = position;
+
+    let Some(target) = spawn_point.camera_target else {
+        return;
+    };
+    let target = base_position + Vec3::new(target.x, target.y, -target.z);
+
+    let facing = (target - position) * Vec3::new(1.0, 0.0, 1.0);
+    if facing.length_squared() > 1e-6 {
+        transform.rotation = Transform::IDENTITY.looking_to(facing, Vec3::Y).rotation;
+    }
+
+    if let Some(camera) = camera.filter(|_| (target - position).length_squared() > 1e-6) {
+        let rotation = Transform::IDENTITY
+            .looking_at(target - position, Vec3::Y)
+            .rotation;
+        let (yaw, pitch, roll) = rotation.to_euler(EulerRot::YXZ);
+        camera.yaw = yaw;
+        camera.pitch = pitch;
+        camera.roll = roll;
+    }
+}
+
+#[derive(SystemParam)]
+pub struct SpawnPointPlacer<'w, 's> {
+    scenes: Query<'w, 's, &'static RendererSceneContext>,
+    player: Query<'w, 's, &'static mut Transform, With<PrimaryUser>>,
+    camera: Query<'w, 's, &'static mut PrimaryCamera>,
+    foreign_players: Query<'w, 's, &'static GlobalTransform, With<ForeignPlayer>>,
+    rng: ResMut<'w, EffectRng>,
+}
+
+impl SpawnPointPlacer<'_, '_> {
+    // move the player to one of the scene's spawn points, returning its name
+    fn place(&mut self, scene: Entity, ix: usize) -> Result<String, String> {
+        let context = self
+            .scenes
+            .get(scene)
+            .map_err(|_| "Scene not found".to_owned())?;
+        let spawn_point = context
+            .spawn_points
+            .get(ix)
+            .ok_or_else(|| "Spawn point not found".to_owned())?;
+        let mut transform = self
+            .player
+            .get_single_mut()
+            .map_err(|_| "No player".to_owned())?;
+
+        let base_position = base_position(context.base);
+        let others = self
+            .foreign_players
+            .iter()
+            .map(GlobalTransform::translation)
+            .collect::<Vec<_>>();
+        let position = spawn_position(spawn_point, base_position, &others, &mut self.rng);
+        place_at_spawn_point(
+            spawn_point,
+            base_position,
+            position,
+            &mut transform,
+            self.camera.get_single_mut().ok().as_deref_mut(),
+        );
+        Ok(spawn_name(ix, spawn_point))
+    }
+}
+
+fn spawn_name(ix: usize, spawn_point: &SpawnPoint) -> String {
+    spawn_point
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("Spawn point {}", ix + 1))
+}
+
+// scenes with several spawn points can ask the user which one to go to
+pub fn choose_spawn_point(
+    mut commands: Commands,
+    mut events: EventReader<RpcCall>,
+    dui: Res<DuiRegistry>,
+    player: Query<Entity, With<PrimaryUser>>,
+    containing_scene: ContainingScene,
+    mut placer: SpawnPointPlacer,
+) {
+    for (scene, response) in events.read().filter_map(|ev| match ev {
+        RpcCall::ChooseSpawnPoint { scene, response } => Some((*scene, response)),
+        _ => None,
+    }) {
+        let Ok(player) = player.get_single() else {
+            response.send(Err("No player".to_owned()));
+            continue;
+        };
+
+        if !containing_scene.get(player).contains(&scene) {
+            response.send(Err("Not in scene".to_owned()));
+            continue;
+        }
+
+        let Ok(context) = placer.scenes.get(scene) else {
+            response.send(Err("Scene not found".to_owned()));
+            continue;
+        };
+
+        match context.spawn_points.len() {
+            0 => response.send(Err("Scene has no spawn points".to_owned())),
+            // nothing to choose from
+            1 => response.send(placer.place(scene, 0)),
+            _ => {
+                let title = context.title.clone();
+                let mut buttons = context
+                    .spawn_points
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, spawn_point)| {
+                        let response = response.clone();
+                        DuiButton::new_enabled_and_close_happy(
+                            spawn_name(ix, spawn_point),
+                            move |mut placer: SpawnPointPlacer| {
+                                response.send(placer.place(scene, ix))
+                            },
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let response = response.clone();
+                buttons.push(DuiButton::new_enabled_and_close_sad("Cancel", move || {
+                    response.send(Err("User cancelled".to_owned()))
+                }));
+
+                commands
+                    .spawn_template(
+                        &dui,
+                        "text-dialog",
+                        DuiProps::new()
+                            .with_prop("title", "Choose a spawn point".to_owned())
+                            .with_prop("body", format!("Where would you like to start in {title}?"))
+                            .with_prop("buttons", buttons),
+                    )
+                    .unwrap();
+            }
+        }
+    }
+}
+
+fn base_position(base: IVec2) -> Vec3 {
+    Vec3::new(base.x as f32, 0.0, -base.y as f32) * PARCEL_SIZE
+}
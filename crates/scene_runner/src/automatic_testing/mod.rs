@@ -11,24 +11,52 @@ use common::{
     profile::SerializedProfile,
     rpc::{CompareSnapshot, CompareSnapshotResult, RpcCall, RpcResultSender},
     sets::SceneSets,
-    structs::PrimaryUser,
+    structs::{PrimaryUser, TestCamera},
 };
 use comms::profile::{CurrentUserProfile, UserProfile};
-use dcl_component::transform_and_parent::DclTranslation;
+use dcl_component::transform_and_parent::{DclQuat, DclTranslation};
 use ipfs::IpfsAssetServer;
 use wallet::Wallet;
 
 use crate::{
-    initialize_scene::{TestingData, PARCEL_SIZE},
+    initialize_scene::{SceneTestResult, TestingData, PARCEL_SIZE},
     renderer_context::RendererSceneContext,
     ContainingScene, OutOfWorld, Toaster,
 };
 
+const SCREENSHOT_DIR: &str = "assets/images/screenshots";
+// outside the screenshot folder, which is loaded as assets
+const REPORT_PATH: &str = "assets/images/test-report.html";
+// per pixel yiq color distance (0-1) at which pixels count as different, as in pixelmatch
+const PIXEL_THRESHOLD: f32 = 0.1;
+// max yiq delta between black and white
+const MAX_YIQ_DELTA: f32 = 35215.0;
+// default fraction of differing pixels allowed before a snapshot fails
+const DEFAULT_SNAPSHOT_THRESHOLD: f32 = 0.01;
+
 pub struct AutomaticTestingPlugin;
 
 impl Plugin for AutomaticTestingPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<TestCamera>();
         app.add_systems(Update, automatic_testing.in_set(SceneSets::PostLoop));
+        app.add_systems(Update, test_timeout);
+    }
+}
+
+fn test_timeout(testing_data: Res<TestingData>, time: Res<Time<Real>>) {
+    let Some(timeout) = testing_data.timeout else {
+        return;
+    };
+    if time.elapsed_seconds() > timeout {
+        error!(
+            "tests timed out after {timeout} seconds, {} scenes remaining",
+            testing_data
+                .test_scenes
+                .as_ref()
+                .map_or(0, |scenes| scenes.0.len())
+        );
+        std::process::exit(2);
     }
 }
 
@@ -39,6 +67,18 @@ struct SnapshotResult {
     camera: Entity,
 }
 
+struct SnapshotReport {
+    name: String,
+    // url encoded, as used for the image files
+    file: String,
+    location: Option<IVec2>,
+    similarity: f64,
+    // fraction of visibly different pixels
+    diff_ratio: Option<f32>,
+    error: Option<String>,
+    passed: bool,
+}
+
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn automatic_testing(
     mut commands: Commands,
@@ -53,17 +93,19 @@ fn automatic_testing(
     mut rpcs: EventReader<RpcCall>,
     mut plans: Local<HashMap<Entity, HashSet<String>>>,
     mut snapshot_in_progress: Local<Option<(CompareSnapshot, Entity)>>,
+    mut snapshot_reports: Local<Vec<SnapshotReport>>,
     (mut local_sender, mut local_receiver, mut screenshots, mut screenshot_in_progress): (
         Local<Option<tokio::sync::mpsc::Sender<SnapshotResult>>>,
         Local<Option<tokio::sync::mpsc::Receiver<SnapshotResult>>>,
         Local<Handle<LoadedFolder>>,
         Local<bool>,
     ),
-    (mut wallet, folders, images, mut screenshotter): (
+    (mut wallet, folders, images, mut screenshotter, mut test_camera): (
         ResMut<Wallet>,
         Res<Assets<LoadedFolder>>,
         Res<Assets<Image>>,
         ResMut<ScreenshotManager>,
+        ResMut<TestCamera>,
     ),
     ui_roots: Query<(Entity, Option<&mut TargetCamera>), (With<Node>, Without<Parent>)>,
 ) {
@@ -172,9 +214,10 @@ fn automatic_testing(
                 img
             });
 
+        let mut diff = None;
         let similarity = match existing_image {
             Some(saved_image) => {
-                let path = format!("assets/images/screenshots/{name}_output.png");
+                let path = format!("{SCREENSHOT_DIR}/{name}_output.png");
                 let _ = result
                     .image
                     .clone()
@@ -184,13 +227,29 @@ fn automatic_testing(
                 let image2 = std::fs::read(path).unwrap();
                 let image2 =
                     image::load_from_memory_with_format(&image2, image::ImageFormat::Png).unwrap();
+                diff = Some(
+                    image::open(format!("{SCREENSHOT_DIR}/{name}.png"))
+                        .map_err(|e| format!("failed to read reference image: {e}"))
+                        .and_then(|expected| {
+                            perceptual_diff(&expected.to_rgba8(), &image2.to_rgba8())
+                        })
+                        .and_then(|(ratio, diff_image)| {
+                            diff_image
+                                .save_with_format(
+                                    format!("{SCREENSHOT_DIR}/{name}_diff.png"),
+                                    image::ImageFormat::Png,
+                                )
+                                .map_err(|e| format!("failed to save diff image: {e}"))?;
+                            Ok(ratio)
+                        }),
+                );
                 let image2 = Image::from_dynamic(image2, false, RenderAssetUsages::default());
                 compute_image_similarity(saved_image.clone(), image2)
             }
             None => {
                 let dy_img = result.image.try_into_dynamic().unwrap();
                 error = match dy_img.save_with_format(
-                    format!("assets/images/screenshots/{name}.png"),
+                    format!("{SCREENSHOT_DIR}/{name}.png"),
                     image::ImageFormat::Png,
                 ) {
                     Ok(_) => Some("image not found (it has been created)".to_owned()),
@@ -202,6 +261,52 @@ fn automatic_testing(
             }
         };
 
+        let location = scenes.get(result.request.scene).ok().map(|ctx| ctx.base);
+        let threshold = testing_data
+            .snapshot_threshold
+            .unwrap_or(DEFAULT_SNAPSHOT_THRESHOLD);
+        let (diff_ratio, diff_error) = match diff {
+            Some(Ok(ratio)) => (Some(ratio), None),
+            Some(Err(e)) => (None, Some(e)),
+            None => (None, None),
+        };
+        let report_error = diff_error.or_else(|| error.clone());
+        let passed = report_error.is_none() && diff_ratio.is_some_and(|ratio| ratio <= threshold);
+        if existing_image.is_some() && !passed {
+            let expected = location.is_some_and(|location| {
+                testing_data
+                    .test_scenes
+                    .as_ref()
+                    .and_then(|scenes| scenes.0.iter().find(|ts| ts.location == location))
+                    .is_some_and(|scene| scene.allow_failures.contains(&result.request.name))
+            });
+            let location = location
+                .map(|location| format!("({},{})", location.x, location.y))
+                .unwrap_or_default();
+            fails.push((
+                format!("[{location} : snapshot {}]", result.request.name),
+                match (diff_ratio, &report_error) {
+                    (_, Some(e)) => e.clone(),
+                    (Some(ratio), None) => format!(
+                        "{:.2}% of pixels differ (threshold {:.2}%)",
+                        ratio * 100.0,
+                        threshold * 100.0
+                    ),
+                    (None, None) => String::default(),
+                },
+                expected,
+            ));
+        }
+        snapshot_reports.push(SnapshotReport {
+            name: result.request.name.clone(),
+            file: name.clone().into_owned(),
+            location,
+            similarity,
+            diff_ratio,
+            error: report_error,
+            passed,
+        });
+
         result.request.response.send(CompareSnapshotResult {
             error,
             found: existing_image.is_some(),
@@ -238,6 +343,9 @@ fn automatic_testing(
                 name,
                 success,
                 error,
+                stack,
+                frames,
+                seconds,
             } => {
                 let Some(plan) = plans.get_mut(scene) else {
                     warn!("unregistered plan for scene {:?}", scene);
@@ -250,31 +358,59 @@ fn automatic_testing(
 
                 info!("test {}: {} [{} remaining]", name, success, plan.len());
 
+                let Some(location) = scenes.get(*scene).ok().map(|ctx| ctx.base) else {
+                    warn!("scene entity {scene:?} not found(?), ignoring this result");
+                    continue;
+                };
+
+                testing_data.results.push(SceneTestResult {
+                    location,
+                    name: name.clone(),
+                    passed: *success,
+                    error: error.clone(),
+                    stack: stack.clone(),
+                    frames: *frames,
+                    seconds: *seconds,
+                });
+
                 if !success {
-                    if let Some(location) = scenes.get(*scene).ok().map(|ctx| ctx.base) {
-                        if let Some(scene) = testing_data
-                            .test_scenes
-                            .as_ref()
-                            .unwrap()
-                            .0
-                            .iter()
-                            .find(|ts| ts.location == location)
-                        {
-                            let expected = scene.allow_failures.contains(name);
-                            let location = format!("({},{})", location.x, location.y);
-                            fails.push((
-                                format!("[{location} : {name}]"),
-                                error.clone().unwrap_or_default(),
-                                expected,
-                            ));
-                        } else {
-                            warn!("location {location} wasn't part of the required set, ignoring this failure");
-                        }
+                    if let Some(scene) = testing_data
+                        .test_scenes
+                        .as_ref()
+                        .unwrap()
+                        .0
+                        .iter()
+                        .find(|ts| ts.location == location)
+                    {
+                        let expected = scene.allow_failures.contains(name);
+                        let location = format!("({},{})", location.x, location.y);
+                        fails.push((
+                            format!("[{location} : {name}]"),
+                            error.clone().unwrap_or_default(),
+                            expected,
+                        ));
                     } else {
-                        warn!("scene entity {scene:?} not found(?), ignoring this failure");
+                        warn!("location {location} wasn't part of the required set, ignoring this failure");
                     }
                 }
             }
+            RpcCall::TestCameraTransform {
+                scene,
+                position,
+                rotation,
+            } => {
+                let Ok(context) = scenes.get(*scene) else {
+                    warn!("scene entity {scene:?} not found(?), ignoring camera transform");
+                    continue;
+                };
+                let base_position =
+                    Vec3::new(context.base.x as f32, 0.0, -context.base.y as f32) * PARCEL_SIZE;
+                test_camera.0 = Some(Transform {
+                    translation: DclTranslation(*position).to_bevy_translation() + base_position,
+                    rotation: DclQuat(*rotation).to_bevy_quat().normalize(),
+                    ..Default::default()
+                });
+            }
             RpcCall::TestSnapshot(snapshot) => {
                 if *screenshot_in_progress {
                     snapshot.response.send(CompareSnapshotResult {
@@ -337,6 +473,10 @@ fn automatic_testing(
     }
 
     let Some(next_test_scene) = testing_data.test_scenes.as_ref().unwrap().0.front() else {
+        match write_report(&testing_data.results, &snapshot_reports, &fails) {
+            Ok(path) => info!("test report written to {path}"),
+            Err(e) => warn!("failed to write test report: {e}"),
+        }
         if fails.is_empty() {
             info!("all tests passed!");
             std::process::exit(0);
@@ -359,6 +499,7 @@ fn automatic_testing(
             .unwrap_or(false)
     }) else {
         info!("moving to next scene {:?}", next_test_scene.location);
+        test_camera.0 = None;
         let to = next_test_scene.location;
         commands.add(move |w: &mut World| {
             w.send_event(RpcCall::TeleportPlayer {
@@ -379,6 +520,7 @@ fn automatic_testing(
     if plan.is_empty() {
         info!("plan completed for scene @ {:?}", context.base);
         testing_data.test_scenes.as_mut().unwrap().0.pop_front();
+        test_camera.0 = None;
     }
 }
 
@@ -411,3 +553,171 @@ fn compute_image_similarity(img_a: Image, img_b: Image) -> f64 {
 
     score
 }
+
+// yiq color of a pixel blended over white
+fn yiq(pixel: &image::Rgba<u8>) -> [f32; 3] {
+    let [r, g, b, a] = pixel.0.map(|c| c as f32);
+    let blend = |c: f32| 255.0 + (c - 255.0) * a / 255.0;
+    let (r, g, b) = (blend(r), blend(g), blend(b));
+    [
+        r * 0.298_895_3 + g * 0.586_622_5 + b * 0.114_482_23,
+        r * 0.595_977_99 - g * 0.274_176_1 - b * 0.321_801_9,
+        r * 0.211_470_17 - g * 0.522_617_1 + b * 0.311_146_94,
+    ]
+}
+
+// fraction of pixels that differ visibly, and an image with them marked in red over a faded copy
+// of the expected image
+fn perceptual_diff(
+    expected: &image::RgbaImage,
+    actual: &image::RgbaImage,
+) -> Result<(f32, image::RgbaImage), String> {
+    if expected.dimensions() != actual.dimensions() {
+        return Err(format!(
+            "size mismatch: expected {:?}, got {:?}",
+            expected.dimensions(),
+            actual.dimensions()
+        ));
+    }
+
+    let max_delta = MAX_YIQ_DELTA * PIXEL_THRESHOLD * PIXEL_THRESHOLD;
+    let mut differing = 0usize;
+    let mut diff = image::RgbaImage::new(expected.width(), expected.height());
+    for ((expected, actual), out) in expected
+        .pixels()
+        .zip(actual.pixels())
+        .zip(diff.pixels_mut())
+    {
+        let [y1, i1, q1] = yiq(expected);
+        let [y2, i2, q2] = yiq(actual);
+        let delta =
+            0.5053 * (y1 - y2).powi(2) + 0.299 * (i1 - i2).powi(2) + 0.1957 * (q1 - q2).powi(2);
+        *out = if delta > max_delta {
+            differing += 1;
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            let faded = (255.0 - (255.0 - y1) * 0.1) as u8;
+            image::Rgba([faded, faded, faded, 255])
+        };
+    }
+
+    let pixels = (expected.width() * expected.height()).max(1);
+    Ok((differing as f32 / pixels as f32, diff))
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// html summary of test failures, scene test results and snapshot comparisons
+fn write_report(
+    results: &[SceneTestResult],
+    snapshots: &[SnapshotReport],
+    fails: &[(String, String, bool)],
+) -> std::io::Result<String> {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>test report</title>\n\
+        <style>body{font-family:sans-serif} img{max-width:256px;border:1px solid #888} \
+        td{padding:4px;vertical-align:top} .fail{color:#c00} .pass{color:#080}</style>\n\
+        </head><body>\n",
+    );
+
+    let failed_tests = results.iter().filter(|r| !r.passed).count();
+    let failed_snapshots = snapshots.iter().filter(|s| !s.passed).count();
+    html += &format!(
+        "<h1>test report</h1>\n\
+        <p>{} test failures, {failed_tests} of {} scene tests failed, \
+        {failed_snapshots} of {} snapshots failed</p>\n",
+        fails.len(),
+        results.len(),
+        snapshots.len(),
+    );
+
+    if !fails.is_empty() {
+        html += "<h2>failures</h2>\n<table>\n";
+        for (test, error, allowed) in fails {
+            html += &format!(
+                "<tr><td class=\"fail\">{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(test),
+                html_escape(error),
+                if *allowed { "allowed" } else { "" }
+            );
+        }
+        html += "</table>\n";
+    }
+
+    if !results.is_empty() {
+        html += "<h2>scene tests</h2>\n<table>\n\
+            <tr><th>test</th><th>result</th><th>frames</th><th>seconds</th></tr>\n";
+        for result in results {
+            let mut outcome = String::from(if result.passed { "passed" } else { "failed" });
+            if let Some(error) = result.error.as_ref() {
+                outcome += &format!("<br>{}", html_escape(error));
+            }
+            if let Some(stack) = result.stack.as_ref() {
+                outcome += &format!("<pre>{}</pre>", html_escape(stack));
+            }
+            html += &format!(
+                "<tr><td>({},{}) {}</td><td class=\"{}\">{outcome}</td>\
+                <td>{}</td><td>{:.2}</td></tr>\n",
+                result.location.x,
+                result.location.y,
+                html_escape(&result.name),
+                if result.passed { "pass" } else { "fail" },
+                result.frames,
+                result.seconds,
+            );
+        }
+        html += "</table>\n";
+    }
+
+    html += "<h2>snapshots</h2>\n<table>\n\
+        <tr><th>snapshot</th><th>result</th><th>expected</th><th>actual</th><th>diff</th></tr>\n";
+    // failures first
+    for snapshot in snapshots
+        .iter()
+        .filter(|s| !s.passed)
+        .chain(snapshots.iter().filter(|s| s.passed))
+    {
+        let location = snapshot
+            .location
+            .map(|location| format!("({},{}) ", location.x, location.y))
+            .unwrap_or_default();
+        let mut result = format!("similarity {:.4}", snapshot.similarity);
+        if let Some(ratio) = snapshot.diff_ratio {
+            result += &format!("<br>{:.2}% of pixels differ", ratio * 100.0);
+        }
+        if let Some(error) = snapshot.error.as_ref() {
+            result += &format!("<br>{}", html_escape(error));
+        }
+        // file names are url encoded on disk, so encode again for the link
+        let image = |suffix: &str| {
+            let file = format!("{}{suffix}.png", snapshot.file);
+            if std::path::Path::new(SCREENSHOT_DIR).join(&file).exists() {
+                format!("<img src=\"screenshots/{}\">", urlencoding::encode(&file))
+            } else {
+                String::default()
+            }
+        };
+        // passing snapshots only show the reference
+        let (actual, diff) = if snapshot.passed {
+            (String::default(), String::default())
+        } else {
+            (image("_output"), image("_diff"))
+        };
+        html += &format!(
+            "<tr><td>{location}{}</td><td class=\"{}\">{result}</td>\
+            <td>{}</td><td>{actual}</td><td>{diff}</td></tr>\n",
+            html_escape(&snapshot.name),
+            if snapshot.passed { "pass" } else { "fail" },
+            image(""),
+        );
+    }
+    html += "</table>\n</body></html>\n";
+
+    std::fs::write(REPORT_PATH, html)?;
+    Ok(REPORT_PATH.to_owned())
+}
@@ -0,0 +1,263 @@
+// automated benchmark: wait for the first parcel of a route to load, then move the player along
+// the route at a fixed speed (in wall time, so every run covers the same ground in the same time
+// regardless of frame rate), recording per-frame timings and loading stalls. on completion a
+// json summary and a csv of frames are written and the app exits.
+
+use std::{path::PathBuf, str::FromStr};
+
+use bevy::{app::AppExit, prelude::*, transform::TransformSystem};
+use common::structs::{AppConfig, IVec2Arg, PrimaryUser, Version};
+
+use crate::{
+    initialize_scene::{LiveScenes, PointerResult, SceneLoading, ScenePointers, PARCEL_SIZE},
+    vec3_to_parcel,
+};
+
+// height above ground the player flies at
+const FLY_HEIGHT: f32 = 10.0;
+// max time to wait for the first parcel before starting anyway
+const MAX_WARMUP_SECS: f32 = 120.0;
+// frames longer than this are counted as hitches
+const HITCH_MS: f32 = 100.0;
+
+#[derive(Clone, Debug)]
+pub struct BenchmarkRoute(pub Vec<IVec2>);
+
+impl FromStr for BenchmarkRoute {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let route = value
+            .split(';')
+            .map(|parcel| IVec2Arg::from_str(parcel).map(|arg| arg.0))
+            .collect::<Result<Vec<_>, _>>()?;
+        if route.is_empty() {
+            anyhow::bail!("benchmark route must contain at least one parcel");
+        }
+        Ok(Self(route))
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct BenchmarkConfig {
+    pub route: BenchmarkRoute,
+    // meters per second
+    pub speed: f32,
+    // report is written to `{output}.json` and `{output}.csv`
+    pub output: PathBuf,
+}
+
+pub struct BenchmarkPlugin;
+
+impl Plugin for BenchmarkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BenchmarkState>();
+        app.add_systems(
+            PostUpdate,
+            run_benchmark.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+struct FrameRecord {
+    time: f32,
+    frame_ms: f32,
+    parcel: IVec2,
+    loading_scenes: usize,
+    stalled: bool,
+}
+
+#[derive(Resource, Default)]
+enum BenchmarkState {
+    #[default]
+    Warmup,
+    Running {
+        start: f32,
+        frames: Vec<FrameRecord>,
+    },
+    Done,
+}
+
+fn route_position(route: &[IVec2], speed: f32, elapsed: f32) -> Option<Vec3> {
+    let to_world = |parcel: IVec2| {
+        Vec3::new(
+            (parcel.x as f32 + 0.5) * PARCEL_SIZE,
+            FLY_HEIGHT,
+            -(parcel.y as f32 + 0.5) * PARCEL_SIZE,
+        )
+    };
+
+    let mut remaining = elapsed * speed;
+    for pair in route.windows(2) {
+        let (from, to) = (to_world(pair[0]), to_world(pair[1]));
+        let length = from.distance(to);
+        if remaining <= length {
+            return Some(from.lerp(to, remaining / length.max(f32::EPSILON)));
+        }
+        remaining -= length;
+    }
+
+    // single parcel routes hold position for one second
+    (route.len() == 1 && elapsed < 1.0).then(|| to_world(route[0]))
+}
+
+// the parcel has a scene which is not yet running
+pub(crate) fn parcel_loading(
+    parcel: IVec2,
+    pointers: &ScenePointers,
+    live_scenes: &LiveScenes,
+    loading: &Query<(), With<SceneLoading>>,
+) -> bool {
+    match pointers.get(parcel) {
+        None => true,
+        Some(PointerResult::Nothing) => false,
+        Some(PointerResult::Exists { hash, .. }) => live_scenes
+            .0
+            .get(hash)
+            .map_or(true, |scene| loading.get(*scene).is_ok()),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_benchmark(
+    mut state: ResMut<BenchmarkState>,
+    bench: Res<BenchmarkConfig>,
+    config: Res<AppConfig>,
+    version: Option<Res<Version>>,
+    time: Res<Time<Real>>,
+    mut player: Query<&mut Transform, With<PrimaryUser>>,
+    pointers: Res<ScenePointers>,
+    live_scenes: Res<LiveScenes>,
+    loading: Query<(), With<SceneLoading>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let Ok(mut transform) = player.get_single_mut() else {
+        return;
+    };
+    let route = &bench.route.0;
+    let now = time.elapsed_seconds();
+
+    match &mut *state {
+        BenchmarkState::Warmup => {
+            let start = route_position(route, bench.speed, 0.0).unwrap();
+            transform.translation = start;
+
+            let first_loading = parcel_loading(route[0], &pointers, &live_scenes, &loading);
+            if (!first_loading && loading.is_empty()) || now > MAX_WARMUP_SECS {
+                info!("benchmark starting");
+                *state = BenchmarkState::Running {
+                    start: now,
+                    frames: Vec::default(),
+                };
+            }
+        }
+        BenchmarkState::Running { start, frames } => {
+            let elapsed = now - *start;
+            let Some(position) = route_position(route, bench.speed, elapsed) else {
+                info!("benchmark complete");
+                if let Err(e) = write_report(&bench, &config, version.as_deref(), frames) {
+                    error!("failed to write benchmark report: {e}");
+                }
+                *state = BenchmarkState::Done;
+                exit.send(AppExit::Success);
+                return;
+            };
+
+            transform.translation = position;
+            let parcel = vec3_to_parcel(position);
+            frames.push(FrameRecord {
+                time: elapsed,
+                frame_ms: time.delta_seconds() * 1000.0,
+                parcel,
+                loading_scenes: loading.iter().count(),
+                stalled: parcel_loading(parcel, &pointers, &live_scenes, &loading),
+            });
+        }
+        BenchmarkState::Done => (),
+    }
+}
+
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let ix = ((sorted.len() - 1) as f32 * p).round() as usize;
+    sorted[ix]
+}
+
+fn write_report(
+    bench: &BenchmarkConfig,
+    config: &AppConfig,
+    version: Option<&Version>,
+    frames: &[FrameRecord],
+) -> Result<(), anyhow::Error> {
+    let mut sorted = frames.iter().map(|f| f.frame_ms).collect::<Vec<_>>();
+    sorted.sort_by(f32::total_cmp);
+    let duration = frames.last().map_or(0.0, |f| f.time);
+    let mean_ms = sorted.iter().sum::<f32>() / sorted.len().max(1) as f32;
+
+    // contiguous runs of frames where the player's parcel was still loading
+    let mut stalls: Vec<(&FrameRecord, f32)> = Vec::default();
+    let mut stall_start: Option<&FrameRecord> = None;
+    for frame in frames {
+        match (stall_start, frame.stalled) {
+            (None, true) => stall_start = Some(frame),
+            (Some(start), false) => {
+                stalls.push((start, frame.time - start.time));
+                stall_start = None;
+            }
+            _ => (),
+        }
+    }
+    if let Some(start) = stall_start {
+        stalls.push((start, duration - start.time));
+    }
+    let stall_secs = stalls.iter().map(|(_, duration)| duration).sum::<f32>();
+    let stalls = stalls
+        .into_iter()
+        .map(|(start, duration)| {
+            serde_json::json!({
+                "start": start.time,
+                "duration": duration,
+                "parcel": [start.parcel.x, start.parcel.y],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let summary = serde_json::json!({
+        "version": version.map(|v| v.0.clone()),
+        "route": bench.route.0.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>(),
+        "speed": bench.speed,
+        "graphics": config.graphics,
+        "scene_load_distance": config.scene_load_distance,
+        "frames": frames.len(),
+        "duration_secs": duration,
+        "mean_fps": if duration > 0.0 { frames.len() as f32 / duration } else { 0.0 },
+        "frame_ms": {
+            "mean": mean_ms,
+            "p50": percentile(&sorted, 0.5),
+            "p95": percentile(&sorted, 0.95),
+            "p99": percentile(&sorted, 0.99),
+            "max": sorted.last().copied().unwrap_or_default(),
+        },
+        "hitches": sorted.iter().filter(|ms| **ms > HITCH_MS).count(),
+        "loading_stall_secs": stall_secs,
+        "loading_stalls": stalls,
+    });
+
+    let json_path = bench.output.with_extension("json");
+    std::fs::write(&json_path, serde_json::to_string_pretty(&summary)?)?;
+
+    let mut csv = "time,frame_ms,parcel_x,parcel_y,loading_scenes,stalled\n".to_owned();
+    for f in frames {
+        csv.push_str(&format!(
+            "{:.4},{:.3},{},{},{},{}\n",
+            f.time, f.frame_ms, f.parcel.x, f.parcel.y, f.loading_scenes, f.stalled as u8
+        ));
+    }
+    let csv_path = bench.output.with_extension("csv");
+    std::fs::write(&csv_path, csv)?;
+
+    info!("benchmark report written to {json_path:?} and {csv_path:?}");
+    Ok(())
+}
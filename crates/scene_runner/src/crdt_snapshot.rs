@@ -0,0 +1,131 @@
+// canonical json snapshots of the crdt state scenes have sent to the renderer, for golden tests of
+// component processing order and lww semantics. while `RecordCrdtState` is set, scenes keep a full
+// copy of the state received since their first update in `RendererSceneContext::received_crdt`.
+// `/crdt_snapshot [file]` starts recording (reloading the current scene), then writes its snapshot.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy_console::ConsoleCommand;
+use common::structs::PrimaryUser;
+use console::DoAddConsoleCommand;
+use dcl::interface::CrdtStore;
+use dcl_component::SceneEntityId;
+use serde_json::{json, Value};
+
+use crate::{
+    initialize_scene::LiveScenes, renderer_context::RendererSceneContext,
+    update_world::CrdtExtractors, ContainingScene,
+};
+
+pub struct CrdtSnapshotPlugin;
+
+impl Plugin for CrdtSnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RecordCrdtState>();
+        app.add_console_command::<CrdtSnapshotCommand, _>(crdt_snapshot_command);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct RecordCrdtState(pub bool);
+
+// components by id, then entities by id, with values decoded by the registered component type.
+// lww entries keep their timestamp, deleted entries are kept with a null value
+pub fn crdt_snapshot(store: &CrdtStore, interfaces: &CrdtExtractors) -> Value {
+    let describe = |id, data: &[u8]| match interfaces.0.get(&id) {
+        Some(interface) => interface.describe(data),
+        None => format!("<unregistered, {} bytes>", data.len()),
+    };
+
+    // zero padded so keys sort numerically
+    let entity_key = |entity: &SceneEntityId| format!("{:05}v{}", entity.id, entity.generation);
+
+    let mut components = BTreeMap::default();
+    for (id, state) in store.lww.iter() {
+        let entities = state
+            .last_write
+            .iter()
+            .map(|(entity, entry)| {
+                let value = entry.is_some.then(|| describe(*id, &entry.data));
+                (
+                    entity_key(entity),
+                    json!({ "timestamp": entry.timestamp.0, "value": value }),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        if !entities.is_empty() {
+            components.insert(format!("{:05}", id.0), json!({ "lww": entities }));
+        }
+    }
+    for (id, state) in store.go.iter() {
+        let entities = state
+            .0
+            .iter()
+            .map(|(entity, entries)| {
+                let values = entries
+                    .iter()
+                    .map(|entry| describe(*id, &entry.data))
+                    .collect::<Vec<_>>();
+                (entity_key(entity), json!(values))
+            })
+            .collect::<BTreeMap<_, _>>();
+        if !entities.is_empty() {
+            components.insert(format!("{:05}", id.0), json!({ "go": entities }));
+        }
+    }
+
+    json!(components)
+}
+
+pub fn crdt_snapshot_string(store: &CrdtStore, interfaces: &CrdtExtractors) -> String {
+    serde_json::to_string_pretty(&crdt_snapshot(store, interfaces)).unwrap()
+}
+
+/// write the current scene's crdt state as json (the first use starts recording and reloads it)
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/crdt_snapshot")]
+struct CrdtSnapshotCommand {
+    // defaults to `crdt-<scene hash>.json`
+    path: Option<String>,
+}
+
+fn crdt_snapshot_command(
+    mut input: ConsoleCommand<CrdtSnapshotCommand>,
+    mut record: ResMut<RecordCrdtState>,
+    mut live_scenes: ResMut<LiveScenes>,
+    interfaces: Res<CrdtExtractors>,
+    scenes: Query<&RendererSceneContext>,
+    containing_scene: ContainingScene,
+    player: Query<Entity, With<PrimaryUser>>,
+) {
+    let Some(Ok(command)) = input.take() else {
+        return;
+    };
+
+    let Some(context) = player
+        .get_single()
+        .ok()
+        .and_then(|player| containing_scene.get_parcel_oow(player))
+        .and_then(|scene| scenes.get(scene).ok())
+    else {
+        input.reply_failed("not in a scene");
+        return;
+    };
+
+    let Some(store) = context.received_crdt.as_ref().filter(|_| record.0) else {
+        // reload so it's recorded from the start
+        record.0 = true;
+        live_scenes.0.remove(&context.hash);
+        input.reply_ok("recording crdt state and reloading the scene, run again to write it");
+        return;
+    };
+
+    let path = command
+        .path
+        .unwrap_or_else(|| format!("crdt-{}.json", context.hash));
+    match std::fs::write(&path, crdt_snapshot_string(store, &interfaces)) {
+        Ok(()) => input.reply_ok(format!("crdt snapshot written to {path}")),
+        Err(e) => input.reply_failed(format!("failed to write {path}: {e}")),
+    }
+}
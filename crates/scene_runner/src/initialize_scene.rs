@@ -1,6 +1,11 @@
 use std::{borrow::Borrow, collections::VecDeque, num::ParseIntError, str::FromStr};
 
-use analytics::segment_system::SegmentConfig;
+use analytics::{
+    data_definition::{
+        SegmentEvent, SegmentEventExplorerRealmChange, SegmentEventExplorerSceneLoadTimes,
+    },
+    segment_system::{SegmentConfig, SegmentMetricsEvents},
+};
 use bevy::{
     asset::{io::Reader, AssetLoader, LoadContext},
     math::Vec3Swizzles,
@@ -13,7 +18,7 @@ use bevy::{
 use futures_lite::AsyncReadExt;
 
 use common::{
-    structs::{AppConfig, IVec2Arg, SceneLoadDistance, SceneMeta},
+    structs::{AppConfig, Determinism, IVec2Arg, MemoryPressure, SceneLoadDistance, SceneMeta},
     util::{TaskExt, TryPushChildrenEx},
 };
 use comms::{global_crdt::GlobalCrdtState, preview::PreviewMode};
@@ -87,6 +92,7 @@ impl Plugin for SceneLifecyclePlugin {
                 initialize_scene,
                 animate_ready_scene,
                 update_loading_quads,
+                record_scene_load_times,
             )
                 .in_set(SceneSets::Init),
         );
@@ -499,6 +505,23 @@ pub struct TestingData {
     pub test_mode: bool,
     pub inspect_hash: Option<String>,
     pub test_scenes: Option<TestScenes>,
+    // seconds before a headless run gives up
+    pub timeout: Option<f32>,
+    // fraction of pixels that may differ from a reference snapshot
+    pub snapshot_threshold: Option<f32>,
+    // results reported by scene test code, in the order they arrived
+    pub results: Vec<SceneTestResult>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SceneTestResult {
+    pub location: IVec2,
+    pub name: String,
+    pub passed: bool,
+    pub error: Option<String>,
+    pub stack: Option<String>,
+    pub frames: i32,
+    pub seconds: f32,
 }
 
 #[derive(Component)]
@@ -523,6 +546,7 @@ pub(crate) fn initialize_scene(
     testing_data: Res<TestingData>,
     preview_mode: Res<PreviewMode>,
     su_bridge: Res<SystemBridge>,
+    determinism: Res<Determinism>,
 ) {
     for (root, mut state, h_code, mut context, super_user) in loading_scenes.iter_mut() {
         if !matches!(state.as_mut(), SceneLoading::Javascript(_)) || context.tick_number != 1 {
@@ -585,6 +609,7 @@ pub(crate) fn initialize_scene(
             inspected,
             testing_data.test_mode,
             preview_mode.is_preview,
+            determinism.fixed_dt,
             super_user.map(|_| su_bridge.sender.clone()),
         );
 
@@ -599,6 +624,47 @@ pub(crate) fn initialize_scene(
     }
 }
 
+// time from spawning to running each scene, for telemetry
+fn record_scene_load_times(
+    loading: Query<(Entity, &SceneHash, Ref<SceneLoading>)>,
+    mut finished: RemovedComponents<SceneLoading>,
+    hashes: Query<&SceneHash>,
+    time: Res<Time<Real>>,
+    mut started: Local<HashMap<Entity, f32>>,
+    metrics: Option<ResMut<SegmentMetricsEvents>>,
+) {
+    let Some(mut metrics) = metrics else {
+        return;
+    };
+    let now = time.elapsed_seconds();
+    let mut report = |hash: &SceneHash, start: f32, success: bool| {
+        metrics.add_event(SegmentEvent::ExplorerSceneLoadTimes(
+            SegmentEventExplorerSceneLoadTimes {
+                scene_hash: hash.0.clone(),
+                elapsed: now - start,
+                success,
+            },
+        ));
+    };
+
+    for (entity, hash, state) in loading.iter() {
+        if state.is_added() {
+            started.insert(entity, now);
+        } else if state.is_changed() && matches!(*state, SceneLoading::Failed) {
+            if let Some(start) = started.remove(&entity) {
+                report(hash, start, false);
+            }
+        }
+    }
+
+    // scenes despawned while loading have no hash and aren't reported
+    for entity in finished.read() {
+        if let (Some(start), Ok(hash)) = (started.remove(&entity), hashes.get(entity)) {
+            report(hash, start, true);
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct LiveScenes(pub HashMap<String, Entity>);
 
@@ -794,9 +860,22 @@ pub fn process_realm_change(
     current_realm: Res<CurrentRealm>,
     mut live_scenes: ResMut<LiveScenes>,
     mut segment_config: Option<ResMut<SegmentConfig>>,
+    mut metrics: Option<ResMut<SegmentMetricsEvents>>,
+    mut previous_realm: Local<String>,
 ) {
     if current_realm.is_changed() {
         info!("realm change `{}`! purging scenes", current_realm.address);
+        if let Some(ref mut metrics) = metrics {
+            metrics.add_event(SegmentEvent::ExplorerRealmChange(
+                SegmentEventExplorerRealmChange {
+                    previous_realm: std::mem::replace(
+                        &mut *previous_realm,
+                        current_realm.address.clone(),
+                    ),
+                    new_realm: current_realm.address.clone(),
+                },
+            ));
+        }
         let mut realm_scene_urns = HashSet::default();
         for urn in current_realm
             .config
@@ -1055,6 +1134,7 @@ pub fn process_scene_lifecycle(
     pointers: Res<ScenePointers>,
     config: Res<AppConfig>,
     imposter_scene: Res<CurrentImposterScene>,
+    memory_pressure: Res<MemoryPressure>,
 ) {
     let mut required_scene_ids: HashMap<(String, Option<String>), bool> = HashMap::default();
 
@@ -1063,22 +1143,25 @@ pub fn process_scene_lifecycle(
         return;
     };
 
+    // shrink ranges under memory pressure so the furthest scenes go first
+    let load = range.load * memory_pressure.load_distance_scale();
+    let unload = if memory_pressure.keep_unload_margin() {
+        range.unload
+    } else {
+        0.0
+    };
+
     let current_scene = parcels_in_range(focus, 0.0, pointers.min(), pointers.max())
         .first()
         .and_then(|(p, _)| pointers.get(p))
         .and_then(PointerResult::hash_and_urn);
 
-    let pir = parcels_in_range(
-        focus,
-        range.load + range.unload,
-        pointers.min(),
-        pointers.max(),
-    );
+    let pir = parcels_in_range(focus, load + unload, pointers.min(), pointers.max());
 
     required_scene_ids.extend(
         pir.iter()
             .flat_map(|(parcel, dist)| {
-                if *dist < range.load {
+                if *dist < load {
                     pointers.get(parcel).and_then(PointerResult::hash_and_urn)
                 } else {
                     None
@@ -1107,7 +1190,7 @@ pub fn process_scene_lifecycle(
     // record additional optional scenes
     let mut keep_scene_ids = required_scene_ids.keys().cloned().collect::<HashSet<_>>();
     keep_scene_ids.extend(pir.iter().flat_map(|(parcel, dist)| {
-        if *dist >= range.load && *dist <= range.unload {
+        if *dist >= load && *dist <= unload {
             pointers
                 .get(parcel)
                 // immediately unload scenes from other realms, even if they might match
@@ -1,7 +1,10 @@
 use std::{
     collections::VecDeque,
     marker::PhantomData,
-    sync::mpsc::{sync_channel, Receiver, SyncSender, TryRecvError},
+    sync::{
+        mpsc::{sync_channel, Receiver, SyncSender, TryRecvError},
+        Arc,
+    },
     time::{Duration, SystemTime},
 };
 
@@ -19,9 +22,14 @@ use bevy::{
 use common::{
     rpc::RpcCall,
     sets::{SceneLoopSets, SceneSets},
-    structs::{AppConfig, PrimaryCamera, PrimaryUser},
+    structs::{
+        AppConfig, Determinism, FrameLoad, NotificationCategory, PowerSaving, PrimaryCamera,
+        PrimaryUser,
+    },
+    trace::{record_span, trace_active, trace_span, MAIN_TRACK},
     util::{dcl_assert, TryPushChildrenEx},
 };
+use crdt_snapshot::{CrdtSnapshotPlugin, RecordCrdtState};
 use dcl::{
     interface::CrdtType, RendererResponse, SceneId, SceneLogLevel, SceneLogMessage, SceneResponse,
 };
@@ -32,10 +40,16 @@ use dcl_component::{
 };
 use initialize_scene::{PortableScenes, TestingData};
 use ipfs::SceneIpfsLocation;
+use memory_pressure::MemoryPressurePlugin;
+use permissions::{answer_permission_requests, PermissionResponder};
 use primary_entities::PrimaryEntities;
+use scene_debug::SceneDebugPlugin;
 use spin_sleep::SpinSleeper;
+use trace::TracePlugin;
 use ui_core::ui_actions::{Click, On};
-use update_world::lights::LightsPlugin;
+use update_world::{
+    lights::LightsPlugin, post_processing::PostProcessingPlugin, skybox::SkyboxPlugin,
+};
 use util::SceneUtilPlugin;
 
 use self::{
@@ -48,14 +62,20 @@ use self::{
 };
 
 pub mod automatic_testing;
+pub mod benchmark;
 pub mod bounds_calc;
+pub mod crdt_snapshot;
 pub mod gltf_resolver;
 pub mod initialize_scene;
+pub mod memory_pressure;
 pub mod permissions;
 pub mod primary_entities;
 pub mod renderer_context;
+pub mod scene_debug;
+pub mod soak;
 #[cfg(test)]
 pub mod test;
+pub mod trace;
 pub mod update_scene;
 pub mod update_world;
 pub mod util;
@@ -71,6 +91,8 @@ pub struct SceneUpdates {
     pub eligible_jobs: usize,
     pub loop_end_time: Instant,
     pub scene_queue: VecDeque<(Entity, FloatOrd)>,
+    // send times of in-flight updates while a trace is running
+    trace_sent: HashMap<Entity, Instant>,
 }
 
 // safety: struct is sync except for the receiver.
@@ -126,9 +148,37 @@ pub struct DebugInfo {
 #[derive(Resource, Default)]
 pub struct Toasts(pub HashMap<String, Toast>);
 
+// max notifications kept in the notification center
+const MAX_NOTIFICATIONS: usize = 100;
+
+// resource holding the notification history, newest first
+#[derive(Resource, Default)]
+pub struct Notifications {
+    pub items: VecDeque<Notification>,
+}
+
+impl Notifications {
+    pub fn unread(&self) -> usize {
+        self.items.iter().filter(|n| !n.read).count()
+    }
+}
+
+// builds the click-through action, once for the toast and again each time the panel is shown
+pub type NotificationAction = Arc<dyn Fn() -> On<Click> + Send + Sync>;
+
+pub struct Notification {
+    pub category: NotificationCategory,
+    pub message: String,
+    pub time: f32,
+    pub read: bool,
+    pub action: Option<NotificationAction>,
+}
+
 #[derive(SystemParam)]
 pub struct Toaster<'w, 's> {
     toasts: ResMut<'w, Toasts>,
+    notifications: ResMut<'w, Notifications>,
+    config: Res<'w, AppConfig>,
     time: Res<'w, Time>,
     #[system_param(ignore)]
     _p: PhantomData<&'s ()>,
@@ -177,6 +227,41 @@ impl Toaster<'_, '_> {
     pub fn clear_toast(&mut self, key: &str) {
         self.toasts.0.remove(key);
     }
+
+    // record an event in the notification center and show it as a toast. repeats of the
+    // current toast for the key only refresh the toast.
+    pub fn notify(
+        &mut self,
+        category: NotificationCategory,
+        key: impl Into<String>,
+        message: impl Into<String>,
+        action: Option<NotificationAction>,
+    ) {
+        if self.config.muted_notifications.contains(&category) {
+            return;
+        }
+
+        let key = key.into();
+        let message = message.into();
+        let repeat = self
+            .toasts
+            .0
+            .get(&key)
+            .is_some_and(|existing| existing.message == message);
+
+        if !repeat {
+            self.notifications.items.push_front(Notification {
+                category,
+                message: message.clone(),
+                time: self.time.elapsed_seconds(),
+                read: false,
+                action: action.clone(),
+            });
+            self.notifications.items.truncate(MAX_NOTIFICATIONS);
+        }
+
+        self.do_add_toast(key, message, action.map(|action| action()));
+    }
 }
 
 pub struct Toast {
@@ -204,8 +289,12 @@ impl Plugin for SceneRunnerPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<CrdtExtractors>();
         app.init_resource::<DebugInfo>();
+        app.init_resource::<FrameLoad>();
         app.init_resource::<Toasts>();
+        app.init_resource::<Notifications>();
         app.init_resource::<TestingData>();
+        app.init_resource::<Determinism>();
+        app.init_resource::<RealmTransition>();
 
         let (sender, receiver) = sync_channel(1000);
         app.insert_resource(SceneUpdates {
@@ -217,6 +306,7 @@ impl Plugin for SceneRunnerPlugin {
             eligible_jobs: 0,
             scene_queue: Default::default(),
             loop_end_time: Instant::now(),
+            trace_sent: Default::default(),
         });
 
         app.add_event::<LoadSceneEvent>();
@@ -272,6 +362,10 @@ impl Plugin for SceneRunnerPlugin {
         );
 
         app.add_plugins(SceneLifecyclePlugin);
+        app.add_systems(
+            PostUpdate,
+            answer_permission_requests.run_if(resource_exists::<PermissionResponder>),
+        );
 
         app.add_systems(
             Update,
@@ -314,7 +408,13 @@ impl Plugin for SceneRunnerPlugin {
         app.add_plugins(SceneInputPlugin);
         app.add_plugins(SceneOutputPlugin);
         app.add_plugins(SceneUtilPlugin);
+        app.add_plugins(SceneDebugPlugin);
+        app.add_plugins(MemoryPressurePlugin);
         app.add_plugins(LightsPlugin);
+        app.add_plugins(PostProcessingPlugin);
+        app.add_plugins(SkyboxPlugin);
+        app.add_plugins(TracePlugin);
+        app.add_plugins(CrdtSnapshotPlugin);
     }
 }
 
@@ -328,7 +428,7 @@ fn run_scene_loop(world: &mut World) {
         .and_then(|window| window.current_monitor())
         .and_then(|monitor| monitor.refresh_rate_millihertz());
     let config = world.resource::<AppConfig>();
-    let fps = if config.graphics.vsync {
+    let mut fps = if config.graphics.vsync {
         // TODO this should use video mode if we add fullscreen video modes
         refresh_rate
             .map(|rr| (((rr as f64 + 999.0) / 1000.0).ceil()))
@@ -336,6 +436,9 @@ fn run_scene_loop(world: &mut World) {
     } else {
         config.graphics.fps_target as f64
     };
+    if world.get_resource::<PowerSaving>().is_some_and(|ps| ps.0) && config.power_save_fps > 0 {
+        fps = fps.min(config.power_save_fps as f64);
+    }
     let mut loop_schedule = world.resource_mut::<SceneLoopSchedule>();
     let mut schedule = std::mem::take(&mut loop_schedule.schedule);
 
@@ -361,40 +464,63 @@ fn run_scene_loop(world: &mut World) {
     let target_end_time = start_loop_time + Duration::from_secs_f64(loop_schedule.run_time);
     loop_schedule.prev_time = start_loop_time;
 
+    let mut frame_load = world.resource_mut::<FrameLoad>();
+    frame_load.non_scene_secs =
+        frame_load.non_scene_secs * 0.9 + non_loop_duration.as_secs_f32() * 0.1;
+    frame_load.target_secs = frame_target_duration.as_secs_f32();
+
     world.resource_mut::<SceneUpdates>().loop_end_time = target_end_time;
 
     // run at least once to collect updates even if no scenes are eligible
     let mut run_once = false;
 
+    let loop_span = trace_span(MAIN_TRACK, "scene loop", "scene loop");
     // run until time elapsed or all scenes are updated
     while !run_once
         || (Instant::now() < target_end_time
             && (world.resource::<SceneUpdates>().eligible_jobs > 0
                 || !world.resource::<SceneUpdates>().jobs_in_flight.is_empty()))
     {
+        let _span = trace_span(MAIN_TRACK, "scene loop", "scene schedule");
         schedule.run(world);
         run_once = true;
     }
+    drop(loop_span);
+
+    let mut frame_load = world.resource_mut::<FrameLoad>();
+    frame_load.scene_secs =
+        frame_load.scene_secs * 0.9 + start_loop_time.elapsed().as_secs_f32() * 0.1;
 
     let mut loop_schedule = world.resource_mut::<SceneLoopSchedule>();
     loop_schedule.schedule = schedule;
 
     if let Some(sleep_time) = target_end_time.checked_duration_since(start_loop_time) {
         if fps != 0.0 {
+            let _span = trace_span(MAIN_TRACK, "scene loop", "sleep");
             loop_schedule.sleeper.sleep(sleep_time)
         }
     }
 }
 
+const POWER_SAVE_SCENE_INTERVAL: f32 = 0.2;
+
 fn update_scene_priority(
     mut scenes: Query<(Entity, &GlobalTransform, &mut RendererSceneContext), Without<SceneLoading>>,
     player: Query<(Entity, &GlobalTransform), With<PrimaryUser>>,
     mut updates: ResMut<SceneUpdates>,
     time: Res<Time>,
     containing_scene: ContainingScene,
+    power_saving: Option<Res<PowerSaving>>,
 ) {
     updates.eligible_jobs = 0;
 
+    // when saving power, only the scenes the player is in run every frame
+    let min_interval = if power_saving.is_some_and(|ps| ps.0) {
+        POWER_SAVE_SCENE_INTERVAL
+    } else {
+        0.0
+    };
+
     let (active_scenes, player_translation) = player
         .get_single()
         .map(|(e, gt)| (containing_scene.get(e), gt.translation()))
@@ -422,7 +548,12 @@ fn update_scene_priority(
             } else {
                 distance
             };
-            let not_yet_run = context.last_sent < time.elapsed_seconds();
+            let interval = if context.priority == 0.0 {
+                0.0
+            } else {
+                min_interval
+            };
+            let not_yet_run = context.last_sent + interval < time.elapsed_seconds();
 
             (!context.in_flight && not_yet_run).then(|| {
                 updates.eligible_jobs += 1;
@@ -453,6 +584,15 @@ fn update_scene_priority(
 #[derive(Component)]
 pub struct OutOfWorld;
 
+// a realm change in progress, from the `ChangeRealmEvent` until the new realm is current. the
+// player is held out of world meanwhile, and a teleport sent along with the change is applied on
+// arrival rather than against the old realm's scenes
+#[derive(Resource, Default, Debug)]
+pub struct RealmTransition {
+    pub realm: Option<String>,
+    pub target: Option<IVec2>,
+}
+
 // helper to get the scene entity containing a given world position
 #[derive(SystemParam)]
 pub struct ContainingScene<'w, 's> {
@@ -499,6 +639,27 @@ impl ContainingScene<'_, '_> {
             .unwrap_or_default()
     }
 
+    // the scene whose settings apply globally (sky, post-processing, etc) for the entity:
+    // the parcel scene at the entity's position if accepted by `filter`, else the accepted
+    // portable with the lowest hash
+    pub fn get_controlling_scene(
+        &self,
+        ent: Entity,
+        filter: impl Fn(Entity) -> bool,
+    ) -> Option<Entity> {
+        self.get_parcel_oow(ent)
+            .filter(|parcel| filter(*parcel))
+            .or_else(|| {
+                self.portable_scenes
+                    .0
+                    .keys()
+                    .filter_map(|hash| self.live_scenes.0.get(hash).map(|scene| (hash, *scene)))
+                    .filter(|(_, scene)| filter(*scene))
+                    .min_by_key(|(hash, _)| *hash)
+                    .map(|(_, scene)| scene)
+            })
+    }
+
     // the parcel at the position, plus any global scenes
     pub fn get_position(&self, position: Vec3) -> HashSet<Entity> {
         let parcel = (position.xz() * Vec2::new(1.0, -1.0) / PARCEL_SIZE)
@@ -784,12 +945,16 @@ fn send_scene_updates(
         context.last_sent = time.elapsed_seconds();
         dcl_assert!(!updates.jobs_in_flight.contains(&ent));
         updates.jobs_in_flight.insert(ent);
+        if trace_active() {
+            updates.trace_sent.insert(ent, Instant::now());
+        }
     }
 
     updates.eligible_jobs -= 1;
 }
 
 // system to run the current active script
+#[allow(clippy::too_many_arguments)]
 fn receive_scene_updates(
     mut commands: Commands,
     mut updates: ResMut<SceneUpdates>,
@@ -798,12 +963,18 @@ fn receive_scene_updates(
     frame: Res<FrameCount>,
     mut rpc_call_events: EventWriter<RpcCall>,
     mut toaster: Toaster,
+    record_crdt: Res<RecordCrdtState>,
 ) {
     loop {
         let maybe_completed_job = match updates.receiver().try_recv() {
             Ok(response) => match response {
                 SceneResponse::WaitingForInspector => {
-                    toaster.add_toast("inspector", "Scene paused waiting for inspector session");
+                    toaster.notify(
+                        NotificationCategory::Scene,
+                        "inspector",
+                        "Scene paused waiting for inspector session, connect with chrome://inspect",
+                        None,
+                    );
                     None
                 }
                 SceneResponse::CompareSnapshot(compare) => {
@@ -813,25 +984,33 @@ fn receive_scene_updates(
                     None
                 }
                 SceneResponse::Error(scene_id, message) => {
-                    if let Some(root) = updates.scene_ids.get(&scene_id) {
-                        if let Ok(mut context) = scenes.get_mut(*root) {
+                    if let Some(root) = updates.scene_ids.get(&scene_id).copied() {
+                        updates.trace_sent.remove(&root);
+                        if let Ok(mut context) = scenes.get_mut(root) {
                             context.broken = true;
                             context.in_flight = false;
                             let timestamp = context.total_runtime as f64 + 1.0;
                             error!("[{scene_id:?} @ {}] error: {message}", context.tick_number);
+                            toaster.notify(
+                                NotificationCategory::Scene,
+                                format!("scene-error-{}", context.hash),
+                                format!("Scene `{}` stopped with an error", context.title),
+                                None,
+                            );
                             context.log(SceneLogMessage {
                                 timestamp,
                                 level: SceneLogLevel::SystemError,
                                 message,
                             });
                         }
-                        Some(*root)
+                        Some(root)
                     } else {
                         None
                     }
                 }
                 SceneResponse::Ok(scene_id, census, mut crdt, runtime, messages, rpc_calls) => {
-                    let root = updates.scene_ids.get(&scene_id).unwrap();
+                    let root = *updates.scene_ids.get(&scene_id).unwrap();
+                    let sent = updates.trace_sent.remove(&root);
                     debug!(
                         "scene {:?}/{:?} received updates! [+{}, -{}]",
                         census.scene_id,
@@ -839,7 +1018,7 @@ fn receive_scene_updates(
                         census.born.len(),
                         census.died.len()
                     );
-                    if let Ok(mut context) = scenes.get_mut(*root) {
+                    if let Ok(mut context) = scenes.get_mut(root) {
                         context.tick_number = context.tick_number.wrapping_add(1);
                         context.last_update_dt = runtime.0 - context.total_runtime;
                         context.total_runtime = runtime.0;
@@ -850,12 +1029,38 @@ fn receive_scene_updates(
                         for message in messages.into_iter() {
                             context.log(message);
                         }
-                        let mut commands = commands.entity(*root);
+                        // only scenes recorded from their first update, so snapshots are complete
+                        if record_crdt.0
+                            && (context.received_crdt.is_some() || context.tick_number == 1)
+                        {
+                            let context = &mut *context;
+                            let received =
+                                context.received_crdt.get_or_insert_with(Default::default);
+                            received.update_from(crdt.clone());
+                            received.clean_up(&context.death_row);
+                        }
+                        let apply_start = Instant::now();
+                        let mut commands = commands.entity(root);
                         for (component_id, interface) in crdt_interfaces.0.iter() {
                             interface.updates_to_entity(*component_id, &mut crdt, &mut commands);
                         }
+                        if trace_active() {
+                            // the scene's own track covers the round trip to its thread
+                            let track = format!("scene {} {}", context.base, context.title);
+                            if let Some(sent) = sent {
+                                let name = format!("update {}", context.tick_number);
+                                record_span(&track, "scene", name, sent, apply_start);
+                            }
+                            record_span(
+                                MAIN_TRACK,
+                                "scene",
+                                format!("apply {track}"),
+                                apply_start,
+                                Instant::now(),
+                            );
+                        }
                         dcl_assert!(
-                            updates.jobs_in_flight.contains(root) || context.tick_number <= 2
+                            updates.jobs_in_flight.contains(&root) || context.tick_number <= 2
                         );
 
                         for rpc_call in rpc_calls {
@@ -866,7 +1071,7 @@ fn receive_scene_updates(
                             "no scene entity, probably got dropped before we processed the result"
                         );
                     }
-                    Some(*root)
+                    Some(root)
                 }
             },
             Err(TryRecvError::Empty) => return,
@@ -0,0 +1,146 @@
+// track process and texture memory, and progressively shed load when the configured limits are
+// exceeded: trimming caches, dropping the unload margin, then downscaling textures and reducing the
+// scene load distance (see `MemoryPressure` for the per-level actions).
+
+use bevy::prelude::*;
+use common::{
+    structs::{AppConfig, MemoryPressure},
+    tr,
+};
+use comms::profile::ProfileCache;
+
+use crate::{DebugInfo, Toaster};
+
+const CHECK_INTERVAL_SECS: f32 = 2.0;
+// time to give the last action to take effect before escalating again
+const ESCALATE_SECS: f32 = 10.0;
+// time to spend within limits before relaxing one level
+const RELAX_SECS: f32 = 10.0;
+// fraction of the limits usage must drop below before relaxing
+const RELAX_FRACTION: f64 = 0.8;
+
+pub struct MemoryPressurePlugin;
+
+impl Plugin for MemoryPressurePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MemoryPressure>();
+        app.add_systems(Update, update_memory_pressure);
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn resident_set_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+// TODO: resident memory on other platforms
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn resident_set_bytes() -> Option<u64> {
+    None
+}
+
+fn describe(level: u32) -> &'static str {
+    match level {
+        1 => "trimming caches",
+        2 => "unloading distant scenes",
+        3 => "downscaling textures and reducing load distance",
+        _ => "reducing load distance further",
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_memory_pressure(
+    mut pressure: ResMut<MemoryPressure>,
+    config: Res<AppConfig>,
+    images: Res<Assets<Image>>,
+    time: Res<Time>,
+    profiles: Option<ResMut<ProfileCache>>,
+    mut toaster: Toaster,
+    mut debug_info: ResMut<DebugInfo>,
+    mut last_check: Local<f32>,
+    mut within_since: Local<Option<f32>>,
+    mut last_escalation: Local<Option<f32>>,
+) {
+    let now = time.elapsed_seconds();
+    if now - *last_check < CHECK_INTERVAL_SECS {
+        return;
+    }
+    *last_check = now;
+
+    let rss_bytes = resident_set_bytes();
+    let texture_bytes = images
+        .iter()
+        .map(|(_, image)| image.data.len() as u64)
+        .sum::<u64>();
+
+    // usage as a fraction of each enabled limit
+    let fraction = |used: u64, limit_mb: u32| {
+        (limit_mb > 0).then(|| used as f64 / (limit_mb as f64 * 1024.0 * 1024.0))
+    };
+    let usage = [
+        rss_bytes.and_then(|rss| fraction(rss, config.memory_limit_mb)),
+        fraction(texture_bytes, config.texture_memory_limit_mb),
+    ]
+    .into_iter()
+    .flatten()
+    .fold(0.0f64, f64::max);
+
+    let prev_level = pressure.level;
+    let mut level = prev_level;
+    if usage > 1.0 {
+        *within_since = None;
+        let cooled_down = !last_escalation.is_some_and(|last| now - last < ESCALATE_SECS);
+        if cooled_down && level < MemoryPressure::MAX_LEVEL {
+            level += 1;
+            *last_escalation = Some(now);
+        }
+    } else if usage < RELAX_FRACTION && level > 0 {
+        let since = *within_since.get_or_insert(now);
+        if now - since > RELAX_SECS {
+            level -= 1;
+            *within_since = None;
+        }
+    } else {
+        *within_since = None;
+    }
+
+    pressure.rss_bytes = rss_bytes;
+    pressure.texture_bytes = texture_bytes;
+
+    debug_info.info.insert(
+        "Memory",
+        format!(
+            "{} rss, {}mb textures, pressure {level}",
+            rss_bytes
+                .map(|rss| format!("{}mb", rss / 1024 / 1024))
+                .unwrap_or_else(|| "?".to_owned()),
+            texture_bytes / 1024 / 1024,
+        ),
+    );
+
+    if level == prev_level {
+        return;
+    }
+    pressure.level = level;
+
+    if level > prev_level {
+        if let Some(mut profiles) = profiles.filter(|_| level == 1) {
+            let trimmed = profiles.trim();
+            debug!("trimmed {trimmed} cached profiles");
+        }
+        let action = describe(level);
+        warn!("memory usage at {:.0}% of limit, {action}", usage * 100.0);
+        toaster.add_toast(
+            "memory-pressure",
+            tr!(&format!("toast-memory-low-{}", level.min(4))),
+        );
+    } else {
+        info!("memory pressure relaxed to level {level}");
+        if level == 0 {
+            toaster.clear_toast("memory-pressure");
+        }
+    }
+}
@@ -1,13 +1,13 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, sync::Arc};
 
 use crate::{renderer_context::RendererSceneContext, ContainingScene, Toaster};
-use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
 use common::{
     dynamics::PLAYER_COLLIDER_RADIUS,
     rpc::RpcResultSender,
     structs::{
-        AppConfig, PermissionTarget, PermissionType, PrimaryPlayerRes, SettingsTab,
-        ShowSettingsEvent,
+        AppConfig, NotificationCategory, PermissionTarget, PermissionType, PermissionValue,
+        PrimaryPlayerRes, SettingsTab, ShowSettingsEvent,
     },
 };
 use ipfs::CurrentRealm;
@@ -26,6 +26,8 @@ pub struct PermissionRequest {
     pub scene: Entity,
     pub is_portable: bool,
     pub additional: Option<String>,
+    // longer content the user can inspect from the dialog
+    pub details: Option<String>,
     pub ty: PermissionType,
     pub sender: RpcResultSender<bool>,
 }
@@ -43,6 +45,7 @@ impl PermissionManager {
         scene: Entity,
         is_portable: bool,
         additional: Option<String>,
+        details: Option<String>,
     ) -> Receiver<bool> {
         let (sender, receiver) = channel();
         self.pending.push_back(PermissionRequest {
@@ -52,11 +55,97 @@ impl PermissionManager {
             ty,
             sender: RpcResultSender::new(sender),
             additional,
+            details,
         });
         receiver
     }
 }
 
+// how long an automatic answer is remembered, matching the options in the permission dialog
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PermissionScope {
+    Once,
+    Scene,
+    Realm,
+    Global,
+}
+
+// answers permission prompts without the dialog, for tests. insert the resource and set a response
+// per type; requests for types without a response stay pending.
+#[derive(Resource, Default)]
+pub struct PermissionResponder {
+    responses: HashMap<PermissionType, (bool, PermissionScope)>,
+    // requests answered so far, oldest first
+    pub answered: Vec<(PermissionType, Entity, bool)>,
+}
+
+impl PermissionResponder {
+    pub fn allow(&mut self, ty: PermissionType) {
+        self.respond(ty, true, PermissionScope::Once);
+    }
+
+    pub fn deny(&mut self, ty: PermissionType) {
+        self.respond(ty, false, PermissionScope::Once);
+    }
+
+    // remembered answers are stored in the `AppConfig` like the dialog's "always" options
+    pub fn respond(&mut self, ty: PermissionType, allow: bool, scope: PermissionScope) {
+        self.responses.insert(ty, (allow, scope));
+    }
+
+    pub fn clear(&mut self, ty: PermissionType) {
+        self.responses.remove(&ty);
+    }
+
+    pub fn answered_count(&self, ty: PermissionType) -> usize {
+        self.answered.iter().filter(|(t, ..)| *t == ty).count()
+    }
+}
+
+pub fn answer_permission_requests(
+    mut manager: ResMut<PermissionManager>,
+    mut responder: ResMut<PermissionResponder>,
+    mut config: ResMut<AppConfig>,
+    scenes: Query<&RendererSceneContext>,
+) {
+    let responder = &mut *responder;
+    manager.pending.retain(|req| {
+        let Some((allow, scope)) = responder.responses.get(&req.ty).copied() else {
+            return true;
+        };
+        let value = if allow {
+            PermissionValue::Allow
+        } else {
+            PermissionValue::Deny
+        };
+        match scope {
+            PermissionScope::Once => (),
+            PermissionScope::Scene => {
+                if let Ok(ctx) = scenes.get(req.scene) {
+                    config
+                        .scene_permissions
+                        .entry(ctx.hash.clone())
+                        .or_default()
+                        .insert(req.ty, value);
+                }
+            }
+            PermissionScope::Realm => {
+                config
+                    .realm_permissions
+                    .entry(req.realm.clone())
+                    .or_default()
+                    .insert(req.ty, value);
+            }
+            PermissionScope::Global => {
+                config.default_permissions.insert(req.ty, value);
+            }
+        }
+        req.sender.clone().send(allow);
+        responder.answered.push((req.ty, req.scene, allow));
+        false
+    });
+}
+
 #[allow(clippy::type_complexity)]
 #[derive(SystemParam)]
 pub struct Permission<'w, 's, T: Send + Sync + 'static> {
@@ -99,6 +188,18 @@ impl<T: Send + Sync + 'static> Permission<'_, '_, T> {
         value: T,
         additional: Option<String>,
         allow_out_of_scene: bool,
+    ) {
+        self.check_with_details(ty, scene, value, additional, None, allow_out_of_scene);
+    }
+
+    pub fn check_with_details(
+        &mut self,
+        ty: PermissionType,
+        scene: Entity,
+        value: T,
+        additional: Option<String>,
+        details: Option<String>,
+        allow_out_of_scene: bool,
     ) {
         let Some((in_scene, hash, _, is_portable)) = self.get_scene_info(scene) else {
             return;
@@ -135,6 +236,7 @@ impl<T: Send + Sync + 'static> Permission<'_, '_, T> {
                         scene,
                         is_portable,
                         additional,
+                        details,
                     ),
                 ));
             }
@@ -182,6 +284,7 @@ impl<T: Send + Sync + 'static> Permission<'_, '_, T> {
                             scene,
                             is_portable,
                             None,
+                            None,
                         ),
                     ))
                 }
@@ -205,16 +308,19 @@ impl<T: Send + Sync + 'static> Permission<'_, '_, T> {
             let portable_name = self
                 .get_scene_info(scene)
                 .and_then(|(_, _, title, is_portable)| is_portable.then_some(title));
-            self.toaster.add_clicky_toast(
+            self.toaster.notify(
+                NotificationCategory::Permission,
                 format!("{:?}", ty),
                 ty.on_success(portable_name),
-                On::<Click>::new(
-                    (move |mut target: ResMut<PermissionTarget>| {
-                        target.scene = Some(scene);
-                        target.ty = Some(ty);
-                    })
-                    .pipe(ShowSettingsEvent(SettingsTab::Permissions).send_value()),
-                ),
+                Some(Arc::new(move || {
+                    On::<Click>::new(
+                        (move |mut target: ResMut<PermissionTarget>| {
+                            target.scene = Some(scene);
+                            target.ty = Some(ty);
+                        })
+                        .pipe(ShowSettingsEvent(SettingsTab::Permissions).send_value()),
+                    )
+                })),
             );
         }
         matching.into_iter().map(|(value, _, _)| value)
@@ -233,16 +339,19 @@ impl<T: Send + Sync + 'static> Permission<'_, '_, T> {
             let portable_name = self
                 .get_scene_info(scene)
                 .and_then(|(_, _, title, is_portable)| is_portable.then_some(title));
-            self.toaster.add_clicky_toast(
+            self.toaster.notify(
+                NotificationCategory::Permission,
                 format!("{:?}", ty),
                 ty.on_fail(portable_name),
-                On::<Click>::new(
-                    (move |mut target: ResMut<PermissionTarget>| {
-                        target.scene = Some(scene);
-                        target.ty = Some(ty);
-                    })
-                    .pipe(ShowSettingsEvent(SettingsTab::Permissions).send_value()),
-                ),
+                Some(Arc::new(move || {
+                    On::<Click>::new(
+                        (move |mut target: ResMut<PermissionTarget>| {
+                            target.scene = Some(scene);
+                            target.ty = Some(ty);
+                        })
+                        .pipe(ShowSettingsEvent(SettingsTab::Permissions).send_value()),
+                    )
+                })),
             );
         }
         matching.into_iter().map(|(value, _, _)| value)
@@ -281,6 +390,7 @@ impl PermissionStrings for PermissionType {
             PermissionType::Fetch => "Fetch Data",
             PermissionType::Websocket => "Open Websocket",
             PermissionType::OpenUrl => "Open Url",
+            PermissionType::ShowStreams => "Show Streams",
         }
     }
 
@@ -332,6 +442,7 @@ impl PermissionStrings for PermissionType {
             PermissionType::Fetch => "fetch data from a remote server",
             PermissionType::Websocket => "open a web socket to communicate with a remote server",
             PermissionType::OpenUrl => "open a url in your browser",
+            PermissionType::ShowStreams => "show video shared by other players",
         }
     }
 
@@ -351,6 +462,7 @@ impl PermissionStrings for PermissionType {
             PermissionType::Fetch => "fetching remote data",
             PermissionType::Websocket => "opening a websocket",
             PermissionType::OpenUrl => "opening a url in your browser",
+            PermissionType::ShowStreams => "showing video shared by other players",
         }
     }
 }
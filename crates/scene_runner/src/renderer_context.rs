@@ -68,6 +68,8 @@ pub struct RendererSceneContext {
     pub broken: bool,
 
     pub crdt_store: CrdtStore,
+    // everything received from the scene, kept while `RecordCrdtState` is set
+    pub received_crdt: Option<CrdtStore>,
 
     // readiness to update, if anything blocks the scene should not run
     pub blocked: HashSet<&'static str>,
@@ -133,6 +135,7 @@ impl RendererSceneContext {
             broken: false,
             priority,
             crdt_store: Default::default(),
+            received_crdt: None,
             blocked: Default::default(),
             total_runtime: 0.0,
             tick_number: 0,
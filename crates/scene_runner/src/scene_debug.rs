@@ -0,0 +1,181 @@
+// `/inspect` console command for debugging live scenes. attaching reloads the scene with the v8
+// inspector enabled (only one scene can be inspected at a time, on port 9222), and scenes can be
+// paused, stepped a tick at a time and resumed independently of the inspector.
+
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy_console::ConsoleCommand;
+use common::structs::{IVec2Arg, PrimaryUser};
+use console::DoAddConsoleCommand;
+
+use crate::{
+    initialize_scene::{LiveScenes, TestingData},
+    renderer_context::RendererSceneContext,
+    ContainingScene,
+};
+
+// blocks the scene from being scheduled
+pub const PAUSED: &str = "paused";
+
+pub struct SceneDebugPlugin;
+
+impl Plugin for SceneDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command::<InspectCommand, _>(inspect_command);
+        app.add_systems(Update, repause_stepped);
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum InspectAction {
+    List,
+    Attach,
+    Detach,
+    Pause,
+    Resume,
+    Step,
+}
+
+/// debug live scenes: list, attach/detach the inspector, pause/resume/step
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/inspect")]
+struct InspectCommand {
+    action: InspectAction,
+    /// scene hash or parcel (defaults to the current scene)
+    scene: Option<String>,
+}
+
+// scenes allowed to run a single tick, with the time their last update was sent
+#[derive(Component)]
+struct Stepping(f32);
+
+#[allow(clippy::too_many_arguments)]
+fn inspect_command(
+    mut commands: Commands,
+    mut input: ConsoleCommand<InspectCommand>,
+    mut live_scenes: ResMut<LiveScenes>,
+    mut testing_data: ResMut<TestingData>,
+    mut scenes: Query<&mut RendererSceneContext>,
+    containing_scene: ContainingScene,
+    player: Query<Entity, With<PrimaryUser>>,
+) {
+    let Some(Ok(InspectCommand { action, scene })) = input.take() else {
+        return;
+    };
+
+    if let InspectAction::List = action {
+        let mut list = live_scenes
+            .0
+            .values()
+            .filter_map(|ent| scenes.get(*ent).ok())
+            .collect::<Vec<_>>();
+        list.sort_by_key(|context| (context.base.x, context.base.y));
+        for context in list {
+            let mut state = Vec::default();
+            if context.inspected {
+                state.push("inspected");
+            }
+            if context.blocked.contains(PAUSED) {
+                state.push("paused");
+            }
+            if context.broken {
+                state.push("broken");
+            }
+            input.reply(format!(
+                "{} `{}` at {},{} [{}] tick {}",
+                context.hash,
+                context.title,
+                context.base.x,
+                context.base.y,
+                state.join(", "),
+                context.tick_number,
+            ));
+        }
+        input.ok();
+        return;
+    }
+
+    if let InspectAction::Detach = action {
+        match testing_data.inspect_hash.take() {
+            Some(hash) => {
+                // restart without the inspector
+                live_scenes.0.remove(&hash);
+                input.reply_ok(format!("detached from {hash}"));
+            }
+            None => input.reply_failed("no scene is inspected"),
+        }
+        return;
+    }
+
+    let target = match scene.as_deref() {
+        None => player
+            .get_single()
+            .ok()
+            .and_then(|player| containing_scene.get_parcel_oow(player)),
+        Some(scene) => live_scenes.0.get(scene).copied().or_else(|| {
+            let parcel = IVec2Arg::from_str(scene).ok()?.0;
+            live_scenes.0.values().copied().find(|ent| {
+                scenes
+                    .get(*ent)
+                    .is_ok_and(|context| context.parcels.contains(&parcel))
+            })
+        }),
+    };
+    let Some((ent, mut context)) = target.and_then(|ent| Some((ent, scenes.get_mut(ent).ok()?)))
+    else {
+        input.reply_failed("scene not found");
+        return;
+    };
+
+    match action {
+        InspectAction::Attach => {
+            if !cfg!(feature = "inspect") {
+                input.reply_failed("the inspector requires the `inspect` feature");
+                return;
+            }
+            if let Some(previous) = testing_data.inspect_hash.replace(context.hash.clone()) {
+                live_scenes.0.remove(&previous);
+            }
+            live_scenes.0.remove(&context.hash);
+            input.reply_ok(format!(
+                "reloading {} for inspection, connect with chrome://inspect",
+                context.hash
+            ));
+        }
+        InspectAction::Pause => {
+            context.blocked.insert(PAUSED);
+            input.reply_ok(format!(
+                "paused {} at tick {}",
+                context.hash, context.tick_number
+            ));
+        }
+        InspectAction::Resume => {
+            context.blocked.remove(PAUSED);
+            commands.entity(ent).remove::<Stepping>();
+            input.reply_ok(format!("resumed {}", context.hash));
+        }
+        InspectAction::Step => {
+            if !context.blocked.remove(PAUSED) {
+                input.reply_failed("scene is not paused");
+                return;
+            }
+            commands.entity(ent).insert(Stepping(context.last_sent));
+            input.reply_ok(format!("stepping {}", context.hash));
+        }
+        InspectAction::List | InspectAction::Detach => unreachable!(),
+    }
+}
+
+// pause stepped scenes again once their tick has been sent
+fn repause_stepped(
+    mut commands: Commands,
+    mut scenes: Query<(Entity, &mut RendererSceneContext, &Stepping)>,
+) {
+    for (ent, mut context, stepping) in scenes.iter_mut() {
+        if context.last_sent != stepping.0 {
+            context.blocked.insert(PAUSED);
+            commands.entity(ent).remove::<Stepping>();
+        }
+    }
+}
@@ -0,0 +1,320 @@
+// soak test: teleport around a list of parcels (optionally in other realms) for hours, sampling
+// memory, entity, asset and handle counts after each full cycle. metrics that grow on every cycle
+// are flagged as likely leaks. the report is rewritten after each cycle so an interrupted run still
+// leaves data, and the app exits when the time is up (with an error if anything was flagged).
+
+use std::{path::PathBuf, str::FromStr};
+
+use bevy::{app::AppExit, ecs::system::SystemParam, gltf::Gltf, prelude::*};
+use common::{
+    rpc::{RpcCall, RpcResultSender},
+    structs::{IVec2Arg, PrimaryUser},
+};
+use ipfs::{ChangeRealmEvent, CurrentRealm};
+use scene_material::SceneMaterial;
+
+use crate::{
+    benchmark::parcel_loading,
+    initialize_scene::{LiveScenes, SceneLoading, ScenePointers},
+    memory_pressure::resident_set_bytes,
+    vec3_to_parcel,
+};
+
+// max time to wait for a realm change or for a stop to load before moving on
+const MAX_LOAD_SECS: f32 = 120.0;
+// cycles a metric must grow for in a row to be flagged. the first cycle is warmup and not counted
+const LEAK_CYCLES: usize = 3;
+
+#[derive(Clone, Debug)]
+pub struct SoakStop {
+    // stays in the current realm if none
+    pub realm: Option<String>,
+    pub parcel: IVec2,
+}
+
+// `x,y` or `realm@x,y`, separated by `;`
+#[derive(Clone, Debug)]
+pub struct SoakRoute(pub Vec<SoakStop>);
+
+impl FromStr for SoakRoute {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let stops = value
+            .split(';')
+            .map(|stop| {
+                let (realm, parcel) = match stop.rsplit_once('@') {
+                    Some((realm, parcel)) => (Some(realm.to_owned()), parcel),
+                    None => (None, stop),
+                };
+                Ok(SoakStop {
+                    realm,
+                    parcel: IVec2Arg::from_str(parcel)?.0,
+                })
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+        if stops.is_empty() {
+            anyhow::bail!("soak route must contain at least one parcel");
+        }
+        Ok(Self(stops))
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct SoakConfig {
+    pub route: SoakRoute,
+    pub duration_secs: f32,
+    // time to stay at each stop once it has loaded
+    pub dwell_secs: f32,
+    // report is written to `{output}.json` and `{output}.csv`
+    pub output: PathBuf,
+}
+
+pub struct SoakPlugin;
+
+impl Plugin for SoakPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoakState>();
+        app.add_systems(Update, run_soak);
+    }
+}
+
+const METRICS: [&str; 10] = [
+    "rss_mb",
+    "entities",
+    "live_scenes",
+    "images",
+    "meshes",
+    "materials",
+    "gltfs",
+    "mesh_handles",
+    "material_handles",
+    "scene_handles",
+];
+
+struct CycleSample {
+    time: f32,
+    values: [f64; METRICS.len()],
+}
+
+#[derive(Default)]
+enum StopPhase {
+    #[default]
+    Start,
+    ChangingRealm {
+        since: f32,
+        previous: String,
+    },
+    Loading {
+        since: f32,
+    },
+    Dwelling {
+        until: f32,
+    },
+}
+
+#[derive(Resource, Default)]
+struct SoakState {
+    start: Option<f32>,
+    stop: usize,
+    phase: StopPhase,
+    realm: Option<String>,
+    samples: Vec<CycleSample>,
+    flagged: Vec<&'static str>,
+    done: bool,
+}
+
+#[derive(SystemParam)]
+struct SoakMetrics<'w, 's> {
+    entities: Query<'w, 's, Entity>,
+    live_scenes: Res<'w, LiveScenes>,
+    images: Res<'w, Assets<Image>>,
+    meshes: Res<'w, Assets<Mesh>>,
+    standard_materials: Res<'w, Assets<StandardMaterial>>,
+    scene_materials: Res<'w, Assets<SceneMaterial>>,
+    gltfs: Res<'w, Assets<Gltf>>,
+    mesh_handles: Query<'w, 's, (), With<Handle<Mesh>>>,
+    material_handles:
+        Query<'w, 's, (), Or<(With<Handle<StandardMaterial>>, With<Handle<SceneMaterial>>)>>,
+    scene_handles: Query<'w, 's, (), With<Handle<Scene>>>,
+}
+
+impl SoakMetrics<'_, '_> {
+    fn sample(&self) -> [f64; METRICS.len()] {
+        [
+            resident_set_bytes().unwrap_or_default() as f64 / (1024.0 * 1024.0),
+            self.entities.iter().count() as f64,
+            self.live_scenes.0.len() as f64,
+            self.images.len() as f64,
+            self.meshes.len() as f64,
+            (self.standard_materials.len() + self.scene_materials.len()) as f64,
+            self.gltfs.len() as f64,
+            self.mesh_handles.iter().count() as f64,
+            self.material_handles.iter().count() as f64,
+            self.scene_handles.iter().count() as f64,
+        ]
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_soak(
+    mut state: ResMut<SoakState>,
+    soak: Res<SoakConfig>,
+    time: Res<Time<Real>>,
+    realm: Res<CurrentRealm>,
+    player: Query<&GlobalTransform, With<PrimaryUser>>,
+    pointers: Res<ScenePointers>,
+    loading: Query<(), With<SceneLoading>>,
+    metrics: SoakMetrics,
+    mut rpc: EventWriter<RpcCall>,
+    mut change_realm: EventWriter<ChangeRealmEvent>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if state.done {
+        return;
+    }
+    let state = &mut *state;
+    let now = time.elapsed_seconds();
+    let start = *state.start.get_or_insert(now);
+    let stop = &soak.route.0[state.stop];
+
+    match &state.phase {
+        StopPhase::Start => {
+            if stop.realm.is_some() && stop.realm != state.realm {
+                info!("soak: changing realm to {}", stop.realm.as_ref().unwrap());
+                state.realm = stop.realm.clone();
+                change_realm.send(ChangeRealmEvent {
+                    new_realm: stop.realm.clone().unwrap(),
+                });
+                state.phase = StopPhase::ChangingRealm {
+                    since: now,
+                    previous: realm.address.clone(),
+                };
+                return;
+            }
+            rpc.send(RpcCall::TeleportPlayer {
+                scene: None,
+                to: stop.parcel,
+                response: RpcResultSender::default(),
+            });
+            state.phase = StopPhase::Loading { since: now };
+        }
+        StopPhase::ChangingRealm { since, previous } => {
+            if realm.address != *previous || now - since > MAX_LOAD_SECS {
+                rpc.send(RpcCall::TeleportPlayer {
+                    scene: None,
+                    to: stop.parcel,
+                    response: RpcResultSender::default(),
+                });
+                state.phase = StopPhase::Loading { since: now };
+            }
+        }
+        StopPhase::Loading { since } => {
+            let Ok(player) = player.get_single() else {
+                return;
+            };
+            let parcel = vec3_to_parcel(player.translation());
+            let arrived = parcel == stop.parcel
+                && !parcel_loading(parcel, &pointers, &metrics.live_scenes, &loading)
+                && loading.is_empty();
+            if arrived || now - since > MAX_LOAD_SECS {
+                if !arrived {
+                    warn!("soak: timed out loading {}", stop.parcel);
+                }
+                state.phase = StopPhase::Dwelling {
+                    until: now + soak.dwell_secs,
+                };
+            }
+        }
+        StopPhase::Dwelling { until } => {
+            if now < *until {
+                return;
+            }
+            state.phase = StopPhase::Start;
+            state.stop = (state.stop + 1) % soak.route.0.len();
+            if state.stop != 0 {
+                return;
+            }
+
+            // cycle complete
+            state.samples.push(CycleSample {
+                time: now - start,
+                values: metrics.sample(),
+            });
+            info!("soak: cycle {} complete", state.samples.len());
+            for (ix, metric) in METRICS.iter().enumerate() {
+                if !state.flagged.contains(metric) && growing(&state.samples, ix) {
+                    warn!("soak: {metric} grew for {LEAK_CYCLES} cycles in a row, possible leak");
+                    state.flagged.push(*metric);
+                }
+            }
+            if let Err(e) = write_report(&soak, &state.samples, &state.flagged) {
+                error!("failed to write soak report: {e}");
+            }
+
+            if now - start >= soak.duration_secs {
+                info!("soak complete");
+                state.done = true;
+                exit.send(if state.flagged.is_empty() {
+                    AppExit::Success
+                } else {
+                    AppExit::error()
+                });
+            }
+        }
+    }
+}
+
+// strictly increasing over the last `LEAK_CYCLES` cycles, after warmup
+fn growing(samples: &[CycleSample], metric: usize) -> bool {
+    let counted = samples.get(1..).unwrap_or_default();
+    counted.len() > LEAK_CYCLES
+        && counted[counted.len() - LEAK_CYCLES - 1..]
+            .windows(2)
+            .all(|pair| pair[1].values[metric] > pair[0].values[metric])
+}
+
+fn write_report(
+    soak: &SoakConfig,
+    samples: &[CycleSample],
+    flagged: &[&str],
+) -> Result<(), anyhow::Error> {
+    let growth = METRICS
+        .iter()
+        .enumerate()
+        .map(|(ix, metric)| {
+            let first = samples.get(1).or(samples.first());
+            let last = samples.last();
+            let growth = first
+                .zip(last)
+                .map_or(0.0, |(first, last)| last.values[ix] - first.values[ix]);
+            (metric.to_string(), serde_json::json!(growth))
+        })
+        .collect::<serde_json::Map<_, _>>();
+
+    let summary = serde_json::json!({
+        "route": soak.route.0.iter().map(|stop| serde_json::json!({
+            "realm": stop.realm,
+            "parcel": [stop.parcel.x, stop.parcel.y],
+        })).collect::<Vec<_>>(),
+        "dwell_secs": soak.dwell_secs,
+        "cycles": samples.len(),
+        "duration_secs": samples.last().map_or(0.0, |s| s.time),
+        "growth_after_warmup": growth,
+        "flagged": flagged,
+    });
+    let json_path = soak.output.with_extension("json");
+    std::fs::write(&json_path, serde_json::to_string_pretty(&summary)?)?;
+
+    let mut csv = format!("cycle,time,{}\n", METRICS.join(","));
+    for (ix, sample) in samples.iter().enumerate() {
+        let values = sample.values.iter().map(|v| format!("{v:.1}"));
+        csv.push_str(&format!(
+            "{ix},{:.1},{}\n",
+            sample.time,
+            values.collect::<Vec<_>>().join(",")
+        ));
+    }
+    std::fs::write(soak.output.with_extension("csv"), csv)?;
+    Ok(())
+}
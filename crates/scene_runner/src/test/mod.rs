@@ -25,33 +25,46 @@ use ui_core::{scrollable::ScrollTargetEvent, stretch_uvs_image::StretchUvMateria
 use visuals::SceneGlobalLight;
 
 use crate::{
+    crdt_snapshot::crdt_snapshot_string,
     initialize_scene::{PointerResult, ScenePointers},
-    permissions::PermissionManager,
+    permissions::{Permission, PermissionManager, PermissionResponder, PermissionScope},
     process_scene_entity_lifecycle,
     update_world::{
-        transform_and_parent::process_transform_and_parent_updates, CrdtStateComponent,
+        transform_and_parent::process_transform_and_parent_updates, CrdtExtractors,
+        CrdtStateComponent,
     },
     RendererSceneContext, SceneEntity, SceneLoopLabel, SceneLoopSchedule, SceneRunnerPlugin,
     SceneUpdates,
 };
 use common::{
+    profile::SerializedProfile,
     rpc::RpcCall,
     structs::{
-        AppConfig, CursorLocks, GraphicsSettings, PrimaryCamera, PrimaryPlayerRes,
-        SceneLoadDistance, ToolTips,
+        AppConfig, CursorLocks, GraphicsSettings, PermissionType, PermissionValue, PrimaryCamera,
+        PrimaryPlayerRes, SceneLoadDistance, ToolTips,
     },
+    util::AsH160,
+};
+use comms::{
+    global_crdt::{ChatEvent, ForeignPlayer},
+    mock_room::MockTransport,
+    preview::PreviewMode,
+    profile::UserProfile,
+    CommsPlugin,
 };
-use comms::{preview::PreviewMode, CommsPlugin};
 use console::{self, ConsolePlugin};
 use dcl::{
     crdt::lww::CrdtLWWState,
     interface::{CrdtStore, CrdtType},
 };
 use dcl_component::{
-    transform_and_parent::DclTransformAndParent, DclReader, DclWriter, SceneComponentId,
-    SceneCrdtTimestamp, SceneEntityId,
+    proto_components::kernel::comms::rfc4, transform_and_parent::DclTransformAndParent, DclReader,
+    DclWriter, SceneComponentId, SceneCrdtTimestamp, SceneEntityId,
+};
+use input_manager::{
+    gestures::InputGestures, toggles::ToggledActions, touch::TouchInput,
+    virtual_actions::VirtualActions, AcceptInput, InputMap,
 };
-use input_manager::{AcceptInput, InputMap};
 use ipfs::{IpfsIoPlugin, IpfsResource, ServerAbout, ServerConfiguration};
 use wallet::WalletPlugin;
 
@@ -131,6 +144,10 @@ fn init_test_app(entity_json: &str) -> App {
     app.init_resource::<PermissionManager>();
     app.init_resource::<InputMap>();
     app.init_resource::<AcceptInput>();
+    app.init_resource::<TouchInput>();
+    app.init_resource::<InputGestures>();
+    app.init_resource::<ToggledActions>();
+    app.init_resource::<VirtualActions>();
     app.init_resource::<ToolTips>();
     app.init_resource::<SceneGlobalLight>();
     app.add_event::<RpcCall>();
@@ -610,3 +627,249 @@ fn test_scene_ray() {
     assert_eq!(results[0].1, 0.0);
     assert!((results[1].1 - f32::sqrt(8.0 * 8.0 * 2.0)).abs() < 0.01);
 }
+
+#[test]
+fn mock_comms_player() {
+    let mut app = init_test_app("empty_scene.entity_definition");
+    let transport = MockTransport::spawn(app.world_mut());
+    let mut chat_reader = app.world().resource::<Events<ChatEvent>>().get_reader();
+
+    let address = "0x0000000000000000000000000000000000000001"
+        .as_h160()
+        .unwrap();
+    {
+        let mut mock = app.world_mut().get_mut::<MockTransport>(transport).unwrap();
+        mock.add_player(
+            address,
+            SerializedProfile {
+                name: "mock player".to_owned(),
+                eth_address: format!("{address:#x}"),
+                version: 3,
+                ..Default::default()
+            },
+        );
+        mock.move_player(address, Transform::from_xyz(8.0, 0.0, -8.0));
+        mock.chat(address, "hello", 0.0);
+    }
+
+    let mut chat = Vec::default();
+    for _ in 0..10 {
+        app.update();
+        let events = app.world().resource::<Events<ChatEvent>>();
+        chat.extend(chat_reader.read(events).map(|ev| ev.message.clone()));
+    }
+
+    let (player, profile) = app
+        .world_mut()
+        .query::<(&ForeignPlayer, Option<&UserProfile>)>()
+        .single(app.world());
+    assert_eq!(player.address, address);
+    assert_eq!(player.profile_version, 3);
+    assert_eq!(
+        profile.map(|profile| profile.content.name.as_str()),
+        Some("mock player")
+    );
+    assert_eq!(chat, vec!["hello".to_owned()]);
+
+    // the profile was requested through the transport
+    let mock = app.world().get::<MockTransport>(transport).unwrap();
+    assert!(mock.sent.iter().any(|packet| matches!(
+        packet.message,
+        Some(rfc4::packet::Message::ProfileRequest(_))
+    )));
+}
+
+const ALL_PERMISSIONS: [PermissionType; 15] = [
+    PermissionType::MovePlayer,
+    PermissionType::ForceCamera,
+    PermissionType::PlayEmote,
+    PermissionType::SetLocomotion,
+    PermissionType::HideAvatars,
+    PermissionType::DisableVoice,
+    PermissionType::Teleport,
+    PermissionType::ChangeRealm,
+    PermissionType::SpawnPortable,
+    PermissionType::KillPortables,
+    PermissionType::Web3,
+    PermissionType::Fetch,
+    PermissionType::Websocket,
+    PermissionType::OpenUrl,
+    PermissionType::ShowStreams,
+];
+
+// permission checks queued by the test, and what the scene would see
+#[derive(Resource, Default)]
+struct PermissionChecks {
+    // type, allow out of scene
+    requests: Vec<(PermissionType, bool)>,
+    allowed: Vec<PermissionType>,
+    denied: Vec<PermissionType>,
+}
+
+fn check_permissions(
+    mut perm: Permission<PermissionType>,
+    mut checks: ResMut<PermissionChecks>,
+    scene: Query<Entity, With<RendererSceneContext>>,
+) {
+    let scene = scene.single();
+    for (ty, allow_out_of_scene) in std::mem::take(&mut checks.requests) {
+        perm.check(ty, scene, ty, None, allow_out_of_scene);
+    }
+    for ty in ALL_PERMISSIONS {
+        checks.allowed.extend(perm.drain_success(ty));
+        checks.denied.extend(perm.drain_fail(ty));
+    }
+}
+
+#[test]
+fn permission_flows() {
+    let mut app = init_test_app("empty_scene.entity_definition");
+    app.init_resource::<PermissionChecks>();
+    app.init_resource::<PermissionResponder>();
+    app.add_systems(Update, check_permissions);
+
+    let run = |app: &mut App, requests: Vec<(PermissionType, bool)>| {
+        {
+            let mut checks = app.world_mut().resource_mut::<PermissionChecks>();
+            checks.requests = requests;
+            checks.allowed.clear();
+            checks.denied.clear();
+        }
+        for _ in 0..5 {
+            app.update();
+        }
+        let checks = app.world().resource::<PermissionChecks>();
+        (checks.allowed.clone(), checks.denied.clone())
+    };
+
+    // every type, answered alternately where the default is to ask
+    let mut expect_allowed = Vec::default();
+    let mut expect_denied = Vec::default();
+    let mut expect_prompted = Vec::default();
+    {
+        let mut responder = app.world_mut().resource_mut::<PermissionResponder>();
+        for (ix, ty) in ALL_PERMISSIONS.into_iter().enumerate() {
+            let allow = match AppConfig::default_permission(ty) {
+                PermissionValue::Ask => {
+                    expect_prompted.push(ty);
+                    ix % 2 == 0
+                }
+                value => value == PermissionValue::Allow,
+            };
+            if allow {
+                responder.allow(ty);
+                expect_allowed.push(ty);
+            } else {
+                responder.deny(ty);
+                expect_denied.push(ty);
+            }
+        }
+    }
+    let (allowed, denied) = run(
+        &mut app,
+        ALL_PERMISSIONS.into_iter().map(|ty| (ty, true)).collect(),
+    );
+    assert_eq!(allowed, expect_allowed);
+    assert_eq!(denied, expect_denied);
+    let prompted = app
+        .world()
+        .resource::<PermissionResponder>()
+        .answered
+        .iter()
+        .map(|(ty, ..)| *ty)
+        .collect::<Vec<_>>();
+    assert_eq!(prompted, expect_prompted);
+
+    // the player is not in the scene, so even allowed-by-default types ask
+    app.world_mut()
+        .resource_mut::<PermissionResponder>()
+        .deny(PermissionType::MovePlayer);
+    let (allowed, denied) = run(&mut app, vec![(PermissionType::MovePlayer, false)]);
+    assert!(allowed.is_empty());
+    assert_eq!(denied, vec![PermissionType::MovePlayer]);
+    assert_eq!(
+        app.world()
+            .resource::<PermissionResponder>()
+            .answered_count(PermissionType::MovePlayer),
+        1
+    );
+
+    // a remembered answer is stored and not asked for again
+    app.world_mut()
+        .resource_mut::<PermissionResponder>()
+        .respond(PermissionType::Teleport, true, PermissionScope::Scene);
+    let before = app
+        .world()
+        .resource::<PermissionResponder>()
+        .answered_count(PermissionType::Teleport);
+    for _ in 0..2 {
+        let (allowed, _) = run(&mut app, vec![(PermissionType::Teleport, true)]);
+        assert_eq!(allowed, vec![PermissionType::Teleport]);
+    }
+    assert_eq!(
+        app.world()
+            .resource::<PermissionResponder>()
+            .answered_count(PermissionType::Teleport),
+        before + 1
+    );
+    let hash = app
+        .world_mut()
+        .query::<&RendererSceneContext>()
+        .single(app.world())
+        .hash
+        .clone();
+    assert_eq!(
+        app.world().resource::<AppConfig>().scene_permissions[&hash][&PermissionType::Teleport],
+        PermissionValue::Allow
+    );
+
+    // without a response the request stays pending
+    app.world_mut()
+        .resource_mut::<PermissionResponder>()
+        .clear(PermissionType::Web3);
+    let (allowed, denied) = run(&mut app, vec![(PermissionType::Web3, true)]);
+    assert!(allowed.is_empty() && denied.is_empty());
+    assert_eq!(app.world().resource::<PermissionManager>().pending.len(), 1);
+}
+
+#[test]
+fn crdt_snapshot_lww() {
+    let app = init_test_app("empty_scene.entity_definition");
+    let interfaces = app.world().resource::<CrdtExtractors>();
+
+    // entity, timestamp, parent (none for a delete)
+    let messages = [
+        // later timestamp wins
+        (512, 1, Some(0)),
+        (512, 2, Some(513)),
+        // a put beats a delete at the same timestamp
+        (513, 3, Some(0)),
+        (513, 3, None),
+        // a later delete beats a put
+        (514, 5, None),
+        (514, 4, Some(0)),
+        // equal timestamps resolve to the greater data
+        (515, 6, Some(1)),
+        (515, 6, Some(2)),
+    ];
+
+    // the result must not depend on arrival order
+    for messages in messages.iter().permutations(messages.len()).step_by(97) {
+        let mut crdt_store = CrdtStore::default();
+        for (entity, timestamp, parent) in messages {
+            let data = parent.map(make_reparent_buffer);
+            crdt_store.try_update(
+                SceneComponentId::TRANSFORM,
+                CrdtType::LWW_ENT,
+                SceneEntityId {
+                    id: *entity,
+                    generation: 0,
+                },
+                SceneCrdtTimestamp(*timestamp),
+                data.as_ref().map(|data| DclReader::new(data)).as_mut(),
+            );
+        }
+        let snapshot = crdt_snapshot_string(&crdt_store, interfaces);
+        check_or_write!(snapshot, "expected/crdt_snapshot_lww.json");
+    }
+}
@@ -0,0 +1,132 @@
+// `/trace start|stop [file]` captures a performance trace. marker schedules between the main
+// schedules time each of them per frame; the scene loop and per-scene updates add their own spans.
+
+use std::time::{Instant, SystemTime};
+
+use bevy::{app::MainScheduleOrder, ecs::schedule::ScheduleLabel, prelude::*};
+use bevy_console::ConsoleCommand;
+use common::trace::{record_span, start_trace, stop_trace, trace_active, MAIN_TRACK};
+use console::DoAddConsoleCommand;
+
+pub struct TracePlugin;
+
+impl Plugin for TracePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command::<TraceCommand, _>(trace_command);
+    }
+
+    // after other plugins have added their main schedules
+    fn finish(&self, app: &mut App) {
+        let mut order = app.world_mut().resource_mut::<MainScheduleOrder>();
+        let names = order
+            .labels
+            .iter()
+            .map(|label| format!("{label:?}"))
+            .collect::<Vec<_>>();
+        let mut labels = Vec::default();
+        for (ix, label) in order.labels.iter().enumerate() {
+            labels.push(TraceMarker(ix).intern());
+            labels.push(*label);
+        }
+        labels.push(TraceMarker(names.len()).intern());
+        order.labels = labels;
+
+        for ix in 0..=names.len() {
+            app.add_systems(TraceMarker(ix), move |markers: ResMut<TraceMarkers>| {
+                mark(markers, ix)
+            });
+        }
+        app.insert_resource(TraceMarkers {
+            names,
+            ..Default::default()
+        });
+    }
+}
+
+// runs before main schedule `n`, or after the last one
+#[derive(ScheduleLabel, Hash, PartialEq, Eq, Clone, Copy, Debug)]
+struct TraceMarker(usize);
+
+#[derive(Resource, Default)]
+struct TraceMarkers {
+    names: Vec<String>,
+    last: Option<Instant>,
+    frame_start: Option<Instant>,
+    frame: u32,
+}
+
+fn mark(mut markers: ResMut<TraceMarkers>, ix: usize) {
+    if !trace_active() {
+        markers.last = None;
+        markers.frame_start = None;
+        return;
+    }
+
+    let now = Instant::now();
+    if let Some(last) = markers.last {
+        let name = match ix.checked_sub(1) {
+            Some(prev) => markers.names[prev].clone(),
+            // render extraction and waiting for the next frame
+            None => "between frames".to_owned(),
+        };
+        record_span(MAIN_TRACK, "schedule", name, last, now);
+    }
+    if ix == 0 {
+        markers.frame_start = Some(now);
+    } else if ix == markers.names.len() {
+        if let Some(start) = markers.frame_start.take() {
+            record_span(
+                MAIN_TRACK,
+                "frame",
+                format!("frame {}", markers.frame),
+                start,
+                now,
+            );
+        }
+        markers.frame = markers.frame.wrapping_add(1);
+    }
+    markers.last = Some(now);
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum TraceAction {
+    Start,
+    Stop,
+}
+
+/// capture a performance trace in chrome trace format, e.g. `/trace start` then `/trace stop`
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/trace")]
+struct TraceCommand {
+    action: TraceAction,
+    // defaults to `trace-<timestamp>.json`
+    path: Option<String>,
+}
+
+fn trace_command(mut input: ConsoleCommand<TraceCommand>) {
+    if let Some(Ok(command)) = input.take() {
+        match command.action {
+            TraceAction::Start => {
+                start_trace();
+                input.reply_ok("trace started");
+            }
+            TraceAction::Stop => {
+                let Some((json, spans)) = stop_trace() else {
+                    input.reply_failed("no trace running");
+                    return;
+                };
+                let path = command.path.unwrap_or_else(|| {
+                    let secs = SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs();
+                    format!("trace-{secs}.json")
+                });
+                match std::fs::write(&path, json) {
+                    Ok(()) => input.reply_ok(format!("{spans} spans written to {path}")),
+                    Err(e) => input.reply_failed(format!("failed to write {path}: {e}")),
+                }
+            }
+        }
+    }
+}
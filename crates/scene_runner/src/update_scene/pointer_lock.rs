@@ -3,7 +3,7 @@ use bevy::{
     prelude::*,
     window::{CursorGrabMode, PrimaryWindow},
 };
-use common::structs::PrimaryCamera;
+use common::{structs::PrimaryCamera, util::window_to_world_ray};
 
 use crate::{renderer_context::RendererSceneContext, SceneSets};
 use dcl::interface::CrdtType;
@@ -64,7 +64,7 @@ fn update_pointer_lock(
     }
 
     let ray = screen_coordinates
-        .and_then(|coords| camera.viewport_to_world(camera_position, coords))
+        .and_then(|coords| window_to_world_ray(camera, camera_position, window, coords))
         .map(|ray| Vector3::world_vec_from_vec3(&ray.direction));
 
     for (entity, mut context, maybe_pointer_delta) in scenes.iter_mut() {
@@ -22,6 +22,7 @@ use crate::{
 use common::{
     dynamics::PLAYER_COLLIDER_RADIUS,
     structs::{CursorLocks, PrimaryCamera},
+    util::window_to_world_ray,
 };
 use dcl::interface::CrdtType;
 use dcl_component::{
@@ -35,7 +36,7 @@ use dcl_component::{
     },
     SceneComponentId, SceneEntityId,
 };
-use input_manager::{AcceptInput, InputManager};
+use input_manager::{touch::TouchInput, AcceptInput, InputManager};
 
 pub struct PointerResultPlugin;
 
@@ -105,6 +106,7 @@ fn update_pointer_target(
     containing_scenes: ContainingScene,
     mut scenes: Query<(Entity, &mut RendererSceneContext, &mut SceneColliderData)>,
     mut world_target: ResMut<WorldPointerTarget>,
+    touch: Res<TouchInput>,
 ) {
     let Ok((camera, camera_position)) = camera.get_single() else {
         // can't do much without a camera
@@ -119,7 +121,9 @@ fn update_pointer_target(
     let Ok(window) = windows.get_single() else {
         return;
     };
-    let cursor_position = if window.cursor.grab_mode == bevy::window::CursorGrabMode::Locked {
+    let cursor_position = if let Some(tap_position) = touch.pointer_position {
+        tap_position
+    } else if window.cursor.grab_mode == bevy::window::CursorGrabMode::Locked {
         // if pointer locked, just middle
         Vec2::new(window.width(), window.height()) / 2.0
     } else {
@@ -130,7 +134,7 @@ fn update_pointer_target(
         cursor_position
     };
 
-    let Some(ray) = camera.viewport_to_world(camera_position, cursor_position) else {
+    let Some(ray) = window_to_world_ray(camera, camera_position, window, cursor_position) else {
         error!("no ray, not sure why that would happen");
         return;
     };
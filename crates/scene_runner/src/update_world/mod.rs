@@ -20,9 +20,9 @@ use self::{
     billboard::BillboardPlugin, camera_mode_area::CameraModeAreaPlugin,
     gltf_container::GltfDefinitionPlugin, material::MaterialDefinitionPlugin,
     mesh_collider::MeshColliderPlugin, mesh_renderer::MeshDefinitionPlugin,
-    pointer_events::PointerEventsPlugin, raycast::RaycastPlugin, scene_ui::SceneUiPlugin,
-    text_shape::TextShapePlugin, transform_and_parent::TransformAndParentPlugin,
-    visibility::VisibilityComponentPlugin,
+    particles::ParticlePlugin, pointer_events::PointerEventsPlugin, raycast::RaycastPlugin,
+    scene_ui::SceneUiPlugin, text_shape::TextShapePlugin, texture_budget::TextureBudgetPlugin,
+    transform_and_parent::TransformAndParentPlugin, visibility::VisibilityComponentPlugin,
 };
 
 use super::{DeletedSceneEntities, RendererSceneContext, SceneLoopSchedule, SceneLoopSets};
@@ -36,10 +36,14 @@ pub mod lights;
 pub mod material;
 pub mod mesh_collider;
 pub mod mesh_renderer;
+pub mod particles;
 pub mod pointer_events;
+pub mod post_processing;
 pub mod raycast;
 pub mod scene_ui;
+pub mod skybox;
 pub mod text_shape;
+pub mod texture_budget;
 pub mod transform_and_parent;
 pub mod visibility;
 
@@ -83,14 +87,24 @@ pub trait CrdtInterface {
         type_map: &mut CrdtStore,
         commands: &mut EntityCommands,
     );
+
+    // readable form of a serialized value, for crdt snapshots
+    fn describe(&self, data: &[u8]) -> String;
+}
+
+fn describe_value<T: FromDclReader + std::fmt::Debug>(data: &[u8]) -> String {
+    match T::from_reader(&mut DclReader::new(data)) {
+        Ok(value) => format!("{value:?}"),
+        Err(_) => format!("<invalid, {} bytes>", data.len()),
+    }
 }
 
-pub struct CrdtLWWInterface<T: FromDclReader> {
+pub struct CrdtLWWInterface<T: FromDclReader + std::fmt::Debug> {
     position: ComponentPosition,
     _marker: PhantomData<T>,
 }
 
-impl<T: FromDclReader> CrdtInterface for CrdtLWWInterface<T> {
+impl<T: FromDclReader + std::fmt::Debug> CrdtInterface for CrdtLWWInterface<T> {
     fn crdt_type(&self) -> CrdtType {
         CrdtType::LWW(self.position)
     }
@@ -106,14 +120,18 @@ impl<T: FromDclReader> CrdtInterface for CrdtLWWInterface<T> {
             .remove(&component_id)
             .map(|state| commands.try_insert(CrdtStateComponent::<CrdtLWWState, T>::new(state)));
     }
+
+    fn describe(&self, data: &[u8]) -> String {
+        describe_value::<T>(data)
+    }
 }
 
-pub struct CrdtGOInterface<T: FromDclReader> {
+pub struct CrdtGOInterface<T: FromDclReader + std::fmt::Debug> {
     position: ComponentPosition,
     _marker: PhantomData<T>,
 }
 
-impl<T: FromDclReader> CrdtInterface for CrdtGOInterface<T> {
+impl<T: FromDclReader + std::fmt::Debug> CrdtInterface for CrdtGOInterface<T> {
     fn crdt_type(&self) -> CrdtType {
         CrdtType::GO(self.position)
     }
@@ -129,6 +147,10 @@ impl<T: FromDclReader> CrdtInterface for CrdtGOInterface<T> {
             .remove(&component_id)
             .map(|state| commands.try_insert(CrdtStateComponent::<CrdtGOState, T>::new(state)));
     }
+
+    fn describe(&self, data: &[u8]) -> String {
+        describe_value::<T>(data)
+    }
 }
 
 #[derive(Resource, Default)]
@@ -168,6 +190,8 @@ impl Plugin for SceneOutputPlugin {
         app.add_plugins(CameraModeAreaPlugin);
         app.add_plugins(VisibilityComponentPlugin);
         app.add_plugins(AvatarModifierAreaPlugin);
+        app.add_plugins(ParticlePlugin);
+        app.add_plugins(TextureBudgetPlugin);
 
         app.init_resource::<TrackComponents>();
 
@@ -185,7 +209,7 @@ impl Plugin for SceneOutputPlugin {
 
 // a helper to automatically apply engine component updates
 pub trait AddCrdtInterfaceExt {
-    fn add_crdt_lww_interface<D: FromDclReader>(
+    fn add_crdt_lww_interface<D: FromDclReader + std::fmt::Debug>(
         &mut self,
         id: SceneComponentId,
         position: ComponentPosition,
@@ -209,7 +233,7 @@ pub trait AddCrdtInterfaceExt {
 }
 
 impl AddCrdtInterfaceExt for App {
-    fn add_crdt_lww_interface<D: FromDclReader>(
+    fn add_crdt_lww_interface<D: FromDclReader + std::fmt::Debug>(
         &mut self,
         id: SceneComponentId,
         position: ComponentPosition,
@@ -0,0 +1,344 @@
+// gpu particle emitters. particles are stateless: each quad in the emitter mesh derives its
+// spawn time, position, size, color and sheet frame from its index and the current time in
+// the vertex shader, so no per-particle data is updated on the cpu.
+
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey, NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    render::{
+        mesh::{Indices, MeshVertexBufferLayoutRef, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            AsBindGroup, RenderPipelineDescriptor, ShaderRef, ShaderType,
+            SpecializedMeshPipelineError,
+        },
+        view::NoFrustumCulling,
+    },
+    utils::HashMap,
+};
+use common::{sets::SceneSets, structs::AppConfig, util::TryPushChildrenEx};
+use dcl::interface::ComponentPosition;
+use dcl_component::{
+    proto_components::sdk::components::{ParticleBlendMode, PbParticleSystem},
+    SceneComponentId,
+};
+use ipfs::IpfsAssetServer;
+
+use crate::{renderer_context::RendererSceneContext, ContainerEntity};
+
+use super::AddCrdtInterfaceExt;
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_crdt_lww_component::<PbParticleSystem, ParticleSystem>(
+            SceneComponentId::PARTICLE_SYSTEM,
+            ComponentPosition::EntityOnly,
+        );
+        app.add_plugins(MaterialPlugin::<ParticleMaterial>::default());
+        app.add_systems(
+            Update,
+            (update_particle_systems, rebase_particle_times)
+                .chain()
+                .in_set(SceneSets::PostLoop),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct ParticleSystem(pub PbParticleSystem);
+
+impl From<PbParticleSystem> for ParticleSystem {
+    fn from(value: PbParticleSystem) -> Self {
+        Self(value)
+    }
+}
+
+impl ParticleSystem {
+    fn requested_count(&self) -> u32 {
+        let rate = self.0.rate.unwrap_or(10.0).max(0.0);
+        let lifetime = self.0.lifetime.unwrap_or(2.0).max(0.0);
+        ((rate * lifetime).ceil() as u32).min(self.0.max_particles.unwrap_or(1000))
+    }
+}
+
+// the child entity rendering an emitter's particles
+#[derive(Component)]
+pub struct ParticleEmitter {
+    child: Entity,
+    count: u32,
+    material: Handle<ParticleMaterial>,
+}
+
+#[derive(ShaderType, Clone, Copy, Debug, Default)]
+pub struct ParticleUniform {
+    pub velocity: Vec3,
+    pub rate: f32,
+    pub acceleration: Vec3,
+    pub lifetime: f32,
+    pub color_start: Vec4,
+    pub color_end: Vec4,
+    pub size_start: f32,
+    pub size_end: f32,
+    pub velocity_spread: f32,
+    pub velocity_end_scale: f32,
+    pub emitter_radius: f32,
+    pub count: u32,
+    pub sheet_columns: u32,
+    pub sheet_rows: u32,
+    pub sheet_fps: f32,
+    // particles spawned outside this range are not drawn. in shader time, which wraps, see
+    // `rebase_particle_times`
+    pub start_time: f32,
+    pub stop_time: f32,
+    pub additive: u32,
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct ParticleMaterial {
+    #[uniform(0)]
+    pub data: ParticleUniform,
+    #[texture(1)]
+    #[sampler(2)]
+    pub texture: Option<Handle<Image>>,
+    pub alpha_mode: AlphaMode,
+}
+
+impl Material for ParticleMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/particles.wgsl".into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        "shaders/particles.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn specialize(
+        _: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        _: &MeshVertexBufferLayoutRef,
+        _: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.primitive.cull_mode = None;
+        if let Some(depth_stencil) = descriptor.depth_stencil.as_mut() {
+            depth_stencil.depth_write_enabled = false;
+        }
+        Ok(())
+    }
+}
+
+// `count` unit quads, with the particle index stored in the z coordinate
+fn particle_mesh(count: u32) -> Mesh {
+    let corners = [[-0.5, -0.5], [0.5, -0.5], [0.5, 0.5], [-0.5, 0.5]];
+    let uvs = [[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]];
+
+    let mut positions = Vec::with_capacity(count as usize * 4);
+    let mut tex_coords = Vec::with_capacity(count as usize * 4);
+    let mut indices = Vec::with_capacity(count as usize * 6);
+    for ix in 0..count {
+        for (corner, uv) in corners.iter().zip(uvs.iter()) {
+            positions.push([corner[0], corner[1], ix as f32]);
+            tex_coords.push(*uv);
+        }
+        let base = ix * 4;
+        indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    let vertex_count = positions.len();
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; vertex_count])
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, tex_coords)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, vec![[1.0; 4]; vertex_count])
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_particle_systems(
+    mut commands: Commands,
+    emitters: Query<(
+        Entity,
+        &ContainerEntity,
+        Ref<ParticleSystem>,
+        Option<&ParticleEmitter>,
+    )>,
+    mut removed: RemovedComponents<ParticleSystem>,
+    existing: Query<&ParticleEmitter>,
+    scenes: Query<&RendererSceneContext>,
+    config: Res<AppConfig>,
+    ipfas: IpfsAssetServer,
+    mut materials: ResMut<Assets<ParticleMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    time: Res<Time>,
+) {
+    for ent in removed.read() {
+        if let Ok(emitter) = existing.get(ent) {
+            if let Some(commands) = commands.get_entity(emitter.child) {
+                commands.despawn_recursive();
+            }
+            if let Some(mut commands) = commands.get_entity(ent) {
+                commands.remove::<ParticleEmitter>();
+            }
+        }
+    }
+
+    // allocate each scene's budget to its emitters in a stable order
+    let mut by_scene = HashMap::<Entity, Vec<_>>::default();
+    for emitter in emitters.iter() {
+        by_scene.entry(emitter.1.root).or_default().push(emitter);
+    }
+
+    let now = time.elapsed_seconds_wrapped();
+    for (root, mut scene_emitters) in by_scene {
+        scene_emitters.sort_by_key(|(ent, ..)| *ent);
+        let mut remaining = config.max_scene_particles as u32;
+        let Ok(ctx) = scenes.get(root) else {
+            continue;
+        };
+
+        for (ent, _, system, maybe_emitter) in scene_emitters {
+            let count = system.requested_count().min(remaining);
+            remaining -= count;
+
+            let unchanged = maybe_emitter.is_some_and(|emitter| emitter.count == count);
+            if unchanged && !system.is_changed() {
+                continue;
+            }
+
+            let pb = &system.0;
+            let active = pb.active.unwrap_or(true);
+            let prev = maybe_emitter
+                .and_then(|emitter| materials.get(emitter.material.id()))
+                .map(|material| material.data);
+            let start_time = prev.map(|data| data.start_time).unwrap_or(now);
+            let stop_time = match (active, prev) {
+                (true, _) => f32::MAX,
+                (false, Some(prev)) if prev.stop_time != f32::MAX => prev.stop_time,
+                (false, _) => now,
+            };
+            let additive = pb.blend_mode == Some(ParticleBlendMode::PbmAdditive as i32);
+            let color_start = pb.color_start.map(Color::from).unwrap_or(Color::WHITE);
+            let size_start = pb.size_start.unwrap_or(0.1);
+
+            let material = ParticleMaterial {
+                data: ParticleUniform {
+                    velocity: pb
+                        .velocity
+                        .as_ref()
+                        .map(|v| v.abs_vec_to_vec3())
+                        .unwrap_or(Vec3::Y),
+                    rate: pb.rate.unwrap_or(10.0).max(0.001),
+                    acceleration: pb
+                        .acceleration
+                        .as_ref()
+                        .map(|v| v.abs_vec_to_vec3())
+                        .unwrap_or(Vec3::ZERO),
+                    lifetime: pb.lifetime.unwrap_or(2.0).max(0.001),
+                    color_start: color_start.to_linear().to_vec4(),
+                    color_end: pb
+                        .color_end
+                        .map(Color::from)
+                        .unwrap_or(color_start)
+                        .to_linear()
+                        .to_vec4(),
+                    size_start,
+                    size_end: pb.size_end.unwrap_or(size_start),
+                    velocity_spread: pb.velocity_spread.unwrap_or(0.0),
+                    velocity_end_scale: pb.velocity_end_scale.unwrap_or(1.0),
+                    emitter_radius: pb.emitter_radius.unwrap_or(0.0),
+                    count,
+                    sheet_columns: pb.sheet_columns.unwrap_or(1).max(1),
+                    sheet_rows: pb.sheet_rows.unwrap_or(1).max(1),
+                    sheet_fps: pb.sheet_fps.unwrap_or(0.0),
+                    start_time,
+                    stop_time,
+                    additive: additive as u32,
+                },
+                texture: pb.texture.as_ref().and_then(|texture| {
+                    ipfas
+                        .load_content_file::<Image>(texture, &ctx.hash)
+                        .map_err(|e| warn!("failed to load particle texture `{texture}`: {e}"))
+                        .ok()
+                }),
+                alpha_mode: if additive {
+                    AlphaMode::Add
+                } else {
+                    AlphaMode::Blend
+                },
+            };
+
+            if unchanged {
+                let emitter = maybe_emitter.unwrap();
+                if let Some(existing) = materials.get_mut(emitter.material.id()) {
+                    *existing = material;
+                }
+                continue;
+            }
+
+            // particle count changed, rebuild the child
+            if let Some(emitter) = maybe_emitter {
+                if let Some(commands) = commands.get_entity(emitter.child) {
+                    commands.despawn_recursive();
+                }
+            }
+
+            let material = materials.add(material);
+            let mesh = meshes.add(particle_mesh(count));
+            let child = commands
+                .spawn((
+                    MaterialMeshBundle {
+                        mesh,
+                        material: material.clone(),
+                        ..Default::default()
+                    },
+                    NotShadowCaster,
+                    NotShadowReceiver,
+                    NoFrustumCulling,
+                ))
+                .id();
+            commands.entity(ent).try_push_children(&[child]);
+            commands.entity(ent).try_insert(ParticleEmitter {
+                child,
+                count,
+                material,
+            });
+        }
+    }
+}
+
+// the shader time wraps (hourly by default). when it does, shift the emitters' start and stop
+// times back by the same amount so they stay in the past. anything further back than one period
+// is older than any live particle, so it is clamped there to keep the values small
+fn rebase_particle_times(
+    emitters: Query<&ParticleEmitter>,
+    mut materials: ResMut<Assets<ParticleMaterial>>,
+    time: Res<Time>,
+    mut last: Local<f32>,
+) {
+    let now = time.elapsed_seconds_wrapped();
+    let wrapped = now < *last;
+    *last = now;
+    if !wrapped {
+        return;
+    }
+
+    let period = time.wrap_period().as_secs_f32();
+    for emitter in emitters.iter() {
+        let Some(material) = materials.get_mut(emitter.material.id()) else {
+            continue;
+        };
+        let data = &mut material.data;
+        data.start_time = (data.start_time - period).max(-period);
+        if data.stop_time != f32::MAX {
+            data.stop_time = (data.stop_time - period).max(-period);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use common::{sets::SceneSets, structs::PrimaryUser};
+use dcl::interface::ComponentPosition;
+use dcl_component::{proto_components::sdk::components::PbPostProcessing, SceneComponentId};
+use ipfs::IpfsAssetServer;
+use visuals::ScenePostProcessing;
+
+use crate::{renderer_context::RendererSceneContext, ContainingScene};
+
+use super::AddCrdtInterfaceExt;
+
+pub struct PostProcessingPlugin;
+
+impl Plugin for PostProcessingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_crdt_lww_component::<PbPostProcessing, PostProcessing>(
+            SceneComponentId::POST_PROCESSING,
+            ComponentPosition::RootOnly,
+        );
+        app.add_systems(
+            Update,
+            (load_scene_color_luts, update_scene_post_processing)
+                .chain()
+                .in_set(SceneSets::PostLoop),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct PostProcessing(pub PbPostProcessing);
+
+impl From<PbPostProcessing> for PostProcessing {
+    fn from(value: PbPostProcessing) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Component)]
+pub struct SceneColorLut(pub Handle<Image>);
+
+fn load_scene_color_luts(
+    mut commands: Commands,
+    q: Query<(Entity, &RendererSceneContext, &PostProcessing), Changed<PostProcessing>>,
+    ipfas: IpfsAssetServer,
+) {
+    for (ent, ctx, post_processing) in q.iter() {
+        let maybe_lut = post_processing.0.color_lut.as_ref().and_then(|src| {
+            ipfas
+                .load_content_file::<Image>(src, &ctx.hash)
+                .map_err(|e| warn!("failed to load color lut `{src}`: {e}"))
+                .ok()
+        });
+
+        match maybe_lut {
+            Some(lut) => commands.entity(ent).try_insert(SceneColorLut(lut)),
+            None => commands.entity(ent).remove::<SceneColorLut>(),
+        };
+    }
+}
+
+fn update_scene_post_processing(
+    scenes: Query<(&PostProcessing, Option<&SceneColorLut>), With<RendererSceneContext>>,
+    mut scene_post_processing: ResMut<ScenePostProcessing>,
+    containing_scene: ContainingScene,
+    player: Query<Entity, With<PrimaryUser>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+
+    let source = containing_scene.get_controlling_scene(player, |scene| scenes.get(scene).is_ok());
+
+    let next = match source.and_then(|source| scenes.get(source).ok()) {
+        Some((post_processing, maybe_lut)) => ScenePostProcessing {
+            source,
+            bloom_intensity: post_processing.0.bloom_intensity,
+            bloom_threshold: post_processing.0.bloom_threshold,
+            color_lut: maybe_lut.map(|lut| lut.0.clone()),
+            color_lut_strength: post_processing.0.color_lut_strength,
+            fog_density: post_processing.0.fog_density,
+        },
+        None => ScenePostProcessing::default(),
+    };
+
+    scene_post_processing.set_if_neq(next);
+}
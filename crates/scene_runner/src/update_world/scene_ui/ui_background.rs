@@ -92,8 +92,12 @@ pub struct UiBackgroundMarker;
 #[derive(Component)]
 pub struct RetryBackground;
 
+// a background node showing a texture from another entity (a video or ui texture)
 #[derive(Component)]
-pub struct UiMaterialSource(Entity);
+pub struct UiMaterialSource {
+    source: Entity,
+    background: Entity,
+}
 
 pub fn set_ui_background(
     mut commands: Commands,
@@ -114,7 +118,6 @@ pub fn set_ui_background(
     mut stretch_uvs: ResMut<Assets<StretchUvMaterial>>,
     mut images: ResMut<Assets<Image>>,
     sourced: Query<(
-        Entity,
         Option<&Handle<StretchUvMaterial>>,
         Option<&Handle<Image>>,
         &UiMaterialSource,
@@ -179,6 +182,14 @@ pub fn set_ui_background(
 
             let texture_mode = match texture.tex.tex {
                 Some(texture_union::Tex::Texture(_)) => texture.mode,
+                // video frames start as a placeholder and resize once the stream loads, so
+                // slicing doesn't make sense, but they can be cropped or shown at native size
+                Some(texture_union::Tex::VideoTexture(_)) => match texture.mode {
+                    BackgroundTextureMode::NineSlices(_) => {
+                        BackgroundTextureMode::stretch_default()
+                    }
+                    mode => mode,
+                },
                 _ => BackgroundTextureMode::stretch_default(),
             };
 
@@ -245,7 +256,10 @@ pub fn set_ui_background(
                                 ..Default::default()
                             },));
                             if let Some(source) = image.source_entity {
-                                inner.insert(UiMaterialSource(source));
+                                inner.insert(UiMaterialSource {
+                                    source,
+                                    background: ent,
+                                });
                             }
                         })
                         .id(),
@@ -296,7 +310,10 @@ pub fn set_ui_background(
                                     ..Default::default()
                                 });
                                 if let Some(source) = image.source_entity {
-                                    inner.insert(UiMaterialSource(source));
+                                    inner.insert(UiMaterialSource {
+                                        source,
+                                        background: ent,
+                                    });
                                 }
                                 c.spacer();
                             });
@@ -314,9 +331,12 @@ pub fn set_ui_background(
         }
     }
 
-    for (ent, maybe_stretch, maybe_image, source) in sourced.iter() {
-        if commands.get_entity(source.0).is_none() {
-            commands.entity(ent).insert(RetryBackground);
+    for (maybe_stretch, maybe_image, source) in sourced.iter() {
+        if commands.get_entity(source.source).is_none() {
+            // the source was despawned, rebuild in case the scene has replaced it
+            if let Some(mut commands) = commands.get_entity(source.background) {
+                commands.try_insert(RetryBackground);
+            }
         } else {
             if let Some(h_stretch) = maybe_stretch {
                 stretch_uvs.get_mut(h_stretch);
@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use common::{sets::SceneSets, structs::PrimaryUser};
+use dcl::interface::ComponentPosition;
+use dcl_component::{proto_components::sdk::components::PbSkybox, SceneComponentId};
+use ipfs::IpfsAssetServer;
+use visuals::scene_skybox::SceneSkybox;
+
+use crate::{renderer_context::RendererSceneContext, ContainingScene};
+
+use super::AddCrdtInterfaceExt;
+
+pub struct SkyboxPlugin;
+
+impl Plugin for SkyboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_crdt_lww_component::<PbSkybox, Skybox>(
+            SceneComponentId::SKYBOX,
+            ComponentPosition::RootOnly,
+        );
+        app.add_systems(
+            Update,
+            (load_scene_skyboxes, update_scene_skybox)
+                .chain()
+                .in_set(SceneSets::PostLoop),
+        );
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Skybox(pub PbSkybox);
+
+impl From<PbSkybox> for Skybox {
+    fn from(value: PbSkybox) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Component)]
+pub struct SkyboxImage(pub Handle<Image>);
+
+fn load_scene_skyboxes(
+    mut commands: Commands,
+    q: Query<(Entity, &RendererSceneContext, &Skybox), Changed<Skybox>>,
+    mut removed: RemovedComponents<Skybox>,
+    ipfas: IpfsAssetServer,
+) {
+    for ent in removed.read() {
+        if let Some(mut commands) = commands.get_entity(ent) {
+            commands.remove::<SkyboxImage>();
+        }
+    }
+
+    for (ent, ctx, skybox) in q.iter() {
+        match ipfas.load_content_file::<Image>(&skybox.0.texture, &ctx.hash) {
+            Ok(image) => {
+                commands.entity(ent).try_insert(SkyboxImage(image));
+            }
+            Err(e) => {
+                warn!("failed to load skybox `{}`: {e}", skybox.0.texture);
+                commands.entity(ent).remove::<SkyboxImage>();
+            }
+        }
+    }
+}
+
+fn update_scene_skybox(
+    scenes: Query<(&Skybox, &SkyboxImage), With<RendererSceneContext>>,
+    mut scene_skybox: ResMut<SceneSkybox>,
+    containing_scene: ContainingScene,
+    player: Query<Entity, With<PrimaryUser>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+
+    let source = containing_scene.get_controlling_scene(player, |scene| scenes.get(scene).is_ok());
+
+    let next = match source.and_then(|source| scenes.get(source).ok()) {
+        Some((skybox, image)) => SceneSkybox {
+            source,
+            image: Some(image.0.clone()),
+            brightness: skybox.0.brightness,
+        },
+        None => SceneSkybox::default(),
+    };
+
+    scene_skybox.set_if_neq(next);
+}
@@ -0,0 +1,108 @@
+// downscale content textures larger than the configured max texture size as they load. under
+// memory pressure the max size is reduced and already loaded textures are downscaled too.
+
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use common::{
+    structs::{AppConfig, MemoryPressure},
+    util::TaskExt,
+};
+
+pub struct TextureBudgetPlugin;
+
+impl Plugin for TextureBudgetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, limit_texture_size);
+    }
+}
+
+fn is_content_path(asset_server: &AssetServer, id: AssetId<Image>) -> bool {
+    asset_server
+        .get_path(id)
+        .is_some_and(|path| path.path().starts_with("$ipfs"))
+}
+
+fn downscale(image: Image, max_size: u32) -> Option<Image> {
+    let is_srgb = image.texture_descriptor.format.is_srgb();
+    let asset_usage = image.asset_usage;
+    let sampler = image.sampler.clone();
+
+    // compressed formats can't be converted, leave them as they are
+    let dynamic = image.try_into_dynamic().ok()?;
+    let resized = dynamic.resize(max_size, max_size, image::imageops::FilterType::Triangle);
+
+    let mut image = Image::from_dynamic(resized, is_srgb, asset_usage);
+    image.sampler = sampler;
+    Some(image)
+}
+
+fn limit_texture_size(
+    mut events: EventReader<AssetEvent<Image>>,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+    config: Res<AppConfig>,
+    memory_pressure: Option<Res<MemoryPressure>>,
+    mut tasks: Local<Vec<(AssetId<Image>, Task<Option<Image>>)>>,
+    mut prev_max_size: Local<u32>,
+) {
+    let max_size = match memory_pressure {
+        Some(pressure) => pressure.max_texture_size(config.graphics.max_texture_size),
+        None => config.graphics.max_texture_size,
+    };
+
+    // when the limit tightens, recheck everything already loaded
+    let shrunk = max_size != 0 && (*prev_max_size == 0 || max_size < *prev_max_size);
+    *prev_max_size = max_size;
+    let mut candidates: Vec<_> = events
+        .read()
+        .filter_map(|ev| match ev {
+            AssetEvent::LoadedWithDependencies { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    if shrunk {
+        candidates = images.ids().collect();
+    }
+
+    for id in candidates {
+        if max_size == 0 || !is_content_path(&asset_server, id) {
+            continue;
+        }
+
+        // already being resized
+        if tasks.iter().any(|(task_id, _)| *task_id == id) {
+            continue;
+        }
+
+        let Some(image) = images.get(id) else {
+            continue;
+        };
+
+        let size = image.size();
+        if size.max_element() <= max_size {
+            continue;
+        }
+
+        debug!("downscaling {:?} from {size}", asset_server.get_path(id));
+        let image = image.clone();
+        tasks.push((
+            id,
+            AsyncComputeTaskPool::get().spawn(async move { downscale(image, max_size) }),
+        ));
+    }
+
+    tasks.retain_mut(|(id, task)| {
+        let Some(result) = task.complete() else {
+            return true;
+        };
+
+        if let Some(resized) = result {
+            if let Some(image) = images.get_mut(*id) {
+                *image = resized;
+            }
+        }
+        false
+    });
+}
@@ -10,7 +10,7 @@ use bevy::{
 };
 use bevy_console::{ConsoleCommand, PrintConsoleLine};
 use clap::builder::StyledStr;
-use common::structs::PrimaryUser;
+use common::structs::{NotificationCategory, PrimaryUser};
 use comms::preview::PreviewCommand;
 use console::DoAddConsoleCommand;
 use futures_lite::AsyncReadExt;
@@ -62,7 +62,9 @@ fn debug_dump_scene(
     ipfas: IpfsAssetServer,
     scene_definitions: Res<Assets<EntityDefinition>>,
     mut tasks: Local<Vec<Task<()>>>,
+    mut dumps: Local<Vec<(String, Arc<Mutex<(usize, usize, usize)>>)>>,
     console_relay: Res<ConsoleRelay>,
+    mut toaster: Toaster,
 ) {
     if let Some(Ok(_)) = input.take() {
         let scenes = player
@@ -96,6 +98,7 @@ fn debug_dump_scene(
 
             // total / succeed / fail
             let count = Arc::new(Mutex::new((0, 0, 0)));
+            dumps.push((scene.hash.clone(), count.clone()));
 
             for content_file in def.content.files() {
                 count.lock().unwrap().0 += 1;
@@ -172,6 +175,26 @@ fn debug_dump_scene(
     }
 
     tasks.retain_mut(|t| !t.is_finished());
+
+    dumps.retain(|(hash, count)| {
+        let (total, succeeded, failed) = *count.lock().unwrap();
+        if total != succeeded + failed {
+            return true;
+        }
+
+        let message = if failed == 0 {
+            format!("Scene {hash} dump complete: {total} files downloaded")
+        } else {
+            format!("Scene {hash} dump failed: {succeeded}/{total} files downloaded")
+        };
+        toaster.notify(
+            NotificationCategory::Download,
+            format!("dump-{hash}"),
+            message,
+            None,
+        );
+        false
+    });
 }
 
 #[derive(clap::Parser, ConsoleCommand)]
@@ -211,7 +234,7 @@ fn handle_preview_command(
             PreviewCommand::ReloadScene { hash } => {
                 if let Some(ctx) = live_scenes.0.get(hash).and_then(|e| scenes.get(*e).ok()) {
                     if ctx.inspected {
-                        toaster.add_toast("reload-inspected", "Scene has updated but an inspector is attached. To force the reload type \"/reload\" in the chat window");
+                        toaster.notify(NotificationCategory::Scene, "reload-inspected", "Scene has updated but an inspector is attached. To force the reload type \"/reload\" in the chat window", None);
                         continue;
                     }
                 };
@@ -1,10 +1,12 @@
 pub mod client;
+pub mod quests;
 
 use bevy::prelude::*;
 use client::{DirectChatMessage, SocialClientHandler};
-use common::util::FireEventEx;
+use common::{rpc::RpcCall, util::FireEventEx};
 use dcl_component::proto_components::social::friendship_event_response;
 use ethers_core::types::Address;
+use quests::{QuestChange, QuestsClientHandler};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 use wallet::Wallet;
 
@@ -21,6 +23,10 @@ impl Plugin for SocialPlugin {
             }
         });
         app.add_systems(PostUpdate, init_social_client);
+
+        app.add_event::<QuestEvent>();
+        app.init_resource::<QuestsClient>();
+        app.add_systems(PostUpdate, (init_quests_client, handle_quest_rpcs));
     }
 }
 
@@ -91,3 +97,58 @@ pub struct FriendshipEvent(pub Option<friendship_event_response::Body>);
 
 #[derive(Event)]
 pub struct DirectChatEvent(pub DirectChatMessage);
+
+#[derive(Resource, Default)]
+pub struct QuestsClient(pub Option<QuestsClientHandler>);
+
+#[derive(Event)]
+pub struct QuestEvent(pub QuestChange);
+
+pub fn init_quests_client(
+    mut commands: Commands,
+    wallet: Res<Wallet>,
+    mut quests: ResMut<QuestsClient>,
+) {
+    if wallet.is_changed() && wallet.address().is_some() {
+        quests.0 = Some(QuestsClientHandler::connect(wallet.clone()));
+    }
+
+    if let Some(client) = quests.0.as_mut() {
+        for change in client.update() {
+            commands.fire_event(QuestEvent(change));
+        }
+    }
+}
+
+fn handle_quest_rpcs(mut events: EventReader<RpcCall>, quests: Res<QuestsClient>) {
+    for ev in events.read() {
+        match ev {
+            RpcCall::StartQuest {
+                quest_id, response, ..
+            } => match quests.0.as_ref() {
+                Some(client) => client.start_quest(quest_id.clone(), response.clone()),
+                None => response.send(Err("not connected".to_owned())),
+            },
+            RpcCall::AbortQuest {
+                instance_id,
+                response,
+                ..
+            } => match quests.0.as_ref() {
+                Some(client) => client.abort_quest(instance_id.clone(), response.clone()),
+                None => response.send(Err("not connected".to_owned())),
+            },
+            RpcCall::SendQuestEvent {
+                action_type,
+                parameters,
+                response,
+                ..
+            } => match quests.0.as_ref() {
+                Some(client) => {
+                    client.send_event(action_type.clone(), parameters.clone(), response.clone())
+                }
+                None => response.send(Err("not connected".to_owned())),
+            },
+            _ => (),
+        }
+    }
+}
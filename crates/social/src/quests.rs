@@ -0,0 +1,380 @@
+// client for the quests service. tracks the user's quest instances and forwards quest actions
+// from the ui and from scenes.
+
+use anyhow::anyhow;
+use bevy::{
+    log::{debug, warn},
+    utils::HashMap,
+};
+use common::rpc::RpcResultSender;
+use dcl_component::proto_components::quests::{
+    abort_quest_response, event_response, get_all_quests_response, start_quest_response,
+    user_update, AbortQuestRequest, Action, EventRequest, QuestInstance, QuestStateUpdate,
+    QuestsServiceClient, QuestsServiceClientDefinition, StartQuestRequest, UserUpdate,
+};
+use dcl_rpc::{
+    client::RpcClient,
+    transports::web_sockets::{Message, WebSocketTransport},
+};
+use futures_util::{pin_mut, select, FutureExt};
+use isahc::{http::Uri, AsyncReadResponseExt};
+use serde::Deserialize;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+const QUESTS_URL: &str = "wss://quests-rpc.decentraland.org";
+const QUESTS_API_URL: &str = "https://quests.decentraland.org/api";
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct QuestReward {
+    pub name: String,
+    pub image_link: String,
+}
+
+#[derive(Deserialize)]
+struct QuestRewards {
+    items: Vec<QuestReward>,
+}
+
+// changes to the user's quests, for notifications
+#[derive(Debug, Clone)]
+pub enum QuestChange {
+    Started { name: String },
+    StepCompleted { name: String, step: String },
+    Completed { name: String },
+}
+
+enum QuestData {
+    Init(Vec<QuestInstance>),
+    Update(user_update::Message),
+    Aborted(String),
+    Rewards(String, Vec<QuestReward>),
+}
+
+enum QuestOutbound {
+    Start(String, RpcResultSender<Result<(), String>>),
+    Abort(String, RpcResultSender<Result<(), String>>),
+    Event(Action, RpcResultSender<Result<String, String>>),
+}
+
+pub struct QuestsClientHandler {
+    sender: UnboundedSender<QuestOutbound>,
+    receiver: UnboundedReceiver<QuestData>,
+
+    pub is_initialized: bool,
+    // active quests by instance id
+    pub instances: HashMap<String, QuestInstance>,
+    // rewards by quest id
+    pub rewards: HashMap<String, Vec<QuestReward>>,
+    // bumped whenever the instances or rewards change
+    pub generation: usize,
+}
+
+impl QuestsClientHandler {
+    pub fn connect(wallet: wallet::Wallet) -> Self {
+        let (event_sx, event_rx) = mpsc::unbounded_channel();
+        let (response_sx, response_rx) = mpsc::unbounded_channel();
+
+        std::thread::spawn(move || quests_socket_handler(wallet, event_rx, response_sx));
+
+        Self {
+            sender: event_sx,
+            receiver: response_rx,
+            is_initialized: false,
+            instances: Default::default(),
+            rewards: Default::default(),
+            generation: 0,
+        }
+    }
+
+    pub fn live(&self) -> bool {
+        !self.receiver.is_closed()
+    }
+
+    pub fn start_quest(&self, quest_id: String, response: RpcResultSender<Result<(), String>>) {
+        if self
+            .sender
+            .send(QuestOutbound::Start(quest_id, response.clone()))
+            .is_err()
+        {
+            response.send(Err("quests service disconnected".to_owned()));
+        }
+    }
+
+    pub fn abort_quest(&self, instance_id: String, response: RpcResultSender<Result<(), String>>) {
+        if self
+            .sender
+            .send(QuestOutbound::Abort(instance_id, response.clone()))
+            .is_err()
+        {
+            response.send(Err("quests service disconnected".to_owned()));
+        }
+    }
+
+    pub fn send_event(
+        &self,
+        action_type: String,
+        parameters: std::collections::HashMap<String, String>,
+        response: RpcResultSender<Result<String, String>>,
+    ) {
+        let action = Action {
+            r#type: action_type,
+            parameters,
+        };
+        if self
+            .sender
+            .send(QuestOutbound::Event(action, response.clone()))
+            .is_err()
+        {
+            response.send(Err("quests service disconnected".to_owned()));
+        }
+    }
+
+    pub fn update(&mut self) -> Vec<QuestChange> {
+        let mut changes = Vec::default();
+
+        while let Ok(rec) = self.receiver.try_recv() {
+            self.generation += 1;
+            match rec {
+                QuestData::Init(instances) => {
+                    self.instances = instances
+                        .into_iter()
+                        .filter(|instance| !is_complete(instance))
+                        .map(|instance| (instance.id.clone(), instance))
+                        .collect();
+                    self.is_initialized = true;
+                }
+                QuestData::Update(user_update::Message::NewQuestStarted(instance)) => {
+                    changes.push(QuestChange::Started {
+                        name: quest_name(&instance),
+                    });
+                    self.instances.insert(instance.id.clone(), instance);
+                }
+                QuestData::Update(user_update::Message::QuestStateUpdate(QuestStateUpdate {
+                    instance_id,
+                    quest_state: Some(state),
+                    ..
+                })) => {
+                    let Some(instance) = self.instances.get_mut(&instance_id) else {
+                        warn!("update for unknown quest instance {instance_id}");
+                        continue;
+                    };
+                    let name = quest_name(instance);
+                    let prev_completed = instance
+                        .state
+                        .as_ref()
+                        .map(|state| state.steps_completed.clone())
+                        .unwrap_or_default();
+                    for step in state.steps_completed.iter() {
+                        if !prev_completed.contains(step) {
+                            changes.push(QuestChange::StepCompleted {
+                                name: name.clone(),
+                                step: step_description(instance, step),
+                            });
+                        }
+                    }
+                    instance.state = Some(state);
+
+                    if is_complete(instance) {
+                        changes.push(QuestChange::Completed { name });
+                        self.instances.remove(&instance_id);
+                    }
+                }
+                QuestData::Update(other) => debug!("quest update: {other:?}"),
+                QuestData::Aborted(instance_id) => {
+                    self.instances.remove(&instance_id);
+                }
+                QuestData::Rewards(quest_id, rewards) => {
+                    self.rewards.insert(quest_id, rewards);
+                }
+            }
+        }
+
+        changes
+    }
+}
+
+pub fn quest_name(instance: &QuestInstance) -> String {
+    instance
+        .quest
+        .as_ref()
+        .map(|quest| quest.name.clone())
+        .unwrap_or_default()
+}
+
+// the step's description if the definition has it, else its id
+pub fn step_description(instance: &QuestInstance, step_id: &str) -> String {
+    instance
+        .quest
+        .as_ref()
+        .and_then(|quest| quest.definition.as_ref())
+        .and_then(|definition| definition.steps.iter().find(|step| step.id == step_id))
+        .map(|step| step.description.clone())
+        .filter(|description| !description.is_empty())
+        .unwrap_or_else(|| step_id.to_owned())
+}
+
+fn is_complete(instance: &QuestInstance) -> bool {
+    instance
+        .state
+        .as_ref()
+        .is_some_and(|state| state.steps_left == 0)
+}
+
+fn quests_socket_handler(
+    wallet: wallet::Wallet,
+    event_rx: UnboundedReceiver<QuestOutbound>,
+    response_sx: UnboundedSender<QuestData>,
+) {
+    let rt = std::sync::Arc::new(
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap(),
+    );
+    if let Err(e) = rt.block_on(quests_socket_handler_inner(wallet, event_rx, response_sx)) {
+        warn!("quests socket handler: {e}");
+    }
+}
+
+fn dbgerr<E: std::fmt::Debug>(e: E) -> anyhow::Error {
+    anyhow!(format!("{e:?}"))
+}
+
+fn fetch_rewards(quest_id: String, sx: UnboundedSender<QuestData>) {
+    tokio::spawn(async move {
+        let url = format!("{QUESTS_API_URL}/quests/{quest_id}/rewards");
+        let rewards = match isahc::get_async(url).await {
+            Ok(mut response) => response.json::<QuestRewards>().await.map_err(dbgerr),
+            Err(e) => Err(dbgerr(e)),
+        };
+        match rewards {
+            Ok(rewards) => {
+                let _ = sx.send(QuestData::Rewards(quest_id, rewards.items));
+            }
+            Err(e) => debug!("no rewards for quest {quest_id}: {e}"),
+        }
+    });
+}
+
+async fn quests_socket_handler_inner(
+    wallet: wallet::Wallet,
+    mut rx: UnboundedReceiver<QuestOutbound>,
+    response_sx: UnboundedSender<QuestData>,
+) -> Result<(), anyhow::Error> {
+    // the service authenticates with a signed fetch auth chain sent as the first message
+    let headers = wallet::sign_request(
+        "get",
+        &Uri::from_static(QUESTS_URL),
+        &wallet,
+        serde_json::Map::new(),
+    )
+    .await?;
+    let auth = serde_json::to_string(&headers.into_iter().collect::<HashMap<_, _>>())?;
+
+    let service_connection =
+        dcl_rpc::transports::web_sockets::tungstenite::WebSocketClient::connect(QUESTS_URL)
+            .await
+            .map_err(dbgerr)?;
+    service_connection
+        .send(Message::Text(auth))
+        .await
+        .map_err(dbgerr)?;
+    let service_transport = WebSocketTransport::new(service_connection);
+    let mut service_client = RpcClient::new(service_transport).await.map_err(dbgerr)?;
+    let port = service_client.create_port("quests").await.map_err(dbgerr)?;
+    let service_module = port
+        .load_module::<QuestsServiceClient<_>>("QuestsService")
+        .await
+        .map_err(dbgerr)?;
+
+    // initial state
+    let instances = match service_module
+        .get_all_quests(())
+        .await
+        .map_err(dbgerr)?
+        .response
+    {
+        Some(get_all_quests_response::Response::Quests(quests)) => quests.instances,
+        other => return Err(dbgerr(other)),
+    };
+    for instance in instances.iter() {
+        if let Some(quest) = instance.quest.as_ref() {
+            fetch_rewards(quest.id.clone(), response_sx.clone());
+        }
+    }
+    response_sx.send(QuestData::Init(instances))?;
+
+    let mut inbound_updates = service_module.subscribe(()).await.map_err(dbgerr)?;
+
+    // inbound state updates
+    let sx = response_sx.clone();
+    let f_read = async move {
+        while let Some(UserUpdate { message, .. }) = inbound_updates.next().await {
+            let Some(message) = message else {
+                continue;
+            };
+            if let user_update::Message::NewQuestStarted(instance) = &message {
+                if let Some(quest) = instance.quest.as_ref() {
+                    fetch_rewards(quest.id.clone(), sx.clone());
+                }
+            }
+            sx.send(QuestData::Update(message)).map_err(dbgerr)?;
+        }
+        Result::<(), anyhow::Error>::Ok(())
+    }
+    .fuse();
+
+    // outbound actions
+    let sx = response_sx.clone();
+    let f_write = async move {
+        while let Some(req) = rx.recv().await {
+            match req {
+                QuestOutbound::Start(quest_id, response) => {
+                    let result = service_module
+                        .start_quest(StartQuestRequest { quest_id })
+                        .await
+                        .map_err(dbgerr)?;
+                    response.send(match result.response {
+                        Some(start_quest_response::Response::Accepted(_)) => Ok(()),
+                        other => Err(format!("{other:?}")),
+                    });
+                }
+                QuestOutbound::Abort(quest_instance_id, response) => {
+                    let result = service_module
+                        .abort_quest(AbortQuestRequest {
+                            quest_instance_id: quest_instance_id.clone(),
+                        })
+                        .await
+                        .map_err(dbgerr)?;
+                    response.send(match result.response {
+                        Some(abort_quest_response::Response::Accepted(_)) => {
+                            sx.send(QuestData::Aborted(quest_instance_id))?;
+                            Ok(())
+                        }
+                        other => Err(format!("{other:?}")),
+                    });
+                }
+                QuestOutbound::Event(action, response) => {
+                    let result = service_module
+                        .send_event(EventRequest {
+                            action: Some(action),
+                        })
+                        .await
+                        .map_err(dbgerr)?;
+                    response.send(match result.response {
+                        Some(event_response::Response::AcceptedEventId(id)) => Ok(id),
+                        other => Err(format!("{other:?}")),
+                    });
+                }
+            }
+        }
+        Result::<(), anyhow::Error>::Ok(())
+    }
+    .fuse();
+
+    pin_mut!(f_read, f_write);
+    select! {
+        r = f_read => r,
+        r = f_write => r,
+    }
+}
@@ -4,7 +4,7 @@ use bevy::{
     app::{Plugin, Update},
     prelude::{Event, EventWriter, ResMut, Resource},
 };
-use common::rpc::RpcResultSender;
+use common::{rpc::RpcResultSender, structs::LedgerAccount};
 use settings::{SettingBridgePlugin, Settings};
 
 pub struct SystemBridgePlugin {
@@ -36,9 +36,25 @@ pub enum SystemApi {
         RpcResultSender<Result<Option<i32>, String>>,
         RpcResultSender<Result<(), String>>,
     ),
+    // sends the auth page url and code to show as a qr code for another device, then the login
+    // result. the local browser is not opened
+    LoginQr(
+        RpcResultSender<Result<(String, Option<i32>), String>>,
+        RpcResultSender<Result<(), String>>,
+    ),
+    // sends the pairing uri for the wallet app, then the login result
+    LoginWalletConnect(
+        RpcResultSender<Result<String, String>>,
+        RpcResultSender<Result<(), String>>,
+    ),
+    // lists accounts on the connected ledger, with legacy derivation paths if set
+    LedgerAccounts(bool, RpcResultSender<Result<Vec<LedgerAccount>, String>>),
+    LoginLedger(LedgerAccount, RpcResultSender<Result<(), String>>),
     LoginGuest,
     LoginCancel,
     Logout,
+    // logout, and forget the saved login and its key, and any walletconnect session
+    SignOutEverywhere,
     GetSettings(RpcResultSender<Settings>),
 }
 
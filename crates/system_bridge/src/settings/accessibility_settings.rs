@@ -0,0 +1,187 @@
+use bevy::math::FloatOrd;
+use bevy::prelude::*;
+use common::structs::{AppConfig, InputAccessibility};
+
+use super::{AppSetting, EnumAppSetting, IntAppSetting};
+
+// sdk input action name for walking
+const WALK_ACTION: &str = "IA_WALK";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HoldMode {
+    Hold,
+    Toggle,
+}
+
+impl HoldMode {
+    fn from_toggle(toggle: bool) -> Self {
+        if toggle {
+            Self::Toggle
+        } else {
+            Self::Hold
+        }
+    }
+}
+
+macro_rules! hold_mode_setting {
+    ($struct:ident, $name:expr, $description:expr, $set:expr, $get:expr) => {
+        #[derive(Debug, PartialEq, Eq)]
+        pub struct $struct(HoldMode);
+
+        impl EnumAppSetting for $struct {
+            fn variants() -> Vec<Self> {
+                vec![Self(HoldMode::Hold), Self(HoldMode::Toggle)]
+            }
+
+            fn name(&self) -> String {
+                format!("{:?}", self.0)
+            }
+        }
+
+        #[allow(clippy::redundant_closure_call)]
+        impl AppSetting for $struct {
+            type Param = ();
+
+            fn title() -> String {
+                $name.to_owned()
+            }
+
+            fn description(&self) -> String {
+                format!("{}\n\n{}", $name, $description)
+            }
+
+            fn save(&self, config: &mut AppConfig) {
+                $set(&mut config.input_accessibility, self.0 == HoldMode::Toggle)
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                Self(HoldMode::from_toggle($get(&config.input_accessibility)))
+            }
+
+            fn apply(&self, _: (), _: Commands) {}
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Gameplay
+            }
+        }
+    };
+}
+
+hold_mode_setting!(
+    WalkModeSetting,
+    "Walk Mode",
+    "Hold: walk while the walk binding is held.\nToggle: each press of the walk binding switches between walking and running.",
+    |cfg: &mut InputAccessibility, toggle: bool| {
+        if toggle {
+            cfg.toggle_actions.insert(WALK_ACTION.to_owned());
+        } else {
+            cfg.toggle_actions.remove(WALK_ACTION);
+        }
+    },
+    |cfg: &InputAccessibility| cfg.toggle_actions.contains(WALK_ACTION)
+);
+
+hold_mode_setting!(
+    PushToTalkModeSetting,
+    "Push To Talk Mode",
+    "Hold: the microphone is switched while Left Ctrl is held.\nToggle: each press of Left Ctrl switches the microphone on or off.",
+    |cfg: &mut InputAccessibility, toggle: bool| cfg.toggle_push_to_talk = toggle,
+    |cfg: &InputAccessibility| cfg.toggle_push_to_talk
+);
+
+macro_rules! timing_setting {
+    ($struct:ident, $name:expr, $description:expr, $set:expr, $get:expr, $min:expr, $max:expr, $scale: expr, $display: expr) => {
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        pub struct $struct(FloatOrd);
+
+        impl IntAppSetting for $struct {
+            fn from_int(value: i32) -> Self {
+                Self(FloatOrd(value as f32 * $scale))
+            }
+
+            fn value(&self) -> i32 {
+                (self.0 .0 / $scale).round() as i32
+            }
+
+            fn min() -> i32 {
+                ($min / $scale) as i32
+            }
+
+            fn max() -> i32 {
+                ($max / $scale) as i32
+            }
+
+            fn scale() -> f32 {
+                $scale
+            }
+
+            #[allow(clippy::redundant_closure_call)]
+            fn display(&self) -> String {
+                $display(self.0 .0)
+            }
+        }
+
+        #[allow(clippy::redundant_closure_call)]
+        impl AppSetting for $struct {
+            type Param = ();
+
+            fn title() -> String {
+                format!("{}", $name)
+            }
+
+            fn description(&self) -> String {
+                format!("{}\n\n{}", $name, $description)
+            }
+
+            fn apply(&self, _: (), _: Commands) {}
+
+            fn save(&self, config: &mut AppConfig) {
+                $set(&mut config.input_accessibility, self.0 .0)
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                Self(FloatOrd($get(&config.input_accessibility)))
+            }
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Gameplay
+            }
+        }
+    };
+}
+
+timing_setting!(
+    PressTimingSetting,
+    "Press Timing",
+    "Multiplier for the time allowed for taps and double taps, and the time needed for long presses. Increase this to allow more time between presses.\nDefault 1.0",
+    |cfg: &mut InputAccessibility, val: f32| cfg.timing_scale = val,
+    |cfg: &InputAccessibility| cfg.timing_scale,
+    1.0,
+    4.0,
+    0.1,
+    |val: f32| format!("{val:.1}x")
+);
+
+timing_setting!(
+    RepeatDelaySetting,
+    "Repeat Delay",
+    "How long a direction must be held when navigating menus with a gamepad before it starts to repeat.\nDefault 0.40s",
+    |cfg: &mut InputAccessibility, val: f32| cfg.repeat_delay = val,
+    |cfg: &InputAccessibility| cfg.repeat_delay,
+    0.1,
+    2.0,
+    0.05,
+    |val: f32| format!("{val:.2}s")
+);
+
+timing_setting!(
+    RepeatIntervalSetting,
+    "Repeat Interval",
+    "Time between repeats when holding a direction to navigate menus with a gamepad.\nDefault 0.15s",
+    |cfg: &mut InputAccessibility, val: f32| cfg.repeat_interval = val,
+    |cfg: &InputAccessibility| cfg.repeat_interval,
+    0.05,
+    1.0,
+    0.05,
+    |val: f32| format!("{val:.2}s")
+);
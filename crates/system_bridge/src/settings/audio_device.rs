@@ -0,0 +1,113 @@
+use bevy::{ecs::system::lifetimeless::SResMut, prelude::*};
+use common::structs::{AppConfig, AudioSettings};
+use cpal::traits::{DeviceTrait, HostTrait};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+// device names, or none for the system default
+#[derive(Debug, PartialEq, Eq)]
+pub struct OutputDeviceSetting(Option<String>);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InputDeviceSetting(Option<String>);
+
+// the system default followed by the plugged in devices. a configured device that's unplugged isn't
+// listed, but stays selected so it's used again when it comes back
+fn device_variants(
+    devices: Result<impl Iterator<Item = cpal::Device>, cpal::DevicesError>,
+) -> Vec<Option<String>> {
+    let names = devices
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    std::iter::once(None)
+        .chain(names.into_iter().map(Some))
+        .collect()
+}
+
+fn device_name(device: &Option<String>) -> String {
+    device
+        .clone()
+        .unwrap_or_else(|| "System Default".to_owned())
+}
+
+impl EnumAppSetting for OutputDeviceSetting {
+    fn variants() -> Vec<Self> {
+        device_variants(cpal::default_host().output_devices())
+            .into_iter()
+            .map(Self)
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        device_name(&self.0)
+    }
+}
+
+impl AppSetting for OutputDeviceSetting {
+    type Param = SResMut<AudioSettings>;
+
+    fn title() -> String {
+        "Output Device".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Output Device\n\nThe speakers or headphones to play sound through. If the chosen device is unplugged, sound plays through the system default until it is plugged back in.\n\nCurrent device: {}", self.name())
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.audio.output_device.clone_from(&self.0);
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.audio.output_device.clone())
+    }
+
+    fn apply(&self, mut settings: ResMut<AudioSettings>, _: Commands) {
+        settings.output_device.clone_from(&self.0);
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Audio
+    }
+}
+
+impl EnumAppSetting for InputDeviceSetting {
+    fn variants() -> Vec<Self> {
+        device_variants(cpal::default_host().input_devices())
+            .into_iter()
+            .map(Self)
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        device_name(&self.0)
+    }
+}
+
+impl AppSetting for InputDeviceSetting {
+    type Param = SResMut<AudioSettings>;
+
+    fn title() -> String {
+        "Input Device".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Input Device\n\nThe microphone used for voice chat. If the chosen device is unplugged, the system default is used until it is plugged back in.\n\nCurrent device: {}", self.name())
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.audio.input_device.clone_from(&self.0);
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.audio.input_device.clone())
+    }
+
+    fn apply(&self, mut settings: ResMut<AudioSettings>, _: Commands) {
+        settings.input_device.clone_from(&self.0);
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Audio
+    }
+}
@@ -6,7 +6,7 @@ use bevy::{
 };
 use common::structs::{AppConfig, BloomSetting, PrimaryCameraRes};
 
-use super::{AppSetting, EnumAppSetting};
+use super::{AppSetting, EnumAppSetting, IntAppSetting};
 
 impl EnumAppSetting for BloomSetting {
     fn variants() -> Vec<Self> {
@@ -24,7 +24,7 @@ impl EnumAppSetting for BloomSetting {
 }
 
 impl AppSetting for BloomSetting {
-    type Param = SRes<PrimaryCameraRes>;
+    type Param = (SRes<AppConfig>, SRes<PrimaryCameraRes>);
 
     fn title() -> String {
         "Bloom".to_owned()
@@ -51,13 +51,14 @@ impl AppSetting for BloomSetting {
         config.graphics.bloom
     }
 
-    fn apply(&self, cam_res: SystemParamItem<Self::Param>, commands: Commands) {
-        self.apply_to_camera(&cam_res, commands, cam_res.0);
+    fn apply(&self, (config, cam_res): SystemParamItem<Self::Param>, commands: Commands) {
+        let primary_cam = cam_res.0;
+        self.apply_to_camera(&(config, cam_res), commands, primary_cam);
     }
 
     fn apply_to_camera(
         &self,
-        _: &SystemParamItem<Self::Param>,
+        (config, _): &SystemParamItem<Self::Param>,
         mut commands: Commands,
         camera_entity: Entity,
     ) {
@@ -65,16 +66,135 @@ impl AppSetting for BloomSetting {
             return;
         };
 
-        match self {
-            BloomSetting::Off => cmds.remove::<BloomSettings>(),
-            BloomSetting::Low => cmds.insert(BloomSettings {
-                intensity: 0.10,
-                ..BloomSettings::NATURAL
-            }),
-            BloomSetting::High => cmds.insert(BloomSettings {
-                intensity: 0.10,
-                ..BloomSettings::OLD_SCHOOL
-            }),
+        // scene overrides are applied on top of these values by `visuals::apply_scene_bloom`
+        let base = match self {
+            BloomSetting::Off => {
+                cmds.remove::<BloomSettings>();
+                return;
+            }
+            BloomSetting::Low => BloomSettings::NATURAL,
+            BloomSetting::High => BloomSettings::OLD_SCHOOL,
+        };
+
+        let mut settings = BloomSettings {
+            intensity: config.graphics.bloom_intensity(),
+            ..base
         };
+        settings.prefilter_settings.threshold = config.graphics.bloom_threshold();
+        cmds.insert(settings);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BloomIntensitySetting(i32);
+
+impl IntAppSetting for BloomIntensitySetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        50
+    }
+
+    fn scale() -> f32 {
+        0.01
+    }
+
+    fn display(&self) -> String {
+        format!("{:.2}", self.0 as f32 / 100.0)
+    }
+}
+
+impl AppSetting for BloomIntensitySetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Bloom Intensity".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Bloom Intensity\n\nHow strongly bright areas bleed into their surroundings. Has no effect when Bloom is Off. Scenes may request a lower intensity than this to avoid bright emissive content washing out the view, but can never increase it.\nDefault 0.10".to_owned()
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Graphics
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.graphics.bloom_intensity)
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.bloom_intensity = self.0;
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via BloomSetting
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BloomThresholdSetting(i32);
+
+impl IntAppSetting for BloomThresholdSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        40
+    }
+
+    fn scale() -> f32 {
+        0.1
+    }
+
+    fn display(&self) -> String {
+        format!("{:.1}", self.0 as f32 / 10.0)
+    }
+}
+
+impl AppSetting for BloomThresholdSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Bloom Threshold".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Bloom Threshold\n\nThe brightness above which pixels start to bloom. At 0 everything contributes a little bloom, giving a soft, hazy look. Higher values restrict bloom to only the brightest lights and emissive surfaces. Scenes may request a higher threshold than this, but can never lower it.\nDefault 0.0".to_owned()
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Graphics
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.graphics.bloom_threshold)
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.bloom_threshold = self.0;
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via BloomSetting
     }
 }
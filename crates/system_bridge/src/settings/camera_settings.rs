@@ -0,0 +1,125 @@
+use bevy::ecs::system::lifetimeless::{SQuery, Write};
+use bevy::math::FloatOrd;
+use bevy::prelude::*;
+use common::structs::{AppConfig, CameraSettings, PrimaryCamera};
+
+use super::{AppSetting, IntAppSetting};
+
+macro_rules! camera_setting {
+    ($struct:ident, $name:expr, $description:expr, $set:expr, $get:expr, $apply:expr, $min:expr, $max:expr, $scale: expr, $display: expr) => {
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        pub struct $struct(FloatOrd);
+
+        impl IntAppSetting for $struct {
+            fn from_int(value: i32) -> Self {
+                Self(FloatOrd(value as f32 * $scale))
+            }
+
+            fn value(&self) -> i32 {
+                (self.0 .0 / $scale).round() as i32
+            }
+
+            fn min() -> i32 {
+                ($min / $scale) as i32
+            }
+
+            fn max() -> i32 {
+                ($max / $scale) as i32
+            }
+
+            fn scale() -> f32 {
+                $scale
+            }
+
+            #[allow(clippy::redundant_closure_call)]
+            fn display(&self) -> String {
+                $display(self.0 .0)
+            }
+        }
+
+        #[allow(clippy::redundant_closure_call)]
+        impl AppSetting for $struct {
+            type Param = SQuery<Write<PrimaryCamera>>;
+
+            fn title() -> String {
+                format!("{}", $name)
+            }
+
+            fn description(&self) -> String {
+                format!("{}\n\n{}", $name, $description)
+            }
+
+            fn apply(&self, mut q: Query<&mut PrimaryCamera>, _: Commands) {
+                let Ok(mut camera) = q.get_single_mut() else {
+                    warn!("no primary camera");
+                    return;
+                };
+                $apply(&mut *camera, self.0 .0)
+            }
+
+            fn save(&self, config: &mut AppConfig) {
+                $set(&mut config.camera_settings, self.0 .0)
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                Self(FloatOrd($get(&config.camera_settings)))
+            }
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Gameplay
+            }
+        }
+    };
+}
+
+camera_setting!(
+    FirstPersonFovSetting,
+    "First Person Field of View",
+    "Vertical field of view in degrees when the camera is in first person. Higher values show more of the world but distort the edges of the screen.\nDefault 56",
+    |cfg: &mut CameraSettings, val: f32| cfg.fov_first_person = val,
+    |cfg: &CameraSettings| cfg.fov_first_person,
+    |cam: &mut PrimaryCamera, val: f32| cam.fov_first_person = val.to_radians(),
+    30.0,
+    110.0,
+    1.0,
+    |val: f32| format!("{val:.0}°")
+);
+
+camera_setting!(
+    ThirdPersonFovSetting,
+    "Third Person Field of View",
+    "Vertical field of view in degrees when the camera is behind the player. The field of view blends between the first and third person values as you zoom in and out.\nDefault 56",
+    |cfg: &mut CameraSettings, val: f32| cfg.fov_third_person = val,
+    |cfg: &CameraSettings| cfg.fov_third_person,
+    |cam: &mut PrimaryCamera, val: f32| cam.fov_third_person = val.to_radians(),
+    30.0,
+    110.0,
+    1.0,
+    |val: f32| format!("{val:.0}°")
+);
+
+camera_setting!(
+    HeadBobSetting,
+    "Head Bob",
+    "Strength of the camera bobbing while walking and running in first person. Set to 0 to disable.\nDefault 0",
+    |cfg: &mut CameraSettings, val: f32| cfg.head_bob = val,
+    |cfg: &CameraSettings| cfg.head_bob,
+    |cam: &mut PrimaryCamera, val: f32| cam.head_bob = val,
+    0.0,
+    1.0,
+    0.01,
+    |val: f32| format!("{:.0}%", val * 100.0)
+);
+
+camera_setting!(
+    CameraShakeSetting,
+    "Camera Shake",
+    "Strength of the camera shake when landing from a high fall. Set to 0 to disable.\nDefault 50",
+    |cfg: &mut CameraSettings, val: f32| cfg.camera_shake = val,
+    |cfg: &CameraSettings| cfg.camera_shake,
+    |cam: &mut PrimaryCamera, val: f32| cam.camera_shake = val,
+    0.0,
+    1.0,
+    0.01,
+    |val: f32| format!("{:.0}%", val * 100.0)
+);
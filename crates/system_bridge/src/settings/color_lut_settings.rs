@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+use common::{structs::AppConfig, util::user_lut_dir};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+const BUNDLED_LUTS: [&str; 3] = ["luts/warm.cube", "luts/cool.cube", "luts/noir.cube"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ColorLutSetting(Option<String>);
+
+impl ColorLutSetting {
+    // user luts are read from `<config dir>/luts/*.cube` and loaded via the `luts://` source
+    fn user_luts() -> Vec<String> {
+        let Ok(dir) = std::fs::read_dir(user_lut_dir()) else {
+            return Vec::default();
+        };
+
+        let mut luts = dir
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "cube"))
+            .filter_map(|path| {
+                path.file_name()
+                    .map(|name| format!("luts://{}", name.to_string_lossy()))
+            })
+            .collect::<Vec<_>>();
+        luts.sort();
+        luts
+    }
+
+    // all selectable luts, bundled first
+    pub fn luts() -> Vec<String> {
+        BUNDLED_LUTS
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .chain(Self::user_luts())
+            .collect()
+    }
+
+    pub fn lut_name(path: &str) -> String {
+        std::path::Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_owned())
+    }
+}
+
+impl EnumAppSetting for ColorLutSetting {
+    fn variants() -> Vec<Self> {
+        std::iter::once(Self(None))
+            .chain(Self::luts().into_iter().map(|path| Self(Some(path))))
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        match &self.0 {
+            None => "Off".to_owned(),
+            Some(path) => Self::lut_name(path),
+        }
+    }
+}
+
+impl AppSetting for ColorLutSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Color Grading".to_owned()
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Graphics
+    }
+
+    fn description(&self) -> String {
+        format!("Color Grading\n\nApplies a color lookup table (LUT) to the final image to change the overall look of the world. Additional `.cube` LUT files can be added to the `luts` folder next to your config file.\n\n{}",
+        match &self.0 {
+            None => "Off: No color grading.".to_owned(),
+            Some(_) => format!("{}: Grades the image using this LUT.", self.name()),
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.color_lut.clone_from(&self.0);
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        // fall back to off if a user lut has been removed
+        Self(config.graphics.color_lut.clone().filter(|path| {
+            BUNDLED_LUTS.contains(&path.as_str()) || Self::user_luts().contains(path)
+        }))
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via visuals
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SceneColorLutSetting {
+    Block,
+    Allow,
+}
+
+impl EnumAppSetting for SceneColorLutSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Block, Self::Allow]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            SceneColorLutSetting::Block => "Block",
+            SceneColorLutSetting::Allow => "Allow",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for SceneColorLutSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Scene Color Grading".to_owned()
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Graphics
+    }
+
+    fn description(&self) -> String {
+        format!("Scene Color Grading\n\nScenes can provide their own color lookup table to give the world a particular look while you are inside them.\n\n{}",
+        match self {
+            SceneColorLutSetting::Block => "Block: Ignore scene color grading and always use your own Color Grading setting.",
+            SceneColorLutSetting::Allow => "Allow: Scene color grading replaces your own Color Grading setting while you are inside the scene.",
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.scene_color_luts = matches!(self, SceneColorLutSetting::Allow);
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        if config.graphics.scene_color_luts {
+            Self::Allow
+        } else {
+            Self::Block
+        }
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via visuals
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, ColorblindMode};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ColorblindSetting(ColorblindMode);
+
+impl EnumAppSetting for ColorblindSetting {
+    fn variants() -> Vec<Self> {
+        vec![
+            Self(ColorblindMode::Off),
+            Self(ColorblindMode::Deuteranopia),
+            Self(ColorblindMode::Protanopia),
+            Self(ColorblindMode::Tritanopia),
+        ]
+    }
+
+    fn name(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+impl AppSetting for ColorblindSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Colorblind Mode".to_owned()
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Gameplay
+    }
+
+    fn description(&self) -> String {
+        format!("Colorblind Mode\n\nAdjusts the interface colors that carry meaning (highlights, item rarity, map markers and nametags) to stay distinguishable. Scene content is not changed, and open menus update when they are next opened.\n\n{}", 
+            match self.0 {
+                ColorblindMode::Off => "Off: The default palette.",
+                ColorblindMode::Deuteranopia => "Deuteranopia: Red-green palette for reduced green sensitivity.",
+                ColorblindMode::Protanopia => "Protanopia: Red-green palette for reduced red sensitivity, avoiding dark reds.",
+                ColorblindMode::Tritanopia => "Tritanopia: Blue-yellow palette, using reds, cyans and magentas.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.colorblind_mode = self.0;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.colorblind_mode)
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via ui_core::theme
+    }
+}
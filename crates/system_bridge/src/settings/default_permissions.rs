@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, PermissionType, PermissionValue};
+
+use super::{AppSetting, EnumAppSetting};
+
+// the default for a permission type, used when no realm or scene specific value is set. the
+// permissions tab edits the same values
+macro_rules! permission_setting {
+    ($struct:ident, $ty:expr, $name:expr, $description:expr) => {
+        #[derive(Debug, PartialEq, Eq)]
+        pub struct $struct(PermissionValue);
+
+        impl EnumAppSetting for $struct {
+            fn variants() -> Vec<Self> {
+                vec![
+                    Self(PermissionValue::Ask),
+                    Self(PermissionValue::Allow),
+                    Self(PermissionValue::Deny),
+                ]
+            }
+
+            fn name(&self) -> String {
+                match self.0 {
+                    PermissionValue::Allow => "Allow",
+                    PermissionValue::Deny => "Deny",
+                    PermissionValue::Ask => "Ask",
+                }
+                .to_owned()
+            }
+        }
+
+        impl AppSetting for $struct {
+            type Param = ();
+
+            fn title() -> String {
+                format!("Default {} Permission", $name)
+            }
+
+            fn description(&self) -> String {
+                format!(
+                    "Default {} Permission\n\n{}\n\nAsk: Prompt each time a scene requests it.\nAllow: Allow without prompting.\nDeny: Deny without prompting.\n\nRealm and scene specific permissions from the Permissions tab take priority.",
+                    $name, $description
+                )
+            }
+
+            fn save(&self, config: &mut AppConfig) {
+                config.default_permissions.insert($ty, self.0);
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                Self(
+                    config
+                        .default_permissions
+                        .get(&$ty)
+                        .copied()
+                        .unwrap_or_else(|| AppConfig::default_permission($ty)),
+                )
+            }
+
+            fn apply(&self, _: (), _: Commands) {}
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Gameplay
+            }
+        }
+    };
+}
+
+permission_setting!(
+    Web3PermissionSetting,
+    PermissionType::Web3,
+    "Web3",
+    "Whether scenes may request wallet signatures and transactions."
+);
+permission_setting!(
+    FetchPermissionSetting,
+    PermissionType::Fetch,
+    "Fetch",
+    "Whether scenes may make http requests to external servers."
+);
+permission_setting!(
+    WebsocketPermissionSetting,
+    PermissionType::Websocket,
+    "Websocket",
+    "Whether scenes may open websocket connections to external servers."
+);
+permission_setting!(
+    OpenUrlPermissionSetting,
+    PermissionType::OpenUrl,
+    "Open Url",
+    "Whether scenes may open links in your browser."
+);
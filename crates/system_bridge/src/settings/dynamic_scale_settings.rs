@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, IntAppSetting, SettingCategory};
+
+// bounds for the dynamic render scale, in percent of the window resolution
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DynamicScaleMinSetting(i32);
+
+impl IntAppSetting for DynamicScaleMinSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        25
+    }
+
+    fn max() -> i32 {
+        100
+    }
+
+    fn display(&self) -> String {
+        format!("{}%", self.0)
+    }
+}
+
+impl AppSetting for DynamicScaleMinSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Dynamic Scale Minimum".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Dynamic Scale Minimum\n\nWhen Render Scale is set to Dynamic, the lowest resolution the world may be rendered at while trying to hold the frame rate target.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.dynamic_scale_min = self.0;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.graphics.dynamic_scale_min)
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Graphics
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via visuals
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DynamicScaleMaxSetting(i32);
+
+impl IntAppSetting for DynamicScaleMaxSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        25
+    }
+
+    fn max() -> i32 {
+        100
+    }
+
+    fn display(&self) -> String {
+        format!("{}%", self.0)
+    }
+}
+
+impl AppSetting for DynamicScaleMaxSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Dynamic Scale Maximum".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Dynamic Scale Maximum\n\nWhen Render Scale is set to Dynamic, the highest resolution the world will be rendered at when there is spare GPU time.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.dynamic_scale_max = self.0;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.graphics.dynamic_scale_max)
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Graphics
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via visuals
+    }
+}
@@ -5,7 +5,7 @@ use super::{AppSetting, EnumAppSetting};
 
 impl EnumAppSetting for FogSetting {
     fn variants() -> Vec<Self> {
-        vec![Self::Off, Self::Basic, Self::Atmospheric]
+        vec![Self::Off, Self::Basic, Self::Atmospheric, Self::Volumetric]
     }
 
     fn name(&self) -> String {
@@ -13,6 +13,7 @@ impl EnumAppSetting for FogSetting {
             FogSetting::Off => "Off",
             FogSetting::Basic => "Basic",
             FogSetting::Atmospheric => "Atmospheric",
+            FogSetting::Volumetric => "Volumetric",
         }
         .to_owned()
     }
@@ -26,11 +27,12 @@ impl AppSetting for FogSetting {
     }
 
     fn description(&self) -> String {
-        format!("Rendering of distant objects. No performance impact, just an aesthetic preference.\n\n{}", 
+        format!("Rendering of distant objects. Apart from Volumetric there is no performance impact, just an aesthetic preference.\n\n{}", 
         match self {
             FogSetting::Off => "Off: No fog adjustment.",
             FogSetting::Basic => "Basic: Distant objects fade into the fog.",
             FogSetting::Atmospheric => "Atmospheric: Distant objects fade into the fog, and distant objects looking towards the sun take the sun color.",
+            FogSetting::Volumetric => "Volumetric: As Atmospheric, plus light-scattering fog near the player with visible shafts of sunlight. Scenes may adjust the fog density. Requires shadows to be enabled, and has a significant GPU cost.",
         })
     }
 
@@ -0,0 +1,59 @@
+use bevy::{ecs::system::lifetimeless::SResMut, prelude::*};
+use common::structs::{AppConfig, AudioSettings};
+
+use super::{AppSetting, EnumAppSetting};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FootstepsSetting {
+    Off,
+    On,
+}
+
+impl EnumAppSetting for FootstepsSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::On]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            FootstepsSetting::Off => "Off",
+            FootstepsSetting::On => "On",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for FootstepsSetting {
+    type Param = SResMut<AudioSettings>;
+
+    fn title() -> String {
+        "Footsteps".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Whether to play footstep sounds for your avatar and nearby players. Steps sound different depending on the surface and are quieter when moving slowly.".to_owned()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.audio.footsteps = match self {
+            FootstepsSetting::Off => false,
+            FootstepsSetting::On => true,
+        };
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        if config.audio.footsteps {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+
+    fn apply(&self, mut settings: ResMut<AudioSettings>, _: Commands) {
+        settings.footsteps = *self == FootstepsSetting::On;
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Audio
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, EnumAppSetting};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GamepadRumbleSetting {
+    Off,
+    On,
+}
+
+impl EnumAppSetting for GamepadRumbleSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::On]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            GamepadRumbleSetting::Off => "Off",
+            GamepadRumbleSetting::On => "On",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for GamepadRumbleSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Gamepad Rumble".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Whether connected gamepads vibrate for feedback, such as pressing buttons or landing from a high fall.".to_owned()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.gamepad_rumble = match self {
+            GamepadRumbleSetting::Off => false,
+            GamepadRumbleSetting::On => true,
+        };
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        if config.gamepad_rumble {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+
+    fn apply(&self, _: (), _: Commands) {}
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Gameplay
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::{prelude::*, render::renderer::RenderAdapterInfo};
+use common::structs::{AppConfig, GraphicsPreset};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+impl EnumAppSetting for GraphicsPreset {
+    fn variants() -> Vec<Self> {
+        vec![
+            Self::Custom,
+            Self::Low,
+            Self::Medium,
+            Self::High,
+            Self::Ultra,
+        ]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            GraphicsPreset::Custom => "Custom",
+            GraphicsPreset::Low => "Low",
+            GraphicsPreset::Medium => "Medium",
+            GraphicsPreset::High => "High",
+            GraphicsPreset::Ultra => "Ultra",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for GraphicsPreset {
+    type Param = ();
+
+    fn title() -> String {
+        "Graphics Preset".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Graphics Preset\n\nSets shadows, fog, ambient occlusion, bloom, max avatars, scene load distance and texture size together. Changing any of these individually switches the preset to Custom.\n\n{}",
+        match self {
+            GraphicsPreset::Custom => "Custom: Settings have been adjusted individually.",
+            GraphicsPreset::Low => "Low: For older or integrated GPUs. No shadows, basic fog, small textures and a short load distance.",
+            GraphicsPreset::Medium => "Medium: Low resolution shadows and atmospheric fog, with moderate texture sizes and avatar counts.",
+            GraphicsPreset::High => "High: Full shadows and light ambient occlusion, for dedicated GPUs.",
+            GraphicsPreset::Ultra => "Ultra: Volumetric fog, high quality ambient occlusion and bloom, full size textures and the longest load distance.",
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        self.apply_to(config);
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        GraphicsPreset::from_config(config)
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Graphics
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // the individual settings are applied once the config is updated
+    }
+}
+
+// on first run, pick a preset for the detected gpu
+pub(crate) fn detect_graphics_preset(
+    mut config: ResMut<AppConfig>,
+    adapter: Option<Res<RenderAdapterInfo>>,
+) {
+    if config.graphics.preset_detected {
+        return;
+    }
+
+    let Some(adapter) = adapter else {
+        return;
+    };
+
+    let preset = GraphicsPreset::detect(Some(&adapter));
+    info!(
+        "detected gpu `{}` ({:?}), using {} graphics preset",
+        adapter.name,
+        adapter.device_type,
+        preset.name()
+    );
+    preset.apply_to(&mut config);
+    config.graphics.preset_detected = true;
+}
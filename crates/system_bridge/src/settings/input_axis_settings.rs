@@ -0,0 +1,151 @@
+use bevy::math::FloatOrd;
+use bevy::prelude::*;
+use common::structs::{AppConfig, InputAxisSettings};
+
+use super::{AppSetting, IntAppSetting};
+
+// axis settings are read from the config when input is processed, so there is nothing to apply
+macro_rules! axis_setting {
+    ($struct:ident, $name:expr, $description:expr, $set:expr, $get:expr, $min:expr, $max:expr, $scale: expr, $display: expr) => {
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        pub struct $struct(FloatOrd);
+
+        impl IntAppSetting for $struct {
+            fn from_int(value: i32) -> Self {
+                Self(FloatOrd(value as f32 * $scale))
+            }
+
+            fn value(&self) -> i32 {
+                (self.0 .0 / $scale).round() as i32
+            }
+
+            fn min() -> i32 {
+                ($min / $scale) as i32
+            }
+
+            fn max() -> i32 {
+                ($max / $scale) as i32
+            }
+
+            fn scale() -> f32 {
+                $scale
+            }
+
+            #[allow(clippy::redundant_closure_call)]
+            fn display(&self) -> String {
+                $display(self.0 .0)
+            }
+        }
+
+        #[allow(clippy::redundant_closure_call)]
+        impl AppSetting for $struct {
+            type Param = ();
+
+            fn title() -> String {
+                format!("{}", $name)
+            }
+
+            fn description(&self) -> String {
+                format!("{}\n\n{}", $name, $description)
+            }
+
+            fn apply(&self, _: (), _: Commands) {}
+
+            fn save(&self, config: &mut AppConfig) {
+                $set(&mut config.input_axes, self.0 .0)
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                Self(FloatOrd($get(&config.input_axes)))
+            }
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Gameplay
+            }
+        }
+    };
+}
+
+axis_setting!(
+    MouseSensitivitySetting,
+    "Mouse Sensitivity",
+    "How far the camera turns when moving the mouse.\nDefault 1.0",
+    |cfg: &mut InputAxisSettings, val: f32| cfg.mouse.sensitivity = val,
+    |cfg: &InputAxisSettings| cfg.mouse.sensitivity,
+    0.1,
+    5.0,
+    0.1,
+    |val: f32| format!("{val:.1}")
+);
+
+axis_setting!(
+    MoveStickDeadZoneSetting,
+    "Move Stick Dead Zone",
+    "How far the left stick must be pushed before the player moves. Increase this if the player drifts when the stick is released.\nDefault 15%",
+    |cfg: &mut InputAxisSettings, val: f32| cfg.move_stick.dead_zone = val,
+    |cfg: &InputAxisSettings| cfg.move_stick.dead_zone,
+    0.0,
+    0.9,
+    0.01,
+    |val: f32| format!("{:.0}%", val * 100.0)
+);
+
+axis_setting!(
+    MoveStickCurveSetting,
+    "Move Stick Response Curve",
+    "Shape of the left stick response. 1 is linear, higher values give finer control of slow movement.\nDefault 1.0",
+    |cfg: &mut InputAxisSettings, val: f32| cfg.move_stick.curve = val,
+    |cfg: &InputAxisSettings| cfg.move_stick.curve,
+    0.5,
+    3.0,
+    0.1,
+    |val: f32| format!("{val:.1}")
+);
+
+axis_setting!(
+    LookStickSensitivitySetting,
+    "Look Stick Sensitivity",
+    "How fast the camera turns with the right stick fully pushed.\nDefault 1.0",
+    |cfg: &mut InputAxisSettings, val: f32| cfg.look_stick.sensitivity = val,
+    |cfg: &InputAxisSettings| cfg.look_stick.sensitivity,
+    0.1,
+    5.0,
+    0.1,
+    |val: f32| format!("{val:.1}")
+);
+
+axis_setting!(
+    LookStickDeadZoneSetting,
+    "Look Stick Dead Zone",
+    "How far the right stick must be pushed before the camera turns. Increase this if the camera drifts when the stick is released.\nDefault 15%",
+    |cfg: &mut InputAxisSettings, val: f32| cfg.look_stick.dead_zone = val,
+    |cfg: &InputAxisSettings| cfg.look_stick.dead_zone,
+    0.0,
+    0.9,
+    0.01,
+    |val: f32| format!("{:.0}%", val * 100.0)
+);
+
+axis_setting!(
+    LookStickCurveSetting,
+    "Look Stick Response Curve",
+    "Shape of the right stick response. 1 is linear, higher values give finer control of slow turns.\nDefault 2.0",
+    |cfg: &mut InputAxisSettings, val: f32| cfg.look_stick.curve = val,
+    |cfg: &InputAxisSettings| cfg.look_stick.curve,
+    0.5,
+    3.0,
+    0.1,
+    |val: f32| format!("{val:.1}")
+);
+
+axis_setting!(
+    TriggerDeadZoneSetting,
+    "Trigger Dead Zone",
+    "How far the triggers must be pulled before they start to register. Triggers count as pressed half way through the remaining travel.\nDefault 10%",
+    |cfg: &mut InputAxisSettings, val: f32| cfg.triggers.dead_zone = val,
+    |cfg: &InputAxisSettings| cfg.triggers.dead_zone,
+    0.0,
+    0.9,
+    0.01,
+    |val: f32| format!("{:.0}%", val * 100.0)
+);
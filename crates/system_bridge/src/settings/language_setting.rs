@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use common::{
+    i18n::{language_name, user_languages, BUNDLED_LANGUAGES, DEFAULT_LANGUAGE},
+    structs::AppConfig,
+};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct LanguageSetting(String);
+
+impl EnumAppSetting for LanguageSetting {
+    fn variants() -> Vec<Self> {
+        BUNDLED_LANGUAGES
+            .into_iter()
+            .map(|(code, _)| code.to_owned())
+            .chain(user_languages())
+            .map(Self)
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        language_name(&self.0)
+    }
+}
+
+impl AppSetting for LanguageSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Language".to_owned()
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Gameplay
+    }
+
+    fn description(&self) -> String {
+        format!("Language\n\nThe language used for menus, notifications and console messages. Scene content is not translated. Community translations can be downloaded with the `/language` console command, or added as `.ftl` files in the `i18n` folder next to your config file.\n\n{}: Uses this translation where available, and English otherwise.", self.name())
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.language.clone_from(&self.0);
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        // fall back to the default if a user pack has been removed
+        if Self::variants().iter().any(|v| v.0 == config.language) {
+            Self(config.language.clone())
+        } else {
+            Self(DEFAULT_LANGUAGE.to_owned())
+        }
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via system_ui::localization
+    }
+}
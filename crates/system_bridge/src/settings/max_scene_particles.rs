@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, IntAppSetting};
+
+// stored in thousands of particles
+#[derive(Debug, PartialEq, Eq)]
+pub struct MaxSceneParticlesSetting(i32);
+
+impl IntAppSetting for MaxSceneParticlesSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        50
+    }
+
+    fn display(&self) -> String {
+        format!("{}k", self.0)
+    }
+}
+
+impl AppSetting for MaxSceneParticlesSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Max Scene Particles".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Max Scene Particles\n\nHow many particles each scene may draw across all of its particle systems. Emitters beyond the budget draw fewer particles, or none at all. Lowering this can help frame rate in scenes with heavy effects.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.max_scene_particles = self.0 as usize * 1000;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self((config.max_scene_particles / 1000) as i32)
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Performance
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in scene_runner
+    }
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, IntAppSetting};
+
+// stored in gb
+#[derive(Debug, PartialEq, Eq)]
+pub struct MemoryLimitSetting(i32);
+
+impl IntAppSetting for MemoryLimitSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        32
+    }
+
+    fn display(&self) -> String {
+        match self.0 {
+            0 => "Off".to_owned(),
+            gb => format!("{gb} GB"),
+        }
+    }
+}
+
+impl AppSetting for MemoryLimitSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Memory Limit".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Memory Limit\n\nWhen the explorer's memory use goes over this limit it will progressively trim caches, unload the furthest scenes, reduce texture sizes and reduce the scene load distance until it is back within the limit. Set this below your system memory to avoid the system slowing down when it runs out.\n\nOff: No limit.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.memory_limit_mb = self.0 as u32 * 1024;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self((config.memory_limit_mb / 1024) as i32)
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Performance
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in scene_runner
+    }
+}
+
+// stored in units of 256mb
+#[derive(Debug, PartialEq, Eq)]
+pub struct TextureMemoryLimitSetting(i32);
+
+impl IntAppSetting for TextureMemoryLimitSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        32
+    }
+
+    fn display(&self) -> String {
+        match self.0 {
+            0 => "Off".to_owned(),
+            units => format!("{} MB", units * 256),
+        }
+    }
+}
+
+impl AppSetting for TextureMemoryLimitSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Texture Memory Limit".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Texture Memory Limit\n\nWhen loaded textures use more than this much memory the explorer will reduce memory use in the same way as for the Memory Limit. Lower this if your GPU has limited video memory.\n\nOff: No limit.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.texture_memory_limit_mb = self.0 as u32 * 256;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self((config.texture_memory_limit_mb / 256) as i32)
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Performance
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in scene_runner
+    }
+}
@@ -3,8 +3,13 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+use accessibility_settings::{
+    PressTimingSetting, PushToTalkModeSetting, RepeatDelaySetting, RepeatIntervalSetting,
+    WalkModeSetting,
+};
 use ambient_brightness_setting::AmbientSetting;
 use anyhow::anyhow;
+use audio_device::{InputDeviceSetting, OutputDeviceSetting};
 use bevy::{
     app::{Plugin, Update},
     ecs::{
@@ -13,49 +18,114 @@ use bevy::{
     },
     prelude::*,
 };
+use bloom_settings::{BloomIntensitySetting, BloomThresholdSetting};
+use camera_settings::{
+    CameraShakeSetting, FirstPersonFovSetting, HeadBobSetting, ThirdPersonFovSetting,
+};
+use color_lut_settings::{ColorLutSetting, SceneColorLutSetting};
+use colorblind_setting::ColorblindSetting;
 use common::{
     structs::{
-        AaSetting, AppConfig, BloomSetting, FogSetting, ShadowSetting, SsaoSetting, WindowSetting,
+        AaSetting, AppConfig, BloomSetting, FogSetting, GraphicsPreset, PowerSaveSetting,
+        PowerSaving, RenderScaleSetting, ShadowSetting, SsaoSetting, TonemapperSetting,
+        WindowSetting,
     },
     util::config_file,
 };
 use constrain_ui::ConstrainUiSetting;
+use default_permissions::{
+    FetchPermissionSetting, OpenUrlPermissionSetting, Web3PermissionSetting,
+    WebsocketPermissionSetting,
+};
 use despawn_workaround::DespawnWorkaroundSetting;
+use dynamic_scale_settings::{DynamicScaleMaxSetting, DynamicScaleMinSetting};
+use footsteps::FootstepsSetting;
 use frame_rate::FpsTargetSetting;
+use gamepad_rumble::GamepadRumbleSetting;
+use graphics_preset::detect_graphics_preset;
+use input_axis_settings::{
+    LookStickCurveSetting, LookStickDeadZoneSetting, LookStickSensitivitySetting,
+    MouseSensitivitySetting, MoveStickCurveSetting, MoveStickDeadZoneSetting,
+    TriggerDeadZoneSetting,
+};
+use language_setting::LanguageSetting;
 use load_distance::{LoadDistanceSetting, UnloadDistanceSetting};
 use max_avatars::MaxAvatarsSetting;
 use max_downloads::MaxDownloadsSetting;
+use max_scene_particles::MaxSceneParticlesSetting;
+use memory_limits::{MemoryLimitSetting, TextureMemoryLimitSetting};
+use narration_setting::NarrationSetting;
+use notification_settings::{
+    FriendRequestNotificationSetting, MentionNotificationSetting, PermissionNotificationSetting,
+};
+use now_playing::NowPlayingSetting;
 use oob_setting::OobSetting;
 use player_settings::{
     FallSpeedSetting, FrictionSetting, GravitySetting, JumpSetting, RunSpeedSetting,
     WalkSpeedSetting,
 };
+use power_save::{update_power_saving, PowerSaveFpsSetting};
+use remember_login::RememberLoginSetting;
 use scene_threads::SceneThreadsSetting;
 use serde::{Deserialize, Serialize};
-use shadow_settings::{ShadowCasterCountSetting, ShadowDistanceSetting};
+use shadow_settings::{
+    ShadowCascadesSetting, ShadowCasterCountSetting, ShadowDistanceSetting, ShadowMapSizeSetting,
+};
+use spatial_audio::SpatialAudioSetting;
+use ssao_setting::reapply_ssao_on_power_change;
+use telemetry::TelemetrySetting;
+use text_scale::{ChatTextScaleSetting, TextScaleSetting};
+use texture_size_setting::TextureSizeSetting;
+use ui_scale::UiScaleSetting;
 use video_threads::VideoThreadsSetting;
 use volume_settings::{
-    AvatarVolumeSetting, MasterVolumeSetting, SceneVolumeSetting, SystemVolumeSetting,
-    VoiceVolumeSetting,
+    AvatarVolumeSetting, MasterVolumeSetting, MediaVolumeSetting, SceneVolumeSetting,
+    SystemVolumeSetting, VoiceDuckingSetting, VoiceVolumeSetting,
 };
 
 use crate::SystemApi;
 
 pub mod aa_settings;
+pub mod accessibility_settings;
 pub mod ambient_brightness_setting;
+pub mod audio_device;
 pub mod bloom_settings;
+pub mod camera_settings;
+pub mod color_lut_settings;
+pub mod colorblind_setting;
 pub mod constrain_ui;
+pub mod default_permissions;
 pub mod despawn_workaround;
+pub mod dynamic_scale_settings;
 pub mod fog_settings;
+pub mod footsteps;
 pub mod frame_rate;
+pub mod gamepad_rumble;
+pub mod graphics_preset;
+pub mod input_axis_settings;
+pub mod language_setting;
 pub mod load_distance;
 pub mod max_avatars;
 pub mod max_downloads;
+pub mod max_scene_particles;
+pub mod memory_limits;
+pub mod narration_setting;
+pub mod notification_settings;
+pub mod now_playing;
 pub mod oob_setting;
 pub mod player_settings;
+pub mod power_save;
+pub mod remember_login;
+pub mod render_scale_setting;
 pub mod scene_threads;
 pub mod shadow_settings;
+pub mod spatial_audio;
 pub mod ssao_setting;
+pub mod telemetry;
+pub mod text_scale;
+pub mod texture_size_setting;
+pub mod tonemapper_setting;
+pub mod ui_scale;
 pub mod video_threads;
 pub mod volume_settings;
 pub mod window_settings;
@@ -109,47 +179,118 @@ impl Plugin for SettingBridgePlugin {
             })),
         };
         app.add_event::<NewCameraEvent>();
-        app.add_systems(Update, (Settings::sync_settings_object, send_settings));
+        app.init_resource::<PowerSaving>();
+        app.add_systems(
+            Update,
+            (
+                detect_graphics_preset.before(Settings::sync_settings_object),
+                (update_power_saving, reapply_ssao_on_power_change)
+                    .chain()
+                    .before(Settings::sync_settings_object),
+                Settings::sync_settings_object,
+                send_settings,
+            ),
+        );
 
         let mut schedule = Schedule::new(ApplyAppSettingsLabel);
 
+        add_enum_setting::<GraphicsPreset>(app, &mut settings, &mut schedule);
+
         add_int_setting::<ShadowDistanceSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<ShadowCasterCountSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<ShadowCascadesSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<ShadowMapSizeSetting>(app, &mut settings, &mut schedule);
 
         // special case for ordering
         settings.add_enum_setting::<ShadowSetting>();
         schedule.add_systems(
-            apply_setting::<ShadowSetting>.after(apply_setting::<ShadowDistanceSetting>),
+            apply_setting::<ShadowSetting>
+                .after(apply_setting::<ShadowDistanceSetting>)
+                .after(apply_setting::<ShadowCascadesSetting>)
+                .after(apply_setting::<ShadowMapSizeSetting>),
         );
 
         add_enum_setting::<FogSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<BloomSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<BloomIntensitySetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<BloomThresholdSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<TonemapperSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<ColorLutSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<SceneColorLutSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<SsaoSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<OobSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<AaSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<RenderScaleSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<DynamicScaleMinSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<DynamicScaleMaxSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<AmbientSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<WindowSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<UiScaleSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<TextScaleSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<ChatTextScaleSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<LoadDistanceSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<UnloadDistanceSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<FpsTargetSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<PowerSaveSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<PowerSaveFpsSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<SceneThreadsSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<MaxAvatarsSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<MaxSceneParticlesSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<TextureSizeSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<MasterVolumeSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<SceneVolumeSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<MediaVolumeSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<VoiceVolumeSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<SystemVolumeSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<AvatarVolumeSetting>(app, &mut settings, &mut schedule);
-
+        add_int_setting::<VoiceDuckingSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<NowPlayingSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<FootstepsSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<SpatialAudioSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<OutputDeviceSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<InputDeviceSetting>(app, &mut settings, &mut schedule);
+
+        add_enum_setting::<LanguageSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<NarrationSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<ColorblindSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<ConstrainUiSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<GamepadRumbleSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<RunSpeedSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<WalkSpeedSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<FrictionSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<JumpSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<GravitySetting>(app, &mut settings, &mut schedule);
         add_int_setting::<FallSpeedSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<FirstPersonFovSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<ThirdPersonFovSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<HeadBobSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<CameraShakeSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<MouseSensitivitySetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<MoveStickDeadZoneSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<MoveStickCurveSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<LookStickSensitivitySetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<LookStickDeadZoneSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<LookStickCurveSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<TriggerDeadZoneSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<WalkModeSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<PushToTalkModeSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<PressTimingSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<RepeatDelaySetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<RepeatIntervalSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<VideoThreadsSetting>(app, &mut settings, &mut schedule);
         add_int_setting::<MaxDownloadsSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<MemoryLimitSetting>(app, &mut settings, &mut schedule);
+        add_int_setting::<TextureMemoryLimitSetting>(app, &mut settings, &mut schedule);
         add_enum_setting::<DespawnWorkaroundSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<MentionNotificationSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<FriendRequestNotificationSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<PermissionNotificationSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<Web3PermissionSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<FetchPermissionSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<WebsocketPermissionSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<OpenUrlPermissionSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<RememberLoginSetting>(app, &mut settings, &mut schedule);
+        add_enum_setting::<TelemetrySetting>(app, &mut settings, &mut schedule);
 
         app.insert_resource(settings);
         app.insert_resource(ApplyAppSettingsSchedule(schedule));
@@ -297,7 +438,7 @@ impl Settings {
             },
             apply: Some(Box::new(
                 |config: &mut AppConfig, value: f32| -> Result<(), anyhow::Error> {
-                    S::from_int((value / S::scale()) as i32).save(config);
+                    S::from_int((value / S::scale()).round() as i32).save(config);
                     Ok(())
                 },
             )),
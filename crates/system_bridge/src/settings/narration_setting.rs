@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, NarrationLevel};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NarrationSetting(NarrationLevel);
+
+impl EnumAppSetting for NarrationSetting {
+    fn variants() -> Vec<Self> {
+        vec![
+            Self(NarrationLevel::Off),
+            Self(NarrationLevel::Focus),
+            Self(NarrationLevel::Alerts),
+            Self(NarrationLevel::All),
+        ]
+    }
+
+    fn name(&self) -> String {
+        match self.0 {
+            NarrationLevel::Off => "Off",
+            NarrationLevel::Focus => "Focused Element",
+            NarrationLevel::Alerts => "Focus and Notifications",
+            NarrationLevel::All => "Focus, Notifications and Chat",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for NarrationSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Screen Reader".to_owned()
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Gameplay
+    }
+
+    fn description(&self) -> String {
+        format!("Screen Reader\n\nWhat to expose to the operating system's screen reader. Focus follows keyboard and gamepad navigation of the menus.\n\n{}", 
+            match self.0 {
+                NarrationLevel::Off => "Off: Nothing is narrated.",
+                NarrationLevel::Focus => "Focused Element: The label of the focused menu element is narrated.",
+                NarrationLevel::Alerts => "Focus and Notifications: The focused element and notification toasts are narrated.",
+                NarrationLevel::All => "Focus, Notifications and Chat: The focused element, notification toasts and incoming chat messages are narrated.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.narration = self.0;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.narration)
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via system_ui::narration
+    }
+}
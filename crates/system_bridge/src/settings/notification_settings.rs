@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, NotificationCategory};
+
+use super::{AppSetting, EnumAppSetting};
+
+macro_rules! notification_setting {
+    ($struct:ident, $category:expr, $name:expr, $description:expr) => {
+        #[derive(Debug, PartialEq, Eq)]
+        pub enum $struct {
+            Off,
+            On,
+        }
+
+        impl EnumAppSetting for $struct {
+            fn variants() -> Vec<Self> {
+                vec![Self::Off, Self::On]
+            }
+
+            fn name(&self) -> String {
+                match self {
+                    Self::Off => "Off",
+                    Self::On => "On",
+                }
+                .to_owned()
+            }
+        }
+
+        impl AppSetting for $struct {
+            type Param = ();
+
+            fn title() -> String {
+                format!("{} Notifications", $name)
+            }
+
+            fn description(&self) -> String {
+                format!(
+                    "{} Notifications\n\n{}\n\nMuted notifications are neither shown as toasts nor recorded in the notification center.",
+                    $name, $description
+                )
+            }
+
+            fn save(&self, config: &mut AppConfig) {
+                config.muted_notifications.retain(|c| *c != $category);
+                if self == &Self::Off {
+                    config.muted_notifications.push($category);
+                }
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                if config.muted_notifications.contains(&$category) {
+                    Self::Off
+                } else {
+                    Self::On
+                }
+            }
+
+            fn apply(&self, _: (), _: Commands) {}
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Gameplay
+            }
+        }
+    };
+}
+
+notification_setting!(
+    MentionNotificationSetting,
+    NotificationCategory::Mention,
+    "Mention",
+    "Notify when someone mentions your name in nearby chat."
+);
+notification_setting!(
+    FriendRequestNotificationSetting,
+    NotificationCategory::FriendRequest,
+    "Friend Request",
+    "Notify when you receive a friend request."
+);
+notification_setting!(
+    PermissionNotificationSetting,
+    NotificationCategory::Permission,
+    "Permission",
+    "Notify when a scene is granted or denied a permission."
+);
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, EnumAppSetting};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum NowPlayingSetting {
+    Off,
+    On,
+}
+
+impl EnumAppSetting for NowPlayingSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::On]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            NowPlayingSetting::Off => "Off",
+            NowPlayingSetting::On => "On",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for NowPlayingSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Now Playing".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Whether to show a notification with the song title when a radio stream in the current scene changes track.".to_owned()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.show_now_playing = match self {
+            NowPlayingSetting::Off => false,
+            NowPlayingSetting::On => true,
+        };
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        if config.show_now_playing {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+
+    fn apply(&self, _: (), _: Commands) {}
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Audio
+    }
+}
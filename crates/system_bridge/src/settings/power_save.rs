@@ -0,0 +1,170 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use common::structs::{AppConfig, PowerSaveSetting, PowerSaving};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+impl EnumAppSetting for PowerSaveSetting {
+    fn variants() -> Vec<Self> {
+        vec![
+            Self::Off,
+            Self::Unfocused,
+            Self::Battery,
+            Self::UnfocusedOrBattery,
+            Self::Always,
+        ]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            PowerSaveSetting::Off => "Off",
+            PowerSaveSetting::Unfocused => "When Unfocused",
+            PowerSaveSetting::Battery => "On Battery",
+            PowerSaveSetting::UnfocusedOrBattery => "Unfocused or on Battery",
+            PowerSaveSetting::Always => "Always",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for PowerSaveSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Power Saving".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Power Saving\n\nWhen power saving is active the frame rate is capped to the Power Saving Frame Rate, distant scenes update less often, imposter baking is paused and expensive effects (volumetric fog and ambient occlusion) are disabled.\n\n{}",
+        match self {
+            PowerSaveSetting::Off => "Off: Always run at full speed.",
+            PowerSaveSetting::Unfocused => "When Unfocused: Save power while the window is in the background.",
+            PowerSaveSetting::Battery => "On Battery: Save power while running from a battery.",
+            PowerSaveSetting::UnfocusedOrBattery => "Unfocused or on Battery: Save power while the window is in the background or running from a battery.",
+            PowerSaveSetting::Always => "Always: Always save power.",
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.power_save = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.power_save
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Performance
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // see update_power_saving
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PowerSaveFpsSetting(usize);
+
+impl EnumAppSetting for PowerSaveFpsSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self(10), Self(15), Self(20), Self(30), Self(45), Self(60)]
+    }
+
+    fn name(&self) -> String {
+        format!("{} fps", self.0)
+    }
+}
+
+impl AppSetting for PowerSaveFpsSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Power Saving Frame Rate".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Power Saving Frame Rate\n\nThe frame rate cap used while power saving is active."
+            .to_owned()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.power_save_fps = self.0;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.power_save_fps)
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Performance
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // handled in scene_runner
+    }
+}
+
+// true when running from a battery with no mains supply connected
+#[cfg(target_os = "linux")]
+fn on_battery() -> bool {
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+
+    let mut has_battery = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |file: &str| {
+            std::fs::read_to_string(path.join(file))
+                .map(|value| value.trim().to_owned())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            "Mains" if read("online") == "1" => return false,
+            "Battery" => has_battery = true,
+            _ => (),
+        }
+    }
+    has_battery
+}
+
+// TODO: battery state on other platforms
+#[cfg(not(target_os = "linux"))]
+fn on_battery() -> bool {
+    false
+}
+
+const BATTERY_POLL_SECS: f32 = 5.0;
+
+pub(crate) fn update_power_saving(
+    config: Res<AppConfig>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut power_saving: ResMut<PowerSaving>,
+    time: Res<Time>,
+    mut battery: Local<Option<(f32, bool)>>,
+) {
+    let unfocused = window.get_single().is_ok_and(|window| !window.focused);
+    let mut battery = || {
+        let now = time.elapsed_seconds();
+        match *battery {
+            Some((at, value)) if now - at < BATTERY_POLL_SECS => value,
+            _ => {
+                let value = on_battery();
+                *battery = Some((now, value));
+                value
+            }
+        }
+    };
+
+    let active = match config.power_save {
+        PowerSaveSetting::Off => false,
+        PowerSaveSetting::Unfocused => unfocused,
+        PowerSaveSetting::Battery => battery(),
+        PowerSaveSetting::UnfocusedOrBattery => unfocused || battery(),
+        PowerSaveSetting::Always => true,
+    };
+
+    if power_saving.0 != active {
+        debug!("power saving: {active}");
+        power_saving.0 = active;
+    }
+}
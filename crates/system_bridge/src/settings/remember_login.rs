@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, EnumAppSetting};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RememberLoginSetting {
+    Off,
+    On,
+}
+
+impl EnumAppSetting for RememberLoginSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::On]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            RememberLoginSetting::Off => "Off",
+            RememberLoginSetting::On => "On",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for RememberLoginSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Remember Login".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Remember Login.\n\nOn: Your login is kept between launches, encrypted with a key held in the system keychain where available, so you can reuse it without signing in again until it expires.\n\nOff: Nothing is kept, and you sign in on every launch. Turning this off forgets any saved login.".to_owned()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.remember_login = matches!(self, RememberLoginSetting::On);
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        if config.remember_login {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // setting is handled in system_ui::login
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Gameplay
+    }
+}
@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use common::structs::{AppConfig, RenderScaleSetting};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+impl EnumAppSetting for RenderScaleSetting {
+    fn variants() -> Vec<Self> {
+        vec![
+            Self::Native,
+            Self::Quality,
+            Self::Balanced,
+            Self::Performance,
+            Self::Dynamic,
+        ]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            RenderScaleSetting::Native => "Native",
+            RenderScaleSetting::Quality => "Quality",
+            RenderScaleSetting::Balanced => "Balanced",
+            RenderScaleSetting::Performance => "Performance",
+            RenderScaleSetting::Dynamic => "Dynamic",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for RenderScaleSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Render Scale".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Render Scale\n\nRenders the 3d world at a reduced resolution and upscales it to fit the window, with sharpening to recover detail. The user interface is always drawn at full resolution. Lower resolutions reduce GPU time significantly, particularly on high resolution displays.\n\n{}",
+        match self {
+            RenderScaleSetting::Native => "Native: The world is rendered at the full window resolution.",
+            RenderScaleSetting::Quality => "Quality: The world is rendered at 67% of the window resolution.",
+            RenderScaleSetting::Balanced => "Balanced: The world is rendered at 59% of the window resolution.",
+            RenderScaleSetting::Performance => "Performance: The world is rendered at 50% of the window resolution. Fastest, but noticeably blurrier.",
+            RenderScaleSetting::Dynamic => "Dynamic: The resolution is adjusted automatically between the Dynamic Scale Minimum and Maximum to hold the frame rate target.",
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.render_scale = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.graphics.render_scale
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Graphics
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via visuals
+    }
+}
@@ -3,10 +3,11 @@ use bevy::{
         lifetimeless::{SQuery, SRes, Write},
         SystemParamItem,
     },
-    pbr::{CascadeShadowConfig, CascadeShadowConfigBuilder, ShadowFilteringMethod},
+    pbr::{CascadeShadowConfig, DirectionalLightShadowMap, ShadowFilteringMethod},
     prelude::*,
+    render::renderer::{RenderAdapterInfo, RenderDevice},
 };
-use common::structs::{AppConfig, PrimaryCameraRes, ShadowSetting};
+use common::structs::{AppConfig, GpuTier, PrimaryCameraRes, ShadowSetting};
 
 use super::{AppSetting, EnumAppSetting, IntAppSetting};
 
@@ -30,6 +31,8 @@ impl AppSetting for ShadowSetting {
         SRes<AppConfig>,
         SRes<PrimaryCameraRes>,
         SQuery<(Write<DirectionalLight>, Write<CascadeShadowConfig>)>,
+        Option<SRes<RenderAdapterInfo>>,
+        Option<SRes<RenderDevice>>,
     );
 
     fn title() -> String {
@@ -59,8 +62,8 @@ impl AppSetting for ShadowSetting {
 
     fn apply(
         &self,
-        (config, cam_res, mut lights): SystemParamItem<Self::Param>,
-        commands: Commands,
+        (config, cam_res, mut lights, adapter, device): SystemParamItem<Self::Param>,
+        mut commands: Commands,
     ) {
         let value = if config.graphics.shadow_distance == 0.0 {
             ShadowSetting::Off
@@ -68,38 +71,28 @@ impl AppSetting for ShadowSetting {
             *self
         };
 
+        let tier = GpuTier::from_adapter(adapter.as_deref());
         for (mut light, mut cascades) in lights.iter_mut() {
-            match value {
-                ShadowSetting::Off => {
-                    light.shadows_enabled = false;
-                }
-                ShadowSetting::Low => {
-                    light.shadows_enabled = true;
-                    *cascades = CascadeShadowConfigBuilder {
-                        num_cascades: 1,
-                        minimum_distance: 0.1,
-                        maximum_distance: config.graphics.shadow_distance,
-                        first_cascade_far_bound: config.graphics.shadow_distance,
-                        overlap_proportion: 0.2,
-                    }
-                    .build()
-                }
-                ShadowSetting::High => {
-                    light.shadows_enabled = true;
-                    *cascades = CascadeShadowConfigBuilder {
-                        num_cascades: 4,
-                        minimum_distance: 0.1,
-                        maximum_distance: config.graphics.shadow_distance,
-                        first_cascade_far_bound: config.graphics.shadow_distance / 15.0,
-                        overlap_proportion: 0.2,
-                    }
-                    .build()
-                }
+            light.shadows_enabled = value != ShadowSetting::Off;
+            if value != ShadowSetting::Off {
+                *cascades = config.graphics.cascade_shadow_config(tier);
             }
         }
 
+        let max_size = device
+            .as_ref()
+            .map(|device| device.limits().max_texture_dimension_2d as usize)
+            .unwrap_or(usize::MAX);
+        commands.insert_resource(DirectionalLightShadowMap {
+            size: config.graphics.shadow_map_size(tier).min(max_size),
+        });
+
         let primary_cam = cam_res.0;
-        self.apply_to_camera(&(config, cam_res, lights), commands, primary_cam);
+        self.apply_to_camera(
+            &(config, cam_res, lights, adapter, device),
+            commands,
+            primary_cam,
+        );
     }
 
     fn apply_to_camera(
@@ -220,3 +213,107 @@ impl AppSetting for ShadowCasterCountSetting {
         super::SettingCategory::Graphics
     }
 }
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ShadowCascadesSetting(i32);
+
+impl IntAppSetting for ShadowCascadesSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        4
+    }
+
+    fn display(&self) -> String {
+        match self.0 {
+            0 => "Auto".to_owned(),
+            count => format!("{count}"),
+        }
+    }
+}
+
+impl AppSetting for ShadowCascadesSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Shadow Cascades".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Shadow Cascades\n\nNumber of shadow maps used to cover the Shadow Distance with High quality shadows. More cascades give sharper shadows at all distances, but each cascade renders the shadow casters again, increasing GPU time. Low quality shadows always use a single cascade.\n\nAuto: Picks a value based on your GPU.".to_owned()
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.graphics.shadow_cascades as i32)
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.shadow_cascades = self.0 as usize
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via ShadowSetting
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Graphics
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ShadowMapSizeSetting(usize);
+
+impl EnumAppSetting for ShadowMapSizeSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self(0), Self(1024), Self(2048), Self(4096), Self(8192)]
+    }
+
+    fn name(&self) -> String {
+        match self.0 {
+            0 => "Auto".to_owned(),
+            size => format!("{size}"),
+        }
+    }
+}
+
+impl AppSetting for ShadowMapSizeSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Shadow Resolution".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Shadow Resolution\n\nSize of the sun's shadow map textures. Higher resolutions give sharper shadow edges but use more GPU memory and time.\n\n{}",
+        match self.0 {
+            0 => "Auto: Picks a resolution based on your GPU.".to_owned(),
+            size => format!("{size}: Each cascade uses a {size}x{size} texture."),
+        })
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.graphics.shadow_map_size)
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.shadow_map_size = self.0
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied via ShadowSetting
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Graphics
+    }
+}
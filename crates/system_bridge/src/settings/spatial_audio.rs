@@ -0,0 +1,61 @@
+use bevy::{ecs::system::lifetimeless::SResMut, prelude::*};
+use common::structs::{AppConfig, AudioSettings};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SpatialAudioSetting {
+    Stereo,
+    Headphones,
+}
+
+impl EnumAppSetting for SpatialAudioSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Stereo, Self::Headphones]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            SpatialAudioSetting::Stereo => "Stereo",
+            SpatialAudioSetting::Headphones => "Headphones",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for SpatialAudioSetting {
+    type Param = SResMut<AudioSettings>;
+
+    fn title() -> String {
+        "Spatial Audio".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Spatial Audio\n\nHow the direction of nearby voice chat is reproduced.\n\n{}",
+            match self {
+                SpatialAudioSetting::Stereo => "Stereo: Voices are panned between the left and right speakers.",
+                SpatialAudioSetting::Headphones => "Headphones: Voices are positioned around your head with binaural processing, so you can tell whether other players are in front or behind. Scene sounds are still panned in stereo. Best with headphones.",
+            }
+        )
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.audio.headphones = *self == SpatialAudioSetting::Headphones;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        if config.audio.headphones {
+            Self::Headphones
+        } else {
+            Self::Stereo
+        }
+    }
+
+    fn apply(&self, mut settings: ResMut<AudioSettings>, _: Commands) {
+        settings.headphones = *self == SpatialAudioSetting::Headphones;
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Audio
+    }
+}
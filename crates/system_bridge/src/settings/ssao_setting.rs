@@ -3,7 +3,7 @@ use bevy::{
     pbr::{ScreenSpaceAmbientOcclusionBundle, ScreenSpaceAmbientOcclusionSettings},
     prelude::*,
 };
-use common::structs::{AppConfig, PrimaryCameraRes, SsaoSetting};
+use common::structs::{AppConfig, PowerSaving, PrimaryCameraRes, SsaoSetting};
 
 use super::{AppSetting, EnumAppSetting};
 
@@ -23,7 +23,7 @@ impl EnumAppSetting for SsaoSetting {
 }
 
 impl AppSetting for SsaoSetting {
-    type Param = (SRes<PrimaryCameraRes>, SRes<Msaa>);
+    type Param = (SRes<PrimaryCameraRes>, SRes<Msaa>, SRes<PowerSaving>);
 
     fn title() -> String {
         "SSAO".to_owned()
@@ -50,14 +50,18 @@ impl AppSetting for SsaoSetting {
         super::SettingCategory::Graphics
     }
 
-    fn apply(&self, (cam_res, msaa_res): SystemParamItem<Self::Param>, commands: Commands) {
+    fn apply(
+        &self,
+        (cam_res, msaa_res, power_saving): SystemParamItem<Self::Param>,
+        commands: Commands,
+    ) {
         let primary_cam = cam_res.0;
-        self.apply_to_camera(&(cam_res, msaa_res), commands, primary_cam);
+        self.apply_to_camera(&(cam_res, msaa_res, power_saving), commands, primary_cam);
     }
 
     fn apply_to_camera(
         &self,
-        (_, msaa_res): &SystemParamItem<Self::Param>,
+        (_, msaa_res, power_saving): &SystemParamItem<Self::Param>,
         mut commands: Commands,
         camera_entity: Entity,
     ) {
@@ -69,7 +73,13 @@ impl AppSetting for SsaoSetting {
             warn!("SSAO disabled due to MSAA setting");
         }
 
-        match (msaa_res.samples() > 1, self) {
+        let setting = if power_saving.0 {
+            &SsaoSetting::Off
+        } else {
+            self
+        };
+
+        match (msaa_res.samples() > 1, setting) {
             (_, SsaoSetting::Off) | (true, _) => {
                 cmds.remove::<ScreenSpaceAmbientOcclusionSettings>()
             }
@@ -88,3 +98,17 @@ impl AppSetting for SsaoSetting {
         };
     }
 }
+
+// ssao is the only setting applied once that depends on power saving, so reapply just it when
+// power saving toggles rather than rerunning every setting
+pub(crate) fn reapply_ssao_on_power_change(
+    config: Res<AppConfig>,
+    cam_res: Res<PrimaryCameraRes>,
+    msaa_res: Res<Msaa>,
+    power_saving: Res<PowerSaving>,
+    commands: Commands,
+) {
+    if power_saving.is_changed() && !power_saving.is_added() {
+        SsaoSetting::load(&config).apply((cam_res, msaa_res, power_saving), commands);
+    }
+}
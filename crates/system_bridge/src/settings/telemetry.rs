@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, EnumAppSetting};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TelemetrySetting {
+    Off,
+    On,
+}
+
+impl EnumAppSetting for TelemetrySetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::Off, Self::On]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            TelemetrySetting::Off => "Off",
+            TelemetrySetting::On => "On",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for TelemetrySetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Telemetry".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "Telemetry.\n\nOn: Aggregate usage metrics (frame time percentiles, scene load times, realm changes and whether the previous session crashed) are sent to help improve the explorer, and crash reports can be sent after a crash.\n\nOff: Nothing is sent.\n\nUse `/telemetry` in chat to see exactly what is recorded.".to_owned()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.telemetry = matches!(self, TelemetrySetting::On);
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        if config.telemetry {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // setting is handled in analytics::segment_system
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Gameplay
+    }
+}
@@ -0,0 +1,80 @@
+use bevy::math::FloatOrd;
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, IntAppSetting};
+
+macro_rules! text_scale_setting {
+    ($struct:ident, $name:expr, $description:expr, $field:ident) => {
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        pub struct $struct(FloatOrd);
+
+        impl IntAppSetting for $struct {
+            fn from_int(value: i32) -> Self {
+                Self(FloatOrd(value as f32 * Self::scale()))
+            }
+
+            fn value(&self) -> i32 {
+                (self.0 .0 / Self::scale()).round() as i32
+            }
+
+            fn min() -> i32 {
+                10
+            }
+
+            fn max() -> i32 {
+                40
+            }
+
+            fn scale() -> f32 {
+                0.05
+            }
+
+            fn display(&self) -> String {
+                format!("{:.0}%", self.0 .0 * 100.0)
+            }
+        }
+
+        impl AppSetting for $struct {
+            type Param = ();
+
+            fn title() -> String {
+                $name.to_owned()
+            }
+
+            fn description(&self) -> String {
+                format!("{}\n\n{}", $name, $description)
+            }
+
+            fn save(&self, config: &mut AppConfig) {
+                config.$field = self.0 .0;
+            }
+
+            fn load(config: &AppConfig) -> Self {
+                Self(FloatOrd(config.$field))
+            }
+
+            fn category() -> super::SettingCategory {
+                super::SettingCategory::Graphics
+            }
+
+            fn apply(&self, (): (), _: Commands) {
+                // handled in ui_core::text_size
+            }
+        }
+    };
+}
+
+text_scale_setting!(
+    TextScaleSetting,
+    "Text Size",
+    "Size of the text in menus and the interface, relative to the UI Scale.",
+    text_scale
+);
+
+text_scale_setting!(
+    ChatTextScaleSetting,
+    "Chat Text Size",
+    "Size of chat messages, relative to the Text Size.",
+    chat_text_scale
+);
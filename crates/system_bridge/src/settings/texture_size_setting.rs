@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TextureSizeSetting(u32);
+
+impl EnumAppSetting for TextureSizeSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self(512), Self(1024), Self(2048), Self(0)]
+    }
+
+    fn name(&self) -> String {
+        match self.0 {
+            0 => "Unlimited".to_owned(),
+            size => format!("{size}"),
+        }
+    }
+}
+
+impl AppSetting for TextureSizeSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "Texture Size".to_owned()
+    }
+
+    fn description(&self) -> String {
+        format!("Texture Size\n\nThe largest texture size used for scene and avatar content. Larger textures are downscaled when they load, reducing GPU memory use at the cost of detail. Changes apply to newly loaded content.\n\n{}",
+        match self.0 {
+            0 => "Unlimited: Textures are used at their original size.".to_owned(),
+            size => format!("{size}: Textures are limited to {size}x{size}."),
+        })
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.graphics.max_texture_size)
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.max_texture_size = self.0;
+    }
+
+    fn apply(&self, _: (), _: Commands) {
+        // applied in scene_runner as textures load
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Performance
+    }
+}
@@ -0,0 +1,73 @@
+use bevy::{
+    core_pipeline::tonemapping::Tonemapping,
+    ecs::system::{lifetimeless::SRes, SystemParamItem},
+    prelude::*,
+};
+use common::structs::{AppConfig, PrimaryCameraRes, TonemapperSetting};
+
+use super::{AppSetting, EnumAppSetting, SettingCategory};
+
+impl EnumAppSetting for TonemapperSetting {
+    fn variants() -> Vec<Self> {
+        vec![Self::TonyMcMapface, Self::AgX, Self::Reinhard]
+    }
+
+    fn name(&self) -> String {
+        match self {
+            TonemapperSetting::TonyMcMapface => "TonyMcMapface",
+            TonemapperSetting::AgX => "AgX",
+            TonemapperSetting::Reinhard => "Reinhard",
+        }
+        .to_owned()
+    }
+}
+
+impl AppSetting for TonemapperSetting {
+    type Param = SRes<PrimaryCameraRes>;
+
+    fn title() -> String {
+        "Tonemapper".to_owned()
+    }
+
+    fn category() -> SettingCategory {
+        SettingCategory::Graphics
+    }
+
+    fn description(&self) -> String {
+        format!("Tonemapping converts the high dynamic range of the rendered scene into colors your display can show. The choice affects how bright lights, saturated colors and emissive surfaces look.\n\n{}",
+        match self {
+            TonemapperSetting::TonyMcMapface => "TonyMcMapface: Neutral and natural looking, bright colors desaturate smoothly towards white. Recommended.",
+            TonemapperSetting::AgX => "AgX: Filmic look with very good handling of extreme brightness, but slightly muted colors.",
+            TonemapperSetting::Reinhard => "Reinhard: Simple and cheap. Keeps colors saturated, but very bright areas can look flat or shift hue.",
+        })
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.graphics.tonemapper = *self;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        config.graphics.tonemapper
+    }
+
+    fn apply(&self, cam_res: SystemParamItem<Self::Param>, commands: Commands) {
+        self.apply_to_camera(&cam_res, commands, cam_res.0);
+    }
+
+    fn apply_to_camera(
+        &self,
+        _: &SystemParamItem<Self::Param>,
+        mut commands: Commands,
+        camera_entity: Entity,
+    ) {
+        let Some(mut cmds) = commands.get_entity(camera_entity) else {
+            return;
+        };
+
+        cmds.insert(match self {
+            TonemapperSetting::TonyMcMapface => Tonemapping::TonyMcMapface,
+            TonemapperSetting::AgX => Tonemapping::AgX,
+            TonemapperSetting::Reinhard => Tonemapping::Reinhard,
+        });
+    }
+}
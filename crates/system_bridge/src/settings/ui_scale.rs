@@ -0,0 +1,62 @@
+use bevy::math::FloatOrd;
+use bevy::prelude::*;
+use common::structs::AppConfig;
+
+use super::{AppSetting, IntAppSetting};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UiScaleSetting(FloatOrd);
+
+impl IntAppSetting for UiScaleSetting {
+    fn from_int(value: i32) -> Self {
+        Self(FloatOrd(value as f32 * Self::scale()))
+    }
+
+    fn value(&self) -> i32 {
+        (self.0 .0 / Self::scale()).round() as i32
+    }
+
+    fn min() -> i32 {
+        10
+    }
+
+    fn max() -> i32 {
+        40
+    }
+
+    fn scale() -> f32 {
+        0.05
+    }
+
+    fn display(&self) -> String {
+        format!("{:.0}%", self.0 .0 * 100.0)
+    }
+}
+
+impl AppSetting for UiScaleSetting {
+    type Param = ();
+
+    fn title() -> String {
+        "UI Scale".to_owned()
+    }
+
+    fn description(&self) -> String {
+        "UI Scale\n\nSize of the menus and interface. Increase this for small or high resolution screens. Defaults to 125% on Steam Deck, 100% elsewhere.".to_string()
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.ui_scale = self.0 .0;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(FloatOrd(config.ui_scale))
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Graphics
+    }
+
+    fn apply(&self, (): (), _: Commands) {
+        // handled in input_manager::touch
+    }
+}
@@ -67,10 +67,17 @@ volume_setting!(
 volume_setting!(
     SceneVolumeSetting,
     "Scene",
-    "The volume of music and sound effects played by scenes in the world.",
+    "The volume of sound effects and videos played by scenes in the world.",
     |cfg: &mut AudioSettings, val: i32| cfg.scene = val,
     |cfg: &AudioSettings| cfg.scene
 );
+volume_setting!(
+    MediaVolumeSetting,
+    "Music and Stream",
+    "The volume of music and radio streams played by scenes in the world.",
+    |cfg: &mut AudioSettings, val: i32| cfg.media = val,
+    |cfg: &AudioSettings| cfg.media
+);
 volume_setting!(
     VoiceVolumeSetting,
     "Voice",
@@ -93,5 +100,54 @@ volume_setting!(
     |cfg: &AudioSettings| cfg.avatar
 );
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct VoiceDuckingSetting(i32);
+
+impl IntAppSetting for VoiceDuckingSetting {
+    fn from_int(value: i32) -> Self {
+        Self(value)
+    }
+
+    fn value(&self) -> i32 {
+        self.0
+    }
+
+    fn min() -> i32 {
+        0
+    }
+
+    fn max() -> i32 {
+        100
+    }
+}
+
+impl AppSetting for VoiceDuckingSetting {
+    type Param = SResMut<AudioSettings>;
+
+    fn title() -> String {
+        "Voice Ducking".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Voice Ducking\n\nHow much scene sounds, music and streams are lowered while you or a nearby player are talking. 0 leaves them unchanged.".to_string()
+    }
+
+    fn apply(&self, mut settings: ResMut<AudioSettings>, _: Commands) {
+        settings.ducking = self.0;
+    }
+
+    fn save(&self, config: &mut AppConfig) {
+        config.audio.ducking = self.0;
+    }
+
+    fn load(config: &AppConfig) -> Self {
+        Self(config.audio.ducking)
+    }
+
+    fn category() -> super::SettingCategory {
+        super::SettingCategory::Audio
+    }
+}
+
 // impl AppSetting for MasterVolumeSetting {
 // }
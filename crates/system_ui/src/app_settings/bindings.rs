@@ -0,0 +1,410 @@
+// input binding rows for the settings tab. clicking a binding opens a capture popup which takes
+// the next key, mouse button or gamepad button, and offers to swap if it is already in use.
+// pressing with a modifier held captures a chord, and holding or double-tapping the input
+// captures a hold or double-tap binding.
+
+use bevy::{input::InputSystem, prelude::*, ui::UiSystem};
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use common::{
+    structs::{AppConfig, InputBinding, InputBindings, InputGesture, InputItem, InputModifier},
+    tr,
+};
+use dcl_component::proto_components::sdk::components::common::InputAction;
+use input_manager::{
+    gestures::{DOUBLE_TAP_SECS, HOLD_SECS},
+    BindingProfile, InputMap,
+};
+use ui_core::{
+    button::DuiButton,
+    gamepad_nav::NavScope,
+    ui_actions::{Click, Enabled, HoverEnter, On},
+};
+
+use crate::profile::SettingsDialog;
+
+use super::{AppSettingDescription, AppSettingsDetail, SettingEntry, SettingsPage};
+
+const DESCRIPTION: &str = "Input bindings for keyboard and mouse (left) and gamepad (right).\n\nClick a binding, then press the new key or button. Hold Ctrl, Shift or Alt while pressing to bind a combination, hold the input to bind a long press, or press it twice quickly to bind a double tap.\n\nIf it is already used by another action you can swap them.";
+
+const CAPTURE_HINT: &str =
+    "Hold it for a long press, or press it twice for a double tap.\n\nEscape to cancel.";
+
+pub struct BindingSettingsPlugin;
+
+impl Plugin for BindingSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            capture_binding.after(InputSystem).before(UiSystem::Focus),
+        );
+        app.add_systems(Update, refresh_binding_values);
+    }
+}
+
+#[derive(Component)]
+struct BindingValue {
+    action: InputAction,
+    profile: BindingProfile,
+}
+
+#[derive(Component)]
+struct BindingCapture {
+    action: InputAction,
+    profile: BindingProfile,
+    pending: Option<PendingCapture>,
+}
+
+// an input that has been pressed, waiting to see if it is held or pressed again
+struct PendingCapture {
+    binding: InputBinding,
+    pressed_at: f32,
+    released_at: Option<f32>,
+}
+
+fn action_name(action: InputAction) -> &'static str {
+    match action {
+        InputAction::IaPointer => "Pointer",
+        InputAction::IaPrimary => "Primary Action",
+        InputAction::IaSecondary => "Secondary Action",
+        InputAction::IaAny => "Any",
+        InputAction::IaForward => "Forward",
+        InputAction::IaBackward => "Backward",
+        InputAction::IaRight => "Right",
+        InputAction::IaLeft => "Left",
+        InputAction::IaJump => "Jump",
+        InputAction::IaWalk => "Walk",
+        InputAction::IaAction3 => "Action 3",
+        InputAction::IaAction4 => "Action 4",
+        InputAction::IaAction5 => "Action 5",
+        InputAction::IaAction6 => "Action 6",
+    }
+}
+
+fn binding_label(map: &InputMap, profile: BindingProfile, action: InputAction) -> String {
+    map.get_binding(profile, action)
+        .map(|item| item.to_string())
+        .unwrap_or_else(|| "-".to_owned())
+}
+
+fn set_binding(
+    bindings: &mut InputBindings,
+    profile: BindingProfile,
+    action: InputAction,
+    binding: InputBinding,
+) {
+    let overrides = match profile {
+        BindingProfile::Keyboard => &mut bindings.keyboard,
+        BindingProfile::Gamepad => &mut bindings.gamepad,
+    };
+    overrides.insert(action.as_str_name().to_owned(), binding);
+}
+
+pub(super) fn spawn_binding_settings(
+    commands: &mut Commands,
+    dui: &DuiRegistry,
+    config: &AppConfig,
+) -> Vec<Entity> {
+    let map = InputMap::from_bindings(&config.input_bindings);
+    let mut children = Vec::default();
+
+    for (category, actions) in InputMap::CATEGORIES {
+        let header = commands
+            .spawn_template(
+                dui,
+                "settings-header",
+                DuiProps::new().with_prop("label", format!("{category} Controls")),
+            )
+            .unwrap()
+            .root;
+        commands.entity(header).insert(SettingEntry {
+            page: SettingsPage::Controls,
+            search_text: None,
+        });
+        children.push(header);
+
+        for &action in actions {
+            let components = commands
+                .spawn_template(
+                    dui,
+                    "binding-setting",
+                    DuiProps::new()
+                        .with_prop("title", action_name(action).to_owned())
+                        .with_prop(
+                            "keyboard-initial",
+                            binding_label(&map, BindingProfile::Keyboard, action),
+                        )
+                        .with_prop(
+                            "gamepad-initial",
+                            binding_label(&map, BindingProfile::Gamepad, action),
+                        ),
+                )
+                .unwrap();
+
+            commands.entity(components.root).insert((
+                SettingEntry {
+                    page: SettingsPage::Controls,
+                    search_text: Some(action_name(action).to_lowercase()),
+                },
+                Interaction::default(),
+                On::<HoverEnter>::new(
+                    |mut description: Query<&mut Text, With<AppSettingDescription>>| {
+                        description.single_mut().sections[0].value = DESCRIPTION.to_owned();
+                    },
+                ),
+            ));
+
+            for (profile, button, label) in [
+                (BindingProfile::Keyboard, "keyboard", "keyboard-label"),
+                (BindingProfile::Gamepad, "gamepad", "gamepad-label"),
+            ] {
+                commands.entity(components.named(button)).insert((
+                    Interaction::default(),
+                    On::<Click>::new(start_capture(action, profile)),
+                ));
+                commands
+                    .entity(components.named(label))
+                    .insert(BindingValue { action, profile });
+            }
+        }
+    }
+
+    children
+}
+
+fn start_capture(
+    action: InputAction,
+    profile: BindingProfile,
+) -> impl FnMut(Commands, Res<DuiRegistry>) {
+    move |mut commands: Commands, dui: Res<DuiRegistry>| {
+        let prompt = match profile {
+            BindingProfile::Keyboard => "Press a key or mouse button",
+            BindingProfile::Gamepad => "Press a gamepad button",
+        };
+        let root = commands
+            .spawn_template(
+                &dui,
+                "text-dialog",
+                DuiProps::new()
+                    .with_prop("title", format!("Rebind {}", action_name(action)))
+                    .with_prop("body", format!("{prompt}. {CAPTURE_HINT}"))
+                    .with_prop(
+                        "buttons",
+                        vec![DuiButton {
+                            back: true,
+                            ..DuiButton::close_silent("Cancel")
+                        }],
+                    ),
+            )
+            .unwrap()
+            .root;
+        commands.entity(root).insert((
+            BindingCapture {
+                action,
+                profile,
+                pending: None,
+            },
+            NavScope,
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn capture_binding(
+    mut commands: Commands,
+    mut capture: Query<(Entity, &mut BindingCapture)>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse: ResMut<ButtonInput<MouseButton>>,
+    mut gamepad: ResMut<ButtonInput<GamepadButton>>,
+    buttons: Query<&Interaction, With<Enabled>>,
+    mut q: Query<(&mut SettingsDialog, &mut AppSettingsDetail)>,
+    dui: Res<DuiRegistry>,
+    time: Res<Time<Real>>,
+    config: Res<AppConfig>,
+) {
+    let Ok((capture_ent, mut capture)) = capture.get_single_mut() else {
+        return;
+    };
+    let (action, profile) = (capture.action, capture.profile);
+
+    if keys.just_pressed(KeyCode::Escape) {
+        keys.clear_just_pressed(KeyCode::Escape);
+        commands.entity(capture_ent).despawn_recursive();
+        return;
+    }
+
+    // take the input so it doesn't also act on the ui or the world
+    let item = match profile {
+        BindingProfile::Keyboard => {
+            if let Some(key) = keys.get_just_pressed().next().copied() {
+                keys.clear_just_pressed(key);
+                Some(InputItem::Key(key))
+            } else if buttons.iter().any(|i| i != &Interaction::None) {
+                // clicking the popup's buttons
+                None
+            } else if let Some(mb) = mouse.get_just_pressed().next().copied() {
+                mouse.clear_just_pressed(mb);
+                Some(InputItem::Mouse(mb))
+            } else {
+                None
+            }
+        }
+        BindingProfile::Gamepad => gamepad.get_just_pressed().next().copied().map(|button| {
+            gamepad.clear_just_pressed(button);
+            InputItem::Gamepad(button.button_type)
+        }),
+    };
+
+    let now = time.elapsed_seconds();
+    let scale = config.input_accessibility.timing_scale;
+    let held_modifier = || {
+        InputModifier::ALL
+            .into_iter()
+            .find(|m| m.keys().iter().any(|k| keys.pressed(*k)))
+    };
+    let is_modifier =
+        |item: InputItem| matches!(item, InputItem::Key(k) if InputModifier::from_key(k).is_some());
+
+    let binding = match (item, capture.pending.as_mut()) {
+        // pressed again after releasing
+        (Some(item), Some(pending))
+            if pending.binding.item == item && pending.released_at.is_some() =>
+        {
+            Some(InputBinding {
+                gesture: InputGesture::DoubleTap,
+                ..pending.binding
+            })
+        }
+        (Some(item), _) => {
+            // an input pressed while a modifier is held makes a chord, unless it is a modifier
+            let modifier = held_modifier()
+                .filter(|_| profile == BindingProfile::Keyboard && !is_modifier(item));
+            capture.pending = Some(PendingCapture {
+                binding: InputBinding {
+                    item,
+                    modifier,
+                    gesture: InputGesture::Press,
+                },
+                pressed_at: now,
+                released_at: None,
+            });
+            None
+        }
+        (None, Some(pending)) => {
+            let held = match pending.binding.item {
+                InputItem::Key(k) => keys.pressed(k),
+                InputItem::Mouse(mb) => mouse.pressed(mb),
+                InputItem::Gamepad(b) => gamepad.get_pressed().any(|g| g.button_type == b),
+                InputItem::Any => false,
+            };
+            // modifiers may be held a while before the chord's key, so they can't be captured
+            // as a hold
+            if held
+                && !is_modifier(pending.binding.item)
+                && now - pending.pressed_at >= HOLD_SECS * scale
+            {
+                Some(InputBinding {
+                    gesture: InputGesture::Hold,
+                    ..pending.binding
+                })
+            } else if !held && pending.released_at.is_none() {
+                pending.released_at = Some(now);
+                None
+            } else if pending
+                .released_at
+                .is_some_and(|released| now - released > DOUBLE_TAP_SECS * scale)
+            {
+                Some(pending.binding)
+            } else {
+                None
+            }
+        }
+        (None, None) => None,
+    };
+    let Some(binding) = binding else {
+        return;
+    };
+
+    commands.entity(capture_ent).despawn_recursive();
+
+    let Ok((mut dialog, mut detail)) = q.get_single_mut() else {
+        return;
+    };
+    let map = InputMap::from_bindings(&detail.0.input_bindings);
+    let previous = map.get_binding(profile, action);
+
+    match map.get_action(profile, binding) {
+        Some(other) if other == action => (),
+        Some(other) => {
+            let body = match previous {
+                Some(previous) => format!(
+                    "{binding} is already bound to {}. Swap it with {previous}?",
+                    action_name(other)
+                ),
+                None => format!(
+                    "{binding} is already bound to {}. Move it to {}?",
+                    action_name(other),
+                    action_name(action)
+                ),
+            };
+            let root = commands
+                .spawn_template(
+                    &dui,
+                    "text-dialog",
+                    DuiProps::new()
+                        .with_prop("title", "Binding Conflict".to_owned())
+                        .with_prop("body", body)
+                        .with_prop(
+                            "buttons",
+                            vec![
+                                DuiButton::new_enabled_and_close_happy(
+                                    "Swap",
+                                    move |mut q: Query<(
+                                        &mut SettingsDialog,
+                                        &mut AppSettingsDetail,
+                                    )>| {
+                                        let Ok((mut dialog, mut detail)) = q.get_single_mut()
+                                        else {
+                                            return;
+                                        };
+                                        let bindings = &mut detail.0.input_bindings;
+                                        set_binding(bindings, profile, action, binding);
+                                        if let Some(previous) = previous {
+                                            set_binding(bindings, profile, other, previous);
+                                        }
+                                        dialog.modified = true;
+                                    },
+                                ),
+                                DuiButton {
+                                    back: true,
+                                    ..DuiButton::close_sad(tr!("button-cancel"))
+                                },
+                            ],
+                        ),
+                )
+                .unwrap()
+                .root;
+            commands.entity(root).insert(NavScope);
+        }
+        None => {
+            set_binding(&mut detail.0.input_bindings, profile, action, binding);
+            dialog.modified = true;
+        }
+    }
+}
+
+fn refresh_binding_values(
+    detail: Query<&AppSettingsDetail, Changed<AppSettingsDetail>>,
+    mut labels: Query<(&BindingValue, &mut Text)>,
+) {
+    let Ok(detail) = detail.get_single() else {
+        return;
+    };
+
+    let map = InputMap::from_bindings(&detail.0.input_bindings);
+    for (value, mut text) in labels.iter_mut() {
+        let label = binding_label(&map, value.profile, value.action);
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+}
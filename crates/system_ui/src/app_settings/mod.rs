@@ -1,50 +1,164 @@
+use std::path::PathBuf;
+
 use bevy::{ecs::system::StaticSystemParam, prelude::*, ui::RelativeCursorPosition};
 use bevy_dui::{DuiCommandsExt, DuiEntities, DuiEntityCommandsExt, DuiProps, DuiRegistry};
-use common::structs::{
-    AaSetting, AppConfig, BloomSetting, FogSetting, SettingsTab, ShadowSetting, SsaoSetting,
-    WindowSetting,
+use common::{
+    structs::{
+        AaSetting, AppConfig, BloomSetting, FogSetting, GraphicsPreset, PowerSaveSetting,
+        RenderScaleSetting, SettingsTab, ShadowSetting, SsaoSetting, TonemapperSetting,
+        WindowSetting,
+    },
+    tr,
+    util::config_file,
+};
+use system_bridge::{
+    settings::{AppSetting, EnumAppSetting, IntAppSetting},
+    SystemApi,
+};
+use ui_core::{
+    button::{DuiButton, TabSelection},
+    text_entry::TextEntryValue,
+    ui_actions::{Click, ClickRepeat, DataChanged, HoverEnter, On, UiCaller},
 };
-use system_bridge::settings::{EnumAppSetting, IntAppSetting};
-use ui_core::ui_actions::{Click, ClickRepeat, HoverEnter, On, UiCaller};
+use wallet::session_store::{self, login_expiration};
 
-use crate::profile::SettingsDialog;
+use crate::{
+    hud_layout::begin_hud_edit,
+    profile::{close_settings, SettingsDialog},
+};
 
 use system_bridge::settings::{
+    accessibility_settings::{
+        PressTimingSetting, PushToTalkModeSetting, RepeatDelaySetting, RepeatIntervalSetting,
+        WalkModeSetting,
+    },
     ambient_brightness_setting::AmbientSetting,
+    audio_device::{InputDeviceSetting, OutputDeviceSetting},
+    bloom_settings::{BloomIntensitySetting, BloomThresholdSetting},
+    camera_settings::{
+        CameraShakeSetting, FirstPersonFovSetting, HeadBobSetting, ThirdPersonFovSetting,
+    },
+    color_lut_settings::{ColorLutSetting, SceneColorLutSetting},
     constrain_ui::ConstrainUiSetting,
+    default_permissions::{
+        FetchPermissionSetting, OpenUrlPermissionSetting, Web3PermissionSetting,
+        WebsocketPermissionSetting,
+    },
     despawn_workaround::DespawnWorkaroundSetting,
+    dynamic_scale_settings::{DynamicScaleMaxSetting, DynamicScaleMinSetting},
+    footsteps::FootstepsSetting,
     frame_rate::FpsTargetSetting,
+    gamepad_rumble::GamepadRumbleSetting,
+    input_axis_settings::{
+        LookStickCurveSetting, LookStickDeadZoneSetting, LookStickSensitivitySetting,
+        MouseSensitivitySetting, MoveStickCurveSetting, MoveStickDeadZoneSetting,
+        TriggerDeadZoneSetting,
+    },
     load_distance::{LoadDistanceSetting, UnloadDistanceSetting},
     max_avatars::MaxAvatarsSetting,
     max_downloads::MaxDownloadsSetting,
+    max_scene_particles::MaxSceneParticlesSetting,
+    memory_limits::{MemoryLimitSetting, TextureMemoryLimitSetting},
+    notification_settings::{
+        FriendRequestNotificationSetting, MentionNotificationSetting, PermissionNotificationSetting,
+    },
+    now_playing::NowPlayingSetting,
     oob_setting::OobSetting,
     player_settings::{
         FallSpeedSetting, FrictionSetting, GravitySetting, JumpSetting, RunSpeedSetting,
         WalkSpeedSetting,
     },
+    power_save::PowerSaveFpsSetting,
+    remember_login::RememberLoginSetting,
     scene_threads::SceneThreadsSetting,
+    shadow_settings::ShadowCascadesSetting,
     shadow_settings::ShadowCasterCountSetting,
     shadow_settings::ShadowDistanceSetting,
+    shadow_settings::ShadowMapSizeSetting,
+    spatial_audio::SpatialAudioSetting,
+    texture_size_setting::TextureSizeSetting,
+    ui_scale::UiScaleSetting,
     video_threads::VideoThreadsSetting,
     volume_settings::{
-        AvatarVolumeSetting, MasterVolumeSetting, SceneVolumeSetting, SystemVolumeSetting,
-        VoiceVolumeSetting,
+        AvatarVolumeSetting, MasterVolumeSetting, MediaVolumeSetting, SceneVolumeSetting,
+        SystemVolumeSetting, VoiceDuckingSetting, VoiceVolumeSetting,
     },
 };
 
 // use self::window_settings::{set_resolutions, MonitorResolutions};
 
+mod bindings;
+
 pub struct AppSettingsPlugin;
 
 impl Plugin for AppSettingsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, set_app_settings_content);
+        app.add_plugins(bindings::BindingSettingsPlugin);
+        app.add_systems(
+            Update,
+            (
+                set_app_settings_content,
+                refresh_setting_values,
+                filter_settings,
+            ),
+        );
     }
 }
 
 #[derive(Component)]
 pub struct AppSettingsDetail(pub AppConfig);
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(super) enum SettingsPage {
+    #[default]
+    Graphics,
+    Audio,
+    Controls,
+    Chat,
+    Privacy,
+    Network,
+    Developer,
+}
+
+impl SettingsPage {
+    const ALL: [SettingsPage; 7] = [
+        SettingsPage::Graphics,
+        SettingsPage::Audio,
+        SettingsPage::Controls,
+        SettingsPage::Chat,
+        SettingsPage::Privacy,
+        SettingsPage::Network,
+        SettingsPage::Developer,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SettingsPage::Graphics => "Graphics",
+            SettingsPage::Audio => "Audio",
+            SettingsPage::Controls => "Controls",
+            SettingsPage::Chat => "Chat",
+            SettingsPage::Privacy => "Privacy",
+            SettingsPage::Network => "Cache/Network",
+            SettingsPage::Developer => "Developer",
+        }
+    }
+}
+
+// a setting row or sub-header. rows are shown when their page is selected or when they match the
+// search, sub-headers only when their page is selected and there is no search
+#[derive(Component)]
+pub(super) struct SettingEntry {
+    pub page: SettingsPage,
+    // lowercase title, None for sub-headers
+    pub search_text: Option<String>,
+}
+
+#[derive(Component, Default)]
+struct SettingsFilter {
+    page: SettingsPage,
+    search: String,
+}
+
 #[allow(clippy::type_complexity)]
 fn set_app_settings_content(
     mut commands: Commands,
@@ -83,80 +197,307 @@ fn set_app_settings_content(
         };
 
         commands.entity(ent).despawn_descendants();
+
+        let category_tabs = SettingsPage::ALL
+            .iter()
+            .map(|page| DuiButton {
+                label: Some(page.label().to_owned()),
+                enabled: true,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        let props = DuiProps::new()
+            .with_prop("category-tabs", category_tabs)
+            .with_prop("initial-category", Some(0usize))
+            .with_prop(
+                "category-changed",
+                On::<DataChanged>::new(
+                    |caller: Res<UiCaller>,
+                     selected: Query<&TabSelection>,
+                     mut filter: Query<&mut SettingsFilter>| {
+                        let Some(page) = selected
+                            .get(caller.0)
+                            .ok()
+                            .and_then(|selection| selection.selected)
+                            .and_then(|ix| SettingsPage::ALL.get(ix))
+                        else {
+                            return;
+                        };
+                        if let Ok(mut filter) = filter.get_single_mut() {
+                            filter.page = *page;
+                        }
+                    },
+                ),
+            )
+            .with_prop(
+                "search-changed",
+                On::<DataChanged>::new(
+                    |caller: Res<UiCaller>,
+                     q: Query<&TextEntryValue>,
+                     mut filter: Query<&mut SettingsFilter>| {
+                        let Ok(value) = q.get(caller.0).map(|te| te.0.trim().to_lowercase()) else {
+                            warn!("no value from text entry?");
+                            return;
+                        };
+                        if let Ok(mut filter) = filter.get_single_mut() {
+                            if filter.search != value {
+                                filter.search = value;
+                            }
+                        }
+                    },
+                ),
+            )
+            .with_prop(
+                "buttons",
+                vec![
+                    DuiButton::new_enabled(tr!("button-import"), import_settings),
+                    DuiButton::new_enabled(tr!("button-export"), export_settings),
+                    DuiButton::new_enabled(
+                        tr!("button-edit-hud"),
+                        close_settings.pipe(begin_hud_edit),
+                    ),
+                ],
+            );
+
         let components = commands
             .entity(ent)
-            .apply_template(&dui, "settings-tab", DuiProps::new())
+            .apply_template(&dui, "settings-tab", props)
             .unwrap();
 
-        let children = vec![
-            commands
-                .spawn_template(
-                    &dui,
-                    "settings-header",
-                    DuiProps::new().with_prop("label", "Graphics Settings".to_owned()),
-                )
-                .unwrap()
-                .root,
-            spawn_enum_setting_template::<WindowSetting>(&mut commands, &dui, &config),
-            // spawn_enum_setting_template::<FullscreenResSetting>(&mut commands, &dui, &config),
-            spawn_enum_setting_template::<AaSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<AmbientSetting>(&mut commands, &dui, &config),
-            spawn_enum_setting_template::<ShadowSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<ShadowDistanceSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<ShadowCasterCountSetting>(&mut commands, &dui, &config),
-            spawn_enum_setting_template::<FogSetting>(&mut commands, &dui, &config),
-            spawn_enum_setting_template::<BloomSetting>(&mut commands, &dui, &config),
-            spawn_enum_setting_template::<SsaoSetting>(&mut commands, &dui, &config),
-            spawn_enum_setting_template::<OobSetting>(&mut commands, &dui, &config),
-            spawn_enum_setting_template::<ConstrainUiSetting>(&mut commands, &dui, &config),
-            commands
-                .spawn_template(
-                    &dui,
-                    "settings-header",
-                    DuiProps::new().with_prop("label", "Performance Settings".to_owned()),
-                )
-                .unwrap()
-                .root,
-            spawn_int_setting_template::<LoadDistanceSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<UnloadDistanceSetting>(&mut commands, &dui, &config),
-            spawn_enum_setting_template::<FpsTargetSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<SceneThreadsSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<VideoThreadsSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<MaxAvatarsSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<MaxDownloadsSetting>(&mut commands, &dui, &config),
-            spawn_enum_setting_template::<DespawnWorkaroundSetting>(&mut commands, &dui, &config),
-            commands
-                .spawn_template(
-                    &dui,
-                    "settings-header",
-                    DuiProps::new().with_prop("label", "Audio Settings".to_owned()),
-                )
-                .unwrap()
-                .root,
-            spawn_int_setting_template::<MasterVolumeSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<SceneVolumeSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<VoiceVolumeSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<SystemVolumeSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<AvatarVolumeSetting>(&mut commands, &dui, &config),
-            commands
-                .spawn_template(
-                    &dui,
-                    "settings-header",
-                    DuiProps::new().with_prop("label", "Player Dynamics Settings".to_owned()),
-                )
-                .unwrap()
-                .root,
-            spawn_int_setting_template::<RunSpeedSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<WalkSpeedSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<FrictionSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<JumpSetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<GravitySetting>(&mut commands, &dui, &config),
-            spawn_int_setting_template::<FallSpeedSetting>(&mut commands, &dui, &config),
-        ];
+        let mut children = Vec::default();
+
+        let page = SettingsPage::Graphics;
+        children.extend([
+            spawn_header(&mut commands, &dui, page, "Display"),
+            spawn_enum_setting_template::<GraphicsPreset>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<WindowSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<UiScaleSetting>(&mut commands, &dui, &config, page),
+            // spawn_enum_setting_template::<FullscreenResSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<ConstrainUiSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<FpsTargetSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<AaSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<RenderScaleSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<DynamicScaleMinSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_int_setting_template::<DynamicScaleMaxSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_enum_setting_template::<TextureSizeSetting>(&mut commands, &dui, &config, page),
+            spawn_header(&mut commands, &dui, page, "Lighting"),
+            spawn_int_setting_template::<AmbientSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<ShadowSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<ShadowDistanceSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<ShadowCascadesSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<ShadowMapSizeSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<ShadowCasterCountSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_enum_setting_template::<FogSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<BloomSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<BloomIntensitySetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<BloomThresholdSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<TonemapperSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<ColorLutSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<SceneColorLutSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<SsaoSetting>(&mut commands, &dui, &config, page),
+            spawn_header(&mut commands, &dui, page, "Camera"),
+            spawn_int_setting_template::<FirstPersonFovSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<ThirdPersonFovSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<HeadBobSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<CameraShakeSetting>(&mut commands, &dui, &config, page),
+            spawn_header(&mut commands, &dui, page, "Performance"),
+            spawn_enum_setting_template::<PowerSaveSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<PowerSaveFpsSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<MaxAvatarsSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<MaxSceneParticlesSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+        ]);
+
+        let page = SettingsPage::Audio;
+        children.extend([
+            spawn_header(&mut commands, &dui, page, "Volume"),
+            spawn_int_setting_template::<MasterVolumeSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<SceneVolumeSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<MediaVolumeSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<VoiceVolumeSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<SystemVolumeSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<AvatarVolumeSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<VoiceDuckingSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<NowPlayingSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<FootstepsSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<SpatialAudioSetting>(&mut commands, &dui, &config, page),
+            spawn_header(&mut commands, &dui, page, "Devices"),
+            spawn_enum_setting_template::<OutputDeviceSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<InputDeviceSetting>(&mut commands, &dui, &config, page),
+        ]);
+
+        let page = SettingsPage::Controls;
+        children.extend([
+            spawn_header(&mut commands, &dui, page, "Input"),
+            spawn_int_setting_template::<MouseSensitivitySetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_int_setting_template::<MoveStickDeadZoneSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_int_setting_template::<MoveStickCurveSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<LookStickSensitivitySetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_int_setting_template::<LookStickDeadZoneSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_int_setting_template::<LookStickCurveSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<TriggerDeadZoneSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_enum_setting_template::<GamepadRumbleSetting>(&mut commands, &dui, &config, page),
+            spawn_header(&mut commands, &dui, page, "Accessibility"),
+            spawn_enum_setting_template::<WalkModeSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<PressTimingSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<RepeatDelaySetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<RepeatIntervalSetting>(&mut commands, &dui, &config, page),
+        ]);
+        children.extend(bindings::spawn_binding_settings(
+            &mut commands,
+            &dui,
+            &config,
+        ));
+
+        let page = SettingsPage::Chat;
+        children.extend([
+            spawn_header(&mut commands, &dui, page, "Voice Chat"),
+            spawn_enum_setting_template::<PushToTalkModeSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_header(&mut commands, &dui, page, "Notifications"),
+            spawn_enum_setting_template::<MentionNotificationSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_enum_setting_template::<FriendRequestNotificationSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+        ]);
+
+        let page = SettingsPage::Privacy;
+        children.extend([
+            spawn_header(&mut commands, &dui, page, "Scene Permissions"),
+            spawn_enum_setting_template::<Web3PermissionSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_enum_setting_template::<FetchPermissionSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_enum_setting_template::<WebsocketPermissionSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_enum_setting_template::<OpenUrlPermissionSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_enum_setting_template::<PermissionNotificationSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_header(&mut commands, &dui, page, "Session"),
+            spawn_enum_setting_template::<RememberLoginSetting>(&mut commands, &dui, &config, page),
+            spawn_session_setting(&mut commands, &dui, page),
+        ]);
+
+        let page = SettingsPage::Network;
+        children.extend([
+            spawn_header(&mut commands, &dui, page, "Scene Loading"),
+            spawn_int_setting_template::<LoadDistanceSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<UnloadDistanceSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<MaxDownloadsSetting>(&mut commands, &dui, &config, page),
+            spawn_header(&mut commands, &dui, page, "Memory"),
+            spawn_int_setting_template::<MemoryLimitSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<TextureMemoryLimitSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+        ]);
+
+        let page = SettingsPage::Developer;
+        children.extend([
+            spawn_header(&mut commands, &dui, page, "Threads"),
+            spawn_int_setting_template::<SceneThreadsSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<VideoThreadsSetting>(&mut commands, &dui, &config, page),
+            spawn_header(&mut commands, &dui, page, "Debug"),
+            spawn_enum_setting_template::<OobSetting>(&mut commands, &dui, &config, page),
+            spawn_enum_setting_template::<DespawnWorkaroundSetting>(
+                &mut commands,
+                &dui,
+                &config,
+                page,
+            ),
+            spawn_header(&mut commands, &dui, page, "Player Dynamics"),
+            spawn_int_setting_template::<RunSpeedSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<WalkSpeedSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<FrictionSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<JumpSetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<GravitySetting>(&mut commands, &dui, &config, page),
+            spawn_int_setting_template::<FallSpeedSetting>(&mut commands, &dui, &config, page),
+        ]);
 
         commands
             .entity(components.named("settings"))
-            .push_children(&children);
+            .push_children(&children)
+            .insert(SettingsFilter::default());
 
         commands
             .entity(components.named("settings-description"))
@@ -164,9 +505,230 @@ fn set_app_settings_content(
     }
 }
 
+fn spawn_header(
+    commands: &mut Commands,
+    dui: &DuiRegistry,
+    page: SettingsPage,
+    label: &str,
+) -> Entity {
+    let root = commands
+        .spawn_template(
+            dui,
+            "settings-header",
+            DuiProps::new().with_prop("label", label.to_owned()),
+        )
+        .unwrap()
+        .root;
+    commands.entity(root).insert(SettingEntry {
+        page,
+        search_text: None,
+    });
+    root
+}
+
+// the saved login's expiry, with a button to forget it
+fn spawn_session_setting(commands: &mut Commands, dui: &DuiRegistry, page: SettingsPage) -> Entity {
+    let session = match session_store::load_session() {
+        Some(login) => match login_expiration(&login) {
+            Some(exp) => format!(
+                "{:#x}, expires {}",
+                login.root_address,
+                exp.format("%Y-%m-%d %H:%M UTC")
+            ),
+            None => format!("{:#x}", login.root_address),
+        },
+        None => "No saved login".to_owned(),
+    };
+
+    let root = commands
+        .spawn_template(
+            dui,
+            "session-setting",
+            DuiProps::new().with_prop("session", session).with_prop(
+                "sign-out",
+                On::<Click>::new(close_settings.pipe(|mut bridge: EventWriter<SystemApi>| {
+                    bridge.send(SystemApi::SignOutEverywhere);
+                })),
+            ),
+        )
+        .unwrap()
+        .root;
+
+    commands.entity(root).insert((
+        SettingEntry {
+            page,
+            search_text: Some("saved login sign out everywhere".to_owned()),
+        },
+        Interaction::default(),
+        On::<HoverEnter>::new(
+            |mut description: Query<&mut Text, With<AppSettingDescription>>| {
+                description.single_mut().sections[0].value = "Saved Login.\n\nThe login kept for reuse on the next launch, and when it expires.\n\nSign Out Everywhere signs out, deletes the saved login and the key it is encrypted with, and ends any WalletConnect session with your wallet app.".to_owned();
+            },
+        ),
+    ));
+
+    root
+}
+
+fn filter_settings(
+    filter: Query<&SettingsFilter, Changed<SettingsFilter>>,
+    mut entries: Query<(&SettingEntry, &mut Style)>,
+) {
+    let Ok(filter) = filter.get_single() else {
+        return;
+    };
+
+    for (entry, mut style) in entries.iter_mut() {
+        let visible = match (&entry.search_text, filter.search.is_empty()) {
+            (_, true) => entry.page == filter.page,
+            (Some(text), false) => text.contains(&filter.search),
+            (None, false) => false,
+        };
+        let display = if visible {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+// exported settings are written next to the config file
+fn settings_export_file() -> PathBuf {
+    config_file().with_file_name("settings-export.json")
+}
+
+fn export_settings(mut commands: Commands, dui: Res<DuiRegistry>, q: Query<&AppSettingsDetail>) {
+    let Ok(detail) = q.get_single() else {
+        return;
+    };
+
+    // login details stay on this machine
+    let config = AppConfig {
+        previous_login: None,
+        user_id: String::default(),
+        ..detail.0.clone()
+    };
+    let path = settings_export_file();
+    let result = serde_json::to_string_pretty(&config)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| std::fs::write(&path, json).map_err(anyhow::Error::from));
+
+    let (title, body) = match result {
+        Ok(()) => (
+            "Settings Exported",
+            format!("Settings were written to {}", path.display()),
+        ),
+        Err(e) => ("Export Failed", format!("Failed to export settings: {e}")),
+    };
+    commands
+        .spawn_template(
+            &dui,
+            "text-dialog",
+            DuiProps::new()
+                .with_prop("title", title.to_owned())
+                .with_prop("body", body)
+                .with_prop("buttons", vec![DuiButton::close_happy(tr!("button-ok"))]),
+        )
+        .unwrap();
+}
+
+fn import_settings(
+    mut commands: Commands,
+    dui: Res<DuiRegistry>,
+    mut q: Query<(&mut SettingsDialog, &mut AppSettingsDetail)>,
+) {
+    let Ok((mut dialog, mut detail)) = q.get_single_mut() else {
+        return;
+    };
+
+    let path = settings_export_file();
+    let imported = std::fs::read(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| serde_json::from_slice::<AppConfig>(&bytes).map_err(anyhow::Error::from));
+
+    let (title, body) = match imported {
+        Ok(imported) => {
+            detail.0 = AppConfig {
+                previous_login: detail.0.previous_login.clone(),
+                user_id: detail.0.user_id.clone(),
+                ..imported
+            };
+            dialog.modified = true;
+            (
+                "Settings Imported",
+                format!(
+                    "Settings were read from {}. Save changes when closing the settings to apply them.",
+                    path.display()
+                ),
+            )
+        }
+        Err(e) => (
+            "Import Failed",
+            format!("Failed to import settings from {}: {e}", path.display()),
+        ),
+    };
+    commands
+        .spawn_template(
+            &dui,
+            "text-dialog",
+            DuiProps::new()
+                .with_prop("title", title.to_owned())
+                .with_prop("body", body)
+                .with_prop("buttons", vec![DuiButton::close_happy(tr!("button-ok"))]),
+        )
+        .unwrap();
+}
+
 #[derive(Component)]
 struct AppSettingDescription;
 
+// reads a setting's label and marker offset, so displayed values can follow settings that
+// change others (e.g. presets)
+#[derive(Component)]
+struct SettingValue {
+    label: Entity,
+    marker: Option<Entity>,
+    read: fn(&AppConfig) -> (String, Option<f32>),
+}
+
+fn read_enum_setting<S: EnumAppSetting>(config: &AppConfig) -> (String, Option<f32>) {
+    (S::load(config).name(), None)
+}
+
+fn read_int_setting<S: IntAppSetting>(config: &AppConfig) -> (String, Option<f32>) {
+    let value = S::load(config);
+    let offset = (value.value() - S::min()) as f32 / (S::max() - S::min()) as f32;
+    (value.display(), Some(offset))
+}
+
+fn refresh_setting_values(
+    detail: Query<&AppSettingsDetail, Changed<AppSettingsDetail>>,
+    settings: Query<&SettingValue>,
+    mut text: Query<&mut Text>,
+    mut style: Query<&mut Style>,
+) {
+    let Ok(detail) = detail.get_single() else {
+        return;
+    };
+
+    for setting in settings.iter() {
+        let (label, offset) = (setting.read)(&detail.0);
+        if let Ok(mut text) = text.get_mut(setting.label) {
+            if text.sections[0].value != label {
+                text.sections[0].value = label;
+            }
+        }
+        if let Some((marker, offset)) = setting.marker.zip(offset) {
+            if let Ok(mut style) = style.get_mut(marker) {
+                style.left = Val::Percent(offset * 100.0);
+            }
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn bump_enum<S: EnumAppSetting, const I: isize>(
     mut q: Query<(&mut SettingsDialog, &mut AppSettingsDetail)>,
@@ -181,7 +743,8 @@ fn bump_enum<S: EnumAppSetting, const I: isize>(
     let (mut dialog, mut config) = q.single_mut();
     let config = &mut config.0;
     let current = S::load(config);
-    let index = variants.iter().position(|v| v == &current).unwrap();
+    // the current value may not be listed, e.g. an unplugged audio device
+    let index = variants.iter().position(|v| v == &current).unwrap_or(0);
     let next =
         variants.remove(((index as isize + I) + variants.len() as isize) as usize % variants.len());
     S::save(&next, config);
@@ -237,10 +800,27 @@ fn bump_int<S: IntAppSetting, const I: i32>(
     dialog.modified = true;
 }
 
+fn reset_setting<S: AppSetting>(
+    mut q: Query<(&mut SettingsDialog, &mut AppSettingsDetail)>,
+    params: StaticSystemParam<S::Param>,
+    commands: Commands,
+) {
+    let (mut dialog, mut config) = q.single_mut();
+    let default = S::load(&AppConfig::default());
+    if S::load(&config.0) == default {
+        return;
+    }
+    // displayed values are updated by `refresh_setting_values`
+    default.save(&mut config.0);
+    default.apply(params.into_inner(), commands);
+    dialog.modified = true;
+}
+
 fn spawn_enum_setting_template<S: EnumAppSetting>(
     commands: &mut Commands,
     dui: &DuiRegistry,
     config: &AppConfig,
+    page: SettingsPage,
 ) -> Entity {
     let components = commands
         .spawn_template(
@@ -250,11 +830,21 @@ fn spawn_enum_setting_template<S: EnumAppSetting>(
                 .with_prop("title", S::title())
                 .with_prop("label-initial", S::load(config).name())
                 .with_prop("next", On::<Click>::new(bump_enum::<S, 1>))
-                .with_prop("prev", On::<Click>::new(bump_enum::<S, -1>)),
+                .with_prop("prev", On::<Click>::new(bump_enum::<S, -1>))
+                .with_prop("reset", On::<Click>::new(reset_setting::<S>)),
         )
         .unwrap();
 
     commands.entity(components.root).insert((
+        SettingValue {
+            label: components.named("setting-label"),
+            marker: None,
+            read: read_enum_setting::<S>,
+        },
+        SettingEntry {
+            page,
+            search_text: Some(S::title().to_lowercase()),
+        },
         Interaction::default(),
         On::<HoverEnter>::new(
             |q: Query<&AppSettingsDetail>,
@@ -272,6 +862,7 @@ fn spawn_int_setting_template<S: IntAppSetting>(
     commands: &mut Commands,
     dui: &DuiRegistry,
     config: &AppConfig,
+    page: SettingsPage,
 ) -> Entity {
     let initial_offset = (S::load(config).value() - S::min()) as f32 / (S::max() - S::min()) as f32;
 
@@ -284,11 +875,21 @@ fn spawn_int_setting_template<S: IntAppSetting>(
                 .with_prop("initial-offset", format!("{}%", initial_offset * 100.0))
                 .with_prop("label-initial", S::load(config).display())
                 .with_prop("next", On::<ClickRepeat>::new(bump_int::<S, 1>))
-                .with_prop("prev", On::<ClickRepeat>::new(bump_int::<S, -1>)),
+                .with_prop("prev", On::<ClickRepeat>::new(bump_int::<S, -1>))
+                .with_prop("reset", On::<Click>::new(reset_setting::<S>)),
         )
         .unwrap();
 
     commands.entity(components.root).insert((
+        SettingValue {
+            label: components.named("setting-label"),
+            marker: Some(components.named("marker")),
+            read: read_int_setting::<S>,
+        },
+        SettingEntry {
+            page,
+            search_text: Some(S::title().to_lowercase()),
+        },
         Interaction::default(),
         On::<HoverEnter>::new(
             |q: Query<&AppSettingsDetail>,
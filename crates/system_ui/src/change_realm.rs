@@ -1,14 +1,22 @@
+// realm browser: lists favorite and recent realms, then the catalyst realms and the worlds with
+// users online, with each one's latency measured by fetching its `/about`. "best realm" joins the
+// fastest catalyst realm.
+
+use std::time::Instant;
+
 use anyhow::anyhow;
 use bevy::{
     prelude::*,
     tasks::{IoTaskPool, Task},
+    utils::HashMap,
 };
 use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
 use common::{
-    structs::SystemAudio,
+    structs::{AppConfig, SavedRealm, SystemAudio},
+    tr,
     util::{FireEventEx, TaskExt},
 };
-use ipfs::{ChangeRealmEvent, CurrentRealm};
+use ipfs::{ChangeRealmEvent, CurrentRealm, IpfsAssetServer};
 use isahc::AsyncReadResponseExt;
 use serde::Deserialize;
 use ui_core::{
@@ -16,24 +24,175 @@ use ui_core::{
     ui_actions::{Click, On},
 };
 
+use crate::realm_history::{is_favorite, load_thumbnail, toggle_favorite};
+
 #[derive(Event, Default)]
 pub struct ChangeRealmDialog;
 
 #[derive(Component)]
 pub struct ServerList {
-    task: Task<Result<std::vec::Vec<ServerDesc>, anyhow::Error>>,
+    // the live list, until fetched
+    task: Option<Task<Vec<RealmEntry>>>,
     root_id: Entity,
 }
 
+#[derive(Clone, Debug)]
+pub struct RealmEntry {
+    pub name: String,
+    pub url: String,
+    // none for saved realms that aren't in the live list
+    pub users: Option<i32>,
+    pub saved: Option<SavedRealm>,
+}
+
+// on a realm's ping text, until measured
+#[derive(Component)]
+struct RealmPing {
+    task: Task<Option<f32>>,
+    url: String,
+}
+
+// the listed realms and their latencies in ms (none if unreachable)
+#[derive(Resource, Default)]
+pub struct RealmBrowser {
+    pub live: Vec<RealmEntry>,
+    pub realms: Vec<RealmEntry>,
+    pub pings: HashMap<String, Option<f32>>,
+}
+
+impl RealmBrowser {
+    // the lowest latency catalyst realm, or the busiest while none has answered
+    pub fn best_realm(&self) -> Option<&RealmEntry> {
+        let catalysts = self
+            .realms
+            .iter()
+            .filter(|realm| !realm.url.starts_with(WORLDS_SERVER));
+        catalysts
+            .clone()
+            .filter_map(|realm| Some((realm, (*self.pings.get(&realm.url)?)?)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(realm, _)| realm)
+            .or_else(|| {
+                catalysts
+                    .filter(|realm| realm.users.is_some())
+                    .max_by_key(|realm| realm.users)
+            })
+    }
+}
+
 #[derive(Component)]
 pub struct UpdateRealmText;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct ServerDesc {
-    server_name: String,
-    url: String,
-    users_count: i32,
+pub(crate) struct ServerDesc {
+    pub server_name: String,
+    pub url: String,
+    pub users_count: i32,
+}
+
+pub(crate) fn fetch_servers() -> Task<Result<Vec<ServerDesc>, anyhow::Error>> {
+    // let endpoint = ipfas
+    //     .ipfs()
+    //     .lambda_endpoint()
+    //     .unwrap_or_else(|| String::from("https://realm-provider.decentraland.org/lambdas"));
+    // let target_url = format!("{endpoint}/explore/realms");
+
+    // hard coded since the other doesn't list main
+    let target_url = "https://realm-provider.decentraland.org/realms";
+
+    IoTaskPool::get().spawn(async move {
+        let mut response = isahc::get_async(target_url).await.map_err(|e| anyhow!(e))?;
+        response
+            .json::<Vec<ServerDesc>>()
+            .await
+            .map_err(|e| anyhow!(e))
+    })
+}
+
+const WORLDS_SERVER: &str = "https://worlds-content-server.decentraland.org";
+
+#[derive(Deserialize)]
+struct WorldsLiveData {
+    data: WorldsLiveDataInner,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorldsLiveDataInner {
+    per_world: Vec<WorldUsers>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorldUsers {
+    world_name: String,
+    users: i32,
+}
+
+// worlds with users online
+async fn fetch_worlds() -> Result<Vec<RealmEntry>, anyhow::Error> {
+    let mut response = isahc::get_async(format!("{WORLDS_SERVER}/live-data"))
+        .await
+        .map_err(|e| anyhow!(e))?;
+    let live_data = response
+        .json::<WorldsLiveData>()
+        .await
+        .map_err(|e| anyhow!(e))?;
+    Ok(live_data
+        .data
+        .per_world
+        .into_iter()
+        .filter(|world| world.users > 0)
+        .map(|world| RealmEntry {
+            url: format!("{WORLDS_SERVER}/world/{}", world.world_name),
+            name: world.world_name,
+            users: Some(world.users),
+            saved: None,
+        })
+        .collect())
+}
+
+// catalyst realms and worlds, either list is skipped if it fails
+fn fetch_realms() -> Task<Vec<RealmEntry>> {
+    let servers = fetch_servers();
+    IoTaskPool::get().spawn(async move {
+        let mut realms = match servers.await {
+            Ok(servers) => servers
+                .into_iter()
+                .map(|server| RealmEntry {
+                    name: server.server_name,
+                    url: server.url,
+                    users: Some(server.users_count),
+                    saved: None,
+                })
+                .collect(),
+            Err(e) => {
+                warn!("realm list query failed: {e}");
+                Vec::default()
+            }
+        };
+        match fetch_worlds().await {
+            Ok(worlds) => realms.extend(worlds),
+            Err(e) => warn!("worlds list query failed: {e}"),
+        }
+        realms.sort_by_key(|realm| -realm.users);
+        realms
+    })
+}
+
+// round trip time in ms for the realm's about endpoint
+fn ping_realm(url: String) -> Task<Option<f32>> {
+    IoTaskPool::get().spawn(async move {
+        let start = Instant::now();
+        let response = isahc::get_async(format!("{}/about", url.trim_end_matches('/')))
+            .await
+            .ok()?;
+        response
+            .status()
+            .is_success()
+            .then(|| start.elapsed().as_secs_f32() * 1000.0)
+    })
 }
 
 pub struct ChangeRealmPlugin;
@@ -41,7 +200,11 @@ pub struct ChangeRealmPlugin;
 impl Plugin for ChangeRealmPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ChangeRealmDialog>()
-            .add_systems(Update, (change_realm_dialog, update_server_list));
+            .init_resource::<RealmBrowser>()
+            .add_systems(
+                Update,
+                (change_realm_dialog, update_server_list, update_realm_pings),
+            );
     }
 }
 
@@ -50,6 +213,7 @@ fn change_realm_dialog(
     mut events: EventReader<ChangeRealmDialog>,
     dui: ResMut<DuiRegistry>,
     realm: Res<CurrentRealm>,
+    mut browser: ResMut<RealmBrowser>,
     // _ipfas: IpfsAssetServer,
     mut q: Query<&mut Text, With<UpdateRealmText>>,
 ) {
@@ -70,22 +234,8 @@ fn change_realm_dialog(
         return;
     }
 
-    // let endpoint = ipfas
-    //     .ipfs()
-    //     .lambda_endpoint()
-    //     .unwrap_or_else(|| String::from("https://realm-provider.decentraland.org/lambdas"));
-    // let target_url = format!("{endpoint}/explore/realms");
-
-    // hard coded since the other doesn't list main
-    let target_url = "https://realm-provider.decentraland.org/realms";
-
-    let task: Task<Result<Vec<ServerDesc>, anyhow::Error>> = IoTaskPool::get().spawn(async move {
-        let mut response = isahc::get_async(target_url).await.map_err(|e| anyhow!(e))?;
-        response
-            .json::<Vec<ServerDesc>>()
-            .await
-            .map_err(|e| anyhow!(e))
-    });
+    let task = fetch_realms();
+    *browser = Default::default();
 
     let mut root = commands.spawn_empty();
     let root_id = root.id();
@@ -103,12 +253,74 @@ fn change_realm_dialog(
                         .clone()
                         .unwrap_or(String::from("<none>")),
                 )
-                .with_prop("buttons", vec![DuiButton::close_sad("cancel")]),
+                .with_prop(
+                    "buttons",
+                    vec![
+                        DuiButton::new_enabled_and_close_happy(
+                            tr!("button-best-realm"),
+                            join_best_realm,
+                        ),
+                        DuiButton::close_sad(tr!("button-cancel")),
+                    ],
+                ),
         )
         .unwrap();
     commands
         .entity(components.named("server-list"))
-        .insert(ServerList { task, root_id });
+        .insert(ServerList {
+            task: Some(task),
+            root_id,
+        });
+}
+
+fn join_best_realm(
+    mut commands: Commands,
+    browser: Res<RealmBrowser>,
+    current_realm: Res<CurrentRealm>,
+    mut e: EventWriter<ChangeRealmEvent>,
+) {
+    let Some(realm) = browser.best_realm() else {
+        return;
+    };
+    if Some(&realm.name) != current_realm.config.realm_name.as_ref() {
+        commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
+        e.send(ChangeRealmEvent {
+            new_realm: realm.url.clone(),
+        });
+    }
+}
+
+// favorites, then other recently visited realms, then the rest of the live list
+fn with_saved_realms(mut live: Vec<RealmEntry>, config: &AppConfig) -> Vec<RealmEntry> {
+    let mut realms = Vec::<RealmEntry>::default();
+    for saved in config
+        .favorite_realms
+        .iter()
+        .chain(config.recent_realms.iter())
+    {
+        if realms.iter().any(|realm| realm.url == saved.url) {
+            continue;
+        }
+        let users = live
+            .iter()
+            .position(|realm| realm.url == saved.url)
+            .and_then(|ix| live.remove(ix).users);
+        realms.push(RealmEntry {
+            name: saved.name.clone(),
+            url: saved.url.clone(),
+            users,
+            saved: Some(saved.clone()),
+        });
+    }
+    realms.extend(live);
+    realms
+}
+
+fn ping_text(ping: Option<f32>) -> String {
+    match ping {
+        Some(ms) => format!("{ms:.0} ms"),
+        None => "-".to_owned(),
+    }
 }
 
 fn update_server_list(
@@ -116,39 +328,112 @@ fn update_server_list(
     mut q: Query<(Entity, &mut ServerList)>,
     dui: Res<DuiRegistry>,
     current_realm: Res<CurrentRealm>,
+    config: Res<AppConfig>,
+    mut browser: ResMut<RealmBrowser>,
+    ipfas: IpfsAssetServer,
 ) {
     for (ent, mut server_list) in q.iter_mut() {
-        if let Some(res) = server_list.task.complete() {
-            commands.entity(ent).remove::<ServerList>();
-
-            match res {
-                Ok(mut servers) => {
-                    let root_id = server_list.root_id;
-                    commands.entity(ent).despawn_descendants();
-                    servers.sort_by_key(|server| -server.users_count);
-                    for server in servers {
-                        commands.entity(ent).spawn_template(
-                            &dui,
-                            "server-item",
-                            DuiProps::new()
-                                .with_prop("enabled", Some(&server.server_name) != current_realm.config.realm_name.as_ref())
-                                .with_prop("name", server.server_name)
-                                .with_prop("users", format!("{}", server.users_count))
-                                .with_prop(
-                                    "onclick",
-                                    On::<Click>::new(move |mut commands: Commands, mut e: EventWriter<ChangeRealmEvent>| {
-                                        commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
-                                        e.send(ChangeRealmEvent {
-                                            new_realm: server.url.clone(),
-                                        });
-                                        commands.entity(root_id).despawn_recursive();
-                                    }),
-                                )
-                        ).unwrap();
-                    }
-                }
-                Err(e) => warn!("lambda query failed: {e}"),
+        // rebuilt when favorites change, keeping the live list and pings
+        if let Some(task) = server_list.task.as_mut() {
+            let Some(live) = task.complete() else {
+                continue;
+            };
+            server_list.task = None;
+            browser.live = live;
+        } else if !config.is_changed() {
+            continue;
+        }
+        commands.entity(ent).despawn_descendants();
+
+        let root_id = server_list.root_id;
+        browser.realms = with_saved_realms(browser.live.clone(), &config);
+        for realm in browser.realms.iter().cloned() {
+            let url = realm.url.clone();
+            let ping = browser.pings.get(&url).copied();
+            let favorite_label = if is_favorite(&config, &url) {
+                tr!("button-unfavorite")
+            } else {
+                tr!("button-favorite")
+            };
+            let thumbnail = realm
+                .saved
+                .as_ref()
+                .and_then(|saved| load_thumbnail(&ipfas, saved));
+            let name = realm.name.clone();
+            let favorite_url = url.clone();
+            let components = commands
+                .entity(ent)
+                .spawn_template(
+                    &dui,
+                    "server-item",
+                    DuiProps::new()
+                        .with_prop(
+                            "enabled",
+                            url != current_realm.address
+                                && Some(&realm.name) != current_realm.config.realm_name.as_ref(),
+                        )
+                        .with_prop("name", realm.name)
+                        .with_prop(
+                            "users",
+                            realm
+                                .users
+                                .map(|users| format!("{users}"))
+                                .unwrap_or_else(|| "-".to_owned()),
+                        )
+                        .with_prop(
+                            "ping",
+                            ping.map(ping_text).unwrap_or_else(|| "...".to_owned()),
+                        )
+                        .with_prop("favorite-label", favorite_label)
+                        .with_prop(
+                            "favorite",
+                            On::<Click>::new(move |mut config: ResMut<AppConfig>| {
+                                toggle_favorite(&mut config, &name, &favorite_url);
+                            }),
+                        )
+                        .with_prop(
+                            "onclick",
+                            On::<Click>::new(
+                                move |mut commands: Commands,
+                                      mut e: EventWriter<ChangeRealmEvent>| {
+                                    commands.fire_event(SystemAudio(
+                                        "sounds/ui/toggle_enable.wav".to_owned(),
+                                    ));
+                                    e.send(ChangeRealmEvent {
+                                        new_realm: realm.url.clone(),
+                                    });
+                                    commands.entity(root_id).despawn_recursive();
+                                },
+                            ),
+                        ),
+                )
+                .unwrap();
+            if ping.is_none() {
+                commands.entity(components.named("ping")).insert(RealmPing {
+                    task: ping_realm(url.clone()),
+                    url,
+                });
+            }
+            if let Some(h_image) = thumbnail {
+                commands
+                    .entity(components.named("thumbnail"))
+                    .try_insert(UiImage::new(h_image));
             }
         }
     }
 }
+
+fn update_realm_pings(
+    mut commands: Commands,
+    mut q: Query<(Entity, &mut RealmPing, &mut Text)>,
+    mut browser: ResMut<RealmBrowser>,
+) {
+    for (ent, mut ping, mut text) in q.iter_mut() {
+        let Some(result) = ping.task.complete() else {
+            continue;
+        };
+        commands.entity(ent).remove::<RealmPing>();
+        browser.pings.insert(ping.url.clone(), result);
+        text.sections[0].value = ping_text(result);
+    }
+}
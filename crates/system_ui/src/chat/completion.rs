@@ -0,0 +1,206 @@
+// slash command suggestions for the chat input. tab applies the first suggestion. up and down step
+// through the command history, and ctrl+r searches it (again for older matches, tab to accept).
+
+use bevy::{prelude::*, tasks::Task};
+use bevy_simple_text_input::TextInputValue;
+use common::{
+    structs::{AppConfig, PrimaryUser},
+    tr,
+    util::TaskExt,
+};
+use console::{
+    history::CommandHistory,
+    slash_commands::{ArgHints, CommandRegistry, Completion},
+};
+use ipfs::CurrentRealm;
+use scene_runner::initialize_scene::PARCEL_SIZE;
+use ui_core::focus::Focus;
+
+use crate::change_realm::{fetch_servers, ServerDesc};
+
+use super::ChatInput;
+
+// suggestions shown at once
+const MAX_COMPLETIONS: usize = 6;
+
+#[derive(Component, Default)]
+pub struct ChatCompletions(Vec<Completion>);
+
+#[derive(Default)]
+pub(super) struct HistoryBrowse {
+    // entry shown while stepping with up/down
+    index: Option<usize>,
+    // reverse search in progress, with the matching entry
+    search: Option<Option<usize>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn update_chat_completions(
+    mut input: Query<(&Parent, &mut TextInputValue, Has<Focus>)>,
+    chat_input: Query<(), With<ChatInput>>,
+    mut completions: Query<(&mut ChatCompletions, &mut Text, &Parent)>,
+    mut style: Query<&mut Style>,
+    registry: CommandRegistry,
+    history: Res<CommandHistory>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut browse: Local<HistoryBrowse>,
+) {
+    let Some((_, mut value, focused)) = input
+        .iter_mut()
+        .find(|(parent, ..)| chat_input.get(parent.get()).is_ok())
+    else {
+        return;
+    };
+    let Ok((mut completions, mut text, container)) = completions.get_single_mut() else {
+        return;
+    };
+
+    let entries = history.entries();
+    let mut refresh = false;
+
+    // typing restarts the search from the most recent entry
+    if value.is_changed() {
+        if let Some(found) = browse.search.as_mut() {
+            *found = history.search(&value.0, entries.len());
+        }
+    }
+
+    if focused {
+        if browse
+            .index
+            .is_some_and(|ix| entries.get(ix) != Some(&value.0))
+        {
+            browse.index = None;
+        }
+
+        if keys.just_pressed(KeyCode::Tab) {
+            if let Some(first) = completions.0.first().filter(|c| c.line != value.0) {
+                value.0.clone_from(&first.line);
+            }
+            refresh |= browse.search.take().is_some();
+        } else if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+            && keys.just_pressed(KeyCode::KeyR)
+        {
+            browse.search = Some(match browse.search {
+                Some(Some(current)) => history.search(&value.0, current).or(Some(current)),
+                _ => history.search(&value.0, entries.len()),
+            });
+            refresh = true;
+        } else if browse.search.is_none() {
+            if keys.just_pressed(KeyCode::ArrowUp) {
+                let ix = browse.index.unwrap_or(entries.len());
+                if ix > 0 {
+                    browse.index = Some(ix - 1);
+                    value.0.clone_from(&entries[ix - 1]);
+                }
+            } else if keys.just_pressed(KeyCode::ArrowDown) {
+                if let Some(ix) = browse.index {
+                    browse.index = (ix + 1 < entries.len()).then_some(ix + 1);
+                    match browse.index {
+                        Some(ix) => value.0.clone_from(&entries[ix]),
+                        None => value.0.clear(),
+                    }
+                }
+            }
+        }
+    } else if browse.index.is_some() || browse.search.is_some() {
+        *browse = HistoryBrowse::default();
+        refresh = true;
+    }
+
+    if !value.is_changed() && !refresh {
+        return;
+    }
+
+    completions.0 = match browse.search {
+        Some(found) => {
+            let entry = found.and_then(|ix| entries.get(ix));
+            vec![Completion {
+                line: entry.unwrap_or(&value.0).clone(),
+                label: match entry {
+                    Some(entry) => tr!("chat-history-search", query = &value.0, entry = entry),
+                    None => tr!("chat-history-search-failed", query = &value.0),
+                },
+            }]
+        }
+        None => registry.complete(&value.0),
+    };
+    completions.0.truncate(MAX_COMPLETIONS);
+    text.sections[0].value = completions
+        .0
+        .iter()
+        .map(|completion| completion.label.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if let Ok(mut style) = style.get_mut(container.get()) {
+        style.display = if completions.0.is_empty() {
+            Display::None
+        } else {
+            Display::Flex
+        };
+    }
+}
+
+// values for clap arguments that are only known at runtime: realm urls for `/changerealm`, and the
+// current parcel, map pins and saved realm names for `/goto`
+pub(super) fn update_arg_hints(
+    mut hints: ResMut<ArgHints>,
+    realm: Res<CurrentRealm>,
+    config: Res<AppConfig>,
+    player: Query<&GlobalTransform, With<PrimaryUser>>,
+    mut servers: Local<Option<Task<Result<Vec<ServerDesc>, anyhow::Error>>>>,
+    mut server_urls: Local<Option<Vec<String>>>,
+    mut last_parcel: Local<Option<IVec2>>,
+) {
+    let mut realms_changed = realm.is_changed();
+    if server_urls.is_none() {
+        let task = servers.get_or_insert_with(fetch_servers);
+        if let Some(result) = task.complete() {
+            *servers = None;
+            let mut list = result.unwrap_or_else(|e| {
+                warn!("failed to fetch realms: {e}");
+                Vec::default()
+            });
+            list.sort_by_key(|server| -server.users_count);
+            *server_urls = Some(list.into_iter().map(|server| server.url).collect());
+            realms_changed = true;
+        }
+    }
+
+    if realms_changed {
+        let mut urls = vec![realm.address.clone()];
+        for url in server_urls.iter().flatten() {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        urls.retain(|url| !url.is_empty());
+        hints.set("new_realm", urls);
+    }
+
+    let parcel = player.get_single().ok().map(|transform| {
+        (transform.translation().xz() * Vec2::new(1.0, -1.0) / PARCEL_SIZE)
+            .floor()
+            .as_ivec2()
+    });
+    if parcel != *last_parcel || config.is_changed() {
+        *last_parcel = parcel;
+        let mut locations = Vec::default();
+        for location in parcel.iter().chain(config.map_pins.iter()) {
+            let location = format!("{},{}", location.x, location.y);
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
+        }
+        for realm in config
+            .favorite_realms
+            .iter()
+            .chain(config.recent_realms.iter())
+        {
+            if !locations.contains(&realm.name) {
+                locations.push(realm.name.clone());
+            }
+        }
+        hints.set("location", locations);
+    }
+}
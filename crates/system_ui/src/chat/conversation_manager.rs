@@ -1,13 +1,25 @@
+use std::{io::Write, path::PathBuf};
+
 use bevy::{core::FrameCount, ecs::system::SystemParam, prelude::*};
 use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps, DuiRegistry};
-use common::{structs::ShowProfileEvent, util::TryPushChildrenEx};
+use common::{
+    structs::ShowProfileEvent,
+    tr,
+    util::{project_directories, FireEventEx, TryPushChildrenEx},
+};
 use copypasta::{ClipboardContext, ClipboardProvider};
 use ethers_core::types::Address;
 use scene_runner::Toaster;
-use ui_core::ui_actions::{Click, EventCloneExt, On, UiCaller};
+use ui_core::{
+    context_menu::ContextMenu,
+    ui_actions::{Click, EventCloneExt, On, UiCaller},
+};
 use wallet::Wallet;
 
-use crate::chat::friends::PendingProfileUiImage;
+use crate::{
+    chat::friends::PendingProfileUiImage,
+    teleport_preview::{find_parcel, TeleportPreview},
+};
 
 use super::friends::PrivateChat;
 
@@ -201,6 +213,49 @@ impl ConversationManager<'_, '_> {
         debug!("container: {content:?}");
 
         let message_body = message.to_string();
+        let copy = {
+            let message_body = message_body.clone();
+            move |mut toaster: Toaster, frame: Res<FrameCount>| {
+                let Ok(mut ctx) = ClipboardContext::new() else {
+                    warn!("failed to copy");
+                    return;
+                };
+
+                if ctx.set_contents(message_body.clone()).is_ok() {
+                    toaster.add_toast(format!("chatcopy {}", frame.0), tr!("toast-message-copied"));
+                } else {
+                    toaster.add_toast(
+                        format!("chatcopy {}", frame.0),
+                        tr!("toast-message-copy-failed"),
+                    );
+                }
+            }
+        };
+        let report = {
+            let message_body = message_body.clone();
+            move |mut toaster: Toaster, frame: Res<FrameCount>| {
+                let toast = match save_report(sender, &message_body) {
+                    Ok(path) => tr!("toast-message-reported", path = path.to_string_lossy()),
+                    Err(e) => {
+                        warn!("failed to save report: {e}");
+                        tr!("toast-message-report-failed")
+                    }
+                };
+                toaster.add_toast(format!("chatreport {}", frame.0), toast);
+            }
+        };
+        let mut context_menu = ContextMenu::default()
+            .with_item(tr!("menu-copy"), copy.clone())
+            .with_item_enabled(tr!("menu-report"), !me_speaking, report);
+        if let Some(parcel) = find_parcel(&message_body) {
+            context_menu = context_menu.with_item(
+                tr!("menu-preview-parcel", x = parcel.x, y = parcel.y),
+                move |mut commands: Commands| {
+                    commands.fire_event(TeleportPreview(parcel));
+                },
+            );
+        }
+
         let message = self
             .commands
             .spawn_template(
@@ -211,31 +266,12 @@ impl ConversationManager<'_, '_> {
                     "chat-content-other"
                 },
                 DuiProps::new()
-                    .with_prop("text", message_body.clone())
-                    .with_prop(
-                        "copy",
-                        On::<Click>::new(move |mut toaster: Toaster, frame: Res<FrameCount>| {
-                            let Ok(mut ctx) = ClipboardContext::new() else {
-                                warn!("failed to copy");
-                                return;
-                            };
-
-                            if ctx.set_contents(message_body.clone()).is_ok() {
-                                toaster.add_toast(
-                                    format!("chatcopy {}", frame.0),
-                                    "Message copied to clipboard",
-                                );
-                            } else {
-                                toaster.add_toast(
-                                    format!("chatcopy {}", frame.0),
-                                    "Failed to copy message",
-                                );
-                            }
-                        }),
-                    ),
+                    .with_prop("text", message_body)
+                    .with_prop("copy", On::<Click>::new(copy)),
             )
             .unwrap()
             .root;
+        self.commands.entity(message).insert(context_menu);
         if historic {
             self.commands.entity(content).insert_children(0, &[message]);
         } else {
@@ -245,3 +281,21 @@ impl ConversationManager<'_, '_> {
         (bubble, message)
     }
 }
+
+// reports are kept locally, one json object per line, for forwarding to moderators
+fn save_report(sender: Option<Address>, message: &str) -> Result<PathBuf, anyhow::Error> {
+    let dir = project_directories().data_dir().join("reports");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("chat.jsonl");
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    let entry = serde_json::json!({
+        "time": chrono::Utc::now().to_rfc3339(),
+        "sender": sender.map(|address| format!("{address:#x}")),
+        "message": message,
+    });
+    writeln!(file, "{entry}")?;
+    Ok(path)
+}
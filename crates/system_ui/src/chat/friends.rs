@@ -13,13 +13,18 @@ use ui_core::{
     focus::Focus,
     text_entry::TextEntry,
     ui_actions::{Click, EventCloneExt, On},
-    user_font, FontName, WeightName,
+    user_font,
+    virtual_list::VirtualList,
+    FontName, WeightName,
 };
 
 use crate::chat::{ChatInput, ChatTab, ChatboxContainer, PrivateChatEntered};
 
 use super::{conversation_manager::ConversationManager, ChatBox};
 
+// height of a row in the friends list, fitting the `friend` template
+const FRIEND_ROW_HEIGHT: Val = Val::VMin(5.5);
+
 pub struct FriendsPlugin;
 
 impl Plugin for FriendsPlugin {
@@ -244,6 +249,7 @@ pub fn update_friends(
     components: Query<&DuiEntities, With<ChatboxContainer>>,
     dui: Res<DuiRegistry>,
     mut friend_events: EventReader<FriendshipEvent>,
+    mut friend_list: Query<&mut VirtualList>,
 ) {
     let is_init = client.0.as_ref().is_some_and(|c| c.is_initialized);
     if is_init != *init || friend_events.read().next().is_some() {
@@ -256,42 +262,45 @@ pub fn update_friends(
         } else {
             //initialize
             let client = client.0.as_ref().unwrap();
-            let new_friends = client
-                .friends
-                .iter()
-                .map(|friend| {
-                    let friend = *friend;
-                    let mut root = commands.spawn_empty();
-                    let components = dui
-                        .apply_template(
-                            &mut root,
-                            "friend",
-                            DuiProps::default()
-                                .with_prop(
-                                    "name",
-                                    format!("<b>{}</b>", format_address(friend, None)),
-                                )
-                                .with_prop(
-                                    "profile",
-                                    ShowProfileEvent(friend).send_value_on::<Click>(),
-                                )
-                                .with_prop(
-                                    "chat",
-                                    ShowConversationEvent(friend).send_value_on::<Click>(),
-                                ),
-                        )
-                        .unwrap();
-
-                    commands
-                        .entity(components.named("name"))
-                        .insert(PendingProfileName(friend))
-                        .insert(BoldUnread(friend));
-                    components.root
-                })
-                .collect::<Vec<_>>();
-            let mut friends = commands.entity(components.named("friends"));
-            friends.despawn_descendants();
-            friends.try_push_children(&new_friends);
+            // friend lists can be long, so only the visible rows are spawned
+            let friends = client.friends.iter().copied().collect::<Vec<_>>();
+            let populate = move |commands: &mut Commands,
+                                 dui: &DuiRegistry,
+                                 slot: Entity,
+                                 index: usize| {
+                let friend = friends[index];
+                let components = commands
+                    .entity(slot)
+                    .spawn_template(
+                        dui,
+                        "friend",
+                        DuiProps::default()
+                            .with_prop("name", format!("<b>{}</b>", format_address(friend, None)))
+                            .with_prop("profile", ShowProfileEvent(friend).send_value_on::<Click>())
+                            .with_prop(
+                                "chat",
+                                ShowConversationEvent(friend).send_value_on::<Click>(),
+                            ),
+                    )
+                    .unwrap();
+
+                commands
+                    .entity(components.named("name"))
+                    .insert(PendingProfileName(friend))
+                    .insert(BoldUnread(friend));
+            };
+
+            let list_entity = components.named("friends");
+            match friend_list.get_mut(list_entity) {
+                Ok(mut list) => list.set_items(client.friends.len(), populate),
+                Err(_) => {
+                    commands.entity(list_entity).insert(VirtualList::new(
+                        client.friends.len(),
+                        FRIEND_ROW_HEIGHT,
+                        populate,
+                    ));
+                }
+            }
 
             let new_sent = client
                 .sent_requests
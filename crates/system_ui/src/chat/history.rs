@@ -1,13 +1,14 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, sync::Arc};
 
 use bevy::prelude::*;
 use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
 use common::{
-    structs::{ShowProfileEvent, SystemAudio},
+    structs::{NotificationCategory, PrimaryUser, ShowProfileEvent, SystemAudio},
     util::{AsH160, FireEventEx},
 };
 use comms::{chat_marker_things, global_crdt::ChatEvent, profile::UserProfile};
 use dcl_component::proto_components::social::friendship_event_response::{self, Body};
+use scene_runner::Toaster;
 use social::{client::DirectChatMessage, DirectChatEvent, FriendshipEvent};
 use ui_core::{
     bound_node::{BoundedNode, NodeBounds},
@@ -64,7 +65,15 @@ fn update_chat_history(
     mut pending_nearby_chats: Local<Vec<DirectChatMessage>>,
     mut convo: ConversationManager,
     mut node: Query<(&mut NodeBounds, &mut BoundedNode)>,
+    me: Query<&UserProfile, With<PrimaryUser>>,
+    mut toaster: Toaster,
 ) {
+    let mention = me
+        .get_single()
+        .ok()
+        .filter(|profile| !profile.content.name.is_empty())
+        .map(|profile| format!("@{}", profile.content.name.to_lowercase()));
+
     pending_friends.extend(friends.read().filter_map(|f| f.0.clone()));
     pending_private_chats.extend(private_chats.read().map(|ev| ev.0.clone()));
     pending_nearby_chats.extend(nearby_chats.read().filter_map(|ev| {
@@ -86,7 +95,19 @@ fn update_chat_history(
                 warn!("can't get profile for chat sender {:?}", ev.sender);
                 return None;
             };
-            profile.content.eth_address.as_h160()?
+            let partner = profile.content.eth_address.as_h160()?;
+            if mention
+                .as_ref()
+                .is_some_and(|mention| ev.message.to_lowercase().contains(mention))
+            {
+                toaster.notify(
+                    NotificationCategory::Mention,
+                    format!("mention-{partner:#x}"),
+                    format!("{} mentioned you: {}", profile.content.name, ev.message),
+                    Some(Arc::new(|| On::<Click>::new(show_nearby_chat))),
+                );
+            }
+            partner
         };
 
         Some(DirectChatMessage {
@@ -184,6 +205,17 @@ fn update_chat_history(
             continue;
         };
 
+        if let Body::Request(_) = &friend {
+            toaster.notify(
+                NotificationCategory::FriendRequest,
+                format!("friend-request-{h160:#x}"),
+                "You received a friend request",
+                Some(Arc::new(move || {
+                    ShowProfileEvent(h160).send_value_on::<Click>()
+                })),
+            );
+        }
+
         let (bubble, message) =
             convo.add_message(entity, Some(h160), color.with_alpha(0.3), message, false);
         commands.entity(bubble).insert((
@@ -224,37 +256,37 @@ fn update_chat_history(
             chat.message,
             false,
         );
-        commands.entity(bubble).insert((
-            Interaction::default(),
-            On::<Click>::new(
-                |mut commands: Commands,
-                 mut container: Query<&mut Style, With<ChatboxContainer>>,
-                 entry: Query<Entity, With<ChatInput>>,
-                 tab_entity: Query<Entity, With<ChatTab>>,
-                 mut tab_mgr: TabManager| {
-                    if let Ok(mut style) = container.get_single_mut() {
-                        if style.display == Display::None {
-                            commands
-                                .fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
-                            style.display = Display::Flex;
-                        };
-                    }
-
-                    if let Ok(entry) = entry.get_single() {
-                        commands.entity(entry).insert(Focus);
-                    }
-
-                    let Ok(tab_entity) = tab_entity.get_single() else {
-                        warn!("no tab");
-                        return;
-                    };
-
-                    tab_mgr.set_selected(tab_entity, Some(0));
-                },
-            ),
-        ));
+        commands
+            .entity(bubble)
+            .insert((Interaction::default(), On::<Click>::new(show_nearby_chat)));
         history
             .current
             .push_back((bubble, message, time.elapsed_seconds()));
     }
 }
+
+fn show_nearby_chat(
+    mut commands: Commands,
+    mut container: Query<&mut Style, With<ChatboxContainer>>,
+    entry: Query<Entity, With<ChatInput>>,
+    tab_entity: Query<Entity, With<ChatTab>>,
+    mut tab_mgr: TabManager,
+) {
+    if let Ok(mut style) = container.get_single_mut() {
+        if style.display == Display::None {
+            commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
+            style.display = Display::Flex;
+        };
+    }
+
+    if let Ok(entry) = entry.get_single() {
+        commands.entity(entry).insert(Focus);
+    }
+
+    let Ok(tab_entity) = tab_entity.get_single() else {
+        warn!("no tab");
+        return;
+    };
+
+    tab_mgr.set_selected(tab_entity, Some(0));
+}
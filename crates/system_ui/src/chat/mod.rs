@@ -1,14 +1,15 @@
+pub mod completion;
 pub mod conversation_manager;
 pub mod friends;
 pub mod history;
 
 use bevy::{color::palettes::css, prelude::*};
 
-use bevy_console::{ConsoleCommand, ConsoleCommandEntered, ConsoleConfiguration, PrintConsoleLine};
+use bevy_console::{ConsoleCommand, PrintConsoleLine};
 use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps, DuiRegistry};
 use common::{
     dcl_assert,
-    structs::{PrimaryUser, SystemAudio, ToolTips, TooltipSource},
+    structs::{HudElement, PrimaryUser, SystemAudio, ToolTips, TooltipSource},
     util::{
         AsH160, FireEventEx, ModifyComponentExt, RingBuffer, RingBufferReceiver, TryPushChildrenEx,
     },
@@ -16,26 +17,28 @@ use common::{
 use comms::{
     chat_marker_things, global_crdt::ChatEvent, profile::UserProfile, NetworkMessage, Transport,
 };
-use console::DoAddConsoleCommand;
+use completion::{update_arg_hints, update_chat_completions, ChatCompletions};
+use console::{history::CommandHistory, slash_commands::CommandDispatcher, DoAddConsoleCommand};
 use conversation_manager::ConversationManager;
 use dcl::{SceneLogLevel, SceneLogMessage};
 use dcl_component::proto_components::kernel::comms::rfc4;
 use ethers_core::types::Address;
 use history::ChatHistoryPlugin;
 use input_manager::should_accept_key;
-use scene_runner::{renderer_context::RendererSceneContext, ContainingScene};
-use shlex::Shlex;
+use scene_runner::{renderer_context::RendererSceneContext, ContainingScene, RealmTransition};
 use social::FriendshipEvent;
 use ui_core::{
     button::{DuiButton, TabSelection},
     focus::Focus,
     text_entry::{TextEntry, TextEntrySubmit},
-    text_size::FontSize,
+    text_size::{ChatText, FontSize},
     ui_actions::{Click, DataChanged, HoverEnter, HoverExit, On},
 };
 
 use friends::FriendsPlugin;
 
+use crate::hud_layout::HudElementNode;
+
 use super::SystemUiRoot;
 
 pub struct ChatPanelPlugin;
@@ -45,6 +48,7 @@ impl Plugin for ChatPanelPlugin {
         app.add_systems(Update, display_chat);
         app.add_systems(Update, append_chat_messages);
         app.add_systems(Update, emit_user_chat);
+        app.add_systems(Update, (update_chat_completions, update_arg_hints));
         app.add_systems(Startup, setup);
         app.add_systems(
             OnEnter::<ui_core::State>(ui_core::State::Ready),
@@ -239,6 +243,7 @@ fn setup_chat_popup(mut commands: Commands, root: Res<SystemUiRoot>, dui: Res<Du
 
     commands.entity(components.root).insert((
         ChatboxContainer,
+        HudElementNode(HudElement::Chat),
         On::<Click>::new(
             |mut commands: Commands, q: Query<Entity, With<ChatInput>>| {
                 commands.entity(q.single()).try_insert(Focus);
@@ -250,6 +255,10 @@ fn setup_chat_popup(mut commands: Commands, root: Res<SystemUiRoot>, dui: Res<Du
         .entity(components.named("chat-entry"))
         .insert(ChatInput);
 
+    commands
+        .entity(components.named("chat-completions"))
+        .insert(ChatCompletions::default());
+
     commands
         .entity(components.named("chat-output-inner"))
         .insert(ChatBox {
@@ -345,6 +354,7 @@ fn make_log(commands: &mut Commands, asset_server: &AssetServer, log: SceneLogMe
                 message: message.clone(),
             },
             FontSize(0.0175),
+            ChatText,
             TextBundle {
                 text: Text::from_sections([TextSection::new(
                     message,
@@ -479,10 +489,12 @@ fn emit_user_chat(
     time: Res<Time>,
     chat_input: Query<(Entity, &TextEntrySubmit), With<ChatInput>>,
     chat_output: Query<&ChatBox>,
-    console_config: Res<ConsoleConfiguration>,
-    mut command_entered: EventWriter<ConsoleCommandEntered>,
+    mut dispatcher: CommandDispatcher,
+    mut history: ResMut<CommandHistory>,
     mut console_lines: EventReader<PrintConsoleLine>,
     f: Query<Entity, With<Focus>>,
+    transition: Res<RealmTransition>,
+    mut held: Local<Vec<String>>,
 ) {
     let Ok(player) = player.get_single() else {
         return;
@@ -491,6 +503,28 @@ fn emit_user_chat(
         return;
     };
 
+    let send_nearby = |message: &str| {
+        for transport in transports.iter() {
+            let _ = transport
+                .sender
+                .try_send(NetworkMessage::reliable(&rfc4::Packet {
+                    message: Some(rfc4::packet::Message::Chat(rfc4::Chat {
+                        message: message.to_owned(),
+                        timestamp: time.elapsed_seconds_f64(),
+                    })),
+                    protocol_version: 999,
+                }));
+        }
+    };
+
+    // nearby chat entered during a realm change is sent once the new realm's comms are up
+    let connected = transition.realm.is_none() && !transports.is_empty();
+    if connected {
+        for message in held.drain(..) {
+            send_nearby(&message);
+        }
+    }
+
     if let Ok((ent, TextEntrySubmit(message))) = chat_input.get_single() {
         let mut cmds = commands.entity(ent);
         cmds.remove::<TextEntrySubmit>();
@@ -521,35 +555,23 @@ fn emit_user_chat(
             });
 
             if message.starts_with('/') {
-                let mut args = Shlex::new(message).collect::<Vec<_>>();
-
-                let command_name = args.remove(0);
-                debug!("Command entered: `{command_name}`, with args: `{args:?}`");
-
-                let command = console_config.commands.get(command_name.as_str());
-
-                if command.is_some() {
-                    command_entered.send(ConsoleCommandEntered { command_name, args });
-                } else {
-                    debug!(
-                        "Command not recognized, recognized commands: `{:?}`",
-                        console_config.commands.keys().collect::<Vec<_>>()
-                    );
+                history.push(message);
+                if let Err(e) = dispatcher.run(message) {
+                    chats.send(ChatEvent {
+                        timestamp: time.elapsed_seconds_f64(),
+                        sender: Entity::PLACEHOLDER,
+                        channel: output.active_tab.to_owned(),
+                        message: e,
+                    });
                 }
             } else if output.active_tab == "Nearby" {
                 commands.fire_event(SystemAudio(
                     "sounds/ui/widget_chat_message_private_send.wav".to_owned(),
                 ));
-                for transport in transports.iter() {
-                    let _ = transport
-                        .sender
-                        .try_send(NetworkMessage::reliable(&rfc4::Packet {
-                            message: Some(rfc4::packet::Message::Chat(rfc4::Chat {
-                                message: message.clone(),
-                                timestamp: time.elapsed_seconds_f64(),
-                            })),
-                            protocol_version: 999,
-                        }));
+                if connected {
+                    send_nearby(message.as_str());
+                } else {
+                    held.push(message.clone());
                 }
             }
         }
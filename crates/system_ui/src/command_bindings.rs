@@ -0,0 +1,113 @@
+// console commands bound to keys. bindings are managed with `/bind`, `/unbind` and `/binds`, and
+// run through the console like commands typed in chat.
+
+use bevy::prelude::*;
+use bevy_console::ConsoleCommand;
+use common::{
+    structs::{AppConfig, CommandBinding, InputBinding},
+    tr,
+};
+use console::{slash_commands::CommandDispatcher, DoAddConsoleCommand};
+use input_manager::InputManager;
+
+pub struct CommandBindingsPlugin;
+
+impl Plugin for CommandBindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, run_command_bindings);
+        app.add_console_command::<BindCommand, _>(bind_command);
+        app.add_console_command::<UnbindCommand, _>(unbind_command);
+        app.add_console_command::<BindsCommand, _>(binds_command);
+    }
+}
+
+fn run_command_bindings(input: InputManager, mut dispatcher: CommandDispatcher) {
+    for command in input.iter_commands_just_down() {
+        if let Err(e) = dispatcher.run(command) {
+            warn!("bound command failed: {e}");
+        }
+    }
+}
+
+/// bind a console command to a key, e.g. `/bind ctrl+k /changerealm main`.
+/// keys may be prefixed with `ctrl+`, `shift+` or `alt+`, and `tap+`, `double+` or `hold+`
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/bind")]
+struct BindCommand {
+    binding: String,
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+    command: Vec<String>,
+}
+
+fn bind_command(mut input: ConsoleCommand<BindCommand>, mut config: ResMut<AppConfig>) {
+    if let Some(Ok(BindCommand { binding, command })) = input.take() {
+        let binding = match binding.parse::<InputBinding>() {
+            Ok(binding) => binding,
+            Err(e) => {
+                input.reply_failed(e);
+                return;
+            }
+        };
+        let Ok(command) = shlex::try_join(command.iter().map(String::as_str)) else {
+            input.reply_failed(tr!("console-bind-invalid"));
+            return;
+        };
+        if !command.starts_with('/') {
+            input.reply_failed(tr!("console-bind-slash"));
+            return;
+        }
+
+        let commands = &mut config.input_bindings.commands;
+        commands.retain(|existing| existing.binding != binding);
+        commands.push(CommandBinding {
+            binding,
+            command: command.clone(),
+        });
+        input.reply_ok(format!("{binding} : {command}"));
+    }
+}
+
+/// remove a command binding
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/unbind")]
+struct UnbindCommand {
+    binding: String,
+}
+
+fn unbind_command(mut input: ConsoleCommand<UnbindCommand>, mut config: ResMut<AppConfig>) {
+    if let Some(Ok(UnbindCommand { binding })) = input.take() {
+        let binding = match binding.parse::<InputBinding>() {
+            Ok(binding) => binding,
+            Err(e) => {
+                input.reply_failed(e);
+                return;
+            }
+        };
+
+        let commands = &mut config.input_bindings.commands;
+        let count = commands.len();
+        commands.retain(|existing| existing.binding != binding);
+        if commands.len() == count {
+            input.reply_failed(tr!("console-unbind-missing", binding = binding));
+        } else {
+            input.reply_ok(tr!("console-unbound", binding = binding));
+        }
+    }
+}
+
+/// list command bindings
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/binds")]
+struct BindsCommand;
+
+fn binds_command(mut input: ConsoleCommand<BindsCommand>, config: Res<AppConfig>) {
+    if let Some(Ok(_)) = input.take() {
+        for CommandBinding { binding, command } in config.input_bindings.commands.iter() {
+            input.reply(format!("{binding} : {command}"));
+        }
+        input.reply_ok(tr!(
+            "console-binds-count",
+            count = config.input_bindings.commands.len()
+        ));
+    }
+}
@@ -0,0 +1,415 @@
+// console macros. `/alias` defines a command that expands to other commands, and `/exec` runs the
+// commands in a text file from the config directory. within a macro, `/wait` and `/wait_until`
+// pause until time passes or a condition holds. macro commands are sent one per frame so each
+// is handled before the next.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_console::{ConsoleCommand, ConsoleConfiguration, PrintConsoleLine};
+use clap::Parser;
+use common::{
+    structs::{AppConfig, PrimaryUser},
+    util::config_file,
+};
+use console::{
+    slash_commands::{
+        CommandDispatcher, CommandOwner, DynamicCommand, DynamicCommandEntered, SlashCommands,
+    },
+    DoAddConsoleCommand,
+};
+use input_manager::playback::InputPlayback;
+use ipfs::CurrentRealm;
+use scene_runner::initialize_scene::{LiveScenes, PointerResult, SceneLoading, ScenePointers};
+use shlex::Shlex;
+
+use crate::scene_info::player_parcel;
+
+// limits aliases and files that include each other
+const MAX_DEPTH: usize = 8;
+const DEFAULT_TIMEOUT_SECS: f32 = 60.0;
+// conditions aren't checked straight away, so a preceding `/goto` or `/changerealm` takes effect
+const CONDITION_GRACE_SECS: f32 = 0.5;
+
+pub struct ConsoleMacrosPlugin;
+
+impl Plugin for ConsoleMacrosPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MacroQueue>();
+        app.add_console_command::<AliasCommand, _>(alias_command);
+        app.add_console_command::<UnaliasCommand, _>(unalias_command);
+        app.add_console_command::<ExecCommand, _>(exec_command);
+        app.add_console_command::<StopMacrosCommand, _>(stop_macros_command);
+        app.add_console_command::<WaitCommand, _>(wait_command);
+        app.add_console_command::<WaitUntilCommand, _>(wait_until_command);
+        app.add_systems(
+            Update,
+            (
+                register_aliases.run_if(resource_changed::<AppConfig>),
+                run_aliases,
+                run_macros,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum WaitCondition {
+    // the scene at the player's parcel has loaded
+    Loaded,
+    // connected to a realm
+    Realm,
+    // input playback has finished
+    Playback,
+}
+
+enum MacroWait {
+    Until(f32),
+    Condition {
+        condition: WaitCondition,
+        start: f32,
+        timeout: f32,
+    },
+}
+
+#[derive(Resource, Default)]
+struct MacroQueue {
+    // command lines with their alias/file nesting depth
+    lines: VecDeque<(String, usize)>,
+    wait: Option<MacroWait>,
+}
+
+impl MacroQueue {
+    // insert lines to run before anything already queued
+    fn insert(&mut self, lines: Vec<String>, depth: usize) {
+        for line in lines.into_iter().rev() {
+            self.lines.push_front((line, depth));
+        }
+    }
+}
+
+// commands in an alias definition
+fn split_alias(commands: &str) -> Vec<String> {
+    commands
+        .split(';')
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+// `$1`, `$2`, .. are replaced by the alias arguments, and `$*` by all of them
+fn expand_alias(commands: &str, args: &[String]) -> Vec<String> {
+    let all = shlex::try_join(args.iter().map(String::as_str)).unwrap_or_default();
+    split_alias(commands)
+        .into_iter()
+        .map(|mut line| {
+            for (ix, arg) in args.iter().enumerate().rev() {
+                line = line.replace(&format!("${}", ix + 1), arg);
+            }
+            line.replace("$*", &all)
+        })
+        .collect()
+}
+
+fn read_macro_file(file: &str) -> Result<Vec<String>, String> {
+    // only plain relative paths, so scripts can't read outside the config folder
+    let relative = std::path::Path::new(file);
+    if relative.as_os_str().is_empty()
+        || !relative
+            .components()
+            .all(|component| matches!(component, std::path::Component::Normal(_)))
+    {
+        return Err(format!("`{file}` must be in the config folder"));
+    }
+    let path = config_file().with_file_name(relative);
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToOwned::to_owned)
+        .collect())
+}
+
+/// define an alias, e.g. `/alias tour "/goto 0,0; /wait_until loaded; /wait 10; /goto 20,20"`.
+/// `$1`, `$2` and `$*` are replaced by the alias arguments. without commands, shows the alias, and
+/// without a name lists them all
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/alias")]
+struct AliasCommand {
+    name: Option<String>,
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    commands: Vec<String>,
+}
+
+fn alias_command(
+    mut input: ConsoleCommand<AliasCommand>,
+    mut config: ResMut<AppConfig>,
+    console: Res<ConsoleConfiguration>,
+) {
+    let Some(Ok(AliasCommand { name, commands })) = input.take() else {
+        return;
+    };
+
+    let Some(name) = name else {
+        for (name, commands) in config.console_aliases.iter() {
+            input.reply(format!("/{name} : {commands}"));
+        }
+        input.ok();
+        return;
+    };
+
+    let name = name.trim_start_matches('/').to_owned();
+    if commands.is_empty() {
+        match config.console_aliases.get(&name) {
+            Some(commands) => input.reply_ok(format!("/{name} : {commands}")),
+            None => input.reply_failed(format!("no alias `/{name}`")),
+        }
+        return;
+    }
+
+    if console.commands.contains_key(format!("/{name}").as_str()) {
+        input.reply_failed(format!("`/{name}` is already a command"));
+        return;
+    }
+
+    // quoted or not, the definition is everything after the name
+    let commands = commands.join(" ");
+    if split_alias(&commands)
+        .iter()
+        .any(|command| !command.starts_with('/'))
+    {
+        input.reply_failed("alias commands must start with `/`");
+        return;
+    }
+    input.reply_ok(format!("/{name} : {commands}"));
+    config.console_aliases.insert(name, commands);
+}
+
+/// remove an alias
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/unalias")]
+struct UnaliasCommand {
+    name: String,
+}
+
+fn unalias_command(mut input: ConsoleCommand<UnaliasCommand>, mut config: ResMut<AppConfig>) {
+    if let Some(Ok(UnaliasCommand { name })) = input.take() {
+        let name = name.trim_start_matches('/');
+        if config.console_aliases.remove(name).is_some() {
+            input.reply_ok(format!("removed /{name}"));
+        } else {
+            input.reply_failed(format!("no alias `/{name}`"));
+        }
+    }
+}
+
+/// run the commands in a file from the config folder, one per line. lines starting with `#` are
+/// ignored
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/exec")]
+struct ExecCommand {
+    file: String,
+}
+
+fn exec_command(mut input: ConsoleCommand<ExecCommand>, mut queue: ResMut<MacroQueue>) {
+    if let Some(Ok(ExecCommand { file })) = input.take() {
+        match read_macro_file(&file) {
+            Ok(lines) => {
+                input.reply_ok(format!("running {file} ({} commands)", lines.len()));
+                queue.lines.extend(lines.into_iter().map(|line| (line, 1)));
+            }
+            Err(e) => input.reply_failed(e),
+        }
+    }
+}
+
+/// cancel running macros
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/stop_macros")]
+struct StopMacrosCommand;
+
+fn stop_macros_command(
+    mut input: ConsoleCommand<StopMacrosCommand>,
+    mut queue: ResMut<MacroQueue>,
+) {
+    if let Some(Ok(_)) = input.take() {
+        let count = queue.lines.len();
+        *queue = MacroQueue::default();
+        input.reply_ok(format!("cancelled {count} commands"));
+    }
+}
+
+/// in a macro, wait before running the next command
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/wait")]
+struct WaitCommand {
+    seconds: f32,
+}
+
+fn wait_command(mut input: ConsoleCommand<WaitCommand>) {
+    if let Some(Ok(_)) = input.take() {
+        input.reply_failed("`/wait` can only be used in aliases and `/exec` files");
+    }
+}
+
+/// in a macro, wait until the condition holds before running the next command
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/wait_until")]
+struct WaitUntilCommand {
+    condition: WaitCondition,
+    /// seconds to wait before giving up
+    timeout: Option<f32>,
+}
+
+fn wait_until_command(mut input: ConsoleCommand<WaitUntilCommand>) {
+    if let Some(Ok(_)) = input.take() {
+        input.reply_failed("`/wait_until` can only be used in aliases and `/exec` files");
+    }
+}
+
+// aliases are listed and completed along with other commands
+fn register_aliases(
+    config: Res<AppConfig>,
+    console: Res<ConsoleConfiguration>,
+    mut slash: ResMut<SlashCommands>,
+) {
+    slash.unregister_owner(CommandOwner::Client);
+    for (name, commands) in config.console_aliases.iter() {
+        if let Err(e) = slash.register(
+            &console,
+            DynamicCommand {
+                name: format!("/{name}"),
+                about: commands.clone(),
+                args: Vec::default(),
+                owner: CommandOwner::Client,
+            },
+        ) {
+            warn!("alias not available: {e}");
+        }
+    }
+}
+
+fn run_aliases(
+    mut entered: EventReader<DynamicCommandEntered>,
+    config: Res<AppConfig>,
+    mut queue: ResMut<MacroQueue>,
+) {
+    for command in entered
+        .read()
+        .filter(|command| command.owner == CommandOwner::Client)
+    {
+        if let Some(commands) = config.console_aliases.get(&command.name[1..]) {
+            let lines = expand_alias(commands, &command.args);
+            queue.lines.extend(lines.into_iter().map(|line| (line, 1)));
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_macros(
+    mut queue: ResMut<MacroQueue>,
+    mut dispatcher: CommandDispatcher,
+    mut console: EventWriter<PrintConsoleLine>,
+    config: Res<AppConfig>,
+    time: Res<Time<Real>>,
+    realm: Res<CurrentRealm>,
+    player: Query<&GlobalTransform, With<PrimaryUser>>,
+    pointers: Res<ScenePointers>,
+    live_scenes: Res<LiveScenes>,
+    loading: Query<(), With<SceneLoading>>,
+    playback: Res<InputPlayback>,
+) {
+    let now = time.elapsed_seconds();
+    let mut print = |line: String| {
+        console.send(PrintConsoleLine::new(line.into()));
+    };
+
+    match queue.wait {
+        Some(MacroWait::Until(until)) if now < until => return,
+        Some(MacroWait::Condition {
+            condition,
+            start,
+            timeout,
+        }) => {
+            let met = now > start + CONDITION_GRACE_SECS
+                && match condition {
+                    WaitCondition::Realm => !realm.address.is_empty(),
+                    WaitCondition::Playback => !playback.is_playing(),
+                    WaitCondition::Loaded => {
+                        let parcel = player.get_single().ok().map(player_parcel);
+                        match parcel.and_then(|parcel| pointers.get(parcel)) {
+                            None => false,
+                            Some(PointerResult::Nothing) => true,
+                            Some(PointerResult::Exists { hash, .. }) => live_scenes
+                                .0
+                                .get(hash)
+                                .is_some_and(|scene| loading.get(*scene).is_err()),
+                        }
+                    }
+                };
+            if !met {
+                if now < start + timeout {
+                    return;
+                }
+                print(format!("macro: timed out waiting for {condition:?}"));
+            }
+        }
+        _ => (),
+    }
+    queue.wait = None;
+
+    let Some((line, depth)) = queue.lines.pop_front() else {
+        return;
+    };
+    let args = Shlex::new(&line).collect::<Vec<_>>();
+    let Some(name) = args.first() else {
+        return;
+    };
+
+    match name.as_str() {
+        "/wait" => match WaitCommand::try_parse_from(&args) {
+            Ok(WaitCommand { seconds }) => queue.wait = Some(MacroWait::Until(now + seconds)),
+            Err(e) => print(format!("macro: {e}")),
+        },
+        "/wait_until" => match WaitUntilCommand::try_parse_from(&args) {
+            Ok(WaitUntilCommand { condition, timeout }) => {
+                queue.wait = Some(MacroWait::Condition {
+                    condition,
+                    start: now,
+                    timeout: timeout.unwrap_or(DEFAULT_TIMEOUT_SECS),
+                })
+            }
+            Err(e) => print(format!("macro: {e}")),
+        },
+        _ => {
+            // nested macros run in place
+            let nested = if name == "/exec" {
+                Some(
+                    ExecCommand::try_parse_from(&args)
+                        .map_err(|e| e.to_string())
+                        .and_then(|ExecCommand { file }| read_macro_file(&file)),
+                )
+            } else {
+                name.strip_prefix('/')
+                    .and_then(|alias| config.console_aliases.get(alias))
+                    .map(|commands| Ok(expand_alias(commands, &args[1..])))
+            };
+            match nested {
+                Some(_) if depth >= MAX_DEPTH => {
+                    print(format!("macro: `{line}` is nested too deeply"))
+                }
+                Some(Ok(lines)) => queue.insert(lines, depth + 1),
+                Some(Err(e)) => print(format!("macro: {e}")),
+                None => {
+                    if let Err(e) = dispatcher.run(&line) {
+                        print(format!("macro: {e}"));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,55 +1,280 @@
-use std::{io::Read, path::PathBuf};
+// crash reports. while running, a snapshot of the session context (realm, parcel, live scenes, gpu
+// and config) is kept next to the session log, and panics append their backtrace there too. after
+// an unclean exit the next launch collects the log, context and backtraces into a crash bundle
+// folder, and asks before uploading it.
 
-use analytics::{data_definition::SegmentEventExplorerError, segment_system::SegmentMetricsEvents};
-use bevy::prelude::*;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use analytics::{
+    data_definition::{SegmentEvent, SegmentEventExplorerError, SegmentEventExplorerSessionStart},
+    segment_system::SegmentMetricsEvents,
+};
+use anyhow::anyhow;
+use bevy::{prelude::*, render::renderer::RenderAdapterInfo};
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use common::{
+    structs::{AppConfig, PrimaryUser, Version},
+    tr,
+};
+use ipfs::CurrentRealm;
+use scene_runner::{renderer_context::RendererSceneContext, Toaster};
+use serde::Serialize;
+use ui_core::button::DuiButton;
+
+use crate::scene_info::player_parcel;
+
+const CONTEXT_INTERVAL_SECS: f32 = 5.0;
+// log kept in the bundle
+const MAX_LOG_BYTES: usize = 4 * 1024 * 1024;
+// analytics event size limit
+const MAX_MESSAGE_BYTES: usize = 31000;
+const MAX_STACK_BYTES: usize = 16000;
 
 pub struct CrashReportPlugin {
-    pub file: PathBuf,
+    // log of this session, context and backtraces are written alongside it
+    pub session_log: PathBuf,
+    // log of a previous session that didn't exit cleanly
+    pub crashed: Option<PathBuf>,
 }
 
 impl Plugin for CrashReportPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(CrashReport(self.file.clone()));
+        app.insert_resource(CrashReport {
+            session_log: self.session_log.clone(),
+            crashed: self.crashed.clone(),
+        });
+        app.add_systems(Update, write_crash_context);
         app.add_systems(OnEnter::<ui_core::State>(ui_core::State::Ready), setup);
     }
 }
 
 #[derive(Resource)]
-pub struct CrashReport(PathBuf);
+pub struct CrashReport {
+    session_log: PathBuf,
+    crashed: Option<PathBuf>,
+}
+
+// files kept next to the session log, named like the `.touch` marker
+fn session_file(session_log: &Path, ext: &str) -> PathBuf {
+    PathBuf::from(format!("{}.{ext}", session_log.display()))
+}
+
+// record panic backtraces for the crash bundle, then run the existing hook
+pub fn install_panic_hook(session_log: &Path) {
+    let path = session_file(session_log, "backtrace.txt");
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = format!(
+            "thread '{}' {info}\n\n{}\n",
+            std::thread::current().name().unwrap_or("<unnamed>"),
+            std::backtrace::Backtrace::force_capture()
+        );
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+        {
+            let _ = file.write_all(report.as_bytes());
+        }
+        previous(info);
+    }));
+}
+
+// remove this session's crash files after a clean exit
+pub fn clean_exit(session_log: &Path) {
+    for ext in ["context.json", "backtrace.txt"] {
+        let _ = std::fs::remove_file(session_file(session_log, ext));
+    }
+}
 
-fn setup(report: Res<CrashReport>, mut metrics: ResMut<SegmentMetricsEvents>) {
-    let mut f = match std::fs::File::open(&report.0) {
-        Ok(f) => f,
+#[derive(Serialize)]
+struct CrashContext {
+    version: Option<String>,
+    realm: String,
+    parcel: Option<IVec2>,
+    // hash, title and base parcel
+    scenes: Vec<String>,
+    gpu: Option<String>,
+    config: AppConfig,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_crash_context(
+    report: Res<CrashReport>,
+    time: Res<Time<Real>>,
+    version: Option<Res<Version>>,
+    realm: Res<CurrentRealm>,
+    player: Query<&GlobalTransform, With<PrimaryUser>>,
+    scenes: Query<&RendererSceneContext>,
+    adapter: Option<Res<RenderAdapterInfo>>,
+    config: Res<AppConfig>,
+    mut last: Local<Option<(f32, String)>>,
+) {
+    let now = time.elapsed_seconds();
+    if last
+        .as_ref()
+        .is_some_and(|(written, _)| now - written < CONTEXT_INTERVAL_SECS)
+    {
+        return;
+    }
+
+    let mut scenes = scenes
+        .iter()
+        .map(|context| {
+            format!(
+                "{} `{}` at {},{}",
+                context.hash, context.title, context.base.x, context.base.y
+            )
+        })
+        .collect::<Vec<_>>();
+    scenes.sort();
+    let context = CrashContext {
+        version: version.map(|version| version.0.clone()),
+        realm: realm.address.clone(),
+        parcel: player.get_single().ok().map(player_parcel),
+        scenes,
+        gpu: adapter.map(|adapter| {
+            format!(
+                "{} ({:?}, {} {})",
+                adapter.name, adapter.backend, adapter.driver, adapter.driver_info
+            )
+        }),
+        // login details stay on this machine
+        config: AppConfig {
+            previous_login: None,
+            user_id: String::default(),
+            ..config.clone()
+        },
+    };
+
+    let json = match serde_json::to_string_pretty(&context) {
+        Ok(json) => json,
         Err(e) => {
-            warn!("failed to open log for crash report: {e}");
+            warn!("failed to serialize crash context: {e}");
             return;
         }
     };
-    let mut buf = Vec::default();
-    if let Err(e) = f.read_to_end(&mut buf) {
-        warn!("failed to read log for crash report: {e}");
+    if last.as_ref().is_some_and(|(_, previous)| *previous == json) {
+        *last = Some((now, json));
         return;
     }
+    if let Err(e) = std::fs::write(session_file(&report.session_log, "context.json"), &json) {
+        warn!("failed to write crash context: {e}");
+    }
+    *last = Some((now, json));
+}
 
-    let start = buf.len().saturating_sub(31000);
-    let Ok(error_message) = std::str::from_utf8(&buf[start..]) else {
-        warn!("failed to convert crash log to utf8");
-        return;
-    };
+// gather the crashed session's files into `crash-reports/<session>`
+fn write_bundle(crashed: &Path) -> Result<PathBuf, anyhow::Error> {
+    let name = crashed
+        .file_stem()
+        .ok_or_else(|| anyhow!("bad log path {}", crashed.display()))?;
+    let dir = crashed
+        .parent()
+        .ok_or_else(|| anyhow!("bad log path {}", crashed.display()))?
+        .join("crash-reports")
+        .join(name);
+    std::fs::create_dir_all(&dir)?;
+
+    // the end of the log leads up to the crash
+    let log = std::fs::read(crashed)?;
+    std::fs::write(
+        dir.join("log.txt"),
+        &log[log.len().saturating_sub(MAX_LOG_BYTES)..],
+    )?;
+    for ext in ["context.json", "backtrace.txt"] {
+        let file = session_file(crashed, ext);
+        if file.exists() {
+            std::fs::rename(file, dir.join(ext))?;
+        }
+    }
+
+    // only ask once
+    std::fs::remove_file(session_file(crashed, "touch"))?;
+    Ok(dir)
+}
+
+fn tail(bytes: &[u8], max: usize) -> String {
+    String::from_utf8_lossy(&bytes[bytes.len().saturating_sub(max)..]).into_owned()
+}
+
+fn send_bundle(dir: &Path, metrics: &mut SegmentMetricsEvents) {
+    let read = |file: &str| std::fs::read(dir.join(file)).unwrap_or_default();
+    let context = read("context.json");
+    let log = read("log.txt");
+    let backtrace = read("backtrace.txt");
+
+    let context = tail(&context, MAX_MESSAGE_BYTES / 2);
+    let log = tail(&log, MAX_MESSAGE_BYTES.saturating_sub(context.len() + 2));
+    metrics.add_event(SegmentEvent::ExplorerError(SegmentEventExplorerError {
+        error_type: "Crash".to_owned(),
+        error_message: format!("{context}\n\n{log}"),
+        error_stack: String::from_utf8_lossy(&backtrace[..backtrace.len().min(MAX_STACK_BYTES)])
+            .into_owned(),
+    }));
+    info!("crash report sent");
+}
 
-    metrics.add_event(analytics::data_definition::SegmentEvent::ExplorerError(
-        SegmentEventExplorerError {
-            error_type: "Crash".to_owned(),
-            error_message: error_message.to_owned(),
-            error_stack: String::default(),
+fn setup(
+    mut commands: Commands,
+    report: Res<CrashReport>,
+    dui: Res<DuiRegistry>,
+    config: Res<AppConfig>,
+    mut metrics: ResMut<SegmentMetricsEvents>,
+) {
+    // crash free sessions
+    metrics.add_event(SegmentEvent::ExplorerSessionStart(
+        SegmentEventExplorerSessionStart {
+            previous_session_crashed: report.crashed.is_some(),
         },
     ));
 
-    let touch = report.0.parent().unwrap().join(format!(
-        "{}.touch",
-        report.0.file_name().unwrap().to_string_lossy()
-    ));
-    std::fs::remove_file(touch.clone()).unwrap();
+    let Some(crashed) = report.crashed.as_ref() else {
+        return;
+    };
+    let bundle = match write_bundle(crashed) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            warn!("failed to write crash report: {e}");
+            return;
+        }
+    };
+    info!("crash report written to {}", bundle.display());
 
-    info!("crash report sent");
+    let path = bundle.display().to_string();
+    let (body, buttons) = if config.telemetry {
+        (
+            tr!("crash-report-body", path = path),
+            vec![
+                DuiButton::new_enabled_and_close_happy(
+                    tr!("crash-report-send"),
+                    move |mut metrics: ResMut<SegmentMetricsEvents>, mut toaster: Toaster| {
+                        send_bundle(&bundle, &mut metrics);
+                        toaster.add_toast("crash-report", tr!("crash-report-sent"));
+                    },
+                ),
+                DuiButton::close_sad(tr!("crash-report-dont-send")),
+            ],
+        )
+    } else {
+        // nothing is sent with telemetry off
+        (
+            tr!("crash-report-body-local", path = path),
+            vec![DuiButton::close_happy(tr!("button-ok"))],
+        )
+    };
+
+    commands
+        .spawn_template(
+            &dui,
+            "text-dialog",
+            DuiProps::new()
+                .with_prop("title", tr!("crash-report-title"))
+                .with_prop("body", body)
+                .with_prop("buttons", buttons),
+        )
+        .unwrap();
 }
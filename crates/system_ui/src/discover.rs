@@ -12,7 +12,7 @@ use common::{
     structs::{IVec2Arg, SettingsTab},
     util::{ModifyComponentExt, TaskExt},
 };
-use ipfs::{ipfs_path::IpfsPath, ChangeRealmEvent, IpfsAssetServer};
+use ipfs::{ipfs_path::IpfsPath, ChangeRealmEvent, CurrentRealm, IpfsAssetServer};
 use isahc::AsyncReadResponseExt;
 use serde::Deserialize;
 use ui_core::{
@@ -387,16 +387,16 @@ pub struct DiscoverPage {
     pub title: String,
     contact_name: Option<String>,
     description: Option<String>,
-    base_position: String,
-    image: String,
+    pub base_position: String,
+    pub image: String,
     world_name: Option<String>,
-    user_count: usize,
+    pub user_count: usize,
     favorites: usize,
     user_visits: Option<usize>,
     like_score: Option<f32>,
     likes: usize,
     categories: Vec<String>,
-    content_rating: String,
+    pub content_rating: String,
     updated_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -489,6 +489,10 @@ fn update_page(
             continue;
         }
 
+        let Some(jump_in) = jump_in_system(item) else {
+            continue;
+        };
+
         visible_count += 1;
 
         let item = item.clone();
@@ -509,7 +513,10 @@ fn update_page(
                     .with_prop(
                         "likes",
                         format!("{:.0}%", item.like_score.unwrap_or(0.0) * 100.0),
-                    ),
+                    )
+                    .with_prop("online", format!("{}", item.user_count))
+                    .with_prop("stats", format!("{} favorites", item.favorites))
+                    .with_prop("jump-in", On::<Click>::new(jump_in.pipe(close_settings))),
             )
             .unwrap();
         commands.commands().entity(button.root).insert((
@@ -587,12 +594,10 @@ fn update_page(
     *prev_count = visible_count;
 }
 
-pub fn spawn_discover_popup(
-    commands: &mut Commands,
-    dui: &DuiRegistry,
-    asset_server: &AssetServer,
+// teleports directly when the place is in the current realm, otherwise changes realm first
+fn jump_in_system(
     item: &DiscoverPage,
-) {
+) -> Option<impl FnMut(Res<CurrentRealm>, Query<&mut SettingsDialog>) + Send + Sync + 'static> {
     let url = match &item.world_name {
         Some(name) => format!(
             "https://worlds-content-server.decentraland.org/world/{}",
@@ -603,23 +608,42 @@ pub fn spawn_discover_popup(
 
     let Ok(to) = IVec2Arg::from_str(&item.base_position) else {
         warn!("invalid location");
-        return;
+        return None;
     };
-    let system = move |mut settings: Query<&mut SettingsDialog>| {
-        let cr_ev = ChangeRealmEvent {
-            new_realm: url.clone(),
-        };
-        let rpc_ev = RpcCall::TeleportPlayer {
-            scene: None,
-            to: to.0,
-            response: Default::default(),
-        };
 
-        if let Ok(mut settings) = settings.get_single_mut() {
-            settings.on_close = Some(OnCloseEvent::ChangeRealm(cr_ev, rpc_ev));
-        } else {
-            warn!("no settings");
-        }
+    Some(
+        move |realm: Res<CurrentRealm>, mut settings: Query<&mut SettingsDialog>| {
+            let rpc_ev = RpcCall::TeleportPlayer {
+                scene: None,
+                to: to.0,
+                response: Default::default(),
+            };
+            let on_close = if realm.address == url {
+                OnCloseEvent::Teleport(rpc_ev)
+            } else {
+                let cr_ev = ChangeRealmEvent {
+                    new_realm: url.clone(),
+                };
+                OnCloseEvent::ChangeRealm(cr_ev, rpc_ev)
+            };
+
+            if let Ok(mut settings) = settings.get_single_mut() {
+                settings.on_close = Some(on_close);
+            } else {
+                warn!("no settings");
+            }
+        },
+    )
+}
+
+pub fn spawn_discover_popup(
+    commands: &mut Commands,
+    dui: &DuiRegistry,
+    asset_server: &AssetServer,
+    item: &DiscoverPage,
+) {
+    let Some(system) = jump_in_system(item) else {
+        return;
     };
 
     let jump_in = On::<Click>::new(system.pipe(close_settings).pipe(close_ui_happy));
@@ -17,10 +17,11 @@ use collectibles::{
     base_wearables::default_bodyshape_instance,
     emotes::{Emote, EmoteInstance},
     wearables::WearableInstance,
-    BaseEmotes, CollectibleData, CollectibleError, CollectibleManager,
+    BaseEmotes, CollectibleData, CollectibleError, CollectibleManager, Collectibles,
+    PurchasedCollectible,
 };
 use common::{
-    structs::{PrimaryUser, SettingsTab, PROFILE_UI_RENDERLAYER},
+    structs::{HotbarAction, PrimaryUser, SettingsTab, ShowPurchaseEvent, PROFILE_UI_RENDERLAYER},
     util::TaskExt,
 };
 use comms::profile::CurrentUserProfile;
@@ -31,8 +32,10 @@ use tween::SystemTween;
 use ui_core::{
     button::{DuiButton, TabSelection},
     combo_box::ComboBox,
+    drag_drop::{DragSource, DropTarget, Dropped},
     interact_style::{InteractStyle, InteractStyles},
     text_entry::TextEntryValue,
+    theme::UiTheme,
     toggle::Toggled,
     ui_actions::{Click, DataChanged, Enabled, On, UiCaller},
 };
@@ -136,6 +139,36 @@ pub struct OwnedEmoteData {
     pub individual_data: Vec<IndividualData>,
 }
 
+impl OwnedEmoteData {
+    // add purchases the lambdas haven't indexed yet
+    fn merge_purchased(owned: &mut Vec<Self>, purchased: &[PurchasedCollectible]) {
+        for purchase in purchased {
+            let individual = IndividualData {
+                transferred_at: purchase.purchased_at.clone(),
+                token_id: purchase.token_id.clone(),
+            };
+            match owned.iter_mut().find(|o| o.urn == purchase.urn) {
+                Some(existing) => {
+                    if !existing
+                        .individual_data
+                        .iter()
+                        .any(|d| d.token_id == individual.token_id)
+                    {
+                        existing.individual_data.push(individual);
+                    }
+                }
+                None => owned.push(Self {
+                    urn: purchase.urn.clone(),
+                    name: purchase.name.clone(),
+                    category: purchase.category.clone(),
+                    rarity: purchase.rarity.clone(),
+                    individual_data: vec![individual],
+                }),
+            }
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct OwnedEmoteServerResponse {
     elements: Vec<OwnedEmoteData>,
@@ -153,6 +186,55 @@ pub struct EmotesSettings {
     pub selected_slot: usize,
 }
 
+// drag payload for an equipped emote slot
+#[derive(Clone, Copy)]
+struct EmoteSlotDrag(usize);
+
+fn drop_on_emote_slot(
+    In(to): In<usize>,
+    caller: Res<UiCaller>,
+    dropped: Query<&Dropped>,
+    mut settings: Query<&mut EmotesSettings>,
+    mut dialog: Query<(&mut SettingsDialog, &BoothInstance, &mut AvatarShape)>,
+    mut booth: PhotoBooth,
+) {
+    let Some(&EmoteSlotDrag(from)) = dropped
+        .get(caller.0)
+        .ok()
+        .and_then(Dropped::payload::<EmoteSlotDrag>)
+    else {
+        return;
+    };
+    if from == to {
+        return;
+    }
+    let Ok(mut settings) = settings.get_single_mut() else {
+        warn!("failed to get settings");
+        return;
+    };
+    let Ok((mut dialog, booth_instance, mut avatar)) = dialog.get_single_mut() else {
+        warn!("fail to update dialog+booth instance");
+        return;
+    };
+
+    let from_emote = settings.current_emotes.remove(&from);
+    let to_emote = settings.current_emotes.remove(&to);
+    if let Some(emote) = from_emote {
+        settings.current_emotes.insert(to, emote);
+    }
+    if let Some(emote) = to_emote {
+        settings.current_emotes.insert(from, emote);
+    }
+    settings.selected_slot = to;
+
+    avatar.shape.emotes.resize(10, String::default());
+    avatar.shape.emotes.swap(from, to);
+    booth.update_shape(booth_instance, avatar.clone());
+
+    // mark profile as modified, which also redraws the slots
+    dialog.modified = true;
+}
+
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn set_emotes_content(
     mut commands: Commands,
@@ -303,11 +385,22 @@ fn set_emotes_content(
                         "emote-slot",
                         DuiProps::new()
                             .with_prop("slot-id", format!("{}", slot))
-                            .with_prop("emote-img", emote_img),
+                            .with_prop("emote-img", emote_img.clone()),
                     )
                     .unwrap()
                     .root;
 
+                // slots can be dragged onto each other to swap them
+                let mut content_cmds = commands.entity(content);
+                content_cmds.insert((
+                    DropTarget::new::<EmoteSlotDrag>(),
+                    On::<Dropped>::new((move || slot).pipe(drop_on_emote_slot)),
+                ));
+                if emote_settings.current_emotes.contains_key(&slot) {
+                    content_cmds
+                        .insert(DragSource::new(EmoteSlotDrag(slot)).with_ghost_image(emote_img));
+                }
+
                 DuiButton {
                     styles: Some(InteractStyles {
                         active: Some(InteractStyle {
@@ -449,7 +542,7 @@ fn only_collectibles(
 }
 
 #[derive(Event, Default)]
-struct GetOwnedEmotes;
+pub(crate) struct GetOwnedEmotes;
 
 fn get_owned_emotes(
     mut e: EventReader<GetOwnedEmotes>,
@@ -457,6 +550,7 @@ fn get_owned_emotes(
     mut q: Query<&mut EmotesSettings>,
     ipfas: IpfsAssetServer,
     current_profile: Res<CurrentUserProfile>,
+    collectibles: Res<Collectibles<Emote>>,
 ) {
     let ev = e.read().last().is_some();
 
@@ -466,6 +560,10 @@ fn get_owned_emotes(
                 if let Ok(mut settings) = q.get_single_mut() {
                     debug!("emote task ok");
                     settings.owned_emotes = emote_data.elements;
+                    OwnedEmoteData::merge_purchased(
+                        &mut settings.owned_emotes,
+                        collectibles.purchased(),
+                    );
                 }
             }
             Some(Err(e)) => {
@@ -593,17 +691,8 @@ impl ColorHexEx for Color {
 }
 
 impl Rarity {
-    pub fn color(&self) -> Color {
-        match self {
-            Rarity::Free => Color::srgb(0.9, 0.9, 0.9),
-            Rarity::Common => Color::srgb(0.7, 0.7, 0.7),
-            Rarity::Uncommon => Color::srgb(1.0, 0.8, 0.4),
-            Rarity::Rare => Color::srgb(0.6, 1.0, 0.6),
-            Rarity::Epic => Color::srgb(0.6, 0.6, 1.0),
-            Rarity::Legendary => Color::srgb(0.8, 0.4, 0.8),
-            Rarity::Mythic => Color::srgb(1.0, 0.6, 1.0),
-            Rarity::Unique => Color::srgb(1.0, 1.0, 0.4),
-        }
+    pub fn color(&self, theme: &UiTheme) -> Color {
+        theme.rarity[*self as usize]
     }
 }
 
@@ -731,9 +820,12 @@ fn update_emotes_list(
                 .spawn_template(&dui, "emote-item-pending", DuiProps::new())
                 .unwrap()
                 .root;
+            // emotes can be dragged onto the hotbar
+            let drag = DragSource::new(HotbarAction::Emote(emote.instance.instance_urn()))
+                .with_ghost_label(emote.name.clone());
             commands
                 .entity(content)
-                .try_insert((emote, EmoteItemState::PendingMeta(ix)));
+                .try_insert((emote, EmoteItemState::PendingMeta(ix), drag));
 
             DuiButton {
                 styles: Some(InteractStyles {
@@ -807,6 +899,7 @@ fn update_emote_item(
     mut emote_loader: CollectibleManager<Emote>,
     ipfas: IpfsAssetServer,
     dui: Res<DuiRegistry>,
+    theme: Res<UiTheme>,
     settings: Query<(Entity, &EmotesSettings)>,
     walker: DuiWalker,
 ) {
@@ -861,7 +954,7 @@ fn update_emote_item(
                                                 .asset_server()
                                                 .load::<Image>("images/backback/empty.png"),
                                         )
-                                        .with_prop("rarity-color", entry.rarity.color()),
+                                        .with_prop("rarity-color", entry.rarity.color(&theme)),
                                 )
                                 .unwrap();
                         }
@@ -877,7 +970,7 @@ fn update_emote_item(
                         .contains(settings.body_shape.base().as_str());
 
                     let (image_color, rarity_color) = if fits {
-                        (Color::WHITE, entry.rarity.color())
+                        (Color::WHITE, entry.rarity.color(&theme))
                     } else {
                         (Color::BLACK, Color::Srgba(css::DARK_GRAY))
                     };
@@ -941,6 +1034,7 @@ fn update_selected_item(
     settings: Query<(Entity, Ref<EmotesSettings>, &DuiEntities, &SelectItem)>,
     avatar: Query<(&AvatarShape, &BoothInstance), With<SettingsDialog>>,
     dui: Res<DuiRegistry>,
+    theme: Res<UiTheme>,
     mut emote_loader: CollectibleManager<Emote>,
     mut retry: Local<bool>,
     mut booth: PhotoBooth,
@@ -1081,18 +1175,27 @@ fn update_selected_item(
             },
         );
 
+        // collection items can be bought again from the marketplace
+        let buy_enabled = sel.instance.base().collection_type() == Some("collections-v2");
+        let buy_urn = sel.instance.base().to_string();
+        let buy_action = On::<Click>::new(move |mut e: EventWriter<ShowPurchaseEvent>| {
+            e.send(ShowPurchaseEvent(buy_urn.clone()));
+        });
+
         commands
             .entity(components.named("selected-item"))
             .spawn_template(
                 &dui,
                 "emote-selection",
                 DuiProps::new()
-                    .with_prop("rarity-color", sel.rarity.color())
+                    .with_prop("rarity-color", sel.rarity.color(&theme))
                     .with_prop("selection-image", data_ref.thumbnail.clone())
                     .with_prop("title", data_ref.name.clone())
                     .with_prop("body", data_ref.description.clone())
                     .with_prop("label", label.to_owned())
-                    .with_prop("onclick", equip_action),
+                    .with_prop("onclick", equip_action)
+                    .with_prop("buy", buy_action)
+                    .with_prop("buy-enabled", buy_enabled),
             )
             .unwrap();
     }
@@ -4,6 +4,7 @@ use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps, DuiRegistry};
 use common::{
     profile::SerializedProfile,
     structs::{ActiveDialog, ShowProfileEvent, PROFILE_UI_RENDERLAYER},
+    tr,
     util::FireEventEx,
 };
 use comms::profile::{ProfileManager, UserProfile};
@@ -152,7 +153,7 @@ fn show_foreign_profiles(
                                     }
                                 },
                             ),
-                            DuiButton::close_happy("Ok"),
+                            DuiButton::close_happy(tr!("button-ok")),
                         ],
                         ),
                 )
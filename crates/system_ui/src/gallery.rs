@@ -0,0 +1,598 @@
+// the photo gallery. photo mode saves each capture with a json sidecar describing where it was
+// taken, the gallery tab browses them, and photos can be uploaded to the camera reel service so
+// they follow the account to other clients.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail};
+use bevy::{
+    ecs::system::SystemParam,
+    math::Vec3Swizzles,
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+};
+use bevy_dui::{DuiCommandsExt, DuiEntities, DuiEntityCommandsExt, DuiProps, DuiRegistry};
+use common::{
+    structs::{PrimaryCamera, PrimaryUser, SettingsTab},
+    tr,
+    util::{gallery_dir, TaskExt},
+};
+use comms::{global_crdt::ForeignPlayer, profile::CurrentUserProfile, profile::UserProfile};
+use ipfs::CurrentRealm;
+use isahc::{http::Uri, AsyncReadResponseExt, RequestExt};
+use scene_runner::{
+    initialize_scene::PARCEL_SIZE, renderer_context::RendererSceneContext, ContainingScene, Toaster,
+};
+use serde::{Deserialize, Serialize};
+use social::SocialClient;
+use ui_core::{
+    button::DuiButton,
+    ui_actions::{Click, On},
+};
+use wallet::Wallet;
+
+use crate::profile::SettingsDialog;
+
+const CAMERA_REEL_URL: &str = "https://camera-reel-service.decentraland.org/api/images";
+// players further away than this aren't recognisable, so aren't tagged
+const TAG_DISTANCE: f32 = 20.0;
+// each photo is loaded at full size, so only the latest are shown
+const MAX_SHOWN: usize = 60;
+
+pub struct GalleryPlugin;
+
+impl Plugin for GalleryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GalleryUploads>();
+        app.add_systems(
+            Update,
+            (
+                set_gallery_content,
+                update_gallery.run_if(|q: Query<&SettingsTab>| {
+                    q.get_single().is_ok_and(|tab| tab == &SettingsTab::Gallery)
+                }),
+                update_uploads,
+            ),
+        );
+    }
+}
+
+// the camera reel's metadata format
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoMetadata {
+    pub user_name: String,
+    pub user_address: String,
+    // unix seconds
+    pub date_time: String,
+    pub realm: String,
+    pub scene: PhotoScene,
+    pub visible_people: Vec<PhotoPerson>,
+    // where the photo was uploaded to, once it has been
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uploaded_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PhotoScene {
+    pub name: String,
+    pub location: PhotoLocation,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PhotoLocation {
+    pub x: String,
+    pub y: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoPerson {
+    pub user_name: String,
+    pub user_address: String,
+    pub is_guest: bool,
+    pub wearables: Vec<String>,
+}
+
+impl PhotoPerson {
+    fn new(address: String, profile: &UserProfile) -> Self {
+        Self {
+            user_name: profile.content.name.clone(),
+            user_address: address,
+            is_guest: !profile.content.has_connected_web3.unwrap_or(false),
+            wearables: profile.content.avatar.wearables.clone(),
+        }
+    }
+}
+
+fn sidecar_path(photo: &Path) -> PathBuf {
+    photo.with_extension("json")
+}
+
+pub fn load_metadata(photo: &Path) -> Option<PhotoMetadata> {
+    let data = std::fs::read(sidecar_path(photo)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+pub fn save_metadata(photo: &Path, metadata: &PhotoMetadata) -> Result<(), anyhow::Error> {
+    std::fs::write(sidecar_path(photo), serde_json::to_vec_pretty(metadata)?)?;
+    Ok(())
+}
+
+// what's needed to describe a photo as it's taken
+#[allow(clippy::type_complexity)]
+#[derive(SystemParam)]
+pub struct PhotoContext<'w, 's> {
+    realm: Res<'w, CurrentRealm>,
+    profile: Res<'w, CurrentUserProfile>,
+    wallet: Res<'w, Wallet>,
+    social: Res<'w, SocialClient>,
+    containing_scene: ContainingScene<'w, 's>,
+    scenes: Query<'w, 's, &'static RendererSceneContext>,
+    user: Query<'w, 's, (Entity, &'static GlobalTransform), With<PrimaryUser>>,
+    camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<PrimaryCamera>>,
+    players: Query<
+        'w,
+        's,
+        (
+            &'static ForeignPlayer,
+            &'static GlobalTransform,
+            Option<&'static UserProfile>,
+        ),
+    >,
+}
+
+impl PhotoContext<'_, '_> {
+    pub fn metadata(&self) -> PhotoMetadata {
+        let address = self
+            .wallet
+            .address()
+            .map(|address| format!("{address:#x}"))
+            .unwrap_or_default();
+        let mut metadata = PhotoMetadata {
+            user_name: self
+                .profile
+                .profile
+                .as_ref()
+                .map(|profile| profile.content.name.clone())
+                .unwrap_or_default(),
+            user_address: address.clone(),
+            date_time: chrono::Utc::now().timestamp().to_string(),
+            realm: self
+                .realm
+                .config
+                .realm_name
+                .clone()
+                .unwrap_or_else(|| self.realm.address.clone()),
+            ..Default::default()
+        };
+
+        if let Ok((user, transform)) = self.user.get_single() {
+            let parcel = (transform.translation().xz() * Vec2::new(1.0, -1.0) / PARCEL_SIZE)
+                .floor()
+                .as_ivec2();
+            metadata.scene.location = PhotoLocation {
+                x: parcel.x.to_string(),
+                y: parcel.y.to_string(),
+            };
+            metadata.scene.name = self
+                .containing_scene
+                .get(user)
+                .into_iter()
+                .filter_map(|scene| self.scenes.get(scene).ok())
+                .find(|context| !context.is_portable)
+                .map(|context| context.title.clone())
+                .unwrap_or_default();
+
+            if self.is_visible(transform) {
+                if let Some(profile) = self.profile.profile.as_ref() {
+                    metadata
+                        .visible_people
+                        .push(PhotoPerson::new(address, profile));
+                }
+            }
+        }
+
+        // comms gives players no way to agree to being tagged, so only friends are
+        for (player, transform, profile) in self.players.iter() {
+            let is_friend = self
+                .social
+                .0
+                .as_ref()
+                .is_some_and(|client| client.friends.contains(&player.address));
+            let Some(profile) = profile.filter(|_| is_friend && self.is_visible(transform)) else {
+                continue;
+            };
+            metadata
+                .visible_people
+                .push(PhotoPerson::new(format!("{:#x}", player.address), profile));
+        }
+
+        metadata
+    }
+
+    // whether an avatar is on screen and close enough to make out
+    fn is_visible(&self, avatar: &GlobalTransform) -> bool {
+        let Ok((camera, camera_transform)) = self.camera.get_single() else {
+            return false;
+        };
+        // roughly the avatar's chest
+        let target = avatar.translation() + Vec3::Y;
+        if target.distance(camera_transform.translation()) > TAG_DISTANCE {
+            return false;
+        }
+        let (Some(point), Some(size)) = (
+            camera.world_to_viewport(camera_transform, target),
+            camera.logical_viewport_size(),
+        ) else {
+            return false;
+        };
+        point.cmpge(Vec2::ZERO).all() && point.cmple(size).all()
+    }
+}
+
+// photos in the gallery folder, newest first
+fn gallery_photos() -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(gallery_dir()) else {
+        return Vec::default();
+    };
+    let mut photos = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+        .collect::<Vec<_>>();
+    // names are timestamped
+    photos.sort();
+    photos.reverse();
+    photos
+}
+
+#[derive(Component, Default)]
+pub struct GalleryView {
+    refresh: bool,
+}
+
+fn set_gallery_content(
+    mut commands: Commands,
+    dialog: Query<Entity, With<SettingsDialog>>,
+    q: Query<(Entity, &SettingsTab), Changed<SettingsTab>>,
+    mut prev_tab: Local<Option<SettingsTab>>,
+    dui: Res<DuiRegistry>,
+) {
+    if dialog.is_empty() {
+        *prev_tab = None;
+    }
+
+    for (ent, tab) in q.iter() {
+        if *prev_tab == Some(*tab) {
+            continue;
+        }
+        *prev_tab = Some(*tab);
+
+        if tab != &SettingsTab::Gallery {
+            return;
+        }
+
+        commands.entity(ent).despawn_descendants();
+        let props = DuiProps::new().with_prop("open-folder", On::<Click>::new(open_gallery_folder));
+        commands
+            .entity(ent)
+            .apply_template(&dui, "gallery", props)
+            .unwrap();
+        commands
+            .entity(ent)
+            .try_insert(GalleryView { refresh: true });
+    }
+}
+
+pub fn open_gallery_folder() {
+    let dir = gallery_dir();
+    if let Err(e) = std::fs::create_dir_all(&dir)
+        .and_then(|_| opener::open(&dir).map_err(std::io::Error::other))
+    {
+        warn!("failed to open gallery {}: {e}", dir.display());
+    }
+}
+
+fn photo_title(photo: &Path, metadata: Option<&PhotoMetadata>) -> String {
+    match metadata {
+        Some(metadata) if !metadata.scene.name.is_empty() => metadata.scene.name.clone(),
+        Some(metadata) => format!(
+            "{},{}",
+            metadata.scene.location.x, metadata.scene.location.y
+        ),
+        None => photo
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    }
+}
+
+fn photo_date(metadata: &PhotoMetadata) -> String {
+    metadata
+        .date_time
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map(|date| {
+            date.with_timezone(&chrono::Local)
+                .format("%d/%m/%Y %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn update_gallery(
+    mut commands: Commands,
+    mut view: Query<(&mut GalleryView, &DuiEntities)>,
+    dui: Res<DuiRegistry>,
+    asset_server: Res<AssetServer>,
+    mut texts: Query<&mut Text>,
+) {
+    let Ok((mut view, components)) = view.get_single_mut() else {
+        return;
+    };
+    if !view.refresh {
+        return;
+    }
+    view.refresh = false;
+
+    let Some(items) = components.get_named("items") else {
+        warn!("no gallery items node");
+        return;
+    };
+    commands.entity(items).despawn_descendants();
+
+    let photos = gallery_photos();
+    if let Some(status) = components.get_named("status") {
+        let text = match photos.len() {
+            0 => tr!("gallery-empty"),
+            n if n > MAX_SHOWN => tr!("gallery-showing-latest", count = MAX_SHOWN, total = n),
+            _ => String::default(),
+        };
+        if let Ok(mut status) = texts.get_mut(status) {
+            status.sections[0].value = text;
+        }
+    }
+
+    for photo in photos.into_iter().take(MAX_SHOWN) {
+        let metadata = load_metadata(&photo);
+        let image = asset_server.load::<Image>(photo.clone());
+        let uploaded = metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.uploaded_url.is_some());
+
+        let props = DuiProps::new()
+            .with_prop("img", image)
+            .with_prop("label", photo_title(&photo, metadata.as_ref()))
+            .with_prop(
+                "date",
+                metadata.as_ref().map(photo_date).unwrap_or_default(),
+            )
+            .with_prop(
+                "uploaded",
+                if uploaded {
+                    tr!("gallery-uploaded")
+                } else {
+                    String::default()
+                },
+            );
+        let item = commands
+            .spawn_template(&dui, "gallery-photo", props)
+            .unwrap()
+            .root;
+        commands.entity(item).insert((
+            Interaction::default(),
+            On::<Click>::new(
+                move |mut commands: Commands,
+                      dui: Res<DuiRegistry>,
+                      asset_server: Res<AssetServer>| {
+                    spawn_photo_popup(&mut commands, &dui, &asset_server, photo.clone());
+                },
+            ),
+        ));
+        commands.entity(items).add_child(item);
+    }
+}
+
+fn spawn_photo_popup(
+    commands: &mut Commands,
+    dui: &DuiRegistry,
+    asset_server: &AssetServer,
+    photo: PathBuf,
+) {
+    let metadata = load_metadata(&photo);
+    let image = asset_server.load::<Image>(photo.clone());
+
+    let details = match metadata.as_ref() {
+        Some(metadata) => {
+            let people = metadata
+                .visible_people
+                .iter()
+                .map(|person| person.user_name.clone())
+                .collect::<Vec<_>>();
+            tr!(
+                "gallery-details",
+                realm = metadata.realm.clone(),
+                x = metadata.scene.location.x.clone(),
+                y = metadata.scene.location.y.clone(),
+                date = photo_date(metadata),
+                people = if people.is_empty() {
+                    "-".to_owned()
+                } else {
+                    people.join(", ")
+                }
+            )
+        }
+        None => tr!("gallery-no-details"),
+    };
+
+    let upload_photo = photo.clone();
+    let upload = match metadata.as_ref().and_then(|m| m.uploaded_url.clone()) {
+        Some(url) => DuiButton::new_enabled(tr!("gallery-view-online"), move || {
+            let _ = opener::open(&url);
+        }),
+        None => DuiButton::new(
+            tr!("gallery-upload"),
+            metadata.is_some(),
+            move |mut uploads: ResMut<GalleryUploads>,
+                  wallet: Res<Wallet>,
+                  mut toaster: Toaster| {
+                uploads.start(upload_photo.clone(), wallet.clone(), &mut toaster);
+            },
+        ),
+    };
+
+    let delete_photo = photo.clone();
+    let buttons = vec![
+        upload,
+        DuiButton::new_enabled_and_close_happy(
+            tr!("gallery-delete"),
+            move |mut view: Query<&mut GalleryView>| {
+                for path in [delete_photo.clone(), sidecar_path(&delete_photo)] {
+                    if let Err(e) = std::fs::remove_file(&path) {
+                        if e.kind() != std::io::ErrorKind::NotFound {
+                            warn!("failed to delete {}: {e}", path.display());
+                        }
+                    }
+                }
+                if let Ok(mut view) = view.get_single_mut() {
+                    view.refresh = true;
+                }
+            },
+        ),
+        DuiButton::close_silent(tr!("button-close")),
+    ];
+
+    let props = DuiProps::new()
+        .with_prop("close", On::<Click>::new(DuiButton::close_dialog))
+        .with_prop("img", image)
+        .with_prop("title", photo_title(&photo, metadata.as_ref()))
+        .with_prop("details", details)
+        .with_prop("buttons", buttons);
+
+    commands
+        .spawn_template(dui, "gallery-popup", props)
+        .unwrap();
+}
+
+#[derive(Resource, Default)]
+pub struct GalleryUploads {
+    tasks: Vec<(PathBuf, Task<Result<String, anyhow::Error>>)>,
+}
+
+impl GalleryUploads {
+    fn start(&mut self, photo: PathBuf, wallet: Wallet, toaster: &mut Toaster) {
+        if self.tasks.iter().any(|(path, _)| path == &photo) {
+            return;
+        }
+        let Some(metadata) = load_metadata(&photo) else {
+            return;
+        };
+        toaster.add_toast("gallery", tr!("gallery-uploading"));
+        let path = photo.clone();
+        self.tasks.push((
+            photo,
+            IoTaskPool::get().spawn(async move { upload_photo(wallet, path, metadata).await }),
+        ));
+    }
+}
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    image: UploadedImage,
+}
+
+#[derive(Deserialize)]
+struct UploadedImage {
+    url: String,
+}
+
+async fn upload_photo(
+    wallet: Wallet,
+    photo: PathBuf,
+    metadata: PhotoMetadata,
+) -> Result<String, anyhow::Error> {
+    if wallet.address().is_none() {
+        bail!("not signed in");
+    }
+
+    let image = std::fs::read(&photo)?;
+    let metadata = serde_json::to_string(&metadata)?;
+    let headers = wallet::sign_request(
+        "post",
+        &Uri::from_static(CAMERA_REEL_URL),
+        &wallet,
+        serde_json::Map::new(),
+    )
+    .await?;
+
+    let boundary = format!(
+        "----bevy-explorer-{}",
+        chrono::Utc::now().timestamp_millis()
+    );
+    let file_name = photo
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "photo.png".to_owned());
+    let mut body = Vec::default();
+    body.extend(
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"{file_name}\"\r\nContent-Type: image/png\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend(image);
+    body.extend(
+        format!(
+            "\r\n--{boundary}\r\nContent-Disposition: form-data; name=\"metadata\"\r\n\r\n{metadata}\r\n--{boundary}--\r\n"
+        )
+        .as_bytes(),
+    );
+
+    let mut request = isahc::Request::post(CAMERA_REEL_URL).header(
+        "Content-Type",
+        format!("multipart/form-data; boundary={boundary}"),
+    );
+    for (key, value) in headers {
+        request = request.header(key, value);
+    }
+    let mut response = request.body(body)?.send_async().await?;
+    if !response.status().is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(anyhow!("{}: {text}", response.status()));
+    }
+    Ok(response.json::<UploadResponse>().await?.image.url)
+}
+
+fn update_uploads(
+    mut uploads: ResMut<GalleryUploads>,
+    mut toaster: Toaster,
+    mut view: Query<&mut GalleryView>,
+) {
+    let mut finished = false;
+    uploads.tasks.retain_mut(|(photo, task)| {
+        let Some(result) = task.complete() else {
+            return true;
+        };
+        let result = result.and_then(|url| {
+            let mut metadata = load_metadata(photo).unwrap_or_default();
+            metadata.uploaded_url = Some(url);
+            save_metadata(photo, &metadata)
+        });
+        match result {
+            Ok(()) => toaster.add_toast("gallery", tr!("gallery-upload-complete")),
+            Err(e) => {
+                warn!("failed to upload {}: {e}", photo.display());
+                toaster.add_toast("gallery", tr!("gallery-upload-failed", error = e));
+            }
+        }
+        finished = true;
+        false
+    });
+
+    if finished {
+        if let Ok(mut view) = view.get_single_mut() {
+            view.refresh = true;
+        }
+    }
+}
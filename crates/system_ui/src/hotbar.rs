@@ -0,0 +1,305 @@
+// quick action hotbar. slots hold emotes, scene actions (used by smart wearables), and shortcuts,
+// and are triggered by the number keys 1-9 or by clicking. slots are edited with `/hotbar` or by
+// dragging actions (e.g. emotes from the backpack) onto them, and persisted in the app config.
+
+use av::microphone::MicState;
+use avatar::animate::{EmoteBroadcast, EmoteList};
+use bevy::prelude::*;
+use bevy_console::ConsoleCommand;
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use collectibles::{CollectibleManager, Emote, EmoteUrn};
+use common::{
+    structs::{
+        ActiveDialog, AppConfig, HotbarAction, HudElement, InputItem, PrimaryUser, SettingsTab,
+        ShowSettingsEvent, SystemAudio, HOTBAR_SLOTS,
+    },
+    tr,
+    util::FireEventEx,
+};
+use console::{slash_commands::CommandDispatcher, DoAddConsoleCommand};
+use dcl_component::proto_components::sdk::components::common::InputAction;
+use input_manager::{virtual_actions::VirtualActions, InputManager};
+use ui_core::{
+    drag_drop::{DragOver, DropTarget, Dropped, UiDrag},
+    theme::UiTheme,
+    ui_actions::{Click, On, UiCaller},
+};
+
+use crate::{emote_select::EmoteDialog, hud_layout::HudElementNode};
+
+pub struct HotbarPlugin;
+
+impl Plugin for HotbarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HotbarSlotEvent>();
+        app.add_systems(OnEnter::<ui_core::State>(ui_core::State::Ready), setup);
+        app.add_systems(
+            Update,
+            (update_hotbar, hotbar_keys, run_hotbar_slots).chain(),
+        );
+        app.add_systems(Update, show_drop_targets);
+        app.add_console_command::<HotbarCommand, _>(hotbar_command);
+    }
+}
+
+#[derive(Component)]
+struct Hotbar;
+
+#[derive(Component)]
+struct HotbarSlot;
+
+// a slot was triggered
+#[derive(Event, Clone, Copy)]
+struct HotbarSlotEvent(usize);
+
+const SLOT_KEYS: [KeyCode; HOTBAR_SLOTS] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+fn setup(mut commands: Commands, dui: Res<DuiRegistry>) {
+    let components = commands
+        .spawn_template(&dui, "hotbar", DuiProps::default())
+        .unwrap();
+    commands
+        .entity(components.root)
+        .insert(HudElementNode(HudElement::Hotbar));
+    commands.entity(components.named("slots")).insert(Hotbar);
+}
+
+fn slot_label(action: &HotbarAction, emote_loader: &mut CollectibleManager<Emote>) -> String {
+    match action {
+        HotbarAction::Emote(urn) => EmoteUrn::new(urn)
+            .ok()
+            .and_then(|emote| emote_loader.get_data(emote).ok())
+            .map(|emote| emote.name.clone())
+            .unwrap_or_else(|| urn.clone()),
+        HotbarAction::SceneAction(action) => action
+            .strip_prefix("IaAction")
+            .map(|n| tr!("hotbar-action", n = n))
+            .unwrap_or_else(|| action.clone()),
+        HotbarAction::OpenMap => tr!("hotbar-map"),
+        HotbarAction::ToggleMic => tr!("hotbar-mic"),
+        HotbarAction::Command(command) => command.clone(),
+    }
+}
+
+fn update_hotbar(
+    mut commands: Commands,
+    hotbar: Query<(Entity, Option<&Children>), With<Hotbar>>,
+    config: Res<AppConfig>,
+    dui: Res<DuiRegistry>,
+    mut emote_loader: CollectibleManager<Emote>,
+    mut labels: Local<Vec<String>>,
+) {
+    let Ok((hotbar, children)) = hotbar.get_single() else {
+        return;
+    };
+
+    // emote names may arrive and the language may change after the config changes, so compare the
+    // labels rather than the config
+    let new_labels = (0..HOTBAR_SLOTS)
+        .map(|i| {
+            config
+                .hotbar
+                .get(i)
+                .and_then(Option::as_ref)
+                .map(|action| slot_label(action, &mut emote_loader))
+                .unwrap_or_else(|| "-".to_owned())
+        })
+        .collect::<Vec<_>>();
+
+    if *labels == new_labels && children.is_some_and(|c| !c.is_empty()) {
+        return;
+    }
+
+    commands.entity(hotbar).despawn_descendants();
+    for (i, label) in new_labels.iter().enumerate() {
+        let props = DuiProps::new()
+            .with_prop("key", format!("{}", i + 1))
+            .with_prop("label", label.clone())
+            .with_prop(
+                "onclick",
+                On::<Click>::new(move |mut e: EventWriter<HotbarSlotEvent>| {
+                    e.send(HotbarSlotEvent(i));
+                }),
+            );
+        let slot = commands
+            .entity(hotbar)
+            .spawn_template(&dui, "hotbar-slot", props)
+            .unwrap()
+            .root;
+        commands.entity(slot).insert((
+            HotbarSlot,
+            DropTarget::new::<HotbarAction>(),
+            On::<Dropped>::new(
+                move |caller: Res<UiCaller>,
+                      dropped: Query<&Dropped>,
+                      mut config: ResMut<AppConfig>| {
+                    let Some(action) = dropped
+                        .get(caller.0)
+                        .ok()
+                        .and_then(Dropped::payload::<HotbarAction>)
+                    else {
+                        return;
+                    };
+                    config.hotbar.resize(HOTBAR_SLOTS, None);
+                    config.hotbar[i] = Some(action.clone());
+                },
+            ),
+        ));
+    }
+    *labels = new_labels;
+}
+
+// while a hotbar action is dragged, raise the hotbar above any open dialog (e.g. the backpack) so
+// it can be dropped on, and highlight the slot under the cursor
+fn show_drop_targets(
+    mut commands: Commands,
+    drag: Res<UiDrag>,
+    theme: Res<UiTheme>,
+    root: Query<(Entity, &HudElementNode, Option<&ZIndex>)>,
+    mut slots: Query<(&mut BackgroundColor, Has<DragOver>), With<HotbarSlot>>,
+) {
+    let dragging = drag.payload::<HotbarAction>().is_some();
+
+    for (entity, element, z_index) in root.iter() {
+        if element.0 != HudElement::Hotbar {
+            continue;
+        }
+        let raised = matches!(z_index, Some(ZIndex::Global(_)));
+        if dragging && !raised {
+            commands
+                .entity(entity)
+                .insert(ZIndex::Global(i16::MAX as i32 + 5));
+        } else if !dragging && raised {
+            commands.entity(entity).insert(ZIndex::default());
+        }
+    }
+
+    for (mut background, over) in slots.iter_mut() {
+        let color = if over {
+            theme.accent.with_alpha(0.5)
+        } else {
+            Color::NONE
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
+
+fn hotbar_keys(
+    input: InputManager,
+    active_dialog: Res<ActiveDialog>,
+    emote_dialog: Query<(), With<EmoteDialog>>,
+    mut events: EventWriter<HotbarSlotEvent>,
+) {
+    // the emote wheel uses the number keys itself
+    if active_dialog.in_use() || !emote_dialog.is_empty() {
+        return;
+    }
+
+    for (i, key) in SLOT_KEYS.into_iter().enumerate() {
+        if input.unbound_just_down(&InputItem::Key(key).into()) {
+            events.send(HotbarSlotEvent(i));
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_hotbar_slots(
+    mut commands: Commands,
+    mut events: EventReader<HotbarSlotEvent>,
+    config: Res<AppConfig>,
+    player: Query<Entity, With<PrimaryUser>>,
+    mut virtual_actions: ResMut<VirtualActions>,
+    mut mic_state: ResMut<MicState>,
+    mut dispatcher: CommandDispatcher,
+) {
+    for HotbarSlotEvent(slot) in events.read() {
+        let Some(action) = config.hotbar.get(*slot).and_then(Option::as_ref) else {
+            continue;
+        };
+
+        match action {
+            HotbarAction::Emote(urn) => {
+                if let Ok(player) = player.get_single() {
+                    commands
+                        .entity(player)
+                        .try_insert(EmoteList::new(urn.clone(), EmoteBroadcast::All));
+                }
+            }
+            HotbarAction::SceneAction(name) => match InputAction::from_str_name(name) {
+                Some(action) => virtual_actions.press(action),
+                None => warn!("hotbar action not recognized: `{name}`"),
+            },
+            HotbarAction::OpenMap => commands.fire_event(ShowSettingsEvent(SettingsTab::Map)),
+            HotbarAction::ToggleMic => {
+                mic_state.enabled = !mic_state.enabled;
+                if mic_state.enabled {
+                    commands.fire_event(SystemAudio("sounds/ui/voice_chat_mic_on.wav".to_owned()));
+                } else {
+                    commands.fire_event(SystemAudio("sounds/ui/voice_chat_mic_off.wav".to_owned()));
+                }
+            }
+            HotbarAction::Command(command) => {
+                if let Err(e) = dispatcher.run(command) {
+                    warn!("hotbar command failed: {e}");
+                }
+            }
+        }
+    }
+}
+
+/// set a hotbar slot. kinds: `emote <urn>`, `action <IaAction3-6>`, `map`, `mic`,
+/// `command <command line>`, `clear`
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/hotbar")]
+struct HotbarCommand {
+    slot: usize,
+    #[arg(value_parser = ["emote", "action", "map", "mic", "command", "clear"])]
+    kind: String,
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+fn hotbar_command(mut input: ConsoleCommand<HotbarCommand>, mut config: ResMut<AppConfig>) {
+    if let Some(Ok(command)) = input.take() {
+        if !(1..=HOTBAR_SLOTS).contains(&command.slot) {
+            input.reply_failed(tr!("console-hotbar-range", max = HOTBAR_SLOTS));
+            return;
+        }
+
+        let arg = command.args.join(" ");
+        let action = match (command.kind.as_str(), arg.is_empty()) {
+            ("emote", false) => Some(HotbarAction::Emote(arg)),
+            ("action", false) => {
+                if InputAction::from_str_name(&arg).is_none() {
+                    input.reply_failed(tr!("console-hotbar-unknown-action", action = arg));
+                    return;
+                }
+                Some(HotbarAction::SceneAction(arg))
+            }
+            ("map", _) => Some(HotbarAction::OpenMap),
+            ("mic", _) => Some(HotbarAction::ToggleMic),
+            ("command", false) => Some(HotbarAction::Command(arg)),
+            ("clear", _) => None,
+            _ => {
+                input.reply_failed(tr!("console-hotbar-usage"));
+                return;
+            }
+        };
+
+        config.hotbar.resize(HOTBAR_SLOTS, None);
+        config.hotbar[command.slot - 1] = action;
+        input.reply_ok(tr!("console-hotbar-updated", slot = command.slot));
+    }
+}
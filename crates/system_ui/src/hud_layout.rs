@@ -0,0 +1,387 @@
+// hud layout customization. hud elements can be moved, scaled and hidden in an edit mode (entered
+// from the settings or with `/hud_edit`), and layouts are stored per resolution in the app config.
+
+use bevy::{prelude::*, utils::HashMap, window::PrimaryWindow};
+use bevy_console::ConsoleCommand;
+use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps, DuiRegistry};
+use common::{
+    structs::{AppConfig, HudElement, HudElementLayout, PhotoMode},
+    tr,
+};
+use console::DoAddConsoleCommand;
+use ui_core::ui_actions::{Click, DragData, Dragged, On, UiCaller};
+
+pub struct HudLayoutPlugin;
+
+impl Plugin for HudLayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HudEditMode>();
+        app.add_systems(Update, (apply_hud_layouts, update_hud_overlays).chain());
+        app.add_console_command::<HudEditCommand, _>(hud_edit_command);
+    }
+}
+
+/// marks the root node of a hud element that can be repositioned
+#[derive(Component, Clone, Copy)]
+pub struct HudElementNode(pub HudElement);
+
+#[derive(Resource, Default)]
+pub struct HudEditMode {
+    active: bool,
+    // working copy of the layouts for the current resolution
+    layouts: HashMap<HudElement, HudElementLayout>,
+}
+
+// the element's style before any layout is applied
+#[derive(Component, Clone)]
+struct HudBase {
+    left: Val,
+    right: Val,
+    top: Val,
+    bottom: Val,
+    min_height: Val,
+    // display to restore when leaving edit mode
+    saved_display: Option<Display>,
+}
+
+impl From<&Style> for HudBase {
+    fn from(style: &Style) -> Self {
+        Self {
+            left: style.left,
+            right: style.right,
+            top: style.top,
+            bottom: style.bottom,
+            min_height: style.min_height,
+            saved_display: None,
+        }
+    }
+}
+
+#[derive(Component)]
+struct HudOverlay(HudElement);
+
+#[derive(Component)]
+struct HudEditPanel;
+
+const MIN_SCALE: f32 = 0.5;
+const MAX_SCALE: f32 = 2.0;
+
+fn resolution_key(window: &Window) -> String {
+    format!("{}x{}", window.physical_width(), window.physical_height())
+}
+
+fn start_edit(edit: &mut HudEditMode, config: &AppConfig, window: &Window) {
+    edit.layouts = config
+        .hud_layouts
+        .get(&resolution_key(window))
+        .cloned()
+        .unwrap_or_default();
+    edit.active = true;
+}
+
+fn finish_edit(edit: &mut HudEditMode, config: &mut AppConfig, window: &Window) {
+    edit.active = false;
+    let layouts = std::mem::take(&mut edit.layouts);
+    let key = resolution_key(window);
+    if layouts
+        .values()
+        .all(|layout| layout == &HudElementLayout::default())
+    {
+        config.hud_layouts.remove(&key);
+    } else {
+        config.hud_layouts.insert(key, layouts);
+    }
+}
+
+pub fn begin_hud_edit(
+    mut edit: ResMut<HudEditMode>,
+    config: Res<AppConfig>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if let Ok(window) = window.get_single() {
+        start_edit(&mut edit, &config, window);
+    }
+}
+
+fn end_hud_edit(
+    mut edit: ResMut<HudEditMode>,
+    mut config: ResMut<AppConfig>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if let Ok(window) = window.get_single() {
+        finish_edit(&mut edit, &mut config, window);
+    }
+}
+
+/// toggle hud edit mode, to move, scale and hide hud elements
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/hud_edit")]
+struct HudEditCommand;
+
+fn hud_edit_command(
+    mut input: ConsoleCommand<HudEditCommand>,
+    mut edit: ResMut<HudEditMode>,
+    mut config: ResMut<AppConfig>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if let Some(Ok(_)) = input.take() {
+        let Ok(window) = window.get_single() else {
+            input.reply_failed(tr!("console-no-window"));
+            return;
+        };
+        if edit.active {
+            finish_edit(&mut edit, &mut config, window);
+            input.reply_ok(tr!("console-hud-saved"));
+        } else {
+            start_edit(&mut edit, &config, window);
+            input.reply_ok(tr!("console-hud-editing"));
+        }
+    }
+}
+
+// convert a position value to vmin. percentages are relative to the window, as hud elements are
+// children of the root node
+fn to_vmin(val: Val, axis: f32, size: Vec2) -> Option<f32> {
+    let vmin = size.min_element() / 100.0;
+    match val {
+        Val::Auto => None,
+        Val::Px(px) => Some(px / vmin),
+        Val::Percent(pc) => Some(pc * axis / 100.0 / vmin),
+        Val::Vw(vw) => Some(vw * size.x / 100.0 / vmin),
+        Val::Vh(vh) => Some(vh * size.y / 100.0 / vmin),
+        Val::VMin(vmin) => Some(vmin),
+        Val::VMax(vmax) => Some(vmax * size.max_element() / size.min_element()),
+    }
+}
+
+// move a node along one axis, keeping its size if it is anchored on both sides
+fn shift(start: Val, end: Val, offset: f32, axis: f32, size: Vec2) -> (Val, Val) {
+    if offset == 0.0 {
+        return (start, end);
+    }
+
+    match (to_vmin(start, axis, size), to_vmin(end, axis, size)) {
+        (None, Some(end)) => (start, Val::VMin(end - offset)),
+        (start, end_vmin) => (
+            Val::VMin(start.unwrap_or(0.0) + offset),
+            end_vmin.map(|end| Val::VMin(end - offset)).unwrap_or(end),
+        ),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_hud_layouts(
+    mut commands: Commands,
+    config: Res<AppConfig>,
+    edit: Res<HudEditMode>,
+    photo: Res<PhotoMode>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut nodes: Query<(
+        Entity,
+        &HudElementNode,
+        Option<&mut HudBase>,
+        &mut Style,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    mut last_size: Local<Vec2>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    let size = Vec2::new(window.width(), window.height());
+    let resized = size != *last_size;
+    *last_size = size;
+    let uncaptured = nodes.iter().any(|(_, _, base, ..)| base.is_none());
+    if !(config.is_changed() || edit.is_changed() || photo.is_changed() || resized || uncaptured) {
+        return;
+    }
+
+    let saved = config.hud_layouts.get(&resolution_key(window));
+
+    for (ent, element, base_ref, mut style, mut transform, mut visibility) in nodes.iter_mut() {
+        let mut base = base_ref
+            .as_deref()
+            .cloned()
+            .unwrap_or_else(|| HudBase::from(&*style));
+
+        let layout = if edit.active {
+            edit.layouts.get(&element.0)
+        } else {
+            saved.and_then(|layouts| layouts.get(&element.0))
+        }
+        .copied()
+        .unwrap_or_default();
+
+        let (left, right) = shift(base.left, base.right, layout.offset.x, size.x, size);
+        let (top, bottom) = shift(base.top, base.bottom, layout.offset.y, size.y, size);
+        if (style.left, style.right, style.top, style.bottom) != (left, right, top, bottom) {
+            style.left = left;
+            style.right = right;
+            style.top = top;
+            style.bottom = bottom;
+        }
+
+        // show everything while editing, with enough height to grab empty elements
+        if edit.active {
+            if base.saved_display.is_none() {
+                base.saved_display = Some(style.display);
+                style.display = Display::Flex;
+                style.min_height = Val::VMin(10.0);
+            }
+        } else if let Some(display) = base.saved_display.take() {
+            style.display = display;
+            style.min_height = base.min_height;
+        }
+
+        let scale = Vec3::splat(layout.scale.clamp(MIN_SCALE, MAX_SCALE));
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+
+        // photo mode hides the whole hud
+        let target_visibility = if photo.active || (layout.hidden && !edit.active) {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+        if *visibility != target_visibility {
+            *visibility = target_visibility;
+        }
+
+        match base_ref {
+            Some(mut base_ref) => *base_ref = base,
+            None => {
+                commands.entity(ent).insert(base);
+            }
+        }
+    }
+}
+
+fn overlay_label(element: HudElement, layout: &HudElementLayout) -> String {
+    let hidden = if layout.hidden { " (hidden)" } else { "" };
+    format!("{} - {:.0}%{hidden}", element.label(), layout.scale * 100.0)
+}
+
+fn edit_layout(
+    element: HudElement,
+    f: impl Fn(&mut HudElementLayout) + Send + Sync + 'static,
+) -> On<Click> {
+    On::<Click>::new(move |mut edit: ResMut<HudEditMode>| {
+        f(edit.layouts.entry(element).or_default());
+    })
+}
+
+fn step_scale(scale: f32, step: f32) -> f32 {
+    ((scale + step) * 10.0)
+        .round()
+        .clamp(MIN_SCALE * 10.0, MAX_SCALE * 10.0)
+        / 10.0
+}
+
+fn drag_overlay(
+    caller: Res<UiCaller>,
+    overlays: Query<(&HudOverlay, &DragData)>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut edit: ResMut<HudEditMode>,
+) {
+    let (Ok((overlay, drag)), Ok(window)) = (overlays.get(caller.0), window.get_single()) else {
+        return;
+    };
+    let vmin = window.width().min(window.height()) / 100.0;
+    edit.layouts.entry(overlay.0).or_default().offset += drag.delta_pixels / vmin;
+}
+
+fn step_ui_scale(step: f32) -> On<Click> {
+    On::<Click>::new(move |mut config: ResMut<AppConfig>| {
+        config.ui_scale = ((config.ui_scale + step) * 20.0).round().clamp(10.0, 40.0) / 20.0;
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_hud_overlays(
+    mut commands: Commands,
+    dui: Res<DuiRegistry>,
+    edit: Res<HudEditMode>,
+    config: Res<AppConfig>,
+    nodes: Query<(Entity, &HudElementNode)>,
+    overlays: Query<(Entity, &HudOverlay, &DuiEntities)>,
+    panel: Query<(Entity, &DuiEntities), With<HudEditPanel>>,
+    mut text: Query<&mut Text>,
+) {
+    if !edit.is_changed() && !config.is_changed() {
+        return;
+    }
+
+    if !edit.active {
+        for ent in overlays
+            .iter()
+            .map(|(ent, ..)| ent)
+            .chain(panel.iter().map(|(ent, _)| ent))
+        {
+            commands.entity(ent).despawn_recursive();
+        }
+        return;
+    }
+
+    let ui_scale = format!("UI Scale: {:.0}%", config.ui_scale * 100.0);
+
+    let Ok((_, panel_nodes)) = panel.get_single() else {
+        let panel = commands
+            .spawn_template(
+                &dui,
+                "hud-edit-panel",
+                DuiProps::new()
+                    .with_prop("ui-scale", ui_scale)
+                    .with_prop("ui-scale-down", step_ui_scale(-0.05))
+                    .with_prop("ui-scale-up", step_ui_scale(0.05))
+                    .with_prop(
+                        "reset",
+                        On::<Click>::new(|mut edit: ResMut<HudEditMode>| edit.layouts.clear()),
+                    )
+                    .with_prop("done", On::<Click>::new(end_hud_edit)),
+            )
+            .unwrap();
+        commands.entity(panel.root).insert(HudEditPanel);
+
+        for (ent, element) in nodes.iter() {
+            let element = element.0;
+            let layout = edit.layouts.get(&element).copied().unwrap_or_default();
+            let overlay = commands
+                .entity(ent)
+                .spawn_template(
+                    &dui,
+                    "hud-element-overlay",
+                    DuiProps::new()
+                        .with_prop("label", overlay_label(element, &layout))
+                        .with_prop(
+                            "scale-down",
+                            edit_layout(element, |l| l.scale = step_scale(l.scale, -0.1)),
+                        )
+                        .with_prop(
+                            "scale-up",
+                            edit_layout(element, |l| l.scale = step_scale(l.scale, 0.1)),
+                        )
+                        .with_prop("hide", edit_layout(element, |l| l.hidden = !l.hidden)),
+                )
+                .unwrap();
+            commands.entity(overlay.root).insert((
+                HudOverlay(element),
+                Interaction::default(),
+                On::<Dragged>::new(drag_overlay),
+            ));
+        }
+        return;
+    };
+
+    if let Ok(mut text) = text.get_mut(panel_nodes.named("ui-scale")) {
+        text.sections[0].value = ui_scale;
+    }
+
+    for (_, overlay, nodes) in overlays.iter() {
+        let layout = edit.layouts.get(&overlay.0).copied().unwrap_or_default();
+        if let Ok(mut text) = text.get_mut(nodes.named("label")) {
+            text.sections[0].value = overlay_label(overlay.0, &layout);
+        }
+    }
+}
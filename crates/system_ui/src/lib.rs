@@ -1,43 +1,86 @@
 pub mod app_settings;
 pub mod change_realm;
 pub mod chat;
+pub mod command_bindings;
+pub mod console_macros;
 pub mod crash_report;
 pub mod discover;
 pub mod emote_select;
 pub mod emotes;
 pub mod foreign_profile;
+pub mod gallery;
+pub mod hotbar;
+pub mod hud_layout;
+pub mod live_events;
+pub mod localization;
 pub mod login;
 pub mod map;
+pub mod map_markers;
+pub mod marketplace;
 pub mod mic;
+pub mod mixer;
+pub mod narration;
+pub mod notifications;
 pub mod oow;
+pub mod perf_hud;
 pub mod permission_manager;
 pub mod permissions;
+pub mod photo_mode;
 pub mod profile;
 pub mod profile_detail;
+pub mod quests;
+pub mod realm_history;
+pub mod remote_control;
+pub mod scene_info;
+pub mod session_renewal;
 pub mod sysinfo;
+pub mod telemetry_panel;
+pub mod teleport_preview;
 pub mod toasts;
 pub mod tooltip;
+pub mod traffic_panel;
 pub mod version_check;
 pub mod wearables;
+pub mod world_inspector;
 
 use bevy::prelude::*;
 
 use change_realm::ChangeRealmPlugin;
+use command_bindings::CommandBindingsPlugin;
 use common::{
     sets::SetupSets,
     structs::{ActiveDialog, UiRoot},
 };
+use console_macros::ConsoleMacrosPlugin;
 use emote_select::EmoteUiPlugin;
 use foreign_profile::ForeignProfilePlugin;
+use hotbar::HotbarPlugin;
+use hud_layout::HudLayoutPlugin;
 use input_manager::MouseInteractionComponent;
+use live_events::LiveEventsPlugin;
+use localization::LocalizationPlugin;
 use login::LoginPlugin;
 use map::MapPlugin;
+use marketplace::MarketplacePlugin;
 use mic::MicUiPlugin;
+use mixer::MixerPlugin;
+use narration::NarrationPlugin;
+use notifications::NotificationsPlugin;
 use oow::OowUiPlugin;
+use perf_hud::PerfHudPlugin;
 use permission_manager::PermissionPlugin;
+use photo_mode::PhotoModePlugin;
 use profile_detail::ProfileDetailPlugin;
+use quests::QuestTrackerPlugin;
+use realm_history::RealmHistoryPlugin;
+use scene_info::SceneInfoPlugin;
+use session_renewal::SessionRenewalPlugin;
+use telemetry_panel::TelemetryPanelPlugin;
+use teleport_preview::TeleportPreviewPlugin;
 use toasts::ToastsPlugin;
 use tooltip::ToolTipPlugin;
+use traffic_panel::TrafficPanelPlugin;
+use world_inspector::WorldInspectorPlugin;
 
 use self::{chat::ChatPanelPlugin, profile::ProfileEditPlugin, sysinfo::SysInfoPanelPlugin};
 
@@ -67,6 +110,30 @@ impl Plugin for SystemUiPlugin {
             OowUiPlugin,
             PermissionPlugin,
             ForeignProfilePlugin,
+            PerfHudPlugin,
+        ));
+        app.add_plugins((
+            CommandBindingsPlugin,
+            NotificationsPlugin,
+            QuestTrackerPlugin,
+            HudLayoutPlugin,
+            PhotoModePlugin,
+            HotbarPlugin,
+            LocalizationPlugin,
+            NarrationPlugin,
+            MarketplacePlugin,
+            SessionRenewalPlugin,
+            MixerPlugin,
+            WorldInspectorPlugin,
+            TrafficPanelPlugin,
+        ));
+        app.add_plugins((
+            SceneInfoPlugin,
+            ConsoleMacrosPlugin,
+            TelemetryPanelPlugin,
+            RealmHistoryPlugin,
+            TeleportPreviewPlugin,
+            LiveEventsPlugin,
         ));
     }
 }
@@ -0,0 +1,373 @@
+// live and upcoming events from the events api: a panel listing them (`/events`, or from the
+// notification center), markers on the map, and "notify me" reminders that become a notification
+// with a jump-in action when the event starts.
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+};
+use bevy_console::ConsoleCommand;
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use chrono::{DateTime, Utc};
+use common::{
+    rpc::RpcCall,
+    structs::{AppConfig, EventReminder, NotificationCategory},
+    tr,
+    util::TaskExt,
+};
+use console::DoAddConsoleCommand;
+use ipfs::{ChangeRealmEvent, CurrentRealm};
+use isahc::AsyncReadResponseExt;
+use scene_runner::Toaster;
+use serde::Deserialize;
+use ui_core::{
+    button::DuiButton,
+    ui_actions::{Click, On},
+    BODY_TEXT_STYLE,
+};
+
+const EVENTS_API: &str = "https://events.decentraland.org/api/events";
+// how often to refresh the events list
+const EVENT_REFRESH_SECS: f32 = 300.0;
+// upcoming events starting within this many minutes are shown on the map
+const UPCOMING_MARKER_MINS: i64 = 120;
+
+pub struct LiveEventsPlugin;
+
+impl Plugin for LiveEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LiveEvents>();
+        app.add_event::<ShowEventsPanel>();
+        app.add_event::<EventStarted>();
+        app.add_systems(
+            Update,
+            (
+                refresh_events,
+                (remind_events, notify_started_events).chain(),
+                show_events_panel,
+                update_event_list,
+            ),
+        );
+        app.add_console_command::<EventsCommand, _>(events_command);
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct LiveEvent {
+    pub id: String,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub start_at: DateTime<Utc>,
+    pub finish_at: DateTime<Utc>,
+    #[serde(default)]
+    pub live: bool,
+    #[serde(default)]
+    pub world: bool,
+    // world name, for events in worlds
+    #[serde(default)]
+    pub server: Option<String>,
+    #[serde(default)]
+    pub total_attendees: usize,
+}
+
+impl LiveEvent {
+    pub fn parcel(&self) -> IVec2 {
+        IVec2::new(self.x, self.y)
+    }
+
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.live || (self.start_at <= now && now < self.finish_at)
+    }
+
+    // the realm to join for the event
+    pub fn realm_url(&self) -> String {
+        match self.server.as_ref().filter(|_| self.world) {
+            Some(world) => format!("https://worlds-content-server.decentraland.org/world/{world}"),
+            None => "https://realm-provider.decentraland.org/main".to_owned(),
+        }
+    }
+
+    pub fn location(&self) -> String {
+        match self.server.as_ref().filter(|_| self.world) {
+            Some(world) => world.clone(),
+            None => format!("{},{}", self.x, self.y),
+        }
+    }
+
+    pub fn when(&self, now: DateTime<Utc>) -> String {
+        if self.is_live(now) {
+            return format!("live until {}", self.finish_at.format("%H:%M UTC"));
+        }
+        match (self.start_at - now).num_minutes() {
+            mins @ 0..=59 => format!("starts in {mins}m"),
+            mins @ 60..=1439 => format!("starts in {}h {}m", mins / 60, mins % 60),
+            _ => format!("{}", self.start_at.format("%a %d %b %H:%M UTC")),
+        }
+    }
+
+    fn reminder(&self) -> EventReminder {
+        EventReminder {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            parcel: self.parcel(),
+            realm: self.realm_url(),
+            start: self.start_at.timestamp().max(0) as u64,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct EventList {
+    data: Vec<LiveEvent>,
+}
+
+#[derive(Resource, Default)]
+pub struct LiveEvents {
+    task: Option<Task<Result<EventList, anyhow::Error>>>,
+    next_refresh: f32,
+    pub events: Vec<LiveEvent>,
+}
+
+impl LiveEvents {
+    // live genesis city events at any of the parcels
+    pub fn live_at<'a>(
+        &'a self,
+        parcels: &'a [IVec2],
+        now: DateTime<Utc>,
+    ) -> impl Iterator<Item = &'a LiveEvent> {
+        self.events.iter().filter(move |event| {
+            !event.world && event.is_live(now) && parcels.contains(&event.parcel())
+        })
+    }
+
+    // genesis city events that are live or starting soon
+    pub fn map_markers(&self, now: DateTime<Utc>) -> impl Iterator<Item = &LiveEvent> {
+        self.events.iter().filter(move |event| {
+            !event.world
+                && (event.is_live(now)
+                    || (event.start_at - now).num_minutes() <= UPCOMING_MARKER_MINS)
+        })
+    }
+}
+
+fn refresh_events(mut events: ResMut<LiveEvents>, time: Res<Time>) {
+    // the list only changes when results arrive
+    if events.task.is_none() && time.elapsed_seconds() >= events.next_refresh {
+        let events = events.bypass_change_detection();
+        events.next_refresh = time.elapsed_seconds() + EVENT_REFRESH_SECS;
+        events.task = Some(IoTaskPool::get().spawn(async move {
+            let mut response = isahc::get_async(EVENTS_API).await?;
+            response.json::<EventList>().await.map_err(|e| anyhow!(e))
+        }));
+    }
+
+    if let Some(mut task) = events.bypass_change_detection().task.take() {
+        match task.complete() {
+            Some(Ok(list)) => {
+                let now = Utc::now();
+                events.events = list
+                    .data
+                    .into_iter()
+                    .filter(|event| event.finish_at > now)
+                    .collect();
+                // live first, then by start time
+                events
+                    .events
+                    .sort_by_key(|event| (!event.is_live(now), event.start_at));
+            }
+            Some(Err(e)) => warn!("events fetch failed: {e}"),
+            None => events.bypass_change_detection().task = Some(task),
+        }
+    }
+}
+
+// join the event's realm if needed and teleport to it
+fn jump_in(
+    parcel: IVec2,
+    realm_url: String,
+) -> impl FnMut(Res<CurrentRealm>, EventWriter<ChangeRealmEvent>, EventWriter<RpcCall>)
+       + Clone
+       + Send
+       + Sync
+       + 'static {
+    move |realm: Res<CurrentRealm>,
+          mut change_realm: EventWriter<ChangeRealmEvent>,
+          mut rpc: EventWriter<RpcCall>| {
+        if realm.address != realm_url {
+            change_realm.send(ChangeRealmEvent {
+                new_realm: realm_url.clone(),
+            });
+        }
+        // user initiated, so no scene permission is needed. applied on arrival after a realm change
+        rpc.send(RpcCall::TeleportPlayer {
+            scene: None,
+            to: parcel,
+            response: Default::default(),
+        });
+    }
+}
+
+// a reminded event has started
+#[derive(Event)]
+struct EventStarted(EventReminder);
+
+fn remind_events(mut config: ResMut<AppConfig>, mut started: EventWriter<EventStarted>) {
+    let now = Utc::now().timestamp().max(0) as u64;
+    if !config
+        .event_reminders
+        .iter()
+        .any(|reminder| reminder.start <= now)
+    {
+        return;
+    }
+
+    let (due, waiting) = std::mem::take(&mut config.event_reminders)
+        .into_iter()
+        .partition::<Vec<_>, _>(|reminder| reminder.start <= now);
+    config.event_reminders = waiting;
+    started.send_batch(due.into_iter().map(EventStarted));
+}
+
+fn notify_started_events(mut started: EventReader<EventStarted>, mut toaster: Toaster) {
+    for EventStarted(reminder) in started.read() {
+        let (parcel, realm) = (reminder.parcel, reminder.realm.clone());
+        toaster.notify(
+            NotificationCategory::Event,
+            format!("event-{}", reminder.id),
+            format!("{} is starting, click to jump in", reminder.name),
+            Some(Arc::new(move || {
+                On::<Click>::new(jump_in(parcel, realm.clone()))
+            })),
+        );
+    }
+}
+
+// open the events panel
+#[derive(Event, Clone)]
+pub struct ShowEventsPanel;
+
+/// show live and upcoming events
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/events")]
+struct EventsCommand;
+
+fn events_command(
+    mut input: ConsoleCommand<EventsCommand>,
+    mut show: EventWriter<ShowEventsPanel>,
+) {
+    if let Some(Ok(_)) = input.take() {
+        show.send(ShowEventsPanel);
+        input.ok();
+    }
+}
+
+#[derive(Component)]
+struct EventPanelList;
+
+fn show_events_panel(
+    mut commands: Commands,
+    mut show: EventReader<ShowEventsPanel>,
+    dui: Res<DuiRegistry>,
+    existing: Query<(), With<EventPanelList>>,
+) {
+    if show.read().last().is_none() || !existing.is_empty() {
+        return;
+    }
+
+    let components = commands
+        .spawn_template(
+            &dui,
+            "events",
+            DuiProps::new().with_prop("buttons", vec![DuiButton::close_happy(tr!("button-close"))]),
+        )
+        .unwrap();
+    commands
+        .entity(components.named("event-list"))
+        .insert(EventPanelList);
+}
+
+fn update_event_list(
+    mut commands: Commands,
+    list: Query<(Entity, Ref<EventPanelList>)>,
+    events: Res<LiveEvents>,
+    config: Res<AppConfig>,
+    dui: Res<DuiRegistry>,
+) {
+    let Ok((entity, list)) = list.get_single() else {
+        return;
+    };
+
+    if !events.is_changed() && !config.is_changed() && !list.is_added() {
+        return;
+    }
+
+    commands.entity(entity).despawn_descendants();
+
+    if events.events.is_empty() {
+        commands.entity(entity).with_children(|c| {
+            c.spawn(TextBundle::from_section(
+                "No upcoming events",
+                BODY_TEXT_STYLE.get().unwrap().clone(),
+            ));
+        });
+    }
+
+    let now = Utc::now();
+    for event in events.events.iter() {
+        let live = event.is_live(now);
+        let reminded = config
+            .event_reminders
+            .iter()
+            .any(|reminder| reminder.id == event.id);
+
+        let button = if live {
+            DuiButton::new_enabled_and_close_happy(
+                tr!("button-jump-in"),
+                jump_in(event.parcel(), event.realm_url()),
+            )
+        } else {
+            let reminder = event.reminder();
+            DuiButton::new_enabled(
+                tr!(if reminded {
+                    "button-dont-notify"
+                } else {
+                    "button-notify-me"
+                }),
+                move |mut config: ResMut<AppConfig>| {
+                    let reminders = &mut config.event_reminders;
+                    match reminders.iter().position(|r| r.id == reminder.id) {
+                        Some(ix) => {
+                            reminders.remove(ix);
+                        }
+                        None => reminders.push(reminder.clone()),
+                    }
+                },
+            )
+        };
+
+        let color = if live {
+            Color::srgb(0.85, 0.8, 1.0)
+        } else {
+            Color::srgb(0.7, 0.63, 0.75)
+        };
+
+        commands
+            .entity(entity)
+            .spawn_template(
+                &dui,
+                "event-item",
+                DuiProps::new()
+                    .with_prop("color", color)
+                    .with_prop("name", event.name.clone())
+                    .with_prop("when", event.when(now))
+                    .with_prop("location", event.location())
+                    .with_prop("attendees", format!("{} attending", event.total_attendees))
+                    .with_prop("buttons", vec![button]),
+            )
+            .unwrap();
+    }
+}
@@ -0,0 +1,261 @@
+// applies the configured language, refreshing `Localized` text when it changes. bundled packs are
+// loaded from `assets/i18n`, user packs from the config folder, and community packs can be
+// downloaded from the realm's content server (by hash) or a url with `/language`.
+
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, LoadState},
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+    utils::ConditionalSendFuture,
+};
+use bevy_console::ConsoleCommand;
+use common::{
+    i18n::{
+        current_language, generation, language_name, parse_ftl, set_language, translation_dir,
+        Localized, BUNDLED_LANGUAGES, DEFAULT_LANGUAGE,
+    },
+    structs::AppConfig,
+    tr,
+    util::TaskExt,
+};
+use console::DoAddConsoleCommand;
+use ipfs::CurrentRealm;
+use isahc::AsyncReadResponseExt;
+use scene_runner::Toaster;
+
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TranslationPack>()
+            .init_asset_loader::<FtlLoader>()
+            .add_event::<DownloadPackEvent>()
+            .add_systems(
+                Update,
+                (download_packs, apply_language, update_localized_text).chain(),
+            );
+        app.add_console_command::<LanguageCommand, _>(language_command);
+    }
+}
+
+#[derive(Asset, TypePath)]
+pub struct TranslationPack(HashMap<String, String>);
+
+#[derive(Default)]
+pub struct FtlLoader;
+
+impl AssetLoader for FtlLoader {
+    type Asset = TranslationPack;
+    type Settings = ();
+    type Error = String;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _: &'a Self::Settings,
+        _: &'a mut LoadContext,
+    ) -> impl ConditionalSendFuture<Output = Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut source = String::default();
+            reader
+                .read_to_string(&mut source)
+                .await
+                .map_err(|e| format!("read failed: {e}"))?;
+            parse_ftl(&source).map(TranslationPack)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ftl"]
+    }
+}
+
+fn read_user_pack(language: &str) -> Option<Result<HashMap<String, String>, String>> {
+    let path = translation_dir().join(format!("{language}.ftl"));
+    let source = std::fs::read_to_string(path).ok()?;
+    Some(parse_ftl(&source))
+}
+
+fn apply_language(
+    config: Res<AppConfig>,
+    asset_server: Res<AssetServer>,
+    packs: Res<Assets<TranslationPack>>,
+    mut pending: Local<Option<(String, Handle<TranslationPack>)>>,
+    mut toaster: Toaster,
+) {
+    let requested = pending.as_ref().map(|(language, _)| language.clone());
+    if config.is_changed()
+        && requested.as_ref() != Some(&config.language)
+        && (requested.is_some() || config.language != current_language())
+    {
+        *pending = None;
+        let language = config.language.clone();
+        // user packs take precedence, so bundled translations can be overridden locally
+        match read_user_pack(&language) {
+            Some(Ok(strings)) => set_language(&language, strings),
+            Some(Err(e)) => warn!("failed to read translations for `{language}`: {e}"),
+            None if language == DEFAULT_LANGUAGE => set_language(&language, Default::default()),
+            None if BUNDLED_LANGUAGES.iter().any(|(code, _)| *code == language) => {
+                let handle = asset_server.load(format!("i18n/{language}.ftl"));
+                *pending = Some((language, handle));
+            }
+            None => warn!("no translations available for `{language}`"),
+        }
+    }
+
+    let Some((language, handle)) = pending.as_ref() else {
+        return;
+    };
+    if let Some(pack) = packs.get(handle) {
+        set_language(language, pack.0.clone());
+        *pending = None;
+    } else if let LoadState::Failed(e) = asset_server.load_state(handle.id()) {
+        warn!("failed to load translations for `{language}`: {e}");
+        toaster.add_toast(
+            "language",
+            tr!("toast-language-failed", language = language_name(language)),
+        );
+        *pending = None;
+    }
+}
+
+fn update_localized_text(
+    mut text: Query<(Ref<Localized>, &mut Text)>,
+    mut last_generation: Local<usize>,
+) {
+    let generation = generation();
+    let refresh_all = *last_generation != generation;
+    *last_generation = generation;
+
+    for (localized, mut text) in text.iter_mut() {
+        if refresh_all || localized.is_added() || localized.is_changed() {
+            if let Some(section) = text.sections.first_mut() {
+                section.value = tr!(&localized.0);
+            }
+        }
+    }
+}
+
+// community pack downloads, written to the translation folder and then selected
+#[derive(Default)]
+struct PackDownloads(Vec<(String, Task<Result<(), anyhow::Error>>)>);
+
+fn download_packs(
+    mut downloads: Local<PackDownloads>,
+    mut requests: EventReader<DownloadPackEvent>,
+    realm: Res<CurrentRealm>,
+    mut config: ResMut<AppConfig>,
+    mut toaster: Toaster,
+) {
+    for DownloadPackEvent { language, source } in requests.read() {
+        let url = if source.starts_with("http://") || source.starts_with("https://") {
+            source.clone()
+        } else if realm.public_url.is_empty() {
+            toaster.add_toast("language", tr!("toast-language-no-content-server"));
+            continue;
+        } else {
+            format!("{}/contents/{}", realm.public_url, source)
+        };
+
+        let language = language.clone();
+        let path = translation_dir().join(format!("{language}.ftl"));
+        let task = IoTaskPool::get().spawn(async move {
+            let mut response = isahc::get_async(url).await?;
+            if !response.status().is_success() {
+                anyhow::bail!("status {}", response.status());
+            }
+            let source = response.text().await?;
+            parse_ftl(&source).map_err(|e| anyhow::anyhow!(e))?;
+            std::fs::create_dir_all(translation_dir())?;
+            std::fs::write(path, source)?;
+            Ok(())
+        });
+        downloads.0.push((language, task));
+    }
+
+    downloads.0.retain_mut(|(language, task)| {
+        let Some(result) = task.complete() else {
+            return true;
+        };
+        match result {
+            Ok(()) => {
+                // apply directly, in case the language was already selected
+                if let Some(Ok(strings)) = read_user_pack(language) {
+                    set_language(language, strings);
+                }
+                config.language.clone_from(language);
+                toaster.add_toast(
+                    "language",
+                    tr!(
+                        "toast-language-installed",
+                        language = language_name(language)
+                    ),
+                );
+            }
+            Err(e) => {
+                warn!("failed to download translations for `{language}`: {e}");
+                toaster.add_toast(
+                    "language",
+                    tr!("toast-language-failed", language = language_name(language)),
+                );
+            }
+        }
+        false
+    });
+}
+
+#[derive(Event)]
+struct DownloadPackEvent {
+    language: String,
+    source: String,
+}
+
+/// select the interface language, e.g. `/language es`. a content hash or url can be given to
+/// download a community translation pack first, e.g. `/language fr bafkrei...`
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/language")]
+struct LanguageCommand {
+    language: Option<String>,
+    source: Option<String>,
+}
+
+fn language_command(
+    mut input: ConsoleCommand<LanguageCommand>,
+    mut config: ResMut<AppConfig>,
+    mut downloads: EventWriter<DownloadPackEvent>,
+) {
+    if let Some(Ok(command)) = input.take() {
+        let Some(language) = command.language else {
+            input.reply_ok(tr!(
+                "console-language-current",
+                language = language_name(&config.language)
+            ));
+            return;
+        };
+
+        if let Some(source) = command.source {
+            downloads.send(DownloadPackEvent {
+                language: language.clone(),
+                source,
+            });
+            input.reply_ok(tr!("console-language-downloading", language = language));
+            return;
+        }
+
+        let available = language == DEFAULT_LANGUAGE
+            || BUNDLED_LANGUAGES.iter().any(|(code, _)| *code == language)
+            || translation_dir().join(format!("{language}.ftl")).exists();
+        if !available {
+            input.reply_failed(tr!("console-language-unknown", language = language));
+            return;
+        }
+
+        input.reply_ok(tr!(
+            "console-language-set",
+            language = language_name(&language)
+        ));
+        config.language = language;
+    }
+}
@@ -1,9 +1,12 @@
-use std::str::FromStr;
-
 use analytics::segment_system::SegmentConfig;
 use bevy::{
     app::AppExit,
     prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
     tasks::{IoTaskPool, Task},
     window::PrimaryWindow,
 };
@@ -11,7 +14,11 @@ use bevy_dui::{DuiCommandsExt, DuiEntityCommandsExt, DuiProps, DuiRegistry};
 use common::{
     profile::SerializedProfile,
     rpc::RpcResultSender,
-    structs::{ActiveDialog, AppConfig, ChainLink, DialogPermit, PreviousLogin, SystemAudio},
+    structs::{
+        ActiveDialog, AppConfig, ChainLink, DialogPermit, LedgerAccount, PreviousLogin,
+        SystemAudio, WalletConnectSession,
+    },
+    tr,
     util::{config_file, FireEventEx, TaskExt},
 };
 use comms::profile::{get_remote_profile, CurrentUserProfile, UserProfile};
@@ -26,7 +33,13 @@ use ui_core::{
     ui_actions::{close_ui_happy, Click, EventCloneExt, On},
 };
 use wallet::{
-    browser_auth::{finish_remote_ephemeral_request, init_remote_ephemeral_request},
+    browser_auth::{
+        finish_remote_ephemeral_request, init_remote_ephemeral_request,
+        poll_remote_ephemeral_request,
+    },
+    ledger::{self, list_accounts, DerivationScheme, LedgerPrompt},
+    session_store::{self, login_expiration},
+    wallet_connect::{self, finish_pairing, init_pairing, pairing_qr_code},
     Wallet,
 };
 
@@ -39,8 +52,10 @@ impl Plugin for LoginPlugin {
         app.add_event::<LoginType>().add_systems(
             Update,
             (
-                (login, update_profile_for_realm).run_if(in_state(ui_core::State::Ready)),
+                (login, update_profile_for_realm, mirror_ledger_prompt)
+                    .run_if(in_state(ui_core::State::Ready)),
                 process_system_bridge,
+                forget_session.run_if(resource_changed::<AppConfig>),
             ),
         );
     }
@@ -50,17 +65,44 @@ impl Plugin for LoginPlugin {
 enum LoginType {
     ExistingRemote,
     NewRemote,
+    // sign in on another device by scanning the auth page url
+    Qr,
+    WalletConnect,
+    Ledger(DerivationScheme),
+    LedgerAccount(LedgerAccount),
     Guest,
     Cancel,
 }
 
+// how many accounts to offer from the ledger
+const LEDGER_ACCOUNTS: usize = 5;
+
+// what handles signing requests after login, when not the browser
+#[derive(Clone)]
+enum LoginSigner {
+    Browser,
+    WalletConnect(WalletConnectSession),
+    Ledger(LedgerAccount),
+}
+
 type RpcReceiver<T> = tokio::sync::oneshot::Receiver<T>;
+type QrReceiver = RpcReceiver<Result<(String, Option<i32>), String>>;
 
 #[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn login(
     mut commands: Commands,
     wallet: Res<Wallet>,
     mut req_code: Local<Option<RpcReceiver<Result<Option<i32>, String>>>>,
+    (mut req_uri, mut req_qr): (
+        Local<Option<RpcReceiver<Result<String, String>>>>,
+        Local<Option<QrReceiver>>,
+    ),
+    mut req_accounts: Local<
+        Option<(
+            DerivationScheme,
+            RpcReceiver<Result<Vec<LedgerAccount>, String>>,
+        )>,
+    >,
     mut req_done: Local<Option<RpcReceiver<Result<(), String>>>>,
     mut logins: EventReader<LoginType>,
     mut dialog: Local<Option<Entity>>,
@@ -70,6 +112,8 @@ fn login(
     mut motd_shown: Local<bool>,
     mut bridge: EventWriter<SystemApi>,
     native_active: Res<NativeUi>,
+    config: Res<AppConfig>,
+    mut images: ResMut<Assets<Image>>,
 ) {
     if !native_active.login {
         return;
@@ -87,7 +131,7 @@ fn login(
                     DuiProps::new()
                         .with_prop("download", url)
                         .with_prop("body", desc)
-                        .with_prop("buttons", vec![DuiButton::new_enabled("Ok", (|mut commands: Commands, dui: Res<DuiRegistry>, mut permit: Query<&mut DialogPermit>| {
+                        .with_prop("buttons", vec![DuiButton::new_enabled(tr!("button-ok"), (|mut commands: Commands, dui: Res<DuiRegistry>, mut permit: Query<&mut DialogPermit>| {
                             let mut permit = permit.single_mut();
                             let permit = permit.take();
                             let components = commands
@@ -95,7 +139,7 @@ fn login(
                                     &dui,
                                     "motd",
                                     DuiProps::default()
-                                        .with_prop("buttons", vec![DuiButton::new_enabled("Ok", close_ui_happy)]),
+                                        .with_prop("buttons", vec![DuiButton::new_enabled(tr!("button-ok"), close_ui_happy)]),
                                 )
                                 .unwrap();
                             commands.entity(components.root).insert(permit);
@@ -110,7 +154,7 @@ fn login(
                     "motd",
                     DuiProps::default().with_prop(
                         "buttons",
-                        vec![DuiButton::new_enabled("Ok", close_ui_happy)],
+                        vec![DuiButton::new_enabled(tr!("button-ok"), close_ui_happy)],
                     ),
                 )
                 .unwrap();
@@ -127,6 +171,9 @@ fn login(
         }
         *dialog = None;
         *req_code = None;
+        *req_uri = None;
+        *req_qr = None;
+        *req_accounts = None;
         *req_done = None;
         return;
     }
@@ -148,6 +195,19 @@ fn login(
                 .with_prop("allow-reuse", previous_login.is_some())
                 .with_prop("reuse", LoginType::ExistingRemote.send_value_on::<Click>())
                 .with_prop("connect", LoginType::NewRemote.send_value_on::<Click>())
+                .with_prop("qr", LoginType::Qr.send_value_on::<Click>())
+                .with_prop(
+                    "allow-wallet-connect",
+                    config.wallet_connect_project_id.is_some(),
+                )
+                .with_prop(
+                    "wallet-connect",
+                    LoginType::WalletConnect.send_value_on::<Click>(),
+                )
+                .with_prop(
+                    "ledger",
+                    LoginType::Ledger(DerivationScheme::default()).send_value_on::<Click>(),
+                )
                 .with_prop("guest", LoginType::Guest.send_value_on::<Click>())
                 .with_prop(
                     "quit",
@@ -191,7 +251,7 @@ fn login(
                 *dialog = Some(components.root);
             }
             Ok(Err(e)) => {
-                toaster.add_toast("login profile", format!("Login failed: {}", e));
+                toaster.add_toast("login profile", tr!("toast-login-failed", error = e));
                 if let Some(commands) = dialog.and_then(|d| commands.get_entity(d)) {
                     commands.despawn_recursive();
                     *dialog = None;
@@ -206,6 +266,179 @@ fn login(
         }
     }
 
+    if let Some(mut t) = req_uri.take() {
+        match t.try_recv() {
+            Ok(Ok(uri)) => {
+                if let Some(commands) = dialog.and_then(|d| commands.get_entity(d)) {
+                    commands.despawn_recursive();
+                    *dialog = None;
+                }
+
+                if let Some(qr) = qr_image(&uri) {
+                    let components = commands
+                        .spawn_template(
+                            &dui,
+                            "wallet-connect-login",
+                            DuiProps::new().with_prop("qr", images.add(qr)).with_prop(
+                                "buttons",
+                                vec![DuiButton::new_enabled(
+                                    "Cancel",
+                                    |mut e: EventWriter<LoginType>| {
+                                        e.send(LoginType::Cancel);
+                                    },
+                                )],
+                            ),
+                        )
+                        .unwrap();
+                    *dialog = Some(components.root);
+                } else {
+                    toaster.add_toast(
+                        "login profile",
+                        tr!("toast-login-failed", error = "failed to display qr code"),
+                    );
+                    *req_done = None;
+                }
+            }
+            Ok(Err(e)) => {
+                toaster.add_toast("login profile", tr!("toast-login-failed", error = e));
+                if let Some(commands) = dialog.and_then(|d| commands.get_entity(d)) {
+                    commands.despawn_recursive();
+                    *dialog = None;
+                }
+            }
+            Err(TryRecvError::Empty) => {
+                *req_uri = Some(t);
+            }
+            Err(e) => {
+                warn!("unexpected {e}");
+            }
+        }
+    }
+
+    if let Some(mut t) = req_qr.take() {
+        match t.try_recv() {
+            Ok(Ok((url, code))) => {
+                if let Some(commands) = dialog.and_then(|d| commands.get_entity(d)) {
+                    commands.despawn_recursive();
+                    *dialog = None;
+                }
+
+                if let Some(qr) = qr_image(&url) {
+                    let components = commands
+                        .spawn_template(
+                            &dui,
+                            "qr-login",
+                            DuiProps::new()
+                                .with_prop("qr", images.add(qr))
+                                .with_prop("code", format!("{}", code.unwrap_or(-1)))
+                                .with_prop(
+                                    "buttons",
+                                    vec![DuiButton::new_enabled(
+                                        "Cancel",
+                                        |mut e: EventWriter<LoginType>| {
+                                            e.send(LoginType::Cancel);
+                                        },
+                                    )],
+                                ),
+                        )
+                        .unwrap();
+                    *dialog = Some(components.root);
+                } else {
+                    toaster.add_toast(
+                        "login profile",
+                        tr!("toast-login-failed", error = "failed to display qr code"),
+                    );
+                    *req_done = None;
+                }
+            }
+            Ok(Err(e)) => {
+                toaster.add_toast("login profile", tr!("toast-login-failed", error = e));
+                if let Some(commands) = dialog.and_then(|d| commands.get_entity(d)) {
+                    commands.despawn_recursive();
+                    *dialog = None;
+                }
+            }
+            Err(TryRecvError::Empty) => {
+                *req_qr = Some(t);
+            }
+            Err(e) => {
+                warn!("unexpected {e}");
+            }
+        }
+    }
+
+    if let Some((scheme, mut t)) = req_accounts.take() {
+        match t.try_recv() {
+            Ok(Ok(accounts)) => {
+                if let Some(commands) = dialog.and_then(|d| commands.get_entity(d)) {
+                    commands.despawn_recursive();
+                    *dialog = None;
+                }
+
+                let accounts = accounts
+                    .into_iter()
+                    .map(|account| {
+                        DuiButton::new_enabled(
+                            format!("{:#x} ({})", account.address, account.path),
+                            move |mut e: EventWriter<LoginType>| {
+                                e.send(LoginType::LedgerAccount(account.clone()));
+                            },
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let scheme_label = match scheme {
+                    DerivationScheme::LedgerLive => "Ledger Live accounts",
+                    DerivationScheme::Legacy => "Legacy (MEW / MyCrypto) accounts",
+                };
+                let other_label = match scheme {
+                    DerivationScheme::LedgerLive => "Show Legacy Accounts",
+                    DerivationScheme::Legacy => "Show Ledger Live Accounts",
+                };
+
+                let components = commands
+                    .spawn_template(
+                        &dui,
+                        "ledger-accounts",
+                        DuiProps::new()
+                            .with_prop("scheme", scheme_label.to_owned())
+                            .with_prop("accounts", accounts)
+                            .with_prop(
+                                "buttons",
+                                vec![
+                                    DuiButton::new_enabled(
+                                        other_label,
+                                        move |mut e: EventWriter<LoginType>| {
+                                            e.send(LoginType::Ledger(scheme.other()));
+                                        },
+                                    ),
+                                    DuiButton::new_enabled(
+                                        "Cancel",
+                                        |mut e: EventWriter<LoginType>| {
+                                            e.send(LoginType::Cancel);
+                                        },
+                                    ),
+                                ],
+                            ),
+                    )
+                    .unwrap();
+                *dialog = Some(components.root);
+            }
+            Ok(Err(e)) => {
+                toaster.add_toast("login profile", tr!("toast-login-failed", error = e));
+                if let Some(commands) = dialog.and_then(|d| commands.get_entity(d)) {
+                    commands.despawn_recursive();
+                    *dialog = None;
+                }
+            }
+            Err(TryRecvError::Empty) => {
+                *req_accounts = Some((scheme, t));
+            }
+            Err(e) => {
+                warn!("unexpected {e}");
+            }
+        }
+    }
+
     if let Some(mut t) = req_done.take() {
         match t.try_recv() {
             Ok(Ok(())) => {
@@ -213,7 +446,7 @@ fn login(
             }
             Ok(Err(e)) => {
                 error!("{e}");
-                toaster.add_toast("login profile", format!("Login failed: {}", e));
+                toaster.add_toast("login profile", tr!("toast-login-failed", error = e));
                 if let Some(commands) = dialog.and_then(|d| commands.get_entity(d)) {
                     commands.despawn_recursive();
                 }
@@ -273,17 +506,130 @@ fn login(
 
                 *dialog = Some(components.root);
             }
+            LoginType::Qr => {
+                info!("qr");
+
+                commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
+                let (sqr, rqr) =
+                    tokio::sync::oneshot::channel::<Result<(String, Option<i32>), String>>();
+                let (sx, rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
+                bridge.send(SystemApi::LoginQr(sqr.into(), sx.into()));
+                *req_qr = Some(rqr);
+                *req_done = Some(rx);
+
+                let components = commands
+                    .spawn_template(
+                        &dui,
+                        "qr-login-pending",
+                        DuiProps::new().with_prop(
+                            "buttons",
+                            vec![DuiButton::new_enabled(
+                                "Cancel",
+                                |mut e: EventWriter<LoginType>| {
+                                    e.send(LoginType::Cancel);
+                                },
+                            )],
+                        ),
+                    )
+                    .unwrap();
+
+                *dialog = Some(components.root);
+            }
+            LoginType::WalletConnect => {
+                info!("walletconnect");
+
+                commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
+                let (suri, ruri) = tokio::sync::oneshot::channel::<Result<String, String>>();
+                let (sx, rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
+                bridge.send(SystemApi::LoginWalletConnect(suri.into(), sx.into()));
+                *req_uri = Some(ruri);
+                *req_done = Some(rx);
+
+                let components = commands
+                    .spawn_template(
+                        &dui,
+                        "wallet-connect-pairing",
+                        DuiProps::new().with_prop(
+                            "buttons",
+                            vec![DuiButton::new_enabled(
+                                "Cancel",
+                                |mut e: EventWriter<LoginType>| {
+                                    e.send(LoginType::Cancel);
+                                },
+                            )],
+                        ),
+                    )
+                    .unwrap();
+
+                *dialog = Some(components.root);
+            }
+            LoginType::Ledger(scheme) => {
+                info!("ledger");
+
+                commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
+                let (sx, rx) =
+                    tokio::sync::oneshot::channel::<Result<Vec<LedgerAccount>, String>>();
+                bridge.send(SystemApi::LedgerAccounts(
+                    *scheme == DerivationScheme::Legacy,
+                    sx.into(),
+                ));
+                *req_accounts = Some((*scheme, rx));
+
+                let components = commands
+                    .spawn_template(
+                        &dui,
+                        "ledger-searching",
+                        DuiProps::new().with_prop(
+                            "buttons",
+                            vec![DuiButton::new_enabled(
+                                "Cancel",
+                                |mut e: EventWriter<LoginType>| {
+                                    e.send(LoginType::Cancel);
+                                },
+                            )],
+                        ),
+                    )
+                    .unwrap();
+
+                *dialog = Some(components.root);
+            }
+            LoginType::LedgerAccount(account) => {
+                info!("ledger account {}", account.path);
+
+                commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
+                let (sx, rx) = tokio::sync::oneshot::channel::<Result<(), String>>();
+                bridge.send(SystemApi::LoginLedger(account.clone(), sx.into()));
+                *req_done = Some(rx);
+
+                let components = commands
+                    .spawn_template(
+                        &dui,
+                        "ledger-login",
+                        DuiProps::new().with_prop(
+                            "buttons",
+                            vec![DuiButton::new_enabled(
+                                "Cancel",
+                                |mut e: EventWriter<LoginType>| {
+                                    e.send(LoginType::Cancel);
+                                },
+                            )],
+                        ),
+                    )
+                    .unwrap();
+
+                *dialog = Some(components.root);
+            }
             LoginType::Guest => {
                 info!("guest");
-                toaster.add_toast(
-                    "login profile",
-                    "Warning: Guest profile will not persist beyond the current session",
-                );
+                toaster.add_toast("login profile", tr!("toast-guest-warning"));
                 commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
                 bridge.send(SystemApi::LoginGuest);
             }
             LoginType::Cancel => {
                 *req_code = None;
+                *req_uri = None;
+                *req_qr = None;
+                *req_accounts = None;
                 *req_done = None;
                 *dialog = None;
                 commands.fire_event(SystemAudio("sounds/ui/toggle_disable.wav".to_owned()));
@@ -328,38 +674,96 @@ fn update_profile_for_realm(
     }
 }
 
-fn get_previous_login() -> Option<PreviousLogin> {
-    let previous_login = std::fs::read(config_file())
-        .ok()
-        .and_then(|f| serde_json::from_slice::<AppConfig>(&f).ok())
-        .unwrap_or_default()
-        .previous_login;
-
-    let mut expired = false;
-    if let Some(prev) = previous_login.as_ref() {
-        for link in &prev.auth {
-            if link.ty == "ECDSA_EPHEMERAL" {
-                for line in link.payload.lines() {
-                    if line.starts_with("Expiration:") {
-                        let exp = line.split_once(':').unwrap().1;
-                        if let Ok(exp) = chrono::DateTime::<chrono::Utc>::from_str(exp.trim()) {
-                            let now: chrono::DateTime<chrono::Utc> =
-                                std::time::SystemTime::now().into();
-                            if now > exp {
-                                warn!("previous login expired, removing");
-                                expired = true;
-                            }
-                        }
-                    }
-                }
-            }
-        }
+// the uri as a qr code image, with the quiet zone scanners expect around it
+fn qr_image(uri: &str) -> Option<Image> {
+    const BORDER: usize = 4;
+
+    let (width, modules) = pairing_qr_code(uri)
+        .map_err(|e| warn!("failed to make qr code: {e}"))
+        .ok()?;
+    let size = width + BORDER * 2;
+    let mut data = vec![255u8; size * size * 4];
+    for (ix, _) in modules.iter().enumerate().filter(|(_, dark)| **dark) {
+        let (x, y) = (ix % width + BORDER, ix / width + BORDER);
+        let offset = (y * size + x) * 4;
+        data[offset..offset + 3].fill(0);
     }
 
-    if expired {
-        None
-    } else {
-        previous_login
+    let mut image = Image::new(
+        Extent3d {
+            width: size as u32,
+            height: size as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    Some(image)
+}
+
+// drop the saved login when remembering is turned off
+fn forget_session(config: Res<AppConfig>, mut remembered: Local<Option<bool>>) {
+    if !config.remember_login && *remembered != Some(false) {
+        session_store::clear_session();
+    }
+    *remembered = Some(config.remember_login);
+}
+
+// show what the ledger is asking the user to confirm, while it waits
+fn mirror_ledger_prompt(
+    mut commands: Commands,
+    wallet: Res<Wallet>,
+    dui: Res<DuiRegistry>,
+    mut current: Local<Option<(LedgerPrompt, Entity)>>,
+) {
+    let prompt = wallet.ledger_prompt();
+    if current.as_ref().map(|(p, _)| p) == prompt.as_ref() {
+        return;
+    }
+
+    if let Some(commands) = current.take().and_then(|(_, e)| commands.get_entity(e)) {
+        commands.despawn_recursive();
+    }
+
+    if let Some(prompt) = prompt {
+        let body = prompt
+            .fields
+            .iter()
+            .map(|(label, value)| format!("{label}: {value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let components = commands
+            .spawn_template(
+                &dui,
+                "ledger-prompt",
+                DuiProps::new()
+                    .with_prop("title", prompt.title.clone())
+                    .with_prop("body", body),
+            )
+            .unwrap();
+        *current = Some((prompt, components.root));
+    }
+}
+
+fn get_previous_login() -> Option<PreviousLogin> {
+    // logins saved before sessions were encrypted are still in the config
+    let previous_login = session_store::load_session().or_else(|| {
+        std::fs::read(config_file())
+            .ok()
+            .and_then(|f| serde_json::from_slice::<AppConfig>(&f).ok())
+            .unwrap_or_default()
+            .previous_login
+    });
+
+    match previous_login {
+        Some(login) if login_expiration(&login).is_some_and(|exp| exp < chrono::Utc::now()) => {
+            warn!("previous login expired, removing");
+            None
+        }
+        login => login,
     }
 }
 
@@ -375,6 +779,7 @@ fn process_system_bridge(
                         Address,
                         LocalWallet,
                         Vec<ChainLink>,
+                        LoginSigner,
                         Option<UserProfile>,
                         RpcResultSender<Result<(), String>>,
                     ),
@@ -387,6 +792,7 @@ fn process_system_bridge(
     mut segment_config: ResMut<SegmentConfig>,
     mut current_profile: ResMut<CurrentUserProfile>,
     mut window: Query<&mut Window, With<PrimaryWindow>>,
+    mut config: ResMut<AppConfig>,
 ) {
     for ev in e.read().cloned() {
         match ev {
@@ -414,8 +820,16 @@ fn process_system_bridge(
                         root_address,
                         ephemeral_key,
                         auth,
+                        wallet_connect,
+                        ledger,
                     } = previous_login;
 
+                    let signer = match (wallet_connect, ledger) {
+                        (Some(session), _) => LoginSigner::WalletConnect(session),
+                        (None, Some(account)) => LoginSigner::Ledger(account),
+                        (None, None) => LoginSigner::Browser,
+                    };
+
                     let profile = get_remote_profile(root_address, ipfs).await.ok();
 
                     let local_wallet = LocalWallet::from_bytes(&ephemeral_key).unwrap();
@@ -424,6 +838,7 @@ fn process_system_bridge(
                         previous_login.root_address,
                         local_wallet,
                         auth,
+                        signer,
                         profile,
                         rpc_result_sender,
                     ))
@@ -456,7 +871,136 @@ fn process_system_bridge(
 
                     let profile = get_remote_profile(root_address, ipfs).await.ok();
 
-                    Ok((root_address, local_wallet, auth, profile, result_sender))
+                    Ok((
+                        root_address,
+                        local_wallet,
+                        auth,
+                        LoginSigner::Browser,
+                        profile,
+                        result_sender,
+                    ))
+                }));
+            }
+            SystemApi::LoginQr(qr_sender, result_sender) => {
+                let ipfs = ipfas.ipfs().clone();
+                *login_task = Some(IoTaskPool::get().spawn(async move {
+                    let req = match init_remote_ephemeral_request().await {
+                        Err(e) => {
+                            qr_sender.send(Err(e.to_string()));
+                            result_sender.send(Err(e.to_string()));
+                            return Err(());
+                        }
+                        Ok(res) => res,
+                    };
+
+                    qr_sender.send(Ok((req.url(), req.code)));
+
+                    let (root_address, local_wallet, auth, _) =
+                        match poll_remote_ephemeral_request(req).await {
+                            Ok(res) => res,
+                            Err(e) => {
+                                result_sender.send(Err(e.to_string()));
+                                return Err(());
+                            }
+                        };
+
+                    let profile = get_remote_profile(root_address, ipfs).await.ok();
+
+                    Ok((
+                        root_address,
+                        local_wallet,
+                        auth,
+                        LoginSigner::Browser,
+                        profile,
+                        result_sender,
+                    ))
+                }));
+            }
+            SystemApi::LoginWalletConnect(uri_sender, result_sender) => {
+                let Some(project_id) = config.wallet_connect_project_id.clone() else {
+                    let e = "no walletconnect project id configured".to_owned();
+                    uri_sender.send(Err(e.clone()));
+                    result_sender.send(Err(e));
+                    continue;
+                };
+                let ipfs = ipfas.ipfs().clone();
+                *login_task = Some(IoTaskPool::get().spawn(async move {
+                    let pairing = match init_pairing(project_id).await {
+                        Err(e) => {
+                            uri_sender.send(Err(e.to_string()));
+                            result_sender.send(Err(e.to_string()));
+                            return Err(());
+                        }
+                        Ok(res) => res,
+                    };
+
+                    uri_sender.send(Ok(pairing.uri.clone()));
+
+                    let signed = match finish_pairing(pairing).await {
+                        Ok(session) => wallet_connect::sign_ephemeral(&session)
+                            .await
+                            .map(|signed| (signed, session)),
+                        Err(e) => Err(e),
+                    };
+                    let ((root_address, local_wallet, auth), session) = match signed {
+                        Ok(res) => res,
+                        Err(e) => {
+                            result_sender.send(Err(e.to_string()));
+                            return Err(());
+                        }
+                    };
+
+                    let profile = get_remote_profile(root_address, ipfs).await.ok();
+
+                    Ok((
+                        root_address,
+                        local_wallet,
+                        auth,
+                        LoginSigner::WalletConnect(session),
+                        profile,
+                        result_sender,
+                    ))
+                }));
+            }
+            SystemApi::LedgerAccounts(legacy, sender) => {
+                let scheme = if legacy {
+                    DerivationScheme::Legacy
+                } else {
+                    DerivationScheme::LedgerLive
+                };
+                IoTaskPool::get()
+                    .spawn(async move {
+                        sender.send(
+                            list_accounts(scheme, LEDGER_ACCOUNTS)
+                                .await
+                                .map_err(|e| e.to_string()),
+                        );
+                    })
+                    .detach();
+            }
+            SystemApi::LoginLedger(account, result_sender) => {
+                let ipfs = ipfas.ipfs().clone();
+                let prompts = wallet.ledger_prompts();
+                *login_task = Some(IoTaskPool::get().spawn(async move {
+                    let (root_address, local_wallet, auth) =
+                        match ledger::sign_ephemeral(&account, &prompts).await {
+                            Ok(res) => res,
+                            Err(e) => {
+                                result_sender.send(Err(e.to_string()));
+                                return Err(());
+                            }
+                        };
+
+                    let profile = get_remote_profile(root_address, ipfs).await.ok();
+
+                    Ok((
+                        root_address,
+                        local_wallet,
+                        auth,
+                        LoginSigner::Ledger(account),
+                        profile,
+                        result_sender,
+                    ))
                 }));
             }
             SystemApi::LoginGuest => {
@@ -482,39 +1026,70 @@ fn process_system_bridge(
                 wallet.disconnect();
                 current_profile.profile = None;
             }
+            SystemApi::SignOutEverywhere => {
+                *login_task = None;
+                if let Some(session) = wallet.wallet_connect_session() {
+                    IoTaskPool::get()
+                        .spawn(async move {
+                            if let Err(e) = wallet_connect::end_session(&session).await {
+                                warn!("failed to end walletconnect session: {e}");
+                            }
+                        })
+                        .detach();
+                }
+                session_store::clear_session();
+                if config.previous_login.is_some() {
+                    config.previous_login = None;
+                }
+                wallet.disconnect();
+                current_profile.profile = None;
+            }
             _ => (),
         }
     }
 
     if let Some(mut task) = login_task.take() {
         match task.complete() {
-            Some(Ok((root_address, local_wallet, auth, profile, sender))) => {
+            Some(Ok((root_address, local_wallet, auth, signer, profile, sender))) => {
                 if let Ok(mut window) = window.get_single_mut() {
                     window.focused = true;
                 }
 
                 let ephemeral_key = local_wallet.signer().to_bytes().to_vec();
 
-                // store to app config
-                let mut config: AppConfig = std::fs::read(config_file())
-                    .ok()
-                    .and_then(|f| serde_json::from_slice(&f).ok())
-                    .unwrap_or_default();
-                config.previous_login = Some(PreviousLogin {
-                    root_address,
-                    ephemeral_key,
-                    auth: auth.clone(),
-                });
-                let config_file = config_file();
-                if let Some(folder) = config_file.parent() {
-                    std::fs::create_dir_all(folder).unwrap();
+                // the plaintext login older versions kept in the config is replaced by the
+                // encrypted session
+                if config.previous_login.is_some() {
+                    config.previous_login = None;
                 }
-                if let Err(e) = std::fs::write(config_file, serde_json::to_string(&config).unwrap())
-                {
-                    warn!("failed to write to config: {e}");
+                if config.remember_login {
+                    let login = PreviousLogin {
+                        root_address,
+                        ephemeral_key,
+                        auth: auth.clone(),
+                        wallet_connect: match &signer {
+                            LoginSigner::WalletConnect(session) => Some(session.clone()),
+                            _ => None,
+                        },
+                        ledger: match &signer {
+                            LoginSigner::Ledger(account) => Some(account.clone()),
+                            _ => None,
+                        },
+                    };
+                    if let Err(e) = session_store::save_session(&login) {
+                        warn!("failed to save login: {e}");
+                    }
                 }
 
-                wallet.finalize(root_address, local_wallet, auth);
+                match signer {
+                    LoginSigner::Browser => wallet.finalize(root_address, local_wallet, auth),
+                    LoginSigner::WalletConnect(session) => {
+                        wallet.finalize_wallet_connect(local_wallet, auth, session)
+                    }
+                    LoginSigner::Ledger(account) => {
+                        wallet.finalize_ledger(local_wallet, auth, account)
+                    }
+                }
                 segment_config.update_identity(format!("{:#x}", wallet.address().unwrap()), false);
                 if let Some(profile) = profile {
                     current_profile.profile = Some(profile);
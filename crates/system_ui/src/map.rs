@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use anyhow::anyhow;
 use bevy::{
+    core::FrameCount,
     prelude::*,
     tasks::{IoTaskPool, Task},
     utils::{hashbrown::hash_map::Entry, HashMap},
@@ -9,21 +10,30 @@ use bevy::{
 };
 use bevy_dui::{DuiEntityCommandsExt, DuiProps, DuiRegistry};
 use common::{
-    structs::{PrimaryUser, SettingsTab},
-    util::{ModifyComponentExt, TaskExt, TryPushChildrenEx},
+    structs::{AppConfig, IVec2Arg, PrimaryUser, SettingsTab},
+    tr,
+    util::{FireEventEx, ModifyComponentExt, TaskExt, TryPushChildrenEx},
 };
+use copypasta::{ClipboardContext, ClipboardProvider};
 use ipfs::ipfs_path::IpfsPath;
 use isahc::AsyncReadResponseExt;
-use scene_runner::{initialize_scene::PARCEL_SIZE, vec3_to_parcel};
+use scene_runner::{initialize_scene::PARCEL_SIZE, vec3_to_parcel, Toaster};
 use ui_core::{
     bound_node::{BoundedNode, BoundedNodeBundle},
+    context_menu::ContextMenu,
+    text_entry::TextEntrySubmit,
     text_size::FontSize,
-    ui_actions::{ClickNoDrag, DragData, Dragged, MouseWheelData, MouseWheeled, On, UiCaller},
+    tooltip::Tooltip,
+    ui_actions::{
+        Click, ClickNoDrag, DragData, Dragged, MouseWheelData, MouseWheeled, On, Submit, UiCaller,
+    },
 };
 
 use crate::{
     discover::{spawn_discover_popup, DiscoverPage, DiscoverPages},
-    profile::SettingsDialog,
+    map_markers::MapMarkersPlugin,
+    profile::{close_settings, OnCloseEvent, SettingsDialog},
+    scene_info::{fetch_parcel_tile, ParcelTile, SceneInfoDialog},
 };
 
 #[derive(Component)]
@@ -37,6 +47,7 @@ pub struct MapPlugin;
 
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
+        app.add_plugins(MapMarkersPlugin);
         app.add_systems(
             Update,
             (
@@ -45,6 +56,7 @@ impl Plugin for MapPlugin {
                 render_map,
                 update_map_data,
                 handle_map_task,
+                update_selection_label,
             )
                 .chain(),
         );
@@ -62,11 +74,84 @@ pub struct MapData {
     tile_entities: HashMap<(usize, i32, i32), Entity>,
 }
 
+impl MapData {
+    // pixel offset from the bottom left of the map node to a (fractional) scene parcel position
+    pub fn parcel_to_pixel(&self, parcel: Vec2) -> Vec2 {
+        self.bottom_left_offset
+            + (parcel - Vec2::Y - self.min_parcel.as_vec2()) * self.pixels_per_parcel
+    }
+
+    pub fn in_view(&self, parcel: Vec2) -> bool {
+        let map_parcel = parcel - Vec2::Y;
+        map_parcel.cmpge(self.min_parcel.as_vec2() - 1.0).all()
+            && map_parcel.cmple(self.max_parcel.as_vec2() + 1.0).all()
+    }
+}
+
 #[derive(Component, Default)]
 pub struct MapSettings {
     task: Option<(IVec2, Task<Result<DiscoverPages, anyhow::Error>>)>,
+    search_task: Option<Task<Result<DiscoverPages, anyhow::Error>>>,
+    selected: Option<IVec2>,
+    selected_page: Option<DiscoverPage>,
+    tile_task: Option<(IVec2, Task<Result<Option<ParcelTile>, anyhow::Error>>)>,
+    selected_tile: Option<ParcelTile>,
 }
 
+impl MapSettings {
+    pub fn selected(&self) -> Option<IVec2> {
+        self.selected
+    }
+
+    fn select(&mut self, parcel: IVec2) {
+        self.selected = Some(parcel);
+        self.selected_page = None;
+        self.fetch_tile(parcel);
+
+        let url = format!(
+            "https://places.decentraland.org/api/places?positions={},{}",
+            parcel.x, parcel.y
+        );
+        self.task = Some((parcel, places_task(url)));
+    }
+
+    fn fetch_tile(&mut self, parcel: IVec2) {
+        self.selected_tile = None;
+        self.tile_task = Some((parcel, fetch_parcel_tile(parcel)));
+    }
+
+    fn search(&mut self, query: &str) {
+        let url = format!(
+            "https://places.decentraland.org/api/places?limit=1&search={}",
+            urlencoding::encode(query)
+        );
+        self.search_task = Some(places_task(url));
+    }
+}
+
+pub(crate) fn places_task(url: String) -> Task<Result<DiscoverPages, anyhow::Error>> {
+    IoTaskPool::get().spawn(async move {
+        debug!("url: {url}");
+        let mut response = isahc::get_async(url).await?;
+        response
+            .json::<DiscoverPages>()
+            .await
+            .map_err(|e| anyhow!(e))
+    })
+}
+
+// map coordinates are offset by one parcel in y from scene parcels
+fn center_on(map: &mut MapTexture, parcel: IVec2) {
+    map.center = parcel.as_vec2() + Vec2::new(0.5, -0.5);
+}
+
+#[derive(Component)]
+struct SelectionLabel;
+
+// the full map in the settings dialog, as opposed to the minimap
+#[derive(Component)]
+pub struct WorldMap;
+
 fn set_map_content(
     mut commands: Commands,
     dialog: Query<(Entity, Ref<SettingsDialog>)>,
@@ -74,6 +159,7 @@ fn set_map_content(
     mut prev_tab: Local<Option<SettingsTab>>,
     dui: Res<DuiRegistry>,
     player: Query<&GlobalTransform, With<PrimaryUser>>,
+    config: Res<AppConfig>,
 ) {
     if dialog.is_empty() {
         *prev_tab = None;
@@ -91,15 +177,107 @@ fn set_map_content(
 
         commands.entity(ent).despawn_descendants();
 
-        if maybe_map_settings.is_none() {
-            commands.entity(ent).insert(MapSettings::default());
-        }
+        let selected = match maybe_map_settings {
+            Some(settings) => selection_text(&settings, &config),
+            None => {
+                let settings = MapSettings::default();
+                let text = selection_text(&settings, &config);
+                commands.entity(ent).insert(settings);
+                text
+            }
+        };
+
+        let props = DuiProps::new()
+            .with_prop("selected", selected)
+            .with_prop(
+                "search",
+                On::<Submit>::new(
+                    |caller: Res<UiCaller>,
+                     submit: Query<&TextEntrySubmit>,
+                     mut settings: Query<&mut MapSettings>,
+                     mut map: Query<&mut MapTexture, With<WorldMap>>| {
+                        let (Ok(TextEntrySubmit(query)), Ok(mut settings)) =
+                            (submit.get(caller.0), settings.get_single_mut())
+                        else {
+                            return;
+                        };
+
+                        if let Ok(IVec2Arg(parcel)) = query.parse::<IVec2Arg>() {
+                            settings.select(parcel);
+                            if let Ok(mut map) = map.get_single_mut() {
+                                center_on(&mut map, parcel);
+                            }
+                        } else if !query.is_empty() {
+                            settings.search(query);
+                        }
+                    },
+                ),
+            )
+            .with_prop(
+                "details",
+                On::<Click>::new(
+                    |mut commands: Commands,
+                     settings: Query<&MapSettings>,
+                     dui: Res<DuiRegistry>,
+                     asset_server: Res<AssetServer>| {
+                        let Ok(settings) = settings.get_single() else {
+                            return;
+                        };
+                        let Some(parcel) = settings.selected else {
+                            return;
+                        };
+                        let page = settings
+                            .selected_page
+                            .clone()
+                            .unwrap_or_else(|| DiscoverPage::dummy(parcel));
+                        spawn_discover_popup(&mut commands, &dui, &asset_server, &page);
+                    },
+                ),
+            )
+            .with_prop(
+                "pin",
+                On::<Click>::new(
+                    |settings: Query<&MapSettings>, mut config: ResMut<AppConfig>| {
+                        let Some(parcel) = settings.get_single().ok().and_then(|s| s.selected)
+                        else {
+                            return;
+                        };
+                        let pins = &mut config.map_pins;
+                        match pins.iter().position(|pin| *pin == parcel) {
+                            Some(ix) => {
+                                pins.remove(ix);
+                            }
+                            None => pins.push(parcel),
+                        }
+                    },
+                ),
+            )
+            .with_prop(
+                "teleport",
+                On::<Click>::new(
+                    (|settings: Query<&MapSettings>, mut dialog: Query<&mut SettingsDialog>| {
+                        let Some(parcel) = settings.get_single().ok().and_then(|s| s.selected)
+                        else {
+                            return;
+                        };
+                        // previewed once the map is closed
+                        if let Ok(mut dialog) = dialog.get_single_mut() {
+                            dialog.on_close = Some(OnCloseEvent::TeleportPreview(parcel));
+                        }
+                    })
+                    .pipe(close_settings),
+                ),
+            );
 
         let components = commands
             .entity(ent)
-            .apply_template(&dui, "map", DuiProps::default())
+            .apply_template(&dui, "map", props)
             .unwrap();
 
+        commands
+            .entity(components.named("selected"))
+            .insert(SelectionLabel);
+
         let center = player
             .get_single()
             .ok()
@@ -110,6 +288,7 @@ fn set_map_content(
         debug!("using parcel {}", center);
 
         commands.entity(components.named("map-node")).insert((
+            WorldMap,
             MapTexture {
                 center,
                 parcels_per_vmin: 20.0,
@@ -192,40 +371,34 @@ fn set_map_content(
                     let parcel = cursor_parcel.floor().as_ivec2() + IVec2::Y;
                     debug!("click parcel {}", parcel);
 
-                    let url = format!(
-                        "https://places.decentraland.org/api/places?positions={},{}",
-                        parcel.x, parcel.y
-                    );
-
-                    settings.task = Some((
-                        parcel,
-                        IoTaskPool::get().spawn(async move {
-                            debug!("url: {url}");
-                            let mut response = isahc::get_async(url).await?;
-                            response
-                                .json::<DiscoverPages>()
-                                .await
-                                .map_err(|e| anyhow!(e))
-                        }),
-                    ));
+                    settings.select(parcel);
                 },
             ),
         ));
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_map_data(
     mut commands: Commands,
-    map: Query<(&GlobalTransform, &MapTexture, &MapData, &Interaction)>,
+    map: Query<(
+        Entity,
+        &GlobalTransform,
+        &MapTexture,
+        &MapData,
+        &Interaction,
+    )>,
     window: Query<&Window, With<PrimaryWindow>>,
     player: Query<&GlobalTransform, With<PrimaryUser>>,
     children: Query<&Children>,
     mut text: Query<&mut Text>,
+    config: Res<AppConfig>,
+    mut hovered: Local<Option<(IVec2, bool)>>,
 ) {
     let Ok(window) = window.get_single() else {
         return;
     };
-    for (gt, map, data, interaction) in map.iter() {
+    for (ent, gt, map, data, interaction) in map.iter() {
         // update you are here
         if let Ok(gt) = player.get_single() {
             let icon_pos = data.bottom_left_offset
@@ -252,6 +425,7 @@ fn update_map_data(
         // update cursor
         if interaction == &Interaction::None {
             commands.entity(data.cursor).try_insert(Visibility::Hidden);
+            *hovered = None;
             continue;
         }
         let cursor_position = window.cursor_position().unwrap_or_default();
@@ -285,9 +459,82 @@ fn update_map_data(
         {
             text.sections[0].value = format!("({},{})", parcel.x, parcel.y + 1);
         }
+
+        // tooltip and context menu for the hovered parcel
+        let scene_parcel = parcel + IVec2::Y;
+        let pinned = config.map_pins.contains(&scene_parcel);
+        if *hovered != Some((scene_parcel, pinned)) {
+            *hovered = Some((scene_parcel, pinned));
+            commands.entity(ent).try_insert((
+                parcel_tooltip(scene_parcel, pinned),
+                parcel_menu(scene_parcel, pinned),
+            ));
+        }
     }
 }
 
+fn parcel_tooltip(parcel: IVec2, pinned: bool) -> Tooltip {
+    let mut text = tr!("map-parcel", x = parcel.x, y = parcel.y);
+    if pinned {
+        text.push('\n');
+        text.push_str(&tr!("map-pinned"));
+    }
+    Tooltip(text)
+}
+
+fn parcel_menu(parcel: IVec2, pinned: bool) -> ContextMenu {
+    ContextMenu::default()
+        .with_item(
+            tr!("menu-select"),
+            move |mut settings: Query<&mut MapSettings>| {
+                if let Ok(mut settings) = settings.get_single_mut() {
+                    settings.select(parcel);
+                }
+            },
+        )
+        .with_action(tr!("menu-teleport"), true, move || {
+            On::<Click>::new(
+                (move |mut dialog: Query<&mut SettingsDialog>| {
+                    // previewed once the map is closed
+                    if let Ok(mut dialog) = dialog.get_single_mut() {
+                        dialog.on_close = Some(OnCloseEvent::TeleportPreview(parcel));
+                    }
+                })
+                .pipe(close_settings),
+            )
+        })
+        .with_item(
+            tr!(if pinned { "menu-unpin" } else { "menu-pin" }),
+            move |mut config: ResMut<AppConfig>| {
+                let pins = &mut config.map_pins;
+                match pins.iter().position(|pin| *pin == parcel) {
+                    Some(ix) => {
+                        pins.remove(ix);
+                    }
+                    None => pins.push(parcel),
+                }
+            },
+        )
+        .with_item(tr!("menu-parcel-info"), move |mut commands: Commands| {
+            commands.fire_event(SceneInfoDialog(parcel));
+        })
+        .with_item(
+            tr!("menu-copy-coords"),
+            move |mut toaster: Toaster, frame: Res<FrameCount>| {
+                let Ok(mut ctx) = ClipboardContext::new() else {
+                    warn!("failed to copy");
+                    return;
+                };
+                if ctx
+                    .set_contents(format!("{},{}", parcel.x, parcel.y))
+                    .is_ok()
+                {
+                    toaster.add_toast(format!("mapcopy {}", frame.0), tr!("toast-coords-copied"));
+                }
+            },
+        )
+}
+
 const TILE_PARCELS: [i32; 6] = [160, 80, 40, 20, 10, 5];
 const PIXELS_PER_PARCEL: [f32; 6] = [
     512.0 / 160.0,
@@ -515,23 +762,97 @@ fn render_map(
 
 fn handle_map_task(
     mut q: Query<&mut MapSettings>,
-    mut commands: Commands,
-    dui: Res<DuiRegistry>,
-    asset_server: Res<AssetServer>,
+    mut map: Query<&mut MapTexture, With<WorldMap>>,
 ) {
     for mut settings in q.iter_mut() {
         if let Some((coords, mut task)) = settings.task.take() {
             match task.complete() {
                 Some(Ok(mut pages)) => {
-                    let page = pages
-                        .data
-                        .pop()
-                        .unwrap_or_else(|| DiscoverPage::dummy(coords));
-                    spawn_discover_popup(&mut commands, &dui, &asset_server, &page);
+                    if settings.selected == Some(coords) {
+                        settings.selected_page = pages.data.pop();
+                    }
                 }
                 Some(Err(e)) => warn!("places task error: {e}"),
                 None => settings.task = Some((coords, task)),
             }
         }
+
+        if let Some((coords, mut task)) = settings.tile_task.take() {
+            match task.complete() {
+                Some(Ok(tile)) => {
+                    if settings.selected == Some(coords) {
+                        settings.selected_tile = tile;
+                    }
+                }
+                Some(Err(e)) => warn!("parcel tile error: {e}"),
+                None => settings.tile_task = Some((coords, task)),
+            }
+        }
+
+        if let Some(mut task) = settings.search_task.take() {
+            match task.complete() {
+                Some(Ok(mut pages)) => {
+                    let Some(page) = pages.data.pop() else {
+                        settings.selected = None;
+                        settings.selected_page = None;
+                        continue;
+                    };
+                    let Ok(IVec2Arg(parcel)) = page.base_position.parse::<IVec2Arg>() else {
+                        warn!("invalid location");
+                        continue;
+                    };
+                    settings.selected = Some(parcel);
+                    settings.selected_page = Some(page);
+                    settings.fetch_tile(parcel);
+                    if let Ok(mut map) = map.get_single_mut() {
+                        center_on(&mut map, parcel);
+                    }
+                }
+                Some(Err(e)) => warn!("places search error: {e}"),
+                None => settings.search_task = Some(task),
+            }
+        }
+    }
+}
+
+fn selection_text(settings: &MapSettings, config: &AppConfig) -> String {
+    let Some(parcel) = settings.selected else {
+        return "Click a parcel to select it".to_owned();
+    };
+
+    let mut text = format!("({},{})", parcel.x, parcel.y);
+    if let Some(page) = settings.selected_page.as_ref() {
+        text = format!("{} {text}", page.title);
+    }
+    if let Some(tile) = settings.selected_tile.as_ref() {
+        if let Some(name) = tile.name.as_ref().filter(|name| !name.is_empty()) {
+            text = format!("{text} - {name}");
+        }
+        if let Some(owner) = tile.owner.as_ref().filter(|owner| owner.len() > 10) {
+            text = format!(
+                "{text} - owner {}..{}",
+                &owner[..6],
+                &owner[owner.len() - 4..]
+            );
+        }
+    }
+    if config.map_pins.contains(&parcel) {
+        text = format!("{text} [pinned]");
+    }
+    text
+}
+
+fn update_selection_label(
+    settings: Query<&MapSettings>,
+    config: Res<AppConfig>,
+    mut label: Query<&mut Text, With<SelectionLabel>>,
+) {
+    let (Ok(settings), Ok(mut label)) = (settings.get_single(), label.get_single_mut()) else {
+        return;
+    };
+
+    let text = selection_text(settings, &config);
+    if label.sections[0].value != text {
+        label.sections[0].value = text;
     }
 }
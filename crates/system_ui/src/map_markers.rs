@@ -0,0 +1,338 @@
+// markers shown on the world map and the minimap: points of interest from the places api, live
+// and upcoming events, the user's pins, pins placed by scenes, and nearby friends. markers that
+// overlap at the current zoom are merged into a cluster showing the count.
+
+use anyhow::anyhow;
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+    utils::HashMap,
+    window::PrimaryWindow,
+};
+use chrono::Utc;
+use common::{
+    rpc::RpcCall,
+    structs::{AppConfig, IVec2Arg},
+    util::{TaskExt, TryPushChildrenEx},
+};
+use comms::global_crdt::ForeignPlayer;
+use ethers_core::types::Address;
+use isahc::AsyncReadResponseExt;
+use scene_runner::{initialize_scene::PARCEL_SIZE, renderer_context::RendererSceneContext};
+use social::SocialClient;
+use ui_core::{text_size::FontSize, theme::UiTheme};
+
+use crate::{
+    discover::DiscoverPages,
+    live_events::LiveEvents,
+    map::{MapData, MapSettings, MapTexture, WorldMap},
+};
+
+// max pins a single scene may place
+const MAX_SCENE_PINS: usize = 10;
+// marker size in vmin relative to the map's icon size
+const MARKER_SCALE: f32 = 0.4;
+
+pub struct MapMarkersPlugin;
+
+impl Plugin for MapMarkersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MapMarkerSources>();
+        app.add_systems(
+            Update,
+            (update_marker_sources, update_scene_pins, update_map_markers).chain(),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct MapMarkerSources {
+    poi_task: Option<Task<Result<DiscoverPages, anyhow::Error>>>,
+    pois_requested: bool,
+    pub pois: Vec<(IVec2, String)>,
+    pub events: Vec<(IVec2, String)>,
+    pub scene_pins: HashMap<(Entity, String), (IVec2, String)>,
+}
+
+fn update_marker_sources(mut sources: ResMut<MapMarkerSources>, live_events: Res<LiveEvents>) {
+    let sources = &mut *sources;
+
+    if !sources.pois_requested {
+        sources.pois_requested = true;
+        sources.poi_task = Some(IoTaskPool::get().spawn(async move {
+            let mut response = isahc::get_async(
+                "https://places.decentraland.org/api/places?categories=poi&limit=100",
+            )
+            .await?;
+            response
+                .json::<DiscoverPages>()
+                .await
+                .map_err(|e| anyhow!(e))
+        }));
+    }
+
+    if let Some(mut task) = sources.poi_task.take() {
+        match task.complete() {
+            Some(Ok(pages)) => {
+                sources.pois = pages
+                    .data
+                    .into_iter()
+                    .filter_map(|page| {
+                        let IVec2Arg(parcel) = page.base_position.parse::<IVec2Arg>().ok()?;
+                        Some((parcel, page.title))
+                    })
+                    .collect();
+            }
+            Some(Err(e)) => warn!("poi fetch failed: {e}"),
+            None => sources.poi_task = Some(task),
+        }
+    }
+
+    if live_events.is_changed() {
+        sources.events = live_events
+            .map_markers(Utc::now())
+            .map(|event| (event.parcel(), event.name.clone()))
+            .collect();
+    }
+}
+
+fn update_scene_pins(
+    mut sources: ResMut<MapMarkerSources>,
+    mut events: EventReader<RpcCall>,
+    scenes: Query<(), With<RendererSceneContext>>,
+) {
+    for (scene, id, marker) in events.read().filter_map(|ev| match ev {
+        RpcCall::SetMapMarker { scene, id, marker } => Some((*scene, id, marker)),
+        _ => None,
+    }) {
+        let key = (scene, id.clone());
+        match marker {
+            Some(marker) => {
+                let count = sources
+                    .scene_pins
+                    .keys()
+                    .filter(|(pin_scene, _)| *pin_scene == scene)
+                    .count();
+                if count >= MAX_SCENE_PINS && !sources.scene_pins.contains_key(&key) {
+                    warn!("scene {scene:?} exceeded the map pin limit");
+                    continue;
+                }
+                sources.scene_pins.insert(key, marker.clone());
+            }
+            None => {
+                sources.scene_pins.remove(&key);
+            }
+        }
+    }
+
+    // pins go when their scene unloads
+    if sources
+        .scene_pins
+        .keys()
+        .any(|(scene, _)| scenes.get(*scene).is_err())
+    {
+        sources
+            .scene_pins
+            .retain(|(scene, _), _| scenes.get(*scene).is_ok());
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+enum MarkerKind {
+    Selected,
+    Pin(IVec2),
+    ScenePin(Entity, String),
+    Poi(IVec2),
+    Event(IVec2),
+    Friend(Address),
+    Cluster(IVec2, usize),
+}
+
+impl MarkerKind {
+    // the minimap.dui legend uses the same theme colors
+    fn color(&self, theme: &UiTheme) -> Color {
+        match self {
+            MarkerKind::Selected => Color::WHITE,
+            MarkerKind::Pin(_) => theme.marker_pin,
+            MarkerKind::ScenePin(..) => theme.marker_scene_pin,
+            MarkerKind::Poi(_) => theme.marker_poi,
+            MarkerKind::Event(_) => theme.marker_event,
+            MarkerKind::Friend(_) => theme.marker_friend,
+            MarkerKind::Cluster(..) => theme.marker_cluster,
+        }
+    }
+
+    fn z_index(&self) -> i32 {
+        match self {
+            MarkerKind::Poi(_) => 7,
+            MarkerKind::Event(_) => 8,
+            MarkerKind::Cluster(..) => 8,
+            MarkerKind::ScenePin(..) => 9,
+            MarkerKind::Pin(_) => 9,
+            MarkerKind::Selected => 10,
+            MarkerKind::Friend(_) => 11,
+        }
+    }
+
+    fn clusters(&self) -> bool {
+        matches!(
+            self,
+            MarkerKind::Pin(_)
+                | MarkerKind::ScenePin(..)
+                | MarkerKind::Poi(_)
+                | MarkerKind::Event(_)
+        )
+    }
+}
+
+#[derive(Component)]
+struct MapMarker(MarkerKind);
+
+#[allow(clippy::too_many_arguments)]
+fn update_map_markers(
+    mut commands: Commands,
+    maps: Query<(Entity, &MapTexture, &MapData, Has<WorldMap>)>,
+    settings: Query<&MapSettings>,
+    sources: Res<MapMarkerSources>,
+    config: Res<AppConfig>,
+    theme: Res<UiTheme>,
+    social: Res<SocialClient>,
+    players: Query<(&ForeignPlayer, &GlobalTransform)>,
+    mut markers: Query<(
+        Entity,
+        &Parent,
+        &MapMarker,
+        &mut Style,
+        &mut BackgroundColor,
+    )>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    let vmin = window.width().min(window.height());
+
+    // marker centers, in scene parcel coordinates
+    let mut all = Vec::default();
+    for (poi, _) in sources.pois.iter() {
+        all.push((MarkerKind::Poi(*poi), poi.as_vec2() + 0.5));
+    }
+    for (event, _) in sources.events.iter() {
+        all.push((MarkerKind::Event(*event), event.as_vec2() + 0.5));
+    }
+    for pin in config.map_pins.iter() {
+        all.push((MarkerKind::Pin(*pin), pin.as_vec2() + 0.5));
+    }
+    for ((scene, id), (pin, _)) in sources.scene_pins.iter() {
+        all.push((
+            MarkerKind::ScenePin(*scene, id.clone()),
+            pin.as_vec2() + 0.5,
+        ));
+    }
+    if let Some(client) = social.0.as_ref() {
+        // only friends we share comms with have a known position
+        for (player, gt) in players.iter() {
+            if client.friends.contains(&player.address) {
+                all.push((
+                    MarkerKind::Friend(player.address),
+                    gt.translation().xz() * Vec2::new(1.0, -1.0) / PARCEL_SIZE,
+                ));
+            }
+        }
+    }
+    let selected = settings
+        .get_single()
+        .ok()
+        .and_then(MapSettings::selected)
+        .map(|selected| (MarkerKind::Selected, selected.as_vec2() + 0.5));
+
+    let mut wanted = HashMap::<(Entity, MarkerKind), Style>::default();
+    for (map_entity, map, data, is_world_map) in maps.iter() {
+        let size = vmin * map.icon_min_size_vmin * MARKER_SCALE;
+        let style = |pixel: Vec2| Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(pixel.x - size * 0.5),
+            bottom: Val::Px(pixel.y - size * 0.5),
+            width: Val::Px(size),
+            height: Val::Px(size),
+            border: UiRect::all(Val::Px((size * 0.1).max(1.0))),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        };
+
+        // group overlapping markers by screen cell
+        let mut cells = HashMap::<IVec2, Vec<(MarkerKind, Vec2)>>::default();
+        let selected = selected.iter().filter(|_| is_world_map);
+        for (kind, center) in all.iter().chain(selected) {
+            if !data.in_view(*center) {
+                continue;
+            }
+            let pixel = data.parcel_to_pixel(*center);
+            if kind.clusters() {
+                let cell = (pixel / (size * 1.5)).floor().as_ivec2();
+                cells.entry(cell).or_default().push((kind.clone(), pixel));
+            } else {
+                wanted.insert((map_entity, kind.clone()), style(pixel));
+            }
+        }
+
+        for (cell, mut members) in cells {
+            if members.len() == 1 {
+                let (kind, pixel) = members.pop().unwrap();
+                wanted.insert((map_entity, kind), style(pixel));
+            } else {
+                let pixel =
+                    members.iter().map(|(_, pixel)| *pixel).sum::<Vec2>() / members.len() as f32;
+                wanted.insert(
+                    (map_entity, MarkerKind::Cluster(cell, members.len())),
+                    style(pixel),
+                );
+            }
+        }
+    }
+
+    for (entity, parent, marker, mut style, mut color) in markers.iter_mut() {
+        match wanted.remove(&(parent.get(), marker.0.clone())) {
+            Some(new_style) => {
+                if *style != new_style {
+                    *style = new_style;
+                }
+                if theme.is_changed() {
+                    color.0 = marker.0.color(&theme);
+                }
+            }
+            None => commands.entity(entity).despawn_recursive(),
+        }
+    }
+
+    for ((map_entity, kind), style) in wanted {
+        let mut marker = commands.spawn((
+            NodeBundle {
+                style,
+                background_color: kind.color(&theme).into(),
+                border_color: Color::BLACK.into(),
+                border_radius: BorderRadius::MAX,
+                z_index: ZIndex::Local(kind.z_index()),
+                ..Default::default()
+            },
+            MapMarker(kind.clone()),
+        ));
+        if let MarkerKind::Cluster(_, count) = kind {
+            marker.with_children(|c| {
+                c.spawn((
+                    TextBundle::from_section(
+                        format!("{count}"),
+                        TextStyle {
+                            color: Color::BLACK,
+                            ..Default::default()
+                        },
+                    ),
+                    FontSize(0.015),
+                ));
+            });
+        }
+        let marker = marker.id();
+        commands.entity(map_entity).try_push_children(&[marker]);
+    }
+}
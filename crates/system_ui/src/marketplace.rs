@@ -0,0 +1,396 @@
+use std::path::PathBuf;
+
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+};
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use collectibles::{wearables::Wearable, CollectibleManager, Emote, PurchasedCollectible};
+use common::{rpc::RPCSendableMessage, structs::ShowPurchaseEvent, tr, util::TaskExt};
+use ipfs::{ipfs_path::IpfsPath, IpfsAssetServer};
+use scene_runner::Toaster;
+use ui_core::{button::DuiButton, theme::UiTheme};
+use wallet::{
+    balances::RefreshBalances,
+    marketplace::{self, Listing},
+    tx_review::{self, RequestReview},
+    Wallet,
+};
+
+use crate::{
+    emotes::GetOwnedEmotes,
+    wearables::{GetOwnedWearables, Rarity},
+};
+
+pub struct MarketplacePlugin;
+
+impl Plugin for MarketplacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShowPurchaseEvent>()
+            .init_resource::<PurchaseFlow>()
+            .add_systems(Update, (show_purchase, update_purchase).chain());
+    }
+}
+
+enum PurchaseTask {
+    Listing(Task<Result<Listing, anyhow::Error>>),
+    Review(
+        Listing,
+        Task<Result<Vec<(RPCSendableMessage, RequestReview)>, anyhow::Error>>,
+    ),
+    Purchase(Listing, Task<Result<Option<String>, anyhow::Error>>),
+}
+
+// the purchase in progress, and the dialog showing it
+#[derive(Resource, Default)]
+struct PurchaseFlow {
+    dialog: Option<Entity>,
+    task: Option<PurchaseTask>,
+}
+
+impl PurchaseFlow {
+    fn replace_dialog(&mut self, commands: &mut Commands, dialog: Entity) {
+        self.close_dialog(commands);
+        self.dialog = Some(dialog);
+    }
+
+    fn close_dialog(&mut self, commands: &mut Commands) {
+        if let Some(commands) = self
+            .dialog
+            .take()
+            .and_then(|prev| commands.get_entity(prev))
+        {
+            commands.despawn_recursive();
+        }
+    }
+
+    fn is_purchasing(&self) -> bool {
+        matches!(self.task, Some(PurchaseTask::Purchase(..)))
+    }
+}
+
+fn spawn_progress(commands: &mut Commands, dui: &DuiRegistry, title: &str, body: &str) -> Entity {
+    commands
+        .spawn_template(
+            dui,
+            "marketplace-progress",
+            DuiProps::new()
+                .with_prop("title", title.to_owned())
+                .with_prop("body", body.to_owned())
+                .with_prop("buttons", vec![DuiButton::close_silent("Cancel")]),
+        )
+        .unwrap()
+        .root
+}
+
+fn show_purchase(
+    mut commands: Commands,
+    mut events: EventReader<ShowPurchaseEvent>,
+    mut flow: ResMut<PurchaseFlow>,
+    mut toaster: Toaster,
+    dui: Res<DuiRegistry>,
+) {
+    let Some(ShowPurchaseEvent(urn)) = events.read().last() else {
+        return;
+    };
+
+    // a sent transaction can't be recalled, so don't lose track of it
+    if flow.is_purchasing() {
+        toaster.add_toast(
+            "marketplace",
+            tr!(
+                "toast-purchase-failed",
+                error = "another purchase is in progress"
+            ),
+        );
+        return;
+    }
+
+    let urn = urn.clone();
+    flow.task =
+        Some(PurchaseTask::Listing(IoTaskPool::get().spawn(async move {
+            marketplace::fetch_listing(&urn).await
+        })));
+    let dialog = spawn_progress(&mut commands, &dui, "Marketplace", "Fetching listing...");
+    flow.replace_dialog(&mut commands, dialog);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_purchase(
+    mut commands: Commands,
+    mut flow: ResMut<PurchaseFlow>,
+    dui: Res<DuiRegistry>,
+    ipfas: IpfsAssetServer,
+    theme: Res<UiTheme>,
+    wallet: Res<Wallet>,
+    mut toaster: Toaster,
+    mut wearables: CollectibleManager<Wearable>,
+    mut emotes: CollectibleManager<Emote>,
+    mut refresh: (EventWriter<GetOwnedWearables>, EventWriter<GetOwnedEmotes>),
+    mut refresh_balances: EventWriter<RefreshBalances>,
+) {
+    // closing the dialog cancels anything short of a sent transaction
+    let dialog_open = flow
+        .dialog
+        .is_some_and(|dialog| commands.get_entity(dialog).is_some());
+    if !dialog_open && !flow.is_purchasing() {
+        flow.task = None;
+        return;
+    }
+
+    let flow = &mut *flow;
+    let Some(task) = flow.task.as_mut() else {
+        return;
+    };
+
+    match task {
+        PurchaseTask::Listing(t) => match t.complete() {
+            Some(Ok(listing)) => {
+                flow.task = None;
+                let dialog = spawn_listing(&mut commands, &dui, &ipfas, &theme, &wallet, listing);
+                flow.replace_dialog(&mut commands, dialog);
+            }
+            Some(Err(e)) => {
+                flow.task = None;
+                flow.close_dialog(&mut commands);
+                toaster.add_toast("marketplace", tr!("toast-purchase-failed", error = e));
+            }
+            None => (),
+        },
+        PurchaseTask::Review(listing, t) => match t.complete() {
+            Some(Ok(txs)) => {
+                let listing = listing.clone();
+                flow.task = None;
+                let dialog = spawn_confirm(&mut commands, &dui, listing, txs);
+                flow.replace_dialog(&mut commands, dialog);
+            }
+            Some(Err(e)) => {
+                flow.task = None;
+                flow.close_dialog(&mut commands);
+                toaster.add_toast("marketplace", tr!("toast-purchase-failed", error = e));
+            }
+            None => (),
+        },
+        PurchaseTask::Purchase(listing, t) => match t.complete() {
+            Some(Ok(token_id)) => {
+                let listing = listing.clone();
+                flow.task = None;
+                flow.close_dialog(&mut commands);
+                toaster.add_toast(
+                    "marketplace",
+                    tr!("toast-purchase-complete", name = listing.name.clone()),
+                );
+                refresh_balances.send(RefreshBalances::Now);
+
+                let Some(token_id) = token_id else {
+                    warn!("purchase of {} minted no token to us", listing.urn);
+                    return;
+                };
+                let purchase = PurchasedCollectible {
+                    urn: listing.urn.clone(),
+                    token_id,
+                    category: listing.item_category(),
+                    name: listing.name,
+                    rarity: listing.rarity,
+                    purchased_at: chrono::Utc::now().timestamp().to_string(),
+                };
+                if listing.category == "emote" {
+                    emotes.add_purchased(purchase);
+                    refresh.1.send_default();
+                } else {
+                    wearables.add_purchased(purchase);
+                    refresh.0.send_default();
+                }
+            }
+            Some(Err(e)) => {
+                flow.task = None;
+                flow.close_dialog(&mut commands);
+                toaster.add_toast("marketplace", tr!("toast-purchase-failed", error = e));
+            }
+            None => (),
+        },
+    }
+}
+
+fn spawn_listing(
+    commands: &mut Commands,
+    dui: &DuiRegistry,
+    ipfas: &IpfsAssetServer,
+    theme: &UiTheme,
+    wallet: &Wallet,
+    listing: Listing,
+) -> Entity {
+    let ipfs_path = IpfsPath::new_from_url(&listing.thumbnail, "image");
+    let image = ipfas
+        .asset_server()
+        .load::<Image>(PathBuf::from(&ipfs_path));
+    let rarity = Rarity::from(listing.rarity.as_str());
+
+    let availability = if !listing.is_on_sale {
+        "Not for sale".to_owned()
+    } else if listing.available == 0 {
+        "Sold out".to_owned()
+    } else {
+        format!("{} available", listing.available)
+    };
+
+    let signed_in = !wallet.is_guest() && wallet.address().is_some();
+    let buy_label = if signed_in {
+        format!("Buy for {}", listing.price_text())
+    } else {
+        "Sign in to buy".to_owned()
+    };
+
+    let url = listing.marketplace_url();
+    let buy_listing = listing.clone();
+    commands
+        .spawn_template(
+            dui,
+            "marketplace-item",
+            DuiProps::new()
+                .with_prop("title", listing.name.clone())
+                .with_prop("img", image)
+                .with_prop("rarity-color", rarity.color(theme))
+                .with_prop(
+                    "info",
+                    tr!(
+                        "wearable-info",
+                        rarity = listing.rarity.clone(),
+                        category = listing.item_category()
+                    ),
+                )
+                .with_prop("price", listing.price_text())
+                .with_prop("availability", availability)
+                .with_prop("description", listing.description())
+                .with_prop(
+                    "buttons",
+                    vec![
+                        DuiButton::new(
+                            buy_label,
+                            signed_in && listing.can_buy(),
+                            move |mut commands: Commands,
+                                  mut flow: ResMut<PurchaseFlow>,
+                                  dui: Res<DuiRegistry>,
+                                  wallet: Res<Wallet>| {
+                                let Some(buyer) = wallet.address() else {
+                                    return;
+                                };
+                                let listing = buy_listing.clone();
+                                let task_listing = listing.clone();
+                                flow.task = Some(PurchaseTask::Review(
+                                    listing,
+                                    IoTaskPool::get().spawn(async move {
+                                        let txs = marketplace::purchase_transactions(
+                                            &task_listing,
+                                            buyer,
+                                        )
+                                        .await?;
+                                        let mut reviewed = Vec::default();
+                                        for tx in txs {
+                                            let review =
+                                                tx_review::review(tx.clone(), Some(buyer)).await;
+                                            reviewed.push((tx, review));
+                                        }
+                                        Ok(reviewed)
+                                    }),
+                                ));
+                                let dialog = spawn_progress(
+                                    &mut commands,
+                                    &dui,
+                                    "Marketplace",
+                                    "Preparing transaction...",
+                                );
+                                flow.replace_dialog(&mut commands, dialog);
+                            },
+                        ),
+                        DuiButton::new_enabled("View on Marketplace", move || {
+                            let _ = opener::open(&url);
+                        }),
+                        DuiButton::close_silent("Cancel"),
+                    ],
+                ),
+        )
+        .unwrap()
+        .root
+}
+
+// show what will be signed before handing the transactions to the wallet
+fn spawn_confirm(
+    commands: &mut Commands,
+    dui: &DuiRegistry,
+    listing: Listing,
+    txs: Vec<(RPCSendableMessage, RequestReview)>,
+) -> Entity {
+    let steps = txs.len();
+    let body = txs
+        .iter()
+        .enumerate()
+        .map(|(ix, (_, review))| format!("Step {} of {steps}\n{}", ix + 1, review.summary))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let txs = txs.into_iter().map(|(tx, _)| tx).collect::<Vec<_>>();
+
+    commands
+        .spawn_template(
+            dui,
+            "text-dialog",
+            DuiProps::new()
+                .with_prop("title", format!("Confirm purchase of {}", listing.name))
+                .with_prop("body", body)
+                .with_prop(
+                    "buttons",
+                    vec![
+                        DuiButton::new_enabled(
+                            "Confirm",
+                            move |mut commands: Commands,
+                                  mut flow: ResMut<PurchaseFlow>,
+                                  dui: Res<DuiRegistry>,
+                                  wallet: Res<Wallet>| {
+                                let Some(buyer) = wallet.address() else {
+                                    return;
+                                };
+                                let txs = txs.clone();
+                                let wallet = wallet.clone();
+                                flow.task = Some(PurchaseTask::Purchase(
+                                    listing.clone(),
+                                    IoTaskPool::get().spawn(async move {
+                                        // each step has to land before the next is sent
+                                        let mut token_id = None;
+                                        for tx in txs {
+                                            let hash = wallet.send_async(tx).await?;
+                                            let hash = hash.as_str().unwrap_or_default();
+                                            token_id =
+                                                marketplace::wait_for_purchase(hash, buyer).await?;
+                                        }
+                                        Ok(token_id)
+                                    }),
+                                ));
+                                let dialog = commands
+                                    .spawn_template(
+                                        &dui,
+                                        "marketplace-progress",
+                                        DuiProps::new()
+                                            .with_prop("title", "Marketplace".to_owned())
+                                            .with_prop(
+                                                "body",
+                                                "Confirm in your wallet, then wait for the \
+                                                 transaction to complete..."
+                                                    .to_owned(),
+                                            )
+                                            .with_prop(
+                                                "buttons",
+                                                vec![DuiButton::close_silent("Hide")],
+                                            ),
+                                    )
+                                    .unwrap()
+                                    .root;
+                                flow.replace_dialog(&mut commands, dialog);
+                            },
+                        ),
+                        DuiButton::close_silent("Cancel"),
+                    ],
+                ),
+        )
+        .unwrap()
+        .root
+}
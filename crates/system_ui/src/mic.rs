@@ -1,7 +1,7 @@
 use av::microphone::MicState;
 use bevy::prelude::*;
 use common::{
-    structs::{SystemAudio, ToolTips, TooltipSource},
+    structs::{AppConfig, SystemAudio, ToolTips, TooltipSource},
     util::FireEventEx,
 };
 use comms::{Transport, TransportType};
@@ -61,16 +61,21 @@ fn setup(mut commands: Commands, images: Res<MicImages>) {
             }
         }),
         On::<HoverEnter>::new(
-            |mut tooltip: ResMut<ToolTips>, transport: Query<&Transport>, state: Res<MicState>| {
+            |mut tooltip: ResMut<ToolTips>,
+             transport: Query<&Transport>,
+             state: Res<MicState>,
+             config: Res<AppConfig>| {
                 let transport_available = transport
                     .iter()
                     .any(|t| t.transport_type == TransportType::Livekit);
+                let label = if config.input_accessibility.toggle_push_to_talk {
+                    "LCtrl : Toggle talk"
+                } else {
+                    "LCtrl : Push to talk"
+                };
                 tooltip.0.insert(
                     TooltipSource::Label("mic"),
-                    vec![(
-                        "LCtrl : Push to talk".to_owned(),
-                        transport_available && state.available,
-                    )],
+                    vec![(label.to_owned(), transport_available && state.available)],
                 );
             },
         ),
@@ -91,6 +96,7 @@ fn update_mic_ui(
     input: Res<ButtonInput<KeyCode>>,
     mic_images: Res<MicImages>,
     mut prev_active: Local<bool>,
+    config: Res<AppConfig>,
 ) {
     let mic_available = mic_state.available;
     let transport_available = transport
@@ -107,7 +113,13 @@ fn update_mic_ui(
         *button.single_mut() = mic_images.inactive.clone_weak().into();
     }
 
-    if input.pressed(KeyCode::ControlLeft) != *pressed {
+    if config.input_accessibility.toggle_push_to_talk {
+        if input.just_pressed(KeyCode::ControlLeft) {
+            mic_state.enabled = !mic_state.enabled;
+        }
+        // keep in sync so switching back to push to talk doesn't flip the state
+        *pressed = input.pressed(KeyCode::ControlLeft);
+    } else if input.pressed(KeyCode::ControlLeft) != *pressed {
         *pressed = !*pressed;
         mic_state.enabled = !mic_state.enabled;
     }
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use bevy_console::ConsoleCommand;
+use common::structs::{AppConfig, AudioBus, AudioSettings};
+use console::DoAddConsoleCommand;
+
+pub struct MixerPlugin;
+
+impl Plugin for MixerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command::<VolumeCommand, _>(volume_command);
+    }
+}
+
+// set or show mixer volumes
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/volume")]
+struct VolumeCommand {
+    // master, scene, media, voice, system or avatar
+    bus: Option<String>,
+    // 0-100
+    value: Option<i32>,
+}
+
+fn volume_command(
+    mut input: ConsoleCommand<VolumeCommand>,
+    mut mixer: ResMut<AudioSettings>,
+    mut config: ResMut<AppConfig>,
+) {
+    if let Some(Ok(command)) = input.take() {
+        let bus = match command.bus.as_deref().map(str::parse::<AudioBus>) {
+            Some(Ok(bus)) => Some(bus),
+            Some(Err(e)) => {
+                input.reply_failed(e);
+                return;
+            }
+            None => None,
+        };
+
+        match (bus, command.value) {
+            (Some(bus), Some(value)) => {
+                mixer.set(bus, value);
+                config.audio.set(bus, value);
+                input.reply_ok(format!("{} volume {}", bus.name(), mixer.get(bus)));
+            }
+            (Some(bus), None) => {
+                input.reply_ok(format!("{} volume {}", bus.name(), mixer.get(bus)));
+            }
+            (None, _) => {
+                let volumes = AudioBus::ALL
+                    .into_iter()
+                    .map(|bus| format!("{}: {}", bus.name(), mixer.get(bus)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                input.reply_ok(volumes);
+            }
+        }
+    }
+}
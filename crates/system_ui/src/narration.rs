@@ -0,0 +1,176 @@
+// screen reader support. the label of the gamepad/keyboard focused ui element is exposed to the os
+// screen reader through accesskit, and toasts and incoming chat are announced through a live
+// region, depending on the configured `NarrationLevel`.
+
+use bevy::{
+    a11y::{
+        accesskit::{Live, NodeBuilder, Role},
+        AccessibilityNode, Focus as A11yFocus,
+    },
+    prelude::*,
+    utils::HashMap,
+};
+use common::{
+    structs::{AppConfig, NarrationLevel, PrimaryUser},
+    tr,
+};
+use comms::{chat_marker_things, global_crdt::ChatEvent, profile::UserProfile};
+use scene_runner::Toasts;
+use ui_core::{gamepad_nav::GamepadFocus, tooltip::Tooltip};
+
+pub struct NarrationPlugin;
+
+impl Plugin for NarrationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup);
+        app.add_systems(
+            Update,
+            (
+                narrate_focus,
+                announce_toasts,
+                announce_chat,
+                update_announcer,
+            )
+                .chain(),
+        );
+    }
+}
+
+// live region that screen readers read out when its label changes
+#[derive(Component, Default)]
+struct Announcer {
+    pending: Vec<String>,
+    // toggled so repeated messages are still announced
+    repeat: bool,
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((Announcer::default(), announcer_node(String::default())));
+}
+
+fn announcer_node(text: String) -> AccessibilityNode {
+    let mut node = NodeBuilder::new(Role::StaticText);
+    node.set_live(Live::Polite);
+    node.set_name(text);
+    AccessibilityNode(node)
+}
+
+// the text shown by a node and its descendants, or its tooltip for image-only nodes
+fn node_label(
+    entity: Entity,
+    children: &Query<&Children>,
+    text: &Query<&Text>,
+    tooltips: &Query<&Tooltip>,
+) -> Option<String> {
+    let label = std::iter::once(entity)
+        .chain(children.iter_descendants(entity))
+        .filter_map(|e| text.get(e).ok())
+        .flat_map(|text| text.sections.iter().map(|section| section.value.trim()))
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if label.is_empty() {
+        tooltips.get(entity).ok().map(|tooltip| tooltip.0.clone())
+    } else {
+        Some(label)
+    }
+}
+
+fn narrate_focus(
+    mut commands: Commands,
+    config: Res<AppConfig>,
+    focus: Res<GamepadFocus>,
+    mut a11y_focus: ResMut<A11yFocus>,
+    children: Query<&Children>,
+    text: Query<&Text>,
+    tooltips: Query<&Tooltip>,
+) {
+    let target = focus
+        .entity
+        .filter(|_| focus.active && config.narration >= NarrationLevel::Focus);
+    if a11y_focus.0 == target {
+        return;
+    }
+
+    if let Some(entity) = target {
+        let mut node = NodeBuilder::new(Role::Button);
+        if let Some(label) = node_label(entity, &children, &text, &tooltips) {
+            node.set_name(label);
+        }
+        commands.entity(entity).try_insert(AccessibilityNode(node));
+    }
+    a11y_focus.0 = target;
+}
+
+fn announce_toasts(
+    config: Res<AppConfig>,
+    toasts: Res<Toasts>,
+    mut announcer: Query<&mut Announcer>,
+    mut seen: Local<HashMap<String, f32>>,
+) {
+    if !toasts.is_changed() {
+        return;
+    }
+
+    let Ok(mut announcer) = announcer.get_single_mut() else {
+        return;
+    };
+
+    let announce = config.narration >= NarrationLevel::Alerts;
+    for (key, toast) in toasts.0.iter() {
+        // a toast's time is reset when its message changes
+        if seen.insert(key.clone(), toast.time) != Some(toast.time) && announce {
+            announcer.pending.push(toast.message.clone());
+        }
+    }
+    seen.retain(|key, _| toasts.0.contains_key(key));
+}
+
+fn announce_chat(
+    config: Res<AppConfig>,
+    mut chats: EventReader<ChatEvent>,
+    users: Query<&UserProfile, Without<PrimaryUser>>,
+    mut announcer: Query<&mut Announcer>,
+) {
+    if config.narration < NarrationLevel::All {
+        chats.clear();
+        return;
+    }
+
+    let Ok(mut announcer) = announcer.get_single_mut() else {
+        return;
+    };
+
+    for ev in chats.read().filter(|ev| {
+        !chat_marker_things::ALL
+            .iter()
+            .any(|marker| ev.message.starts_with(*marker))
+    }) {
+        // only messages from other players
+        let Ok(profile) = users.get(ev.sender) else {
+            continue;
+        };
+        announcer.pending.push(tr!(
+            "narration-chat",
+            name = profile.content.name,
+            message = ev.message
+        ));
+    }
+}
+
+fn update_announcer(mut commands: Commands, mut announcer: Query<(Entity, &mut Announcer)>) {
+    let Ok((entity, mut announcer)) = announcer.get_single_mut() else {
+        return;
+    };
+    if announcer.pending.is_empty() {
+        return;
+    }
+
+    let mut text = announcer.pending.drain(..).collect::<Vec<_>>().join(". ");
+    announcer.repeat = !announcer.repeat;
+    if announcer.repeat {
+        text.push(' ');
+    }
+    commands.entity(entity).insert(announcer_node(text));
+}
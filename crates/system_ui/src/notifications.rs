@@ -0,0 +1,276 @@
+// notification center: a badge button showing the unread count, and a panel listing the
+// notification history with per-category toggles and a link to the events panel. notifications
+// are recorded via `Toaster::notify`, which also shows them as toasts.
+
+use bevy::prelude::*;
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use common::{
+    structs::{AppConfig, NotificationCategory, ToolTips, TooltipSource},
+    tr,
+};
+use scene_runner::Notifications;
+use ui_core::{
+    button::DuiButton,
+    toggle::Toggled,
+    ui_actions::{
+        Click, DataChanged, EntityActionExt, EventCloneExt, HoverEnter, HoverExit, On, UiCaller,
+    },
+    BODY_TEXT_STYLE,
+};
+
+use crate::{chat::BUTTON_SCALE, live_events::ShowEventsPanel};
+
+pub struct NotificationsPlugin;
+
+impl Plugin for NotificationsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup);
+        app.add_systems(Update, (update_badge, update_notification_list));
+    }
+}
+
+#[derive(Component)]
+struct NotificationBadge;
+
+#[derive(Component)]
+struct NotificationList {
+    root: Entity,
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            ImageBundle {
+                image: asset_server.load("images/notifications_button.png").into(),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::VMin(BUTTON_SCALE * 4.5),
+                    right: Val::VMin(BUTTON_SCALE * 0.5),
+                    width: Val::VMin(BUTTON_SCALE),
+                    height: Val::VMin(BUTTON_SCALE),
+                    ..Default::default()
+                },
+                focus_policy: bevy::ui::FocusPolicy::Block,
+                ..Default::default()
+            },
+            Interaction::default(),
+            On::<Click>::new(show_notifications),
+            On::<HoverEnter>::new(|mut tooltip: ResMut<ToolTips>| {
+                tooltip.0.insert(
+                    TooltipSource::Label("notifications"),
+                    vec![("Notifications".to_owned(), true)],
+                );
+            }),
+            On::<HoverExit>::new(|mut tooltip: ResMut<ToolTips>| {
+                tooltip.0.remove(&TooltipSource::Label("notifications"));
+            }),
+        ))
+        .with_children(|c| {
+            c.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::VMin(0.0),
+                        right: Val::VMin(0.0),
+                        min_width: Val::VMin(BUTTON_SCALE * 0.4),
+                        height: Val::VMin(BUTTON_SCALE * 0.4),
+                        padding: UiRect::horizontal(Val::VMin(0.3)),
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        display: Display::None,
+                        ..Default::default()
+                    },
+                    background_color: Color::srgb(0.9, 0.2, 0.2).into(),
+                    border_radius: BorderRadius::MAX,
+                    ..Default::default()
+                },
+                NotificationBadge,
+            ))
+            .with_children(|c| {
+                c.spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 14.0,
+                        ..Default::default()
+                    },
+                ));
+            });
+        });
+}
+
+fn update_badge(
+    notifications: Res<Notifications>,
+    mut badge: Query<(&mut Style, &Children), With<NotificationBadge>>,
+    mut text: Query<&mut Text>,
+) {
+    let Ok((mut style, children)) = badge.get_single_mut() else {
+        return;
+    };
+
+    let unread = notifications.unread();
+    let display = if unread == 0 {
+        Display::None
+    } else {
+        Display::Flex
+    };
+    if style.display != display {
+        style.display = display;
+    }
+
+    let label = if unread > 99 {
+        "99+".to_owned()
+    } else {
+        format!("{unread}")
+    };
+    if let Some(mut text) = children.first().and_then(|c| text.get_mut(*c).ok()) {
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+}
+
+fn show_notifications(
+    mut commands: Commands,
+    dui: Res<DuiRegistry>,
+    config: Res<AppConfig>,
+    existing: Query<&NotificationList>,
+) {
+    // the badge toggles the panel
+    if let Ok(list) = existing.get_single() {
+        commands.entity(list.root).despawn_recursive();
+        return;
+    }
+
+    let components = commands
+        .spawn_template(
+            &dui,
+            "notifications",
+            DuiProps::new().with_prop(
+                "buttons",
+                vec![
+                    DuiButton::new_enabled(
+                        tr!("button-clear"),
+                        |mut notifications: ResMut<Notifications>| {
+                            notifications.items.clear();
+                        },
+                    ),
+                    DuiButton::new_enabled_and_close_happy(
+                        tr!("button-events"),
+                        ShowEventsPanel.send_value(),
+                    ),
+                    DuiButton::close_happy(tr!("button-close")),
+                ],
+            ),
+        )
+        .unwrap();
+
+    for category in NotificationCategory::ALL {
+        commands
+            .entity(components.named("categories"))
+            .spawn_template(
+                &dui,
+                "notification-category",
+                DuiProps::new()
+                    .with_prop("label", category.label().to_owned())
+                    .with_prop("enabled", !config.muted_notifications.contains(&category))
+                    .with_prop(
+                        "ontoggle",
+                        On::<DataChanged>::new(
+                            move |caller: Res<UiCaller>,
+                                  toggle: Query<&Toggled>,
+                                  mut config: ResMut<AppConfig>| {
+                                let Ok(toggle) = toggle.get(caller.0) else {
+                                    warn!("toggle access failed");
+                                    return;
+                                };
+
+                                config.muted_notifications.retain(|c| *c != category);
+                                if !toggle.0 {
+                                    config.muted_notifications.push(category);
+                                }
+                            },
+                        ),
+                    ),
+            )
+            .unwrap();
+    }
+
+    commands
+        .entity(components.named("notification-list"))
+        .insert(NotificationList {
+            root: components.root,
+        });
+}
+
+fn update_notification_list(
+    mut commands: Commands,
+    mut notifications: ResMut<Notifications>,
+    list: Query<(Entity, Ref<NotificationList>)>,
+    dui: Res<DuiRegistry>,
+    time: Res<Time>,
+) {
+    let Ok((entity, list)) = list.get_single() else {
+        return;
+    };
+
+    if !notifications.is_changed() && !list.is_added() {
+        return;
+    }
+
+    commands.entity(entity).despawn_descendants();
+
+    if notifications.items.is_empty() {
+        commands.entity(entity).with_children(|c| {
+            c.spawn(TextBundle::from_section(
+                "Nothing to show",
+                BODY_TEXT_STYLE.get().unwrap().clone(),
+            ));
+        });
+    }
+
+    for notification in notifications.items.iter() {
+        let age = (time.elapsed_seconds() - notification.time).max(0.0) as u32;
+        let age = match age {
+            0..=59 => "just now".to_owned(),
+            60..=3599 => format!("{}m ago", age / 60),
+            _ => format!("{}h ago", age / 3600),
+        };
+        let color = if notification.read {
+            Color::srgb(0.7, 0.63, 0.75)
+        } else {
+            Color::srgb(0.85, 0.8, 1.0)
+        };
+
+        let item = commands
+            .entity(entity)
+            .spawn_template(
+                &dui,
+                "notification-item",
+                DuiProps::new()
+                    .with_prop("color", color)
+                    .with_prop("category", notification.category.label().to_owned())
+                    .with_prop("age", age)
+                    .with_prop("message", notification.message.clone()),
+            )
+            .unwrap();
+
+        // click through to the action and close the panel
+        if let Some(action) = notification.action.as_ref() {
+            commands
+                .entity(item.root)
+                .insert((Interaction::default(), action()));
+            commands.entity(item.named("body")).insert((
+                Interaction::default(),
+                list.root.despawn_recursive_on::<Click>(),
+            ));
+        }
+    }
+
+    // everything listed has now been seen. the list keeps the unread highlight until it is
+    // rebuilt, so don't trigger a rebuild
+    notifications
+        .bypass_change_detection()
+        .items
+        .iter_mut()
+        .for_each(|n| n.read = true);
+}
@@ -1,96 +1,307 @@
-use bevy::prelude::*;
+use std::path::PathBuf;
+
+use bevy::{math::Vec3Swizzles, prelude::*};
 use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps};
-use common::structs::PrimaryUser;
+use common::{
+    rpc::RpcCall,
+    structs::{PrimaryUser, SceneMeta},
+    util::FireEventEx,
+};
+use ipfs::{
+    ipfs_path::IpfsPath, ChangeRealmEvent, CurrentRealm, EntityDefinition, IpfsAssetServer,
+};
 use scene_runner::{
-    renderer_context::RendererSceneContext, update_world::gltf_container::GltfLoadingCount,
-    ContainingScene, OutOfWorld,
+    initialize_scene::{LiveScenes, PointerResult, ScenePointers, PARCEL_SIZE},
+    renderer_context::RendererSceneContext,
+    update_world::gltf_container::GltfLoadingCount,
+    ContainingScene, OutOfWorld, RealmTransition,
 };
-use ui_core::ui_actions::{Click, EventDefaultExt};
+use ui_core::ui_actions::{Click, EventDefaultExt, On};
 use wallet::Wallet;
 
 use crate::change_realm::ChangeRealmDialog;
 
+const TIPS: [&str; 8] = [
+    "Open the map to search for places and jump to any parcel",
+    "Use the Discover tab to find popular places and events",
+    "Click on another player to view their profile",
+    "Press Enter to chat with people nearby",
+    "Play emotes from the emote wheel to express yourself",
+    "Change realm to visit worlds and community servers",
+    "Scenes may ask for permissions, which you can manage in the settings",
+    "Pin your favourite places on the map to find them again",
+];
+// seconds each tip is shown for
+const TIP_SECS: f32 = 8.0;
+// ticks a scene runs before the player is placed into it (see `handle_out_of_world`)
+const READY_TICKS: u32 = 5;
+
+const STAGES: [&str; 4] = [
+    "Resolving location",
+    "Loading scene code",
+    "Downloading assets",
+    "Starting scene",
+];
+
 pub struct OowUiPlugin;
 
 impl Plugin for OowUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, set_oow);
+        app.init_resource::<LastLocation>();
+        app.add_systems(Update, (track_last_location, set_oow));
+    }
+}
+
+#[derive(Resource, Default)]
+struct LastLocation {
+    // the realm and parcel the player was last in a running scene at
+    location: Option<(String, IVec2)>,
+    // a realm change that has been requested but not yet applied
+    pending_realm: Option<String>,
+}
+
+#[derive(Default)]
+struct LoadProgress {
+    // most gltfs seen pending, to show the asset stage as a fraction
+    max_assets: usize,
+    thumbnail: bool,
+}
+
+fn player_parcel(transform: &GlobalTransform) -> IVec2 {
+    (transform.translation().xz() * Vec2::new(1.0, -1.0) / PARCEL_SIZE)
+        .floor()
+        .as_ivec2()
+}
+
+fn track_last_location(
+    mut last: ResMut<LastLocation>,
+    mut realm_changes: EventReader<ChangeRealmEvent>,
+    realm: Res<CurrentRealm>,
+    player: Query<(Entity, &GlobalTransform), (With<PrimaryUser>, Without<OutOfWorld>)>,
+    containing_scene: ContainingScene,
+    scenes: Query<&RendererSceneContext>,
+) {
+    if let Some(ev) = realm_changes.read().last() {
+        last.pending_realm = Some(ev.new_realm.clone());
+    }
+    if last.pending_realm.as_ref() == Some(&realm.address) {
+        last.pending_realm = None;
+    }
+
+    let Ok((player, transform)) = player.get_single() else {
+        return;
+    };
+
+    // only record positions inside a running scene, so a realm change in progress doesn't
+    // record the new realm against the old position
+    let running = containing_scene
+        .get_parcel_oow(player)
+        .and_then(|scene| scenes.get(scene).ok())
+        .is_some_and(|context| context.tick_number > READY_TICKS);
+    if !running || last.pending_realm.is_some() {
+        return;
+    }
+
+    let location = (realm.address.clone(), player_parcel(transform));
+    if last.location.as_ref() != Some(&location) {
+        last.location = Some(location);
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+// return to where the player was before the pending teleport or realm change
+fn go_back(mut commands: Commands, last: Res<LastLocation>, realm: Res<CurrentRealm>) {
+    let Some((address, parcel)) = last.location.clone() else {
+        commands.fire_event(ChangeRealmDialog);
+        return;
+    };
+
+    if last.pending_realm.is_some() || address != realm.address {
+        commands.fire_event(ChangeRealmEvent { new_realm: address });
+    }
+    commands.fire_event(RpcCall::TeleportPlayer {
+        scene: None,
+        to: parcel,
+        response: Default::default(),
+    });
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn set_oow(
     mut commands: Commands,
     wallet: Res<Wallet>,
-    oow: Query<&OutOfWorld>,
-    mut last_count: Local<usize>,
-    mut dialog: Local<Option<Entity>>,
+    player: Query<&GlobalTransform, (With<PrimaryUser>, With<OutOfWorld>)>,
+    mut dialog: Local<Option<(Entity, LoadProgress)>>,
     dui: Res<bevy_dui::DuiRegistry>,
-    player: Query<Entity, With<PrimaryUser>>,
-    containing_scene: ContainingScene,
-    scenes: Query<(&RendererSceneContext, Option<&GltfLoadingCount>)>,
+    (pointers, live_scenes): (Res<ScenePointers>, Res<LiveScenes>),
+    scenes: Query<(
+        Option<&RendererSceneContext>,
+        Option<&GltfLoadingCount>,
+        Option<&Handle<EntityDefinition>>,
+    )>,
+    definitions: Res<Assets<EntityDefinition>>,
+    ipfas: IpfsAssetServer,
     template: Query<&DuiEntities>,
     mut text: Query<&mut Text>,
+    mut style: Query<&mut Style>,
+    time: Res<Time>,
+    transition: Res<RealmTransition>,
 ) {
-    if wallet.address().is_none() || oow.is_empty() {
-        if let Some(ent) = dialog.take() {
-            commands.entity(ent).despawn_recursive();
+    let player = match player.get_single() {
+        Ok(player) if wallet.address().is_some() => player,
+        _ => {
+            if let Some((ent, _)) = dialog.take() {
+                commands.entity(ent).despawn_recursive();
+            }
+            return;
         }
-        *last_count = 0;
+    };
+
+    let tip = TIPS[(time.elapsed_seconds() / TIP_SECS) as usize % TIPS.len()];
+
+    let Some((ent, progress)) = dialog.as_mut() else {
+        *dialog = Some((
+            commands
+                .spawn_template(
+                    &dui,
+                    "out-of-world",
+                    DuiProps::new()
+                        .with_prop("title", "Scene".to_owned())
+                        .with_prop("tip", tip.to_owned())
+                        .with_prop("back", On::<Click>::new(go_back))
+                        .with_prop("cancel", ChangeRealmDialog::send_default_on::<Click>()),
+                )
+                .unwrap()
+                .root,
+            LoadProgress::default(),
+        ));
         return;
-    }
+    };
 
-    let Ok(player) = player.get_single() else {
+    let Ok(components) = template.get(*ent) else {
+        warn!("no components?!");
         return;
     };
 
-    let scene = containing_scene
-        .get_parcel_oow(player)
-        .and_then(|scene| scenes.get(scene).ok());
-    let title_text = scene
-        .map(|(context, _)| context.title.clone())
-        .unwrap_or("Scene".to_owned());
-    let state_text = scene
-        .map(|(_, gltf_count)| {
-            gltf_count
-                .map(|c| format!("{} assets", c.0))
-                .unwrap_or_else(|| "assets".to_owned())
-        })
-        .unwrap_or("Scene Info".to_owned());
-
-    match dialog.as_ref() {
-        Some(ent) => {
-            let Ok(components) = template.get(*ent) else {
-                warn!("no components?!");
-                return;
+    // the old realm's scenes are still listed until a realm change completes
+    let pointer = transition
+        .realm
+        .is_none()
+        .then(|| pointers.get(player_parcel(player)))
+        .flatten();
+    let (context, gltf_count, h_definition) = match pointer {
+        Some(PointerResult::Exists { hash, .. }) => live_scenes
+            .0
+            .get(hash)
+            .and_then(|scene| scenes.get(*scene).ok()),
+        _ => None,
+    }
+    .unwrap_or_default();
+    let definition = h_definition.and_then(|h| definitions.get(h));
+    let meta = definition
+        .and_then(|definition| definition.metadata.clone())
+        .and_then(|meta| serde_json::from_value::<SceneMeta>(meta).ok());
+
+    // stage progress
+    let tick = context.map(|context| context.tick_number).unwrap_or(0);
+    let pending_assets = gltf_count.map(|count| count.0).unwrap_or(0);
+    progress.max_assets = progress.max_assets.max(pending_assets);
+    let resolved = if pointer.is_some() { 1.0 } else { 0.0 };
+    let code = match (context, definition) {
+        (Some(_), _) => 1.0,
+        (None, Some(_)) => 0.5,
+        _ => 0.0,
+    };
+    let assets = match (context, pending_assets) {
+        (None, _) => 0.0,
+        (Some(_), 0) if tick > 0 => 1.0,
+        (Some(_), 0) => 0.0,
+        (Some(_), n) => 1.0 - n as f32 / progress.max_assets as f32,
+    };
+    let started = if assets == 1.0 {
+        (tick as f32 / READY_TICKS as f32).min(1.0)
+    } else {
+        0.0
+    };
+    let stages = [resolved, code, assets, started];
+
+    if let Ok(mut style) = style.get_mut(components.named("progress")) {
+        style.width = Val::Percent(stages.iter().sum::<f32>() * 100.0 / stages.len() as f32);
+    }
+
+    let mut current = true;
+    for (i, (label, stage_progress)) in STAGES.iter().zip(stages).enumerate() {
+        let Some(mut text) = components
+            .get_named(&format!("stage-{i}"))
+            .and_then(|c| text.get_mut(c).ok())
+        else {
+            continue;
+        };
+        let (value, color) = if stage_progress == 1.0 {
+            (format!("{label} - done"), Color::srgb(0.6, 1.0, 0.6))
+        } else if current {
+            current = false;
+            let detail = if i == 2 && pending_assets > 0 {
+                format!(" ({pending_assets} remaining)")
+            } else {
+                String::default()
             };
-            if let Some(mut title) = components
-                .get_named("title")
-                .and_then(|c| text.get_mut(c).ok())
-            {
-                title.sections[0].value = title_text;
-            }
-            if let Some(mut state) = components
-                .get_named("load-state")
-                .and_then(|c| text.get_mut(c).ok())
-            {
-                state.sections[0].value = state_text;
-            }
+            (format!("{label}...{detail}"), Color::WHITE)
+        } else {
+            (label.to_string(), Color::srgb(0.5, 0.5, 0.5))
+        };
+        text.sections[0].value = value;
+        text.sections[0].style.color = color;
+    }
+
+    // destination
+    let title = context
+        .map(|context| context.title.clone())
+        .or_else(|| meta.as_ref()?.display.as_ref()?.title.clone())
+        .or_else(|| {
+            let realm = transition.realm.as_ref()?;
+            Some(format!("Changing realm to {realm}"))
+        });
+    if let Some(title_text) = title {
+        if let Some(mut title) = components
+            .get_named("title")
+            .and_then(|c| text.get_mut(c).ok())
+        {
+            title.sections[0].value = title_text;
         }
-        None => {
-            *dialog = Some(
-                commands
-                    .spawn_template(
-                        &dui,
-                        "out-of-world",
-                        DuiProps::new()
-                            .with_prop("title", title_text)
-                            .with_prop("load-state", state_text)
-                            .with_prop("cancel", ChangeRealmDialog::send_default_on::<Click>()),
-                    )
-                    .unwrap()
-                    .root,
-            );
+    }
+
+    if !progress.thumbnail {
+        let thumbnail = meta
+            .as_ref()
+            .and_then(|meta| meta.display.as_ref()?.navmap_thumbnail.as_ref())
+            .zip(definition);
+        if let Some((path, definition)) = thumbnail {
+            let h_image = if path.starts_with("http") {
+                let image_path = IpfsPath::new_from_url(path, "image");
+                Some(
+                    ipfas
+                        .asset_server()
+                        .load::<Image>(PathBuf::from(&image_path)),
+                )
+            } else {
+                ipfas.load_content_file::<Image>(path, &definition.id).ok()
+            };
+
+            if let Some(h_image) = h_image {
+                let node = components.named("thumbnail");
+                commands.entity(node).try_insert(UiImage::new(h_image));
+                if let Ok(mut style) = style.get_mut(node) {
+                    style.display = Display::Flex;
+                }
+            }
+            progress.thumbnail = true;
         }
     }
+
+    if let Some(mut tip_text) = components
+        .get_named("tip")
+        .and_then(|c| text.get_mut(c).ok())
+    {
+        tip_text.sections[0].value = tip.to_owned();
+    }
 }
@@ -0,0 +1,266 @@
+// lightweight performance overlay: frame time graph and breakdown, visible meshes, entities,
+// texture memory, scene counts and network throughput. toggle with `/perfhud`.
+
+use std::{collections::VecDeque, sync::atomic::Ordering};
+
+use bevy::{prelude::*, ui::FocusPolicy};
+use bevy_console::ConsoleCommand;
+use common::{
+    sets::{SceneSets, SetupSets},
+    structs::{FrameLoad, MemoryPressure},
+};
+use comms::{NETWORK_BYTES_RECEIVED, NETWORK_BYTES_SENT};
+use console::DoAddConsoleCommand;
+use scene_runner::{initialize_scene::SceneLoading, renderer_context::RendererSceneContext};
+use ui_core::BODY_TEXT_STYLE;
+
+use super::SystemUiRoot;
+
+const GRAPH_SAMPLES: usize = 120;
+const GRAPH_HEIGHT_PX: f32 = 60.0;
+// frame time at the top of the graph
+const GRAPH_MAX_MS: f32 = 50.0;
+const TEXT_INTERVAL_SECS: f32 = 0.25;
+
+pub struct PerfHudPlugin;
+
+impl Plugin for PerfHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PerfHudVisible>();
+        app.add_systems(
+            Startup,
+            setup.in_set(SetupSets::Main).after(SetupSets::Init),
+        );
+        app.add_systems(
+            Update,
+            (update_visibility, update_graph, update_text)
+                .chain()
+                .after(SceneSets::PostLoop),
+        );
+        app.add_console_command::<PerfHudCommand, _>(set_perf_hud);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PerfHudVisible(pub bool);
+
+#[derive(Component)]
+struct PerfHud;
+
+#[derive(Component)]
+struct PerfHudBar(usize);
+
+#[derive(Component)]
+struct PerfHudText;
+
+fn setup(mut commands: Commands, root: Res<SystemUiRoot>) {
+    commands.entity(root.0).with_children(|commands| {
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(10.0),
+                        top: Val::Px(10.0),
+                        display: Display::None,
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(5.0)),
+                        ..Default::default()
+                    },
+                    background_color: Color::srgba(0.0, 0.0, 0.0, 0.6).into(),
+                    focus_policy: FocusPolicy::Pass,
+                    z_index: ZIndex::Global(i16::MAX as i32 + 3),
+                    ..Default::default()
+                },
+                PerfHud,
+            ))
+            .with_children(|commands| {
+                commands
+                    .spawn(NodeBundle {
+                        style: Style {
+                            height: Val::Px(GRAPH_HEIGHT_PX),
+                            align_items: AlignItems::FlexEnd,
+                            ..Default::default()
+                        },
+                        background_color: Color::srgba(1.0, 1.0, 1.0, 0.1).into(),
+                        ..Default::default()
+                    })
+                    .with_children(|commands| {
+                        for ix in 0..GRAPH_SAMPLES {
+                            commands.spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Px(2.0),
+                                        height: Val::Px(0.0),
+                                        ..Default::default()
+                                    },
+                                    ..Default::default()
+                                },
+                                PerfHudBar(ix),
+                            ));
+                        }
+                    });
+
+                commands.spawn((
+                    TextBundle::from_section("", BODY_TEXT_STYLE.get().unwrap().clone()),
+                    PerfHudText,
+                ));
+            });
+    });
+}
+
+fn update_visibility(visible: Res<PerfHudVisible>, mut hud: Query<&mut Style, With<PerfHud>>) {
+    if !visible.is_changed() {
+        return;
+    }
+
+    if let Ok(mut style) = hud.get_single_mut() {
+        style.display = if visible.0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+fn bar_color(ms: f32, target_ms: f32) -> Color {
+    if ms <= target_ms * 1.1 {
+        Color::srgb(0.2, 0.9, 0.2)
+    } else if ms <= target_ms * 2.0 {
+        Color::srgb(0.9, 0.8, 0.2)
+    } else {
+        Color::srgb(0.9, 0.2, 0.2)
+    }
+}
+
+fn update_graph(
+    visible: Res<PerfHudVisible>,
+    time: Res<Time<Real>>,
+    frame_load: Res<FrameLoad>,
+    mut samples: Local<VecDeque<f32>>,
+    mut bars: Query<(&PerfHudBar, &mut Style, &mut BackgroundColor)>,
+) {
+    if !visible.0 {
+        samples.clear();
+        return;
+    }
+
+    samples.push_back(time.delta_seconds() * 1000.0);
+    if samples.len() > GRAPH_SAMPLES {
+        samples.pop_front();
+    }
+
+    let target_ms = frame_load.target_secs * 1000.0;
+    // right-align so the newest sample is always at the right edge
+    let offset = GRAPH_SAMPLES - samples.len();
+    for (bar, mut style, mut color) in bars.iter_mut() {
+        let ms = bar
+            .0
+            .checked_sub(offset)
+            .and_then(|ix| samples.get(ix))
+            .copied()
+            .unwrap_or(0.0);
+        style.height = Val::Px((ms / GRAPH_MAX_MS).min(1.0) * GRAPH_HEIGHT_PX);
+        *color = bar_color(ms, target_ms).into();
+    }
+}
+
+pub(crate) fn format_bytes(bytes: f64) -> String {
+    if bytes >= 1024.0 * 1024.0 {
+        format!("{:.1}mb", bytes / 1024.0 / 1024.0)
+    } else {
+        format!("{:.1}kb", bytes / 1024.0)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_text(
+    visible: Res<PerfHudVisible>,
+    time: Res<Time<Real>>,
+    frame_load: Res<FrameLoad>,
+    memory: Option<Res<MemoryPressure>>,
+    meshes: Query<&ViewVisibility, With<Handle<Mesh>>>,
+    entities: Query<()>,
+    loading_scenes: Query<(), With<SceneLoading>>,
+    running_scenes: Query<&RendererSceneContext, Without<SceneLoading>>,
+    mut text: Query<&mut Text, With<PerfHudText>>,
+    mut last_update: Local<Option<(f32, u64, u64)>>,
+    mut frames: Local<u32>,
+) {
+    if !visible.0 {
+        *last_update = None;
+        return;
+    }
+
+    // averaged over the update interval, the frame time diagnostics are only registered with
+    // `log_fps`
+    *frames += 1;
+
+    let now = time.elapsed_seconds();
+    let sent = NETWORK_BYTES_SENT.load(Ordering::Relaxed);
+    let received = NETWORK_BYTES_RECEIVED.load(Ordering::Relaxed);
+    let Some((prev_time, prev_sent, prev_received)) = *last_update else {
+        *last_update = Some((now, sent, received));
+        *frames = 0;
+        return;
+    };
+    let elapsed = now - prev_time;
+    if elapsed < TEXT_INTERVAL_SECS {
+        return;
+    }
+    *last_update = Some((now, sent, received));
+    let frame_count = std::mem::take(&mut *frames).max(1);
+
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+
+    let fps = frame_count as f32 / elapsed;
+    let frame_ms = elapsed * 1000.0 / frame_count as f32;
+
+    let visible_meshes = meshes.iter().filter(|vis| vis.get()).count();
+    let running = running_scenes
+        .iter()
+        .filter(|context| !context.broken)
+        .count();
+    let textures = memory
+        .as_ref()
+        .map(|memory| format_bytes(memory.texture_bytes as f64))
+        .unwrap_or_else(|| "-".to_owned());
+    let rss = memory
+        .as_ref()
+        .and_then(|memory| memory.rss_bytes)
+        .map(|rss| format_bytes(rss as f64))
+        .unwrap_or_else(|| "-".to_owned());
+    let elapsed = elapsed as f64;
+    let up = format_bytes((sent - prev_sent) as f64 / elapsed);
+    let down = format_bytes((received - prev_received) as f64 / elapsed);
+
+    text.sections[0].value = format!(
+        "{fps:.0} fps ({frame_ms:.1}ms, target {:.1}ms)\n\
+        scene loop: {:.1}ms, other: {:.1}ms\n\
+        visible meshes: {visible_meshes}\n\
+        entities: {}\n\
+        textures: {textures}, process: {rss}\n\
+        scenes: {running} running, {} loading\n\
+        network: {up}/s up, {down}/s down",
+        frame_load.target_secs * 1000.0,
+        frame_load.scene_secs * 1000.0,
+        frame_load.non_scene_secs * 1000.0,
+        entities.iter().count(),
+        loading_scenes.iter().count(),
+    );
+}
+
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/perfhud")]
+struct PerfHudCommand {
+    on: Option<bool>,
+}
+
+fn set_perf_hud(mut input: ConsoleCommand<PerfHudCommand>, mut visible: ResMut<PerfHudVisible>) {
+    if let Some(Ok(command)) = input.take() {
+        visible.0 = command.on.unwrap_or(!visible.0);
+        input.reply_ok("");
+    }
+}
@@ -11,7 +11,10 @@ use common::{
 };
 use ipfs::CurrentRealm;
 use scene_runner::{
-    permissions::{PermissionLevel, PermissionManager, PermissionRequest, PermissionStrings},
+    permissions::{
+        answer_permission_requests, PermissionLevel, PermissionManager, PermissionRequest,
+        PermissionStrings,
+    },
     renderer_context::RendererSceneContext,
     ContainingScene,
 };
@@ -27,7 +30,11 @@ pub struct PermissionPlugin;
 impl Plugin for PermissionPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PermissionManager>()
-            .add_systems(PostUpdate, update_permissions);
+            // automatic test answers take their requests first
+            .add_systems(
+                PostUpdate,
+                update_permissions.after(answer_permission_requests),
+            );
     }
 }
 
@@ -167,6 +174,38 @@ fn update_permissions(
         let is_portable = req.is_portable;
         let scene_ent = req.scene;
         let ty = req.ty;
+
+        let mut buttons2 = vec![DuiButton::new_enabled_and_close_silent(
+            "Manage Permissions",
+            (move |mut target: ResMut<PermissionTarget>| {
+                target.scene = Some(scene_ent);
+                target.ty = Some(ty);
+            })
+            .pipe(ShowSettingsEvent(SettingsTab::Permissions).send_value())
+            .pipe(move || {
+                cancel_sx.clone().send(());
+            }),
+        )];
+        if let Some(details) = req.details.clone() {
+            buttons2.insert(
+                0,
+                DuiButton::new_enabled(
+                    "View Details",
+                    move |mut commands: Commands, dui: Res<DuiRegistry>| {
+                        commands
+                            .spawn_template(
+                                &dui,
+                                "permission-details-dialog",
+                                DuiProps::new()
+                                    .with_prop("title", format!("{} Details", ty.title()))
+                                    .with_prop("body", details.clone())
+                                    .with_prop("buttons", vec![DuiButton::close_happy("Ok")]),
+                            )
+                            .unwrap();
+                    },
+                ),
+            );
+        }
         let popup = commands
             .spawn_template(
                 &dui,
@@ -187,20 +226,7 @@ fn update_permissions(
                             ),
                         ],
                     )
-                    .with_prop(
-                        "buttons2",
-                        vec![DuiButton::new_enabled_and_close_silent(
-                            "Manage Permissions",
-                            (move |mut target: ResMut<PermissionTarget>| {
-                                target.scene = Some(scene_ent);
-                                target.ty = Some(ty);
-                            })
-                            .pipe(ShowSettingsEvent(SettingsTab::Permissions).send_value())
-                            .pipe(move || {
-                                cancel_sx.clone().send(());
-                            }),
-                        )],
-                    )
+                    .with_prop("buttons2", buttons2)
                     .with_prop(
                         "options",
                         if is_portable {
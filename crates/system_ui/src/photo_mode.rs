@@ -0,0 +1,338 @@
+// photo mode controls. the camera itself is flown by `user_input::photo_mode`, and the view
+// overrides are applied in `visuals`. this module hides the hud, shows the control panel and saves
+// screenshots with their metadata to the gallery folder (browsed in `gallery`).
+
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, render::view::screenshot::ScreenshotManager, window::PrimaryWindow};
+use bevy_console::ConsoleCommand;
+use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps, DuiRegistry};
+use common::{
+    structs::{ActiveDialog, DofConfig, PhotoMode, RenderTargetCapture},
+    tr,
+    util::gallery_dir,
+};
+use console::DoAddConsoleCommand;
+use system_bridge::settings::color_lut_settings::ColorLutSetting;
+use ui_core::ui_actions::{Click, On};
+
+use crate::{
+    gallery::{open_gallery_folder, save_metadata, PhotoContext},
+    SystemUiRoot,
+};
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (exit_on_escape, update_photo_panel, capture_photo).chain(),
+        );
+        app.add_console_command::<PhotoCommand, _>(photo_command);
+    }
+}
+
+#[derive(Component, Default)]
+struct PhotoPanel {
+    // frames until the screenshot is taken, with the panel hidden
+    capture: Option<u32>,
+}
+
+const MIN_FOV: f32 = 10.0 * PI / 180.0;
+const MAX_FOV: f32 = 120.0 * PI / 180.0;
+const MAX_ROLL: f32 = PI / 4.0;
+
+fn new_session() -> PhotoMode {
+    PhotoMode {
+        active: true,
+        supersample: 1,
+        ..Default::default()
+    }
+}
+
+pub fn enter_photo_mode(mut photo: ResMut<PhotoMode>) {
+    *photo = new_session();
+}
+
+fn exit_photo_mode(mut photo: ResMut<PhotoMode>) {
+    photo.active = false;
+}
+
+/// enter or leave photo mode
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/photo")]
+struct PhotoCommand;
+
+fn photo_command(mut input: ConsoleCommand<PhotoCommand>, mut photo: ResMut<PhotoMode>) {
+    if let Some(Ok(_)) = input.take() {
+        if photo.active {
+            photo.active = false;
+        } else {
+            *photo = new_session();
+        }
+        input.ok();
+    }
+}
+
+fn exit_on_escape(
+    mut photo: ResMut<PhotoMode>,
+    keys: Res<ButtonInput<KeyCode>>,
+    active_dialog: Res<ActiveDialog>,
+) {
+    if photo.active && !active_dialog.in_use() && keys.just_pressed(KeyCode::Escape) {
+        photo.active = false;
+    }
+}
+
+fn adjust(f: impl Fn(&mut PhotoMode) + Send + Sync + 'static) -> On<Click> {
+    On::<Click>::new(move |mut photo: ResMut<PhotoMode>| f(&mut photo))
+}
+
+fn step_filter(photo: &mut PhotoMode, step: isize) {
+    let options = std::iter::once(None)
+        .chain(ColorLutSetting::luts().into_iter().map(Some))
+        .collect::<Vec<_>>();
+    let current = options
+        .iter()
+        .position(|option| option == &photo.filter)
+        .unwrap_or(0) as isize;
+    photo.filter = options[(current + step).rem_euclid(options.len() as isize) as usize].clone();
+}
+
+fn photo_props() -> DuiProps {
+    DuiProps::new()
+        .with_prop(
+            "fov-down",
+            adjust(|p| p.fov = (p.fov - 5f32.to_radians()).max(MIN_FOV)),
+        )
+        .with_prop(
+            "fov-up",
+            adjust(|p| p.fov = (p.fov + 5f32.to_radians()).min(MAX_FOV)),
+        )
+        .with_prop(
+            "roll-down",
+            adjust(|p| p.roll = (p.roll - 5f32.to_radians()).max(-MAX_ROLL)),
+        )
+        .with_prop(
+            "roll-up",
+            adjust(|p| p.roll = (p.roll + 5f32.to_radians()).min(MAX_ROLL)),
+        )
+        .with_prop(
+            "dof-toggle",
+            adjust(|p| {
+                p.dof = match p.dof {
+                    Some(_) => None,
+                    None => Some(DofConfig::default()),
+                }
+            }),
+        )
+        .with_prop(
+            "focus-down",
+            adjust(|p| {
+                if let Some(dof) = p.dof.as_mut() {
+                    dof.focal_distance = (dof.focal_distance / 1.25).max(0.5);
+                }
+            }),
+        )
+        .with_prop(
+            "focus-up",
+            adjust(|p| {
+                if let Some(dof) = p.dof.as_mut() {
+                    dof.focal_distance = (dof.focal_distance * 1.25).min(500.0);
+                }
+            }),
+        )
+        .with_prop(
+            "aperture-down",
+            adjust(|p| {
+                if let Some(dof) = p.dof.as_mut() {
+                    dof.aperture_f_stops = (dof.aperture_f_stops / 1.4).max(0.5);
+                }
+            }),
+        )
+        .with_prop(
+            "aperture-up",
+            adjust(|p| {
+                if let Some(dof) = p.dof.as_mut() {
+                    dof.aperture_f_stops = (dof.aperture_f_stops * 1.4).min(32.0);
+                }
+            }),
+        )
+        .with_prop(
+            "time-down",
+            adjust(|p| {
+                p.time_of_day = Some((p.time_of_day.unwrap_or(12.0) - 1.0).rem_euclid(24.0))
+            }),
+        )
+        .with_prop(
+            "time-up",
+            adjust(|p| {
+                p.time_of_day = Some((p.time_of_day.unwrap_or(12.0) + 1.0).rem_euclid(24.0))
+            }),
+        )
+        .with_prop("time-reset", adjust(|p| p.time_of_day = None))
+        .with_prop("filter-prev", adjust(|p| step_filter(p, -1)))
+        .with_prop("filter-next", adjust(|p| step_filter(p, 1)))
+        .with_prop(
+            "supersample",
+            adjust(|p| p.supersample = if p.supersample > 1 { 1 } else { 2 }),
+        )
+        .with_prop(
+            "capture",
+            On::<Click>::new(|mut panel: Query<&mut PhotoPanel>| {
+                if let Ok(mut panel) = panel.get_single_mut() {
+                    panel.capture.get_or_insert(2);
+                }
+            }),
+        )
+        .with_prop("gallery", On::<Click>::new(open_gallery_folder))
+        .with_prop("exit", On::<Click>::new(exit_photo_mode))
+}
+
+fn photo_values(photo: &PhotoMode) -> [(&'static str, String); 7] {
+    let dof = photo.dof;
+    [
+        ("fov", format!("{:.0}°", photo.fov.to_degrees())),
+        ("roll", format!("{:.0}°", photo.roll.to_degrees())),
+        ("dof", if dof.is_some() { "On" } else { "Off" }.to_owned()),
+        (
+            "focus",
+            dof.map(|dof| format!("{:.1}m", dof.focal_distance))
+                .unwrap_or_else(|| "-".to_owned()),
+        ),
+        (
+            "aperture",
+            dof.map(|dof| format!("f/{:.1}", dof.aperture_f_stops))
+                .unwrap_or_else(|| "-".to_owned()),
+        ),
+        (
+            "time",
+            photo
+                .time_of_day
+                .map(|hours| format!("{:02.0}:00", hours))
+                .unwrap_or_else(|| "Scene".to_owned()),
+        ),
+        (
+            "filter",
+            photo
+                .filter
+                .as_deref()
+                .map(ColorLutSetting::lut_name)
+                .unwrap_or_else(|| "None".to_owned()),
+        ),
+    ]
+}
+
+fn update_photo_panel(
+    mut commands: Commands,
+    dui: Res<DuiRegistry>,
+    photo: Res<PhotoMode>,
+    root: Res<SystemUiRoot>,
+    mut root_visibility: Query<&mut Visibility>,
+    panel: Query<(Entity, &DuiEntities), With<PhotoPanel>>,
+    mut text: Query<&mut Text>,
+) {
+    if !photo.is_changed() {
+        return;
+    }
+
+    if let Ok(mut visibility) = root_visibility.get_mut(root.0) {
+        *visibility = if photo.active {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+
+    let Ok((panel, nodes)) = panel.get_single() else {
+        if photo.active {
+            let mut props = photo_props();
+            for (key, value) in photo_values(&photo) {
+                props.insert_prop(key, value);
+            }
+            props.insert_prop("supersample-label", supersample_label(&photo));
+            let components = commands.spawn_template(&dui, "photo-mode", props).unwrap();
+            commands
+                .entity(components.root)
+                .insert(PhotoPanel::default());
+        }
+        return;
+    };
+
+    if !photo.active {
+        commands.entity(panel).despawn_recursive();
+        return;
+    }
+
+    for (key, value) in photo_values(&photo).into_iter().chain(std::iter::once((
+        "supersample-label",
+        supersample_label(&photo),
+    ))) {
+        if let Ok(mut text) = text.get_mut(nodes.named(key)) {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn supersample_label(photo: &PhotoMode) -> String {
+    format!("Supersample: {}x", photo.supersample.max(1))
+}
+
+fn capture_photo(
+    mut panel: Query<(&mut PhotoPanel, &DuiEntities, &mut Visibility)>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    mut target_capture: ResMut<RenderTargetCapture>,
+    photo: Res<PhotoMode>,
+    mut text: Query<&mut Text>,
+    context: PhotoContext,
+) {
+    let Ok((mut panel, nodes, mut visibility)) = panel.get_single_mut() else {
+        return;
+    };
+    let Some(frames) = panel.capture.as_mut() else {
+        return;
+    };
+
+    // hide the panel for a frame before capturing
+    *visibility = Visibility::Hidden;
+    *frames -= 1;
+    if *frames > 0 {
+        return;
+    }
+    panel.capture = None;
+    *visibility = Visibility::Inherited;
+
+    let dir = gallery_dir();
+    let path = dir.join(format!(
+        "photo-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let status = match (std::fs::create_dir_all(&dir), window.get_single()) {
+        (Err(e), _) => tr!("photo-folder-failed", error = e),
+        (_, Err(_)) => tr!("photo-no-window"),
+        (Ok(_), Ok(window)) => {
+            // supersampled photos are saved from the offscreen target at full resolution
+            let saved = if photo.supersample > 1 && target_capture.save_to_disk(&path) {
+                Ok(())
+            } else {
+                screenshots.save_screenshot_to_disk(window, &path)
+            };
+            match saved {
+                Ok(()) => {
+                    // described for the gallery and camera reel
+                    if let Err(e) = save_metadata(&path, &context.metadata()) {
+                        warn!("failed to save photo metadata: {e}");
+                    }
+                    tr!("photo-saved", path = path.display())
+                }
+                Err(e) => tr!("photo-capture-failed", error = e),
+            }
+        }
+    };
+
+    if let Ok(mut text) = text.get_mut(nodes.named("status")) {
+        text.sections[0].value = status;
+    }
+}
@@ -9,14 +9,17 @@ use common::{
     structs::{
         ActiveDialog, AppConfig, PermissionTarget, SettingsTab, ShowSettingsEvent, SystemAudio,
     },
+    tr,
     util::FireEventEx,
 };
 use comms::profile::CurrentUserProfile;
 use ipfs::{ChangeRealmEvent, CurrentRealm};
 use ui_core::{
+    animation::DespawnAnimatedExt,
     button::{DuiButton, TabSelection},
     ui_actions::{Click, DataChanged, EventCloneExt, EventDefaultExt, On, UiCaller},
 };
+use wallet::balances::{Balance, RefreshBalances, WalletBalances};
 
 use crate::{
     app_settings::{AppSettingsDetail, AppSettingsPlugin},
@@ -24,8 +27,10 @@ use crate::{
     chat::BUTTON_SCALE,
     discover::DiscoverSettingsPlugin,
     emotes::EmoteSettingsPlugin,
+    gallery::GalleryPlugin,
     permissions::{PermissionSettingsDetail, PermissionSettingsPlugin},
     profile_detail::ProfileDetail,
+    teleport_preview::TeleportPreview,
     wearables::WearableSettingsPlugin,
 };
 
@@ -35,13 +40,14 @@ impl Plugin for ProfileEditPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ShowSettingsEvent>();
         app.add_systems(Startup, setup);
-        app.add_systems(Update, show_settings);
+        app.add_systems(Update, (show_settings, update_balance_text));
         app.add_plugins((
             DiscoverSettingsPlugin,
             WearableSettingsPlugin,
             EmoteSettingsPlugin,
             AppSettingsPlugin,
             PermissionSettingsPlugin,
+            GalleryPlugin,
         ));
     }
 }
@@ -86,7 +92,7 @@ impl InfoDialog {
                     DuiProps::new()
                         .with_prop("title", title.clone())
                         .with_prop("body", body.clone())
-                        .with_prop("buttons", vec![DuiButton::close_happy("Ok")]),
+                        .with_prop("buttons", vec![DuiButton::close_happy(tr!("button-ok"))]),
                 )
                 .unwrap();
         })
@@ -103,6 +109,8 @@ pub struct SettingsDialog {
 #[derive(Clone)]
 pub enum OnCloseEvent {
     ChangeRealm(ChangeRealmEvent, RpcCall),
+    Teleport(RpcCall),
+    TeleportPreview(IVec2),
     SomethingElse,
 }
 
@@ -209,7 +217,10 @@ fn really_close_settings(
         return;
     };
 
-    commands.entity(dialog_ent).despawn_recursive();
+    commands
+        .entity(dialog_ent)
+        .remove::<SettingsDialog>()
+        .despawn_animated();
 
     // touch the app config so all settings get reverted
     config.set_changed();
@@ -229,15 +240,23 @@ pub fn close_settings(
 
     let ev = settings.on_close.take();
     if settings.modified {
-        let send_onclose =
-            move |mut cr: EventWriter<ChangeRealmEvent>, mut rpc: EventWriter<RpcCall>| match &ev {
-                Some(OnCloseEvent::ChangeRealm(cr_ev, rpc_ev)) => {
-                    cr.send(cr_ev.clone());
-                    rpc.send(rpc_ev.clone());
-                }
-                Some(OnCloseEvent::SomethingElse) => (),
-                _ => (),
-            };
+        let send_onclose = move |mut commands: Commands,
+                                 mut cr: EventWriter<ChangeRealmEvent>,
+                                 mut rpc: EventWriter<RpcCall>| match &ev
+        {
+            Some(OnCloseEvent::ChangeRealm(cr_ev, rpc_ev)) => {
+                cr.send(cr_ev.clone());
+                rpc.send(rpc_ev.clone());
+            }
+            Some(OnCloseEvent::Teleport(rpc_ev)) => {
+                rpc.send(rpc_ev.clone());
+            }
+            Some(OnCloseEvent::TeleportPreview(parcel)) => {
+                commands.fire_event(TeleportPreview(*parcel));
+            }
+            Some(OnCloseEvent::SomethingElse) => (),
+            _ => (),
+        };
 
         commands
             .spawn_template(
@@ -260,26 +279,40 @@ pub fn close_settings(
                                 "Discard",
                                 really_close_settings.pipe(send_onclose),
                             ),
-                            DuiButton::new_enabled_and_close_sad(
-                                "Cancel",
-                                |mut q: Query<&mut SettingsDialog>| {
-                                    if let Ok(mut settings) = q.get_single_mut() {
-                                        settings.on_close = None;
-                                    }
-                                },
-                            ),
+                            DuiButton {
+                                back: true,
+                                ..DuiButton::new_enabled_and_close_sad(
+                                    "Cancel",
+                                    |mut q: Query<&mut SettingsDialog>| {
+                                        if let Ok(mut settings) = q.get_single_mut() {
+                                            settings.on_close = None;
+                                        }
+                                    },
+                                )
+                            },
                         ],
                     ),
             )
             .unwrap();
     } else {
-        commands.entity(settings_ent).despawn_recursive();
+        // the marker goes straight away so the dialog can be reopened while it animates out
+        commands
+            .entity(settings_ent)
+            .remove::<SettingsDialog>()
+            .despawn_animated();
         match &ev {
             Some(OnCloseEvent::ChangeRealm(cr_ev, rpc_ev)) => {
                 cr.send(cr_ev.clone());
                 rpc.send(rpc_ev.clone());
                 commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
             }
+            Some(OnCloseEvent::Teleport(rpc_ev)) => {
+                rpc.send(rpc_ev.clone());
+                commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
+            }
+            Some(OnCloseEvent::TeleportPreview(parcel)) => {
+                commands.fire_event(TeleportPreview(*parcel));
+            }
             _ => {
                 commands.fire_event(SystemAudio("sounds/ui/toggle_disable.wav".to_owned()));
             }
@@ -297,6 +330,8 @@ pub fn show_settings(
     existing: Query<(), With<SettingsDialog>>,
     active_dialog: Res<ActiveDialog>,
     mut pending: Local<Option<SettingsTab>>,
+    balances: Res<WalletBalances>,
+    mut refresh_balances: EventWriter<RefreshBalances>,
 ) {
     let Some(tab) = ev.read().last().map(|ev| ev.0).or(pending.take()) else {
         return;
@@ -320,6 +355,7 @@ pub fn show_settings(
         SettingsTab::Map => 4,
         SettingsTab::Settings => 5,
         SettingsTab::Permissions => 6,
+        SettingsTab::Gallery => 7,
     };
 
     let Some(profile) = &current_profile.profile.as_ref() else {
@@ -339,9 +375,11 @@ pub fn show_settings(
 
     let mut props = DuiProps::new();
 
+    refresh_balances.send(RefreshBalances::Cached);
+    props.insert_prop("balances", balance_text(&balances));
     props.insert_prop(
-        "connect-wallet",
-        InfoDialog::click("Not implemented".to_owned(), "Not implemented".to_owned()),
+        "refresh-balances",
+        RefreshBalances::Now.send_value_on::<Click>(),
     );
 
     let tabs = vec![
@@ -378,6 +416,11 @@ pub fn show_settings(
             enabled: true,
             ..Default::default()
         },
+        DuiButton {
+            label: Some("Gallery".to_owned()),
+            enabled: true,
+            ..Default::default()
+        },
     ];
 
     props.insert_prop(
@@ -413,6 +456,7 @@ pub fn show_settings(
                     4 => SettingsTab::Map,
                     5 => SettingsTab::Settings,
                     6 => SettingsTab::Permissions,
+                    7 => SettingsTab::Gallery,
                     _ => panic!(),
                 }
             },
@@ -427,9 +471,42 @@ pub fn show_settings(
     commands
         .entity(components.named("change-realm-button"))
         .insert(UpdateRealmText);
+    commands
+        .entity(components.named("wallet-balance"))
+        .insert(UpdateBalanceText);
     commands
         .entity(components.named("settings-content"))
         .insert(tab);
 
     //start on the wearables tab
 }
+
+#[derive(Component)]
+pub struct UpdateBalanceText;
+
+fn balance_text(balances: &WalletBalances) -> String {
+    match balances.balances() {
+        [] if balances.is_loading() => "Loading balances...".to_owned(),
+        [] => "No balances".to_owned(),
+        balances => balances
+            .iter()
+            .map(Balance::amount_text)
+            .collect::<Vec<_>>()
+            .join(" | "),
+    }
+}
+
+fn update_balance_text(
+    balances: Res<WalletBalances>,
+    mut q: Query<&mut Text, With<UpdateBalanceText>>,
+    added: Query<(), Added<UpdateBalanceText>>,
+) {
+    if !balances.is_changed() && added.is_empty() {
+        return;
+    }
+
+    let text = balance_text(&balances);
+    for mut label in q.iter_mut() {
+        label.sections[0].value.clone_from(&text);
+    }
+}
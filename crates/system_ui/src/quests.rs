@@ -0,0 +1,169 @@
+// quest tracker hud, shown under the minimap. lists the active quests with their current steps,
+// tasks and rewards, and raises notifications as quests progress.
+
+use bevy::prelude::*;
+use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps, DuiRegistry};
+use common::{rpc::RpcResultSender, structs::NotificationCategory};
+use scene_runner::Toaster;
+use social::{
+    quests::{quest_name, step_description, QuestChange},
+    QuestEvent, QuestsClient,
+};
+use ui_core::ui_actions::{Click, On};
+
+use crate::sysinfo::Minimap;
+
+pub struct QuestTrackerPlugin;
+
+impl Plugin for QuestTrackerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                setup_quest_tracker,
+                update_quest_tracker,
+                notify_quest_changes,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Component)]
+struct QuestTracker;
+
+fn setup_quest_tracker(
+    mut commands: Commands,
+    dui: Res<DuiRegistry>,
+    minimap: Query<Entity, Added<Minimap>>,
+) {
+    for minimap in minimap.iter() {
+        let tracker = commands
+            .entity(minimap)
+            .spawn_template(&dui, "quest-tracker", DuiProps::new())
+            .unwrap();
+        commands.entity(tracker.root).insert(QuestTracker);
+    }
+}
+
+fn update_quest_tracker(
+    mut commands: Commands,
+    quests: Res<QuestsClient>,
+    mut tracker: Query<(Ref<QuestTracker>, &DuiEntities, &mut Style)>,
+    dui: Res<DuiRegistry>,
+    mut generation: Local<Option<usize>>,
+) {
+    let Ok((tracker, nodes, mut style)) = tracker.get_single_mut() else {
+        return;
+    };
+
+    let client = quests.0.as_ref();
+    let current = client.map(|client| client.generation);
+    if current == *generation && !tracker.is_added() {
+        return;
+    }
+    *generation = current;
+
+    let list = nodes.named("quests");
+    commands.entity(list).despawn_descendants();
+
+    let mut instances = client
+        .map(|client| client.instances.values().collect::<Vec<_>>())
+        .unwrap_or_default();
+    style.display = if instances.is_empty() {
+        Display::None
+    } else {
+        Display::Flex
+    };
+    instances.sort_by_key(|instance| quest_name(instance));
+
+    for instance in instances {
+        let Some(state) = instance.state.as_ref() else {
+            continue;
+        };
+        let completed = state.steps_completed.len();
+        let total = completed + state.steps_left as usize;
+
+        let rewards = instance
+            .quest
+            .as_ref()
+            .and_then(|quest| client?.rewards.get(&quest.id))
+            .filter(|rewards| !rewards.is_empty())
+            .map(|rewards| {
+                let names = rewards
+                    .iter()
+                    .map(|reward| reward.name.as_str())
+                    .collect::<Vec<_>>();
+                format!("Rewards: {}", names.join(", "))
+            })
+            .unwrap_or_default();
+
+        let instance_id = instance.id.clone();
+        let item = commands
+            .entity(list)
+            .spawn_template(
+                &dui,
+                "quest-item",
+                DuiProps::new()
+                    .with_prop("name", quest_name(instance))
+                    .with_prop("progress", format!("{completed} of {total} steps"))
+                    .with_prop("rewards", rewards)
+                    .with_prop(
+                        "abort",
+                        On::<Click>::new(move |quests: Res<QuestsClient>| {
+                            if let Some(client) = quests.0.as_ref() {
+                                client.abort_quest(instance_id.clone(), RpcResultSender::default());
+                            }
+                        }),
+                    ),
+            )
+            .unwrap();
+
+        let mut steps = state.current_steps.iter().collect::<Vec<_>>();
+        steps.sort_by_key(|(step_id, _)| *step_id);
+        for (step_id, content) in steps {
+            let mut lines = vec![(step_description(instance, step_id), "#ffffff")];
+            lines.extend(
+                content
+                    .tasks_completed
+                    .iter()
+                    .map(|task| (format!("  [x] {}", task.description), "#aaaaaa")),
+            );
+            lines.extend(
+                content
+                    .to_dos
+                    .iter()
+                    .map(|task| (format!("  [ ] {}", task.description), "#ffffff")),
+            );
+
+            for (text, color) in lines {
+                commands
+                    .entity(item.named("steps"))
+                    .spawn_template(
+                        &dui,
+                        "quest-line",
+                        DuiProps::new()
+                            .with_prop("text", text)
+                            .with_prop("color", color.to_owned()),
+                    )
+                    .unwrap();
+            }
+        }
+    }
+}
+
+fn notify_quest_changes(mut events: EventReader<QuestEvent>, mut toaster: Toaster) {
+    for QuestEvent(change) in events.read() {
+        let (name, message) = match change {
+            QuestChange::Started { name } => (name, format!("Quest started: {name}")),
+            QuestChange::StepCompleted { name, step } => (name, format!("{name}: {step} complete")),
+            QuestChange::Completed { name } => (name, format!("Quest complete: {name}")),
+        };
+        toaster.notify(
+            NotificationCategory::Quest,
+            format!("quest-{name}"),
+            message,
+            None,
+        );
+    }
+}
@@ -0,0 +1,220 @@
+// recently visited and favorite realms, kept in the app config. they are listed first in the realm
+// browser, and `/goto <name>` joins one by name.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bevy_console::ConsoleCommand;
+use common::structs::{AppConfig, PrimaryUser, SavedRealm, SceneMeta};
+use console::DoAddConsoleCommand;
+use ipfs::{ipfs_path::IpfsPath, CurrentRealm, EntityDefinition, IpfsAssetServer};
+use scene_runner::ContainingScene;
+
+const MAX_RECENT_REALMS: usize = 10;
+
+pub struct RealmHistoryPlugin;
+
+impl Plugin for RealmHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (record_recent_realm, record_realm_thumbnail));
+        app.add_console_command::<FavoriteCommand, _>(favorite_command);
+        app.add_console_command::<UnfavoriteCommand, _>(unfavorite_command);
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn realm_name(realm: &CurrentRealm) -> String {
+    realm
+        .config
+        .realm_name
+        .clone()
+        .unwrap_or_else(|| realm.address.clone())
+}
+
+pub fn is_favorite(config: &AppConfig, url: &str) -> bool {
+    config.favorite_realms.iter().any(|realm| realm.url == url)
+}
+
+// add or remove a realm from the favorites, copying its visit details from the recent list
+pub fn toggle_favorite(config: &mut AppConfig, name: &str, url: &str) {
+    if let Some(ix) = config.favorite_realms.iter().position(|r| r.url == url) {
+        config.favorite_realms.remove(ix);
+        return;
+    }
+    let favorite = config
+        .recent_realms
+        .iter()
+        .find(|realm| realm.url == url)
+        .cloned()
+        .unwrap_or_else(|| SavedRealm {
+            name: name.to_owned(),
+            url: url.to_owned(),
+            last_visit: 0,
+            thumbnail: None,
+        });
+    config.favorite_realms.push(favorite);
+}
+
+fn record_recent_realm(realm: Res<CurrentRealm>, mut config: ResMut<AppConfig>) {
+    if !realm.is_changed() || realm.address.is_empty() {
+        return;
+    }
+
+    let now = now_secs();
+    let config = &mut *config;
+    let previous = config
+        .recent_realms
+        .iter()
+        .position(|recent| recent.url == realm.address)
+        .map(|ix| config.recent_realms.remove(ix));
+    config.recent_realms.insert(
+        0,
+        SavedRealm {
+            name: realm_name(&realm),
+            url: realm.address.clone(),
+            last_visit: now,
+            thumbnail: previous.and_then(|previous| previous.thumbnail),
+        },
+    );
+    config.recent_realms.truncate(MAX_RECENT_REALMS);
+
+    if let Some(favorite) = config
+        .favorite_realms
+        .iter_mut()
+        .find(|favorite| favorite.url == realm.address)
+    {
+        favorite.last_visit = now;
+    }
+}
+
+// use the navmap thumbnail of the first scene visited in a realm without one
+fn record_realm_thumbnail(
+    mut config: ResMut<AppConfig>,
+    player: Query<Entity, With<PrimaryUser>>,
+    containing_scene: ContainingScene,
+    scenes: Query<&Handle<EntityDefinition>>,
+    definitions: Res<Assets<EntityDefinition>>,
+    ipfas: IpfsAssetServer,
+) {
+    let Some(url) = config
+        .recent_realms
+        .first()
+        .filter(|recent| recent.thumbnail.is_none())
+        .map(|recent| recent.url.clone())
+    else {
+        return;
+    };
+
+    let Some(definition) = player
+        .get_single()
+        .ok()
+        .and_then(|player| containing_scene.get_parcel(player))
+        .and_then(|scene| scenes.get(scene).ok())
+        .and_then(|h_definition| definitions.get(h_definition))
+    else {
+        return;
+    };
+
+    let thumbnail = definition
+        .metadata
+        .clone()
+        .and_then(|meta| serde_json::from_value::<SceneMeta>(meta).ok())
+        .and_then(|meta| meta.display?.navmap_thumbnail)
+        .and_then(|path| {
+            if path.starts_with("http") {
+                Some(path)
+            } else {
+                let hash = definition.content.hash(&path)?;
+                Some(format!("{}{hash}", ipfas.ipfs().contents_endpoint()?))
+            }
+        });
+    // an empty string marks realms whose scene has no thumbnail, so it isn't checked again
+    let thumbnail = thumbnail.unwrap_or_default();
+
+    let config = &mut *config;
+    for saved in config
+        .recent_realms
+        .iter_mut()
+        .chain(config.favorite_realms.iter_mut())
+        .filter(|saved| saved.url == url)
+    {
+        saved.thumbnail = Some(thumbnail.clone());
+    }
+}
+
+// load a saved realm's thumbnail, if it has one
+pub fn load_thumbnail(ipfas: &IpfsAssetServer, saved: &SavedRealm) -> Option<Handle<Image>> {
+    let url = saved.thumbnail.as_ref().filter(|url| !url.is_empty())?;
+    let path = IpfsPath::new_from_url(url, "image");
+    Some(
+        ipfas
+            .asset_server()
+            .load::<Image>(std::path::PathBuf::from(&path)),
+    )
+}
+
+/// add the current realm to the favorites, optionally under another name for `/goto`
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/favorite")]
+struct FavoriteCommand {
+    name: Option<String>,
+}
+
+fn favorite_command(
+    mut input: ConsoleCommand<FavoriteCommand>,
+    realm: Res<CurrentRealm>,
+    mut config: ResMut<AppConfig>,
+) {
+    let Some(Ok(command)) = input.take() else {
+        return;
+    };
+
+    if realm.address.is_empty() {
+        input.reply_failed("not in a realm");
+        return;
+    }
+
+    let name = command.name.unwrap_or_else(|| realm_name(&realm));
+    if !is_favorite(&config, &realm.address) {
+        toggle_favorite(&mut config, &name, &realm.address);
+    }
+    if let Some(favorite) = config
+        .favorite_realms
+        .iter_mut()
+        .find(|favorite| favorite.url == realm.address)
+    {
+        favorite.name = name.clone();
+    }
+    input.reply_ok(format!(
+        "added {name} to favorites, use `/goto {name}` to return"
+    ));
+}
+
+/// remove a realm from the favorites
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/unfavorite")]
+struct UnfavoriteCommand {
+    name: String,
+}
+
+fn unfavorite_command(mut input: ConsoleCommand<UnfavoriteCommand>, mut config: ResMut<AppConfig>) {
+    let Some(Ok(command)) = input.take() else {
+        return;
+    };
+
+    let count = config.favorite_realms.len();
+    config
+        .favorite_realms
+        .retain(|favorite| !favorite.name.eq_ignore_ascii_case(&command.name));
+    if config.favorite_realms.len() < count {
+        input.reply_ok(format!("removed {} from favorites", command.name));
+    } else {
+        input.reply_failed(format!("no favorite named {}", command.name));
+    }
+}
@@ -0,0 +1,355 @@
+// remote control server for external tools (scene ci, stream overlays, test harnesses). enabled
+// with `--remote_control <port>`, it listens on localhost only and accepts websocket connections
+// from anything but web pages that present the session token (`--remote_control_token`, or random
+// and logged at startup) as `Authorization: Bearer <token>` or a `?token=` query parameter. each
+// text message is a json request answered with one json reply carrying the same `id`:
+//   {"id": 1, "type": "command", "line": "/goto 0,0"}
+//   {"id": 2, "type": "teleport", "parcel": [0, 0]}
+//   {"id": 3, "type": "screenshot"}
+//   {"id": 4, "type": "status"}
+// replies are `{"id", "ok": true, "result"}` or `{"id", "ok": false, "error"}`.
+
+use std::collections::VecDeque;
+
+use anyhow::anyhow;
+use async_std::net::{TcpListener, TcpStream};
+use async_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http, Message,
+};
+use bevy::{
+    prelude::*, render::view::screenshot::ScreenshotManager, tasks::IoTaskPool,
+    window::PrimaryWindow,
+};
+use bevy_console::PrintConsoleLine;
+use common::{
+    rpc::{RpcCall, RpcResultSender},
+    structs::PrimaryUser,
+    util::gallery_dir,
+};
+use console::slash_commands::CommandDispatcher;
+use futures_util::{SinkExt, StreamExt};
+use ipfs::CurrentRealm;
+use scene_runner::{
+    initialize_scene::{LiveScenes, SceneLoading},
+    renderer_context::RendererSceneContext,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::scene_info::player_parcel;
+
+// commands without a final `[ok]` or `[failed]` line
+const COMMAND_TIMEOUT_SECS: f32 = 10.0;
+
+type RemoteResult = Result<Value, String>;
+
+pub struct RemoteControlPlugin {
+    pub port: u16,
+    pub token: String,
+}
+
+impl Plugin for RemoteControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RemoteControl {
+            port: self.port,
+            token: self.token.clone(),
+            ..Default::default()
+        });
+        app.add_systems(Startup, start_server);
+        app.add_systems(Update, handle_requests);
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RemoteRequest {
+    Command { line: String },
+    Teleport { parcel: IVec2 },
+    Screenshot,
+    Status,
+}
+
+#[derive(Deserialize)]
+struct RemoteMessage {
+    #[serde(default)]
+    id: Value,
+    #[serde(flatten)]
+    request: RemoteRequest,
+}
+
+struct RunningCommand {
+    started: f32,
+    output: Vec<String>,
+    reply: oneshot::Sender<RemoteResult>,
+}
+
+#[derive(Resource, Default)]
+struct RemoteControl {
+    port: u16,
+    token: String,
+    requests: Option<mpsc::UnboundedReceiver<(RemoteRequest, oneshot::Sender<RemoteResult>)>>,
+    // commands run one at a time so their output can be collected
+    commands: VecDeque<(String, oneshot::Sender<RemoteResult>)>,
+    running: Option<RunningCommand>,
+}
+
+fn start_server(mut remote: ResMut<RemoteControl>) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    remote.requests = Some(receiver);
+    let (port, token) = (remote.port, remote.token.clone());
+    IoTaskPool::get()
+        .spawn(async move {
+            if let Err(e) = serve(port, token, sender).await {
+                warn!("remote control server failed: {e}");
+            }
+        })
+        .detach();
+}
+
+async fn serve(
+    port: u16,
+    token: String,
+    sender: mpsc::UnboundedSender<(RemoteRequest, oneshot::Sender<RemoteResult>)>,
+) -> Result<(), anyhow::Error> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    info!("remote control listening on 127.0.0.1:{port}, token {token}");
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let sender = sender.clone();
+        let token = token.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                if let Err(e) = handle_connection(stream, &token, sender).await {
+                    debug!("remote control connection {peer} closed: {e}");
+                }
+            })
+            .detach();
+    }
+}
+
+// the token from an `Authorization: Bearer` header or a `token` query parameter
+fn request_token(request: &Request) -> Option<&str> {
+    let header = request
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    header.or_else(|| {
+        request
+            .uri()
+            .query()?
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    })
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    token: &str,
+    sender: mpsc::UnboundedSender<(RemoteRequest, oneshot::Sender<RemoteResult>)>,
+) -> Result<(), anyhow::Error> {
+    let mut socket = async_tungstenite::accept_hdr_async(
+        stream,
+        |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+            // web pages can reach localhost too, but always send an origin
+            if request.headers().contains_key(http::header::ORIGIN) {
+                return Err(http::Response::builder()
+                    .status(http::StatusCode::FORBIDDEN)
+                    .body(Some("browser connections are not accepted".to_owned()))
+                    .unwrap());
+            }
+            if request_token(request) != Some(token) {
+                return Err(http::Response::builder()
+                    .status(http::StatusCode::UNAUTHORIZED)
+                    .body(Some("missing or invalid token".to_owned()))
+                    .unwrap());
+            }
+            Ok(response)
+        },
+    )
+    .await?;
+
+    while let Some(message) = socket.next().await {
+        let text = match message? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let reply = match serde_json::from_str::<RemoteMessage>(&text) {
+            Ok(RemoteMessage { id, request }) => {
+                let (reply_sender, reply) = oneshot::channel();
+                sender
+                    .send((request, reply_sender))
+                    .map_err(|_| anyhow!("client is shutting down"))?;
+                match reply.await {
+                    Ok(Ok(result)) => json!({ "id": id, "ok": true, "result": result }),
+                    Ok(Err(error)) => json!({ "id": id, "ok": false, "error": error }),
+                    Err(_) => json!({ "id": id, "ok": false, "error": "request dropped" }),
+                }
+            }
+            Err(e) => {
+                json!({ "id": null, "ok": false, "error": format!("invalid request: {e}") })
+            }
+        };
+        socket.send(Message::Text(reply.to_string())).await?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_requests(
+    mut remote: ResMut<RemoteControl>,
+    mut dispatcher: CommandDispatcher,
+    mut console: EventReader<PrintConsoleLine>,
+    mut rpc: EventWriter<RpcCall>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    time: Res<Time<Real>>,
+    realm: Res<CurrentRealm>,
+    player: Query<&GlobalTransform, With<PrimaryUser>>,
+    live_scenes: Res<LiveScenes>,
+    scenes: Query<(Option<&RendererSceneContext>, Option<&SceneLoading>)>,
+) {
+    let remote = &mut *remote;
+    while let Some((request, reply)) = remote
+        .requests
+        .as_mut()
+        .and_then(|requests| requests.try_recv().ok())
+    {
+        match request {
+            RemoteRequest::Command { line } => remote.commands.push_back((line, reply)),
+            RemoteRequest::Teleport { parcel } => {
+                let (sender, result) = oneshot::channel();
+                rpc.send(RpcCall::TeleportPlayer {
+                    scene: None,
+                    to: parcel,
+                    response: RpcResultSender::new(sender),
+                });
+                IoTaskPool::get()
+                    .spawn(async move {
+                        let result = result
+                            .await
+                            .unwrap_or_else(|_| Err("teleport dropped".to_owned()));
+                        let _ = reply.send(result.map(|_| json!({ "parcel": parcel })));
+                    })
+                    .detach();
+            }
+            RemoteRequest::Screenshot => {
+                let _ = reply.send(screenshot(&mut screenshots, window.get_single().ok()));
+            }
+            RemoteRequest::Status => {
+                let _ = reply.send(Ok(status(
+                    &realm,
+                    player.get_single().ok(),
+                    &live_scenes,
+                    &scenes,
+                )));
+            }
+        }
+    }
+
+    // collect output until the command reports a result
+    let now = time.elapsed_seconds();
+    for line in console.read() {
+        let Some(running) = remote.running.as_mut() else {
+            continue;
+        };
+        let line = line.line.to_string();
+        let ok = line.starts_with("[ok]");
+        let failed = line.starts_with("[failed]");
+        running.output.push(line);
+        if ok || failed {
+            let running = remote.running.take().unwrap();
+            let _ = running.reply.send(if ok {
+                Ok(json!({ "output": running.output }))
+            } else {
+                Err(running.output.join("\n"))
+            });
+        }
+    }
+    if remote
+        .running
+        .as_ref()
+        .is_some_and(|running| now - running.started > COMMAND_TIMEOUT_SECS)
+    {
+        let running = remote.running.take().unwrap();
+        let _ = running.reply.send(Err(format!(
+            "no result after {COMMAND_TIMEOUT_SECS} seconds, output: {}",
+            running.output.join("\n")
+        )));
+    }
+
+    if remote.running.is_none() {
+        if let Some((line, reply)) = remote.commands.pop_front() {
+            match dispatcher.run(&line) {
+                Ok(()) => {
+                    remote.running = Some(RunningCommand {
+                        started: now,
+                        output: Vec::default(),
+                        reply,
+                    })
+                }
+                Err(e) => {
+                    let _ = reply.send(Err(e));
+                }
+            }
+        }
+    }
+}
+
+fn screenshot(screenshots: &mut ScreenshotManager, window: Option<Entity>) -> RemoteResult {
+    let window = window.ok_or("no window")?;
+    let dir = gallery_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!(
+        "remote-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+    ));
+    screenshots
+        .save_screenshot_to_disk(window, &path)
+        .map_err(|e| e.to_string())?;
+    // written once the frame is captured
+    Ok(json!({ "path": path }))
+}
+
+fn status(
+    realm: &CurrentRealm,
+    player: Option<&GlobalTransform>,
+    live_scenes: &LiveScenes,
+    scenes: &Query<(Option<&RendererSceneContext>, Option<&SceneLoading>)>,
+) -> Value {
+    let mut scene_status = live_scenes
+        .0
+        .iter()
+        .map(|(hash, entity)| {
+            let (context, loading) = scenes.get(*entity).unwrap_or_default();
+            let state = match (context, loading) {
+                (_, Some(SceneLoading::Failed)) => "failed",
+                (_, Some(_)) => "loading",
+                (Some(context), _) if context.broken => "broken",
+                (Some(_), _) => "running",
+                (None, None) => "pending",
+            };
+            json!({
+                "hash": hash,
+                "title": context.map(|context| context.title.as_str()),
+                "base": context.map(|context| context.base),
+                "state": state,
+                "tick": context.map(|context| context.tick_number),
+            })
+        })
+        .collect::<Vec<_>>();
+    scene_status.sort_by(|a, b| a["hash"].as_str().cmp(&b["hash"].as_str()));
+
+    json!({
+        "realm": realm.address,
+        "realm_name": realm.config.realm_name,
+        "parcel": player.map(player_parcel),
+        "scenes": scene_status,
+    })
+}
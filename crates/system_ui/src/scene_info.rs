@@ -0,0 +1,398 @@
+// scene details from the scene's entity metadata, parcel ownership from the land registry and any
+// live event there: `/where` for the current location, `/scene_info <x,y>` for any parcel, and a
+// dialog opened from the minimap or the map's parcel menu.
+
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+    utils::HashMap,
+};
+use bevy_console::{ConsoleCommand, PrintConsoleLine};
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use common::{
+    structs::{IVec2Arg, PrimaryUser, SceneMeta},
+    tr,
+    util::TaskExt,
+};
+use console::DoAddConsoleCommand;
+use ipfs::{ActiveEntitiesRequest, CurrentRealm, EntityDefinition, IpfsAssetServer};
+use isahc::AsyncReadResponseExt;
+use scene_runner::{
+    initialize_scene::PARCEL_SIZE, renderer_context::RendererSceneContext, ContainingScene,
+};
+use serde::Deserialize;
+use ui_core::button::DuiButton;
+
+use crate::live_events::LiveEvents;
+
+pub struct SceneInfoPlugin;
+
+impl Plugin for SceneInfoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SceneInfoDialog>();
+        app.init_resource::<PendingSceneInfo>();
+        app.add_console_command::<WhereCommand, _>(where_command);
+        app.add_console_command::<SceneInfoCommand, _>(scene_info_command);
+        app.add_systems(Update, (request_scene_info_dialog, show_scene_info));
+    }
+}
+
+// show the scene info dialog for a parcel
+#[derive(Event)]
+pub struct SceneInfoDialog(pub IVec2);
+
+#[derive(Clone, Copy)]
+enum SceneInfoTarget {
+    Console,
+    Dialog,
+}
+
+type SceneInfoResult = (
+    Result<Vec<EntityDefinition>, anyhow::Error>,
+    Result<Option<ParcelTile>, anyhow::Error>,
+);
+
+struct SceneInfoRequest {
+    parcel: IVec2,
+    target: SceneInfoTarget,
+    // for linking the deployed entity
+    content_url: String,
+    task: Task<SceneInfoResult>,
+}
+
+#[derive(Resource, Default)]
+struct PendingSceneInfo(Vec<SceneInfoRequest>);
+
+const TILES_API: &str = "https://api.decentraland.org/v2/tiles";
+
+// a parcel's entry in the land registry
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ParcelTile {
+    // parcel or estate name
+    pub name: Option<String>,
+    pub owner: Option<String>,
+    pub estate_id: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct TilesResponse {
+    data: HashMap<String, ParcelTile>,
+}
+
+pub fn fetch_parcel_tile(parcel: IVec2) -> Task<Result<Option<ParcelTile>, anyhow::Error>> {
+    IoTaskPool::get().spawn(async move {
+        let (x, y) = (parcel.x, parcel.y);
+        let url = format!("{TILES_API}?x1={x}&y1={y}&x2={x}&y2={y}&include=name,owner,estateId");
+        let mut response = isahc::get_async(url).await.map_err(|e| anyhow!(e))?;
+        let tiles = response
+            .json::<TilesResponse>()
+            .await
+            .map_err(|e| anyhow!(e))?;
+        Ok(tiles.data.get(&format!("{x},{y}")).cloned())
+    })
+}
+
+impl ParcelTile {
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::default();
+        if let Some(name) = self.name.as_ref().filter(|name| !name.is_empty()) {
+            let kind = if self.estate_id.is_some() {
+                "estate"
+            } else {
+                "parcel name"
+            };
+            lines.push(format!("{kind}: {name}"));
+        }
+        lines.push(format!(
+            "owner: {}",
+            self.owner.as_deref().unwrap_or("none")
+        ));
+        lines
+    }
+}
+
+pub(crate) fn player_parcel(transform: &GlobalTransform) -> IVec2 {
+    (transform.translation().xz() * Vec2::new(1.0, -1.0) / PARCEL_SIZE)
+        .floor()
+        .as_ivec2()
+}
+
+/// show the current parcel, scene and content server
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/where")]
+struct WhereCommand;
+
+fn where_command(
+    mut input: ConsoleCommand<WhereCommand>,
+    player: Query<(Entity, &GlobalTransform), With<PrimaryUser>>,
+    containing_scene: ContainingScene,
+    scenes: Query<&RendererSceneContext>,
+    realm: Res<CurrentRealm>,
+) {
+    if let Some(Ok(_)) = input.take() {
+        let Ok((player, transform)) = player.get_single() else {
+            input.reply_failed("no player");
+            return;
+        };
+
+        let parcel = player_parcel(transform);
+        input.reply(format!("parcel: {},{}", parcel.x, parcel.y));
+        match containing_scene
+            .get_parcel_oow(player)
+            .and_then(|scene| scenes.get(scene).ok())
+        {
+            Some(context) => {
+                input.reply(format!("scene: {}", context.title));
+                input.reply(format!("hash: {}", context.hash));
+            }
+            None => input.reply("scene: none"),
+        }
+        input.reply(format!(
+            "realm: {} ({})",
+            realm.config.realm_name.as_deref().unwrap_or("-"),
+            realm.address
+        ));
+        input.reply_ok(format!("content server: {}", realm.public_url));
+    }
+}
+
+/// show the metadata of the scene at a parcel
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/scene_info")]
+struct SceneInfoCommand {
+    location: String,
+}
+
+fn scene_info_command(
+    mut input: ConsoleCommand<SceneInfoCommand>,
+    mut pending: ResMut<PendingSceneInfo>,
+    ipfas: IpfsAssetServer,
+    realm: Res<CurrentRealm>,
+) {
+    if let Some(Ok(command)) = input.take() {
+        match IVec2Arg::from_str(&command.location) {
+            Ok(IVec2Arg(parcel)) => {
+                pending
+                    .0
+                    .push(request(&ipfas, &realm, parcel, SceneInfoTarget::Console));
+                input.ok();
+            }
+            Err(_) => input.reply_failed(format!("invalid location `{}`", command.location)),
+        }
+    }
+}
+
+fn request(
+    ipfas: &IpfsAssetServer,
+    realm: &CurrentRealm,
+    parcel: IVec2,
+    target: SceneInfoTarget,
+) -> SceneInfoRequest {
+    let scene = ipfas.ipfs().active_entities(
+        ActiveEntitiesRequest::Pointers(vec![format!("{},{}", parcel.x, parcel.y)]),
+        None,
+    );
+    let tile = fetch_parcel_tile(parcel);
+    SceneInfoRequest {
+        parcel,
+        target,
+        content_url: realm.public_url.clone(),
+        task: IoTaskPool::get().spawn(async move { (scene.await, tile.await) }),
+    }
+}
+
+fn request_scene_info_dialog(
+    mut events: EventReader<SceneInfoDialog>,
+    mut pending: ResMut<PendingSceneInfo>,
+    ipfas: IpfsAssetServer,
+    realm: Res<CurrentRealm>,
+) {
+    for SceneInfoDialog(parcel) in events.read() {
+        pending
+            .0
+            .push(request(&ipfas, &realm, *parcel, SceneInfoTarget::Dialog));
+    }
+}
+
+// deployment date and the entity on the content server
+fn deployment_lines(entity: &EntityDefinition, content_url: &str) -> (Vec<String>, String) {
+    let mut lines = Vec::default();
+    if let Some(deployed) = entity
+        .timestamp
+        .and_then(|ms| chrono::DateTime::from_timestamp_millis(ms as i64))
+    {
+        lines.push(format!(
+            "deployed: {}",
+            deployed.format("%Y-%m-%d %H:%M UTC")
+        ));
+    }
+    let url = format!(
+        "{}/contents/{}",
+        content_url.trim_end_matches('/'),
+        entity.id
+    );
+    lines.push(format!("entity: {url}"));
+    (lines, url)
+}
+
+// title and detail lines
+fn describe(hash: &str, meta: &SceneMeta) -> (String, Vec<String>) {
+    let display = meta.display.as_ref();
+    let title = display
+        .and_then(|display| display.title.clone())
+        .unwrap_or_else(|| "Untitled scene".to_owned());
+
+    let mut lines = Vec::default();
+    if let Some(description) = display.and_then(|display| display.description.as_ref()) {
+        lines.push(description.clone());
+    }
+    lines.push(format!(
+        "base: {} ({} parcels)",
+        meta.scene.base,
+        meta.scene.parcels.len()
+    ));
+    lines.push(format!("hash: {hash}"));
+    if let Some(contact) = meta.contact.as_ref() {
+        let contact = [contact.name.as_deref(), contact.email.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !contact.is_empty() {
+            lines.push(format!("contact: {contact}"));
+        }
+    }
+    if let Some(rating) = meta
+        .policy
+        .as_ref()
+        .and_then(|policy| policy.content_rating.as_ref())
+    {
+        lines.push(format!("rating: {rating}"));
+    }
+    match meta.required_permissions.as_deref() {
+        Some(permissions) if !permissions.is_empty() => {
+            lines.push(format!("required permissions: {}", permissions.join(", ")))
+        }
+        _ => lines.push("required permissions: none".to_owned()),
+    }
+    for spawn in meta.spawn_points.iter().flatten() {
+        let (min, max) = spawn.position.bounding_box();
+        lines.push(format!(
+            "spawn point{}{}: ({:.1}, {:.1}, {:.1}) to ({:.1}, {:.1}, {:.1})",
+            spawn
+                .name
+                .as_ref()
+                .map(|name| format!(" `{name}`"))
+                .unwrap_or_default(),
+            if spawn.default { " (default)" } else { "" },
+            min.x,
+            min.y,
+            min.z,
+            max.x,
+            max.y,
+            max.z,
+        ));
+    }
+    if let Some(runtime) = meta.runtime_version.as_ref() {
+        lines.push(format!("sdk: {runtime}"));
+    }
+
+    (title, lines)
+}
+
+fn show_scene_info(
+    mut commands: Commands,
+    mut pending: ResMut<PendingSceneInfo>,
+    mut console: EventWriter<PrintConsoleLine>,
+    dui: Res<DuiRegistry>,
+    live_events: Res<LiveEvents>,
+) {
+    pending.0.retain_mut(|request| {
+        let Some((scene, tile)) = request.task.complete() else {
+            return true;
+        };
+        let parcel = request.parcel;
+
+        let mut entity_url = None;
+        let mut scene_parcels = vec![parcel];
+        let (title, mut lines) = match scene {
+            Ok(entities) => match entities.first() {
+                Some(entity) => {
+                    scene_parcels.extend(
+                        entity
+                            .pointers
+                            .iter()
+                            .flat_map(|pointer| pointer.parse::<IVec2Arg>())
+                            .map(|IVec2Arg(parcel)| parcel),
+                    );
+                    let (title, mut lines) = match entity
+                        .metadata
+                        .clone()
+                        .and_then(|meta| serde_json::from_value::<SceneMeta>(meta).ok())
+                    {
+                        Some(meta) => describe(&entity.id, &meta),
+                        None => (
+                            "Unknown scene".to_owned(),
+                            vec!["scene metadata could not be read".to_owned()],
+                        ),
+                    };
+                    let (deployment, url) = deployment_lines(entity, &request.content_url);
+                    lines.extend(deployment);
+                    entity_url = Some(url);
+                    (title, lines)
+                }
+                None => (
+                    "Empty parcel".to_owned(),
+                    vec![format!("no scene at {},{}", parcel.x, parcel.y)],
+                ),
+            },
+            Err(e) => ("Scene info".to_owned(), vec![format!("lookup failed: {e}")]),
+        };
+        let now = chrono::Utc::now();
+        for event in live_events.live_at(&scene_parcels, now) {
+            lines.push(format!("event: {} ({})", event.name, event.when(now)));
+        }
+        match tile {
+            Ok(Some(tile)) => lines.extend(tile.lines()),
+            Ok(None) => (),
+            Err(e) => lines.push(format!("ownership lookup failed: {e}")),
+        }
+
+        match request.target {
+            SceneInfoTarget::Console => {
+                for line in std::iter::once(&title).chain(lines.iter()) {
+                    console.send(PrintConsoleLine {
+                        line: line.as_str().into(),
+                    });
+                }
+            }
+            SceneInfoTarget::Dialog => {
+                let mut buttons = vec![DuiButton::close_happy(tr!("button-ok"))];
+                if let Some(url) = entity_url {
+                    buttons.push(DuiButton::new_enabled(
+                        tr!("button-view-entity"),
+                        move || {
+                            let _ = opener::open(&url);
+                        },
+                    ));
+                }
+                commands
+                    .spawn_template(
+                        &dui,
+                        "text-dialog",
+                        DuiProps::new()
+                            .with_prop("title", title)
+                            .with_prop("body", lines.join("\n"))
+                            .with_prop("buttons", buttons),
+                    )
+                    .unwrap();
+            }
+        }
+        false
+    });
+}
@@ -0,0 +1,286 @@
+// renew the ephemeral login key before it expires, so signed requests don't start failing with
+// 401s part way through a session. the user is asked first, as the new key has to be signed by
+// their wallet.
+
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+};
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use chrono::{DateTime, Utc};
+use common::{
+    structs::{AppConfig, ChainLink, PreviousLogin},
+    tr,
+    util::TaskExt,
+};
+use ethers_core::types::Address;
+use ethers_signers::LocalWallet;
+use scene_runner::Toaster;
+use ui_core::button::DuiButton;
+use wallet::{
+    browser_auth::{
+        finish_remote_ephemeral_request, init_remote_ephemeral_request, RemoteEphemeralRequest,
+    },
+    session_store, Wallet,
+};
+
+// offer to renew when the key has less than this many days left
+const RENEWAL_WINDOW_DAYS: i64 = 3;
+// ask again even if put off, once it gets this close
+const RENEWAL_URGENT_HOURS: i64 = 12;
+
+pub struct SessionRenewalPlugin;
+
+impl Plugin for SessionRenewalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SessionRenewal>()
+            .add_systems(Update, (prompt_renewal, update_renewal).chain());
+    }
+}
+
+type SignedKey = (Address, LocalWallet, Vec<ChainLink>);
+
+enum RenewalTask {
+    // waiting for the browser request to be created, so the code can be shown
+    BrowserInit(Task<Result<RemoteEphemeralRequest, anyhow::Error>>),
+    Sign(Task<Result<SignedKey, anyhow::Error>>),
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+enum RenewalState {
+    #[default]
+    Idle,
+    Prompted,
+    Renewing,
+    Renewed(DateTime<Utc>),
+    Failed(String),
+}
+
+#[derive(Resource, Default)]
+pub struct SessionRenewal {
+    state: RenewalState,
+    // the key expiry we last asked about, and whether that was the urgent prompt
+    prompted: Option<(DateTime<Utc>, bool)>,
+    dialog: Option<Entity>,
+    task: Option<RenewalTask>,
+}
+
+impl SessionRenewal {
+    // expiry and renewal state for the sysinfo panel
+    pub fn status_text(&self, expiry: Option<DateTime<Utc>>) -> String {
+        let Some(expiry) = expiry else {
+            return "No ephemeral key".to_owned();
+        };
+        let remaining = expiry - Utc::now();
+        let expiry = if remaining.num_seconds() <= 0 {
+            "expired".to_owned()
+        } else if remaining.num_days() > 0 {
+            format!("expires in {}d", remaining.num_days())
+        } else {
+            format!("expires in {}h", remaining.num_hours())
+        };
+        let state = match &self.state {
+            RenewalState::Idle => String::default(),
+            RenewalState::Prompted => ", renewal offered".to_owned(),
+            RenewalState::Renewing => ", renewing".to_owned(),
+            RenewalState::Renewed(at) => format!(", renewed {}", at.format("%H:%M UTC")),
+            RenewalState::Failed(e) => format!(", renewal failed: {e}"),
+        };
+        format!("{expiry}{state}")
+    }
+
+    fn replace_dialog(&mut self, commands: &mut Commands, dialog: Option<Entity>) {
+        if let Some(commands) = self
+            .dialog
+            .take()
+            .and_then(|prev| commands.get_entity(prev))
+        {
+            commands.despawn_recursive();
+        }
+        self.dialog = dialog;
+    }
+}
+
+fn time_left(expiry: DateTime<Utc>) -> String {
+    let remaining = expiry - Utc::now();
+    if remaining.num_seconds() <= 0 {
+        "Your login has expired".to_owned()
+    } else if remaining.num_days() > 0 {
+        format!("Your login expires in {} days", remaining.num_days())
+    } else {
+        format!(
+            "Your login expires in {} hours",
+            remaining.num_hours().max(1)
+        )
+    }
+}
+
+fn prompt_renewal(
+    mut commands: Commands,
+    mut renewal: ResMut<SessionRenewal>,
+    wallet: Res<Wallet>,
+    dui: Res<DuiRegistry>,
+    time: Res<Time>,
+    mut last_check: Local<f32>,
+) {
+    // the expiry is only interesting to the nearest minute or so
+    if time.elapsed_seconds() - *last_check < 10.0 {
+        return;
+    }
+    *last_check = time.elapsed_seconds();
+
+    if renewal.task.is_some() || wallet.is_guest() {
+        return;
+    }
+    let Some(expiry) = wallet.ephemeral_expiry() else {
+        return;
+    };
+
+    let remaining = expiry - Utc::now();
+    if remaining > chrono::Duration::days(RENEWAL_WINDOW_DAYS) {
+        return;
+    }
+    let urgent = remaining < chrono::Duration::hours(RENEWAL_URGENT_HOURS);
+    if renewal
+        .prompted
+        .is_some_and(|(prompted, was_urgent)| prompted == expiry && (was_urgent || !urgent))
+    {
+        return;
+    }
+    renewal.prompted = Some((expiry, urgent));
+    renewal.state = RenewalState::Prompted;
+
+    let dialog = commands
+        .spawn_template(
+            &dui,
+            "text-dialog",
+            DuiProps::new()
+                .with_prop("title", "Renew Login".to_owned())
+                .with_prop(
+                    "body",
+                    format!(
+                        "{}. Renew it now to keep scenes and services signed in without \
+                         interruption. You will be asked to sign a new key with your wallet.",
+                        time_left(expiry)
+                    ),
+                )
+                .with_prop(
+                    "buttons",
+                    vec![
+                        DuiButton::new_enabled_and_close_happy("Renew", start_renewal),
+                        DuiButton::new_enabled_and_close_silent(
+                            "Later",
+                            |mut renewal: ResMut<SessionRenewal>| {
+                                renewal.state = RenewalState::Idle;
+                            },
+                        ),
+                    ],
+                ),
+        )
+        .unwrap()
+        .root;
+    renewal.replace_dialog(&mut commands, Some(dialog));
+}
+
+fn start_renewal(mut renewal: ResMut<SessionRenewal>, wallet: Res<Wallet>) {
+    let signs_remotely = wallet
+        .wallet_connect_session()
+        .is_some_and(|session| !session.is_expired())
+        || wallet.ledger_account().is_some();
+
+    renewal.state = RenewalState::Renewing;
+    renewal.task = Some(if signs_remotely {
+        RenewalTask::Sign(IoTaskPool::get().spawn(wallet.sign_ephemeral_async()))
+    } else {
+        RenewalTask::BrowserInit(IoTaskPool::get().spawn(init_remote_ephemeral_request()))
+    });
+}
+
+fn update_renewal(
+    mut commands: Commands,
+    mut renewal: ResMut<SessionRenewal>,
+    mut wallet: ResMut<Wallet>,
+    config: Res<AppConfig>,
+    dui: Res<DuiRegistry>,
+    mut toaster: Toaster,
+) {
+    let renewal = &mut *renewal;
+    let Some(task) = renewal.task.as_mut() else {
+        return;
+    };
+
+    let result = match task {
+        RenewalTask::BrowserInit(t) => match t.complete() {
+            Some(Ok(request)) => {
+                let dialog = commands
+                    .spawn_template(
+                        &dui,
+                        "cancel-login",
+                        DuiProps::new()
+                            .with_prop(
+                                "buttons",
+                                vec![DuiButton::new_enabled_and_close_silent(
+                                    "Cancel",
+                                    |mut renewal: ResMut<SessionRenewal>| {
+                                        renewal.task = None;
+                                        renewal.state =
+                                            RenewalState::Failed("cancelled".to_owned());
+                                    },
+                                )],
+                            )
+                            .with_prop("code", format!("{}", request.code.unwrap_or(-1))),
+                    )
+                    .unwrap()
+                    .root;
+                renewal.replace_dialog(&mut commands, Some(dialog));
+                renewal.task = Some(RenewalTask::Sign(IoTaskPool::get().spawn(async move {
+                    finish_remote_ephemeral_request(request)
+                        .await
+                        .map(|(root, local_wallet, auth, _)| (root, local_wallet, auth))
+                })));
+                return;
+            }
+            Some(Err(e)) => Err(e),
+            None => return,
+        },
+        RenewalTask::Sign(t) => match t.complete() {
+            Some(result) => result,
+            None => return,
+        },
+    };
+
+    renewal.task = None;
+    renewal.replace_dialog(&mut commands, None);
+
+    let result = result.and_then(|(root_address, local_wallet, auth)| {
+        let ephemeral_key = local_wallet.signer().to_bytes().to_vec();
+        wallet.renew(root_address, local_wallet, auth.clone())?;
+        Ok(PreviousLogin {
+            root_address,
+            ephemeral_key,
+            auth,
+            wallet_connect: wallet.wallet_connect_session(),
+            ledger: wallet.ledger_account(),
+        })
+    });
+
+    match result {
+        Ok(login) => {
+            if config.remember_login {
+                if let Err(e) = session_store::save_session(&login) {
+                    warn!("failed to save renewed login: {e}");
+                }
+            }
+            renewal.state = RenewalState::Renewed(Utc::now());
+            toaster.add_toast("session renewal", tr!("toast-session-renewed"));
+        }
+        Err(e) => {
+            warn!("failed to renew login: {e}");
+            renewal.state = RenewalState::Failed(e.to_string());
+            toaster.add_toast(
+                "session renewal",
+                tr!("toast-session-renew-failed", error = e),
+            );
+        }
+    }
+}
@@ -14,7 +14,11 @@ use bevy_console::ConsoleCommand;
 use bevy_dui::{DuiCommandsExt, DuiEntities, DuiProps, DuiRegistry};
 use common::{
     sets::{SceneSets, SetupSets},
-    structs::{AppConfig, CursorLocked, PrimaryUser, SettingsTab, ShowSettingsEvent, Version},
+    structs::{
+        AppConfig, CursorLocked, DynamicRenderScale, HudElement, PrimaryUser, SettingsTab,
+        ShowSettingsEvent, Version,
+    },
+    tr,
     util::ModifyComponentExt,
 };
 use comms::{
@@ -41,9 +45,16 @@ use ui_core::{
     ui_actions::{Click, EventCloneExt, On},
     BODY_TEXT_STYLE, TITLE_TEXT_STYLE,
 };
+use wallet::Wallet;
 use world_ui::TextShapeMaterial;
 
-use crate::map::MapTexture;
+use crate::{
+    hud_layout::HudElementNode,
+    map::MapTexture,
+    photo_mode::enter_photo_mode,
+    scene_info::{player_parcel, SceneInfoDialog},
+    session_renewal::SessionRenewal,
+};
 
 use super::SystemUiRoot;
 
@@ -64,6 +75,7 @@ impl Plugin for SysInfoPanelPlugin {
                 update_tracker,
                 update_map_visibilty,
                 update_crosshair,
+                update_dynamic_scale_indicator,
             )
                 .before(update_fontsize)
                 .after(SceneSets::PostLoop),
@@ -88,6 +100,9 @@ struct SysInfoContainer;
 #[derive(Component)]
 struct CrossHair;
 
+#[derive(Component)]
+struct DynamicScaleIndicator;
+
 pub(crate) fn setup(
     mut commands: Commands,
     root: Res<SystemUiRoot>,
@@ -142,6 +157,20 @@ pub(crate) fn setup(
             ),
             ..Default::default()
         });
+        commands.spawn((
+            TextBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::VMin(2.0),
+                    bottom: Val::VMin(5.0),
+                    display: Display::None,
+                    ..Default::default()
+                },
+                text: Text::from_section("", BODY_TEXT_STYLE.get().unwrap().clone()),
+                ..Default::default()
+            },
+            DynamicScaleIndicator,
+        ));
     });
 
     commands.entity(root.0).with_children(|commands| {
@@ -230,6 +259,7 @@ pub(crate) fn setup(
                         info_node("Broken Scenes :".to_owned());
                         info_node("Transports :".to_owned());
                         info_node("Players :".to_owned());
+                        info_node("Login :".to_owned());
                         info_node("Debug info :".to_owned());
                     });
             });
@@ -259,6 +289,7 @@ fn update_scene_load_state(
     containing_scene: ContainingScene,
     player: Query<(Entity, &GlobalTransform), With<PrimaryUser>>,
     debug_info: Res<DebugInfo>,
+    session: (Res<Wallet>, Res<SessionRenewal>),
 ) {
     let tick = (time.elapsed_seconds() * 10.0) as u32;
     if tick == *last_update {
@@ -346,6 +377,9 @@ fn update_scene_load_state(
         set_child(format!("{}", transports));
         set_child(format!("{}", players));
 
+        let (wallet, renewal) = session;
+        set_child(renewal.status_text(wallet.ephemeral_expiry()));
+
         let debug_info = debug_info
             .info
             .iter()
@@ -363,13 +397,49 @@ fn setup_minimap(
     preview: Res<PreviewMode>,
 ) {
     let components = commands
-        .spawn_template(&dui, "minimap", Default::default())
+        .spawn_template(
+            &dui,
+            "minimap",
+            DuiProps::new()
+                .with_prop("photo", On::<Click>::new(enter_photo_mode))
+                .with_prop(
+                    "info",
+                    On::<Click>::new(
+                        |player: Query<&GlobalTransform, With<PrimaryUser>>,
+                         mut dialog: EventWriter<SceneInfoDialog>| {
+                            if let Ok(transform) = player.get_single() {
+                                dialog.send(SceneInfoDialog(player_parcel(transform)));
+                            }
+                        },
+                    ),
+                )
+                .with_prop(
+                    "legend",
+                    On::<Click>::new(
+                        |map: Query<&DuiEntities, With<Minimap>>, mut style: Query<&mut Style>| {
+                            let Some(mut style) = map
+                                .get_single()
+                                .ok()
+                                .and_then(|nodes| style.get_mut(nodes.named("legend")).ok())
+                            else {
+                                return;
+                            };
+                            style.display = match style.display {
+                                Display::None => Display::Flex,
+                                _ => Display::None,
+                            };
+                        },
+                    ),
+                ),
+        )
         .unwrap();
     commands
         .entity(root.0)
         .insert_children(0, &[components.root]);
 
-    commands.entity(components.root).insert(Minimap);
+    commands
+        .entity(components.root)
+        .insert((Minimap, HudElementNode(HudElement::Minimap)));
     commands.entity(components.named("map-node")).insert((
         MapTexture {
             center: Default::default(),
@@ -386,38 +456,42 @@ fn setup_minimap(
             .spawn_template(
                 &dui,
                 "tracker",
-                DuiProps::new().with_prop(
-                    "toggle",
-                    On::<Click>::new(|mut trackers: Query<&mut Tracker>| {
-                        for mut tracker in trackers.iter_mut() {
-                            tracker.0 = !tracker.0;
-                        }
-                    })
-                ).with_prop(
-                    "inspect",
-                    On::<Click>::new(|
-                        mut reload: EventWriter<PreviewCommand>,
-                        mut test_data: ResMut<TestingData>,
-                        containing_scene: ContainingScene,
-                        scenes: Query<&RendererSceneContext>,
-                        player: Query<Entity, With<PrimaryUser>>,
-                        mut toaster: Toaster,
-                    | {
-                        let Ok(player) = player.get_single() else {
-                            return;
-                        };
-                        let Some(scene) = containing_scene.get_parcel_oow(player) else {
-                            return;
-                        };
-                        let Ok(scene) = scenes.get(scene) else {
-                            return;
-                        };
-                        test_data.inspect_hash = Some(scene.hash.clone());
-                        reload.send(PreviewCommand::ReloadScene { hash: scene.hash.clone() });
-                        toaster.add_toast("inspector", "Please open chrome and navigate to \"chrome://inspect\" to attach a debugger");
-                    })
-                )
-                .with_prop("inspect-enabled", cfg!(feature = "inspect")),
+                DuiProps::new()
+                    .with_prop(
+                        "toggle",
+                        On::<Click>::new(|mut trackers: Query<&mut Tracker>| {
+                            for mut tracker in trackers.iter_mut() {
+                                tracker.0 = !tracker.0;
+                            }
+                        }),
+                    )
+                    .with_prop(
+                        "inspect",
+                        On::<Click>::new(
+                            |mut reload: EventWriter<PreviewCommand>,
+                             mut test_data: ResMut<TestingData>,
+                             containing_scene: ContainingScene,
+                             scenes: Query<&RendererSceneContext>,
+                             player: Query<Entity, With<PrimaryUser>>,
+                             mut toaster: Toaster| {
+                                let Ok(player) = player.get_single() else {
+                                    return;
+                                };
+                                let Some(scene) = containing_scene.get_parcel_oow(player) else {
+                                    return;
+                                };
+                                let Ok(scene) = scenes.get(scene) else {
+                                    return;
+                                };
+                                test_data.inspect_hash = Some(scene.hash.clone());
+                                reload.send(PreviewCommand::ReloadScene {
+                                    hash: scene.hash.clone(),
+                                });
+                                toaster.add_toast("inspector", tr!("toast-inspector"));
+                            },
+                        ),
+                    )
+                    .with_prop("inspect-enabled", cfg!(feature = "inspect")),
             )
             .unwrap();
         commands.entity(tracker.root).insert(Tracker(true));
@@ -771,3 +845,24 @@ fn update_crosshair(
         }
     }
 }
+
+// shown while dynamic resolution is rendering below the window resolution
+fn update_dynamic_scale_indicator(
+    dynamic: Res<DynamicRenderScale>,
+    mut indicator: Query<(&mut Text, &mut Style), With<DynamicScaleIndicator>>,
+) {
+    if !dynamic.is_changed() {
+        return;
+    }
+
+    let Ok((mut text, mut style)) = indicator.get_single_mut() else {
+        return;
+    };
+
+    if dynamic.active && dynamic.scale < 1.0 {
+        style.display = Display::Flex;
+        text.sections[0].value = format!("Resolution: {:.0}%", dynamic.scale * 100.0);
+    } else {
+        style.display = Display::None;
+    }
+}
@@ -0,0 +1,114 @@
+// telemetry viewer: the recent analytics events exactly as they were sent, or would have been sent
+// with telemetry turned off, and the switch to turn it off. `/telemetry` toggles the panel and
+// `/telemetry on|off` sets the switch.
+
+use analytics::segment_system::SegmentMetricsEvents;
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_console::ConsoleCommand;
+use bevy_egui::{egui, EguiContext};
+use common::structs::AppConfig;
+use console::DoAddConsoleCommand;
+
+pub struct TelemetryPanelPlugin;
+
+impl Plugin for TelemetryPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TelemetryPanel>();
+        app.add_systems(
+            Update,
+            telemetry_panel.run_if(|panel: Res<TelemetryPanel>| panel.open),
+        );
+        app.add_console_command::<TelemetryCommand, _>(telemetry_command);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct TelemetryPanel {
+    pub open: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum TelemetryAction {
+    Show,
+    On,
+    Off,
+}
+
+/// show what telemetry records, or turn it on or off
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/telemetry")]
+struct TelemetryCommand {
+    action: Option<TelemetryAction>,
+}
+
+fn telemetry_command(
+    mut input: ConsoleCommand<TelemetryCommand>,
+    mut panel: ResMut<TelemetryPanel>,
+    mut config: ResMut<AppConfig>,
+) {
+    if let Some(Ok(TelemetryCommand { action })) = input.take() {
+        match action {
+            None => panel.open = !panel.open,
+            Some(TelemetryAction::Show) => panel.open = true,
+            Some(TelemetryAction::On) => config.telemetry = true,
+            Some(TelemetryAction::Off) => config.telemetry = false,
+        }
+        input.reply_ok(format!(
+            "telemetry is {}",
+            if config.telemetry { "on" } else { "off" }
+        ));
+    }
+}
+
+fn telemetry_panel(
+    mut egui_ctx: Query<&mut EguiContext, With<PrimaryWindow>>,
+    mut panel: ResMut<TelemetryPanel>,
+    mut config: ResMut<AppConfig>,
+    metrics: Res<SegmentMetricsEvents>,
+) {
+    let Ok(mut ctx) = egui_ctx.get_single_mut() else {
+        return;
+    };
+
+    let mut open = true;
+    let mut enabled = config.telemetry;
+    egui::Window::new("Telemetry")
+        .open(&mut open)
+        .default_size([560.0, 480.0])
+        .show(ctx.get_mut(), |ui| {
+            ui.checkbox(&mut enabled, "send telemetry");
+            ui.label(if enabled {
+                "events are sent every 10 seconds"
+            } else {
+                "nothing is sent, events below show what would have been"
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    let mut empty = true;
+                    for (ix, record) in metrics.history().enumerate() {
+                        empty = false;
+                        let status = if record.sent { "sent" } else { "not sent" };
+                        egui::CollapsingHeader::new(format!("{} ({status})", record.event))
+                            .id_source(("telemetry", ix, &record.json))
+                            .show(ui, |ui| {
+                                // pretty printed, but the same content as the payload
+                                let json = serde_json::from_str::<serde_json::Value>(&record.json)
+                                    .and_then(|value| serde_json::to_string_pretty(&value))
+                                    .unwrap_or_else(|_| record.json.clone());
+                                ui.monospace(json);
+                            });
+                    }
+                    if empty {
+                        ui.label("no events recorded yet");
+                    }
+                });
+        });
+
+    panel.open = open;
+    if enabled != config.telemetry {
+        config.telemetry = enabled;
+    }
+}
@@ -0,0 +1,188 @@
+// a preview card shown before teleporting from the map or a coordinate in chat, with the
+// destination's thumbnail, name, player count and content rating. details come from the places
+// api, falling back to the scene's own metadata for parcels that aren't listed.
+
+use std::path::PathBuf;
+
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+};
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use common::{rpc::RpcCall, structs::SceneMeta, tr, util::TaskExt};
+use ipfs::{ipfs_path::IpfsPath, ActiveEntitiesRequest, EntityDefinition, IpfsAssetServer};
+use ui_core::button::DuiButton;
+
+use crate::{
+    discover::{DiscoverPage, DiscoverPages},
+    map::places_task,
+};
+
+// scenes bigger than this are flagged as slow to load
+const LARGE_SCENE_PARCELS: usize = 25;
+
+pub struct TeleportPreviewPlugin;
+
+impl Plugin for TeleportPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TeleportPreview>();
+        app.init_resource::<PendingPreviews>();
+        app.add_systems(Update, (request_preview, show_preview));
+    }
+}
+
+// preview a parcel, teleporting there if the user confirms
+#[derive(Event, Clone, Copy)]
+pub struct TeleportPreview(pub IVec2);
+
+type PreviewResult = (
+    Result<DiscoverPages, anyhow::Error>,
+    Result<Vec<EntityDefinition>, anyhow::Error>,
+);
+
+struct PreviewRequest {
+    parcel: IVec2,
+    // for scene thumbnails
+    contents_endpoint: Option<String>,
+    task: Task<PreviewResult>,
+}
+
+#[derive(Resource, Default)]
+struct PendingPreviews(Vec<PreviewRequest>);
+
+// the first `x,y` coordinate in a chat message
+pub fn find_parcel(message: &str) -> Option<IVec2> {
+    message.split_whitespace().find_map(|word| {
+        let word = word.trim_matches(|c: char| !(c.is_ascii_digit() || c == '-'));
+        let (x, y) = word.split_once(',')?;
+        Some(IVec2::new(x.parse().ok()?, y.parse().ok()?))
+    })
+}
+
+fn request_preview(
+    mut events: EventReader<TeleportPreview>,
+    mut pending: ResMut<PendingPreviews>,
+    ipfas: IpfsAssetServer,
+) {
+    for TeleportPreview(parcel) in events.read() {
+        let parcel = *parcel;
+        let place = places_task(format!(
+            "https://places.decentraland.org/api/places?positions={},{}",
+            parcel.x, parcel.y
+        ));
+        let scene = ipfas.ipfs().active_entities(
+            ActiveEntitiesRequest::Pointers(vec![format!("{},{}", parcel.x, parcel.y)]),
+            None,
+        );
+        pending.0.push(PreviewRequest {
+            parcel,
+            contents_endpoint: ipfas.ipfs().contents_endpoint(),
+            task: IoTaskPool::get().spawn(async move { (place.await, scene.await) }),
+        });
+    }
+}
+
+fn show_preview(
+    mut commands: Commands,
+    mut pending: ResMut<PendingPreviews>,
+    dui: Res<DuiRegistry>,
+    asset_server: Res<AssetServer>,
+) {
+    pending.0.retain_mut(|request| {
+        let Some((place, scene)) = request.task.complete() else {
+            return true;
+        };
+        let parcel = request.parcel;
+
+        let place = place
+            .map_err(|e| warn!("place lookup failed: {e}"))
+            .ok()
+            .and_then(|pages| pages.data.into_iter().next());
+        let scene = scene
+            .map_err(|e| warn!("scene lookup failed: {e}"))
+            .ok()
+            .and_then(|entities| entities.into_iter().next());
+        let meta = scene
+            .as_ref()
+            .and_then(|entity| serde_json::from_value::<SceneMeta>(entity.metadata.clone()?).ok());
+
+        let scene_thumbnail = || {
+            let path = meta.as_ref()?.display.as_ref()?.navmap_thumbnail.clone()?;
+            if path.starts_with("http") {
+                Some(path)
+            } else {
+                let hash = scene.as_ref()?.content.hash(&path)?;
+                Some(format!("{}{hash}", request.contents_endpoint.as_ref()?))
+            }
+        };
+        let scene_title = || meta.as_ref()?.display.as_ref()?.title.clone();
+
+        let (title, image, active, rating) = match &place {
+            Some(place) => (
+                place.title.clone(),
+                place.image.clone(),
+                format!("{}", place.user_count),
+                place.content_rating.clone(),
+            ),
+            None => (
+                scene_title().unwrap_or_else(|| format!("({}, {})", parcel.x, parcel.y)),
+                scene_thumbnail().unwrap_or_else(|| DiscoverPage::dummy(parcel).image),
+                "-".to_owned(),
+                meta.as_ref()
+                    .and_then(|meta| meta.policy.as_ref()?.content_rating.clone())
+                    .unwrap_or_else(|| "-".to_owned()),
+            ),
+        };
+        let parcels = meta.as_ref().map(|meta| meta.scene.parcels.len());
+
+        let mut notes = Vec::default();
+        if scene.is_none() {
+            notes.push("There is no scene here.");
+        }
+        if place.as_ref().is_some_and(|place| place.user_count == 0) {
+            notes.push("Nobody is here right now.");
+        }
+        if parcels.is_some_and(|parcels| parcels > LARGE_SCENE_PARCELS) {
+            notes.push("This is a large scene and may take a while to load.");
+        }
+
+        let image_path = IpfsPath::new_from_url(&image, "image");
+        let h_image = asset_server.load::<Image>(PathBuf::from(&image_path));
+
+        commands
+            .spawn_template(
+                &dui,
+                "teleport-preview",
+                DuiProps::new()
+                    .with_prop("title", title)
+                    .with_prop("img", h_image)
+                    .with_prop("location", format!("{},{}", parcel.x, parcel.y))
+                    .with_prop("rating", rating)
+                    .with_prop("active", active)
+                    .with_prop(
+                        "parcels",
+                        parcels.map_or_else(|| "-".to_owned(), |parcels| format!("{parcels}")),
+                    )
+                    .with_prop("notes", notes.join("\n"))
+                    .with_prop(
+                        "buttons",
+                        vec![
+                            DuiButton::new_enabled_and_close_happy(
+                                tr!("button-jump-in"),
+                                move |mut rpc: EventWriter<RpcCall>| {
+                                    // user initiated, so no scene permission is needed
+                                    rpc.send(RpcCall::TeleportPlayer {
+                                        scene: None,
+                                        to: parcel,
+                                        response: Default::default(),
+                                    });
+                                },
+                            ),
+                            DuiButton::close_sad(tr!("button-cancel")),
+                        ],
+                    ),
+            )
+            .unwrap();
+        false
+    });
+}
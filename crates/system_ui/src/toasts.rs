@@ -1,7 +1,10 @@
 use bevy::{prelude::*, utils::HashMap};
 use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use common::structs::HudElement;
 use scene_runner::Toasts;
 
+use crate::hud_layout::HudElementNode;
+
 pub struct ToastsPlugin;
 
 impl Plugin for ToastsPlugin {
@@ -14,11 +17,15 @@ impl Plugin for ToastsPlugin {
 #[derive(Component)]
 pub struct ToastMarker;
 fn setup(mut commands: Commands, dui: Res<DuiRegistry>) {
-    let inner = commands
+    let components = commands
         .spawn_template(&dui, "toaster", DuiProps::default())
-        .unwrap()
-        .named("inner");
-    commands.entity(inner).insert(ToastMarker);
+        .unwrap();
+    commands
+        .entity(components.root)
+        .insert(HudElementNode(HudElement::Toasts));
+    commands
+        .entity(components.named("inner"))
+        .insert(ToastMarker);
 }
 
 fn update_toasts(
@@ -2,7 +2,7 @@ use std::collections::{btree_map::Entry, BTreeMap};
 
 use bevy::prelude::*;
 use common::structs::{ToolTips, TooltipSource};
-use ui_core::{ui_builder::SpawnSpacer, HOVER_TEXT_STYLE};
+use ui_core::{gamepad_nav::GamepadFocus, ui_builder::SpawnSpacer, HOVER_TEXT_STYLE};
 
 #[derive(Component)]
 pub struct ToolTipNode;
@@ -24,11 +24,21 @@ pub fn update_tooltip(
     cur_tips: Query<Entity, With<ToolTipNode>>,
     mut active_tips: Local<BTreeMap<TooltipSource, (Vec<(String, bool)>, f32)>>,
     time: Res<Time>,
+    focus: Res<GamepadFocus>,
+    nodes: Query<(&Node, &GlobalTransform)>,
 ) {
     let Ok(window) = windows.get_single() else {
         return;
     };
-    let cursor_position = if window.cursor.grab_mode == bevy::window::CursorGrabMode::Locked {
+    // tips for the gamepad/keyboard focused node are placed beside the node rather than the cursor
+    let focus_rect = focus
+        .entity
+        .filter(|e| focus.active && tips.0.contains_key(&TooltipSource::Entity(*e)))
+        .and_then(|e| nodes.get(e).ok())
+        .map(|(node, transform)| node.logical_rect(transform));
+    let cursor_position = if let Some(rect) = focus_rect {
+        Vec2::new(rect.center().x, rect.min.y)
+    } else if window.cursor.grab_mode == bevy::window::CursorGrabMode::Locked {
         // if pointer locked, just middle
         Vec2::new(window.width(), window.height()) / 2.0
     } else {
@@ -79,13 +89,14 @@ pub fn update_tooltip(
 
     let mut y_offset = 0.0;
 
+    let half_width = focus_rect.map_or(0.0, |rect| rect.width() / 2.0);
     let (left, right) = if cursor_position.x > window.width() / 2.0 {
         (
             Val::Auto,
-            Val::Px(window.width() - cursor_position.x + 20.0),
+            Val::Px(window.width() - cursor_position.x + half_width + 20.0),
         )
     } else {
-        (Val::Px(cursor_position.x + 20.0), Val::Auto)
+        (Val::Px(cursor_position.x + half_width + 20.0), Val::Auto)
     };
 
     for (content, vis) in active_tips.values() {
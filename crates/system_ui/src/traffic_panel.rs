@@ -0,0 +1,263 @@
+// network traffic debug panel: bytes and messages per second for each transport, peer message
+// type and scene network api, with a rolling graph of total throughput. detailed counters are
+// only recorded while the panel is open. toggle with `/traffic`.
+
+use std::{collections::VecDeque, sync::atomic::Ordering};
+
+use bevy::{prelude::*, utils::HashMap, window::PrimaryWindow};
+use bevy_console::ConsoleCommand;
+use bevy_egui::{egui, EguiContext};
+use common::traffic::{
+    set_traffic_detail, traffic_detail, traffic_totals, TrafficCount, TrafficGroup,
+};
+use comms::{NETWORK_BYTES_RECEIVED, NETWORK_BYTES_SENT};
+use console::DoAddConsoleCommand;
+use scene_runner::renderer_context::RendererSceneContext;
+
+use crate::perf_hud::format_bytes;
+
+const SAMPLE_INTERVAL_SECS: f32 = 1.0;
+// seconds of history in the graph
+const GRAPH_SAMPLES: usize = 60;
+const GRAPH_HEIGHT: f32 = 80.0;
+
+pub struct TrafficPanelPlugin;
+
+impl Plugin for TrafficPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TrafficPanel>();
+        app.add_systems(Update, traffic_panel);
+        app.add_console_command::<TrafficCommand, _>(set_traffic_panel);
+    }
+}
+
+struct TrafficRow {
+    group: TrafficGroup,
+    name: String,
+    // per second over the last sample
+    rate: TrafficCount,
+    total: TrafficCount,
+}
+
+#[derive(Resource, Default)]
+pub struct TrafficPanel {
+    pub open: bool,
+    last_sample: Option<f32>,
+    previous: HashMap<(TrafficGroup, String), TrafficCount>,
+    rows: Vec<TrafficRow>,
+    // total bytes (sent, received) at the last sample, and bytes per second history
+    previous_bytes: (u64, u64),
+    graph: VecDeque<(f32, f32)>,
+}
+
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/traffic")]
+struct TrafficCommand {
+    on: Option<bool>,
+}
+
+fn set_traffic_panel(mut input: ConsoleCommand<TrafficCommand>, mut panel: ResMut<TrafficPanel>) {
+    if let Some(Ok(command)) = input.take() {
+        panel.open = command.on.unwrap_or(!panel.open);
+        input.reply_ok("");
+    }
+}
+
+fn traffic_panel(
+    mut egui_ctx: Query<&mut EguiContext, With<PrimaryWindow>>,
+    mut panel: ResMut<TrafficPanel>,
+    scenes: Query<&RendererSceneContext>,
+    time: Res<Time<Real>>,
+) {
+    if traffic_detail() != panel.open {
+        set_traffic_detail(panel.open);
+    }
+    if !panel.open {
+        panel.last_sample = None;
+        panel.graph.clear();
+        return;
+    }
+
+    let now = time.elapsed_seconds();
+    let sent = NETWORK_BYTES_SENT.load(Ordering::Relaxed);
+    let received = NETWORK_BYTES_RECEIVED.load(Ordering::Relaxed);
+    match panel.last_sample {
+        None => {
+            panel.last_sample = Some(now);
+            panel.previous_bytes = (sent, received);
+        }
+        Some(last) if now - last >= SAMPLE_INTERVAL_SECS => {
+            sample(&mut panel, now - last, sent, received);
+            panel.last_sample = Some(now);
+        }
+        _ => (),
+    }
+
+    let Ok(mut ctx) = egui_ctx.get_single_mut() else {
+        return;
+    };
+    let titles = scenes
+        .iter()
+        .map(|context| (context.hash.as_str(), context.title.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    let mut open = true;
+    egui::Window::new("Network Traffic")
+        .open(&mut open)
+        .default_size([520.0, 480.0])
+        .show(ctx.get_mut(), |ui| {
+            graph(ui, &panel.graph);
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                table(
+                    ui,
+                    "transports",
+                    panel
+                        .rows
+                        .iter()
+                        .filter(|row| row.group == TrafficGroup::Transport),
+                    |row| row.name.clone(),
+                );
+                table(
+                    ui,
+                    "message types",
+                    panel
+                        .rows
+                        .iter()
+                        .filter(|row| row.group == TrafficGroup::Message),
+                    |row| row.name.clone(),
+                );
+                table(
+                    ui,
+                    "scenes",
+                    panel
+                        .rows
+                        .iter()
+                        .filter(|row| matches!(row.group, TrafficGroup::Scene(_))),
+                    |row| {
+                        let TrafficGroup::Scene(api) = row.group else {
+                            unreachable!()
+                        };
+                        let scene = titles.get(row.name.as_str()).copied().unwrap_or(&row.name);
+                        format!("{scene} ({api})")
+                    },
+                );
+            });
+        });
+    panel.open = open;
+}
+
+fn sample(panel: &mut TrafficPanel, elapsed: f32, sent: u64, received: u64) {
+    let per_second = |now: u64, before: u64| ((now - before) as f32 / elapsed).round() as u64;
+
+    let (prev_sent, prev_received) = panel.previous_bytes;
+    panel.graph.push_back((
+        (received - prev_received) as f32 / elapsed,
+        (sent - prev_sent) as f32 / elapsed,
+    ));
+    if panel.graph.len() > GRAPH_SAMPLES {
+        panel.graph.pop_front();
+    }
+    panel.previous_bytes = (sent, received);
+
+    let totals = traffic_totals();
+    panel.rows = totals
+        .iter()
+        .map(|(group, name, total)| {
+            let before = panel
+                .previous
+                .get(&(*group, name.clone()))
+                .copied()
+                .unwrap_or_default();
+            TrafficRow {
+                group: *group,
+                name: name.clone(),
+                rate: TrafficCount {
+                    bytes_in: per_second(total.bytes_in, before.bytes_in),
+                    bytes_out: per_second(total.bytes_out, before.bytes_out),
+                    messages_in: per_second(total.messages_in, before.messages_in),
+                    messages_out: per_second(total.messages_out, before.messages_out),
+                },
+                total: *total,
+            }
+        })
+        .collect();
+    panel.previous = totals
+        .into_iter()
+        .map(|(group, name, total)| ((group, name), total))
+        .collect();
+}
+
+// received and sent bytes per second
+fn graph(ui: &mut egui::Ui, samples: &VecDeque<(f32, f32)>) {
+    let received = egui::Color32::from_rgb(100, 200, 100);
+    let sent = egui::Color32::from_rgb(230, 160, 60);
+    let max = samples
+        .iter()
+        .map(|(down, up)| down.max(*up))
+        .fold(1024.0, f32::max);
+
+    ui.horizontal(|ui| {
+        ui.colored_label(received, "down");
+        ui.colored_label(sent, "up");
+        ui.label(format!("(scale {}/s)", format_bytes(max as f64)));
+    });
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), GRAPH_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(120));
+    let step = rect.width() / (GRAPH_SAMPLES - 1) as f32;
+    let point = |ix: usize, value: f32| {
+        egui::pos2(
+            rect.left() + ix as f32 * step,
+            rect.bottom() - value / max * rect.height(),
+        )
+    };
+    let offset = GRAPH_SAMPLES - samples.len();
+    for (color, up) in [(received, false), (sent, true)] {
+        let points = samples
+            .iter()
+            .enumerate()
+            .map(|(ix, (down_bytes, up_bytes))| {
+                point(ix + offset, if up { *up_bytes } else { *down_bytes })
+            })
+            .collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+    }
+}
+
+fn table<'a>(
+    ui: &mut egui::Ui,
+    heading: &str,
+    rows: impl Iterator<Item = &'a TrafficRow>,
+    label: impl Fn(&TrafficRow) -> String,
+) {
+    egui::CollapsingHeader::new(heading)
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::Grid::new(heading).striped(true).show(ui, |ui| {
+                for title in [
+                    "",
+                    "down/s",
+                    "up/s",
+                    "msgs in/s",
+                    "msgs out/s",
+                    "msgs total",
+                ] {
+                    ui.strong(title);
+                }
+                ui.end_row();
+
+                for row in rows {
+                    ui.label(label(row));
+                    ui.label(format_bytes(row.rate.bytes_in as f64));
+                    ui.label(format_bytes(row.rate.bytes_out as f64));
+                    ui.label(row.rate.messages_in.to_string());
+                    ui.label(row.rate.messages_out.to_string());
+                    ui.label((row.total.messages_in + row.total.messages_out).to_string());
+                    ui.end_row();
+                }
+            });
+        });
+}
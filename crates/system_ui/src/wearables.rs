@@ -18,10 +18,11 @@ use bevy_dui::{
 use collectibles::{
     base_wearables::{self, base_wearable_urns, default_bodyshape_instance},
     wearables::{Wearable, WearableCategory, WearableCollections, WearableInstance},
-    CollectibleData, CollectibleError, CollectibleManager,
+    CollectibleData, CollectibleError, CollectibleManager, Collectibles, PurchasedCollectible,
 };
 use common::{
-    structs::{PrimaryUser, SettingsTab, PROFILE_UI_RENDERLAYER},
+    structs::{PrimaryUser, SettingsTab, ShowPurchaseEvent, PROFILE_UI_RENDERLAYER},
+    tr,
     util::{TaskExt, TryPushChildrenEx},
 };
 use comms::profile::CurrentUserProfile;
@@ -35,7 +36,9 @@ use ui_core::{
     combo_box::ComboBox,
     interact_style::{InteractStyle, InteractStyles},
     text_entry::TextEntryValue,
+    theme::UiTheme,
     toggle::Toggled,
+    tooltip::Tooltip,
     ui_actions::{Click, DataChanged, Enabled, On, UiCaller},
 };
 
@@ -139,6 +142,36 @@ pub struct OwnedWearableData {
     pub individual_data: Vec<IndividualData>,
 }
 
+impl OwnedWearableData {
+    // add purchases the lambdas haven't indexed yet
+    fn merge_purchased(owned: &mut Vec<Self>, purchased: &[PurchasedCollectible]) {
+        for purchase in purchased {
+            let individual = IndividualData {
+                transferred_at: purchase.purchased_at.clone(),
+                token_id: purchase.token_id.clone(),
+            };
+            match owned.iter_mut().find(|o| o.urn == purchase.urn) {
+                Some(existing) => {
+                    if !existing
+                        .individual_data
+                        .iter()
+                        .any(|d| d.token_id == individual.token_id)
+                    {
+                        existing.individual_data.push(individual);
+                    }
+                }
+                None => owned.push(Self {
+                    urn: purchase.urn.clone(),
+                    name: purchase.name.clone(),
+                    category: purchase.category.clone(),
+                    rarity: purchase.rarity.clone(),
+                    individual_data: vec![individual],
+                }),
+            }
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct OwnedWearableServerResponse {
     elements: Vec<OwnedWearableData>,
@@ -470,8 +503,9 @@ fn only_collectibles(
 }
 
 #[derive(Event, Default)]
-struct GetOwnedWearables;
+pub(crate) struct GetOwnedWearables;
 
+#[allow(clippy::too_many_arguments)]
 fn get_owned_wearables(
     mut e: EventReader<GetOwnedWearables>,
     mut task: Local<Option<Task<Result<OwnedWearableServerResponse, anyhow::Error>>>>,
@@ -480,6 +514,7 @@ fn get_owned_wearables(
     current_profile: Res<CurrentUserProfile>,
     collections: Res<WearableCollections>,
     mut collections_box: Query<(&mut ComboBox, &Name)>,
+    collectibles: Res<Collectibles<Wearable>>,
 ) {
     let ev = e.read().last().is_some();
 
@@ -489,6 +524,10 @@ fn get_owned_wearables(
                 if let Ok(mut settings) = q.get_single_mut() {
                     debug!("wearable task ok");
                     settings.owned_wearables = wearable_data.elements;
+                    OwnedWearableData::merge_purchased(
+                        &mut settings.owned_wearables,
+                        collectibles.purchased(),
+                    );
 
                     let owned = settings
                         .owned_wearables
@@ -653,17 +692,8 @@ impl ColorHexEx for Color {
 }
 
 impl Rarity {
-    pub fn color(&self) -> Color {
-        match self {
-            Rarity::Free => Color::srgb(0.9, 0.9, 0.9),
-            Rarity::Common => Color::srgb(0.7, 0.7, 0.7),
-            Rarity::Uncommon => Color::srgb(1.0, 0.8, 0.4),
-            Rarity::Rare => Color::srgb(0.6, 1.0, 0.6),
-            Rarity::Epic => Color::srgb(0.6, 0.6, 1.0),
-            Rarity::Legendary => Color::srgb(0.8, 0.4, 0.8),
-            Rarity::Mythic => Color::srgb(1.0, 0.6, 1.0),
-            Rarity::Unique => Color::srgb(1.0, 1.0, 0.4),
-        }
+    pub fn color(&self, theme: &UiTheme) -> Color {
+        theme.rarity[*self as usize]
     }
 }
 
@@ -883,6 +913,7 @@ fn update_wearable_item(
     mut wearable_loader: CollectibleManager<Wearable>,
     ipfas: IpfsAssetServer,
     dui: Res<DuiRegistry>,
+    theme: Res<UiTheme>,
     settings: Query<(Entity, &WearablesSettings)>,
     walker: DuiWalker,
 ) {
@@ -919,7 +950,24 @@ fn update_wearable_item(
                                 continue;
                             };
 
-                            commands.entity(button_bg).try_insert(Enabled(fits));
+                            let mut info = vec![
+                                data.name.clone(),
+                                tr!(
+                                    "wearable-info",
+                                    rarity = format!("{:?}", entry.rarity),
+                                    category = entry.category.slot
+                                ),
+                            ];
+                            if !data.description.is_empty() {
+                                info.push(data.description.clone());
+                            }
+                            if !fits {
+                                info.push(tr!("wearable-incompatible"));
+                            }
+
+                            commands
+                                .entity(button_bg)
+                                .try_insert((Enabled(fits), Tooltip(info.join("\n"))));
                         }
                         Err(CollectibleError::Loading) => (),
                         other => {
@@ -938,7 +986,7 @@ fn update_wearable_item(
                                                 .asset_server()
                                                 .load::<Image>("images/backback/empty.png"),
                                         )
-                                        .with_prop("rarity-color", entry.rarity.color()),
+                                        .with_prop("rarity-color", entry.rarity.color(&theme)),
                                 )
                                 .unwrap();
                         }
@@ -955,7 +1003,7 @@ fn update_wearable_item(
                             .contains(settings.body_shape.base().as_str());
 
                     let (image_color, rarity_color) = if fits {
-                        (Color::WHITE, entry.rarity.color())
+                        (Color::WHITE, entry.rarity.color(&theme))
                     } else {
                         (Color::BLACK, Color::Srgba(css::DARK_GRAY))
                     };
@@ -1015,6 +1063,7 @@ fn update_selected_item(
     settings: Query<(Entity, Ref<WearablesSettings>, &DuiEntities, &SelectItem)>,
     avatar: Query<&AvatarShape, With<SettingsDialog>>,
     dui: Res<DuiRegistry>,
+    theme: Res<UiTheme>,
     mut wearable_loader: CollectibleManager<Wearable>,
     mut retry: Local<bool>,
 ) {
@@ -1184,6 +1233,13 @@ fn update_selected_item(
             _ => ("none".to_owned(), default()),
         };
 
+        // collection items can be bought again from the marketplace
+        let buy_enabled = sel.instance.base().collection_type() == Some("collections-v2");
+        let buy_urn = sel.instance.base().to_string();
+        let buy_action = On::<Click>::new(move |mut e: EventWriter<ShowPurchaseEvent>| {
+            e.send(ShowPurchaseEvent(buy_urn.clone()));
+        });
+
         debug!("display : {picker_display}");
         let color_picker_changed = On::<DataChanged>::new(
             move |caller: Res<UiCaller>,
@@ -1225,13 +1281,15 @@ fn update_selected_item(
                 &dui,
                 "wearable-selection",
                 DuiProps::new()
-                    .with_prop("rarity-color", sel.rarity.color())
+                    .with_prop("rarity-color", sel.rarity.color(&theme))
                     .with_prop("selection-image", data_ref.thumbnail.clone())
                     .with_prop("title", data_ref.name.clone())
                     .with_prop("body", data_ref.description.clone())
                     .with_prop("label", label.to_owned())
                     .with_prop("enabled", enabled)
                     .with_prop("onclick", equip_action)
+                    .with_prop("buy", buy_action)
+                    .with_prop("buy-enabled", buy_enabled)
                     .with_prop("color-picker-display", picker_display)
                     .with_prop("color", color)
                     .with_prop("color-changed", color_picker_changed),
@@ -0,0 +1,310 @@
+// developer panel browsing the renderer-side bevy world: entity hierarchy, components and
+// resources, filtered by scene root. scene entities are labelled with their scene entity id and
+// entities owned by a scene entity link back to it. toggle with `/world_inspector`.
+
+use bevy::{prelude::*, utils::get_short_name, window::PrimaryWindow};
+use bevy_console::ConsoleCommand;
+use bevy_egui::{egui, EguiContext};
+use console::DoAddConsoleCommand;
+use scene_runner::{renderer_context::RendererSceneContext, ContainerEntity, SceneEntity};
+
+// the world holds many thousands of entities, don't list them all
+const MAX_ROWS: usize = 500;
+
+pub struct WorldInspectorPlugin;
+
+impl Plugin for WorldInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldInspector>();
+        app.add_systems(
+            Update,
+            world_inspector.run_if(|inspector: Res<WorldInspector>| inspector.open),
+        );
+        app.add_console_command::<WorldInspectorCommand, _>(set_world_inspector);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct WorldInspector {
+    pub open: bool,
+    // scene root to browse, or all top level entities
+    root: Option<Entity>,
+    selected: Option<Entity>,
+    // matched against entity labels and component names
+    filter: String,
+}
+
+#[derive(clap::Parser, ConsoleCommand)]
+#[command(name = "/world_inspector")]
+struct WorldInspectorCommand {
+    on: Option<bool>,
+}
+
+fn set_world_inspector(
+    mut input: ConsoleCommand<WorldInspectorCommand>,
+    mut inspector: ResMut<WorldInspector>,
+) {
+    if let Some(Ok(command)) = input.take() {
+        inspector.open = command.on.unwrap_or(!inspector.open);
+        input.reply_ok("");
+    }
+}
+
+fn world_inspector(world: &mut World) {
+    let Ok(ctx) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+        .map(|mut ctx| ctx.get_mut().clone())
+    else {
+        return;
+    };
+
+    let mut scenes = world
+        .query::<(Entity, &RendererSceneContext)>()
+        .iter(world)
+        .map(|(root, context)| {
+            (
+                root,
+                format!("{} ({},{})", context.title, context.base.x, context.base.y),
+            )
+        })
+        .collect::<Vec<_>>();
+    scenes.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+    world.resource_scope(|world, mut inspector: Mut<WorldInspector>| {
+        let mut open = true;
+        egui::Window::new("World Inspector")
+            .open(&mut open)
+            .default_size([420.0, 640.0])
+            .show(&ctx, |ui| inspector_ui(ui, world, &mut inspector, &scenes));
+        inspector.open = open;
+    });
+}
+
+fn inspector_ui(
+    ui: &mut egui::Ui,
+    world: &World,
+    inspector: &mut WorldInspector,
+    scenes: &[(Entity, String)],
+) {
+    if inspector
+        .root
+        .is_some_and(|root| world.get_entity(root).is_none())
+    {
+        inspector.root = None;
+    }
+    if inspector
+        .selected
+        .is_some_and(|ent| world.get_entity(ent).is_none())
+    {
+        inspector.selected = None;
+    }
+
+    let scene_label = inspector
+        .root
+        .and_then(|root| scenes.iter().find(|(ent, _)| *ent == root))
+        .map_or("all entities", |(_, label)| label.as_str());
+    egui::ComboBox::from_label("scene")
+        .selected_text(scene_label)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut inspector.root, None, "all entities");
+            for (root, label) in scenes {
+                ui.selectable_value(&mut inspector.root, Some(*root), label);
+            }
+        });
+    ui.horizontal(|ui| {
+        ui.label("filter");
+        ui.text_edit_singleline(&mut inspector.filter);
+    });
+
+    let roots = match inspector.root {
+        Some(root) => vec![root],
+        None => world
+            .iter_entities()
+            .filter(|ent| !ent.contains::<Parent>())
+            .map(|ent| ent.id())
+            .collect(),
+    };
+
+    ui.separator();
+    egui::ScrollArea::vertical()
+        .id_source("world-inspector-entities")
+        .max_height(ui.available_height() * 0.5)
+        .auto_shrink([false, true])
+        .show(ui, |ui| {
+            let mut rows = 0;
+            if inspector.filter.is_empty() {
+                for root in roots {
+                    entity_tree(ui, world, root, &mut inspector.selected, &mut rows);
+                }
+            } else {
+                // flat list of matches
+                let filter = inspector.filter.to_lowercase();
+                for ent in roots.into_iter().flat_map(|root| descendants(world, root)) {
+                    if rows >= MAX_ROWS {
+                        break;
+                    }
+                    if matches_filter(world, ent, &filter) {
+                        rows += 1;
+                        entity_row(ui, world, ent, &mut inspector.selected);
+                    }
+                }
+            }
+            if rows >= MAX_ROWS {
+                ui.label(format!("(only the first {MAX_ROWS} are listed)"));
+            }
+        });
+
+    ui.separator();
+    egui::ScrollArea::vertical()
+        .id_source("world-inspector-components")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            match inspector.selected {
+                Some(ent) => components_ui(ui, world, ent, &mut inspector.selected),
+                None => {
+                    ui.label("select an entity to show its components");
+                }
+            }
+            ui.separator();
+            egui::CollapsingHeader::new("resources").show(ui, |ui| resources_ui(ui, world));
+        });
+}
+
+// bevy entity, scene entity id and name
+fn entity_label(world: &World, ent: Entity) -> String {
+    let mut label = format!("{ent:?}");
+    if let Some(scene_entity) = world.get::<SceneEntity>(ent) {
+        label = format!("{} {label}", scene_entity.id);
+    }
+    if let Some(name) = world.get::<Name>(ent) {
+        label.push_str(&format!(" \"{name}\""));
+    }
+    label
+}
+
+fn entity_row(ui: &mut egui::Ui, world: &World, ent: Entity, selected: &mut Option<Entity>) {
+    if ui
+        .selectable_label(*selected == Some(ent), entity_label(world, ent))
+        .clicked()
+    {
+        *selected = Some(ent);
+    }
+}
+
+fn entity_tree(
+    ui: &mut egui::Ui,
+    world: &World,
+    ent: Entity,
+    selected: &mut Option<Entity>,
+    rows: &mut usize,
+) {
+    if *rows >= MAX_ROWS {
+        return;
+    }
+    *rows += 1;
+
+    match world.get::<Children>(ent) {
+        Some(children) if !children.is_empty() => {
+            egui::collapsing_header::CollapsingState::load_with_default_open(
+                ui.ctx(),
+                ui.make_persistent_id(ent),
+                false,
+            )
+            .show_header(ui, |ui| entity_row(ui, world, ent, selected))
+            .body(|ui| {
+                for child in children.iter() {
+                    entity_tree(ui, world, *child, selected, rows);
+                }
+            });
+        }
+        _ => entity_row(ui, world, ent, selected),
+    }
+}
+
+fn descendants(world: &World, root: Entity) -> Vec<Entity> {
+    let mut entities = vec![root];
+    let mut ix = 0;
+    while let Some(ent) = entities.get(ix).copied() {
+        if let Some(children) = world.get::<Children>(ent) {
+            entities.extend(children.iter());
+        }
+        ix += 1;
+    }
+    entities
+}
+
+fn matches_filter(world: &World, ent: Entity, filter: &str) -> bool {
+    entity_label(world, ent).to_lowercase().contains(filter)
+        || world
+            .inspect_entity(ent)
+            .into_iter()
+            .any(|info| get_short_name(info.name()).to_lowercase().contains(filter))
+}
+
+fn components_ui(ui: &mut egui::Ui, world: &World, ent: Entity, selected: &mut Option<Entity>) {
+    ui.strong(entity_label(world, ent));
+
+    // map between scene and bevy entities
+    if let Some(container) = world.get::<ContainerEntity>(ent) {
+        ui.horizontal(|ui| {
+            ui.label(format!("scene entity {}", container.container_id));
+            if container.container != ent && ui.link(format!("{:?}", container.container)).clicked()
+            {
+                *selected = Some(container.container);
+            }
+        });
+    }
+    if let Some(parent) = world.get::<Parent>(ent) {
+        ui.horizontal(|ui| {
+            ui.label("parent");
+            if ui.link(entity_label(world, parent.get())).clicked() {
+                *selected = Some(parent.get());
+            }
+        });
+    }
+
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut components = world.inspect_entity(ent).into_iter().collect::<Vec<_>>();
+    components.sort_by_key(|info| get_short_name(info.name()));
+    for info in components {
+        // only reflected components can show their values
+        let value = info
+            .type_id()
+            .and_then(|type_id| registry.get(type_id))
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .and_then(|reflect| reflect.reflect(world.entity(ent)));
+        value_row(ui, (ent, info.id()), info.name(), value);
+    }
+}
+
+fn resources_ui(ui: &mut egui::Ui, world: &World) {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut resources = world
+        .iter_resources()
+        .map(|(info, _)| info)
+        .collect::<Vec<_>>();
+    resources.sort_by_key(|info| get_short_name(info.name()));
+    for info in resources {
+        let value = info
+            .type_id()
+            .and_then(|type_id| registry.get(type_id))
+            .and_then(|registration| registration.data::<ReflectResource>())
+            .and_then(|reflect| reflect.reflect(world));
+        value_row(ui, info.id(), info.name(), value);
+    }
+}
+
+fn value_row(ui: &mut egui::Ui, id: impl std::hash::Hash, name: &str, value: Option<&dyn Reflect>) {
+    let name = get_short_name(name);
+    match value {
+        Some(value) => {
+            egui::CollapsingHeader::new(name)
+                .id_source(id)
+                .show(ui, |ui| ui.monospace(format!("{value:#?}")));
+        }
+        None => {
+            ui.label(name);
+        }
+    }
+}
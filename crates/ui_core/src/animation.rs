@@ -0,0 +1,536 @@
+// declarative open / close / hover animations for ui nodes. in a dui template, either use an
+// `<animated>` node, or add the same props to a `<bounds>` node:
+//
+//   <animated animate-open="fade scale" animate-close="fade" animate-hover="1.05"
+//       animate-duration="0.2" animate-curve="spring">
+//
+// open effects play when the node is spawned or shown again after `display: none`. close effects
+// play when the ui is closed with `DespawnAnimatedExt::despawn_animated` (used by the `close_ui_*`
+// actions), which despawns the ui once they finish. `animate-hover` is the scale the node eases
+// to while hovered. effects are `fade`, `scale`, `slide-up`, `slide-down`, `slide-left` and
+// `slide-right`; curves are `linear`, `ease-in`, `ease-out` (the default), `ease-in-out` and
+// `spring`.
+
+use std::f32::consts::PI;
+
+use anyhow::anyhow;
+use bevy::{
+    ecs::{system::SystemParam, world::Command},
+    prelude::*,
+    transform::TransformSystem,
+    ui::UiSystem,
+    window::PrimaryWindow,
+};
+use bevy_dui::{DuiContext, DuiProps, DuiRegistry, DuiTemplate};
+
+use crate::{
+    bound_node::{BoundedNode, NodeBounds},
+    dui_utils::{DuiFromStr, PropsExt},
+};
+
+pub const DEFAULT_DURATION: f32 = 0.2;
+// starting scale for the `scale` effect
+const SCALE_FROM: f32 = 0.9;
+// distance for the `slide-*` effects, as a fraction of the window's smaller dimension
+const SLIDE_DISTANCE: f32 = 0.05;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UiEffect {
+    Fade,
+    Scale,
+    SlideUp,
+    SlideDown,
+    SlideLeft,
+    SlideRight,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TweenCurve {
+    Linear,
+    EaseIn,
+    #[default]
+    EaseOut,
+    EaseInOut,
+    // overshoots and settles
+    Spring,
+}
+
+impl TweenCurve {
+    pub fn ease(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            TweenCurve::Linear => t,
+            TweenCurve::EaseIn => t * t * t,
+            TweenCurve::EaseOut => 1.0 - (1.0 - t).powi(3),
+            TweenCurve::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            TweenCurve::Spring => 1.0 - (1.0 - t) * (-4.0 * t).exp() * (3.0 * PI * t).cos(),
+        }
+    }
+}
+
+impl DuiFromStr for TweenCurve {
+    fn from_str(_: &DuiContext, value: &str) -> Result<Self, anyhow::Error> {
+        Ok(match value {
+            "linear" => Self::Linear,
+            "ease-in" => Self::EaseIn,
+            "ease-out" => Self::EaseOut,
+            "ease-in-out" => Self::EaseInOut,
+            "spring" => Self::Spring,
+            _ => return Err(anyhow!("unrecognised curve `{value}`")),
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct UiEffects(pub Vec<UiEffect>);
+
+impl DuiFromStr for UiEffects {
+    fn from_str(_: &DuiContext, value: &str) -> Result<Self, anyhow::Error> {
+        value
+            .split_whitespace()
+            .map(|effect| {
+                Ok(match effect {
+                    "fade" => UiEffect::Fade,
+                    "scale" => UiEffect::Scale,
+                    "slide-up" => UiEffect::SlideUp,
+                    "slide-down" => UiEffect::SlideDown,
+                    "slide-left" => UiEffect::SlideLeft,
+                    "slide-right" => UiEffect::SlideRight,
+                    _ => return Err(anyhow!("unrecognised effect `{effect}`")),
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct UiAnimation {
+    pub open: Vec<UiEffect>,
+    pub close: Vec<UiEffect>,
+    pub hover_scale: Option<f32>,
+    pub duration: f32,
+    pub curve: TweenCurve,
+    // 0 when hidden, 1 when fully shown
+    shown: f32,
+    closing: bool,
+    hidden: bool,
+    // 0 when not hovered, 1 when fully hovered
+    hover: f32,
+    // base alphas of faded nodes, recorded when a fade starts
+    faded: Vec<(Entity, FadeBase)>,
+}
+
+impl Default for UiAnimation {
+    fn default() -> Self {
+        Self {
+            open: Vec::default(),
+            close: Vec::default(),
+            hover_scale: None,
+            duration: DEFAULT_DURATION,
+            curve: TweenCurve::default(),
+            shown: 0.0,
+            closing: false,
+            hidden: false,
+            hover: 0.0,
+            faded: Vec::default(),
+        }
+    }
+}
+
+impl UiAnimation {
+    pub fn new(open: Vec<UiEffect>, close: Vec<UiEffect>) -> Self {
+        Self {
+            open,
+            close,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_hover_scale(mut self, scale: f32) -> Self {
+        self.hover_scale = Some(scale);
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_curve(mut self, curve: TweenCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    // reads the `animate-*` props, if any are present
+    pub fn from_props(
+        props: &mut DuiProps,
+        ctx: &DuiContext,
+    ) -> Result<Option<Self>, anyhow::Error> {
+        let open = props.take_as::<UiEffects>(ctx, "animate-open")?;
+        let close = props.take_as::<UiEffects>(ctx, "animate-close")?;
+        let hover_scale = props.take_as::<f32>(ctx, "animate-hover")?;
+        let duration = props.take_as::<f32>(ctx, "animate-duration")?;
+        let curve = props.take_as::<TweenCurve>(ctx, "animate-curve")?;
+
+        if open.is_none() && close.is_none() && hover_scale.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            open: open.unwrap_or_default().0,
+            close: close.unwrap_or_default().0,
+            hover_scale,
+            duration: duration.unwrap_or(DEFAULT_DURATION),
+            curve: curve.unwrap_or_default(),
+            ..Default::default()
+        }))
+    }
+
+    fn effects(&self) -> &[UiEffect] {
+        if self.closing {
+            &self.close
+        } else {
+            &self.open
+        }
+    }
+
+    fn has(&self, effect: UiEffect) -> bool {
+        self.effects().contains(&effect)
+    }
+
+    // eased amount of the open / close effects still to apply, 0 when fully shown
+    fn remaining(&self) -> f32 {
+        1.0 - self.curve.ease(self.shown)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct FadeBase {
+    background: Option<f32>,
+    border: Option<f32>,
+    image: Option<f32>,
+    text: Vec<f32>,
+    bounded: Option<f32>,
+    bounds_border: Option<f32>,
+}
+
+// a ui root waiting for its close animations before despawning
+#[derive(Component)]
+pub struct ClosingUi {
+    remaining: f32,
+}
+
+pub struct UiAnimationPlugin;
+
+impl Plugin for UiAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_templates);
+        app.add_systems(
+            Update,
+            (add_interaction, tick_animations, despawn_closed).chain(),
+        );
+        app.add_systems(
+            PostUpdate,
+            (apply_transforms, apply_fades)
+                .after(UiSystem::Layout)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+fn setup_templates(mut dui: ResMut<DuiRegistry>) {
+    dui.register_template("animated", DuiAnimatedTemplate);
+}
+
+// hover animations need the node's interaction
+fn add_interaction(
+    mut commands: Commands,
+    new: Query<(Entity, &UiAnimation), (Added<UiAnimation>, Without<Interaction>)>,
+) {
+    for (entity, animation) in new.iter() {
+        if animation.hover_scale.is_some() {
+            commands.entity(entity).try_insert(Interaction::default());
+        }
+    }
+}
+
+pub struct DuiAnimatedTemplate;
+impl DuiTemplate for DuiAnimatedTemplate {
+    fn render(
+        &self,
+        commands: &mut bevy::ecs::system::EntityCommands,
+        mut props: DuiProps,
+        ctx: &mut DuiContext,
+    ) -> Result<bevy_dui::NodeMap, anyhow::Error> {
+        if let Some(animation) = UiAnimation::from_props(&mut props, ctx)? {
+            commands.insert(animation);
+        }
+        Ok(Default::default())
+    }
+}
+
+pub trait DespawnAnimatedExt {
+    // plays the close animations of the entity and its descendants, then despawns it
+    fn despawn_animated(&mut self);
+}
+
+impl DespawnAnimatedExt for bevy::ecs::system::EntityCommands<'_> {
+    fn despawn_animated(&mut self) {
+        let entity = self.id();
+        self.commands().add(DespawnAnimated(entity));
+    }
+}
+
+struct DespawnAnimated(Entity);
+
+impl Command for DespawnAnimated {
+    fn apply(self, world: &mut World) {
+        if world.get::<ClosingUi>(self.0).is_some() {
+            return;
+        }
+
+        let mut animated = Vec::default();
+        let mut pending = vec![self.0];
+        while let Some(entity) = pending.pop() {
+            let Some(entity_ref) = world.get_entity(entity) else {
+                continue;
+            };
+            if let Some(animation) = entity_ref.get::<UiAnimation>() {
+                if !animation.close.is_empty() && !animation.hidden {
+                    animated.push(entity);
+                }
+            }
+            if let Some(children) = entity_ref.get::<Children>() {
+                pending.extend(children.iter().copied());
+            }
+        }
+
+        let mut remaining = 0f32;
+        for entity in animated {
+            let Some(mut animation) = world.get_mut::<UiAnimation>(entity) else {
+                continue;
+            };
+            animation.closing = true;
+            // close from wherever the open animation has got to
+            remaining = remaining.max(animation.shown * animation.duration);
+        }
+
+        if remaining > 0.0 {
+            world.entity_mut(self.0).insert(ClosingUi { remaining });
+        } else if let Some(entity) = world.get_entity_mut(self.0) {
+            entity.despawn_recursive();
+        }
+    }
+}
+
+fn despawn_closed(
+    mut commands: Commands,
+    mut closing: Query<(Entity, &mut ClosingUi)>,
+    time: Res<Time>,
+) {
+    for (entity, mut closing) in closing.iter_mut() {
+        closing.remaining -= time.delta_seconds();
+        if closing.remaining <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn tick_animations(
+    mut q: Query<(&mut UiAnimation, &Style, Option<&Interaction>)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for (mut animation, style, interaction) in q.iter_mut() {
+        let step = if animation.duration > 0.0 {
+            dt / animation.duration
+        } else {
+            1.0
+        };
+
+        // replay the open animation when shown again
+        let hidden = style.display == Display::None;
+        if hidden != animation.hidden {
+            animation.hidden = hidden;
+            if hidden && !animation.open.is_empty() {
+                animation.shown = 0.0;
+            }
+        }
+
+        if animation.closing {
+            animation.shown = (animation.shown - step).max(0.0);
+        } else if !hidden && animation.shown < 1.0 {
+            animation.shown = if animation.open.is_empty() {
+                1.0
+            } else {
+                (animation.shown + step).min(1.0)
+            };
+        }
+
+        let hovered = interaction.is_some_and(|i| *i != Interaction::None);
+        if animation.hover_scale.is_some() {
+            animation.hover = if hovered {
+                (animation.hover + step).min(1.0)
+            } else {
+                (animation.hover - step).max(0.0)
+            };
+        }
+    }
+}
+
+fn apply_transforms(
+    mut q: Query<(&UiAnimation, &mut Transform)>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let slide_distance = window
+        .get_single()
+        .map(|window| window.width().min(window.height()) * SLIDE_DISTANCE)
+        .unwrap_or_default();
+
+    for (animation, mut transform) in q.iter_mut() {
+        let remaining = animation.remaining();
+
+        let mut scale = 1.0;
+        if animation.has(UiEffect::Scale) {
+            scale *= 1.0 - (1.0 - SCALE_FROM) * remaining;
+        }
+        if let Some(hover_scale) = animation.hover_scale {
+            scale *= 1.0 + (hover_scale - 1.0) * animation.curve.ease(animation.hover);
+        }
+
+        // ui y is down the screen. layout resets the translation every frame, so the offset is
+        // added on top
+        let mut offset = Vec2::ZERO;
+        for effect in animation.effects() {
+            offset += match effect {
+                UiEffect::SlideUp => Vec2::Y,
+                UiEffect::SlideDown => -Vec2::Y,
+                UiEffect::SlideLeft => Vec2::X,
+                UiEffect::SlideRight => -Vec2::X,
+                _ => Vec2::ZERO,
+            };
+        }
+        offset *= slide_distance * remaining;
+
+        let scale = Vec3::new(scale, scale, 1.0);
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+        if offset != Vec2::ZERO {
+            transform.translation += offset.extend(0.0);
+        }
+    }
+}
+
+// the colors a fade affects
+#[derive(SystemParam)]
+struct FadeTargets<'w, 's> {
+    background: Query<'w, 's, &'static mut BackgroundColor>,
+    border: Query<'w, 's, &'static mut BorderColor>,
+    image: Query<'w, 's, &'static mut UiImage>,
+    text: Query<'w, 's, &'static mut Text>,
+    bounded: Query<'w, 's, &'static mut BoundedNode>,
+    bounds: Query<'w, 's, &'static mut NodeBounds>,
+}
+
+impl FadeTargets<'_, '_> {
+    fn base(&self, entity: Entity) -> FadeBase {
+        FadeBase {
+            background: self.background.get(entity).ok().map(|c| c.0.alpha()),
+            border: self.border.get(entity).ok().map(|c| c.0.alpha()),
+            image: self.image.get(entity).ok().map(|i| i.color.alpha()),
+            text: self
+                .text
+                .get(entity)
+                .map(|t| t.sections.iter().map(|s| s.style.color.alpha()).collect())
+                .unwrap_or_default(),
+            bounded: self
+                .bounded
+                .get(entity)
+                .ok()
+                .and_then(|n| n.color)
+                .map(|c| c.alpha()),
+            bounds_border: self.bounds.get(entity).ok().map(|b| b.border_color.alpha()),
+        }
+    }
+
+    // colors are only touched when they change, to avoid triggering change detection
+    fn apply(&mut self, entity: Entity, base: &FadeBase, opacity: f32) {
+        if let (Ok(mut color), Some(alpha)) = (self.background.get_mut(entity), base.background) {
+            if color.0.alpha() != alpha * opacity {
+                color.0.set_alpha(alpha * opacity);
+            }
+        }
+        if let (Ok(mut color), Some(alpha)) = (self.border.get_mut(entity), base.border) {
+            if color.0.alpha() != alpha * opacity {
+                color.0.set_alpha(alpha * opacity);
+            }
+        }
+        if let (Ok(mut image), Some(alpha)) = (self.image.get_mut(entity), base.image) {
+            if image.color.alpha() != alpha * opacity {
+                image.color.set_alpha(alpha * opacity);
+            }
+        }
+        if let Ok(mut text) = self.text.get_mut(entity) {
+            let changed = text
+                .sections
+                .iter()
+                .zip(base.text.iter())
+                .any(|(section, alpha)| section.style.color.alpha() != alpha * opacity);
+            if changed {
+                for (section, alpha) in text.sections.iter_mut().zip(base.text.iter()) {
+                    section.style.color.set_alpha(alpha * opacity);
+                }
+            }
+        }
+        if let (Ok(mut node), Some(alpha)) = (self.bounded.get_mut(entity), base.bounded) {
+            if node.color.is_some_and(|c| c.alpha() != alpha * opacity) {
+                if let Some(color) = node.color.as_mut() {
+                    color.set_alpha(alpha * opacity);
+                }
+            }
+        }
+        if let (Ok(mut node), Some(alpha)) = (self.bounds.get_mut(entity), base.bounds_border) {
+            if node.border_color.alpha() != alpha * opacity {
+                node.border_color.set_alpha(alpha * opacity);
+            }
+        }
+    }
+}
+
+fn apply_fades(
+    mut animations: Query<(Entity, &mut UiAnimation)>,
+    children: Query<&Children>,
+    mut targets: FadeTargets,
+) {
+    for (root, mut animation) in animations.iter_mut() {
+        let fading = animation.has(UiEffect::Fade) && animation.remaining() > 0.0;
+        if !fading {
+            // restore the base alphas once finished
+            for (entity, base) in std::mem::take(&mut animation.faded) {
+                targets.apply(entity, &base, 1.0);
+            }
+            continue;
+        }
+
+        if animation.faded.is_empty() {
+            animation.faded = std::iter::once(root)
+                .chain(children.iter_descendants(root))
+                .map(|entity| (entity, targets.base(entity)))
+                .collect();
+        }
+
+        // springs overshoot
+        let opacity = (1.0 - animation.remaining()).clamp(0.0, 1.0);
+        for (entity, base) in animation.faded.iter() {
+            targets.apply(*entity, base, opacity);
+        }
+    }
+}
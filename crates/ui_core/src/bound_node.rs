@@ -8,7 +8,10 @@ use bevy::{
 };
 use bevy_dui::{DuiRegistry, DuiTemplate};
 
-use crate::{dui_utils::PropsExt, interact_sounds::InteractSounds, interact_style::InteractStyles};
+use crate::{
+    animation::UiAnimation, context_menu::ContextMenu, dui_utils::PropsExt,
+    interact_sounds::InteractSounds, interact_style::InteractStyles, tooltip::Tooltip,
+};
 
 #[derive(Component)]
 pub struct NodeBounds {
@@ -152,8 +155,10 @@ fn update_bounded_nodes(
         window: Vec2,
         add_border: bool,
     ) {
-        let center = gt.translation().xy();
-        let size = node.unrounded_size();
+        let (scale, _, translation) = gt.to_scale_rotation_translation();
+        let center = translation.xy();
+        // including any animated scale
+        let size = node.unrounded_size() * scale.xy();
         mat.bounds.bounds = Vec4::new(
             center.x - size.x * 0.5,
             center.y - size.y * 0.5,
@@ -301,6 +306,15 @@ impl DuiTemplate for DuiNodeBounds {
         if let Some(sounds) = props.take_as::<InteractSounds>(ctx, "sounds")? {
             commands.insert(sounds);
         }
+        if let Some(tooltip) = props.take::<String>("tooltip")? {
+            commands.insert(Tooltip(tooltip));
+        }
+        if let Some(context_menu) = props.take::<ContextMenu>("context-menu")? {
+            commands.insert(context_menu);
+        }
+        if let Some(animation) = UiAnimation::from_props(&mut props, ctx)? {
+            commands.insert(animation);
+        }
         DuiBoundNode.render(commands, props, ctx)
     }
 }
@@ -7,19 +7,19 @@ use bevy::{
 use bevy_dui::{
     DuiCommandsExt, DuiContext, DuiEntities, DuiProps, DuiRegistry, DuiTemplate, NodeMap,
 };
-use common::{
-    structs::{ToolTips, TooltipSource},
-    util::{ModifyComponentExt, TryPushChildrenEx},
-};
+use common::util::{ModifyComponentExt, TryPushChildrenEx};
 
 use crate::{
     bound_node::NodeBounds,
+    context_menu::ContextMenu,
     dui_utils::PropsExt,
+    gamepad_nav::BackButton,
     interact_style::{Active, InteractStyles},
     text_size::FontSize,
+    tooltip::Tooltip,
     ui_actions::{
         close_ui_happy, close_ui_sad, close_ui_silent, Click, ClickRepeat, DataChanged, Enabled,
-        HoverEnter, HoverExit, On, UiCaller,
+        On, UiCaller,
     },
 };
 
@@ -35,6 +35,9 @@ pub struct DuiButton {
     pub image_height: Option<Val>,
     pub text_size: Option<f32>,
     pub tooltip: Option<String>,
+    pub context_menu: Option<ContextMenu>,
+    // pressed by the gamepad back button
+    pub back: bool,
 }
 
 impl Default for DuiButton {
@@ -51,6 +54,8 @@ impl Default for DuiButton {
             image_height: None,
             text_size: None,
             tooltip: None,
+            context_menu: None,
+            back: false,
         }
     }
 }
@@ -167,6 +172,12 @@ impl DuiTemplate for DuiButtonTemplate {
         if let Some(tooltip) = props.take::<String>("tooltip")? {
             data.tooltip = Some(tooltip);
         }
+        if let Some(context_menu) = props.take::<ContextMenu>("context-menu")? {
+            data.context_menu = Some(context_menu);
+        }
+        if let Some(back) = props.take::<String>("back")? {
+            data.back = back == "true";
+        }
 
         let mut components = match (data.label, data.image) {
             (Some(label), _) => ctx.render_template(
@@ -210,23 +221,16 @@ impl DuiTemplate for DuiButtonTemplate {
             button.insert(styles);
         }
 
+        if data.back {
+            button.insert(BackButton);
+        }
+
         if let Some(tooltip) = data.tooltip {
-            button.insert(On::<HoverEnter>::new(
-                move |mut tooltips: ResMut<ToolTips>,
-                      enabled: Query<&Enabled>,
-                      caller: Res<UiCaller>| {
-                    let enabled = enabled.get(caller.0).map(|e| e.0).unwrap_or(false);
-                    tooltips.0.insert(
-                        TooltipSource::Entity(button_id),
-                        vec![(tooltip.clone(), enabled)],
-                    );
-                },
-            ));
-            button.insert(On::<HoverExit>::new(
-                move |mut tooltips: ResMut<ToolTips>| {
-                    tooltips.0.remove(&TooltipSource::Entity(button_id));
-                },
-            ));
+            button.insert(Tooltip(tooltip));
+        }
+
+        if let Some(context_menu) = data.context_menu {
+            button.insert(context_menu);
         }
 
         if let Some(onclick) = data.onclick {
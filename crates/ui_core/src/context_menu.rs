@@ -0,0 +1,264 @@
+// right-click context menus for ui nodes. the menu opens at the cursor, or beside the focused node
+// with the menu key, shift+f10 or the gamepad west (X) button. it is kept on screen, takes gamepad
+// focus, and closes when an item is chosen, on escape / gamepad east (B), or on clicking elsewhere.
+
+use std::sync::Arc;
+
+use bevy::{
+    input::gamepad::{GamepadButtonType, Gamepads},
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_dui::{DuiCommandsExt, DuiProps, DuiRegistry};
+use common::util::DespawnWith;
+
+use crate::{
+    button::DuiButton,
+    focus::Focus,
+    gamepad_nav::{GamepadFocus, NavScope},
+    ui_actions::{close_ui_silent, Click, Defocus, Enabled, On},
+};
+
+type ItemAction = Arc<dyn Fn() -> DuiButton + Send + Sync>;
+
+#[derive(Clone)]
+pub struct ContextMenuItem {
+    pub label: String,
+    pub enabled: bool,
+    action: ItemAction,
+}
+
+// the items shown for a node. items are rebuilt each time the menu opens, so actions must be
+// `Clone` (closures over cloneable data are)
+#[derive(Component, Clone, Default)]
+pub struct ContextMenu(pub Vec<ContextMenuItem>);
+
+impl ContextMenu {
+    pub fn with_item<M, S: IntoSystem<(), (), M> + Clone + Send + Sync + 'static>(
+        self,
+        label: impl Into<String>,
+        action: S,
+    ) -> Self {
+        self.with_item_enabled(label, true, action)
+    }
+
+    pub fn with_item_enabled<M, S: IntoSystem<(), (), M> + Clone + Send + Sync + 'static>(
+        mut self,
+        label: impl Into<String>,
+        enabled: bool,
+        action: S,
+    ) -> Self {
+        let label = label.into();
+        let button_label = label.clone();
+        self.0.push(ContextMenuItem {
+            label,
+            enabled,
+            action: Arc::new(move || {
+                DuiButton::new(
+                    button_label.clone(),
+                    enabled,
+                    action.clone().pipe(close_ui_silent),
+                )
+            }),
+        });
+        self
+    }
+
+    // for actions that can't be cloned, e.g. piped systems. the action is built each time the menu
+    // opens, and the menu is not closed automatically when it runs unless the action closes the
+    // menu's target
+    pub fn with_action(
+        mut self,
+        label: impl Into<String>,
+        enabled: bool,
+        action: impl Fn() -> On<Click> + Send + Sync + 'static,
+    ) -> Self {
+        let label = label.into();
+        let button_label = label.clone();
+        self.0.push(ContextMenuItem {
+            label,
+            enabled,
+            action: Arc::new(move || DuiButton {
+                label: Some(button_label.clone()),
+                onclick: Some(action()),
+                enabled,
+                ..Default::default()
+            }),
+        });
+        self
+    }
+}
+
+// an open menu, with the point it was opened from
+#[derive(Component)]
+pub struct ContextMenuPopup {
+    anchor: Vec2,
+}
+
+pub struct ContextMenuPlugin;
+
+impl Plugin for ContextMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                add_interaction,
+                close_context_menus,
+                open_context_menus,
+                position_context_menus,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn add_interaction(
+    mut commands: Commands,
+    new: Query<Entity, (Added<ContextMenu>, Without<Interaction>)>,
+) {
+    for entity in new.iter() {
+        commands.entity(entity).try_insert(Interaction::default());
+    }
+}
+
+fn gamepad_just_pressed(
+    gamepads: &Gamepads,
+    buttons: &ButtonInput<GamepadButton>,
+    button_type: GamepadButtonType,
+) -> bool {
+    gamepads
+        .iter()
+        .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+}
+
+fn close_context_menus(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    popups: Query<Entity, With<ContextMenuPopup>>,
+) {
+    if keys.just_pressed(KeyCode::Escape)
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::East)
+    {
+        for popup in popups.iter() {
+            commands.entity(popup).despawn_recursive();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn open_context_menus(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    focus: Res<GamepadFocus>,
+    menus: Query<(Entity, &ContextMenu, &Interaction, Option<&Enabled>)>,
+    nodes: Query<(&Node, &GlobalTransform)>,
+    parents: Query<&Parent>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    popups: Query<Entity, With<ContextMenuPopup>>,
+    dui: Res<DuiRegistry>,
+) {
+    let enabled = |enabled: Option<&Enabled>| enabled.map_or(true, |e| e.0);
+
+    let menu_key = keys.just_pressed(KeyCode::ContextMenu)
+        || (keys.just_pressed(KeyCode::F10)
+            && keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]))
+        || gamepad_just_pressed(&gamepads, &buttons, GamepadButtonType::West);
+
+    let opened = if mouse.just_pressed(MouseButton::Right) {
+        let Some(cursor) = window.get_single().ok().and_then(Window::cursor_position) else {
+            return;
+        };
+        // the innermost hovered node with a menu
+        menus
+            .iter()
+            .filter(|(_, _, interaction, e)| **interaction != Interaction::None && enabled(*e))
+            .max_by_key(|(entity, ..)| parents.iter_ancestors(*entity).count())
+            .map(|(entity, menu, ..)| (entity, menu, cursor))
+    } else if menu_key && focus.active {
+        // the focused node or its nearest ancestor with a menu, opened below the focused node
+        focus.entity.and_then(|focused| {
+            let (node, transform) = nodes.get(focused).ok()?;
+            let rect = node.logical_rect(transform);
+            std::iter::once(focused)
+                .chain(parents.iter_ancestors(focused))
+                .find_map(|entity| menus.get(entity).ok())
+                .filter(|(_, _, _, e)| enabled(*e))
+                .map(|(entity, menu, ..)| (entity, menu, Vec2::new(rect.min.x, rect.max.y)))
+        })
+    } else {
+        None
+    };
+
+    let Some((target, menu, anchor)) = opened else {
+        return;
+    };
+    if menu.0.is_empty() {
+        return;
+    }
+
+    for popup in popups.iter() {
+        commands.entity(popup).despawn_recursive();
+    }
+
+    let components = match commands.spawn_template(
+        &dui,
+        "context-menu",
+        DuiProps::new()
+            .with_prop("left", format!("{}px", anchor.x))
+            .with_prop("top", format!("{}px", anchor.y)),
+    ) {
+        Ok(components) => components,
+        Err(e) => {
+            warn!("failed to spawn context menu: {e}");
+            return;
+        }
+    };
+
+    for item in menu.0.iter() {
+        commands
+            .entity(components.named("items"))
+            .spawn_template(
+                &dui,
+                "button",
+                DuiProps::new().with_prop("button-data", (item.action)()),
+            )
+            .unwrap();
+    }
+
+    commands.entity(components.root).insert((
+        ContextMenuPopup { anchor },
+        Focus,
+        On::<Defocus>::new(close_ui_silent),
+        NavScope,
+        DespawnWith(target),
+    ));
+}
+
+fn position_context_menus(
+    mut popups: Query<(&ContextMenuPopup, &Node, &mut Style), Changed<Node>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    let bounds = Vec2::new(window.width(), window.height());
+
+    for (popup, node, mut style) in popups.iter_mut() {
+        let size = node.size();
+        // open away from the window edges
+        let mut position = popup.anchor;
+        if position.x + size.x > bounds.x {
+            position.x = (position.x - size.x).max(0.0);
+        }
+        if position.y + size.y > bounds.y {
+            position.y = (position.y - size.y).max(0.0);
+        }
+        style.left = Val::Px(position.x);
+        style.top = Val::Px(position.y);
+    }
+}
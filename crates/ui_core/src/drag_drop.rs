@@ -0,0 +1,331 @@
+// drag and drop between ui nodes. a `DragSource` carries a payload, and pressing it and moving the
+// cursor past a small threshold starts a drag, showing a ghost under the cursor. `DropTarget`s list
+// the payload types they accept; the accepting target under the cursor is marked with `DragOver`,
+// and releasing over it inserts `Dropped`, which triggers `On<Dropped>` handlers on the target.
+// escape cancels a drag. hovering uses the normal ui interaction, so nodes covering a target
+// (dialogs etc) block drops as they block clicks.
+
+use std::{
+    any::{Any, TypeId},
+    sync::Arc,
+};
+
+use bevy::{
+    ecs::query::{QueryData, WorldQuery},
+    prelude::*,
+    ui::{FocusPolicy, UiSystem},
+    window::PrimaryWindow,
+};
+use common::{structs::SystemAudio, util::FireEventEx};
+
+use crate::{
+    ui_actions::{ActionMarker, UiActionSet},
+    user_font, FontName, WeightName,
+};
+
+// cursor movement in pixels before a press becomes a drag
+const DRAG_THRESHOLD: f32 = 6.0;
+const GHOST_SIZE: Val = Val::VMin(8.0);
+
+pub type DragPayload = Arc<dyn Any + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub enum DragGhost {
+    #[default]
+    Empty,
+    Image(Handle<Image>),
+    Label(String),
+}
+
+#[derive(Component, Clone)]
+pub struct DragSource {
+    payload: DragPayload,
+    ghost: DragGhost,
+}
+
+impl DragSource {
+    pub fn new<T: Any + Send + Sync>(payload: T) -> Self {
+        Self {
+            payload: Arc::new(payload),
+            ghost: DragGhost::Empty,
+        }
+    }
+
+    pub fn with_ghost_image(self, image: Handle<Image>) -> Self {
+        Self {
+            ghost: DragGhost::Image(image),
+            ..self
+        }
+    }
+
+    pub fn with_ghost_label(self, label: impl Into<String>) -> Self {
+        Self {
+            ghost: DragGhost::Label(label.into()),
+            ..self
+        }
+    }
+}
+
+#[derive(Component, Clone, Default)]
+pub struct DropTarget {
+    accepts: Vec<TypeId>,
+}
+
+impl DropTarget {
+    pub fn new<T: Any>() -> Self {
+        Self::default().or::<T>()
+    }
+
+    // accept an additional payload type
+    pub fn or<T: Any>(mut self) -> Self {
+        self.accepts.push(TypeId::of::<T>());
+        self
+    }
+
+    pub fn accepts(&self, payload: &DragPayload) -> bool {
+        self.accepts.contains(&Any::type_id(payload.as_ref()))
+    }
+}
+
+// marks the accepting target under the cursor during a drag
+#[derive(Component)]
+pub struct DragOver;
+
+// inserted on a target when a payload is dropped on it
+#[derive(Component, Clone)]
+pub struct Dropped {
+    pub source: Entity,
+    pub payload: DragPayload,
+}
+
+impl Dropped {
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        self.payload.downcast_ref()
+    }
+}
+
+impl ActionMarker for Dropped {
+    type Component = Option<Ref<'static, Dropped>>;
+    fn activate(param: <<Self::Component as QueryData>::ReadOnly as WorldQuery>::Item<'_>) -> bool {
+        param.map(|p| p.is_changed()).unwrap_or(false)
+    }
+
+    fn repeat_activate() -> bool {
+        true
+    }
+}
+
+pub struct ActiveDrag {
+    pub source: Entity,
+    pub payload: DragPayload,
+    ghost_kind: DragGhost,
+    origin: Vec2,
+    // spawned once the cursor passes the threshold
+    ghost: Option<Entity>,
+}
+
+impl ActiveDrag {
+    pub fn is_dragging(&self) -> bool {
+        self.ghost.is_some()
+    }
+}
+
+// the drag in progress, if any
+#[derive(Resource, Default)]
+pub struct UiDrag(pub Option<ActiveDrag>);
+
+impl UiDrag {
+    // the payload being dragged, if it is a `T`
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        self.0
+            .as_ref()
+            .filter(|drag| drag.is_dragging())
+            .and_then(|drag| drag.payload.downcast_ref())
+    }
+}
+
+#[derive(Component)]
+struct DragGhostNode;
+
+pub struct DragDropPlugin;
+
+impl Plugin for DragDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiDrag>();
+        app.add_systems(
+            PreUpdate,
+            update_drag_drop.after(UiSystem::Focus).before(UiActionSet),
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_drag_drop(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut drag: ResMut<UiDrag>,
+    sources: Query<(Entity, &DragSource, &Interaction)>,
+    targets: Query<(Entity, &DropTarget, &Interaction)>,
+    over: Query<Entity, With<DragOver>>,
+    uninteractive: Query<
+        Entity,
+        (
+            Or<(With<DragSource>, With<DropTarget>)>,
+            Without<Interaction>,
+        ),
+    >,
+    parents: Query<&Parent>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut ghosts: Query<(&mut Style, &mut BackgroundColor), With<DragGhostNode>>,
+) {
+    for entity in uninteractive.iter() {
+        commands.entity(entity).try_insert(Interaction::default());
+    }
+
+    let cursor = window.get_single().ok().and_then(Window::cursor_position);
+
+    if mouse.just_pressed(MouseButton::Left) && drag.0.is_none() {
+        // the innermost pressed source
+        if let Some((source, data, _)) = sources
+            .iter()
+            .filter(|(_, _, interaction)| **interaction == Interaction::Pressed)
+            .max_by_key(|(entity, ..)| parents.iter_ancestors(*entity).count())
+        {
+            drag.0 = Some(ActiveDrag {
+                source,
+                payload: data.payload.clone(),
+                ghost_kind: data.ghost.clone(),
+                origin: cursor.unwrap_or_default(),
+                ghost: None,
+            });
+        }
+    }
+
+    let Some(active) = drag.0.as_mut() else {
+        return;
+    };
+
+    // the innermost hovered target accepting the payload
+    let target = active
+        .is_dragging()
+        .then(|| {
+            targets
+                .iter()
+                .filter(|(entity, target, interaction)| {
+                    *entity != active.source
+                        && **interaction != Interaction::None
+                        && target.accepts(&active.payload)
+                })
+                .max_by_key(|(entity, ..)| parents.iter_ancestors(*entity).count())
+                .map(|(entity, ..)| entity)
+        })
+        .flatten();
+
+    for entity in over.iter().filter(|e| Some(*e) != target) {
+        commands.entity(entity).remove::<DragOver>();
+    }
+
+    let cancelled = keys.just_pressed(KeyCode::Escape);
+    if !mouse.pressed(MouseButton::Left) || cancelled {
+        if let (Some(target), false) = (target, cancelled) {
+            commands
+                .entity(target)
+                .remove::<DragOver>()
+                .try_insert(Dropped {
+                    source: active.source,
+                    payload: active.payload.clone(),
+                });
+            commands.fire_event(SystemAudio("sounds/ui/toggle_enable.wav".to_owned()));
+        }
+        if let Some(ghost) = active.ghost {
+            commands.entity(ghost).despawn_recursive();
+        }
+        drag.0 = None;
+        return;
+    }
+
+    if let Some(target) = target {
+        if !over.contains(target) {
+            commands.entity(target).try_insert(DragOver);
+        }
+    }
+
+    let Some(cursor) = cursor else {
+        return;
+    };
+
+    match active.ghost {
+        None => {
+            if cursor.distance(active.origin) > DRAG_THRESHOLD {
+                active.ghost = Some(spawn_ghost(&mut commands, &active.ghost_kind, cursor));
+            }
+        }
+        Some(ghost) => {
+            if let Ok((mut style, mut background)) = ghosts.get_mut(ghost) {
+                style.left = Val::Px(cursor.x);
+                style.top = Val::Px(cursor.y);
+                // fainter when there is nowhere to drop
+                let alpha = if target.is_some() { 0.8 } else { 0.4 };
+                if background.0.alpha() != alpha {
+                    background.0.set_alpha(alpha);
+                }
+            }
+        }
+    }
+}
+
+fn spawn_ghost(commands: &mut Commands, kind: &DragGhost, cursor: Vec2) -> Entity {
+    let mut ghost = commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(cursor.x),
+                top: Val::Px(cursor.y),
+                min_width: GHOST_SIZE,
+                min_height: GHOST_SIZE,
+                padding: UiRect::all(Val::VMin(0.5)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            background_color: Color::srgba(0.0, 0.0, 0.0, 0.4).into(),
+            focus_policy: FocusPolicy::Pass,
+            z_index: ZIndex::Global(i16::MAX as i32 + 7),
+            ..Default::default()
+        },
+        DragGhostNode,
+    ));
+
+    ghost.with_children(|c| match kind {
+        DragGhost::Empty => (),
+        DragGhost::Image(image) => {
+            c.spawn(ImageBundle {
+                style: Style {
+                    width: GHOST_SIZE,
+                    height: GHOST_SIZE,
+                    ..Default::default()
+                },
+                image: UiImage::new(image.clone()),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            });
+        }
+        DragGhost::Label(label) => {
+            c.spawn(TextBundle {
+                text: Text::from_section(
+                    label.clone(),
+                    TextStyle {
+                        font: user_font(FontName::Sans, WeightName::Regular),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            });
+        }
+    });
+
+    ghost.id()
+}
@@ -0,0 +1,97 @@
+// additional fonts for scripts the bundled noto fonts don't cover (cjk, cyrillic extensions,
+// arabic, ...). fonts are read from the data directory's `fonts` folder and from
+// `AppConfig::extra_fonts`. text layout falls back through every font it knows about for missing
+// glyphs, and shapes complex scripts, but only knows fonts that some text has used, so each extra
+// font is used once by a hidden node.
+
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use common::{structs::AppConfig, util::project_directories};
+
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "otc"];
+
+pub struct ExtraFontsPlugin;
+
+impl Plugin for ExtraFontsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ExtraFonts>();
+        app.add_systems(Startup, load_extra_fonts);
+    }
+}
+
+// the loaded extra fonts, with the files they came from
+#[derive(Resource, Default)]
+pub struct ExtraFonts(pub Vec<(PathBuf, Handle<Font>)>);
+
+pub fn user_fonts_folder() -> PathBuf {
+    project_directories().data_dir().join("fonts")
+}
+
+fn font_files(config: &AppConfig) -> Vec<PathBuf> {
+    let is_font = |path: &Path| {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| FONT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+    };
+
+    let mut files = std::fs::read_dir(user_fonts_folder())
+        .map(|dir| {
+            dir.filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| is_font(path))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files.extend(config.extra_fonts.iter().map(PathBuf::from));
+    files
+}
+
+fn load_extra_fonts(
+    mut commands: Commands,
+    config: Res<AppConfig>,
+    mut fonts: ResMut<Assets<Font>>,
+    mut extra: ResMut<ExtraFonts>,
+) {
+    for path in font_files(&config) {
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!("failed to read font {}: {e}", path.display());
+                continue;
+            }
+        };
+        match Font::try_from_bytes(bytes) {
+            Ok(font) => {
+                info!("loaded extra font {}", path.display());
+                extra.0.push((path, fonts.add(font)));
+            }
+            Err(e) => warn!("failed to parse font {}: {e:?}", path.display()),
+        }
+    }
+
+    if extra.0.is_empty() {
+        return;
+    }
+
+    // a section per font, so the text layout registers each of them
+    commands.spawn(TextBundle {
+        text: Text::from_sections(extra.0.iter().map(|(_, font)| {
+            TextSection::new(
+                " ",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 1.0,
+                    color: Color::NONE,
+                },
+            )
+        })),
+        style: Style {
+            position_type: PositionType::Absolute,
+            ..Default::default()
+        },
+        visibility: Visibility::Hidden,
+        ..Default::default()
+    });
+}
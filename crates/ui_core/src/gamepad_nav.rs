@@ -0,0 +1,373 @@
+// gamepad navigation of ui: the d-pad (or the left stick while a dialog is open) moves focus
+// between clickable nodes, south (A) presses the focused node and east (B) presses the
+// `BackButton` in scope, or releases focus when no dialog is open. while a dialog is open,
+// navigation is limited to the most recent dialog, and focus moves into new dialogs automatically.
+
+use bevy::{
+    input::{
+        gamepad::{GamepadAxisType, GamepadButtonType, Gamepads},
+        mouse::MouseMotion,
+    },
+    prelude::*,
+    ui::UiSystem,
+};
+use common::structs::{AppConfig, DialogPermit};
+
+use crate::{
+    focus::Focusable,
+    scrollable::{ScrollTarget, ScrollTargetEvent, Scrollable},
+    theme::UiTheme,
+    ui_actions::{Click, ClickRepeat, Enabled, On, UiActionSet},
+};
+
+const STICK_THRESHOLD: f32 = 0.5;
+
+pub struct GamepadNavPlugin;
+
+impl Plugin for GamepadNavPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GamepadFocus>();
+        app.add_systems(
+            PreUpdate,
+            update_gamepad_nav
+                .after(UiSystem::Focus)
+                .before(UiActionSet),
+        );
+        app.add_systems(Update, update_focus_outline);
+    }
+}
+
+// marker for buttons that back out of their ui, pressed by the gamepad east (B) button
+#[derive(Component)]
+pub struct BackButton;
+
+// marker for popups that limit navigation like dialogs, for use when another dialog already holds
+// the dialog permit
+#[derive(Component)]
+pub struct NavScope;
+
+#[derive(Resource, Default)]
+pub struct GamepadFocus {
+    // true after gamepad input, until the mouse is used
+    pub active: bool,
+    pub entity: Option<Entity>,
+    pressed: Option<Entity>,
+}
+
+struct NavNode {
+    entity: Entity,
+    center: Vec2,
+}
+
+fn held_direction(
+    gamepads: &Gamepads,
+    buttons: &ButtonInput<GamepadButton>,
+    axes: &Axis<GamepadAxis>,
+    use_stick: bool,
+) -> Option<Vec2> {
+    for gamepad in gamepads.iter() {
+        let pressed = |button_type| buttons.pressed(GamepadButton::new(gamepad, button_type));
+        // ui y is downwards
+        if pressed(GamepadButtonType::DPadUp) {
+            return Some(Vec2::NEG_Y);
+        }
+        if pressed(GamepadButtonType::DPadDown) {
+            return Some(Vec2::Y);
+        }
+        if pressed(GamepadButtonType::DPadLeft) {
+            return Some(Vec2::NEG_X);
+        }
+        if pressed(GamepadButtonType::DPadRight) {
+            return Some(Vec2::X);
+        }
+
+        if use_stick {
+            let axis = |axis_type| {
+                axes.get(GamepadAxis::new(gamepad, axis_type))
+                    .unwrap_or(0.0)
+            };
+            let stick = Vec2::new(
+                axis(GamepadAxisType::LeftStickX),
+                -axis(GamepadAxisType::LeftStickY),
+            );
+            if stick.length() > STICK_THRESHOLD {
+                return Some(if stick.x.abs() > stick.y.abs() {
+                    Vec2::new(stick.x.signum(), 0.0)
+                } else {
+                    Vec2::new(0.0, stick.y.signum())
+                });
+            }
+        }
+    }
+
+    None
+}
+
+fn in_scope(entity: Entity, scope: Option<Entity>, parents: &Query<&Parent>) -> bool {
+    let Some(root) = scope else {
+        return true;
+    };
+    let mut ent = entity;
+    loop {
+        if ent == root {
+            return true;
+        }
+        let Ok(parent) = parents.get(ent) else {
+            return false;
+        };
+        ent = parent.get();
+    }
+}
+
+// bring the node into view if it is clipped by a scrollable ancestor
+fn scroll_to(
+    entity: Entity,
+    nodes: &Query<(&Node, &GlobalTransform)>,
+    scrollables: &Query<(), With<Scrollable>>,
+    parents: &Query<&Parent>,
+    scroll_events: &mut EventWriter<ScrollTargetEvent>,
+) {
+    let Ok((node, transform)) = nodes.get(entity) else {
+        return;
+    };
+    let rect = node.logical_rect(transform);
+
+    let mut ent = entity;
+    while let Ok(parent) = parents.get(ent) {
+        ent = parent.get();
+        if scrollables.get(ent).is_err() {
+            continue;
+        }
+
+        let Ok((scroll_node, scroll_transform)) = nodes.get(ent) else {
+            return;
+        };
+        let view = scroll_node.logical_rect(scroll_transform);
+        if view.union(rect) != view {
+            scroll_events.send(ScrollTargetEvent {
+                scrollable: ent,
+                position: ScrollTarget::Entity(entity),
+            });
+        }
+        return;
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_gamepad_nav(
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    time: Res<Time<Real>>,
+    config: Res<AppConfig>,
+    mut focus: ResMut<GamepadFocus>,
+    targets: Query<
+        (Entity, &ViewVisibility, Option<&Enabled>),
+        (
+            With<Interaction>,
+            Or<(With<On<Click>>, With<On<ClickRepeat>>, With<Focusable>)>,
+        ),
+    >,
+    back_buttons: Query<Entity, With<BackButton>>,
+    nodes: Query<(&Node, &GlobalTransform)>,
+    mut interactions: Query<&mut Interaction>,
+    new_dialogs: Query<Entity, Or<(Added<DialogPermit>, Added<NavScope>)>>,
+    live_dialogs: Query<(), Or<(With<DialogPermit>, With<NavScope>)>>,
+    parents: Query<&Parent>,
+    scrollables: Query<(), With<Scrollable>>,
+    mut scroll_events: EventWriter<ScrollTargetEvent>,
+    mut dialogs: Local<Vec<Entity>>,
+    mut pending_capture: Local<bool>,
+    mut repeat: Local<Option<(Vec2, f32)>>,
+) {
+    let any_just_pressed = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+    let any_pressed = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| buttons.pressed(GamepadButton::new(gamepad, button_type)))
+    };
+
+    // release a previous press
+    if let Some(pressed) = focus.pressed {
+        if !any_pressed(GamepadButtonType::South) && !any_pressed(GamepadButtonType::East) {
+            if let Ok(mut interaction) = interactions.get_mut(pressed) {
+                if *interaction == Interaction::Pressed {
+                    *interaction = Interaction::None;
+                }
+            }
+            focus.pressed = None;
+        }
+    }
+
+    if mouse_motion.read().count() > 0 || mouse_buttons.get_just_pressed().len() != 0 {
+        focus.active = false;
+        focus.entity = None;
+    }
+
+    // track open dialogs, most recent last
+    dialogs.retain(|dialog| live_dialogs.get(*dialog).is_ok());
+    for dialog in new_dialogs.iter() {
+        dialogs.push(dialog);
+        focus.entity = None;
+        *pending_capture = true;
+    }
+    let scope = dialogs.last().copied();
+
+    let candidates = targets
+        .iter()
+        .filter(|(entity, vis, enabled)| {
+            vis.get() && enabled.map_or(true, |e| e.0) && in_scope(*entity, scope, &parents)
+        })
+        .filter_map(|(entity, ..)| {
+            let (node, transform) = nodes.get(entity).ok()?;
+            (node.size() != Vec2::ZERO).then(|| NavNode {
+                entity,
+                center: transform.translation().truncate(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if focus
+        .entity
+        .is_some_and(|current| !candidates.iter().any(|c| c.entity == current))
+    {
+        focus.entity = None;
+    }
+
+    // direction input, repeating while held
+    let now = time.elapsed_seconds();
+    let accessibility = &config.input_accessibility;
+    let direction = match (
+        held_direction(&gamepads, &buttons, &axes, scope.is_some()),
+        *repeat,
+    ) {
+        (Some(dir), Some((prev, next))) if dir == prev => {
+            if now >= next {
+                *repeat = Some((dir, now + accessibility.repeat_interval));
+                Some(dir)
+            } else {
+                None
+            }
+        }
+        (Some(dir), _) => {
+            *repeat = Some((dir, now + accessibility.repeat_delay));
+            Some(dir)
+        }
+        (None, _) => {
+            *repeat = None;
+            None
+        }
+    };
+
+    let activate = any_just_pressed(GamepadButtonType::South);
+    let back = any_just_pressed(GamepadButtonType::East);
+    if direction.is_some() || activate || back {
+        focus.active = true;
+    }
+
+    if !focus.active {
+        *pending_capture = false;
+        return;
+    }
+
+    let first = || {
+        candidates
+            .iter()
+            .min_by(|a, b| {
+                (a.center.y, a.center.x)
+                    .partial_cmp(&(b.center.y, b.center.x))
+                    .unwrap()
+            })
+            .map(|c| c.entity)
+    };
+
+    let mut new_focus = None;
+    if *pending_capture && !candidates.is_empty() {
+        *pending_capture = false;
+        new_focus = first();
+    }
+
+    if let Some(direction) = direction {
+        let current = focus
+            .entity
+            .and_then(|e| candidates.iter().find(|c| c.entity == e));
+        new_focus = match current {
+            None => first(),
+            Some(current) => candidates
+                .iter()
+                .filter_map(|c| {
+                    let delta = c.center - current.center;
+                    let along = delta.dot(direction);
+                    // prefer nodes in line with the current node
+                    let across = (delta - direction * along).length();
+                    (along > 1.0).then_some((c.entity, along + across * 2.0))
+                })
+                .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                .map(|(entity, _)| entity)
+                .or(Some(current.entity)),
+        };
+    }
+
+    if let Some(entity) = new_focus {
+        if focus.entity != Some(entity) {
+            scroll_to(entity, &nodes, &scrollables, &parents, &mut scroll_events);
+        }
+        focus.entity = Some(entity);
+    }
+
+    // outside of dialogs the face buttons only go to the ui while it has focus, so they stay
+    // available for gameplay. back releases the focus
+    let press = if back && scope.is_some() {
+        back_buttons
+            .iter()
+            .find(|entity| in_scope(*entity, scope, &parents))
+    } else if back {
+        focus.entity = None;
+        None
+    } else if activate && focus.entity.is_none() && scope.is_some() {
+        focus.entity = first();
+        None
+    } else if activate {
+        focus.entity
+    } else {
+        None
+    };
+
+    if let Some(entity) = press {
+        if let Ok(mut interaction) = interactions.get_mut(entity) {
+            *interaction = Interaction::Pressed;
+            focus.pressed = Some(entity);
+        }
+    }
+}
+
+fn update_focus_outline(
+    mut commands: Commands,
+    focus: Res<GamepadFocus>,
+    theme: Res<UiTheme>,
+    mut outlined: Local<Option<Entity>>,
+) {
+    let target = focus.entity.filter(|_| focus.active);
+    if target == *outlined && !theme.is_changed() {
+        return;
+    }
+
+    if let Some(prev) = outlined.take() {
+        if let Some(mut commands) = commands.get_entity(prev) {
+            commands.remove::<Outline>();
+        }
+    }
+
+    if let Some(entity) = target {
+        if let Some(mut commands) = commands.get_entity(entity) {
+            commands.try_insert(Outline::new(Val::Px(2.0), Val::Px(2.0), theme.accent));
+            *outlined = Some(entity);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+// os input method (ime) composition for text entries. while a text entry is focused the window's
+// ime is enabled and its candidate box is placed at the entry. the in-progress composition is
+// shown underlined above the entry, and committed text is typed into the entry.
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput, NativeKeyCode},
+        ButtonState,
+    },
+    prelude::*,
+    window::{Ime, PrimaryWindow},
+};
+use bevy_simple_text_input::{
+    TextInputInactive, TextInputSettings, TextInputSystem, TextInputTextStyle,
+};
+use common::sets::SceneSets;
+
+use crate::focus::Focus;
+
+pub struct ImePlugin;
+
+impl Plugin for ImePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ImeComposition>();
+        app.add_systems(
+            Update,
+            (
+                update_ime_target,
+                handle_ime_events,
+                update_composition_display,
+            )
+                .chain()
+                .in_set(SceneSets::PostLoop)
+                .before(TextInputSystem),
+        );
+    }
+}
+
+// the text being composed, and the text input it is being composed for
+#[derive(Resource, Default, Debug)]
+pub struct ImeComposition {
+    pub entity: Option<Entity>,
+    pub preedit: String,
+}
+
+impl ImeComposition {
+    pub fn composing(&self) -> bool {
+        self.entity.is_some()
+    }
+
+    fn clear(&mut self) {
+        self.entity = None;
+        self.preedit.clear();
+    }
+}
+
+#[derive(Component)]
+struct CompositionDisplay;
+
+type FocusedTextInput = (With<TextInputSettings>, With<Focus>);
+
+fn update_ime_target(
+    focused: Query<(Entity, &Node, &GlobalTransform), FocusedTextInput>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+    mut composition: ResMut<ImeComposition>,
+) {
+    let target = focused.get_single().ok();
+
+    // focus moved away mid-composition, the os will cancel it when the ime is disabled
+    if composition.composing() && composition.entity != target.map(|(e, ..)| e) {
+        composition.clear();
+    }
+
+    let Ok(mut window) = window.get_single_mut() else {
+        return;
+    };
+
+    if window.ime_enabled != target.is_some() {
+        window.ime_enabled = target.is_some();
+    }
+
+    if let Some((_, node, transform)) = target {
+        let rect = node.logical_rect(transform);
+        let position = Vec2::new(rect.min.x, rect.max.y);
+        if window.ime_position != position {
+            window.ime_position = position;
+        }
+    }
+}
+
+fn handle_ime_events(
+    mut ime: EventReader<Ime>,
+    focused: Query<Entity, FocusedTextInput>,
+    mut inactive: Query<&mut TextInputInactive>,
+    mut composition: ResMut<ImeComposition>,
+    mut keys: EventWriter<KeyboardInput>,
+) {
+    for ev in ime.read() {
+        let Ok(entity) = focused.get_single() else {
+            composition.clear();
+            continue;
+        };
+
+        match ev {
+            Ime::Preedit { value, .. } => {
+                // an empty preedit means the composition was cancelled
+                composition.entity = (!value.is_empty()).then_some(entity);
+                composition.preedit.clone_from(value);
+            }
+            Ime::Commit { value, window } => {
+                composition.clear();
+                if value.is_empty() {
+                    continue;
+                }
+
+                // type the committed text so that it goes in at the cursor
+                if let Ok(mut inactive) = inactive.get_mut(entity) {
+                    inactive.0 = false;
+                }
+                for state in [ButtonState::Pressed, ButtonState::Released] {
+                    keys.send(KeyboardInput {
+                        key_code: KeyCode::Unidentified(NativeKeyCode::Unidentified),
+                        logical_key: Key::Character(value.as_str().into()),
+                        state,
+                        window: *window,
+                    });
+                }
+            }
+            Ime::Enabled { .. } | Ime::Disabled { .. } => composition.clear(),
+        }
+    }
+
+    // keys are consumed by the ime while composing, so the text input must ignore them
+    if let Ok(entity) = focused.get_single() {
+        if let Ok(mut inactive) = inactive.get_mut(entity) {
+            if inactive.0 != composition.composing() {
+                inactive.0 = composition.composing();
+            }
+        }
+    }
+}
+
+fn update_composition_display(
+    mut commands: Commands,
+    composition: Res<ImeComposition>,
+    styles: Query<&TextInputTextStyle>,
+    parents: Query<&Parent>,
+    mut existing: Query<(Entity, &Parent, &mut Text), With<CompositionDisplay>>,
+) {
+    if !composition.is_changed() {
+        return;
+    }
+
+    let input = composition
+        .entity
+        .filter(|_| !composition.preedit.is_empty());
+    // the display is added to the `TextEntry`, the text input's children belong to the text input
+    let target = input.and_then(|input| parents.get(input).ok().map(Parent::get));
+
+    for (display, parent, mut text) in existing.iter_mut() {
+        if Some(parent.get()) == target {
+            text.sections[0].value.clone_from(&composition.preedit);
+        } else {
+            commands.entity(display).despawn_recursive();
+        }
+    }
+
+    let Some(target) = target else {
+        return;
+    };
+    if existing.iter().any(|(_, parent, _)| parent.get() == target) {
+        return;
+    }
+
+    let style = input
+        .and_then(|input| styles.get(input).ok())
+        .map(|style| style.0.clone())
+        .unwrap_or_default();
+    let underline = style.color;
+    let background = if Lcha::from(underline).lightness > 0.5 {
+        Color::BLACK.with_alpha(0.85)
+    } else {
+        Color::WHITE.with_alpha(0.85)
+    };
+
+    commands.entity(target).with_children(|c| {
+        c.spawn((
+            TextBundle {
+                text: Text::from_section(composition.preedit.clone(), style),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    bottom: Val::Percent(100.0),
+                    border: UiRect::bottom(Val::Px(1.0)),
+                    ..Default::default()
+                },
+                background_color: background.into(),
+                z_index: ZIndex::Global(1),
+                ..Default::default()
+            },
+            BorderColor(underline),
+            CompositionDisplay,
+        ));
+    });
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use bevy_ecss::PropertyValues;
-use common::structs::SystemAudio;
+use common::structs::{RumbleRequest, SystemAudio};
 
 use crate::{dui_utils::DuiFromStr, ui_actions::UiActionSet};
 
@@ -21,6 +21,7 @@ impl Plugin for InteractSoundsPlugin {
 fn play_interact_sounds(
     q: Query<(&InteractSounds, &Interaction), Changed<Interaction>>,
     mut writer: EventWriter<SystemAudio>,
+    mut rumble: EventWriter<RumbleRequest>,
 ) {
     for (sounds, act) in q.iter() {
         match (sounds, act) {
@@ -37,6 +38,9 @@ fn play_interact_sounds(
                 Interaction::Hovered,
             ) => {
                 writer.send(format!("sounds/ui/{}", sound).into());
+                if act == &Interaction::Pressed {
+                    rumble.send(RumbleRequest::pulse(0.0, 0.3, 0.05));
+                }
             }
             _ => (),
         }
@@ -1,9 +1,15 @@
+pub mod animation;
 pub mod bound_node;
 pub mod button;
 pub mod color_picker;
 pub mod combo_box;
+pub mod context_menu;
+pub mod drag_drop;
 pub mod dui_utils;
 pub mod focus;
+pub mod fonts;
+pub mod gamepad_nav;
+pub mod ime;
 pub mod interact_style;
 pub mod nine_slice;
 pub mod scrollable;
@@ -13,12 +19,16 @@ pub mod text_size;
 // pub mod textentry;
 pub mod interact_sounds;
 pub mod text_entry;
+pub mod theme;
 pub mod toggle;
+pub mod tooltip;
 pub mod ui_actions;
 pub mod ui_builder;
+pub mod virtual_list;
 
 use std::{any::type_name, marker::PhantomData};
 
+use animation::UiAnimationPlugin;
 use bevy::{
     asset::{DependencyLoadState, LoadState, RecursiveDependencyLoadState},
     ecs::schedule::SystemConfigs,
@@ -32,6 +42,10 @@ use bound_node::BoundedNodePlugin;
 use button::{DuiButtonSetTemplate, DuiButtonTemplate, DuiTabGroupTemplate};
 use color_picker::ColorPickerPlugin;
 use combo_box::ComboBoxPlugin;
+use context_menu::ContextMenuPlugin;
+use drag_drop::DragDropPlugin;
+use fonts::ExtraFontsPlugin;
+use gamepad_nav::GamepadNavPlugin;
 use interact_sounds::InteractSoundsPlugin;
 use nine_slice::Ui9SlicePlugin;
 use once_cell::sync::OnceCell;
@@ -41,7 +55,10 @@ use spinner::SpinnerPlugin;
 use stretch_uvs_image::StretchUvsImagePlugin;
 use text_entry::TextEntryPlugin;
 use text_size::TextSizePlugin;
+use theme::ThemePlugin;
 use toggle::TogglePlugin;
+use tooltip::TooltipPlugin;
+use virtual_list::VirtualListPlugin;
 
 use self::{
     focus::FocusPlugin, interact_style::InteractStylePlugin, scrollable::ScrollablePlugin,
@@ -82,6 +99,7 @@ impl Plugin for UiCorePlugin {
         app.add_plugins(EguiPlugin);
         app.add_plugins(UiActionPlugin);
         app.add_plugins(FocusPlugin);
+        app.add_plugins(GamepadNavPlugin);
         app.add_plugins(InteractStylePlugin);
         app.add_plugins(InteractSoundsPlugin);
         app.add_plugins(ScrollablePlugin);
@@ -93,6 +111,13 @@ impl Plugin for UiCorePlugin {
         app.add_plugins(TextEntryPlugin);
         app.add_plugins(SpinnerPlugin);
         app.add_plugins(ColorPickerPlugin);
+        app.add_plugins(TooltipPlugin);
+        app.add_plugins(ThemePlugin);
+        app.add_plugins(ExtraFontsPlugin);
+        app.add_plugins(UiAnimationPlugin);
+        app.add_plugins(VirtualListPlugin);
+        app.add_plugins(ContextMenuPlugin);
+        app.add_plugins(DragDropPlugin);
         app.init_state::<State>();
         app.init_resource::<StateTracker<State>>();
         app.add_systems(Startup, setup.in_set(SetupSets::Init));
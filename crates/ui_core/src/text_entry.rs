@@ -1,7 +1,8 @@
 use crate::{
     dui_utils::PropsExt,
     focus::{BlockKeyboard, Focusable},
-    text_size::FontSize,
+    ime::{ImeComposition, ImePlugin},
+    text_size::{text_scale, ChatText, FontSize},
     ui_actions::{DataChanged, Defocus, On, Submit, UiCaller},
 };
 use bevy::{
@@ -10,11 +11,11 @@ use bevy::{
 };
 use bevy_dui::{DuiRegistry, DuiTemplate};
 use bevy_simple_text_input::{
-    TextInputBundle, TextInputInactive, TextInputPlaceholder, TextInputPlugin,
+    TextInputBundle, TextInputCursorPos, TextInputInactive, TextInputPlaceholder, TextInputPlugin,
     TextInputSelectionStyle, TextInputSettings, TextInputSubmitEvent, TextInputSystem,
     TextInputTextStyle, TextInputValue,
 };
-use common::sets::SceneSets;
+use common::{sets::SceneSets, structs::AppConfig, util::is_steam_deck};
 
 use super::focus::Focus;
 
@@ -49,13 +50,16 @@ pub struct TextEntryPlugin;
 
 impl Plugin for TextEntryPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(TextInputPlugin);
+        app.add_plugins((TextInputPlugin, ImePlugin));
         app.add_systems(Startup, setup).add_systems(
             Update,
             (
                 update_text_entry_components,
+                record_history,
+                undo_redo,
                 pipe_events,
                 propagate_focus,
+                open_steam_keyboard,
                 update_fontsize,
             )
                 .chain()
@@ -132,6 +136,7 @@ pub fn update_text_entry_components(
                     },
                 ),
                 BlockKeyboard,
+                TextEntryHistory::new(&textbox.content),
             ));
 
             if textbox.text_style.is_none() {
@@ -142,9 +147,11 @@ pub fn update_text_entry_components(
 }
 
 pub fn update_fontsize(
-    mut q: Query<(&mut TextInputTextStyle, Ref<FontSize>)>,
+    mut q: Query<(&mut TextInputTextStyle, Ref<FontSize>, Has<ChatText>)>,
     mut resized: EventReader<WindowResized>,
     window: Query<&Window, With<PrimaryWindow>>,
+    config: Res<AppConfig>,
+    mut scales: Local<(f32, f32)>,
 ) {
     let resized = resized.read().last().is_some();
     let Ok(window) = window.get_single() else {
@@ -154,8 +161,15 @@ pub fn update_fontsize(
     if win_size <= 0.0 {
         return;
     }
-    for (mut text, size) in q.iter_mut().filter(|(_, sz)| resized || sz.is_changed()) {
-        text.0.font_size = win_size * size.0;
+    let new_scales = (config.text_scale, config.chat_text_scale);
+    let rescaled = *scales != new_scales;
+    *scales = new_scales;
+
+    for (mut text, size, chat) in q
+        .iter_mut()
+        .filter(|(_, sz, _)| resized || rescaled || sz.is_changed())
+    {
+        text.0.font_size = win_size * size.0 * text_scale(&config, chat);
     }
 }
 
@@ -164,6 +178,7 @@ fn propagate_focus(
     child: Query<Entity, With<TextInputSettings>>,
     focussed_text: Query<Entity, (With<TextInputSettings>, With<Focus>)>,
     key_events: Res<ButtonInput<KeyCode>>,
+    composition: Res<ImeComposition>,
     mut commands: Commands,
 ) {
     for (textbox, children) in q.iter() {
@@ -176,12 +191,138 @@ fn propagate_focus(
     }
 
     if let Ok(focussed_text) = focussed_text.get_single() {
-        if key_events.just_pressed(KeyCode::Escape) {
+        // escape cancels an ime composition rather than leaving the entry
+        if key_events.just_pressed(KeyCode::Escape) && !composition.composing() {
             commands.entity(focussed_text).remove::<Focus>();
         }
     }
 }
 
+// steam only raises its on-screen keyboard for its own text fields, so we request it when a text
+// entry is focused on the deck
+fn open_steam_keyboard(focussed_text: Query<(), (With<TextInputSettings>, Added<Focus>)>) {
+    if focussed_text.is_empty() || !is_steam_deck() {
+        return;
+    }
+
+    if let Err(e) = opener::open("steam://open/keyboard") {
+        warn!("failed to open steam keyboard: {e}");
+    }
+}
+
+// undo / redo for text entries. cursor movement, shift-selection and clipboard copy/cut/paste are
+// handled by the text input itself (with its `clipboard` feature, using the same copypasta
+// provider as chat message copying)
+const UNDO_LIMIT: usize = 100;
+// consecutive single character edits closer together than this are undone together
+const UNDO_MERGE_SECONDS: f32 = 1.0;
+
+#[derive(Component)]
+pub struct TextEntryHistory {
+    current: String,
+    undo: Vec<String>,
+    redo: Vec<String>,
+    last_edit: f32,
+}
+
+impl TextEntryHistory {
+    fn new(content: &str) -> Self {
+        Self {
+            current: content.to_owned(),
+            undo: Default::default(),
+            redo: Default::default(),
+            last_edit: f32::NEG_INFINITY,
+        }
+    }
+
+    fn record(&mut self, value: &str, now: f32) {
+        if value == self.current {
+            return;
+        }
+
+        let small_edit = self.current.chars().count().abs_diff(value.chars().count()) == 1;
+        let merge =
+            small_edit && now - self.last_edit < UNDO_MERGE_SECONDS && !self.undo.is_empty();
+        if !merge {
+            self.undo
+                .push(std::mem::replace(&mut self.current, value.to_owned()));
+            if self.undo.len() > UNDO_LIMIT {
+                self.undo.remove(0);
+            }
+        } else {
+            value.clone_into(&mut self.current);
+        }
+        self.redo.clear();
+        self.last_edit = now;
+    }
+
+    fn undo(&mut self) -> Option<&str> {
+        let previous = self.undo.pop()?;
+        self.redo
+            .push(std::mem::replace(&mut self.current, previous));
+        self.last_edit = f32::NEG_INFINITY;
+        Some(&self.current)
+    }
+
+    fn redo(&mut self) -> Option<&str> {
+        let next = self.redo.pop()?;
+        self.undo.push(std::mem::replace(&mut self.current, next));
+        self.last_edit = f32::NEG_INFINITY;
+        Some(&self.current)
+    }
+}
+
+fn record_history(
+    mut q: Query<(&TextInputValue, &mut TextEntryHistory), Changed<TextInputValue>>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_seconds();
+    for (value, mut history) in q.iter_mut() {
+        history.record(&value.0, now);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn undo_redo(
+    mut q: Query<
+        (
+            &mut TextInputValue,
+            &mut TextInputCursorPos,
+            &mut TextEntryHistory,
+        ),
+        With<Focus>,
+    >,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let command = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+    if !command {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let undo = keys.just_pressed(KeyCode::KeyZ) && !shift;
+    let redo = keys.just_pressed(KeyCode::KeyY) || (keys.just_pressed(KeyCode::KeyZ) && shift);
+
+    for (mut value, mut cursor, mut history) in q.iter_mut() {
+        let restored = if undo {
+            history.undo()
+        } else if redo {
+            history.redo()
+        } else {
+            None
+        };
+
+        if let Some(restored) = restored {
+            restored.clone_into(&mut value.0);
+            cursor.0 = restored.chars().count();
+        }
+    }
+}
+
 fn pipe_events(
     mut submit: EventReader<TextInputSubmitEvent>,
     changed: Query<(Entity, &TextInputValue), Changed<TextInputValue>>,
@@ -264,6 +405,10 @@ impl DuiTemplate for DuiTextEntryTemplate {
             commands.insert(onchanged);
         }
 
+        if let Some(onsubmit) = props.take::<On<Submit>>("onsubmit")? {
+            commands.insert(onsubmit);
+        }
+
         Ok(Default::default())
     }
 }
@@ -5,7 +5,7 @@ use bevy::{
 };
 use bevy_dui::{DuiEntityCommandsExt, DuiProps, DuiRegistry, DuiTemplate};
 use bevy_egui::EguiSettings;
-use common::util::ModifyComponentExt;
+use common::{structs::AppConfig, util::ModifyComponentExt};
 
 use crate::{
     dui_utils::PropsExt,
@@ -43,6 +43,9 @@ impl DuiTemplate for TextTemplate {
         ctx: &mut bevy_dui::DuiContext,
     ) -> Result<bevy_dui::NodeMap, anyhow::Error> {
         commands.insert(FontSize(self.0));
+        if props.take_as::<bool>(ctx, "chat")?.unwrap_or(false) {
+            commands.insert(ChatText);
+        }
         let wrap = props.take_as::<bool>(ctx, "wrap")?.unwrap_or(true);
         commands.modify_component(move |text: &mut Text| {
             text.linebreak_behavior = if wrap {
@@ -119,11 +122,22 @@ impl DuiTemplate for LinkTemplate {
 #[derive(Component)]
 pub struct FontSize(pub f32);
 
+// text that follows the chat text scale as well as the interface text scale
+#[derive(Component)]
+pub struct ChatText;
+
+// the user's text size multiplier
+pub fn text_scale(config: &AppConfig, chat: bool) -> f32 {
+    config.text_scale * if chat { config.chat_text_scale } else { 1.0 }
+}
+
 pub fn update_fontsize(
-    mut q: Query<(&mut Text, Ref<FontSize>)>,
+    mut q: Query<(&mut Text, Ref<FontSize>, Has<ChatText>)>,
     mut resized: EventReader<WindowResized>,
     window: Query<&Window, With<PrimaryWindow>>,
     mut egui_settings: ResMut<EguiSettings>,
+    config: Res<AppConfig>,
+    mut scales: Local<(f32, f32)>,
 ) {
     let resized = resized.read().last().is_some();
     let Ok(window) = window.get_single() else {
@@ -133,7 +147,15 @@ pub fn update_fontsize(
     if win_size <= 0.0 {
         return;
     }
-    for (mut text, size) in q.iter_mut().filter(|(_, sz)| resized || sz.is_changed()) {
+    let new_scales = (config.text_scale, config.chat_text_scale);
+    let rescaled = *scales != new_scales;
+    *scales = new_scales;
+
+    for (mut text, size, chat) in q
+        .iter_mut()
+        .filter(|(_, sz, _)| resized || rescaled || sz.is_changed())
+    {
+        let font_size = win_size * size.0 * text_scale(&config, chat);
         if size.is_added() {
             let raw_text = text
                 .sections
@@ -145,13 +167,13 @@ pub fn update_fontsize(
             let new_sections = make_text_sections(
                 &raw_text,
                 FontName::Sans,
-                win_size * size.0,
+                font_size,
                 text.sections[0].style.color,
             );
             text.sections = new_sections;
         } else {
             for section in &mut text.sections {
-                section.style.font_size = win_size * size.0;
+                section.style.font_size = font_size;
             }
         }
     }
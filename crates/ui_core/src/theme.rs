@@ -0,0 +1,170 @@
+// colors that carry meaning in the ui (accents, item rarity, map markers, nametags), adjusted for
+// the configured `ColorblindMode`. the palette is exposed to dui templates as default props
+// (`theme-accent`, `theme-marker-pin`, ...) so templates pick it up when they are spawned, and as
+// the `UiTheme` resource for colors set from code.
+
+use bevy::prelude::*;
+use bevy_dui::DuiRegistry;
+use common::structs::{AppConfig, ColorblindMode};
+
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct UiTheme {
+    pub mode: ColorblindMode,
+    // highlights, focus outlines and notable text
+    pub accent: Color,
+    // avatar nametag background
+    pub nametag: Color,
+    // item rarities, from free to unique
+    pub rarity: [Color; 8],
+    pub marker_pin: Color,
+    pub marker_scene_pin: Color,
+    pub marker_poi: Color,
+    pub marker_event: Color,
+    pub marker_friend: Color,
+    pub marker_cluster: Color,
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self::new(ColorblindMode::Off)
+    }
+}
+
+impl UiTheme {
+    pub fn new(mode: ColorblindMode) -> Self {
+        let rgb = |hex: u32| {
+            Color::srgb_u8(
+                (hex >> 16) as u8,
+                ((hex >> 8) & 0xff) as u8,
+                (hex & 0xff) as u8,
+            )
+        };
+        let grey_rarities = [rgb(0xe6e6e6), rgb(0xb3b3b3)];
+
+        match mode {
+            ColorblindMode::Off => Self {
+                mode,
+                accent: rgb(0xffcc33),
+                nametag: Color::srgba_u8(0xaa, 0xc1, 0x1d, 0x66),
+                rarity: [
+                    grey_rarities[0],
+                    grey_rarities[1],
+                    rgb(0xffcc66),
+                    rgb(0x99ff99),
+                    rgb(0x9999ff),
+                    rgb(0xcc66cc),
+                    rgb(0xff99ff),
+                    rgb(0xffff66),
+                ],
+                marker_pin: rgb(0xe63333),
+                marker_scene_pin: rgb(0xcc4de6),
+                marker_poi: rgb(0xffcc33),
+                marker_event: rgb(0x4d99ff),
+                marker_friend: rgb(0x4de666),
+                marker_cluster: rgb(0xd9d9d9),
+            },
+            // red and green are confused, so meaning is carried by blue / orange / yellow and by
+            // lightness. protanopes also see reds darker, so their reds are shifted to orange.
+            ColorblindMode::Deuteranopia | ColorblindMode::Protanopia => {
+                let orange = if mode == ColorblindMode::Protanopia {
+                    rgb(0xffb000)
+                } else {
+                    rgb(0xe69f00)
+                };
+                Self {
+                    mode,
+                    accent: rgb(0xffcc33),
+                    nametag: Color::srgba_u8(0x00, 0x72, 0xb2, 0x66),
+                    rarity: [
+                        grey_rarities[0],
+                        grey_rarities[1],
+                        orange,
+                        rgb(0x56b4e9),
+                        rgb(0x0072b2),
+                        rgb(0x8c4da6),
+                        rgb(0xf2b3e6),
+                        rgb(0xf0e442),
+                    ],
+                    marker_pin: orange,
+                    marker_scene_pin: rgb(0xcc79a7),
+                    marker_poi: rgb(0xf0e442),
+                    marker_event: rgb(0x0072b2),
+                    marker_friend: rgb(0x56b4e9),
+                    marker_cluster: rgb(0xd9d9d9),
+                }
+            }
+            // blue and yellow are confused, so meaning is carried by red / cyan / magenta
+            ColorblindMode::Tritanopia => Self {
+                mode,
+                accent: rgb(0xff5c7a),
+                nametag: Color::srgba_u8(0xd8, 0x1b, 0x60, 0x66),
+                rarity: [
+                    grey_rarities[0],
+                    grey_rarities[1],
+                    rgb(0xff9999),
+                    rgb(0x00b3b3),
+                    rgb(0x66e6ff),
+                    rgb(0xb30059),
+                    rgb(0xff99cc),
+                    rgb(0xe60000),
+                ],
+                marker_pin: rgb(0xe63333),
+                marker_scene_pin: rgb(0xff99cc),
+                marker_poi: rgb(0x008080),
+                marker_event: rgb(0x66e6ff),
+                marker_friend: rgb(0xb30059),
+                marker_cluster: rgb(0xd9d9d9),
+            },
+        }
+    }
+
+    fn props(&self) -> [(&'static str, Color); 8] {
+        [
+            ("theme-accent", self.accent),
+            ("theme-nametag", self.nametag),
+            ("theme-marker-pin", self.marker_pin),
+            ("theme-marker-scene-pin", self.marker_scene_pin),
+            ("theme-marker-poi", self.marker_poi),
+            ("theme-marker-event", self.marker_event),
+            ("theme-marker-friend", self.marker_friend),
+            ("theme-marker-cluster", self.marker_cluster),
+        ]
+    }
+}
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiTheme>();
+        app.add_systems(Startup, setup);
+        app.add_systems(Update, update_theme);
+    }
+}
+
+fn setup(theme: Res<UiTheme>, mut dui: ResMut<DuiRegistry>) {
+    set_theme_props(&theme, &mut dui);
+}
+
+fn set_theme_props(theme: &UiTheme, dui: &mut DuiRegistry) {
+    // as strings so they can be used in both attributes and styles
+    for (name, color) in theme.props() {
+        dui.set_default_prop(name, color.to_srgba().to_hex());
+    }
+}
+
+fn update_theme(
+    config: Option<Res<AppConfig>>,
+    mut theme: ResMut<UiTheme>,
+    mut dui: ResMut<DuiRegistry>,
+) {
+    let Some(config) = config.filter(|config| config.is_changed()) else {
+        return;
+    };
+    if theme.mode == config.colorblind_mode {
+        return;
+    }
+
+    *theme = UiTheme::new(config.colorblind_mode);
+    set_theme_props(&theme, &mut dui);
+}
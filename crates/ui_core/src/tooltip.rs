@@ -0,0 +1,85 @@
+// hover tooltips for any ui node. the text is shown after `TOOLTIP_DELAY` while the node is
+// hovered, or straight away while it has gamepad/keyboard focus. lines are separated by `\n`, and
+// are greyed out while the node is disabled. drawing is left to the system ui via `ToolTips`.
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use common::structs::{ToolTips, TooltipSource};
+
+use crate::{gamepad_nav::GamepadFocus, ui_actions::Enabled};
+
+pub const TOOLTIP_DELAY: f32 = 0.5;
+
+#[derive(Component, Clone, Debug)]
+pub struct Tooltip(pub String);
+
+pub struct TooltipPlugin;
+
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ToolTips>();
+        app.add_systems(Update, (add_interaction, update_tooltips).chain());
+    }
+}
+
+fn add_interaction(
+    mut commands: Commands,
+    new: Query<Entity, (Added<Tooltip>, Without<Interaction>)>,
+) {
+    for entity in new.iter() {
+        commands.entity(entity).try_insert(Interaction::default());
+    }
+}
+
+fn update_tooltips(
+    mut tips: ResMut<ToolTips>,
+    nodes: Query<(Entity, &Tooltip, &Interaction, Option<&Enabled>)>,
+    focus: Res<GamepadFocus>,
+    time: Res<Time>,
+    mut hover_time: Local<HashMap<Entity, f32>>,
+    mut shown: Local<HashSet<Entity>>,
+) {
+    let focused = focus.entity.filter(|_| focus.active);
+    let mut live = HashSet::default();
+
+    for (entity, tooltip, interaction, enabled) in nodes.iter() {
+        live.insert(entity);
+
+        let hovered = if interaction == &Interaction::None {
+            hover_time.remove(&entity);
+            false
+        } else {
+            let elapsed = hover_time.entry(entity).or_default();
+            *elapsed += time.delta_seconds();
+            *elapsed >= TOOLTIP_DELAY
+        };
+
+        let source = TooltipSource::Entity(entity);
+        if hovered || focused == Some(entity) {
+            let enabled = enabled.map_or(true, |e| e.0);
+            tips.0.insert(
+                source,
+                tooltip
+                    .0
+                    .lines()
+                    .map(|line| (line.to_owned(), enabled))
+                    .collect(),
+            );
+            shown.insert(entity);
+        } else if shown.remove(&entity) {
+            tips.0.remove(&source);
+        }
+    }
+
+    // nodes that were despawned or lost their tooltip
+    hover_time.retain(|entity, _| live.contains(entity));
+    shown.retain(|entity| {
+        let keep = live.contains(entity);
+        if !keep {
+            tips.0.remove(&TooltipSource::Entity(*entity));
+        }
+        keep
+    });
+}
@@ -17,7 +17,7 @@ use bevy::{
 
 use common::{sets::SceneSets, structs::SystemAudio, util::FireEventEx};
 
-use super::focus::Focus;
+use super::{animation::DespawnAnimatedExt, drag_drop::Dropped, focus::Focus};
 
 #[derive(Component)]
 pub struct Enabled(pub bool);
@@ -44,6 +44,7 @@ impl Plugin for UiActionPlugin {
             .init_resource::<UiActions<Dragged>>()
             .init_resource::<UiActions<ClickNoDrag>>()
             .init_resource::<UiActions<MouseWheeled>>()
+            .init_resource::<UiActions<Dropped>>()
             .add_systems(
                 PreUpdate,
                 (
@@ -59,6 +60,7 @@ impl Plugin for UiActionPlugin {
                         gather_actions::<Dragged>,
                         gather_actions::<ClickNoDrag>,
                         gather_actions::<MouseWheeled>,
+                        gather_actions::<Dropped>,
                     )
                         .chain(),
                     apply_deferred,
@@ -72,6 +74,7 @@ impl Plugin for UiActionPlugin {
                         run_actions::<Dragged>,
                         run_actions::<ClickNoDrag>,
                         run_actions::<MouseWheeled>,
+                        run_actions::<Dropped>,
                     )
                         .chain(),
                 )
@@ -115,8 +118,8 @@ pub fn close_ui_silent(mut commands: Commands, parents: Query<&Parent>, c: Res<U
     while let Ok(p) = parents.get(ent) {
         ent = **p;
     }
-    if let Some(commands) = commands.get_entity(ent) {
-        commands.despawn_recursive();
+    if let Some(mut commands) = commands.get_entity(ent) {
+        commands.despawn_animated();
     }
 }
 
@@ -0,0 +1,237 @@
+// a list container that only instantiates the rows on screen, for collections too large to spawn
+// in full. the list node is sized for the whole collection so scrollbars are right, and items are
+// fixed size slots positioned absolutely within it, `columns` to a line when the item width is
+// less than the list width. slots that scroll out of view go back to a pool and are reused for
+// items scrolling in: their children are despawned and `populate` is called to fill them again.
+// the visible area is the nearest `Scrollable` ancestor, so the list can be one part of a larger
+// scrolled panel.
+
+use std::sync::Arc;
+
+use bevy::{prelude::*, utils::HashMap, window::PrimaryWindow};
+use bevy_dui::DuiRegistry;
+
+use crate::scrollable::Scrollable;
+
+// rows spawned beyond each edge of the visible area
+const DEFAULT_OVERSCAN: usize = 2;
+
+pub type PopulateFn = Arc<dyn Fn(&mut Commands, &DuiRegistry, Entity, usize) + Send + Sync>;
+
+#[derive(Component)]
+pub struct VirtualList {
+    len: usize,
+    item_width: Val,
+    item_height: Val,
+    overscan: usize,
+    populate: PopulateFn,
+    dirty: bool,
+    // item index -> slot
+    visible: HashMap<usize, Entity>,
+    pool: Vec<Entity>,
+    // the slot layout visible items were placed with
+    layout: Option<(usize, Vec2)>,
+}
+
+impl VirtualList {
+    // `populate` fills the slot for the given item index. rows span the list's width unless
+    // `with_item_width` is used
+    pub fn new(
+        len: usize,
+        item_height: Val,
+        populate: impl Fn(&mut Commands, &DuiRegistry, Entity, usize) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            len,
+            item_width: Val::Percent(100.0),
+            item_height,
+            overscan: DEFAULT_OVERSCAN,
+            populate: Arc::new(populate),
+            dirty: false,
+            visible: Default::default(),
+            pool: Default::default(),
+            layout: None,
+        }
+    }
+
+    pub fn with_item_width(mut self, item_width: Val) -> Self {
+        self.item_width = item_width;
+        self
+    }
+
+    pub fn with_overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // replace the contents. visible slots are repopulated
+    pub fn set_items(
+        &mut self,
+        len: usize,
+        populate: impl Fn(&mut Commands, &DuiRegistry, Entity, usize) + Send + Sync + 'static,
+    ) {
+        self.len = len;
+        self.populate = Arc::new(populate);
+        self.dirty = true;
+    }
+
+    // repopulate the visible slots, after the underlying data changes
+    pub fn refresh(&mut self) {
+        self.dirty = true;
+    }
+}
+
+// the item index a slot currently shows
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VirtualRow(pub usize);
+
+pub struct VirtualListPlugin;
+
+impl Plugin for VirtualListPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_virtual_lists);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_virtual_lists(
+    mut commands: Commands,
+    mut lists: Query<(
+        Entity,
+        &mut VirtualList,
+        &Node,
+        &GlobalTransform,
+        &mut Style,
+    )>,
+    mut slots: Query<&mut Style, (With<VirtualRow>, Without<VirtualList>)>,
+    clips: Query<(&Node, &GlobalTransform), With<Scrollable>>,
+    parents: Query<&Parent>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    dui: Res<DuiRegistry>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+
+    for (entity, mut list, node, transform, mut style) in lists.iter_mut() {
+        let list = &mut *list;
+        let size = node.size();
+        let item_size = Vec2::new(
+            list.item_width
+                .resolve(size.x, window_size)
+                .unwrap_or(size.x),
+            list.item_height.resolve(size.y, window_size).unwrap_or(0.0),
+        )
+        .max(Vec2::ONE);
+        let columns = ((size.x / item_size.x).floor() as usize).max(1);
+        let rows = list.len.div_ceil(columns);
+
+        let height = Val::Px(rows as f32 * item_size.y);
+        if style.height != height {
+            style.height = height;
+            style.min_height = height;
+        }
+
+        // the visible part of the list, in list-local pixels
+        let top = transform.translation().y - size.y * 0.5;
+        let (clip_top, clip_bottom) = parents
+            .iter_ancestors(entity)
+            .find_map(|ancestor| clips.get(ancestor).ok())
+            .map(|(clip, clip_transform)| {
+                let center = clip_transform.translation().y;
+                (center - clip.size().y * 0.5, center + clip.size().y * 0.5)
+            })
+            .unwrap_or((0.0, window_size.y));
+        let visible_top = (clip_top - top).max(0.0);
+        let visible_bottom = (clip_bottom - top).min(size.y);
+
+        let wanted = if node.size() == Vec2::ZERO || visible_bottom <= visible_top {
+            0..0
+        } else {
+            let first_row =
+                ((visible_top / item_size.y).floor() as usize).saturating_sub(list.overscan);
+            let last_row = (visible_bottom / item_size.y).ceil() as usize + list.overscan;
+            (first_row * columns).min(list.len)..(last_row * columns).min(list.len)
+        };
+
+        let relayout = list.layout != Some((columns, item_size));
+        list.layout = Some((columns, item_size));
+
+        // return slots that went out of view, or all of them if the data changed
+        let dirty = std::mem::take(&mut list.dirty);
+        let released = list
+            .visible
+            .iter()
+            .filter(|(index, _)| dirty || !wanted.contains(*index))
+            .map(|(index, slot)| (*index, *slot))
+            .collect::<Vec<_>>();
+        for (index, slot) in released {
+            list.visible.remove(&index);
+            if let Ok(mut slot_style) = slots.get_mut(slot) {
+                slot_style.display = Display::None;
+            }
+            commands.entity(slot).despawn_descendants();
+            list.pool.push(slot);
+        }
+
+        let place = |style: &mut Style, index: usize| {
+            style.display = Display::Flex;
+            style.position_type = PositionType::Absolute;
+            style.left = Val::Px((index % columns) as f32 * item_size.x);
+            style.top = Val::Px((index / columns) as f32 * item_size.y);
+            style.width = Val::Px(item_size.x);
+            style.height = Val::Px(item_size.y);
+        };
+
+        if relayout {
+            for (index, slot) in list.visible.iter() {
+                if let Ok(mut slot_style) = slots.get_mut(*slot) {
+                    place(&mut slot_style, *index);
+                }
+            }
+        }
+
+        for index in wanted {
+            if list.visible.contains_key(&index) {
+                continue;
+            }
+
+            let slot = match list.pool.pop() {
+                Some(slot) => {
+                    if let Ok(mut slot_style) = slots.get_mut(slot) {
+                        place(&mut slot_style, index);
+                    }
+                    commands.entity(slot).insert(VirtualRow(index));
+                    slot
+                }
+                None => {
+                    let mut slot_style = Style::default();
+                    place(&mut slot_style, index);
+                    let slot = commands
+                        .spawn((
+                            NodeBundle {
+                                style: slot_style,
+                                ..Default::default()
+                            },
+                            VirtualRow(index),
+                        ))
+                        .id();
+                    commands.entity(entity).add_child(slot);
+                    slot
+                }
+            };
+
+            (list.populate)(&mut commands, &dui, slot, index);
+            list.visible.insert(index, slot);
+        }
+    }
+}
@@ -13,12 +13,14 @@ use bevy::{
 };
 
 use common::{
+    dynamics::PLAYER_GROUND_THRESHOLD,
     structs::{
         ActiveDialog, CameraOverride, CursorLocked, CursorLocks, PrimaryCamera, PrimaryUser,
+        RumbleRequest, TestCamera,
     },
-    util::ModifyComponentExt,
+    util::{FireEventEx, ModifyComponentExt},
 };
-use input_manager::AcceptInput;
+use input_manager::{axes::AnalogInput, touch::TouchInput, AcceptInput};
 use scene_runner::{
     renderer_context::RendererSceneContext, update_world::mesh_collider::SceneColliderData,
     ContainingScene,
@@ -28,6 +30,11 @@ use ui_core::scrollable::UsedScrollWheel;
 
 use crate::TRANSITION_TIME;
 
+// radians per pixel of mouse motion at sensitivity 1
+pub(crate) const MOUSE_LOOK_SCALE: f32 = 0.005;
+// radians per second at full right stick deflection and sensitivity 1
+pub(crate) const STICK_LOOK_SPEED: f32 = PI;
+
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub struct CinematicInitialData {
     base_yaw: f32,
@@ -99,6 +106,8 @@ pub fn update_camera(
     mut cinematic_data: Local<Option<CinematicInitialData>>,
     mut mb_state: MouseInteractionState,
     gt_helper: TransformHelper,
+    touch: Res<TouchInput>,
+    analog: AnalogInput,
 ) {
     let dt = time.delta_seconds();
 
@@ -192,7 +201,7 @@ pub fn update_camera(
         }
 
         for mouse_event in mouse_events.read() {
-            mouse_delta += mouse_event.delta;
+            mouse_delta += analog.mouse(mouse_event.delta);
         }
     } else {
         locks.0.remove("camera");
@@ -201,6 +210,13 @@ pub fn update_camera(
         }
     }
 
+    // the right stick turns at a rate rather than by a distance
+    let mut stick_delta = Vec2::ZERO;
+    if !in_dialog {
+        mouse_delta += touch.look_delta;
+        stick_delta = analog.look_stick() * STICK_LOOK_SPEED * dt;
+    }
+
     if allow_cam_move {
         if state == ClickState::Clicked {
             *move_toggled = !*move_toggled;
@@ -221,9 +237,9 @@ pub fn update_camera(
             }
         }
 
-        options.pitch = (options.pitch - mouse_delta.y * options.sensitivity / 1000.0)
+        options.pitch = (options.pitch - mouse_delta.y * MOUSE_LOOK_SCALE + stick_delta.y)
             .clamp(-PI / 2.1, PI / 2.1);
-        options.yaw -= mouse_delta.x * options.sensitivity / 1000.0;
+        options.yaw -= mouse_delta.x * MOUSE_LOOK_SCALE + stick_delta.x;
         if accept_input.mouse && !used_wheel.0 {
             if let Some(event) = wheel_events.read().last() {
                 if (event.y > 0.0) == zoom_range.is_none() {
@@ -249,7 +265,22 @@ pub fn update_camera(
     }
 }
 
-#[allow(clippy::type_complexity)]
+// limits for scene-requested (cinematic) fields of view
+const MIN_SCENE_FOV: f32 = 10.0 * PI / 180.0;
+const MAX_SCENE_FOV: f32 = 120.0 * PI / 180.0;
+
+// minimum fall speed (m/s) that shakes the camera on landing
+const SHAKE_FALL_SPEED: f32 = 8.0;
+const SHAKE_DURATION: f32 = 0.4;
+
+#[derive(Default)]
+pub struct CameraEffects {
+    bob_phase: f32,
+    shake: f32,
+    prev_vertical_velocity: f32,
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn update_camera_position(
     mut commands: Commands,
     mut camera: Query<(
@@ -267,6 +298,8 @@ pub fn update_camera_position(
     mut scene_colliders: Query<(&RendererSceneContext, &mut SceneColliderData)>,
     mut prev_override: Local<Option<CameraOverride>>,
     gt_helper: TransformHelper,
+    time: Res<Time>,
+    mut effects: Local<CameraEffects>,
 ) {
     let (
         Ok((player_transform, dynamic_state)),
@@ -289,7 +322,7 @@ pub fn update_camera_position(
         target_transform.translation = translation;
         target_transform.rotation =
             rotation * Quat::from_euler(EulerRot::YXZ, options.yaw, options.pitch, options.roll);
-        let target_fov = FRAC_PI_4 * 1.25 / options.distance;
+        let target_fov = (FRAC_PI_4 * 1.25 / options.distance).clamp(MIN_SCENE_FOV, MAX_SCENE_FOV);
         let Projection::Perspective(PerspectiveProjection { ref mut fov, .. }) = &mut *projection
         else {
             panic!();
@@ -298,7 +331,14 @@ pub fn update_camera_position(
             *fov = target_fov;
         }
     } else {
-        let target_fov = (dynamic_state.velocity.length() / 4.0).clamp(1.25, 1.25) * FRAC_PI_4;
+        let distance = match options.scene_override {
+            Some(CameraOverride::Distance(d)) => d,
+            _ => options.distance,
+        };
+
+        // blend between first and third person fov as we zoom
+        let target_fov = options.fov_first_person
+            + (options.fov_third_person - options.fov_first_person) * distance.clamp(0.0, 1.0);
         if let Projection::Perspective(PerspectiveProjection { ref mut fov, .. }) = &mut *projection
         {
             if *fov != target_fov {
@@ -306,11 +346,6 @@ pub fn update_camera_position(
             }
         };
 
-        let distance = match options.scene_override {
-            Some(CameraOverride::Distance(d)) => d,
-            _ => options.distance,
-        };
-
         target_transform.rotation =
             Quat::from_euler(EulerRot::YXZ, options.yaw, options.pitch, options.roll);
 
@@ -357,6 +392,19 @@ pub fn update_camera_position(
         }
 
         target_transform.translation = player_head + target_direction * distance;
+
+        let impact = apply_camera_effects(
+            &mut target_transform,
+            &mut effects,
+            options,
+            dynamic_state,
+            time.delta_seconds(),
+        );
+        if impact > 0.0 {
+            commands.fire_event(
+                RumbleRequest::pulse(impact, impact * 0.5, 0.1).with_envelope(0.0, SHAKE_DURATION),
+            );
+        }
     }
 
     if prev_override.as_ref().map(std::mem::discriminant)
@@ -378,6 +426,75 @@ pub fn update_camera_position(
     }
 }
 
+pub(crate) fn in_test_camera(test_camera: Res<TestCamera>) -> bool {
+    test_camera.0.is_some()
+}
+
+// places the camera where scene test code asked, without transitions
+pub(crate) fn apply_test_camera(
+    mut commands: Commands,
+    test_camera: Res<TestCamera>,
+    mut camera: Query<(Entity, &mut Transform), With<PrimaryCamera>>,
+) {
+    let (Some(target), Ok((camera_ent, mut transform))) = (test_camera.0, camera.get_single_mut())
+    else {
+        return;
+    };
+
+    if *transform != target {
+        commands.entity(camera_ent).remove::<SystemTween>();
+        *transform = target;
+    }
+}
+
+fn apply_camera_effects(
+    transform: &mut Transform,
+    effects: &mut CameraEffects,
+    options: &PrimaryCamera,
+    dynamic_state: &AvatarDynamicState,
+    dt: f32,
+) -> f32 {
+    let grounded = dynamic_state.ground_height < PLAYER_GROUND_THRESHOLD;
+
+    // head bob, first person only
+    let horizontal_speed = (dynamic_state.velocity * Vec3::new(1.0, 0.0, 1.0)).length();
+    if grounded && horizontal_speed > 0.1 && options.distance < 0.1 {
+        effects.bob_phase = (effects.bob_phase + dt * horizontal_speed * 2.0) % (2.0 * PI);
+    } else {
+        // settle back to the nearest rest position
+        let rest = (effects.bob_phase / PI).round() * PI;
+        effects.bob_phase += (rest - effects.bob_phase) * (dt * 10.0).min(1.0);
+    }
+    let bob_scale = options.head_bob * (horizontal_speed / 8.0).min(1.0);
+    transform.translation += transform.rotation.mul_vec3(Vec3::new(
+        effects.bob_phase.sin() * 0.03,
+        (effects.bob_phase * 2.0).sin().abs() * 0.05,
+        0.0,
+    )) * bob_scale;
+
+    // shake on hard landings
+    let mut impact = 0.0;
+    if grounded && effects.prev_vertical_velocity < -SHAKE_FALL_SPEED {
+        impact = ((-effects.prev_vertical_velocity - SHAKE_FALL_SPEED) / SHAKE_FALL_SPEED).min(1.0);
+        effects.shake = (effects.shake + impact).min(1.0);
+    }
+    effects.prev_vertical_velocity = dynamic_state.velocity.y;
+
+    if effects.shake > 0.0 {
+        let amount = effects.shake * options.camera_shake * 0.05;
+        let t = effects.shake * SHAKE_DURATION * 60.0;
+        transform.rotation *= Quat::from_euler(
+            EulerRot::YXZ,
+            t.sin() * amount * 0.5,
+            (t * 1.3).cos() * amount,
+            0.0,
+        );
+        effects.shake = (effects.shake - dt / SHAKE_DURATION).max(0.0);
+    }
+
+    impact
+}
+
 pub fn update_cursor_lock(
     locks: Res<CursorLocks>,
     mut windows: Query<&mut Window, With<PrimaryWindow>>,
@@ -1,5 +1,6 @@
 pub mod camera;
 pub mod dynamics;
+pub mod photo_mode;
 pub mod player_input;
 
 use bevy::{
@@ -13,7 +14,10 @@ use camera::update_cursor_lock;
 use common::{
     anim_last_system,
     sets::SceneSets,
-    structs::{CursorLocks, PrimaryCamera, PrimaryUser, PRIMARY_AVATAR_LIGHT_LAYER_INDEX},
+    structs::{
+        CursorLocks, PhotoMode, PrimaryCamera, PrimaryUser, TestCamera,
+        PRIMARY_AVATAR_LIGHT_LAYER_INDEX,
+    },
 };
 use console::DoAddConsoleCommand;
 use dynamics::{
@@ -30,8 +34,9 @@ use scene_runner::{
 };
 
 use self::{
-    camera::{update_camera, update_camera_position},
+    camera::{apply_test_camera, in_test_camera, update_camera, update_camera_position},
     dynamics::update_user_position,
+    photo_mode::{in_photo_mode, update_photo_camera},
     player_input::update_user_velocity,
 };
 
@@ -45,8 +50,11 @@ impl Plugin for UserInputPlugin {
         app.add_systems(
             Update,
             (
-                update_user_velocity.run_if(should_accept_key),
-                update_camera,
+                update_user_velocity
+                    .run_if(should_accept_key)
+                    .run_if(not(in_photo_mode)),
+                update_camera.run_if(not(in_photo_mode)),
+                update_photo_camera,
             )
                 .chain()
                 .in_set(SceneSets::Input),
@@ -62,6 +70,8 @@ impl Plugin for UserInputPlugin {
                     .before(parent_position_sync::<SceneProxyStage>)
                     .before(TransformSystem::TransformPropagate),
                 update_camera_position
+                    .run_if(not(in_photo_mode))
+                    .run_if(not(in_test_camera))
                     .after(anim_last_system!())
                     .after(GltfLinkSet)
                     .after(update_user_position)
@@ -70,10 +80,17 @@ impl Plugin for UserInputPlugin {
                     .before(TransformSystem::TransformPropagate)
                     .before(CameraUpdateSystem),
                 update_cursor_lock.after(update_camera_position),
+                apply_test_camera
+                    .run_if(in_test_camera)
+                    .after(update_camera_position)
+                    .before(TransformSystem::TransformPropagate)
+                    .before(CameraUpdateSystem),
             ),
         );
         app.insert_resource(UserClipping(true))
-            .init_resource::<CursorLocks>();
+            .init_resource::<CursorLocks>()
+            .init_resource::<PhotoMode>()
+            .init_resource::<TestCamera>();
         app.add_console_command::<NoClipCommand, _>(no_clip);
         app.add_console_command::<SpeedCommand, _>(speed_cmd);
         app.add_console_command::<JumpCommand, _>(jump_cmd);
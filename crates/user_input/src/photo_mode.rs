@@ -0,0 +1,131 @@
+// free-flying camera for photo mode. the player is held in place while the camera is moved with the
+// movement keys, and rotated with the mouse while the camera mouse button is held.
+
+use std::f32::consts::PI;
+
+use avatar::AvatarDynamicState;
+use bevy::{input::mouse::MouseMotion, prelude::*};
+use common::structs::{
+    ActiveDialog, CursorLocked, CursorLocks, PhotoMode, PrimaryCamera, PrimaryUser,
+};
+use dcl_component::proto_components::sdk::components::common::InputAction;
+use input_manager::{axes::AnalogInput, AcceptInput, InputManager};
+use tween::SystemTween;
+
+use crate::camera::{MOUSE_LOOK_SCALE, STICK_LOOK_SPEED};
+
+// meters per second, doubled while the walk key is held
+const FLY_SPEED: f32 = 4.0;
+// how far the camera may fly from the player's head
+const MAX_PHOTO_DISTANCE: f32 = 50.0;
+
+pub(crate) fn in_photo_mode(photo: Res<PhotoMode>) -> bool {
+    photo.active
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn update_photo_camera(
+    mut commands: Commands,
+    mut photo: ResMut<PhotoMode>,
+    mut camera: Query<(Entity, &mut Transform, &mut Projection, &PrimaryCamera)>,
+    mut player: Query<
+        (&Transform, &mut AvatarDynamicState),
+        (With<PrimaryUser>, Without<PrimaryCamera>),
+    >,
+    input: InputManager,
+    mut mouse_events: EventReader<MouseMotion>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    accept_input: Res<AcceptInput>,
+    active_dialog: Res<ActiveDialog>,
+    analog: AnalogInput,
+    mut locks: ResMut<CursorLocks>,
+    mut cursor_locked: ResMut<CursorLocked>,
+    time: Res<Time>,
+    mut look: Local<Option<(f32, f32)>>,
+) {
+    if !photo.active {
+        if look.take().is_some() {
+            locks.0.remove("photo");
+            cursor_locked.0 = false;
+        }
+        return;
+    }
+
+    let (
+        Ok((camera_ent, mut transform, mut projection, options)),
+        Ok((player_transform, mut dynamic_state)),
+    ) = (camera.get_single_mut(), player.get_single_mut())
+    else {
+        return;
+    };
+
+    // hold the player still
+    dynamic_state.force = Vec2::ZERO;
+    dynamic_state.rotate = 0.0;
+
+    let (yaw, pitch) = look.get_or_insert_with(|| {
+        commands.entity(camera_ent).remove::<SystemTween>();
+        locks.0.remove("camera");
+        if let Projection::Perspective(PerspectiveProjection { fov, .. }) = &*projection {
+            photo.fov = *fov;
+        }
+        let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        (yaw, pitch)
+    });
+
+    let dt = time.delta_seconds();
+
+    // look
+    let mut look_delta = Vec2::ZERO;
+    let held = !active_dialog.in_use()
+        && (mouse_button.pressed(options.mouse_key_enable_mouse) && locks.0.contains("photo")
+            || accept_input.mouse && mouse_button.just_pressed(options.mouse_key_enable_mouse));
+    if held {
+        locks.0.insert("photo");
+        cursor_locked.0 = true;
+        for event in mouse_events.read() {
+            look_delta += analog.mouse(event.delta) * MOUSE_LOOK_SCALE;
+        }
+    } else {
+        locks.0.remove("photo");
+        cursor_locked.0 = false;
+        mouse_events.clear();
+    }
+    if !active_dialog.in_use() {
+        look_delta -= analog.look_stick() * STICK_LOOK_SPEED * dt;
+    }
+
+    *yaw -= look_delta.x;
+    *pitch = (*pitch - look_delta.y).clamp(-PI / 2.1, PI / 2.1);
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, *yaw, *pitch, photo.roll);
+
+    // move
+    let mut axis_input = analog.move_stick().extend(0.0);
+    for (action, axis) in [
+        (InputAction::IaForward, Vec3::Y),
+        (InputAction::IaBackward, Vec3::NEG_Y),
+        (InputAction::IaRight, Vec3::X),
+        (InputAction::IaLeft, Vec3::NEG_X),
+        (InputAction::IaJump, Vec3::Z),
+    ] {
+        if input.is_down(action) {
+            axis_input += axis;
+        }
+    }
+    let speed = if input.is_down(InputAction::IaWalk) {
+        FLY_SPEED * 2.0
+    } else {
+        FLY_SPEED
+    };
+    let movement =
+        transform.rotation * Vec3::new(axis_input.x, 0.0, -axis_input.y) + Vec3::Y * axis_input.z;
+    let head = player_transform.translation + Vec3::Y * 1.81;
+    let target = transform.translation + movement.clamp_length_max(1.0) * speed * dt;
+    transform.translation = head + (target - head).clamp_length_max(MAX_PHOTO_DISTANCE);
+
+    if let Projection::Perspective(PerspectiveProjection { fov, .. }) = &mut *projection {
+        if *fov != photo.fov {
+            *fov = photo.fov;
+        }
+    }
+}
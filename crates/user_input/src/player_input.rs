@@ -7,7 +7,7 @@ use common::{
 
 use avatar::AvatarDynamicState;
 use dcl_component::proto_components::sdk::components::common::InputAction;
-use input_manager::InputManager;
+use input_manager::{axes::AnalogInput, touch::TouchInput, InputManager};
 use scene_runner::update_world::avatar_modifier_area::PlayerModifiers;
 
 use crate::TRANSITION_TIME;
@@ -22,6 +22,8 @@ pub(crate) fn update_user_velocity(
         Option<&PlayerModifiers>,
     )>,
     input: InputManager,
+    touch: Res<TouchInput>,
+    analog: AnalogInput,
     mut tankiness: Local<f32>,
     time: Res<Time>,
 ) {
@@ -57,6 +59,8 @@ pub(crate) fn update_user_velocity(
     if input.is_down(InputAction::IaLeft) {
         axis_input.x -= 1.0;
     }
+    axis_input += touch.movement;
+    axis_input += analog.move_stick();
 
     dynamic_state.force = Vec2::ZERO;
     dynamic_state.rotate = 0.0;
@@ -82,7 +86,8 @@ pub(crate) fn update_user_velocity(
         } else {
             user.walk_speed
         };
-        axis_input = axis_input.normalize();
+        // analog (touch and stick) input below full deflection moves slower
+        axis_input = axis_input.clamp_length_max(1.0);
 
         let ground = Vec3::X + Vec3::Z;
         let forward = (Vec3::from(relative_transform.forward()) * ground)
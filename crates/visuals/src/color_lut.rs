@@ -0,0 +1,416 @@
+// color grading via 3d lookup tables, applied after tonemapping
+
+use bevy::{
+    asset::{
+        io::{file::FileAssetReader, AssetSource, Reader},
+        AssetLoader, AsyncReadExt, LoadContext,
+    },
+    core_pipeline::{
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_asset::{RenderAssetUsages, RenderAssets},
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, texture_3d, uniform_buffer},
+            AddressMode, BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries,
+            CachedRenderPipelineId, ColorTargetState, ColorWrites, Extent3d, FilterMode,
+            FragmentState, MultisampleState, Operations, PipelineCache, PrimitiveState,
+            RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor, Sampler,
+            SamplerBindingType, SamplerDescriptor, ShaderStages, ShaderType, TextureDimension,
+            TextureFormat, TextureSampleType,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::{BevyDefault, GpuImage},
+        view::ViewTarget,
+        RenderApp,
+    },
+    utils::ConditionalSendFuture,
+};
+use common::util::user_lut_dir;
+
+// serves `<config dir>/luts` as `luts://`, must be added before the `AssetPlugin`
+pub struct UserLutSourcePlugin;
+
+impl Plugin for UserLutSourcePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_asset_source(
+            "luts",
+            AssetSource::build().with_reader(|| Box::new(FileAssetReader::new(user_lut_dir()))),
+        );
+    }
+}
+
+pub struct ColorLutPlugin;
+
+impl Plugin for ColorLutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset_loader::<CubeLutLoader>().add_plugins((
+            ExtractComponentPlugin::<ColorLut>::default(),
+            UniformComponentPlugin::<ColorLutUniform>::default(),
+        ));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<ColorLutNode>>(Core3d, ColorLutLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::Tonemapping,
+                    ColorLutLabel,
+                    Node3d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<ColorLutPipeline>();
+    }
+}
+
+// add to a camera to grade the final image through the given 3d lut
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct ColorLut {
+    pub lut: Handle<Image>,
+    // 0 -> ungraded, 1 -> fully graded
+    pub strength: f32,
+}
+
+#[derive(Component, Clone, ShaderType)]
+pub struct ColorLutUniform {
+    strength: f32,
+    // webgl2 requires 16 byte alignment
+    _padding: Vec3,
+}
+
+#[derive(Component, Clone)]
+pub struct ExtractedColorLut(AssetId<Image>);
+
+impl ExtractComponent for ColorLut {
+    type QueryData = &'static ColorLut;
+    type QueryFilter = With<Camera>;
+    type Out = (ExtractedColorLut, ColorLutUniform);
+
+    fn extract_component(item: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        (item.strength > 0.0).then(|| {
+            (
+                ExtractedColorLut(item.lut.id()),
+                ColorLutUniform {
+                    strength: item.strength.min(1.0),
+                    _padding: Vec3::ZERO,
+                },
+            )
+        })
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct ColorLutLabel;
+
+#[derive(Default)]
+struct ColorLutNode;
+
+impl ViewNode for ColorLutNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static ExtractedColorLut,
+        &'static DynamicUniformIndex<ColorLutUniform>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, lut, uniform_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let lut_pipeline = world.resource::<ColorLutPipeline>();
+        let pipeline_id = if view_target.is_hdr() {
+            lut_pipeline.hdr_pipeline_id
+        } else {
+            lut_pipeline.sdr_pipeline_id
+        };
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(pipeline_id)
+        else {
+            return Ok(());
+        };
+
+        // skip until the lut is ready
+        let Some(lut_image) = world.resource::<RenderAssets<GpuImage>>().get(lut.0) else {
+            return Ok(());
+        };
+
+        let Some(uniform_binding) = world
+            .resource::<ComponentUniforms<ColorLutUniform>>()
+            .uniforms()
+            .binding()
+        else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+
+        let bind_group = render_context.render_device().create_bind_group(
+            "color_lut_bind_group",
+            &lut_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &lut_pipeline.screen_sampler,
+                &lut_image.texture_view,
+                &lut_pipeline.lut_sampler,
+                uniform_binding,
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("color_lut_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_index.index()]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct ColorLutPipeline {
+    layout: BindGroupLayout,
+    screen_sampler: Sampler,
+    lut_sampler: Sampler,
+    hdr_pipeline_id: CachedRenderPipelineId,
+    sdr_pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for ColorLutPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>().clone();
+
+        let layout = render_device.create_bind_group_layout(
+            "color_lut_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    texture_3d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<ColorLutUniform>(true),
+                ),
+            ),
+        );
+
+        let screen_sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let lut_sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("color_lut_sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let shader = world
+            .resource::<AssetServer>()
+            .load("shaders/color_lut.wgsl");
+
+        let mut queue_pipeline = |format: TextureFormat| {
+            world
+                .resource_mut::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("color_lut_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader: shader.clone(),
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: vec![],
+                })
+        };
+
+        let hdr_pipeline_id = queue_pipeline(ViewTarget::TEXTURE_FORMAT_HDR);
+        let sdr_pipeline_id = queue_pipeline(TextureFormat::bevy_default());
+
+        Self {
+            layout,
+            screen_sampler,
+            lut_sampler,
+            hdr_pipeline_id,
+            sdr_pipeline_id,
+        }
+    }
+}
+
+// loads adobe/resolve `.cube` files as 3d textures
+#[derive(Default)]
+pub struct CubeLutLoader;
+
+impl AssetLoader for CubeLutLoader {
+    type Asset = Image;
+    type Settings = ();
+    type Error = String;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _: &'a Self::Settings,
+        _: &'a mut LoadContext,
+    ) -> impl ConditionalSendFuture<Output = Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut source = String::default();
+            reader
+                .read_to_string(&mut source)
+                .await
+                .map_err(|e| format!("read failed: {e}"))?;
+            let (size, data) = parse_cube_lut(&source)?;
+
+            Ok(Image::new(
+                Extent3d {
+                    width: size,
+                    height: size,
+                    depth_or_array_layers: size,
+                },
+                TextureDimension::D3,
+                data,
+                TextureFormat::Rgba8Unorm,
+                RenderAssetUsages::RENDER_WORLD,
+            ))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["cube"]
+    }
+}
+
+// returns the lut edge size and rgba8 data, red varying fastest
+pub fn parse_cube_lut(source: &str) -> Result<(u32, Vec<u8>), String> {
+    let mut size = None;
+    let mut domain_min = Vec3::ZERO;
+    let mut domain_max = Vec3::ONE;
+    let mut data = Vec::default();
+
+    let parse_vec3 = |parts: &[&str]| -> Result<Vec3, String> {
+        let values = parts
+            .iter()
+            .map(|p| p.parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("bad value: {e}"))?;
+        match values.as_slice() {
+            [r, g, b] => Ok(Vec3::new(*r, *g, *b)),
+            _ => Err(format!("expected 3 values, found {}", values.len())),
+        }
+    };
+
+    for line in source.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+        match parts[0] {
+            "TITLE" => (),
+            "LUT_1D_SIZE" => return Err("1d luts are not supported".to_owned()),
+            "LUT_3D_SIZE" => {
+                let value = parts
+                    .get(1)
+                    .and_then(|s| s.parse::<u32>().ok())
+                    .ok_or("bad LUT_3D_SIZE")?;
+                if !(2..=256).contains(&value) {
+                    return Err(format!("unsupported LUT_3D_SIZE {value}"));
+                }
+                size = Some(value);
+            }
+            "DOMAIN_MIN" => domain_min = parse_vec3(&parts[1..])?,
+            "DOMAIN_MAX" => domain_max = parse_vec3(&parts[1..])?,
+            _ => {
+                let rgb = parse_vec3(&parts)?;
+                let normalized =
+                    ((rgb - domain_min) / (domain_max - domain_min)).clamp(Vec3::ZERO, Vec3::ONE);
+                data.extend(
+                    normalized
+                        .to_array()
+                        .map(|c| (c * 255.0).round() as u8)
+                        .into_iter()
+                        .chain(std::iter::once(255)),
+                );
+            }
+        }
+    }
+
+    let size = size.ok_or("missing LUT_3D_SIZE")?;
+    let expected = (size * size * size * 4) as usize;
+    if data.len() != expected {
+        return Err(format!(
+            "expected {} entries, found {}",
+            expected / 4,
+            data.len() / 4
+        ));
+    }
+
+    Ok((size, data))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_cube_lut;
+
+    #[test]
+    fn parse_identity_lut() {
+        let source = "TITLE \"identity\"\n# comment\nLUT_3D_SIZE 2\n\n0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1\n";
+        let (size, data) = parse_cube_lut(source).unwrap();
+        assert_eq!(size, 2);
+        assert_eq!(&data[0..8], &[0, 0, 0, 255, 255, 0, 0, 255]);
+        assert_eq!(&data[28..32], &[255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn parse_domain_and_errors() {
+        let source =
+            "LUT_3D_SIZE 2\nDOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2\n".to_owned() + &"2 1 0\n".repeat(8);
+        let (_, data) = parse_cube_lut(&source).unwrap();
+        assert_eq!(&data[0..4], &[255, 128, 0, 255]);
+
+        assert!(parse_cube_lut("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        assert!(parse_cube_lut("LUT_1D_SIZE 16\n").is_err());
+        assert!(parse_cube_lut("0 0 0\n").is_err());
+    }
+}
@@ -1,9 +1,12 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
 use bevy::{
-    core_pipeline::Skybox,
-    pbr::{wireframe::WireframePlugin, CascadeShadowConfigBuilder, DirectionalLightShadowMap},
+    core_pipeline::{bloom::BloomSettings, dof::DepthOfFieldSettings, Skybox},
+    pbr::{wireframe::WireframePlugin, DirectionalLightShadowMap},
     prelude::*,
     render::{
         render_asset::RenderAssetBytesPerFrame,
+        renderer::RenderAdapterInfo,
         view::{Layer, RenderLayers},
     },
 };
@@ -13,14 +16,25 @@ use bevy_atmosphere::{
 };
 
 use bevy_console::ConsoleCommand;
+use color_lut::{ColorLut, ColorLutPlugin};
 use common::{
     sets::SetupSets,
     structs::{
-        AppConfig, FogSetting, PrimaryCamera, PrimaryCameraRes, PrimaryUser, SceneLoadDistance,
-        ShadowSetting, GROUND_RENDERLAYER, PRIMARY_AVATAR_LIGHT_LAYER,
+        AppConfig, FogSetting, GpuTier, PhotoMode, PrimaryCamera, PrimaryCameraRes, PrimaryUser,
+        SceneLoadDistance, ShadowSetting, GROUND_RENDERLAYER, PRIMARY_AVATAR_LIGHT_LAYER,
     },
 };
 use console::DoAddConsoleCommand;
+use night_sky::{night_amount, NightSkyPlugin};
+use render_scale::RenderScalePlugin;
+use scene_skybox::SceneSkyboxPlugin;
+use volumetric_fog::VolumetricFogPlugin;
+
+pub mod color_lut;
+pub mod night_sky;
+pub mod render_scale;
+pub mod scene_skybox;
+pub mod volumetric_fog;
 
 pub struct VisualsPlugin {
     pub no_fog: bool,
@@ -30,10 +44,19 @@ impl Plugin for VisualsPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(DirectionalLightShadowMap { size: 4096 })
             .init_resource::<SceneGlobalLight>()
+            .init_resource::<ScenePostProcessing>()
             .insert_resource(AtmosphereModel::default())
             .add_plugins(AtmospherePlugin)
             .add_plugins(WireframePlugin)
+            .add_plugins(ColorLutPlugin)
+            .add_plugins(SceneSkyboxPlugin)
+            .add_plugins(NightSkyPlugin)
+            .add_plugins(RenderScalePlugin)
+            .add_plugins(VolumetricFogPlugin)
             .add_systems(Update, apply_global_light)
+            .add_systems(Update, apply_scene_bloom)
+            .add_systems(Update, apply_color_lut)
+            .add_systems(Update, apply_photo_dof)
             .add_systems(Update, move_ground)
             .add_systems(Startup, setup.in_set(SetupSets::Main))
             .insert_resource(RenderAssetBytesPerFrame::new(16777216));
@@ -44,7 +67,7 @@ impl Plugin for VisualsPlugin {
 }
 
 #[derive(Component)]
-struct DirectionalLightLayer(Layer);
+pub(crate) struct DirectionalLightLayer(Layer);
 
 fn setup(
     mut commands: Commands,
@@ -93,10 +116,15 @@ pub struct SceneGlobalLight {
     pub layers: RenderLayers,
 }
 
-static TRANSITION_TIME: f32 = 1.0;
+pub(crate) static TRANSITION_TIME: f32 = 1.0;
+
+// night-time floor for sky brightness, and tints for the fog and ambient light
+const NIGHT_SKY_BRIGHTNESS: f32 = 60.0;
+const NIGHT_FOG_COLOR: Color = Color::srgb(0.01, 0.015, 0.04);
+const NIGHT_AMBIENT_COLOR: Color = Color::srgb(0.55, 0.65, 1.0);
 
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
-fn apply_global_light(
+pub(crate) fn apply_global_light(
     mut commands: Commands,
     setting: Res<AppConfig>,
     mut atmosphere: AtmosphereMut<Nishita>,
@@ -120,8 +148,10 @@ fn apply_global_light(
     scene_global_light: Res<SceneGlobalLight>,
     mut prev: Local<(f32, SceneGlobalLight)>,
     config: Res<AppConfig>,
+    adapter: Option<Res<RenderAdapterInfo>>,
+    photo: Res<PhotoMode>,
 ) {
-    let next_light = if prev.0 >= TRANSITION_TIME && prev.1.source == scene_global_light.source {
+    let scene_light = if prev.0 >= TRANSITION_TIME && prev.1.source == scene_global_light.source {
         scene_global_light.clone()
     } else {
         // transition part way
@@ -151,6 +181,17 @@ fn apply_global_light(
         }
     };
 
+    // photo mode can move the sun, without affecting the scene light transition
+    let mut next_light = scene_light.clone();
+    if let Some(hours) = photo.time_of_day.filter(|_| photo.active) {
+        // matches the default day cycle in `update_directional_light`
+        let t = (hours - 6.0) / 24.0 * TAU;
+        next_light.dir_direction =
+            Quat::from_euler(EulerRot::YXZ, FRAC_PI_2 * 0.8, -t, 0.0) * Vec3::NEG_Z;
+        next_light.dir_illuminance = t.sin().max(0.0).powf(2.0) * 10_000.0;
+    }
+
+    let night = night_amount(next_light.dir_direction);
     let rotation = Quat::from_rotation_arc(Vec3::NEG_Z, next_light.dir_direction);
     atmosphere.sun_position = -next_light.dir_direction;
 
@@ -177,31 +218,10 @@ fn apply_global_light(
             layer = layer.union(&PRIMARY_AVATAR_LIGHT_LAYER);
         }
 
-        let (shadows_enabled, cascade_shadow_config) = match config.graphics.shadow_settings {
-            ShadowSetting::Off => (false, Default::default()),
-            ShadowSetting::Low => (
-                true,
-                CascadeShadowConfigBuilder {
-                    num_cascades: 1,
-                    minimum_distance: 0.1,
-                    maximum_distance: config.graphics.shadow_distance,
-                    first_cascade_far_bound: config.graphics.shadow_distance,
-                    overlap_proportion: 0.2,
-                }
-                .build(),
-            ),
-            ShadowSetting::High => (
-                true,
-                CascadeShadowConfigBuilder {
-                    num_cascades: 4,
-                    minimum_distance: 0.1,
-                    maximum_distance: config.graphics.shadow_distance,
-                    first_cascade_far_bound: config.graphics.shadow_distance / 15.0,
-                    overlap_proportion: 0.2,
-                }
-                .build(),
-            ),
-        };
+        let shadows_enabled = config.graphics.shadow_settings != ShadowSetting::Off;
+        let cascade_shadow_config = config
+            .graphics
+            .cascade_shadow_config(GpuTier::from_adapter(adapter.as_deref()));
 
         commands.spawn((
             DirectionalLightBundle {
@@ -222,8 +242,10 @@ fn apply_global_light(
 
     for (maybe_primary, maybe_skybox, maybe_fog) in cameras.iter_mut() {
         let dir_light_lightness = Lcha::from(next_light.dir_color).lightness;
-        let skybox_brightness =
-            (next_light.dir_illuminance.sqrt() * 40.0 * dir_light_lightness).min(2000.0);
+        // keep some moonlit sky brightness after sunset rather than going black
+        let skybox_brightness = (next_light.dir_illuminance.sqrt() * 40.0 * dir_light_lightness)
+            .min(2000.0)
+            .max(NIGHT_SKY_BRIGHTNESS * night);
         if let Some(mut skybox) = maybe_skybox {
             skybox.brightness = skybox_brightness;
             atmosphere.rayleigh_coefficient =
@@ -240,7 +262,9 @@ fn apply_global_light(
                 * 0.5
                 * skybox_brightness
                 / 2000.0;
-            let base_color = Color::from(base_color).with_alpha(1.0);
+            let base_color = Color::from(base_color)
+                .mix(&NIGHT_FOG_COLOR, night * 0.5)
+                .with_alpha(1.0);
 
             fog.color = base_color;
             match setting.graphics.fog {
@@ -252,7 +276,7 @@ fn apply_global_light(
                     fog.falloff = FogFalloff::from_visibility_squared(distance * 2.0);
                     fog.directional_light_color = base_color;
                 }
-                FogSetting::Atmospheric => {
+                FogSetting::Atmospheric | FogSetting::Volumetric => {
                     fog.falloff = FogFalloff::from_visibility_squared(distance * 2.0);
                     fog.directional_light_color = next_light.dir_color;
                 }
@@ -268,14 +292,141 @@ fn apply_global_light(
 
     ambient.brightness =
         next_light.ambient_brightness * config.graphics.ambient_brightness as f32 * 20.0;
-    ambient.color = next_light.ambient_color;
+    ambient.color = next_light
+        .ambient_color
+        .mix(&NIGHT_AMBIENT_COLOR, night * 0.5);
 
     if prev.1.source == scene_global_light.source {
         prev.0 += time.delta_seconds()
     } else {
         prev.0 = time.delta_seconds()
     };
-    prev.1 = next_light;
+    prev.1 = scene_light;
+}
+
+// post processing limits requested by the current scene
+#[derive(Resource, Default, Clone, Debug, PartialEq)]
+pub struct ScenePostProcessing {
+    pub source: Option<Entity>,
+    pub bloom_intensity: Option<f32>,
+    pub bloom_threshold: Option<f32>,
+    pub color_lut: Option<Handle<Image>>,
+    pub color_lut_strength: Option<f32>,
+    pub fog_density: Option<f32>,
+}
+
+// scenes may only reduce bloom relative to the user settings
+fn apply_scene_bloom(
+    config: Res<AppConfig>,
+    scene_post_processing: Res<ScenePostProcessing>,
+    mut cameras: Query<&mut BloomSettings>,
+) {
+    let intensity = scene_post_processing
+        .bloom_intensity
+        .map_or(config.graphics.bloom_intensity(), |scene| {
+            scene.clamp(0.0, config.graphics.bloom_intensity())
+        });
+    let threshold = scene_post_processing
+        .bloom_threshold
+        .map_or(config.graphics.bloom_threshold(), |scene| {
+            scene.max(config.graphics.bloom_threshold())
+        });
+
+    for mut bloom in cameras.iter_mut() {
+        if bloom.intensity != intensity {
+            bloom.intensity = intensity;
+        }
+        if bloom.prefilter_settings.threshold != threshold {
+            bloom.prefilter_settings.threshold = threshold;
+        }
+    }
+}
+
+// only the primary camera is graded, texture camera output is graded when it is viewed
+fn apply_color_lut(
+    mut commands: Commands,
+    config: Res<AppConfig>,
+    scene_post_processing: Res<ScenePostProcessing>,
+    asset_server: Res<AssetServer>,
+    camera: Query<(Entity, Option<&ColorLut>), With<PrimaryCamera>>,
+    mut user_lut: Local<Option<(String, Handle<Image>)>>,
+    photo: Res<PhotoMode>,
+) {
+    let Ok((camera, current)) = camera.get_single() else {
+        return;
+    };
+
+    if user_lut.as_ref().map(|(path, _)| path) != config.graphics.color_lut.as_ref() {
+        *user_lut = config
+            .graphics
+            .color_lut
+            .clone()
+            .map(|path| (path.clone(), asset_server.load(path)));
+    }
+
+    let scene_lut = scene_post_processing
+        .color_lut
+        .clone()
+        .filter(|_| config.graphics.scene_color_luts)
+        .map(|lut| ColorLut {
+            lut,
+            strength: scene_post_processing.color_lut_strength.unwrap_or(1.0),
+        });
+
+    // photo mode filters take precedence over everything
+    let photo_lut = photo
+        .filter
+        .as_ref()
+        .filter(|_| photo.active)
+        .map(|path| ColorLut {
+            lut: asset_server.load(path.clone()),
+            strength: 1.0,
+        });
+
+    let target = photo_lut.or(scene_lut).or_else(|| {
+        user_lut.as_ref().map(|(_, lut)| ColorLut {
+            lut: lut.clone(),
+            strength: 1.0,
+        })
+    });
+
+    match (current, target) {
+        (Some(current), Some(target)) if current == &target => (),
+        (None, None) => (),
+        (_, Some(target)) => {
+            commands.entity(camera).insert(target);
+        }
+        (Some(_), None) => {
+            commands.entity(camera).remove::<ColorLut>();
+        }
+    }
+}
+
+fn apply_photo_dof(
+    mut commands: Commands,
+    photo: Res<PhotoMode>,
+    camera: Query<(Entity, Option<&DepthOfFieldSettings>), With<PrimaryCamera>>,
+) {
+    let Ok((camera, current)) = camera.get_single() else {
+        return;
+    };
+
+    match (current, photo.dof.filter(|_| photo.active)) {
+        (Some(current), Some(dof))
+            if current.focal_distance == dof.focal_distance
+                && current.aperture_f_stops == dof.aperture_f_stops => {}
+        (_, Some(dof)) => {
+            commands.entity(camera).insert(DepthOfFieldSettings {
+                focal_distance: dof.focal_distance,
+                aperture_f_stops: dof.aperture_f_stops,
+                ..Default::default()
+            });
+        }
+        (Some(_), None) => {
+            commands.entity(camera).remove::<DepthOfFieldSettings>();
+        }
+        (None, None) => (),
+    }
 }
 
 #[derive(Component)]
@@ -0,0 +1,179 @@
+// stars and moon, faded in over the atmosphere as the sun sets
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey, NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    render::{
+        mesh::MeshVertexBufferLayoutRef,
+        render_resource::{
+            AsBindGroup, RenderPipelineDescriptor, ShaderRef, ShaderType,
+            SpecializedMeshPipelineError,
+        },
+        view::NoFrustumCulling,
+    },
+};
+use common::structs::{Determinism, PrimaryCamera};
+
+use crate::{apply_global_light, DirectionalLightLayer};
+
+pub struct NightSkyPlugin;
+
+impl Plugin for NightSkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<NightSkyMaterial>::default())
+            .init_resource::<Determinism>()
+            .add_systems(Update, update_night_sky.after(apply_global_light));
+    }
+}
+
+// 0 during the day, 1 once the sun is well below the horizon
+pub fn night_amount(sun_light_direction: Vec3) -> f32 {
+    let sun_height = -sun_light_direction.y;
+    ((0.1 - sun_height) / 0.25).clamp(0.0, 1.0)
+}
+
+// phase used with a fixed time step, so snapshots don't depend on the date
+const FIXED_MOON_PHASE: f32 = 0.5;
+
+// fraction through the current lunar cycle, 0 = new moon, 0.5 = full moon
+fn moon_phase() -> f32 {
+    const SYNODIC_MONTH_SECS: f64 = 29.530588 * 86400.0;
+    // 2000-01-06 18:14 UTC
+    const REFERENCE_NEW_MOON: f64 = 947182440.0;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(REFERENCE_NEW_MOON);
+    (((now - REFERENCE_NEW_MOON) / SYNODIC_MONTH_SECS).rem_euclid(1.0)) as f32
+}
+
+#[derive(ShaderType, Clone, Copy, Debug, Default)]
+pub struct NightSkyUniform {
+    pub moon_direction: Vec3,
+    pub night: f32,
+    pub sun_direction: Vec3,
+    pub moon_phase: f32,
+    pub time: f32,
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct NightSkyMaterial {
+    #[uniform(0)]
+    pub data: NightSkyUniform,
+}
+
+impl Material for NightSkyMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/night_sky.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Add
+    }
+
+    fn specialize(
+        _: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        _: &MeshVertexBufferLayoutRef,
+        _: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.primitive.cull_mode = None;
+        if let Some(depth_stencil) = descriptor.depth_stencil.as_mut() {
+            depth_stencil.depth_write_enabled = false;
+        }
+        Ok(())
+    }
+}
+
+// just outside the scene skybox, so scene skies cover it
+const NIGHT_SKY_SIZE: f32 = 65_000.0;
+
+#[derive(Component)]
+struct NightSkyEntity;
+
+fn update_night_sky(
+    mut commands: Commands,
+    camera: Query<Entity, With<PrimaryCamera>>,
+    mut sky: Query<(&Handle<NightSkyMaterial>, &mut Visibility), With<NightSkyEntity>>,
+    sun: Query<(&Transform, &DirectionalLightLayer)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<NightSkyMaterial>>,
+    time: Res<Time>,
+    mut phase: Local<Option<(f32, f32)>>,
+    determinism: Res<Determinism>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+
+    let Ok((material, mut visibility)) = sky.get_single_mut() else {
+        let sky = commands
+            .spawn((
+                MaterialMeshBundle {
+                    mesh: meshes.add(Cuboid::from_length(NIGHT_SKY_SIZE)),
+                    material: materials.add(NightSkyMaterial {
+                        data: Default::default(),
+                    }),
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                NotShadowCaster,
+                NotShadowReceiver,
+                NoFrustumCulling,
+                NightSkyEntity,
+            ))
+            .id();
+        commands.entity(camera).add_child(sky);
+        return;
+    };
+
+    let Some((sun_transform, _)) = sun.iter().find(|(_, layer)| layer.0 == 0) else {
+        return;
+    };
+
+    let sun_light_direction = sun_transform.rotation * Vec3::NEG_Z;
+    let night = night_amount(sun_light_direction);
+    visibility.set_if_neq(if night > 0.0 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    if night == 0.0 {
+        return;
+    }
+
+    // the phase only needs refreshing occasionally
+    let now = time.elapsed_seconds();
+    let moon_phase = match *phase {
+        _ if determinism.fixed_dt.is_some() => FIXED_MOON_PHASE,
+        Some((at, value)) if now - at < 60.0 => value,
+        _ => {
+            let value = moon_phase();
+            *phase = Some((now, value));
+            value
+        }
+    };
+
+    // the moon trails the sun by the phase angle
+    let sun_direction = -sun_light_direction;
+    let axis = Vec3::Y
+        .cross(sun_direction)
+        .try_normalize()
+        .unwrap_or(Vec3::X);
+    let moon_direction =
+        Quat::from_axis_angle(axis, moon_phase * std::f32::consts::TAU) * sun_direction;
+
+    let Some(material) = materials.get_mut(material) else {
+        return;
+    };
+    material.data = NightSkyUniform {
+        moon_direction,
+        night,
+        sun_direction,
+        moon_phase,
+        time: time.elapsed_seconds_wrapped(),
+    };
+}
@@ -0,0 +1,345 @@
+// render the 3d view below native resolution and upscale it to the window, keeping ui at full resolution
+
+use std::path::PathBuf;
+
+use bevy::{
+    core_pipeline::contrast_adaptive_sharpening::ContrastAdaptiveSharpeningSettings,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::{RenderAssetUsages, RenderAssets},
+        render_resource::{
+            BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
+            ImageDataLayout, MapMode, TextureDimension, TextureFormat, TextureUsages,
+        },
+        renderer::{render_system, RenderDevice, RenderQueue},
+        texture::{BevyDefault, GpuImage, ImageSampler},
+        ExtractSchedule, MainWorld, Render, RenderApp, RenderSet,
+    },
+    tasks::AsyncComputeTaskPool,
+    window::{PrimaryWindow, WindowRef},
+};
+use common::structs::{
+    AppConfig, DynamicRenderScale, FrameLoad, PhotoMode, PrimaryCamera, RenderScaleSetting,
+    RenderTargetCapture, UPSCALE_RENDERLAYER,
+};
+
+pub struct RenderScalePlugin;
+
+impl Plugin for RenderScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DynamicRenderScale>();
+        app.init_resource::<RenderTargetCapture>();
+        app.add_systems(
+            PostUpdate,
+            (update_dynamic_render_scale, update_render_scale).chain(),
+        );
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<PendingCaptures>()
+            .add_systems(ExtractSchedule, extract_captures)
+            .add_systems(
+                Render,
+                save_captures.after(render_system).in_set(RenderSet::Render),
+            );
+    }
+}
+
+// fraction of the frame budget used outside the scene loop above which we drop resolution
+const DYNAMIC_OVER_BUDGET: f32 = 0.9;
+// and below which we raise it. the gap between the two avoids oscillating
+const DYNAMIC_UNDER_BUDGET: f32 = 0.65;
+// how long the load must stay out of range before we step
+const DYNAMIC_DOWN_DELAY: f32 = 0.5;
+const DYNAMIC_UP_DELAY: f32 = 2.0;
+const DYNAMIC_STEP: f32 = 0.05;
+
+fn update_dynamic_render_scale(
+    config: Res<AppConfig>,
+    frame_load: Option<Res<FrameLoad>>,
+    mut dynamic: ResMut<DynamicRenderScale>,
+    time: Res<Time>,
+    mut out_of_range: Local<f32>,
+) {
+    let (min, max) = config.graphics.dynamic_scale_range();
+    let active = config.graphics.render_scale == RenderScaleSetting::Dynamic;
+    if dynamic.active != active {
+        dynamic.active = active;
+        dynamic.scale = max;
+        *out_of_range = 0.0;
+    }
+
+    let Some(frame_load) = frame_load.filter(|_| active) else {
+        return;
+    };
+
+    if frame_load.target_secs <= 0.0 {
+        return;
+    }
+
+    let load = frame_load.non_scene_secs / frame_load.target_secs;
+    let direction = if load > DYNAMIC_OVER_BUDGET && dynamic.scale > min {
+        -1.0
+    } else if load < DYNAMIC_UNDER_BUDGET && dynamic.scale < max {
+        1.0
+    } else {
+        0.0
+    };
+
+    // accumulate time in the current direction, resetting when it changes
+    if direction == 0.0 || out_of_range.signum() != direction {
+        *out_of_range = 0.0;
+    }
+    *out_of_range += direction * time.delta_seconds();
+
+    let step = if *out_of_range < -DYNAMIC_DOWN_DELAY {
+        -DYNAMIC_STEP
+    } else if *out_of_range > DYNAMIC_UP_DELAY {
+        DYNAMIC_STEP
+    } else {
+        // keep the bounds current if the settings change
+        let clamped = dynamic.scale.clamp(min, max);
+        if clamped != dynamic.scale {
+            dynamic.scale = clamped;
+        }
+        return;
+    };
+
+    *out_of_range = 0.0;
+    dynamic.scale = (dynamic.scale + step).clamp(min, max);
+    debug!(
+        "dynamic render scale -> {:.2} (load {load:.2})",
+        dynamic.scale
+    );
+}
+
+#[derive(Component)]
+pub struct UpscaleCamera;
+
+#[derive(Component)]
+struct UpscaleSprite;
+
+#[allow(clippy::type_complexity)]
+fn update_render_scale(
+    mut commands: Commands,
+    config: Res<AppConfig>,
+    dynamic: Res<DynamicRenderScale>,
+    mut primary_camera: Query<&mut Camera, (With<PrimaryCamera>, Without<UpscaleCamera>)>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    upscale_camera: Query<Entity, With<UpscaleCamera>>,
+    mut sprite: Query<&mut Sprite, With<UpscaleSprite>>,
+    mut images: ResMut<Assets<Image>>,
+    mut capture: ResMut<RenderTargetCapture>,
+    photo: Res<PhotoMode>,
+) {
+    let (Ok(mut camera), Ok(window)) = (primary_camera.get_single_mut(), window.get_single())
+    else {
+        return;
+    };
+
+    // photo mode supersamples by rendering above native resolution and downscaling
+    let scale = if photo.active && photo.supersample > 1 {
+        photo.supersample as f32
+    } else if dynamic.active {
+        dynamic.scale
+    } else {
+        config.graphics.render_scale.scale()
+    };
+    if scale == 1.0 {
+        if !matches!(camera.target, RenderTarget::Window(WindowRef::Primary)) {
+            camera.target = RenderTarget::Window(WindowRef::Primary);
+        }
+        for ent in upscale_camera.iter() {
+            commands.entity(ent).despawn_recursive();
+        }
+        capture.target = None;
+        return;
+    }
+
+    let size = (window.physical_size().as_vec2() * scale)
+        .round()
+        .as_uvec2()
+        .max(UVec2::ONE);
+    let extent = Extent3d {
+        width: size.x,
+        height: size.y,
+        depth_or_array_layers: 1,
+    };
+
+    let handle = capture.target.get_or_insert_with(|| {
+        let mut image = Image::new_fill(
+            extent,
+            TextureDimension::D2,
+            &[0; 4],
+            TextureFormat::bevy_default(),
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_DST
+            | TextureUsages::COPY_SRC
+            | TextureUsages::RENDER_ATTACHMENT;
+        image.sampler = ImageSampler::linear();
+        images.add(image)
+    });
+
+    if let Some(image) = images.get(handle.id()) {
+        if image.texture_descriptor.size != extent {
+            images.get_mut(handle.id()).unwrap().resize(extent);
+        }
+    }
+
+    if camera.target != RenderTarget::Image(handle.clone()) {
+        camera.target = RenderTarget::Image(handle.clone());
+    }
+
+    if upscale_camera.is_empty() {
+        commands
+            .spawn((
+                Camera2dBundle {
+                    camera: Camera {
+                        order: 1,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                // restore detail lost to the bilinear upscale
+                ContrastAdaptiveSharpeningSettings {
+                    enabled: true,
+                    sharpening_strength: 0.6,
+                    denoise: false,
+                },
+                UPSCALE_RENDERLAYER,
+                UpscaleCamera,
+            ))
+            .with_children(|c| {
+                c.spawn((
+                    SpriteBundle {
+                        texture: handle.clone(),
+                        sprite: Sprite {
+                            custom_size: Some(window.size()),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_translation(-Vec3::Z),
+                        ..Default::default()
+                    },
+                    UPSCALE_RENDERLAYER,
+                    UpscaleSprite,
+                ));
+            });
+        return;
+    }
+
+    for mut sprite in sprite.iter_mut() {
+        if sprite.custom_size != Some(window.size()) {
+            sprite.custom_size = Some(window.size());
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct PendingCaptures(Vec<(Handle<Image>, PathBuf)>);
+
+fn extract_captures(mut main_world: ResMut<MainWorld>, mut pending: ResMut<PendingCaptures>) {
+    if main_world
+        .resource::<RenderTargetCapture>()
+        .requests
+        .is_empty()
+    {
+        return;
+    }
+
+    let mut capture = main_world.resource_mut::<RenderTargetCapture>();
+    let requests = std::mem::take(&mut capture.requests);
+    if let Some(target) = capture.target.clone() {
+        pending
+            .0
+            .extend(requests.into_iter().map(|path| (target.clone(), path)));
+    }
+}
+
+// copy the target to a buffer after rendering, and write it out once the buffer is mapped
+fn save_captures(
+    mut pending: ResMut<PendingCaptures>,
+    images: Res<RenderAssets<GpuImage>>,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+) {
+    for (handle, path) in pending.0.drain(..) {
+        let Some(gpu_image) = images.get(&handle) else {
+            warn!("render target not ready, failed to save {path:?}");
+            continue;
+        };
+
+        let size = Extent3d {
+            width: gpu_image.size.x,
+            height: gpu_image.size.y,
+            depth_or_array_layers: 1,
+        };
+        let format = gpu_image.texture_format;
+        let row_bytes = size.width as usize * format.block_copy_size(None).unwrap_or(4) as usize;
+        let padded_row_bytes = RenderDevice::align_copy_bytes_per_row(row_bytes);
+
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("render_target_capture"),
+            size: (padded_row_bytes * size.height as usize) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+        encoder.copy_texture_to_buffer(
+            gpu_image.texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes as u32),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        queue.submit([encoder.finish()]);
+
+        let mapped = buffer.clone();
+        buffer.slice(..).map_async(MapMode::Read, move |result| {
+            if let Err(e) = result {
+                warn!("failed to read render target for {path:?}: {e}");
+                return;
+            }
+
+            // strip the row padding
+            let data = mapped
+                .slice(..)
+                .get_mapped_range()
+                .chunks(padded_row_bytes)
+                .flat_map(|row| &row[..row_bytes])
+                .copied()
+                .collect::<Vec<_>>();
+            mapped.unmap();
+
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    let image = Image::new(
+                        size,
+                        TextureDimension::D2,
+                        data,
+                        format,
+                        RenderAssetUsages::default(),
+                    );
+                    let result = image
+                        .try_into_dynamic()
+                        .map_err(|e| e.to_string())
+                        .and_then(|image| image.to_rgba8().save(&path).map_err(|e| e.to_string()));
+                    if let Err(e) = result {
+                        warn!("failed to save {path:?}: {e}");
+                    }
+                })
+                .detach();
+        });
+    }
+}
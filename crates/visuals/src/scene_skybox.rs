@@ -0,0 +1,241 @@
+// scene-provided skies, drawn over the procedural atmosphere
+
+use bevy::{
+    core_pipeline::Skybox,
+    pbr::{MaterialPipeline, MaterialPipelineKey, NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    render::{
+        mesh::MeshVertexBufferLayoutRef,
+        render_resource::{
+            AsBindGroup, RenderPipelineDescriptor, ShaderRef, ShaderType,
+            SpecializedMeshPipelineError, TextureFormat, TextureViewDescriptor,
+            TextureViewDimension,
+        },
+        view::NoFrustumCulling,
+    },
+    utils::HashSet,
+};
+use common::structs::PrimaryCamera;
+
+use crate::TRANSITION_TIME;
+
+pub struct SceneSkyboxPlugin;
+
+impl Plugin for SceneSkyboxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MaterialPlugin::<SceneSkyboxMaterial>::default())
+            .init_resource::<SceneSkybox>()
+            .add_systems(Update, update_scene_skybox.after(crate::apply_global_light));
+    }
+}
+
+// sky requested by the current scene
+#[derive(Resource, Default, Clone, Debug, PartialEq)]
+pub struct SceneSkybox {
+    pub source: Option<Entity>,
+    pub image: Option<Handle<Image>>,
+    pub brightness: Option<f32>,
+}
+
+#[derive(ShaderType, Clone, Copy, Debug, Default)]
+pub struct SceneSkyboxUniform {
+    pub brightness: f32,
+    pub alpha: f32,
+    // 1 -> sample `equirect`, 0 -> sample `cubemap`
+    pub equirect: u32,
+    _padding: u32,
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct SceneSkyboxMaterial {
+    #[uniform(0)]
+    pub data: SceneSkyboxUniform,
+    #[texture(1, dimension = "cube")]
+    #[sampler(2)]
+    pub cubemap: Option<Handle<Image>>,
+    #[texture(3)]
+    #[sampler(4)]
+    pub equirect: Option<Handle<Image>>,
+}
+
+impl Material for SceneSkyboxMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/scene_skybox.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+
+    fn specialize(
+        _: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        _: &MeshVertexBufferLayoutRef,
+        _: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // we view the box from inside
+        descriptor.primitive.cull_mode = None;
+        if let Some(depth_stencil) = descriptor.depth_stencil.as_mut() {
+            depth_stencil.depth_write_enabled = false;
+        }
+        Ok(())
+    }
+}
+
+// big enough to surround the loaded world, small enough to sit inside the atmosphere's sky box
+const SKYBOX_SIZE: f32 = 60_000.0;
+
+#[derive(Component)]
+struct SceneSkyboxEntity;
+
+#[derive(Default)]
+struct SkyboxState {
+    current: Option<Handle<Image>>,
+    alpha: f32,
+    // images we have already reinterpreted as cubemaps / converted to filterable formats
+    prepared: HashSet<AssetId<Image>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_scene_skybox(
+    mut commands: Commands,
+    scene_skybox: Res<SceneSkybox>,
+    camera: Query<(Entity, &Skybox), With<PrimaryCamera>>,
+    mut sky_entity: Query<(&Handle<SceneSkyboxMaterial>, &mut Visibility), With<SceneSkyboxEntity>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<SceneSkyboxMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    time: Res<Time>,
+    mut state: Local<SkyboxState>,
+) {
+    let Ok((camera, camera_skybox)) = camera.get_single() else {
+        return;
+    };
+
+    let Ok((material, mut visibility)) = sky_entity.get_single_mut() else {
+        let material = materials.add(SceneSkyboxMaterial {
+            data: Default::default(),
+            cubemap: None,
+            equirect: None,
+        });
+        let sky = commands
+            .spawn((
+                MaterialMeshBundle {
+                    mesh: meshes.add(Cuboid::from_length(SKYBOX_SIZE)),
+                    material,
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                NotShadowCaster,
+                NotShadowReceiver,
+                NoFrustumCulling,
+                SceneSkyboxEntity,
+            ))
+            .id();
+        commands.entity(camera).add_child(sky);
+        return;
+    };
+
+    let target_ready = scene_skybox
+        .image
+        .as_ref()
+        .map(|image| prepare_image(image, &mut images, &mut state.prepared));
+
+    // fade out the current sky before switching, and fade in once the new image is ready
+    let step = time.delta_seconds() / TRANSITION_TIME;
+    if state.current != scene_skybox.image {
+        state.alpha = (state.alpha - step).max(0.0);
+        if state.alpha == 0.0 && target_ready != Some(false) {
+            state.current.clone_from(&scene_skybox.image);
+        }
+    } else if target_ready == Some(true) {
+        state.alpha = (state.alpha + step).min(1.0);
+    }
+
+    let Some(material) = materials.get_mut(material) else {
+        return;
+    };
+
+    let is_equirect = state
+        .current
+        .as_ref()
+        .and_then(|image| images.get(image))
+        .is_some_and(|image| image.texture_descriptor.size.depth_or_array_layers == 1);
+
+    material.data = SceneSkyboxUniform {
+        brightness: scene_skybox.brightness.unwrap_or(camera_skybox.brightness),
+        alpha: state.alpha,
+        equirect: is_equirect as u32,
+        _padding: 0,
+    };
+    if is_equirect {
+        material.equirect.clone_from(&state.current);
+        material.cubemap = None;
+    } else {
+        material.cubemap.clone_from(&state.current);
+        material.equirect = None;
+    }
+
+    let target_visibility = if state.alpha > 0.0 && state.current.is_some() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    visibility.set_if_neq(target_visibility);
+}
+
+// reinterpret vertically stacked faces as a cubemap, and convert 32-bit float (hdr) images
+// to 16-bit so they can be filtered. returns true when the image is ready to use
+fn prepare_image(
+    handle: &Handle<Image>,
+    images: &mut Assets<Image>,
+    prepared: &mut HashSet<AssetId<Image>>,
+) -> bool {
+    if prepared.contains(&handle.id()) {
+        return true;
+    }
+
+    let Some(image) = images.get_mut(handle) else {
+        return false;
+    };
+
+    if image.texture_descriptor.format == TextureFormat::Rgba32Float {
+        image.data = image
+            .data
+            .chunks_exact(4)
+            .flat_map(|bytes| {
+                f32_to_f16_bits(f32::from_le_bytes(bytes.try_into().unwrap())).to_le_bytes()
+            })
+            .collect();
+        image.texture_descriptor.format = TextureFormat::Rgba16Float;
+    }
+
+    if image.height() == image.width() * 6 {
+        image.reinterpret_stacked_2d_as_array(6);
+        image.texture_view_descriptor = Some(TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::Cube),
+            ..default()
+        });
+    }
+
+    prepared.insert(handle.id());
+    true
+}
+
+// clamps out-of-range values and flushes subnormals to zero
+fn f32_to_f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+    let mantissa = ((bits >> 13) & 0x3ff) as u16;
+
+    if value.is_nan() {
+        sign | 0x7e00
+    } else if exponent <= 0 {
+        sign
+    } else if exponent >= 0x1f {
+        sign | 0x7bff
+    } else {
+        sign | ((exponent as u16) << 10) | mantissa
+    }
+}
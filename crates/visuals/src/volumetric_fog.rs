@@ -0,0 +1,102 @@
+// volumetric fog with light shafts from the sun, for the highest fog setting
+
+use bevy::{
+    pbr::{VolumetricFogSettings, VolumetricLight},
+    prelude::*,
+    render::renderer::RenderAdapterInfo,
+};
+use common::structs::{AppConfig, FogSetting, GpuTier, PowerSaving, PrimaryCamera};
+
+use crate::{apply_global_light, DirectionalLightLayer, ScenePostProcessing};
+
+const DEFAULT_DENSITY: f32 = 0.05;
+
+pub struct VolumetricFogPlugin;
+
+impl Plugin for VolumetricFogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_volumetric_fog.after(apply_global_light));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_volumetric_fog(
+    mut commands: Commands,
+    config: Res<AppConfig>,
+    scene_post_processing: Res<ScenePostProcessing>,
+    mut cameras: Query<(Entity, Option<&mut VolumetricFogSettings>), With<PrimaryCamera>>,
+    lights: Query<(
+        Entity,
+        &DirectionalLight,
+        &DirectionalLightLayer,
+        Has<VolumetricLight>,
+    )>,
+    ambient: Res<AmbientLight>,
+    adapter: Option<Res<RenderAdapterInfo>>,
+    power_saving: Option<Res<PowerSaving>>,
+) {
+    let enabled =
+        config.graphics.fog == FogSetting::Volumetric && !power_saving.is_some_and(|ps| ps.0);
+
+    // only the main world sun scatters, so the shafts follow the scene / default light direction
+    let mut sun_color = None;
+    for (ent, light, layer, is_volumetric) in lights.iter() {
+        let want_volumetric = enabled && layer.0 == 0;
+        if want_volumetric {
+            sun_color = Some(light.color);
+        }
+
+        match (want_volumetric, is_volumetric) {
+            (true, false) => {
+                commands.entity(ent).try_insert(VolumetricLight);
+            }
+            (false, true) => {
+                commands.entity(ent).remove::<VolumetricLight>();
+            }
+            _ => (),
+        }
+    }
+
+    for (ent, maybe_settings) in cameras.iter_mut() {
+        if !enabled {
+            if maybe_settings.is_some() {
+                commands.entity(ent).remove::<VolumetricFogSettings>();
+            }
+            continue;
+        }
+
+        let density = scene_post_processing
+            .fog_density
+            .map(|density| density.clamp(0.0, 1.0))
+            .unwrap_or(DEFAULT_DENSITY);
+        let step_count = match GpuTier::from_adapter(adapter.as_deref()) {
+            GpuTier::Low => 32,
+            GpuTier::Medium => 48,
+            GpuTier::High => 64,
+        };
+
+        let target = VolumetricFogSettings {
+            ambient_color: ambient.color,
+            ambient_intensity: 0.05,
+            light_tint: sun_color.unwrap_or(Color::WHITE),
+            density,
+            step_count,
+            ..Default::default()
+        };
+
+        match maybe_settings {
+            Some(mut settings) => {
+                if settings.density != target.density
+                    || settings.ambient_color != target.ambient_color
+                    || settings.light_tint != target.light_tint
+                    || settings.step_count != target.step_count
+                {
+                    *settings = target;
+                }
+            }
+            None => {
+                commands.entity(ent).try_insert(target);
+            }
+        }
+    }
+}
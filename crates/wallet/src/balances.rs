@@ -0,0 +1,214 @@
+// eth, mana and any configured erc20 balances for the logged in account. results are cached so
+// that reopening the profile or repeatedly clicking refresh doesn't hit the rpc nodes every time.
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, Task},
+};
+use common::{
+    structs::{AppConfig, TrackedToken},
+    util::TaskExt,
+};
+use ethers_core::{
+    abi::{decode, encode, ParamType, Token},
+    types::{Address, U256},
+    utils::{format_units, id},
+};
+use serde_json::json;
+
+use crate::{
+    eth_rpc::{polygon_rpc, rpc},
+    Wallet,
+};
+
+// cached balances are reused for this long
+const BALANCE_CACHE_TIME: Duration = Duration::from_secs(120);
+// explicit refreshes are ignored if the balances are younger than this
+const BALANCE_MIN_REFRESH: Duration = Duration::from_secs(10);
+// time for a sent transaction to be included before we look at the balances again
+const BALANCE_TRANSACTION_DELAY: Duration = Duration::from_secs(15);
+
+const MAINNET_MANA: &str = "0x0f5d2fb29fb7d3cfee444a200298f468908cc942";
+const POLYGON_MANA: &str = "0xa1c57f48f0deb89f569dfbe6e2b7f46d33606fd4";
+
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RefreshBalances {
+    // fetch unless the cached balances are recent
+    Cached,
+    // the user asked for fresh balances
+    Now,
+    // a transaction was sent, refresh once it has had time to land
+    AfterTransaction,
+}
+
+#[derive(Clone, Debug)]
+pub struct Balance {
+    pub symbol: String,
+    pub amount: U256,
+    pub decimals: u32,
+}
+
+impl Balance {
+    // at most 4 decimal places, without trailing zeros
+    pub fn amount_text(&self) -> String {
+        let text = format_units(self.amount, self.decimals).unwrap_or_default();
+        let text = match text.split_once('.') {
+            Some((whole, fraction)) => {
+                let fraction = fraction[..fraction.len().min(4)].trim_end_matches('0');
+                if fraction.is_empty() {
+                    whole.to_owned()
+                } else {
+                    format!("{whole}.{fraction}")
+                }
+            }
+            None => text,
+        };
+        format!("{text} {}", self.symbol)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct WalletBalances {
+    balances: Vec<Balance>,
+    address: Option<Address>,
+    fetched_at: Option<Instant>,
+    refresh_at: Option<Instant>,
+    task: Option<Task<Result<Vec<Balance>, anyhow::Error>>>,
+}
+
+impl WalletBalances {
+    pub fn balances(&self) -> &[Balance] {
+        &self.balances
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.task.is_some()
+    }
+
+    fn is_fresh(&self, max_age: Duration) -> bool {
+        self.fetched_at.is_some_and(|at| at.elapsed() < max_age)
+    }
+}
+
+pub(crate) fn update_balances(
+    mut events: EventReader<RefreshBalances>,
+    mut balances: ResMut<WalletBalances>,
+    wallet: Res<Wallet>,
+    config: Res<AppConfig>,
+) {
+    let address = wallet.address().filter(|_| !wallet.is_guest());
+    if address != balances.address {
+        *balances = WalletBalances {
+            address,
+            ..Default::default()
+        };
+    }
+
+    let mut fetch = false;
+    for ev in events.read() {
+        match ev {
+            RefreshBalances::Cached => fetch |= !balances.is_fresh(BALANCE_CACHE_TIME),
+            RefreshBalances::Now => fetch |= !balances.is_fresh(BALANCE_MIN_REFRESH),
+            RefreshBalances::AfterTransaction => {
+                balances.refresh_at = Some(Instant::now() + BALANCE_TRANSACTION_DELAY)
+            }
+        }
+    }
+    if !balances.is_loading() && balances.refresh_at.is_some_and(|at| at <= Instant::now()) {
+        balances.refresh_at = None;
+        fetch = true;
+    }
+
+    // only touch the resource mutably when something changes, so the ui can watch for changes
+    if balances.is_loading() {
+        let mut task = balances.task.take().unwrap();
+        match task.complete() {
+            Some(Ok(result)) => {
+                balances.balances = result;
+                balances.fetched_at = Some(Instant::now());
+            }
+            Some(Err(e)) => {
+                // keep the old balances, and don't retry until the cache expires
+                warn!("failed to fetch balances: {e}");
+                balances.fetched_at = Some(Instant::now());
+            }
+            None => balances.task = Some(task),
+        }
+        return;
+    }
+
+    if let (true, Some(address)) = (fetch, address) {
+        let tokens = config.tracked_tokens.clone();
+        balances.task = Some(IoTaskPool::get().spawn(fetch_balances(address, tokens)));
+    }
+}
+
+async fn token_balance(
+    owner: Address,
+    token: Address,
+    polygon: bool,
+) -> Result<U256, anyhow::Error> {
+    let mut data = id("balanceOf(address)").to_vec();
+    data.extend(encode(&[Token::Address(owner)]));
+    let params = json!([{
+        "to": format!("{token:#x}"),
+        "data": format!("0x{}", data_encoding::HEXLOWER.encode(&data)),
+    }, "latest"]);
+    let result = if polygon {
+        polygon_rpc("eth_call", params).await?
+    } else {
+        rpc("eth_call", params).await?
+    };
+    let result = data_encoding::HEXLOWER_PERMISSIVE.decode(
+        result
+            .as_str()
+            .unwrap_or_default()
+            .trim_start_matches("0x")
+            .as_bytes(),
+    )?;
+    Ok(decode(&[ParamType::Uint(256)], &result)?
+        .pop()
+        .and_then(Token::into_uint)
+        .unwrap_or_default())
+}
+
+pub async fn fetch_balances(
+    address: Address,
+    tracked: Vec<TrackedToken>,
+) -> Result<Vec<Balance>, anyhow::Error> {
+    let eth = serde_json::from_value::<U256>(
+        rpc("eth_getBalance", json!([format!("{address:#x}"), "latest"])).await?,
+    )?;
+
+    // mana is held on both chains, show the total
+    let mana = token_balance(address, MAINNET_MANA.parse()?, false).await?
+        + token_balance(address, POLYGON_MANA.parse()?, true).await?;
+
+    let mut balances = vec![
+        Balance {
+            symbol: "MANA".to_owned(),
+            amount: mana,
+            decimals: 18,
+        },
+        Balance {
+            symbol: "ETH".to_owned(),
+            amount: eth,
+            decimals: 18,
+        },
+    ];
+
+    for token in tracked {
+        match token_balance(address, token.address, token.polygon).await {
+            Ok(amount) => balances.push(Balance {
+                symbol: token.symbol,
+                amount,
+                decimals: token.decimals,
+            }),
+            Err(e) => warn!("failed to fetch {} balance: {e}", token.symbol),
+        }
+    }
+
+    Ok(balances)
+}
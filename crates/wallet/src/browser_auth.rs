@@ -132,9 +132,12 @@ async fn init_request(request: CreateRequest) -> Result<InitializedRequest, anyh
     }
 }
 
+fn request_url(request_id: &str) -> String {
+    format!("{AUTH_FRONT_URL}/{request_id}?targetConfigId=alternative")
+}
+
 async fn finish_request(request_id: String) -> Result<(H160, serde_json::Value), anyhow::Error> {
-    let url = format!("{AUTH_FRONT_URL}/{request_id}?targetConfigId=alternative");
-    opener::open_browser(url)?;
+    opener::open_browser(request_url(&request_id))?;
 
     fetch_server(request_id).await
 }
@@ -160,7 +163,10 @@ pub async fn remote_send_async(
         .map(|(_, payload)| payload)
 }
 
-fn get_ephemeral_message(ephemeral_address: &str, expiration: std::time::SystemTime) -> String {
+pub(crate) fn get_ephemeral_message(
+    ephemeral_address: &str,
+    expiration: std::time::SystemTime,
+) -> String {
     let datetime: chrono::DateTime<chrono::Utc> = expiration.into();
     let formatted_time = datetime.format("%Y-%m-%dT%H:%M:%S%.3fZ");
     format!(
@@ -175,6 +181,13 @@ pub struct RemoteEphemeralRequest {
     ephemeral_wallet: LocalWallet,
 }
 
+impl RemoteEphemeralRequest {
+    // the auth page for the request, to open on another device
+    pub fn url(&self) -> String {
+        request_url(&self.request_id)
+    }
+}
+
 pub async fn init_remote_ephemeral_request() -> Result<RemoteEphemeralRequest, anyhow::Error> {
     let ephemeral_wallet = LocalWallet::new(&mut thread_rng());
     let ephemeral_address = format!("{:#x}", ephemeral_wallet.address());
@@ -198,6 +211,15 @@ pub async fn init_remote_ephemeral_request() -> Result<RemoteEphemeralRequest, a
 
 pub async fn finish_remote_ephemeral_request(
     request: RemoteEphemeralRequest,
+) -> Result<(H160, LocalWallet, Vec<ChainLink>, u64), anyhow::Error> {
+    opener::open_browser(request.url())?;
+    poll_remote_ephemeral_request(request).await
+}
+
+// wait for the request to be signed elsewhere, e.g. on a phone that scanned the url, without
+// opening the local browser
+pub async fn poll_remote_ephemeral_request(
+    request: RemoteEphemeralRequest,
 ) -> Result<(H160, LocalWallet, Vec<ChainLink>, u64), anyhow::Error> {
     let RemoteEphemeralRequest {
         request_id,
@@ -206,7 +228,7 @@ pub async fn finish_remote_ephemeral_request(
         ..
     } = request;
 
-    let (signer, result) = finish_request(request_id).await?;
+    let (signer, result) = fetch_server(request_id).await?;
     let signature = Signature::from_str(result.as_str().ok_or(anyhow!("result is not a string"))?)?;
 
     let delegate = ChainLink {
@@ -0,0 +1,42 @@
+// json-rpc calls to public nodes, for requests that don't need the user's wallet
+
+use std::time::Duration;
+
+use anyhow::bail;
+use isahc::{config::Configurable, AsyncReadResponseExt, RequestExt};
+use serde_json::{json, Value};
+
+const ETH_RPC_URL: &str = "https://rpc.decentraland.org/mainnet";
+const POLYGON_RPC_URL: &str = "https://rpc.decentraland.org/polygon";
+const ETH_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub async fn rpc(method: &str, params: Value) -> Result<Value, anyhow::Error> {
+    call(ETH_RPC_URL, method, params).await
+}
+
+// collections and marketplace items live on polygon
+pub async fn polygon_rpc(method: &str, params: Value) -> Result<Value, anyhow::Error> {
+    call(POLYGON_RPC_URL, method, params).await
+}
+
+async fn call(url: &str, method: &str, params: Value) -> Result<Value, anyhow::Error> {
+    let body = json!({ "id": 1, "jsonrpc": "2.0", "method": method, "params": params });
+    let mut response = isahc::Request::post(url)
+        .timeout(ETH_RPC_TIMEOUT)
+        .header("Content-Type", "application/json")
+        .body(body.to_string())?
+        .send_async()
+        .await?;
+
+    if !response.status().is_success() {
+        bail!("rpc {method} failed: {}", response.status());
+    }
+    let mut response = response.json::<Value>().await?;
+    if let Some(error) = response.get("error") {
+        bail!(
+            "rpc {method} failed: {}",
+            error["message"].as_str().unwrap_or_default()
+        );
+    }
+    Ok(response["result"].take())
+}
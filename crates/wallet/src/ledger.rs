@@ -0,0 +1,296 @@
+// ledger hardware wallets over usb/hid, via the device's ethereum app. the ledger signs the login
+// and later `SendAsync` requests; anything that doesn't need a signature goes to a public rpc node.
+// while the device is waiting for the user, the request is published as a `LedgerPrompt` so the
+// client can mirror what the device is showing.
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail};
+use common::structs::{ChainLink, LedgerAccount};
+use data_encoding::HEXLOWER;
+use ethers_core::{
+    types::{
+        transaction::{
+            eip2718::TypedTransaction,
+            eip712::{Eip712, TypedData},
+        },
+        TransactionRequest, H160, U256,
+    },
+    utils::format_ether,
+};
+use ethers_signers::{HDPath, Ledger, LocalWallet, Signer};
+use serde_json::{json, Value};
+
+use crate::{browser_auth::get_ephemeral_message, eth_rpc::rpc};
+
+const CHAIN_ID: u64 = 1;
+
+// standard derivation path families
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DerivationScheme {
+    // m/44'/60'/{index}'/0/0
+    #[default]
+    LedgerLive,
+    // m/44'/60'/0'/{index}
+    Legacy,
+}
+
+impl DerivationScheme {
+    pub fn path(&self, index: usize) -> String {
+        match self {
+            DerivationScheme::LedgerLive => format!("m/44'/60'/{index}'/0/0"),
+            DerivationScheme::Legacy => format!("m/44'/60'/0'/{index}"),
+        }
+    }
+
+    pub fn other(&self) -> Self {
+        match self {
+            DerivationScheme::LedgerLive => DerivationScheme::Legacy,
+            DerivationScheme::Legacy => DerivationScheme::LedgerLive,
+        }
+    }
+}
+
+// what the device is asking the user to confirm
+#[derive(Clone, Debug, PartialEq)]
+pub struct LedgerPrompt {
+    pub title: String,
+    pub fields: Vec<(String, String)>,
+}
+
+// the prompt currently showing on the device, if any
+#[derive(Clone, Default)]
+pub struct LedgerPrompts(Arc<Mutex<Option<LedgerPrompt>>>);
+
+impl LedgerPrompts {
+    pub fn current(&self) -> Option<LedgerPrompt> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn show(&self, prompt: LedgerPrompt) -> PromptGuard<'_> {
+        *self.0.lock().unwrap() = Some(prompt);
+        PromptGuard(self)
+    }
+}
+
+// clears the prompt when the device responds, or the request is dropped
+struct PromptGuard<'a>(&'a LedgerPrompts);
+
+impl Drop for PromptGuard<'_> {
+    fn drop(&mut self) {
+        *self.0 .0.lock().unwrap() = None;
+    }
+}
+
+async fn open_device(path: &str) -> Result<Ledger, anyhow::Error> {
+    Ledger::new(HDPath::Other(path.to_owned()), CHAIN_ID)
+        .await
+        .map_err(|e| {
+            anyhow!("no ledger found, connect and unlock it and open the ethereum app ({e})")
+        })
+}
+
+// open the device and check it holds the account
+async fn open_account(account: &LedgerAccount) -> Result<Ledger, anyhow::Error> {
+    let ledger = open_device(&account.path).await?;
+    if ledger.address() != account.address {
+        bail!(
+            "the connected ledger does not hold account {:#x}",
+            account.address
+        );
+    }
+    Ok(ledger)
+}
+
+// the first `count` accounts on the connected device
+pub async fn list_accounts(
+    scheme: DerivationScheme,
+    count: usize,
+) -> Result<Vec<LedgerAccount>, anyhow::Error> {
+    let ledger = open_device(&scheme.path(0)).await?;
+    let mut accounts = Vec::with_capacity(count);
+    for index in 0..count {
+        let path = scheme.path(index);
+        let address = ledger
+            .get_address_with_path(&HDPath::Other(path.clone()))
+            .await?;
+        accounts.push(LedgerAccount { path, address });
+    }
+    Ok(accounts)
+}
+
+// the device shows utf8 messages as text and anything else as hex
+fn message_prompt(message: &[u8]) -> LedgerPrompt {
+    let text = match std::str::from_utf8(message) {
+        Ok(text) => text.to_owned(),
+        Err(_) => format!("0x{}", HEXLOWER.encode(message)),
+    };
+    LedgerPrompt {
+        title: "Sign message".to_owned(),
+        fields: vec![("Message".to_owned(), text)],
+    }
+}
+
+fn transaction_prompt(tx: &TypedTransaction) -> LedgerPrompt {
+    let mut fields = vec![
+        (
+            "Amount".to_owned(),
+            format!(
+                "ETH {}",
+                format_ether(tx.value().copied().unwrap_or_default())
+            ),
+        ),
+        (
+            "Address".to_owned(),
+            tx.to_addr()
+                .map(|to| format!("{to:#x}"))
+                .unwrap_or_else(|| "Contract creation".to_owned()),
+        ),
+        (
+            "Max Fees".to_owned(),
+            format!(
+                "ETH {}",
+                format_ether(
+                    tx.gas().copied().unwrap_or_default() * tx.gas_price().unwrap_or_default()
+                )
+            ),
+        ),
+    ];
+    if tx.data().is_some_and(|data| !data.is_empty()) {
+        fields.push(("Data".to_owned(), "Present".to_owned()));
+    }
+    LedgerPrompt {
+        title: "Review transaction".to_owned(),
+        fields,
+    }
+}
+
+// `0x` prefixed params are raw bytes, anything else is taken as text
+fn message_bytes(param: Option<&Value>) -> Result<Vec<u8>, anyhow::Error> {
+    let message = param
+        .and_then(Value::as_str)
+        .ok_or(anyhow!("missing message"))?;
+    match message.strip_prefix("0x") {
+        Some(hex) => Ok(HEXLOWER.decode(hex.to_lowercase().as_bytes())?),
+        None => Ok(message.as_bytes().to_vec()),
+    }
+}
+
+// fill in whatever the scene left out, and sign it on the device
+async fn sign_transaction(
+    ledger: &Ledger,
+    account: &LedgerAccount,
+    prompts: &LedgerPrompts,
+    param: Option<Value>,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let mut tx: TransactionRequest =
+        serde_json::from_value(param.ok_or(anyhow!("missing transaction"))?)?;
+    let from = format!("{:#x}", account.address);
+    tx.from = Some(account.address);
+    tx.chain_id = Some(CHAIN_ID.into());
+    if tx.nonce.is_none() {
+        let nonce = rpc("eth_getTransactionCount", json!([from, "pending"])).await?;
+        tx.nonce = Some(serde_json::from_value::<U256>(nonce)?);
+    }
+    if tx.gas_price.is_none() {
+        tx.gas_price = Some(serde_json::from_value::<U256>(
+            rpc("eth_gasPrice", json!([])).await?,
+        )?);
+    }
+    if tx.gas.is_none() {
+        tx.gas = Some(serde_json::from_value::<U256>(
+            rpc("eth_estimateGas", json!([tx])).await?,
+        )?);
+    }
+
+    let tx = TypedTransaction::Legacy(tx);
+    let _prompt = prompts.show(transaction_prompt(&tx));
+    let signature = ledger.sign_tx(&tx).await?;
+    Ok(tx.rlp_signed(&signature).to_vec())
+}
+
+// handle a scene's request, signing on the device where needed
+pub async fn send_request(
+    account: &LedgerAccount,
+    prompts: &LedgerPrompts,
+    method: &str,
+    params: Vec<Value>,
+) -> Result<Value, anyhow::Error> {
+    match method {
+        "personal_sign" => {
+            let message = message_bytes(params.first())?;
+            let ledger = open_account(account).await?;
+            let _prompt = prompts.show(message_prompt(&message));
+            let signature = ledger.sign_message(message).await?;
+            Ok(format!("0x{signature}").into())
+        }
+        "eth_signTypedData_v4" => {
+            let typed_data: TypedData = match params.get(1) {
+                Some(Value::String(s)) => serde_json::from_str(s)?,
+                Some(value) => serde_json::from_value(value.clone())?,
+                None => bail!("missing typed data"),
+            };
+            let ledger = open_account(account).await?;
+            let _prompt = prompts.show(LedgerPrompt {
+                title: "Sign typed message".to_owned(),
+                fields: vec![
+                    (
+                        "Domain hash".to_owned(),
+                        format!("0x{}", HEXLOWER.encode(&typed_data.domain_separator()?)),
+                    ),
+                    (
+                        "Message hash".to_owned(),
+                        format!("0x{}", HEXLOWER.encode(&typed_data.struct_hash()?)),
+                    ),
+                ],
+            });
+            let signature = ledger.sign_typed_struct(&typed_data).await?;
+            Ok(format!("0x{signature}").into())
+        }
+        "eth_signTransaction" => {
+            let ledger = open_account(account).await?;
+            let raw =
+                sign_transaction(&ledger, account, prompts, params.into_iter().next()).await?;
+            Ok(format!("0x{}", HEXLOWER.encode(&raw)).into())
+        }
+        "eth_sendTransaction" => {
+            let ledger = open_account(account).await?;
+            let raw =
+                sign_transaction(&ledger, account, prompts, params.into_iter().next()).await?;
+            rpc(
+                "eth_sendRawTransaction",
+                json!([format!("0x{}", HEXLOWER.encode(&raw))]),
+            )
+            .await
+        }
+        "eth_accounts" | "eth_requestAccounts" => Ok(json!([format!("{:#x}", account.address)])),
+        "eth_sign" | "eth_signTypedData" | "eth_signTypedData_v3" => {
+            bail!("{method} is not supported by ledger")
+        }
+        _ => rpc(method, Value::Array(params)).await,
+    }
+}
+
+// sign an ephemeral login key with the ledger account, as the browser flow does
+pub async fn sign_ephemeral(
+    account: &LedgerAccount,
+    prompts: &LedgerPrompts,
+) -> Result<(H160, LocalWallet, Vec<ChainLink>), anyhow::Error> {
+    let ephemeral_wallet = LocalWallet::new(&mut rand::thread_rng());
+    let ephemeral_address = format!("{:#x}", ephemeral_wallet.address());
+    let expiration = std::time::SystemTime::now() + std::time::Duration::from_secs(30 * 24 * 3600);
+    let message = get_ephemeral_message(ephemeral_address.as_str(), expiration);
+
+    let ledger = open_account(account).await?;
+    let signature = {
+        let _prompt = prompts.show(message_prompt(message.as_bytes()));
+        ledger.sign_message(&message).await?
+    };
+
+    let delegate = ChainLink {
+        ty: "ECDSA_EPHEMERAL".to_owned(),
+        payload: message,
+        signature: format!("0x{}", signature),
+    };
+    Ok((account.address, ephemeral_wallet, vec![delegate]))
+}
@@ -1,25 +1,50 @@
-use std::sync::Arc;
-
-use anyhow::anyhow;
+use std::{
+    collections::HashMap,
+    future::Future,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail};
 use async_trait::async_trait;
 use bevy::prelude::*;
-use common::structs::ChainLink;
+use common::{
+    rpc::RPCSendableMessage,
+    structs::{ChainLink, LedgerAccount, WalletConnectSession},
+};
 // use ethers_core::types::transaction::eip2718::TypedTransaction;
+use balances::{update_balances, RefreshBalances, WalletBalances};
 use ethers_core::types::{Address, Signature};
 use ethers_signers::{LocalWallet, Signer, WalletError};
 use isahc::http::Uri;
+use ledger::{LedgerPrompt, LedgerPrompts};
 use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
+pub mod balances;
 pub mod browser_auth;
+pub mod eth_rpc;
+pub mod ledger;
+pub mod marketplace;
+pub mod session_store;
 pub mod signed_login;
+pub mod tx_review;
+pub mod wallet_connect;
+
+// signed-fetch headers for get requests are reused for this long. servers accept a signature for
+// a while after its timestamp, so there's no need to sign every request
+const SIGNED_HEADERS_CACHE_TIME: Duration = Duration::from_secs(30);
 
 pub struct WalletPlugin;
 
 impl Plugin for WalletPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Wallet>();
+        app.init_resource::<Wallet>()
+            .init_resource::<WalletBalances>()
+            .add_event::<RefreshBalances>()
+            .add_systems(Update, update_balances);
     }
 }
 
@@ -31,44 +56,66 @@ struct WalletInner {
     pub(crate) inner: Option<Box<dyn ObjSafeWalletSigner + 'static + Send + Sync>>,
     pub(crate) root_address: Option<Address>,
     pub(crate) delegates: Vec<ChainLink>,
+    // when set, remote requests go to this wallet app rather than through the browser
+    pub(crate) wallet_connect: Option<WalletConnectSession>,
+    // when set, remote requests are signed by this ledger account
+    pub(crate) ledger: Option<LedgerAccount>,
+    pub(crate) ledger_prompts: LedgerPrompts,
+    // the signer and delegates, built once per login
+    pub(crate) auth_chain: Option<SimpleAuthChain>,
+    // recently signed headers by request
+    pub(crate) signed_headers: Mutex<HashMap<String, (Instant, Vec<(String, String)>)>>,
+}
+
+impl WalletInner {
+    fn set_signer(
+        &mut self,
+        inner: Option<Box<dyn ObjSafeWalletSigner + 'static + Send + Sync>>,
+        root_address: Option<Address>,
+        delegates: Vec<ChainLink>,
+    ) {
+        self.auth_chain = root_address.map(|root| SimpleAuthChain::new_chain(root, &delegates));
+        self.signed_headers.get_mut().unwrap().clear();
+        self.inner = inner;
+        self.root_address = root_address;
+        self.delegates = delegates;
+    }
 }
 
 impl Wallet {
     pub fn auth_chain(&self) -> Result<SimpleAuthChain, anyhow::Error> {
-        let inner = self.0.blocking_read();
-
-        Ok(SimpleAuthChain::new_chain(
-            *inner
-                .root_address
-                .as_ref()
-                .ok_or(anyhow!("wallet not connected"))?,
-            &inner.delegates,
-        ))
+        self.0
+            .blocking_read()
+            .auth_chain
+            .clone()
+            .ok_or(anyhow!("wallet not connected"))
     }
 
     pub fn disconnect(&mut self) {
         let mut write = self.0.try_write().unwrap();
-        write.inner = None;
-        write.root_address = None;
-        write.delegates.clear();
+        write.set_signer(None, None, Vec::default());
+        write.wallet_connect = None;
+        write.ledger = None;
     }
 
     pub fn finalize_as_guest(&mut self) {
         let inner: Box<dyn ObjSafeWalletSigner + Send + Sync> =
             Box::new(LocalWallet::new(&mut rand::thread_rng()));
         let mut write = self.0.try_write().unwrap();
-        write.root_address = Some(inner.address());
-        write.delegates.clear();
-        write.inner = Some(inner);
+        let root_address = Some(inner.address());
+        write.set_signer(Some(inner), root_address, Vec::default());
+        write.wallet_connect = None;
+        write.ledger = None;
     }
 
     pub fn finalize_as_guest_with_seed(&mut self, seed: [u8; 32]) {
         let inner: Box<dyn ObjSafeWalletSigner + Send + Sync> =
             Box::new(LocalWallet::new(&mut rand::rngs::StdRng::from_seed(seed)));
         let mut write = self.0.try_write().unwrap();
-        write.root_address = Some(inner.address());
-        write.delegates.clear();
-        write.inner = Some(inner);
+        let root_address = Some(inner.address());
+        write.set_signer(Some(inner), root_address, Vec::default());
+        write.wallet_connect = None;
+        write.ledger = None;
     }
 
     pub fn finalize(
@@ -78,9 +125,115 @@ impl Wallet {
         auth: Vec<ChainLink>,
     ) {
         let mut write = self.0.try_write().unwrap();
-        write.root_address = Some(root_address);
-        write.delegates = auth;
-        write.inner = Some(Box::new(local_wallet));
+        write.set_signer(Some(Box::new(local_wallet)), Some(root_address), auth);
+        write.wallet_connect = None;
+        write.ledger = None;
+    }
+
+    // swap in a newly signed ephemeral key, keeping the account and whatever signs for it
+    pub fn renew(
+        &mut self,
+        root_address: Address,
+        local_wallet: LocalWallet,
+        auth: Vec<ChainLink>,
+    ) -> Result<(), anyhow::Error> {
+        let mut write = self.0.try_write().unwrap();
+        if write.root_address != Some(root_address) {
+            bail!("the key was signed by a different account");
+        }
+        write.set_signer(Some(Box::new(local_wallet)), Some(root_address), auth);
+        Ok(())
+    }
+
+    // when the ephemeral key stops being accepted, none for guests
+    pub fn ephemeral_expiry(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        delegate_expiration(&self.0.try_read().ok()?.delegates)
+    }
+
+    // sign a new ephemeral key with the walletconnect wallet or the ledger. browser logins use
+    // `browser_auth::init_remote_ephemeral_request` instead, as the user has to be shown the code
+    pub fn sign_ephemeral_async(
+        &self,
+    ) -> impl Future<Output = Result<(Address, LocalWallet, Vec<ChainLink>), anyhow::Error>>
+           + Send
+           + 'static {
+        let session = self
+            .wallet_connect_session()
+            .filter(|session| !session.is_expired());
+        let ledger = self.ledger_account();
+        let prompts = self.ledger_prompts();
+        async move {
+            match (session, ledger) {
+                (Some(session), _) => wallet_connect::sign_ephemeral(&session).await,
+                (None, Some(account)) => ledger::sign_ephemeral(&account, &prompts).await,
+                (None, None) => bail!("no walletconnect session or ledger to sign with"),
+            }
+        }
+    }
+
+    pub fn finalize_wallet_connect(
+        &mut self,
+        local_wallet: LocalWallet,
+        auth: Vec<ChainLink>,
+        session: WalletConnectSession,
+    ) {
+        self.finalize(session.address, local_wallet, auth);
+        self.0.try_write().unwrap().wallet_connect = Some(session);
+    }
+
+    pub fn wallet_connect_session(&self) -> Option<WalletConnectSession> {
+        self.0.try_read().unwrap().wallet_connect.clone()
+    }
+
+    pub fn finalize_ledger(
+        &mut self,
+        local_wallet: LocalWallet,
+        auth: Vec<ChainLink>,
+        account: LedgerAccount,
+    ) {
+        self.finalize(account.address, local_wallet, auth);
+        self.0.try_write().unwrap().ledger = Some(account);
+    }
+
+    pub fn ledger_account(&self) -> Option<LedgerAccount> {
+        self.0.try_read().unwrap().ledger.clone()
+    }
+
+    pub fn ledger_prompts(&self) -> LedgerPrompts {
+        self.0.try_read().unwrap().ledger_prompts.clone()
+    }
+
+    // what the ledger is waiting for the user to confirm, if anything
+    pub fn ledger_prompt(&self) -> Option<LedgerPrompt> {
+        self.0.try_read().ok()?.ledger_prompts.current()
+    }
+
+    // send a request needing the user's signature, to the walletconnect wallet if there is a
+    // session or the ledger if logged in with one, otherwise through the browser. an expired
+    // walletconnect session fails rather than quietly switching to another signer
+    pub fn send_async(
+        &self,
+        message: RPCSendableMessage,
+    ) -> impl Future<Output = Result<serde_json::Value, anyhow::Error>> + Send + 'static {
+        let session = self.wallet_connect_session();
+        let ledger = self.ledger_account();
+        let prompts = self.ledger_prompts();
+        let auth_chain = self.auth_chain().ok();
+        async move {
+            match (session, ledger) {
+                (Some(session), _) if session.is_expired() => Err(anyhow!(
+                    "walletconnect session expired, pair {} again to sign",
+                    session.peer_name
+                )),
+                (Some(session), _) => {
+                    wallet_connect::session_request(&session, &message.method, message.params).await
+                }
+                (None, Some(account)) => {
+                    ledger::send_request(&account, &prompts, &message.method, message.params).await
+                }
+                (None, None) => browser_auth::remote_send_async(message, auth_chain).await,
+            }
+        }
     }
 
     pub async fn sign_message(&self, message: String) -> Result<SimpleAuthChain, WalletError> {
@@ -155,7 +308,17 @@ impl ObjSafeWalletSigner for LocalWallet {
     // }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+// the expiry written into the ephemeral delegate's payload
+pub fn delegate_expiration(delegates: &[ChainLink]) -> Option<chrono::DateTime<chrono::Utc>> {
+    delegates
+        .iter()
+        .filter(|link| link.ty == "ECDSA_EPHEMERAL")
+        .flat_map(|link| link.payload.lines())
+        .filter_map(|line| line.strip_prefix("Expiration:"))
+        .find_map(|exp| chrono::DateTime::<chrono::Utc>::from_str(exp.trim()).ok())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SimpleAuthChain(Vec<ChainLink>);
 
 impl SimpleAuthChain {
@@ -248,11 +411,40 @@ pub async fn sign_request<META: Serialize>(
         .as_millis();
 
     let meta = serde_json::to_string(&meta).unwrap();
+
+    // fail here rather than with a 401 from the server
+    let expired = delegate_expiration(&wallet.0.read().await.delegates)
+        .is_some_and(|exp| exp < chrono::Utc::now());
+    if expired {
+        bail!("login expired, please sign in again");
+    }
+
+    let cache_key = method
+        .eq_ignore_ascii_case("get")
+        .then(|| format!("{}:{}", uri.path(), meta).to_lowercase());
+    if let Some(key) = cache_key.as_ref() {
+        let read = wallet.0.read().await;
+        let cache = read.signed_headers.lock().unwrap();
+        if let Some((_, headers)) = cache
+            .get(key)
+            .filter(|(at, _)| at.elapsed() < SIGNED_HEADERS_CACHE_TIME)
+        {
+            return Ok(headers.clone());
+        }
+    }
+
     let payload = format!("{}:{}:{}:{}", method, uri.path(), unix_time, meta).to_lowercase();
     let auth_chain = wallet.sign_message(payload).await?;
 
     let mut headers: Vec<_> = auth_chain.headers().collect();
     headers.push(("x-identity-timestamp".to_owned(), format!("{}", unix_time)));
     headers.push(("x-identity-metadata".to_owned(), meta));
+
+    if let Some(key) = cache_key {
+        let read = wallet.0.read().await;
+        let mut cache = read.signed_headers.lock().unwrap();
+        cache.retain(|_, (at, _)| at.elapsed() < SIGNED_HEADERS_CACHE_TIME);
+        cache.insert(key, (Instant::now(), headers.clone()));
+    }
     Ok(headers)
 }
@@ -0,0 +1,241 @@
+// primary sales of wearable and emote items from the decentraland collection store. listings come
+// from the nft api, and purchases are polygon transactions sent through the user's wallet, so they
+// get the usual web3 confirmation from whichever signer is logged in.
+
+use std::{str::FromStr, time::Duration};
+
+use anyhow::{anyhow, bail};
+use common::rpc::RPCSendableMessage;
+use ethers_core::{
+    abi::{decode, encode, ParamType, Token},
+    types::{Address, U256},
+    utils::{format_units, id, keccak256},
+};
+use isahc::AsyncReadResponseExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::eth_rpc::polygon_rpc;
+
+const NFT_API_URL: &str = "https://nft-api.decentraland.org/v1";
+const MARKETPLACE_URL: &str = "https://decentraland.org/marketplace";
+const COLLECTION_STORE: &str = "0x214ffc0f0103735728dc66b61a22e4f163e275ae";
+const POLYGON_MANA: &str = "0xa1c57f48f0deb89f569dfbe6e2b7f46d33606fd4";
+const POLYGON_CHAIN_ID: &str = "0x89";
+
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Listing {
+    pub urn: String,
+    pub name: String,
+    pub thumbnail: String,
+    // "wearable" or "emote"
+    pub category: String,
+    pub rarity: String,
+    pub contract_address: String,
+    pub item_id: String,
+    pub price: String,
+    pub available: u64,
+    pub is_on_sale: bool,
+    pub beneficiary: Option<String>,
+    #[serde(default)]
+    pub data: Value,
+}
+
+impl Listing {
+    pub fn price(&self) -> U256 {
+        U256::from_dec_str(&self.price).unwrap_or_default()
+    }
+
+    pub fn price_text(&self) -> String {
+        let price = self.price();
+        if price.is_zero() {
+            return "Free".to_owned();
+        }
+        format!(
+            "{} MANA",
+            format_units(price, 18).unwrap_or_else(|_| price.to_string())
+        )
+    }
+
+    pub fn description(&self) -> String {
+        self.data[&self.category]["description"]
+            .as_str()
+            .unwrap_or_default()
+            .to_owned()
+    }
+
+    // the wearable slot or emote category
+    pub fn item_category(&self) -> String {
+        self.data[&self.category]["category"]
+            .as_str()
+            .unwrap_or_default()
+            .to_owned()
+    }
+
+    pub fn can_buy(&self) -> bool {
+        self.is_on_sale && self.available > 0
+    }
+
+    pub fn marketplace_url(&self) -> String {
+        format!(
+            "{MARKETPLACE_URL}/contracts/{}/items/{}",
+            self.contract_address, self.item_id
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct ItemsResponse {
+    data: Vec<Listing>,
+}
+
+// look up the item for a `urn:decentraland:matic:collections-v2:{contract}:{item}` urn
+pub async fn fetch_listing(urn: &str) -> Result<Listing, anyhow::Error> {
+    let parts = urn.to_lowercase();
+    let parts = parts.split(':').collect::<Vec<_>>();
+    let (Some(&"collections-v2"), Some(contract), Some(item)) =
+        (parts.get(3), parts.get(4), parts.get(5))
+    else {
+        bail!("{urn} is not a marketplace item");
+    };
+
+    let mut response = isahc::get_async(format!(
+        "{NFT_API_URL}/items?contractAddress={contract}&itemId={item}"
+    ))
+    .await?;
+    if !response.status().is_success() {
+        bail!("listing request failed: {}", response.status());
+    }
+    response
+        .json::<ItemsResponse>()
+        .await?
+        .data
+        .into_iter()
+        .next()
+        .ok_or(anyhow!("item not found"))
+}
+
+fn address(value: &str) -> Result<Address, anyhow::Error> {
+    Address::from_str(value).map_err(|e| anyhow!("bad address {value}: {e}"))
+}
+
+fn transaction(from: Address, to: &str, data: Vec<u8>) -> RPCSendableMessage {
+    RPCSendableMessage {
+        method: "eth_sendTransaction".to_owned(),
+        params: vec![json!({
+            "from": format!("{from:#x}"),
+            "to": to,
+            "data": format!("0x{}", data_encoding::HEXLOWER.encode(&data)),
+            "chainId": POLYGON_CHAIN_ID,
+        })],
+    }
+}
+
+async fn mana_allowance(owner: Address) -> Result<U256, anyhow::Error> {
+    let mut data = id("allowance(address,address)").to_vec();
+    data.extend(encode(&[
+        Token::Address(owner),
+        Token::Address(address(COLLECTION_STORE)?),
+    ]));
+    let result = polygon_rpc(
+        "eth_call",
+        json!([{
+            "to": POLYGON_MANA,
+            "data": format!("0x{}", data_encoding::HEXLOWER.encode(&data)),
+        }, "latest"]),
+    )
+    .await?;
+    let result = data_encoding::HEXLOWER_PERMISSIVE.decode(
+        result
+            .as_str()
+            .unwrap_or_default()
+            .trim_start_matches("0x")
+            .as_bytes(),
+    )?;
+    decode(&[ParamType::Uint(256)], &result)?
+        .pop()
+        .and_then(Token::into_uint)
+        .ok_or(anyhow!("bad allowance response"))
+}
+
+// the transactions needed to buy the item: a mana approval for the store if the current allowance
+// doesn't cover the price, then the purchase itself
+pub async fn purchase_transactions(
+    listing: &Listing,
+    buyer: Address,
+) -> Result<Vec<RPCSendableMessage>, anyhow::Error> {
+    if !listing.can_buy() {
+        bail!("this item is not for sale");
+    }
+
+    let price = listing.price();
+    let mut txs = Vec::default();
+
+    if !price.is_zero() && mana_allowance(buyer).await? < price {
+        let mut data = id("approve(address,uint256)").to_vec();
+        data.extend(encode(&[
+            Token::Address(address(COLLECTION_STORE)?),
+            Token::Uint(price),
+        ]));
+        txs.push(transaction(buyer, POLYGON_MANA, data));
+    }
+
+    let item_id = U256::from_dec_str(&listing.item_id)?;
+    let mut data = id("buy((address,uint256[],uint256[],address[])[])").to_vec();
+    data.extend(encode(&[Token::Array(vec![Token::Tuple(vec![
+        Token::Address(address(&listing.contract_address)?),
+        Token::Array(vec![Token::Uint(item_id)]),
+        Token::Array(vec![Token::Uint(price)]),
+        Token::Array(vec![Token::Address(buyer)]),
+    ])])]));
+    txs.push(transaction(buyer, COLLECTION_STORE, data));
+
+    Ok(txs)
+}
+
+// wait for the transaction to be mined, returning the id of the token it minted to `buyer`, if any
+pub async fn wait_for_purchase(
+    tx_hash: &str,
+    buyer: Address,
+) -> Result<Option<String>, anyhow::Error> {
+    let start = std::time::Instant::now();
+    let receipt = loop {
+        let receipt = polygon_rpc("eth_getTransactionReceipt", json!([tx_hash])).await?;
+        if !receipt.is_null() {
+            break receipt;
+        }
+        if start.elapsed() > RECEIPT_TIMEOUT {
+            bail!("timed out waiting for the transaction");
+        }
+        async_std::task::sleep(RECEIPT_POLL_INTERVAL).await;
+    };
+
+    if receipt["status"].as_str() != Some("0x1") {
+        bail!("the transaction failed");
+    }
+
+    let transfer_topic = format!(
+        "0x{}",
+        data_encoding::HEXLOWER.encode(&keccak256("Transfer(address,address,uint256)"))
+    );
+    let buyer_topic = format!("0x{:0>64}", format!("{buyer:x}"));
+    let token_id = receipt["logs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|log| log["topics"].as_array())
+        .find(|topics| {
+            topics.len() == 4
+                && topics[0].as_str() == Some(transfer_topic.as_str())
+                && topics[2].as_str() == Some(buyer_topic.as_str())
+        })
+        .and_then(|topics| topics[3].as_str())
+        .and_then(|token| U256::from_str_radix(token.trim_start_matches("0x"), 16).ok())
+        .map(|token| token.to_string());
+
+    Ok(token_id)
+}
@@ -0,0 +1,130 @@
+// the last login, kept encrypted at rest so it can be reused on the next launch. the key lives in
+// the os keychain where there is one, otherwise in a key file beside the session. deleting the key
+// makes any copy of the session unreadable.
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use bevy::log::{debug, warn};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Nonce,
+};
+use common::{structs::PreviousLogin, util::config_file};
+use data_encoding::BASE64;
+use rand::{rngs::OsRng, RngCore};
+
+const KEYRING_SERVICE: &str = "bevy-explorer";
+const KEYRING_USER: &str = "session-key";
+
+fn session_file() -> PathBuf {
+    config_file().with_file_name("session.bin")
+}
+
+fn key_file() -> PathBuf {
+    config_file().with_file_name("session.key")
+}
+
+fn keyring_entry() -> Option<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| debug!("no keychain: {e}"))
+        .ok()
+}
+
+fn decode_key(encoded: &str) -> Option<[u8; 32]> {
+    BASE64
+        .decode(encoded.trim().as_bytes())
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+fn load_key() -> Option<[u8; 32]> {
+    if let Some(key) = keyring_entry()
+        .and_then(|entry| entry.get_password().ok())
+        .and_then(|encoded| decode_key(&encoded))
+    {
+        return Some(key);
+    }
+
+    decode_key(&std::fs::read_to_string(key_file()).ok()?)
+}
+
+fn create_key() -> Result<[u8; 32], anyhow::Error> {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    let encoded = BASE64.encode(&key);
+
+    match keyring_entry().map(|entry| entry.set_password(&encoded)) {
+        Some(Ok(())) => return Ok(key),
+        Some(Err(e)) => warn!("failed to store session key in keychain, using key file: {e}"),
+        None => (),
+    }
+
+    let path = key_file();
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder)?;
+    }
+    std::fs::write(&path, encoded)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(key)
+}
+
+pub fn save_session(login: &PreviousLogin) -> Result<(), anyhow::Error> {
+    let key = match load_key() {
+        Some(key) => key,
+        None => create_key()?,
+    };
+
+    let mut iv = [0u8; 12];
+    OsRng.fill_bytes(&mut iv);
+    let sealed = ChaCha20Poly1305::new(&key.into())
+        .encrypt(
+            Nonce::from_slice(&iv),
+            serde_json::to_vec(login)?.as_slice(),
+        )
+        .map_err(|_| anyhow!("encryption failed"))?;
+
+    let mut data = iv.to_vec();
+    data.extend_from_slice(&sealed);
+
+    let path = session_file();
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder)?;
+    }
+    std::fs::write(path, BASE64.encode(&data))?;
+    Ok(())
+}
+
+pub fn load_session() -> Option<PreviousLogin> {
+    let data = BASE64
+        .decode(std::fs::read(session_file()).ok()?.as_slice())
+        .ok()?;
+    if data.len() < 12 {
+        return None;
+    }
+    let key = load_key()?;
+    let opened = ChaCha20Poly1305::new(&key.into())
+        .decrypt(Nonce::from_slice(&data[..12]), &data[12..])
+        .map_err(|_| warn!("failed to decrypt saved session"))
+        .ok()?;
+    serde_json::from_slice(&opened).ok()
+}
+
+// forget the session and its key
+pub fn clear_session() {
+    let _ = std::fs::remove_file(session_file());
+    let _ = std::fs::remove_file(key_file());
+    if let Some(entry) = keyring_entry() {
+        let _ = entry.delete_credential();
+    }
+}
+
+// when the login's ephemeral key stops being accepted
+pub fn login_expiration(login: &PreviousLogin) -> Option<chrono::DateTime<chrono::Utc>> {
+    crate::delegate_expiration(&login.auth)
+}
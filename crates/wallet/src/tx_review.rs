@@ -0,0 +1,364 @@
+// readable summaries of scene web3 requests, so the user can see what they are agreeing to before
+// the request reaches their wallet. common token and marketplace calls are decoded from the
+// calldata, and transactions get a gas estimate from the rpc node.
+
+use std::str::FromStr;
+
+use common::rpc::RPCSendableMessage;
+use ethers_core::{
+    abi::{decode, ParamType, Token},
+    types::{Address, U256},
+    utils::{format_ether, format_units, id},
+};
+use serde_json::{json, Value};
+
+use crate::eth_rpc::{polygon_rpc, rpc};
+
+// mainnet and polygon
+const MANA_CONTRACTS: [&str; 2] = [
+    "0x0f5d2fb29fb7d3cfee444a200298f468908cc942",
+    "0xa1c57f48f0deb89f569dfbe6e2b7f46d33606fd4",
+];
+
+pub struct RequestReview {
+    pub summary: String,
+    // pretty printed request, for inspection
+    pub raw: String,
+}
+
+pub async fn review(message: RPCSendableMessage, from: Option<Address>) -> RequestReview {
+    let mut fields = summarize(&message.method, &message.params);
+
+    if message.method == "eth_sendTransaction" {
+        if let Some(tx) = message.params.first() {
+            fields.push((
+                "Estimated gas".to_owned(),
+                match estimate_fee(tx, from).await {
+                    Ok((gas, fee)) => format!("{gas} (~{} {})", format_ether(fee), currency(tx)),
+                    // a failed estimate usually means the transaction would revert
+                    Err(e) => format!("unavailable ({e})"),
+                },
+            ));
+        }
+    }
+
+    RequestReview {
+        summary: fields
+            .into_iter()
+            .map(|(label, value)| format!("{label}: {value}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        raw: serde_json::to_string_pretty(&json!({
+            "method": message.method,
+            "params": message.params,
+        }))
+        .unwrap_or_default(),
+    }
+}
+
+async fn estimate_fee(tx: &Value, from: Option<Address>) -> Result<(U256, U256), anyhow::Error> {
+    let mut tx = tx.clone();
+    if let (Some(from), Some(tx)) = (from, tx.as_object_mut()) {
+        tx.entry("from")
+            .or_insert_with(|| format!("{from:#x}").into());
+    }
+    let (gas, gas_price) = if is_polygon(&tx) {
+        (
+            polygon_rpc("eth_estimateGas", json!([tx])).await?,
+            polygon_rpc("eth_gasPrice", json!([])).await?,
+        )
+    } else {
+        (
+            rpc("eth_estimateGas", json!([tx])).await?,
+            rpc("eth_gasPrice", json!([])).await?,
+        )
+    };
+    let gas = serde_json::from_value::<U256>(gas)?;
+    let gas_price = serde_json::from_value::<U256>(gas_price)?;
+    Ok((gas, gas * gas_price))
+}
+
+fn is_polygon(tx: &Value) -> bool {
+    serde_json::from_value::<U256>(tx["chainId"].clone()).is_ok_and(|chain| chain == 137.into())
+}
+
+fn currency(tx: &Value) -> &'static str {
+    if is_polygon(tx) {
+        "MATIC"
+    } else {
+        "ETH"
+    }
+}
+
+fn summarize(method: &str, params: &[Value]) -> Vec<(String, String)> {
+    match method {
+        "eth_sendTransaction" | "eth_signTransaction" => {
+            params.first().map(summarize_tx).unwrap_or_default()
+        }
+        "personal_sign" => vec![(
+            "Sign message".to_owned(),
+            params
+                .first()
+                .and_then(Value::as_str)
+                .map(message_text)
+                .unwrap_or_default(),
+        )],
+        "eth_signTypedData" | "eth_signTypedData_v3" | "eth_signTypedData_v4" => {
+            let typed_data = match params.get(1) {
+                Some(Value::String(s)) => serde_json::from_str(s).unwrap_or_default(),
+                Some(value) => value.clone(),
+                None => Value::Null,
+            };
+            vec![
+                (
+                    "Sign typed data".to_owned(),
+                    typed_data["primaryType"]
+                        .as_str()
+                        .unwrap_or("unknown")
+                        .to_owned(),
+                ),
+                (
+                    "For".to_owned(),
+                    typed_data["domain"]["name"]
+                        .as_str()
+                        .unwrap_or("unknown")
+                        .to_owned(),
+                ),
+            ]
+        }
+        _ => vec![("Method".to_owned(), method.to_owned())],
+    }
+}
+
+// hex messages are shown as text when they are valid utf8
+fn message_text(message: &str) -> String {
+    message
+        .strip_prefix("0x")
+        .and_then(|hex| {
+            data_encoding::HEXLOWER_PERMISSIVE
+                .decode(hex.as_bytes())
+                .ok()
+        })
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or_else(|| message.to_owned())
+}
+
+fn summarize_tx(tx: &Value) -> Vec<(String, String)> {
+    let to = tx["to"].as_str().and_then(|to| Address::from_str(to).ok());
+    let value = serde_json::from_value::<U256>(tx["value"].clone()).unwrap_or_default();
+    let data = tx["data"]
+        .as_str()
+        .or(tx["input"].as_str())
+        .and_then(|data| {
+            data_encoding::HEXLOWER_PERMISSIVE
+                .decode(data.trim_start_matches("0x").as_bytes())
+                .ok()
+        })
+        .unwrap_or_default();
+
+    let mut fields = Vec::default();
+    match (to, decode_call(to, &data)) {
+        (Some(to), Some(mut call)) => {
+            fields.append(&mut call);
+            fields.push(("Contract".to_owned(), format!("{to:#x}")));
+        }
+        (Some(to), None) if data.is_empty() => {
+            fields.push(("Action".to_owned(), "Send ETH".to_owned()));
+            fields.push(("Recipient".to_owned(), format!("{to:#x}")));
+        }
+        (Some(to), None) => {
+            fields.push(("Action".to_owned(), "Contract call".to_owned()));
+            fields.push(("Contract".to_owned(), format!("{to:#x}")));
+            if data.len() >= 4 {
+                fields.push((
+                    "Function".to_owned(),
+                    format!("0x{}", data_encoding::HEXLOWER.encode(&data[..4])),
+                ));
+            }
+        }
+        (None, _) => fields.push(("Action".to_owned(), "Deploy contract".to_owned())),
+    }
+    if !value.is_zero() {
+        fields.push((
+            "Value".to_owned(),
+            format!("{} {}", format_ether(value), currency(tx)),
+        ));
+    }
+    fields
+}
+
+fn is_mana(contract: Option<Address>) -> bool {
+    contract.is_some_and(|contract| MANA_CONTRACTS.contains(&format!("{contract:#x}").as_str()))
+}
+
+fn mana(amount: U256) -> String {
+    format!(
+        "{} MANA",
+        format_units(amount, 18).unwrap_or_else(|_| amount.to_string())
+    )
+}
+
+// token amounts are only formatted for tokens we know the decimals of
+fn token_amount(contract: Option<Address>, amount: U256) -> String {
+    if is_mana(contract) {
+        mana(amount)
+    } else if amount == U256::MAX {
+        "unlimited".to_owned()
+    } else {
+        format!("{amount} (token units)")
+    }
+}
+
+fn decode_call(contract: Option<Address>, data: &[u8]) -> Option<Vec<(String, String)>> {
+    use ParamType::{Address as A, Bool, Bytes, Uint};
+
+    if data.len() < 4 {
+        return None;
+    }
+    let (selector, args) = data.split_at(4);
+
+    let decode_args = |signature: &str, types: &[ParamType]| -> Option<Vec<Token>> {
+        (selector == id(signature)).then(|| decode(types, args).ok())?
+    };
+    let field = |label: &str, value: String| (label.to_owned(), value);
+    let address = |token: &Token| {
+        token
+            .clone()
+            .into_address()
+            .map(|a| format!("{a:#x}"))
+            .unwrap_or_default()
+    };
+    let uint = |token: &Token| token.clone().into_uint().unwrap_or_default();
+
+    if let Some(t) = decode_args("transfer(address,uint256)", &[A, Uint(256)]) {
+        return Some(vec![
+            field("Action", "Transfer tokens".to_owned()),
+            field("Recipient", address(&t[0])),
+            field("Amount", token_amount(contract, uint(&t[1]))),
+        ]);
+    }
+    // shared by erc20 and erc721, so the last argument may be an amount or a token id
+    if let Some(t) = decode_args("transferFrom(address,address,uint256)", &[A, A, Uint(256)]) {
+        return Some(vec![
+            field("Action", "Transfer".to_owned()),
+            field("From", address(&t[0])),
+            field("Recipient", address(&t[1])),
+            field("Amount / Token Id", uint(&t[2]).to_string()),
+        ]);
+    }
+    if let Some(t) = decode_args("approve(address,uint256)", &[A, Uint(256)]) {
+        return Some(vec![
+            field("Action", "Approve spending".to_owned()),
+            field("Spender", address(&t[0])),
+            field("Allowance", token_amount(contract, uint(&t[1]))),
+        ]);
+    }
+    if let Some(t) = decode_args(
+        "safeTransferFrom(address,address,uint256)",
+        &[A, A, Uint(256)],
+    )
+    .or_else(|| {
+        decode_args(
+            "safeTransferFrom(address,address,uint256,bytes)",
+            &[A, A, Uint(256), Bytes],
+        )
+    }) {
+        return Some(vec![
+            field("Action", "Transfer NFT".to_owned()),
+            field("From", address(&t[0])),
+            field("Recipient", address(&t[1])),
+            field("Token Id", uint(&t[2]).to_string()),
+        ]);
+    }
+    if let Some(t) = decode_args("setApprovalForAll(address,bool)", &[A, Bool]) {
+        let approved = t[1].clone().into_bool().unwrap_or_default();
+        return Some(vec![
+            field(
+                "Action",
+                if approved {
+                    "Approve operator for all NFTs"
+                } else {
+                    "Revoke operator for all NFTs"
+                }
+                .to_owned(),
+            ),
+            field("Operator", address(&t[0])),
+        ]);
+    }
+    // decentraland marketplace orders, priced in mana
+    if let Some(t) = decode_args(
+        "executeOrder(address,uint256,uint256)",
+        &[A, Uint(256), Uint(256)],
+    )
+    .or_else(|| {
+        decode_args(
+            "safeExecuteOrder(address,uint256,uint256,bytes)",
+            &[A, Uint(256), Uint(256), Bytes],
+        )
+    }) {
+        return Some(vec![
+            field("Action", "Buy NFT".to_owned()),
+            field("Collection", address(&t[0])),
+            field("Token Id", uint(&t[1]).to_string()),
+            field("Price", mana(uint(&t[2]))),
+        ]);
+    }
+    if let Some(t) = decode_args(
+        "createOrder(address,uint256,uint256,uint256)",
+        &[A, Uint(256), Uint(256), Uint(256)],
+    ) {
+        // the marketplace site sends milliseconds, though the contract compares it to seconds
+        let expires = uint(&t[3]).low_u64() as i64;
+        let expires = if expires > 100_000_000_000 {
+            chrono::DateTime::from_timestamp_millis(expires)
+        } else {
+            chrono::DateTime::from_timestamp(expires, 0)
+        }
+        .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+        return Some(vec![
+            field("Action", "List NFT for sale".to_owned()),
+            field("Collection", address(&t[0])),
+            field("Token Id", uint(&t[1]).to_string()),
+            field("Price", mana(uint(&t[2]))),
+            field("Expires", expires),
+        ]);
+    }
+    // primary sales from the collection store
+    if let Some(t) = decode_args(
+        "buy((address,uint256[],uint256[],address[])[])",
+        &[ParamType::Array(Box::new(ParamType::Tuple(vec![
+            A,
+            ParamType::Array(Box::new(Uint(256))),
+            ParamType::Array(Box::new(Uint(256))),
+            ParamType::Array(Box::new(A)),
+        ])))],
+    ) {
+        let mut fields = vec![field("Action", "Buy items".to_owned())];
+        for item in t[0].clone().into_array().unwrap_or_default() {
+            let item = item.into_tuple().unwrap_or_default();
+            let [collection, ids, prices, beneficiaries] = item.as_slice() else {
+                continue;
+            };
+            let ids = ids.clone().into_array().unwrap_or_default();
+            let prices = prices.clone().into_array().unwrap_or_default();
+            let beneficiaries = beneficiaries.clone().into_array().unwrap_or_default();
+            for ((id, price), beneficiary) in ids.iter().zip(&prices).zip(&beneficiaries) {
+                fields.push(field("Collection", address(collection)));
+                fields.push(field("Item Id", uint(id).to_string()));
+                fields.push(field("Price", mana(uint(price))));
+                fields.push(field("Recipient", address(beneficiary)));
+            }
+        }
+        return Some(fields);
+    }
+    if let Some(t) = decode_args("cancelOrder(address,uint256)", &[A, Uint(256)]) {
+        return Some(vec![
+            field("Action", "Cancel NFT listing".to_owned()),
+            field("Collection", address(&t[0])),
+            field("Token Id", uint(&t[1]).to_string()),
+        ]);
+    }
+
+    None
+}
@@ -0,0 +1,593 @@
+// walletconnect v2, as the dapp side, over the public relay. pairing produces a `wc:` uri for a
+// wallet app to scan; once the wallet approves, the session's account signs the login and later
+// `SendAsync` requests are forwarded to it as `wc_sessionRequest`s. each exchange opens its own
+// relay connection, so a session can be resumed after a restart from its topic and key alone.
+
+use std::{collections::VecDeque, str::FromStr, time::Duration};
+
+use anyhow::{anyhow, bail};
+use async_tungstenite::{
+    async_std::{connect_async, ConnectStream},
+    tungstenite::Message,
+    WebSocketStream,
+};
+use bevy::log::{debug, warn};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Nonce,
+};
+use common::structs::{ChainLink, WalletConnectSession};
+use data_encoding::{BASE64, BASE64URL_NOPAD, HEXLOWER};
+use ed25519_dalek::{Signer as _, SigningKey};
+use ethers_core::types::{Address, Signature, H160};
+use ethers_signers::{LocalWallet, Signer};
+use futures_util::{SinkExt, StreamExt};
+use hkdf::Hkdf;
+use rand::{rngs::OsRng, Rng, RngCore};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::browser_auth::get_ephemeral_message;
+
+const RELAY_URL: &str = "wss://relay.walletconnect.com";
+const CHAIN_ID: &str = "eip155:1";
+const METHODS: [&str; 6] = [
+    "personal_sign",
+    "eth_sign",
+    "eth_signTypedData",
+    "eth_signTypedData_v4",
+    "eth_sendTransaction",
+    "eth_signTransaction",
+];
+// how long the wallet has to respond to a proposal or request
+const REQUEST_TTL: Duration = Duration::from_secs(300);
+
+// message tags, from the walletconnect sign api spec
+const TAG_SESSION_PROPOSE: u32 = 1100;
+const TAG_SESSION_SETTLE_RESPONSE: u32 = 1103;
+const TAG_SESSION_REQUEST: u32 = 1108;
+const TAG_SESSION_DELETE: u32 = 1112;
+const TAG_SESSION_PING_RESPONSE: u32 = 1115;
+
+fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// json-rpc ids as walletconnect generates them: millisecond time plus some randomness
+fn payload_id() -> u64 {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    millis * 1000 + rand::thread_rng().gen_range(0..1000)
+}
+
+fn random_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+fn parse_key(hex: &str) -> Result<[u8; 32], anyhow::Error> {
+    HEXLOWER
+        .decode(hex.to_lowercase().as_bytes())?
+        .try_into()
+        .map_err(|_| anyhow!("invalid key length"))
+}
+
+// type 0 envelope: version byte, nonce, then the sealed json
+fn encrypt(key: &[u8; 32], payload: &Value) -> Result<String, anyhow::Error> {
+    let mut iv = [0u8; 12];
+    OsRng.fill_bytes(&mut iv);
+    let sealed = ChaCha20Poly1305::new(key.into())
+        .encrypt(Nonce::from_slice(&iv), payload.to_string().as_bytes())
+        .map_err(|_| anyhow!("encryption failed"))?;
+
+    let mut envelope = Vec::with_capacity(1 + iv.len() + sealed.len());
+    envelope.push(0);
+    envelope.extend_from_slice(&iv);
+    envelope.extend_from_slice(&sealed);
+    Ok(BASE64.encode(&envelope))
+}
+
+fn decrypt(key: &[u8; 32], message: &str) -> Result<Value, anyhow::Error> {
+    let envelope = BASE64.decode(message.as_bytes())?;
+    if envelope.len() < 13 || envelope[0] != 0 {
+        bail!("unsupported envelope");
+    }
+    let opened = ChaCha20Poly1305::new(key.into())
+        .decrypt(Nonce::from_slice(&envelope[1..13]), &envelope[13..])
+        .map_err(|_| anyhow!("decryption failed"))?;
+    Ok(serde_json::from_slice(&opened)?)
+}
+
+// the session's symmetric key, from the x25519 exchange with the wallet's key, and the topic that
+// is the key's hash
+fn derive_session_key(
+    secret: &StaticSecret,
+    peer_public: [u8; 32],
+) -> Result<([u8; 32], String), anyhow::Error> {
+    let shared = secret.diffie_hellman(&PublicKey::from(peer_public));
+    let mut session_key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, shared.as_bytes())
+        .expand(&[], &mut session_key)
+        .map_err(|_| anyhow!("key derivation failed"))?;
+    let session_topic = HEXLOWER.encode(&Sha256::digest(session_key));
+    Ok((session_key, session_topic))
+}
+
+// the relay authenticates clients with a jwt signed by a throwaway ed25519 `did:key`
+fn relay_auth_token() -> String {
+    let key = SigningKey::generate(&mut OsRng);
+    let mut multicodec = vec![0xed, 0x01];
+    multicodec.extend_from_slice(key.verifying_key().as_bytes());
+    let did = format!("did:key:z{}", bs58::encode(multicodec).into_string());
+
+    let now = unix_time();
+    let header = json!({ "alg": "EdDSA", "typ": "JWT" });
+    let claims = json!({
+        "iss": did,
+        "sub": HEXLOWER.encode(&random_key()),
+        "aud": RELAY_URL,
+        "iat": now,
+        "exp": now + 24 * 3600,
+    });
+    let data = format!(
+        "{}.{}",
+        BASE64URL_NOPAD.encode(header.to_string().as_bytes()),
+        BASE64URL_NOPAD.encode(claims.to_string().as_bytes())
+    );
+    let signature = key.sign(data.as_bytes());
+    format!("{data}.{}", BASE64URL_NOPAD.encode(&signature.to_bytes()))
+}
+
+struct Relay {
+    stream: WebSocketStream<ConnectStream>,
+    // (topic, message) received while awaiting something else
+    received: VecDeque<(String, String)>,
+}
+
+impl Relay {
+    async fn connect(project_id: &str) -> Result<Self, anyhow::Error> {
+        let url = format!(
+            "{RELAY_URL}/?auth={}&projectId={}",
+            relay_auth_token(),
+            urlencoding::encode(project_id)
+        );
+        let (stream, _) = connect_async(url).await?;
+        Ok(Self {
+            stream,
+            received: Default::default(),
+        })
+    }
+
+    async fn send(&mut self, value: Value) -> Result<(), anyhow::Error> {
+        self.stream.send(Message::text(value.to_string())).await?;
+        Ok(())
+    }
+
+    async fn read(&mut self) -> Result<Value, anyhow::Error> {
+        loop {
+            match self.stream.next().await {
+                Some(Ok(Message::Text(text))) => return Ok(serde_json::from_str(&text)?),
+                Some(Ok(Message::Close(_))) | None => bail!("relay connection closed"),
+                Some(Ok(_)) => (),
+                Some(Err(e)) => return Err(e.into()),
+            }
+        }
+    }
+
+    // queue subscription messages, acknowledging them to the relay
+    async fn handle_incoming(&mut self, message: Value) -> Result<(), anyhow::Error> {
+        if message["method"] != "irn_subscription" {
+            return Ok(());
+        }
+        let data = &message["params"]["data"];
+        if let (Some(topic), Some(payload)) = (data["topic"].as_str(), data["message"].as_str()) {
+            self.received
+                .push_back((topic.to_owned(), payload.to_owned()));
+        }
+        self.send(json!({ "id": message["id"], "jsonrpc": "2.0", "result": true }))
+            .await
+    }
+
+    async fn call(&mut self, method: &str, params: Value) -> Result<Value, anyhow::Error> {
+        let id = payload_id();
+        self.send(json!({ "id": id, "jsonrpc": "2.0", "method": method, "params": params }))
+            .await?;
+        loop {
+            let message = self.read().await?;
+            if message["id"] == id && message.get("method").is_none() {
+                if let Some(error) = message.get("error") {
+                    bail!("relay {method} failed: {error}");
+                }
+                return Ok(message["result"].clone());
+            }
+            self.handle_incoming(message).await?;
+        }
+    }
+
+    async fn subscribe(&mut self, topic: &str) -> Result<(), anyhow::Error> {
+        self.call("irn_subscribe", json!({ "topic": topic }))
+            .await
+            .map(|_| ())
+    }
+
+    async fn publish(
+        &mut self,
+        topic: &str,
+        key: &[u8; 32],
+        payload: &Value,
+        tag: u32,
+    ) -> Result<(), anyhow::Error> {
+        let message = encrypt(key, payload)?;
+        self.call(
+            "irn_publish",
+            json!({
+                "topic": topic,
+                "message": message,
+                "ttl": REQUEST_TTL.as_secs(),
+                "tag": tag,
+                "prompt": tag == TAG_SESSION_REQUEST,
+            }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    // the next decryptable message on the topic
+    async fn next_message(&mut self, topic: &str, key: &[u8; 32]) -> Result<Value, anyhow::Error> {
+        loop {
+            while let Some((msg_topic, message)) = self.received.pop_front() {
+                if msg_topic != topic {
+                    continue;
+                }
+                match decrypt(key, &message) {
+                    Ok(payload) => return Ok(payload),
+                    Err(e) => warn!("ignoring walletconnect message: {e}"),
+                }
+            }
+            let message = self.read().await?;
+            self.handle_incoming(message).await?;
+        }
+    }
+}
+
+// a proposal awaiting approval by a wallet app
+pub struct WalletConnectPairing {
+    // for the wallet app to scan
+    pub uri: String,
+    project_id: String,
+    topic: String,
+    sym_key: [u8; 32],
+    secret: StaticSecret,
+    proposal_id: u64,
+    relay: Relay,
+}
+
+pub async fn init_pairing(project_id: String) -> Result<WalletConnectPairing, anyhow::Error> {
+    let topic = HEXLOWER.encode(&random_key());
+    let sym_key = random_key();
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let public = PublicKey::from(&secret);
+    let expiry = unix_time() + REQUEST_TTL.as_secs();
+
+    let mut relay = Relay::connect(&project_id).await?;
+    relay.subscribe(&topic).await?;
+
+    let proposal_id = payload_id();
+    let namespace = json!({
+        "chains": [CHAIN_ID],
+        "methods": METHODS,
+        "events": ["chainChanged", "accountsChanged"],
+    });
+    let proposal = json!({
+        "id": proposal_id,
+        "jsonrpc": "2.0",
+        "method": "wc_sessionPropose",
+        "params": {
+            "relays": [{ "protocol": "irn" }],
+            "requiredNamespaces": { "eip155": namespace },
+            "proposer": {
+                "publicKey": HEXLOWER.encode(public.as_bytes()),
+                "metadata": {
+                    "name": "Decentraland Bevy Explorer",
+                    "description": "Decentraland explorer",
+                    "url": "https://decentraland.org",
+                    "icons": ["https://decentraland.org/favicon.ico"],
+                },
+            },
+            "expiryTimestamp": expiry,
+        },
+    });
+    relay
+        .publish(&topic, &sym_key, &proposal, TAG_SESSION_PROPOSE)
+        .await?;
+
+    let uri = format!(
+        "wc:{topic}@2?relay-protocol=irn&symKey={}&expiryTimestamp={expiry}",
+        HEXLOWER.encode(&sym_key)
+    );
+    debug!("walletconnect pairing: {uri}");
+
+    Ok(WalletConnectPairing {
+        uri,
+        project_id,
+        topic,
+        sym_key,
+        secret,
+        proposal_id,
+        relay,
+    })
+}
+
+// wait for the wallet to approve the proposal and settle the session
+pub async fn finish_pairing(
+    pairing: WalletConnectPairing,
+) -> Result<WalletConnectSession, anyhow::Error> {
+    async_std::future::timeout(REQUEST_TTL, finish_pairing_inner(pairing))
+        .await
+        .map_err(|_| anyhow!("timed out awaiting wallet approval"))?
+}
+
+async fn finish_pairing_inner(
+    pairing: WalletConnectPairing,
+) -> Result<WalletConnectSession, anyhow::Error> {
+    let WalletConnectPairing {
+        project_id,
+        topic,
+        sym_key,
+        secret,
+        proposal_id,
+        mut relay,
+        ..
+    } = pairing;
+
+    let responder_key = loop {
+        let message = relay.next_message(&topic, &sym_key).await?;
+        if message["id"] != proposal_id {
+            continue;
+        }
+        if let Some(error) = message.get("error") {
+            bail!(
+                "wallet rejected the connection: {}",
+                error["message"].as_str().unwrap_or_default()
+            );
+        }
+        let key = message["result"]["responderPublicKey"]
+            .as_str()
+            .ok_or(anyhow!("approval without a public key"))?;
+        break parse_key(key)?;
+    };
+
+    let (session_key, session_topic) = derive_session_key(&secret, responder_key)?;
+
+    relay.subscribe(&session_topic).await?;
+    loop {
+        let message = relay.next_message(&session_topic, &session_key).await?;
+        if message["method"] != "wc_sessionSettle" {
+            continue;
+        }
+
+        let params = &message["params"];
+        let address = params["namespaces"]["eip155"]["accounts"][0]
+            .as_str()
+            .and_then(|account| account.rsplit(':').next())
+            .and_then(|address| Address::from_str(address).ok())
+            .ok_or(anyhow!("session without an ethereum account"))?;
+
+        relay
+            .publish(
+                &session_topic,
+                &session_key,
+                &json!({ "id": message["id"], "jsonrpc": "2.0", "result": true }),
+                TAG_SESSION_SETTLE_RESPONSE,
+            )
+            .await?;
+
+        return Ok(WalletConnectSession {
+            project_id,
+            topic: session_topic,
+            sym_key: HEXLOWER.encode(&session_key),
+            address,
+            peer_name: params["controller"]["metadata"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_owned(),
+            expiry: params["expiry"]
+                .as_u64()
+                .unwrap_or(unix_time() + 7 * 24 * 3600),
+        });
+    }
+}
+
+// send a request to the session's wallet and wait for the user to respond to it there
+pub async fn session_request(
+    session: &WalletConnectSession,
+    method: &str,
+    params: Vec<Value>,
+) -> Result<Value, anyhow::Error> {
+    if session.is_expired() {
+        bail!("walletconnect session expired");
+    }
+
+    let key = parse_key(&session.sym_key)?;
+    let mut relay = Relay::connect(&session.project_id).await?;
+    relay.subscribe(&session.topic).await?;
+
+    let id = payload_id();
+    let request = json!({
+        "id": id,
+        "jsonrpc": "2.0",
+        "method": "wc_sessionRequest",
+        "params": {
+            "request": { "method": method, "params": params },
+            "chainId": CHAIN_ID,
+        },
+    });
+    relay
+        .publish(&session.topic, &key, &request, TAG_SESSION_REQUEST)
+        .await?;
+
+    let response = async {
+        loop {
+            let message = relay.next_message(&session.topic, &key).await?;
+            match message["method"].as_str() {
+                Some("wc_sessionDelete") => bail!("wallet ended the session"),
+                Some("wc_sessionPing") => {
+                    relay
+                        .publish(
+                            &session.topic,
+                            &key,
+                            &json!({ "id": message["id"], "jsonrpc": "2.0", "result": true }),
+                            TAG_SESSION_PING_RESPONSE,
+                        )
+                        .await?;
+                }
+                Some(_) => (),
+                None if message["id"] == id => {
+                    if let Some(error) = message.get("error") {
+                        bail!(
+                            "wallet rejected the request: {}",
+                            error["message"].as_str().unwrap_or_default()
+                        );
+                    }
+                    return Ok(message["result"].clone());
+                }
+                None => (),
+            }
+        }
+    };
+
+    async_std::future::timeout(REQUEST_TTL, response)
+        .await
+        .map_err(|_| anyhow!("timed out awaiting the wallet"))?
+}
+
+// tell the wallet app the session is over
+pub async fn end_session(session: &WalletConnectSession) -> Result<(), anyhow::Error> {
+    if session.is_expired() {
+        return Ok(());
+    }
+
+    let key = parse_key(&session.sym_key)?;
+    let mut relay = Relay::connect(&session.project_id).await?;
+    let request = json!({
+        "id": payload_id(),
+        "jsonrpc": "2.0",
+        "method": "wc_sessionDelete",
+        "params": { "code": 6000, "message": "User disconnected." },
+    });
+    relay
+        .publish(&session.topic, &key, &request, TAG_SESSION_DELETE)
+        .await
+}
+
+// sign an ephemeral login key with the session's account, as the browser flow does
+pub async fn sign_ephemeral(
+    session: &WalletConnectSession,
+) -> Result<(H160, LocalWallet, Vec<ChainLink>), anyhow::Error> {
+    let ephemeral_wallet = LocalWallet::new(&mut rand::thread_rng());
+    let ephemeral_address = format!("{:#x}", ephemeral_wallet.address());
+    let expiration = std::time::SystemTime::now() + std::time::Duration::from_secs(30 * 24 * 3600);
+    let message = get_ephemeral_message(ephemeral_address.as_str(), expiration);
+
+    let result = session_request(
+        session,
+        "personal_sign",
+        vec![
+            format!("0x{}", HEXLOWER.encode(message.as_bytes())).into(),
+            format!("{:#x}", session.address).into(),
+        ],
+    )
+    .await?;
+    let signature = Signature::from_str(result.as_str().ok_or(anyhow!("result is not a string"))?)?;
+
+    let delegate = ChainLink {
+        ty: "ECDSA_EPHEMERAL".to_owned(),
+        payload: message,
+        signature: format!("0x{}", signature),
+    };
+    Ok((session.address, ephemeral_wallet, vec![delegate]))
+}
+
+// the pairing uri as a square of dark (true) and light modules, row by row
+pub fn pairing_qr_code(uri: &str) -> Result<(usize, Vec<bool>), anyhow::Error> {
+    let code = qrcode::QrCode::new(uri.as_bytes())?;
+    let modules = code
+        .to_colors()
+        .into_iter()
+        .map(|color| color == qrcode::Color::Dark)
+        .collect();
+    Ok((code.width(), modules))
+}
+
+#[cfg(test)]
+mod test {
+    use data_encoding::{BASE64, HEXLOWER};
+    use rand::rngs::OsRng;
+    use serde_json::json;
+    use sha2::{Digest, Sha256};
+    use x25519_dalek::{PublicKey, StaticSecret};
+
+    use super::{decrypt, derive_session_key, encrypt, random_key};
+
+    #[test]
+    fn envelope_roundtrip() {
+        let key = random_key();
+        let payload = json!({ "id": 1, "jsonrpc": "2.0", "method": "wc_sessionPing" });
+        let sealed = encrypt(&key, &payload).unwrap();
+        assert_eq!(decrypt(&key, &sealed).unwrap(), payload);
+
+        // fresh nonce per message
+        assert_ne!(encrypt(&key, &payload).unwrap(), sealed);
+        // wrong key
+        assert!(decrypt(&random_key(), &sealed).is_err());
+    }
+
+    #[test]
+    fn envelope_rejects_bad_input() {
+        let key = random_key();
+        let mut envelope = BASE64
+            .decode(encrypt(&key, &json!("x")).unwrap().as_bytes())
+            .unwrap();
+
+        // only type 0 envelopes are supported
+        envelope[0] = 1;
+        assert!(decrypt(&key, &BASE64.encode(&envelope)).is_err());
+
+        // tampered ciphertext
+        envelope[0] = 0;
+        *envelope.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&key, &BASE64.encode(&envelope)).is_err());
+
+        assert!(decrypt(&key, "AAAA").is_err());
+        assert!(decrypt(&key, "not base64!").is_err());
+    }
+
+    #[test]
+    fn session_key_agreement() {
+        let dapp = StaticSecret::random_from_rng(OsRng);
+        let wallet = StaticSecret::random_from_rng(OsRng);
+
+        let (dapp_key, dapp_topic) =
+            derive_session_key(&dapp, PublicKey::from(&wallet).to_bytes()).unwrap();
+        let (wallet_key, wallet_topic) =
+            derive_session_key(&wallet, PublicKey::from(&dapp).to_bytes()).unwrap();
+        assert_eq!(dapp_key, wallet_key);
+        assert_eq!(dapp_topic, wallet_topic);
+
+        // the topic is the hex sha256 of the key
+        assert_eq!(dapp_topic.len(), 64);
+        assert_eq!(dapp_topic, HEXLOWER.encode(&Sha256::digest(dapp_key)));
+
+        let other = StaticSecret::random_from_rng(OsRng);
+        let (other_key, _) =
+            derive_session_key(&other, PublicKey::from(&wallet).to_bytes()).unwrap();
+        assert_ne!(other_key, dapp_key);
+    }
+}
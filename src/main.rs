@@ -1,7 +1,12 @@
 #![cfg_attr(not(feature = "console"), windows_subsystem = "windows")]
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-use std::{fs::File, io::Write, sync::OnceLock};
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 
 use analytics::{metrics::MetricsPlugin, segment_system::SegmentConfig};
 use build_time::build_time_utc;
@@ -29,6 +34,7 @@ use bevy::{
         view::{ColorGrading, ColorGradingGlobal, ColorGradingSection, RenderLayers},
     },
     tasks::{IoTaskPool, Task},
+    time::TimeUpdateStrategy,
     window::WindowResolution,
 };
 use bevy_console::ConsoleCommand;
@@ -37,17 +43,19 @@ use collectibles::CollectiblesPlugin;
 use common::{
     sets::SetupSets,
     structs::{
-        AppConfig, AttachPoints, Cubemap, GraphicsSettings, IVec2Arg, PrimaryCamera,
-        PrimaryCameraRes, PrimaryPlayerRes, PrimaryUser, SceneImposterBake, SceneLoadDistance,
-        Version, GROUND_RENDERLAYER,
+        AppConfig, AttachPoints, Cubemap, Determinism, EffectRng, GraphicsSettings, IVec2Arg,
+        PowerSaveSetting, PrimaryCamera, PrimaryCameraRes, PrimaryPlayerRes, PrimaryUser,
+        SceneImposterBake, SceneLoadDistance, Version, GROUND_RENDERLAYER,
     },
-    util::{config_file, project_directories, TaskExt, UtilsPlugin},
+    util::{config_file, is_steam_deck, project_directories, TaskExt, UtilsPlugin},
 };
 use restricted_actions::{lookup_portable, RestrictedActionsPlugin};
 use scene_material::SceneBoundPlugin;
 use scene_runner::{
     automatic_testing::AutomaticTestingPlugin,
+    benchmark::{BenchmarkConfig, BenchmarkPlugin, BenchmarkRoute},
     initialize_scene::{PortableScenes, PortableSource, TestingData, PARCEL_SIZE},
+    soak::{SoakConfig, SoakPlugin, SoakRoute},
     update_world::{mesh_collider::GroundCollider, NoGltf},
     OutOfWorld, SceneRunnerPlugin,
 };
@@ -61,13 +69,17 @@ use ipfs::{IpfsAssetServer, IpfsIoPlugin};
 use nft::{asset_source::NftReaderPlugin, NftShapePlugin};
 use social::SocialPlugin;
 use system_bridge::{NativeUi, SystemBridgePlugin};
-use system_ui::{crash_report::CrashReportPlugin, SystemUiPlugin};
+use system_ui::{
+    crash_report::{clean_exit, install_panic_hook, CrashReportPlugin},
+    remote_control::RemoteControlPlugin,
+    SystemUiPlugin,
+};
 use texture_camera::TextureCameraPlugin;
 use tween::TweenPlugin;
 use ui_core::UiCorePlugin;
 use user_input::UserInputPlugin;
 use uuid::Uuid;
-use visuals::VisualsPlugin;
+use visuals::{color_lut::UserLutSourcePlugin, VisualsPlugin};
 use wallet::WalletPlugin;
 use world_ui::WorldUiPlugin;
 
@@ -105,6 +117,8 @@ fn main() {
         });
 
     let mut args = pico_args::Arguments::from_env();
+    // no visible window, run the test scenes and exit
+    let headless = args.contains("--headless");
 
     File::create(SESSION_LOG.get().unwrap())
         .expect("failed to create log file")
@@ -133,7 +147,12 @@ fn main() {
                 "config file not found at {:?}, generating default",
                 config_file
             ));
-            Default::default()
+            if is_steam_deck() {
+                infos.push("steam deck detected, using steam deck defaults".to_owned());
+                AppConfig::steam_deck()
+            } else {
+                Default::default()
+            }
         });
 
     let final_config = AppConfig {
@@ -210,19 +229,93 @@ fn main() {
             .ok()
             .unwrap_or(base_config.scene_imposter_multisample),
         sysinfo_visible: args.contains("--sysinfo"),
-        scene_log_to_console: args.contains("--scene_log_to_console"),
+        scene_log_to_console: args.contains("--scene_log_to_console") || headless,
+        // the headless window is never focused, and throttling would skew test and benchmark runs
+        power_save: if headless {
+            PowerSaveSetting::Off
+        } else {
+            base_config.power_save
+        },
         ..base_config
     };
 
     let test_scenes = args.value_from_str("--test_scenes").ok();
     let test_mode = args.contains("--testing") || test_scenes.is_some();
+    if headless && test_scenes.is_none() {
+        eprintln!("--headless requires --test_scenes");
+        std::process::exit(2);
+    }
+    let timeout = headless.then(|| args.value_from_str("--timeout").unwrap_or(600.0));
 
     app.insert_resource(TestingData {
         inspect_hash: args.value_from_str("--inspect").ok(),
         test_mode,
         test_scenes: test_scenes.clone(),
+        timeout,
+        snapshot_threshold: args.value_from_str("--snapshot_threshold").ok(),
+        results: Default::default(),
+    });
+
+    let fixed_dt: Option<f32> = args.value_from_str("--fixed_dt").ok();
+    if fixed_dt.is_some_and(|dt| !dt.is_finite() || dt <= 0.0) {
+        eprintln!("--fixed_dt must be a positive number of seconds");
+        std::process::exit(2);
+    }
+    let seed: Option<u64> = args.value_from_str("--seed").ok();
+    if let Some(fixed_dt) = fixed_dt {
+        // frames advance `Time` by the fixed step regardless of how long they take
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            std::time::Duration::from_secs_f32(fixed_dt),
+        ));
+    }
+    let seed = if seed.is_some() || fixed_dt.is_some() {
+        let seed = seed.unwrap_or(1);
+        app.insert_resource(EffectRng::with_seed(seed));
+        dcl::js::set_random_seed(seed);
+        seed
+    } else {
+        0
+    };
+    app.insert_resource(Determinism { fixed_dt, seed });
+
+    let benchmark_speed: f32 = args.value_from_str("--benchmark_speed").unwrap_or(10.0);
+    if !benchmark_speed.is_finite() || benchmark_speed <= 0.0 {
+        eprintln!("--benchmark_speed must be greater than 0");
+        std::process::exit(2);
+    }
+    // a bad route must fail rather than start a normal session that a ci job would count as passed
+    let benchmark_route = match args.opt_value_from_str::<_, BenchmarkRoute>("--benchmark") {
+        Ok(route) => route,
+        Err(e) => {
+            eprintln!("--benchmark: {e}");
+            std::process::exit(2);
+        }
+    };
+    let benchmark = benchmark_route.map(|route| BenchmarkConfig {
+        route,
+        speed: benchmark_speed,
+        output: args
+            .value_from_str("--benchmark_out")
+            .unwrap_or_else(|_| "benchmark".into()),
     });
 
+    let soak = args
+        .value_from_str::<_, SoakRoute>("--soak")
+        .ok()
+        .map(|route| SoakConfig {
+            route,
+            duration_secs: args.value_from_str("--soak_hours").unwrap_or(4.0) * 3600.0,
+            dwell_secs: args.value_from_str("--soak_dwell").unwrap_or(30.0),
+            output: args
+                .value_from_str("--soak_out")
+                .unwrap_or_else(|_| "soak".into()),
+        });
+
+    let remote_control: Option<u16> = args.value_from_str("--remote_control").ok();
+    let remote_control_token: String = args
+        .value_from_str("--remote_control_token")
+        .unwrap_or_else(|_| Uuid::new_v4().simple().to_string());
+
     let no_avatar = args.contains("--no_avatar");
     let no_gltf = args.contains("--no_gltf");
     let no_fog = args.contains("--no_fog");
@@ -253,7 +346,7 @@ fn main() {
         return;
     }
 
-    let present_mode = match final_config.graphics.vsync {
+    let present_mode = match final_config.graphics.vsync && !headless {
         true => bevy::window::PresentMode::AutoVsync,
         false => bevy::window::PresentMode::AutoNoVsync,
     };
@@ -291,6 +384,9 @@ fn main() {
                         present_mode,
                         resolution: WindowResolution::new(1280.0, 720.0)
                             .with_scale_factor_override(1.0),
+                        // still rendered to, so snapshots work
+                        visible: !headless,
+                        focused: !headless,
                         ..Default::default()
                     }),
                     ..Default::default()
@@ -320,7 +416,8 @@ fn main() {
                     assets_root: Default::default(),
                     num_slots: final_config.max_concurrent_remotes,
                 })
-                .add_before::<IpfsIoPlugin, _>(NftReaderPlugin),
+                .add_before::<IpfsIoPlugin, _>(NftReaderPlugin)
+                .add_before::<IpfsIoPlugin, _>(UserLutSourcePlugin),
         );
 
     if final_config.graphics.log_fps || is_preview {
@@ -391,11 +488,10 @@ fn main() {
         .add_plugins(TextureCameraPlugin)
         .add_plugins(SystemBridgePlugin { bare: false });
 
-    if let Some(crashed) = crash_file {
-        app.add_plugins(CrashReportPlugin {
-            file: crashed.canonicalize().unwrap(),
-        });
-    }
+    app.add_plugins(CrashReportPlugin {
+        session_log: PathBuf::from(SESSION_LOG.get().unwrap()),
+        crashed: crash_file.map(|crashed| crashed.canonicalize().unwrap()),
+    });
 
     if !no_avatar {
         app.add_plugins(AvatarPlugin);
@@ -405,6 +501,23 @@ fn main() {
         app.add_plugins(AutomaticTestingPlugin);
     }
 
+    if let Some(benchmark) = benchmark {
+        app.insert_resource(benchmark);
+        app.add_plugins(BenchmarkPlugin);
+    }
+
+    if let Some(soak) = soak {
+        app.insert_resource(soak);
+        app.add_plugins(SoakPlugin);
+    }
+
+    if let Some(port) = remote_control {
+        app.add_plugins(RemoteControlPlugin {
+            port,
+            token: remote_control_token,
+        });
+    }
+
     app.add_plugins(AudioPlugin)
         .add_plugins(RestrictedActionsPlugin)
         .insert_resource(PrimaryPlayerRes(Entity::PLACEHOLDER))
@@ -436,10 +549,12 @@ fn main() {
     // bevy_mod_debugdump::print_main_schedule(&mut app);
     #[cfg(not(feature = "console"))]
     log_panics::init();
+    install_panic_hook(Path::new(SESSION_LOG.get().unwrap()));
 
     app.run();
 
     let _ = std::fs::remove_file(format!("{}.touch", SESSION_LOG.get().unwrap()));
+    clean_exit(Path::new(SESSION_LOG.get().unwrap()));
 }
 
 fn setup(